@@ -0,0 +1,371 @@
+//! Command-line arguments for the orchestrator binary
+//!
+//! Arguments can also come from a `--config` TOML file. Its values are applied as if
+//! they had been typed before the real command line, so clap validates them the same
+//! way and any flag given explicitly wins.
+
+use crate::config::OrchestratorConfig;
+use crate::core::NormalizationMode;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, Parser};
+use shared::logging::LogFormat;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Orchestrator for managing LLM-based unique attribute generation
+#[derive(Parser, Debug, Clone, PartialEq)]
+#[command(name = "orchestrator")]
+#[command(about = "Orchestrates multiple producer processes for unique attribute generation")]
+pub struct Args {
+    /// TOML file of settings, keyed by flag name with underscores (flags given here override it)
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Tracing endpoint URL (if set, all spawned processes will also trace here)
+    #[arg(long)]
+    pub trace_ep: Option<String>,
+
+    /// Log level (trace, debug, info, warn, error)
+    #[arg(long, default_value = "info")]
+    pub log_level: String,
+
+    /// Plain ASCII logs without emoji or colors (also passed to spawned processes)
+    #[arg(long)]
+    pub plain_logs: bool,
+
+    /// Console log format: text or json (also passed to spawned processes)
+    #[arg(long, default_value = "text")]
+    pub log_format: LogFormat,
+
+    /// CLI mode: Topic for generation (when provided, starts producers immediately without webserver)
+    #[arg(long)]
+    pub topic: Option<String>,
+
+    /// CLI mode: File of newline-delimited topics to run one after another in this process
+    #[arg(long, conflicts_with_all = ["topic", "resume"])]
+    pub topics_file: Option<PathBuf>,
+
+    /// CLI mode: Run the --topics-file topics at the same time, splitting the producers between them
+    #[arg(long, requires = "topics_file")]
+    pub concurrent_topics: bool,
+
+    /// CLI mode: Number of producers to spawn, at least 1 (only used with --topic)
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    pub producers: u32,
+
+    /// Idle producers kept spawned but unstarted, promoted to replace failed ones without a cold start (0 disables)
+    #[arg(long, default_value = "0")]
+    pub warm_pool_size: usize,
+
+    /// CLI mode: Number of iterations to run (only used with --topic, runs indefinitely if not specified)
+    #[arg(long)]
+    pub iterations: Option<u32>,
+
+    /// CLI mode: Resume from the topic's checkpoint.json, counting its completed iterations toward --iterations
+    #[arg(long)]
+    pub resume: bool,
+
+    /// CLI mode: Request size (number of items to request per API call)
+    #[arg(long, default_value = "60")]
+    pub request_size: usize,
+
+    /// Drop generated attributes shorter than this many characters after normalization (no minimum if omitted)
+    #[arg(long)]
+    pub min_attribute_length: Option<usize>,
+
+    /// Drop generated attributes longer than this many characters after normalization (no maximum if omitted)
+    #[arg(long)]
+    pub max_attribute_length: Option<usize>,
+
+    /// List recently generated attributes in each prompt so the model steers away from them
+    #[arg(long)]
+    pub include_seen_hint: bool,
+
+    /// Most recent attributes listed by --include-seen-hint
+    #[arg(long, default_value = "50")]
+    pub seen_hint_count: usize,
+
+    /// Output directory (relative or absolute path, defaults to ./output/<topic>)
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// CLI mode: Also stream unique attributes to stdout, one per line (logs go to stderr)
+    #[arg(long)]
+    pub stdout_output: bool,
+
+    /// CLI mode: Hold output.txt lines in memory and write them on each flush instead of every iteration
+    #[arg(long)]
+    pub buffer_output: bool,
+
+    /// Minimum free space (MB) required in the output directory before starting (0 disables)
+    #[arg(long, default_value = "64")]
+    pub min_free_space_mb: u64,
+
+    /// Stop CLI generation after this many seconds (unlimited if omitted)
+    #[arg(long)]
+    pub max_runtime_seconds: Option<u64>,
+
+    /// Stop CLI generation once cycle efficiency stays below this percentage (disabled if omitted)
+    #[arg(long)]
+    pub min_efficiency_percent: Option<f64>,
+
+    /// Consecutive cycles below --min-efficiency-percent before stopping (default: 3)
+    #[arg(long, requires = "min_efficiency_percent")]
+    pub efficiency_patience: Option<u32>,
+
+    /// Stop CLI generation once this many unique attributes have been generated (unlimited if omitted)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub target_count: Option<u64>,
+
+    /// Seconds between statistics updates sent to the webserver (at least 1)
+    #[arg(long, default_value = "3")]
+    pub metrics_interval_secs: u64,
+
+    /// Seconds between producer health checks (at least 1)
+    #[arg(long, default_value = "10")]
+    pub health_interval_secs: u64,
+
+    /// Seconds between flushes of output to disk with fsync, also done at shutdown (at least 1)
+    #[arg(long, default_value = "5")]
+    pub output_flush_secs: u64,
+
+    /// Half-life in seconds of the performance data behind adaptive routing (default 0 weighs the whole window equally)
+    #[arg(long, default_value = "0")]
+    pub performance_half_life_secs: u64,
+
+    /// Weight of the newest sample in the smoothed UAM and cost per minute shown on the dashboard (0.01-1, 1 disables smoothing)
+    #[arg(long, default_value = "0.3")]
+    pub metrics_smoothing: f64,
+
+    /// Run-level RNG seed for reproducible runs (random if omitted)
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// JSON file of few-shot example attributes passed to producers
+    #[arg(long)]
+    pub examples_file: Option<String>,
+
+    /// JSON file of per-model token prices passed to producers for cost estimates
+    #[arg(long)]
+    pub pricing: Option<String>,
+
+    /// Deduplication normalization (exact, case-insensitive, trimmed, case-insensitive-trimmed)
+    #[arg(long, default_value = "exact")]
+    pub normalization: NormalizationMode,
+
+    /// Newline-delimited known attributes counted as already seen at topic start
+    #[arg(long)]
+    pub seed_file: Option<PathBuf>,
+
+    /// Keep every generated attribute instead of dropping duplicates (e.g. for sampling distributions)
+    #[arg(long)]
+    pub no_dedup: bool,
+
+    /// Let producers auto-tune provider concurrency; measured limits land in provider_performance.json
+    #[arg(long)]
+    pub auto_tune: bool,
+
+    /// Let producers retry a request blocked by a content filter once with a reworded prompt
+    #[arg(long)]
+    pub reword_filtered_prompts: bool,
+
+    /// Let producers answer repeats of a recent or in-flight identical request from a shared response
+    #[arg(long)]
+    pub enable_request_cache: bool,
+
+    /// How long a cached response may answer repeat requests (with --enable-request-cache)
+    #[arg(long, default_value = "5000")]
+    pub request_cache_ttl_ms: u64,
+
+    /// Provider that producers ask to re-check sampled attributes (format: "provider" or "provider:model")
+    #[arg(long)]
+    pub verification_provider: Option<String>,
+
+    /// Fraction of accepted attributes re-checked by the verification provider (0.0 to 1.0)
+    #[arg(long, default_value = "0.0")]
+    pub verification_sample_rate: f32,
+
+    /// Gzip the bloom filter and seen values sent to producers in sync messages
+    #[arg(long)]
+    pub compress_sync: bool,
+
+    /// CLI mode: What the optimizer aims for (maximize-uam, minimize-cost, efficiency, weighted)
+    #[arg(long, default_value = "efficiency", value_parser = ["maximize-uam", "minimize-cost", "efficiency", "weighted"])]
+    pub optimization_mode: String,
+
+    /// Spending limit in USD per minute (required by maximize-uam)
+    #[arg(long, required_if_eq("optimization_mode", "maximize-uam"))]
+    pub budget_per_minute: Option<f64>,
+
+    /// Unique attributes per minute to sustain (required by minimize-cost)
+    #[arg(long, required_if_eq("optimization_mode", "minimize-cost"))]
+    pub target_uam: Option<f64>,
+
+    /// Weight of unique attributes per minute (required by weighted)
+    #[arg(long, required_if_eq("optimization_mode", "weighted"))]
+    pub uam_weight: Option<f64>,
+
+    /// Weight of cost (required by weighted)
+    #[arg(long, required_if_eq("optimization_mode", "weighted"))]
+    pub cost_weight: Option<f64>,
+
+    /// Weight of token usage (required by weighted)
+    #[arg(long, required_if_eq("optimization_mode", "weighted"))]
+    pub token_weight: Option<f64>,
+
+    /// Optimizer driving prompt and routing updates (basic, adaptive)
+    #[arg(long, default_value = "basic", value_parser = ["basic", "adaptive"])]
+    pub optimizer: String,
+
+    /// TOML or JSON file of prompt templates replacing the built-in prompts (each must contain {topic})
+    #[arg(long)]
+    pub prompt_templates: Option<PathBuf>,
+
+    /// Routing strategy type (backoff, roundrobin, priority, weighted, circuitbreaker, lowestlatency)
+    #[arg(long)]
+    pub routing_strategy: Option<String>,
+
+    /// Strategy-specific routing configuration (e.g., "openai" for backoff, "openai+anthropic" for roundrobin, "openai:0.7+anthropic:0.3" for weighted)
+    #[arg(long)]
+    pub routing_config: Option<String>,
+
+    /// Webserver bind address
+    #[arg(long)]
+    pub webserver_addr: Option<String>,
+
+    /// Producer communication bind address  
+    #[arg(long)]
+    pub producer_addr: Option<String>,
+}
+
+impl Args {
+    /// Parse the process arguments, exiting with usage on error like `Args::parse`
+    pub fn parse_with_config() -> Self {
+        Self::try_parse_with_config(std::env::args_os()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse arguments, taking every flag not given explicitly from the `--config` file
+    pub fn try_parse_with_config<I, T>(itr: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let argv: Vec<OsString> = itr.into_iter().map(Into::into).collect();
+        let command = Self::command();
+        let explicit = command.clone().ignore_errors(true).try_get_matches_from(&argv)?;
+        let Some(path) = explicit.get_one::<PathBuf>("config") else {
+            return Self::try_parse_from(argv);
+        };
+        let config = OrchestratorConfig::load(path).map_err(|e| command.clone().error(ErrorKind::InvalidValue, e))?;
+
+        let given = |id: &str| explicit.value_source(id) == Some(ValueSource::CommandLine);
+        let mut merged: Vec<OsString> = argv.iter().take(1).cloned().collect();
+        for (id, value) in config.arg_values() {
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_id() == id)
+                .unwrap_or_else(|| panic!("config key {id} has no matching argument"));
+            // A file value never blocks a flag the user chose instead of it
+            let conflicts = |a: &clap::Arg, b: &clap::Arg| {
+                command.get_arg_conflicts_with(a).iter().any(|other| other.get_id() == b.get_id())
+            };
+            let overridden = given(id)
+                || command
+                    .get_arguments()
+                    .filter(|other| given(other.get_id().as_str()))
+                    .any(|other| conflicts(arg, other) || conflicts(other, arg));
+            if overridden {
+                continue;
+            }
+            let long = arg.get_long().unwrap_or(id);
+            if arg.get_action().takes_values() {
+                merged.push(format!("--{long}={value}").into());
+            } else {
+                merged.push(format!("--{long}").into());
+            }
+        }
+        merged.extend(argv.into_iter().skip(1));
+        Self::try_parse_from(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/orchestrator.example.toml");
+
+    fn parse(args: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_with_config(std::iter::once("orchestrator").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_config_file_fills_unset_flags_and_cli_overrides() {
+        let args = parse(&["--config", EXAMPLE_CONFIG, "--producers", "7", "--log-level", "warn"]).unwrap();
+
+        // Explicit flags win over the file
+        assert_eq!(args.producers, 7);
+        assert_eq!(args.log_level, "warn");
+
+        // Everything else comes from the file, or the flag default when the file is silent
+        assert_eq!(args.topic.as_deref(), Some("Paris attractions"));
+        assert_eq!(args.iterations, Some(10));
+        assert_eq!(args.request_size, 80);
+        assert_eq!(args.output.as_deref(), Some("./output/paris"));
+        assert_eq!(args.seed, Some(42));
+        assert_eq!(args.optimization_mode, "maximize-uam");
+        assert_eq!(args.budget_per_minute, Some(0.5));
+        assert_eq!(args.optimizer, "adaptive");
+        assert_eq!(args.routing_strategy.as_deref(), Some("backoff"));
+        assert_eq!(args.routing_config.as_deref(), Some("random"));
+        assert_eq!(args.webserver_addr.as_deref(), Some("127.0.0.1:7000"));
+        assert_eq!(args.producer_addr.as_deref(), Some("127.0.0.1:7001"));
+        assert_eq!(args.min_free_space_mb, 64);
+        assert!(!args.no_dedup);
+
+        // A flag that conflicts with a file value replaces it instead of failing
+        let dir = tempfile::tempdir().unwrap();
+        let topics = dir.path().join("topics.txt");
+        let args = parse(&["--config", EXAMPLE_CONFIG, "--topics-file", topics.to_str().unwrap()]).unwrap();
+        assert_eq!(args.topic, None);
+        assert_eq!(args.topics_file, Some(topics));
+    }
+
+    #[test]
+    fn test_empty_config_matches_flag_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.toml");
+        std::fs::write(&path, "").unwrap();
+
+        let from_file = parse(&["--config", path.to_str().unwrap()]).unwrap();
+        let from_flags = parse(&[]).unwrap();
+        assert_eq!(
+            Args { config: None, ..from_file },
+            Args {
+                webserver_addr: Some("127.0.0.1:6000".to_string()),
+                producer_addr: Some("127.0.0.1:6001".to_string()),
+                ..from_flags
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_config_reports_offending_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orchestrator.toml");
+
+        std::fs::write(&path, "producers = 3\nproducerz = 4\n").unwrap();
+        let error = parse(&["--config", path.to_str().unwrap()]).unwrap_err().to_string();
+        assert!(error.contains("unknown field `producerz`"), "{error}");
+
+        std::fs::write(&path, "producers = \"five\"\n").unwrap();
+        let error = parse(&["--config", path.to_str().unwrap()]).unwrap_err().to_string();
+        assert!(error.contains("producers"), "{error}");
+
+        // Values clap rejects on the command line are rejected from the file too
+        std::fs::write(&path, "optimizer = \"genetic\"\n").unwrap();
+        let error = parse(&["--config", path.to_str().unwrap()]).unwrap_err().to_string();
+        assert!(error.contains("--optimizer"), "{error}");
+    }
+}
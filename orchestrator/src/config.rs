@@ -0,0 +1,231 @@
+//! Orchestrator configuration file
+//!
+//! A TOML file holding the same settings as the command line, one key per flag
+//! with dashes replaced by underscores (`--request-size` becomes `request_size`).
+//! Missing keys take the flag defaults, unknown keys are rejected, and flags given
+//! on the command line override the file.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Every orchestrator setting as read from a configuration file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrchestratorConfig {
+    // Logging and tracing
+    pub trace_ep: Option<String>,
+    pub log_level: String,
+    pub plain_logs: bool,
+    pub log_format: String,
+
+    // Generation
+    pub topic: Option<String>,
+    pub topics_file: Option<PathBuf>,
+    pub concurrent_topics: bool,
+    pub producers: u32,
+    pub warm_pool_size: usize,
+    pub iterations: Option<u32>,
+    pub resume: bool,
+    pub request_size: usize,
+    pub min_attribute_length: Option<usize>,
+    pub max_attribute_length: Option<usize>,
+    pub include_seen_hint: bool,
+    pub seen_hint_count: usize,
+    pub max_runtime_seconds: Option<u64>,
+    pub min_efficiency_percent: Option<f64>,
+    pub efficiency_patience: Option<u32>,
+    pub target_count: Option<u64>,
+    pub seed: Option<u64>,
+    pub examples_file: Option<String>,
+    pub pricing: Option<String>,
+    pub normalization: String,
+    pub seed_file: Option<PathBuf>,
+    pub no_dedup: bool,
+    pub auto_tune: bool,
+    pub reword_filtered_prompts: bool,
+    pub enable_request_cache: bool,
+    pub request_cache_ttl_ms: u64,
+    pub verification_provider: Option<String>,
+    pub verification_sample_rate: f32,
+    pub compress_sync: bool,
+
+    // Output
+    pub output: Option<String>,
+    pub stdout_output: bool,
+    pub buffer_output: bool,
+    pub min_free_space_mb: u64,
+
+    // Loop timing
+    pub metrics_interval_secs: u64,
+    pub health_interval_secs: u64,
+    pub output_flush_secs: u64,
+    pub performance_half_life_secs: u64,
+    pub metrics_smoothing: f64,
+
+    // Optimization
+    pub optimization_mode: String,
+    pub budget_per_minute: Option<f64>,
+    pub target_uam: Option<f64>,
+    pub uam_weight: Option<f64>,
+    pub cost_weight: Option<f64>,
+    pub token_weight: Option<f64>,
+    pub optimizer: String,
+    pub prompt_templates: Option<PathBuf>,
+
+    // Provider routing
+    pub routing_strategy: Option<String>,
+    pub routing_config: Option<String>,
+
+    // Addresses
+    pub webserver_addr: String,
+    pub producer_addr: String,
+}
+
+impl Default for OrchestratorConfig {
+    fn default() -> Self {
+        Self {
+            trace_ep: None,
+            log_level: "info".to_string(),
+            plain_logs: false,
+            log_format: "text".to_string(),
+            topic: None,
+            topics_file: None,
+            concurrent_topics: false,
+            producers: 5,
+            warm_pool_size: 0,
+            iterations: None,
+            resume: false,
+            request_size: 60,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 50,
+            max_runtime_seconds: None,
+            min_efficiency_percent: None,
+            efficiency_patience: None,
+            target_count: None,
+            seed: None,
+            examples_file: None,
+            pricing: None,
+            normalization: "exact".to_string(),
+            seed_file: None,
+            no_dedup: false,
+            auto_tune: false,
+            reword_filtered_prompts: false,
+            enable_request_cache: false,
+            request_cache_ttl_ms: 5000,
+            verification_provider: None,
+            verification_sample_rate: 0.0,
+            compress_sync: false,
+            output: None,
+            stdout_output: false,
+            buffer_output: false,
+            min_free_space_mb: 64,
+            metrics_interval_secs: 3,
+            health_interval_secs: 10,
+            output_flush_secs: 5,
+            performance_half_life_secs: 0,
+            metrics_smoothing: 0.3,
+            optimization_mode: "efficiency".to_string(),
+            budget_per_minute: None,
+            target_uam: None,
+            uam_weight: None,
+            cost_weight: None,
+            token_weight: None,
+            optimizer: "basic".to_string(),
+            prompt_templates: None,
+            routing_strategy: None,
+            routing_config: None,
+            webserver_addr: "127.0.0.1:6000".to_string(),
+            producer_addr: "127.0.0.1:6001".to_string(),
+        }
+    }
+}
+
+impl OrchestratorConfig {
+    /// Read and validate a configuration file
+    pub fn load(path: &Path) -> OrchestratorResult<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            OrchestratorError::config(format!("Failed to read config file {}: {}", path.display(), e))
+        })?;
+        Self::from_toml(&contents)
+            .map_err(|e| OrchestratorError::config(format!("Invalid config file {}: {}", path.display(), e)))
+    }
+
+    /// Parse configuration from TOML text; errors name the offending key
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Settings as `(argument id, value)` pairs for the command-line parser
+    ///
+    /// Unset options are left out, and boolean flags only appear when enabled.
+    pub fn arg_values(&self) -> Vec<(&'static str, String)> {
+        let mut values = Vec::new();
+        let mut set = |id: &'static str, value: Option<String>| {
+            if let Some(value) = value {
+                values.push((id, value));
+            }
+        };
+        let flag = |enabled: bool| enabled.then(|| "true".to_string());
+        let text = |value: &Option<String>| value.clone();
+        let number = |value: Option<f64>| value.map(|v| v.to_string());
+
+        set("trace_ep", text(&self.trace_ep));
+        set("log_level", Some(self.log_level.clone()));
+        set("plain_logs", flag(self.plain_logs));
+        set("log_format", Some(self.log_format.clone()));
+        set("topic", text(&self.topic));
+        set("topics_file", self.topics_file.as_ref().map(|path| path.display().to_string()));
+        set("concurrent_topics", flag(self.concurrent_topics));
+        set("producers", Some(self.producers.to_string()));
+        set("warm_pool_size", Some(self.warm_pool_size.to_string()));
+        set("iterations", self.iterations.map(|v| v.to_string()));
+        set("resume", flag(self.resume));
+        set("request_size", Some(self.request_size.to_string()));
+        set("min_attribute_length", self.min_attribute_length.map(|v| v.to_string()));
+        set("max_attribute_length", self.max_attribute_length.map(|v| v.to_string()));
+        set("include_seen_hint", flag(self.include_seen_hint));
+        set("seen_hint_count", Some(self.seen_hint_count.to_string()));
+        set("max_runtime_seconds", self.max_runtime_seconds.map(|v| v.to_string()));
+        set("min_efficiency_percent", number(self.min_efficiency_percent));
+        set("efficiency_patience", self.efficiency_patience.map(|v| v.to_string()));
+        set("target_count", self.target_count.map(|v| v.to_string()));
+        set("seed", self.seed.map(|v| v.to_string()));
+        set("examples_file", text(&self.examples_file));
+        set("pricing", text(&self.pricing));
+        set("normalization", Some(self.normalization.clone()));
+        set("seed_file", self.seed_file.as_ref().map(|path| path.display().to_string()));
+        set("no_dedup", flag(self.no_dedup));
+        set("auto_tune", flag(self.auto_tune));
+        set("reword_filtered_prompts", flag(self.reword_filtered_prompts));
+        set("enable_request_cache", flag(self.enable_request_cache));
+        set("request_cache_ttl_ms", Some(self.request_cache_ttl_ms.to_string()));
+        set("verification_provider", text(&self.verification_provider));
+        set("verification_sample_rate", Some(self.verification_sample_rate.to_string()));
+        set("compress_sync", flag(self.compress_sync));
+        set("output", text(&self.output));
+        set("stdout_output", flag(self.stdout_output));
+        set("buffer_output", flag(self.buffer_output));
+        set("min_free_space_mb", Some(self.min_free_space_mb.to_string()));
+        set("metrics_interval_secs", Some(self.metrics_interval_secs.to_string()));
+        set("health_interval_secs", Some(self.health_interval_secs.to_string()));
+        set("output_flush_secs", Some(self.output_flush_secs.to_string()));
+        set("performance_half_life_secs", Some(self.performance_half_life_secs.to_string()));
+        set("metrics_smoothing", Some(self.metrics_smoothing.to_string()));
+        set("optimization_mode", Some(self.optimization_mode.clone()));
+        set("budget_per_minute", number(self.budget_per_minute));
+        set("target_uam", number(self.target_uam));
+        set("uam_weight", number(self.uam_weight));
+        set("cost_weight", number(self.cost_weight));
+        set("token_weight", number(self.token_weight));
+        set("optimizer", Some(self.optimizer.clone()));
+        set("prompt_templates", self.prompt_templates.as_ref().map(|path| path.display().to_string()));
+        set("routing_strategy", text(&self.routing_strategy));
+        set("routing_config", text(&self.routing_config));
+        set("webserver_addr", Some(self.webserver_addr.clone()));
+        set("producer_addr", Some(self.producer_addr.clone()));
+        values
+    }
+}
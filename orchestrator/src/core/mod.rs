@@ -9,4 +9,4 @@ pub mod uniqueness;
 
 pub use performance::PerformanceTracker;
 pub use state::OrchestratorState;
-pub use uniqueness::UniquenessTracker;
+pub use uniqueness::{read_seed_file, NormalizationMode, UniquenessTracker};
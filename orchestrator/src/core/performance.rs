@@ -2,11 +2,22 @@
 //!
 //! This module tracks the three key metrics: UAM (Unique Attributes per Minute),
 //! token usage, and cost across rolling time windows for optimization decisions.
+//! Older buckets can decay exponentially with a configurable half-life, so the statistics
+//! follow changes in provider performance instead of the whole window's average; decay
+//! is off by default and the whole window is weighed equally.
+//! The overall UAM and cost per minute are also kept as exponential moving averages
+//! updated once per statistics tick, giving the dashboard a steadier line than the raw window.
 
 use shared::{ProviderId, TokenUsage};
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
+/// Default weight of the newest sample in the smoothed rates
+pub const DEFAULT_METRICS_SMOOTHING: f64 = 0.3;
+
+/// Smallest accepted smoothing factor, so the averages never stop following the raw rates
+const MIN_METRICS_SMOOTHING: f64 = 0.01;
+
 /// Tracks performance metrics over rolling time windows
 pub struct PerformanceTracker {
     /// Time buckets for the last 5 minutes (30-second buckets)
@@ -15,15 +26,18 @@ pub struct PerformanceTracker {
     /// Current aggregated performance statistics
     current_stats: PerformanceStats,
 
-    /// Cost model for each provider
-    provider_costs: HashMap<ProviderId, CostModel>,
-
     /// When we last recalculated statistics
     last_stats_update: Instant,
 
     /// Configuration
     bucket_duration: Duration,
     max_buckets: usize,
+
+    /// Half-life of bucket weights (None weighs every bucket equally)
+    half_life: Option<Duration>,
+
+    /// Weight of the newest sample in the moving averages (1.0 follows the raw rates)
+    smoothing_factor: f64,
 }
 
 /// Performance metrics for a 30-second time bucket
@@ -50,24 +64,34 @@ pub struct BucketMetrics {
     pub tokens_used: TokenUsage,
     pub cost_usd: f64,
     pub request_count: u64,
+    pub outcomes: RequestOutcomes,
 }
 
-/// Cost model for a provider
-#[derive(Debug, Clone)]
-pub struct CostModel {
-    pub input_cost_per_1k: f64,  // USD per 1K input tokens
-    pub output_cost_per_1k: f64, // USD per 1K output tokens
-    pub model_name: String,
+/// Completed provider requests as reported by producers
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RequestOutcomes {
+    pub successes: u64,
+    pub failures: u64,
+    pub total_response_time_ms: f64,
 }
 
 /// Current performance statistics (last 5 minutes)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PerformanceStats {
     pub overall: PerformanceMetrics,
     pub by_producer: HashMap<shared::ProcessId, PerformanceMetrics>,
     pub by_provider: HashMap<ProviderId, PerformanceMetrics>,
     pub efficiency: EfficiencyMetrics,
     pub trends: TrendMetrics,
+    /// Moving averages of the overall rates, None until the first recalculation with data
+    pub smoothed: Option<SmoothedMetrics>,
+}
+
+/// Exponential moving averages of the overall rates
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SmoothedMetrics {
+    pub uam: f64,
+    pub cost_per_minute: f64,
 }
 
 /// Individual performance metrics
@@ -80,6 +104,8 @@ pub struct PerformanceMetrics {
     pub unique_per_1k_tokens: f64,
     pub uniqueness_ratio: f64, // unique/total ratio
     pub request_rate: f64,     // requests per minute
+    pub success_rate: f64,     // successful/completed requests (1.0 before any are reported)
+    pub avg_response_time_ms: f64,
 }
 
 /// Efficiency comparison metrics
@@ -108,19 +134,53 @@ pub enum TrendDirection {
     Declining,
 }
 
+impl Default for PerformanceTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PerformanceTracker {
     /// Create new performance tracker
     pub fn new() -> Self {
         Self {
             time_buckets: VecDeque::new(),
             current_stats: PerformanceStats::default(),
-            provider_costs: Self::default_cost_models(),
             last_stats_update: Instant::now(),
             bucket_duration: Duration::from_secs(30), // 30-second buckets
             max_buckets: 10,                          // 5 minutes of history
+            half_life: None,
+            smoothing_factor: DEFAULT_METRICS_SMOOTHING,
         }
     }
 
+    /// Decay bucket weights with this half-life, or weigh the whole window equally with None
+    pub fn with_half_life(mut self, half_life: Option<Duration>) -> Self {
+        self.half_life = half_life.filter(|h| !h.is_zero());
+        self
+    }
+
+    /// Half-life applied to bucket weights
+    pub fn half_life(&self) -> Option<Duration> {
+        self.half_life
+    }
+
+    /// Weight the newest sample by `factor` in the smoothed rates, clamped to 0.01..=1
+    ///
+    /// Lower values give a steadier but slower line; 1 follows the raw rates. Non-finite
+    /// values keep the current factor.
+    pub fn with_smoothing_factor(mut self, factor: f64) -> Self {
+        if factor.is_finite() {
+            self.smoothing_factor = factor.clamp(MIN_METRICS_SMOOTHING, 1.0);
+        }
+        self
+    }
+
+    /// Weight of the newest sample in the smoothed rates
+    pub fn smoothing_factor(&self) -> f64 {
+        self.smoothing_factor
+    }
+
     /// Reset for new topic
     pub fn reset(&mut self) {
         self.time_buckets.clear();
@@ -129,6 +189,9 @@ impl PerformanceTracker {
     }
 
     /// Record a contribution from a producer
+    ///
+    /// `cost_usd` is the request cost the producer priced from its own model and
+    /// token usage, so the tracker never re-derives it.
     pub fn record_contribution(
         &mut self,
         producer_id: shared::ProcessId,
@@ -136,19 +199,18 @@ impl PerformanceTracker {
         unique_count: u64,
         total_count: u64,
         tokens: TokenUsage,
+        cost_usd: f64,
     ) {
         let now = Instant::now();
 
-        // Calculate cost for this contribution
-        let cost = self.calculate_cost(&provider_id, &tokens);
-
         // Create bucket metrics
         let metrics = BucketMetrics {
             unique_attributes: unique_count,
             total_attributes: total_count,
             tokens_used: tokens,
-            cost_usd: cost,
+            cost_usd,
             request_count: 1,
+            outcomes: RequestOutcomes::default(),
         };
 
         // Get or create current bucket
@@ -176,7 +238,37 @@ impl PerformanceTracker {
         self.last_stats_update = now;
     }
 
-    /// Get current performance statistics
+    /// Record request successes, failures and latency a producer reported for a provider
+    pub fn record_request_outcomes(
+        &mut self,
+        producer_id: shared::ProcessId,
+        provider_id: ProviderId,
+        outcomes: RequestOutcomes,
+    ) {
+        let now = Instant::now();
+        let metrics = BucketMetrics {
+            outcomes,
+            ..BucketMetrics::default()
+        };
+
+        let current_bucket = self.get_or_create_current_bucket(now);
+        current_bucket
+            .producer_metrics
+            .entry(producer_id)
+            .and_modify(|m| m.add(&metrics))
+            .or_insert(metrics.clone());
+        current_bucket
+            .provider_metrics
+            .entry(provider_id)
+            .and_modify(|m| m.add(&metrics))
+            .or_insert(metrics.clone());
+        current_bucket.total_metrics.add(&metrics);
+
+        self.recalculate_stats();
+        self.last_stats_update = now;
+    }
+
+    /// Get current performance statistics, with older buckets decayed by the half-life
     pub fn get_current_stats(&self) -> &PerformanceStats {
         &self.current_stats
     }
@@ -209,6 +301,16 @@ impl PerformanceTracker {
         self.current_stats.trends = self.calculate_trend_metrics();
     }
 
+    /// Fold the current overall rates into the moving averages
+    ///
+    /// Called once per statistics tick rather than from `recalculate_stats`, which runs on
+    /// every recorded contribution, so the averages follow time instead of traffic volume.
+    pub fn update_smoothed(&mut self) {
+        if !self.time_buckets.is_empty() {
+            self.current_stats.smoothed = Some(self.smoothed_metrics());
+        }
+    }
+
     /// Get or create the current time bucket
     fn get_or_create_current_bucket(&mut self, now: Instant) -> &mut TimeBucket {
         // Check if we need a new bucket - current bucket is "full" if it's older than bucket_duration
@@ -244,42 +346,86 @@ impl PerformanceTracker {
         self.time_buckets.retain(|bucket| bucket.start_time > cutoff_time);
     }
 
-    /// Calculate duration covered by current buckets
+    /// Weight of each bucket, halving per half-life of age behind the newest bucket
+    ///
+    /// Ages are measured between bucket start times so every contribution within a
+    /// bucket shares one weight and the newest bucket always counts in full.
+    fn bucket_weights(&self) -> Vec<f64> {
+        let Some(newest) = self.time_buckets.back().map(|bucket| bucket.start_time) else {
+            return Vec::new();
+        };
+
+        self.time_buckets
+            .iter()
+            .map(|bucket| match self.half_life {
+                Some(half_life) => {
+                    let age = newest.duration_since(bucket.start_time);
+                    0.5_f64.powf(age.as_secs_f64() / half_life.as_secs_f64())
+                }
+                None => 1.0,
+            })
+            .collect()
+    }
+
+    /// Calculate the decayed duration covered by current buckets
+    ///
+    /// Each bucket's span (up to the next bucket's start, or now for the newest) is
+    /// weighted like its contributions, so a steady rate reads the same with or without decay.
     fn calculate_duration_minutes(&self) -> f64 {
         if self.time_buckets.is_empty() {
             return 1.0; // Avoid division by zero
         }
 
-        let oldest = self.time_buckets.front().unwrap().start_time;
-        let newest = Instant::now(); // Use current time instead of future end_time
-        let duration = newest.duration_since(oldest);
+        let now = Instant::now(); // Use current time instead of future end_time
+        let ends = self.time_buckets.iter().skip(1).map(|bucket| bucket.start_time).chain([now]);
+        let seconds: f64 = self
+            .time_buckets
+            .iter()
+            .zip(ends)
+            .zip(self.bucket_weights())
+            .map(|((bucket, end), weight)| end.duration_since(bucket.start_time).as_secs_f64() * weight)
+            .sum();
 
         // Ensure minimum duration to avoid division by zero
         let min_duration = 1.0; // 1 second minimum
-        (duration.as_secs_f64() / 60.0).max(min_duration / 60.0)
+        (seconds / 60.0).max(min_duration / 60.0)
     }
 
     /// Calculate overall metrics across all buckets
     fn calculate_overall_metrics(&self, duration_minutes: f64) -> PerformanceMetrics {
-        let mut total = BucketMetrics::default();
+        let mut total = DecayedMetrics::default();
 
-        for bucket in &self.time_buckets {
-            total.add(&bucket.total_metrics);
+        for (bucket, weight) in self.time_buckets.iter().zip(self.bucket_weights()) {
+            total.add(&bucket.total_metrics, weight);
         }
 
         self.metrics_from_bucket_data(&total, duration_minutes)
     }
 
+    /// Fold the latest overall rates into the moving averages
+    fn smoothed_metrics(&self) -> SmoothedMetrics {
+        let overall = &self.current_stats.overall;
+        let Some(previous) = self.current_stats.smoothed else {
+            return SmoothedMetrics {
+                uam: overall.uam,
+                cost_per_minute: overall.cost_per_minute,
+            };
+        };
+
+        let alpha = self.smoothing_factor;
+        SmoothedMetrics {
+            uam: alpha * overall.uam + (1.0 - alpha) * previous.uam,
+            cost_per_minute: alpha * overall.cost_per_minute + (1.0 - alpha) * previous.cost_per_minute,
+        }
+    }
+
     /// Calculate per-producer metrics
     fn calculate_producer_metrics(&self, duration_minutes: f64) -> HashMap<shared::ProcessId, PerformanceMetrics> {
-        let mut producer_totals: HashMap<shared::ProcessId, BucketMetrics> = HashMap::new();
+        let mut producer_totals: HashMap<shared::ProcessId, DecayedMetrics> = HashMap::new();
 
-        for bucket in &self.time_buckets {
+        for (bucket, weight) in self.time_buckets.iter().zip(self.bucket_weights()) {
             for (producer_id, metrics) in &bucket.producer_metrics {
-                producer_totals
-                    .entry(producer_id.clone())
-                    .and_modify(|total| total.add(metrics))
-                    .or_insert(metrics.clone());
+                producer_totals.entry(producer_id.clone()).or_default().add(metrics, weight);
             }
         }
 
@@ -291,14 +437,11 @@ impl PerformanceTracker {
 
     /// Calculate per-provider metrics
     fn calculate_provider_metrics(&self, duration_minutes: f64) -> HashMap<ProviderId, PerformanceMetrics> {
-        let mut provider_totals: HashMap<ProviderId, BucketMetrics> = HashMap::new();
+        let mut provider_totals: HashMap<ProviderId, DecayedMetrics> = HashMap::new();
 
-        for bucket in &self.time_buckets {
+        for (bucket, weight) in self.time_buckets.iter().zip(self.bucket_weights()) {
             for (provider_id, metrics) in &bucket.provider_metrics {
-                provider_totals
-                    .entry(*provider_id)
-                    .and_modify(|total| total.add(metrics))
-                    .or_insert(metrics.clone());
+                provider_totals.entry(*provider_id).or_default().add(metrics, weight);
             }
         }
 
@@ -309,27 +452,29 @@ impl PerformanceTracker {
     }
 
     /// Convert bucket data to performance metrics
-    fn metrics_from_bucket_data(&self, data: &BucketMetrics, duration_minutes: f64) -> PerformanceMetrics {
+    fn metrics_from_bucket_data(&self, data: &DecayedMetrics, duration_minutes: f64) -> PerformanceMetrics {
         PerformanceMetrics {
-            uam: data.unique_attributes as f64 / duration_minutes,
-            tokens_per_minute: data.tokens_used.total() as f64 / duration_minutes,
+            uam: data.unique_attributes / duration_minutes,
+            tokens_per_minute: data.tokens_used / duration_minutes,
             cost_per_minute: data.cost_usd / duration_minutes,
             unique_per_dollar: if data.cost_usd > 0.0 {
-                data.unique_attributes as f64 / data.cost_usd
+                data.unique_attributes / data.cost_usd
             } else {
                 0.0
             },
-            unique_per_1k_tokens: if data.tokens_used.total() > 0 {
-                (data.unique_attributes as f64 / data.tokens_used.total() as f64) * 1000.0
+            unique_per_1k_tokens: if data.tokens_used > 0.0 {
+                (data.unique_attributes / data.tokens_used) * 1000.0
             } else {
                 0.0
             },
-            uniqueness_ratio: if data.total_attributes > 0 {
-                data.unique_attributes as f64 / data.total_attributes as f64
+            uniqueness_ratio: if data.total_attributes > 0.0 {
+                data.unique_attributes / data.total_attributes
             } else {
                 0.0
             },
-            request_rate: data.request_count as f64 / duration_minutes,
+            request_rate: data.request_count / duration_minutes,
+            success_rate: data.success_rate(),
+            avg_response_time_ms: data.avg_response_time_ms(),
         }
     }
 
@@ -398,65 +543,11 @@ impl PerformanceTracker {
         }
     }
 
-    /// Calculate cost for a token usage
-    fn calculate_cost(&self, provider_id: &ProviderId, tokens: &TokenUsage) -> f64 {
-        if let Some(cost_model) = self.provider_costs.get(provider_id) {
-            let input_cost = (tokens.input_tokens as f64 / 1000.0) * cost_model.input_cost_per_1k;
-            let output_cost = (tokens.output_tokens as f64 / 1000.0) * cost_model.output_cost_per_1k;
-            input_cost + output_cost
-        } else {
-            0.0 // Unknown provider
-        }
-    }
-
-    /// Default cost models for providers
-    fn default_cost_models() -> HashMap<ProviderId, CostModel> {
-        let mut models = HashMap::new();
-
-        models.insert(
-            ProviderId::OpenAI,
-            CostModel {
-                input_cost_per_1k: 0.00015, // GPT-4o-mini pricing: $0.15/1M input tokens
-                output_cost_per_1k: 0.0006,  // GPT-4o-mini pricing: $0.60/1M output tokens
-                model_name: "gpt-4o-mini".to_string(),
-            },
-        );
-
-        models.insert(
-            ProviderId::Anthropic,
-            CostModel {
-                input_cost_per_1k: 0.015, // Claude 3 pricing
-                output_cost_per_1k: 0.075,
-                model_name: "claude-3-opus".to_string(),
-            },
-        );
-
-        models.insert(
-            ProviderId::Gemini,
-            CostModel {
-                input_cost_per_1k: 0.00125, // Gemini Pro pricing
-                output_cost_per_1k: 0.00375,
-                model_name: "gemini-pro".to_string(),
-            },
-        );
-
-        models.insert(
-            ProviderId::Random,
-            CostModel {
-                input_cost_per_1k: 0.001,  // Test pricing - low cost
-                output_cost_per_1k: 0.001, // Test pricing - low cost
-                model_name: "random-test".to_string(),
-            },
-        );
-
-        models
-    }
-
-    // Helper methods for trend calculation
-    fn sum_bucket_metrics(&self, buckets: &[&TimeBucket]) -> BucketMetrics {
-        let mut total = BucketMetrics::default();
+    // Helper methods for trend calculation; the halves are compared undecayed
+    fn sum_bucket_metrics(&self, buckets: &[&TimeBucket]) -> DecayedMetrics {
+        let mut total = DecayedMetrics::default();
         for bucket in buckets {
-            total.add(&bucket.total_metrics);
+            total.add(&bucket.total_metrics, 1.0);
         }
         total
     }
@@ -485,7 +576,7 @@ impl PerformanceTracker {
 
         // Lower variance = higher stability (inverted and clamped to 0-1)
         let avg_variance = (uam_variance + cost_variance) / 2.0;
-        (1.0 - avg_variance).max(0.0).min(1.0)
+        (1.0 - avg_variance).clamp(0.0, 1.0)
     }
 }
 
@@ -498,17 +589,69 @@ impl BucketMetrics {
         self.tokens_used.output_tokens += other.tokens_used.output_tokens;
         self.cost_usd += other.cost_usd;
         self.request_count += other.request_count;
+        self.outcomes.successes += other.outcomes.successes;
+        self.outcomes.failures += other.outcomes.failures;
+        self.outcomes.total_response_time_ms += other.outcomes.total_response_time_ms;
     }
 }
 
-impl Default for PerformanceStats {
-    fn default() -> Self {
-        Self {
-            overall: PerformanceMetrics::default(),
-            by_producer: HashMap::new(),
-            by_provider: HashMap::new(),
-            efficiency: EfficiencyMetrics::default(),
-            trends: TrendMetrics::default(),
+/// Bucket metrics summed with per-bucket decay weights
+#[derive(Debug, Clone, Default)]
+struct DecayedMetrics {
+    unique_attributes: f64,
+    total_attributes: f64,
+    tokens_used: f64,
+    cost_usd: f64,
+    request_count: f64,
+    successes: f64,
+    failures: f64,
+    total_response_time_ms: f64,
+}
+
+impl DecayedMetrics {
+    /// Add a bucket's metrics scaled by `weight`
+    fn add(&mut self, metrics: &BucketMetrics, weight: f64) {
+        self.unique_attributes += metrics.unique_attributes as f64 * weight;
+        self.total_attributes += metrics.total_attributes as f64 * weight;
+        self.tokens_used += metrics.tokens_used.total() as f64 * weight;
+        self.cost_usd += metrics.cost_usd * weight;
+        self.request_count += metrics.request_count as f64 * weight;
+        self.successes += metrics.outcomes.successes as f64 * weight;
+        self.failures += metrics.outcomes.failures as f64 * weight;
+        self.total_response_time_ms += metrics.outcomes.total_response_time_ms * weight;
+    }
+
+    /// Weighted share of completed requests that succeeded; no requests counts as healthy
+    fn success_rate(&self) -> f64 {
+        let completed = self.successes + self.failures;
+        if completed > 0.0 { self.successes / completed } else { 1.0 }
+    }
+
+    /// Weighted mean response time over completed requests
+    fn avg_response_time_ms(&self) -> f64 {
+        let completed = self.successes + self.failures;
+        if completed > 0.0 {
+            self.total_response_time_ms / completed
+        } else {
+            0.0
+        }
+    }
+}
+
+impl RequestOutcomes {
+    /// Fraction of completed requests that succeeded; no requests counts as healthy
+    pub fn success_rate(&self) -> f64 {
+        match self.successes + self.failures {
+            0 => 1.0,
+            completed => self.successes as f64 / completed as f64,
+        }
+    }
+
+    /// Mean response time over completed requests
+    pub fn avg_response_time_ms(&self) -> f64 {
+        match self.successes + self.failures {
+            0 => 0.0,
+            completed => self.total_response_time_ms / completed as f64,
         }
     }
 }
@@ -531,6 +674,7 @@ mod tests {
                 input_tokens: 100,
                 output_tokens: 200,
             },
+            0.00015,
         );
 
         tracker.recalculate_stats();
@@ -543,18 +687,38 @@ mod tests {
     }
 
     #[test]
-    fn test_cost_calculation() {
-        let tracker = PerformanceTracker::new();
-        let tokens = TokenUsage {
-            input_tokens: 1000,
-            output_tokens: 500,
+    fn test_request_outcomes_give_success_rate_and_mean_latency() {
+        let mut tracker = PerformanceTracker::new();
+        let report = |successes, failures, total_response_time_ms| RequestOutcomes {
+            successes,
+            failures,
+            total_response_time_ms,
         };
 
-        let cost = tracker.calculate_cost(&ProviderId::OpenAI, &tokens);
-        assert!(cost > 0.0);
+        tracker.record_request_outcomes(shared::ProcessId::Producer(1), ProviderId::OpenAI, report(3, 1, 1600.0));
+        tracker.record_request_outcomes(shared::ProcessId::Producer(2), ProviderId::OpenAI, report(3, 3, 2400.0));
+        tracker.record_request_outcomes(shared::ProcessId::Producer(1), ProviderId::Gemini, report(0, 2, 100.0));
+        tracker.record_contribution(
+            shared::ProcessId::Producer(1),
+            ProviderId::Anthropic,
+            5,
+            10,
+            TokenUsage::default(),
+            0.0,
+        );
+
+        let stats = tracker.get_current_stats();
+        let openai = &stats.by_provider[&ProviderId::OpenAI];
+        assert_eq!(openai.success_rate, 0.6);
+        assert_eq!(openai.avg_response_time_ms, 400.0);
+        assert_eq!(stats.by_provider[&ProviderId::Gemini].success_rate, 0.0);
+        assert_eq!(stats.by_provider[&ProviderId::Gemini].avg_response_time_ms, 50.0);
+
+        // Providers nobody has reported outcomes for yet are not penalised
+        assert_eq!(stats.by_provider[&ProviderId::Anthropic].success_rate, 1.0);
 
-        // Should be: (1000/1000 * 0.00015) + (500/1000 * 0.0006) = 0.00015 + 0.0003 = 0.00045
-        assert!((cost - 0.00045).abs() < 0.000001);
+        assert_eq!(stats.overall.success_rate, 0.5);
+        assert_eq!(stats.by_producer[&shared::ProcessId::Producer(2)].avg_response_time_ms, 400.0);
     }
 
     #[test]
@@ -571,6 +735,7 @@ mod tests {
                 input_tokens: 50,
                 output_tokens: 100,
             },
+            0.0045,
         );
 
         tracker.recalculate_stats();
@@ -581,4 +746,124 @@ mod tests {
         assert_eq!(tracker.get_current_stats().overall.uam, 0.0);
         assert!(tracker.time_buckets.is_empty());
     }
+
+    /// A bucket that started `age_secs` ago holding each provider's unique attributes
+    fn past_bucket(age_secs: u64, unique_by_provider: &[(ProviderId, u64)]) -> TimeBucket {
+        let start_time = Instant::now() - Duration::from_secs(age_secs);
+        let mut bucket = TimeBucket {
+            start_time,
+            end_time: start_time + Duration::from_secs(30),
+            producer_metrics: HashMap::new(),
+            provider_metrics: HashMap::new(),
+            total_metrics: BucketMetrics::default(),
+        };
+        for &(provider_id, unique) in unique_by_provider {
+            let metrics = BucketMetrics {
+                unique_attributes: unique,
+                total_attributes: unique,
+                request_count: 1,
+                ..BucketMetrics::default()
+            };
+            bucket.total_metrics.add(&metrics);
+            bucket.provider_metrics.insert(provider_id, metrics);
+        }
+        bucket
+    }
+
+    #[test]
+    fn test_provider_fast_early_but_slow_recently_loses_priority() {
+        let leader = |tracker: &PerformanceTracker| {
+            let by_provider = &tracker.get_current_stats().by_provider;
+            if by_provider[&ProviderId::OpenAI].uam > by_provider[&ProviderId::Anthropic].uam {
+                ProviderId::OpenAI
+            } else {
+                ProviderId::Anthropic
+            }
+        };
+        let mut decayed = PerformanceTracker::new().with_half_life(Some(Duration::from_secs(60)));
+        let mut cumulative = PerformanceTracker::new().with_half_life(None);
+
+        // OpenAI leads for the first three minutes, Anthropic holds a steady pace throughout
+        for age_secs in (120..=270).rev().step_by(30) {
+            let bucket = past_bucket(age_secs, &[(ProviderId::OpenAI, 100), (ProviderId::Anthropic, 40)]);
+            decayed.time_buckets.push_back(bucket.clone());
+            cumulative.time_buckets.push_back(bucket);
+        }
+        decayed.recalculate_stats();
+        assert_eq!(leader(&decayed), ProviderId::OpenAI);
+
+        // Then OpenAI slows down; each cycle adds another slow bucket
+        for age_secs in [90, 60, 30, 0] {
+            let bucket = past_bucket(age_secs, &[(ProviderId::OpenAI, 10), (ProviderId::Anthropic, 40)]);
+            decayed.time_buckets.push_back(bucket.clone());
+            cumulative.time_buckets.push_back(bucket);
+            decayed.recalculate_stats();
+            cumulative.recalculate_stats();
+        }
+
+        assert_eq!(leader(&decayed), ProviderId::Anthropic);
+        assert_eq!(leader(&cumulative), ProviderId::OpenAI);
+        assert_eq!(decayed.get_current_stats().efficiency.best_uam.unwrap().0, ProviderId::Anthropic);
+    }
+
+    #[test]
+    fn test_smoothed_uam_varies_less_than_raw_uam() {
+        let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+        let variance = |values: &[f64]| {
+            let mean = mean(values);
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+        };
+        let mut tracker = PerformanceTracker::new().with_half_life(None).with_smoothing_factor(0.3);
+
+        // Each tick sees only its latest bucket, alternating bursts and silence
+        let mut raw = Vec::new();
+        let mut smoothed = Vec::new();
+        for tick in 0..20 {
+            let unique = if tick % 2 == 0 { 200 } else { 0 };
+            tracker.time_buckets.clear();
+            tracker.time_buckets.push_back(past_bucket(30, &[(ProviderId::OpenAI, unique)]));
+            tracker.recalculate_stats();
+            tracker.update_smoothed();
+
+            let stats = tracker.get_current_stats();
+            raw.push(stats.overall.uam);
+            smoothed.push(stats.smoothed.unwrap().uam);
+        }
+
+        assert!(variance(&smoothed) < variance(&raw) / 4.0);
+        assert!((mean(&smoothed[10..]) - mean(&raw[10..])).abs() < mean(&raw[10..]) * 0.2);
+
+        // Smoothing restarts from the raw rates once the window empties
+        tracker.reset();
+        assert!(tracker.get_current_stats().smoothed.is_none());
+    }
+
+    #[test]
+    fn test_recorded_contributions_do_not_advance_smoothing() {
+        let mut tracker = PerformanceTracker::new().with_half_life(None).with_smoothing_factor(0.3);
+        tracker.time_buckets.push_back(past_bucket(30, &[(ProviderId::OpenAI, 10)]));
+        tracker.recalculate_stats();
+        tracker.update_smoothed();
+        let before = tracker.get_current_stats().smoothed.unwrap();
+
+        // A burst of contributions between ticks moves the raw rate but not the average
+        for _ in 0..50 {
+            tracker.record_contribution(
+                shared::ProcessId::Producer(1),
+                ProviderId::OpenAI,
+                100,
+                100,
+                TokenUsage::default(),
+                0.0,
+            );
+        }
+        assert!(tracker.get_current_stats().overall.uam > before.uam);
+        assert_eq!(tracker.get_current_stats().smoothed, Some(before));
+
+        // The next tick folds the burst in with a single step
+        tracker.update_smoothed();
+        let after = tracker.get_current_stats().smoothed.unwrap();
+        let expected = 0.3 * tracker.get_current_stats().overall.uam + 0.7 * before.uam;
+        assert!((after.uam - expected).abs() < 1e-9);
+    }
 }
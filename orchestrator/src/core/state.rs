@@ -3,12 +3,31 @@
 //! This module provides the main state structure that coordinates
 //! all aspects of the orchestrator system.
 
-use super::{PerformanceTracker, UniquenessTracker};
-use crate::error::OrchestratorResult;
+use super::performance::RequestOutcomes;
+use super::{NormalizationMode, PerformanceTracker, UniquenessTracker};
+use crate::error::{OrchestratorError, OrchestratorResult};
 use serde::{Deserialize, Serialize};
-use shared::{process_debug, process_info, OrchestratorCommand, ProcessId, ProviderId, SystemMetrics};
+use shared::messages::producer::ProviderUsageStats;
+use shared::messages::webserver::{CompletionReason, OptimizationPlan};
+use shared::{
+    process_debug, process_info, process_warn, OrchestratorCommand, ProcessId, ProviderId, SystemMetrics, TunedLimits,
+    VerificationStats,
+};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Per-topic file holding the CLI iteration checkpoint
+pub const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+/// Per-topic file holding the end-of-run summary
+pub const SUMMARY_FILE: &str = "summary.json";
+
+/// Per-topic file logging every prompt sent to producers
+pub const PROMPTS_FILE: &str = "prompts.jsonl";
+
+/// Output-root file combining the summaries of a `--topics-file` batch
+pub const BATCH_SUMMARY_FILE: &str = "batch_summary.json";
 
 /// Statistics for a single cycle/iteration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,10 +55,24 @@ pub struct ProviderPerformanceStats {
     pub total_cost: f64,
     pub average_response_time_ms: f64,
     pub success_rate: f64,
+    /// Per-producer limits measured by auto-tuning (most conservative across producers)
+    pub tuned_limits: Option<TunedLimits>,
+}
+
+/// Prompt sent to producers, exported as one line of the topic's prompts.jsonl
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptRecord {
+    pub timestamp: String,
+    pub iteration: u32,
+    /// Provider the prompt was assigned to (None when shared by all producers)
+    pub provider: Option<ProviderId>,
+    pub prompt: String,
+    /// What produced the prompt: "start", "optimization" or "recovery"
+    pub source: String,
 }
 
 /// Cycle performance summary for JSON export
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CyclePerformanceSummary {
     pub topic: String,
     pub total_cycles: u32,
@@ -48,7 +81,7 @@ pub struct CyclePerformanceSummary {
 }
 
 /// Summary statistics across all cycles
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CycleSummaryStats {
     pub total_unique_attributes: u64,
     pub average_efficiency: f64,
@@ -58,6 +91,73 @@ pub struct CycleSummaryStats {
     pub attributes_per_minute: f64,
 }
 
+/// Iteration progress persisted after every CLI iteration so a crashed run can resume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub topic: String,
+    pub iteration: u32,
+    pub previous_unique_count: u64,
+    pub cycle_history: Vec<CycleStats>,
+}
+
+impl Checkpoint {
+    /// Load a checkpoint written for `topic`
+    ///
+    /// Returns None when no checkpoint exists; a checkpoint written for a
+    /// different topic is rejected.
+    pub async fn load(file_system: &dyn crate::traits::FileSystem, topic: &str) -> OrchestratorResult<Option<Self>> {
+        let Some(content) = file_system.read_file(&format!("{}/{}", topic, CHECKPOINT_FILE)).await? else {
+            return Ok(None);
+        };
+        let checkpoint: Self = serde_json::from_slice(&content)?;
+        if checkpoint.topic != topic {
+            return Err(OrchestratorError::config(format!(
+                "Checkpoint {} belongs to topic '{}', not '{}'",
+                file_system.topic_file_path(topic, CHECKPOINT_FILE).display(),
+                checkpoint.topic,
+                topic
+            )));
+        }
+        Ok(Some(checkpoint))
+    }
+}
+
+/// Machine-readable record of a finished run
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub topic: String,
+    pub total_iterations: u32,
+    pub final_unique_count: u64,
+    pub completion_reason: CompletionReason,
+    pub started_at: String,
+    pub finished_at: String,
+    pub runtime_seconds: f64,
+    pub cycle_performance: Option<CyclePerformanceSummary>,
+}
+
+/// Combined record of every topic run by one batch
+#[derive(Debug, Serialize)]
+pub struct BatchSummary {
+    pub total_topics: usize,
+    pub completed_topics: usize,
+    pub total_unique_count: u64,
+    pub total_runtime_seconds: f64,
+    pub topics: Vec<RunSummary>,
+}
+
+impl BatchSummary {
+    /// Total up the per-topic summaries of a batch of `total_topics`
+    pub fn new(total_topics: usize, topics: Vec<RunSummary>) -> Self {
+        Self {
+            total_topics,
+            completed_topics: topics.len(),
+            total_unique_count: topics.iter().map(|topic| topic.final_unique_count).sum(),
+            total_runtime_seconds: topics.iter().map(|topic| topic.runtime_seconds).sum(),
+            topics,
+        }
+    }
+}
+
 /// Main orchestrator state containing all system information
 pub struct OrchestratorState {
     /// Uniqueness tracking and bloom filter management
@@ -72,16 +172,50 @@ pub struct OrchestratorState {
     /// Active producer states
     producers: HashMap<ProcessId, ProducerState>,
 
+    /// Replacement producers spawned under a new id, mapped to the id they stand in for
+    producer_aliases: HashMap<ProcessId, ProcessId>,
+
     /// System timing information
     start_time: Instant,
+    started_at: chrono::DateTime<chrono::Utc>,
 
     /// CLI mode: iteration limit and current count
     cli_iterations: Option<u32>,
     current_iteration: u32,
 
+    /// Iterations already completed by the run this one resumed (0 for a fresh run)
+    resumed_iteration: u32,
+
+    /// Wall-clock limit for the current generation and when it started
+    max_runtime: Option<Duration>,
+    generation_started: Option<Instant>,
+
+    /// Why the current generation finished, once it has
+    completion_reason: Option<CompletionReason>,
+
+    /// A manual stop is waiting for the iteration in progress to be written out
+    stop_after_iteration: bool,
+
+    /// Cycle efficiency floor and how many consecutive cycles may fall below it
+    min_efficiency_percent: Option<f64>,
+    efficiency_patience: u32,
+    low_efficiency_cycles: u32,
+
+    /// Stop once this many unique attributes have been generated
+    target_unique_count: Option<u64>,
+
+    /// When the optimizer last reconfigured producers
+    last_reconfigured: Option<Instant>,
+
+    /// Plan behind the current producer configuration, reported to the webserver
+    optimization_plan: Option<OptimizationPlan>,
+
     /// Previous iteration stats for delta calculation
     previous_unique_count: u64,
 
+    /// Every attribute received for the current topic, duplicates included
+    raw_attribute_count: u64,
+
     /// Cycle performance history
     cycle_history: Vec<CycleStats>,
 
@@ -90,6 +224,21 @@ pub struct OrchestratorState {
 
     /// Default routing strategy from orchestrator args/env (global fallback)
     default_routing_strategy: Option<shared::RoutingStrategy>,
+
+    /// Run-level RNG seed sent to producers so a whole run can be replayed
+    run_seed: u64,
+
+    /// Latest auto-tuned provider limits reported by each producer
+    tuned_limits: HashMap<ProcessId, HashMap<ProviderId, TunedLimits>>,
+
+    /// Latest cumulative provider usage reported by each producer, to diff the next report against
+    provider_usage: HashMap<ProcessId, HashMap<ProviderId, ProviderUsageStats>>,
+
+    /// Latest cumulative verification spot checks reported by each producer
+    verification: HashMap<ProcessId, VerificationStats>,
+
+    /// Topics generated side by side under `--concurrent-topics`, keyed by topic
+    topics: HashMap<String, TopicState>,
 }
 
 /// Current generation task configuration
@@ -107,6 +256,12 @@ pub struct GenerationContext {
     /// Whether bloom filter deduplication is required
     pub requires_bloom_filter: bool,
 
+    /// Normalization applied before attributes are compared for uniqueness
+    pub normalization: NormalizationMode,
+
+    /// Drop duplicate attributes; when false every generated item is kept
+    pub dedup_enabled: bool,
+
     /// Optimization targets
     pub optimization_targets: OptimizationTargets,
 }
@@ -128,6 +283,84 @@ pub struct ProducerState {
     pub last_sync_version: Option<u64>, // Last bloom filter version sent
     pub consecutive_failures: u32,
     pub started_for_current_topic: bool, // Track if producer has been sent Start command for current topic
+    pub config: Option<ProducerConfig>,  // Configuration last delivered to the producer
+    /// Prompt partition the producer was started on, None when all producers share one prompt
+    pub category: Option<String>,
+}
+
+/// Prompt, routing and generation settings a producer is running with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProducerConfig {
+    pub prompt: String,
+    pub routing_strategy: shared::RoutingStrategy,
+    pub generation_config: shared::GenerationConfig,
+}
+
+impl ProducerConfig {
+    /// Names of the settings that differ in `other`
+    pub fn changes(&self, other: &ProducerConfig) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if self.prompt != other.prompt {
+            changes.push("prompt");
+        }
+        if self.routing_strategy != other.routing_strategy {
+            changes.push("routing strategy");
+        }
+        if self.generation_config != other.generation_config {
+            changes.push("generation config");
+        }
+        changes
+    }
+}
+
+/// Progress of one topic generated alongside others under `--concurrent-topics`
+///
+/// Each topic deduplicates against its own tracker, so an attribute already found
+/// for one topic is still new to another. Performance tracking stays shared, since
+/// it measures providers rather than topics.
+pub struct TopicState {
+    uniqueness: UniquenessTracker,
+    producers: Vec<ProcessId>,
+    current_iteration: u32,
+    completed: bool,
+}
+
+impl TopicState {
+    fn new(normalization: NormalizationMode, producers: Vec<ProcessId>) -> Self {
+        let mut uniqueness = UniquenessTracker::new();
+        uniqueness.set_normalization_mode(normalization);
+        Self {
+            uniqueness,
+            producers,
+            current_iteration: 0,
+            completed: false,
+        }
+    }
+
+    /// Producers generating for this topic
+    pub fn producers(&self) -> &[ProcessId] {
+        &self.producers
+    }
+
+    /// Unique attributes found for this topic
+    pub fn unique_count(&self) -> u64 {
+        self.uniqueness.total_unique_count()
+    }
+
+    pub fn current_iteration(&self) -> u32 {
+        self.current_iteration
+    }
+
+    /// Whether the topic has reached its iteration limit
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+}
+
+impl Default for OrchestratorState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OrchestratorState {
@@ -138,60 +371,137 @@ impl OrchestratorState {
             performance: PerformanceTracker::new(),
             context: GenerationContext::default(),
             producers: HashMap::new(),
+            producer_aliases: HashMap::new(),
             start_time: Instant::now(),
+            started_at: chrono::Utc::now(),
             cli_iterations: None,
             current_iteration: 0,
+            resumed_iteration: 0,
+            max_runtime: None,
+            generation_started: None,
+            completion_reason: None,
+            stop_after_iteration: false,
+            min_efficiency_percent: None,
+            efficiency_patience: shared::types::DEFAULT_EFFICIENCY_PATIENCE,
+            low_efficiency_cycles: 0,
+            target_unique_count: None,
+            last_reconfigured: None,
+            optimization_plan: None,
             previous_unique_count: 0,
+            raw_attribute_count: 0,
             cycle_history: Vec::new(),
             pending_start_commands: HashMap::new(),
             default_routing_strategy: None,
+            run_seed: Self::entropy_seed(),
+            tuned_limits: HashMap::new(),
+            verification: HashMap::new(),
+            provider_usage: HashMap::new(),
+            topics: HashMap::new(),
         }
     }
 
-    /// Set the default routing strategy from orchestrator args/env
-    pub fn set_default_routing_strategy(&mut self, strategy: Option<shared::RoutingStrategy>) {
-        self.default_routing_strategy = strategy;
+    /// Seed used when none is configured: different for every run
+    fn entropy_seed() -> u64 {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        shared::derive_seed(nanos, std::process::id() as u64)
     }
 
-    /// Get the default routing strategy (orchestrator fallback)
-    pub fn get_default_routing_strategy(&self) -> Option<&shared::RoutingStrategy> {
-        self.default_routing_strategy.as_ref()
+    /// Fix the run-level RNG seed (for reproducible runs)
+    pub fn set_run_seed(&mut self, seed: u64) {
+        self.run_seed = seed;
     }
 
-    /// Initialize system for a new topic
-    pub fn initialize_topic(
-        &mut self,
-        topic: String,
-        producer_count: u32,
-        optimization_targets: OptimizationTargets,
-    ) -> OrchestratorResult<()> {
-        // Reset state for new topic
-        self.uniqueness.reset();
-        self.performance.reset();
+    /// Choose how attributes are normalized before deduplication
+    pub fn set_normalization_mode(&mut self, mode: NormalizationMode) {
+        self.context.normalization = mode;
+        self.uniqueness.set_normalization_mode(mode);
+    }
 
-        // Update context
-        self.context.topic = Some(topic.clone());
-        self.context.optimization_targets = optimization_targets;
+    /// Decay performance data with this half-life; None or zero weighs the whole window equally
+    pub fn set_performance_half_life(&mut self, half_life: Option<Duration>) {
+        let tracker = std::mem::take(&mut self.performance);
+        self.performance = tracker.with_half_life(half_life);
+        self.performance.recalculate_stats();
+    }
 
-        // Initialize producer states
-        self.producers.clear();
-        for i in 0..producer_count {
-            let producer_id = ProcessId::Producer(i + 1);
-            let producer_state = ProducerState {
-                id: producer_id.clone(),
-                status: shared::ProcessStatus::Starting,
-                last_activity: None,
-                last_sync_version: None,
-                consecutive_failures: 0,
-                started_for_current_topic: false,
-            };
-            self.producers.insert(producer_id, producer_state);
-        }
-
-        // Determine if bloom filter is needed based on strategy
+    /// Weight of the newest sample in the smoothed UAM and cost per minute (1 disables smoothing)
+    pub fn set_metrics_smoothing(&mut self, factor: f64) {
+        let tracker = std::mem::take(&mut self.performance);
+        self.performance = tracker.with_smoothing_factor(factor);
+    }
+
+    /// Keep every generated attribute instead of dropping duplicates
+    pub fn set_dedup_enabled(&mut self, enabled: bool) {
+        self.context.dedup_enabled = enabled;
         self.context.requires_bloom_filter = self.should_use_bloom_filter();
+    }
 
-        Ok(())
+    /// Run-level RNG seed propagated to producers
+    pub fn run_seed(&self) -> u64 {
+        self.run_seed
+    }
+
+    /// Record the auto-tuned limits from a producer status report
+    pub fn record_tuned_limits(&mut self, producer_id: ProcessId, limits: HashMap<ProviderId, TunedLimits>) {
+        if limits.is_empty() {
+            self.tuned_limits.remove(&producer_id);
+        } else {
+            self.tuned_limits.insert(producer_id, limits);
+        }
+    }
+
+    /// Feed the requests a producer completed since its last status report into performance tracking
+    pub fn record_provider_usage(&mut self, producer_id: ProcessId, usage: HashMap<ProviderId, ProviderUsageStats>) {
+        let previous = self.provider_usage.remove(&producer_id).unwrap_or_default();
+        for (provider, current) in &usage {
+            let outcomes = usage_since(previous.get(provider), current);
+            if outcomes.successes + outcomes.failures > 0 {
+                self.performance.record_request_outcomes(producer_id.clone(), *provider, outcomes);
+            }
+        }
+        self.provider_usage.insert(producer_id, usage);
+    }
+
+    /// Record the verification spot checks from a producer status report
+    pub fn record_verification(&mut self, producer_id: ProcessId, stats: VerificationStats) {
+        self.verification.insert(producer_id, stats);
+    }
+
+    /// Verification spot checks summed across producers
+    pub fn verification_totals(&self) -> VerificationStats {
+        let mut totals = VerificationStats::default();
+        for stats in self.verification.values() {
+            totals.add(stats);
+        }
+        totals
+    }
+
+    /// Auto-tuned limits per provider, taking the most conservative producer report
+    pub fn tuned_limits(&self) -> HashMap<ProviderId, TunedLimits> {
+        let mut merged: HashMap<ProviderId, TunedLimits> = HashMap::new();
+        for (provider, limits) in self.tuned_limits.values().flatten() {
+            merged
+                .entry(*provider)
+                .and_modify(|current| {
+                    current.max_concurrent = current.max_concurrent.min(limits.max_concurrent);
+                    current.max_rpm = current.max_rpm.min(limits.max_rpm);
+                })
+                .or_insert(*limits);
+        }
+        merged
+    }
+
+    /// Set the default routing strategy from orchestrator args/env
+    pub fn set_default_routing_strategy(&mut self, strategy: Option<shared::RoutingStrategy>) {
+        self.default_routing_strategy = strategy;
+    }
+
+    /// Get the default routing strategy (orchestrator fallback)
+    pub fn get_default_routing_strategy(&self) -> Option<&shared::RoutingStrategy> {
+        self.default_routing_strategy.as_ref()
     }
 
     /// Process a batch of attributes from a producer
@@ -200,6 +510,7 @@ impl OrchestratorState {
         producer_id: ProcessId,
         provider_metadata: shared::ProviderMetadata,
         attributes: Vec<String>,
+        estimated_cost: f64,
     ) -> OrchestratorResult<ProcessResult> {
         // 1. Update producer activity
         if let Some(producer) = self.producers.get_mut(&producer_id) {
@@ -209,17 +520,18 @@ impl OrchestratorState {
         }
 
         // 2. Check uniqueness
-        let unique_attributes = self.uniqueness.filter_unique(attributes.clone())?;
+        let unique_attributes = self.accept_attributes(&attributes)?;
         let unique_count = unique_attributes.len() as u64;
         let total_count = attributes.len() as u64;
 
         // 3. Track performance
         self.performance.record_contribution(
-            producer_id,
+            self.stable_producer_id(&producer_id),
             provider_metadata.provider_id,
             unique_count,
             total_count,
             provider_metadata.tokens.clone(),
+            estimated_cost,
         );
 
         // 4. Check if bloom filter needs distribution
@@ -236,9 +548,31 @@ impl OrchestratorState {
         })
     }
 
+    /// Attributes kept for output: the unique ones, or all of them when dedup is disabled
+    fn accept_attributes(&mut self, attributes: &[String]) -> OrchestratorResult<Vec<String>> {
+        self.raw_attribute_count += attributes.len() as u64;
+        if self.context.dedup_enabled {
+            let unique = self.uniqueness.filter_unique(attributes.to_vec())?;
+            if let Some(rate) = self.uniqueness.false_positive_pressure_crossed() {
+                process_warn!(
+                    ProcessId::current(),
+                    false_positive_rate = rate,
+                    "⚠️ Bloom filter saturating: estimated false-positive rate {:.2}% after {} unique values; producers may discard new attributes",
+                    rate * 100.0,
+                    self.uniqueness.total_unique_count()
+                );
+            }
+            Ok(unique)
+        } else {
+            self.uniqueness.record_without_dedup(attributes);
+            Ok(attributes.to_vec())
+        }
+    }
+
     /// Get current system metrics
     pub fn get_system_metrics(&self) -> SystemMetrics {
         let performance_stats = self.performance.get_current_stats();
+        let smoothed = performance_stats.smoothed.unwrap_or_default();
 
         SystemMetrics {
             uam: performance_stats.overall.uam,
@@ -246,6 +580,8 @@ impl OrchestratorState {
             tokens_per_minute: performance_stats.overall.tokens_per_minute,
             unique_per_dollar: performance_stats.overall.unique_per_dollar,
             unique_per_1k_tokens: performance_stats.overall.unique_per_1k_tokens,
+            uam_smoothed: smoothed.uam,
+            cost_per_minute_smoothed: smoothed.cost_per_minute,
             by_producer: performance_stats
                 .by_producer
                 .iter()
@@ -256,6 +592,8 @@ impl OrchestratorState {
                 .iter()
                 .map(|(id, metrics)| (*id, convert_to_provider_metrics(metrics.clone())))
                 .collect(),
+            bloom_filter: self.uniqueness.bloom_filter_stats(),
+            verification: self.verification_totals(),
             active_producers: self.producers.len() as u32,
             current_topic: self.context.topic.clone(),
             uptime_seconds: self.start_time.elapsed().as_secs(),
@@ -272,6 +610,20 @@ impl OrchestratorState {
         }
     }
 
+    /// Seen values producers need alongside the bloom filter
+    pub fn get_seen_values(&self) -> Option<Vec<String>> {
+        if self.context.requires_bloom_filter {
+            Some(self.uniqueness.get_seen_values())
+        } else {
+            None
+        }
+    }
+
+    /// Get current bloom filter version
+    pub fn get_bloom_version(&self) -> u64 {
+        self.uniqueness.get_bloom_version()
+    }
+
     /// Update producer sync status
     pub fn update_producer_sync(&mut self, producer_id: ProcessId, bloom_version: u64) {
         if let Some(producer) = self.producers.get_mut(&producer_id) {
@@ -287,6 +639,35 @@ impl OrchestratorState {
         }
     }
 
+    /// Get producers currently marked as failed
+    pub fn failed_producer_ids(&self) -> Vec<ProcessId> {
+        self.producers
+            .iter()
+            .filter(|(_, state)| state.status == shared::ProcessStatus::Failed)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Tracked producers that have not failed, in id order
+    pub fn active_producer_ids(&self) -> Vec<ProcessId> {
+        let mut ids: Vec<ProcessId> = self
+            .producers
+            .iter()
+            .filter(|(_, state)| state.status != shared::ProcessStatus::Failed)
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort_by_key(|id| match id {
+            ProcessId::Producer(n) => *n,
+            _ => u32::MAX,
+        });
+        ids
+    }
+
+    /// Check whether a producer is being tracked
+    pub fn has_producer(&self, producer_id: &ProcessId) -> bool {
+        self.producers.contains_key(producer_id)
+    }
+
     /// Get producers that need sync updates
     pub fn get_producers_needing_sync(&self) -> Vec<ProcessId> {
         if !self.context.requires_bloom_filter {
@@ -296,7 +677,7 @@ impl OrchestratorState {
         let current_version = self.uniqueness.get_bloom_version();
         self.producers
             .iter()
-            .filter(|(_, state)| state.last_sync_version.map_or(true, |v| v < current_version))
+            .filter(|(_, state)| state.last_sync_version.is_none_or(|v| v < current_version))
             .map(|(id, _)| id.clone())
             .collect()
     }
@@ -305,12 +686,23 @@ impl OrchestratorState {
     fn should_use_bloom_filter(&self) -> bool {
         // For now, always use bloom filter unless we have provider-specific partitioning
         // In the future, this could be more sophisticated based on the optimizer's decisions
-        true
+        self.context.dedup_enabled
     }
 
     // Accessors for testing and debugging
     pub fn total_unique_count(&self) -> u64 {
-        self.uniqueness.total_unique_count()
+        if self.context.dedup_enabled && self.is_concurrent() {
+            self.topics.values().map(TopicState::unique_count).sum()
+        } else if self.context.dedup_enabled {
+            self.uniqueness.total_unique_count()
+        } else {
+            self.raw_attribute_count
+        }
+    }
+
+    /// Every attribute received for the current topic, duplicates included
+    pub fn raw_attribute_count(&self) -> u64 {
+        self.raw_attribute_count
     }
 
     pub fn active_producer_count(&self) -> usize {
@@ -326,15 +718,47 @@ impl OrchestratorState {
         self.active_producer_count()
     }
 
+    /// Each producer's even share of the cost budget, passed along so producers can self-throttle
+    pub fn producer_cost_budget(&self) -> Option<f64> {
+        let budget = self.context.optimization_targets.max_cost_per_minute;
+        (budget.is_finite() && budget > 0.0).then(|| budget / self.active_producer_count().max(1) as f64)
+    }
+
     /// Get unique attribute count for public API
     pub fn get_unique_attribute_count(&self) -> usize {
         self.total_unique_count() as usize
     }
 
     /// Set CLI iterations limit
+    ///
+    /// The limit counts iterations completed before a resume, so a resumed run
+    /// only performs the remainder.
     pub fn set_cli_iterations(&mut self, iterations: Option<u32>) {
         self.cli_iterations = iterations;
-        self.current_iteration = 0;
+        self.current_iteration = self.resumed_iteration;
+    }
+
+    /// Snapshot iteration progress for the current topic
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        Some(Checkpoint {
+            topic: self.context.topic.clone()?,
+            iteration: self.current_iteration,
+            previous_unique_count: self.previous_unique_count,
+            cycle_history: self.cycle_history.clone(),
+        })
+    }
+
+    /// Continue iteration counting from a checkpoint of an earlier run
+    pub fn restore_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.current_iteration = checkpoint.iteration;
+        self.resumed_iteration = checkpoint.iteration;
+        self.previous_unique_count = checkpoint.previous_unique_count;
+        self.cycle_history = checkpoint.cycle_history;
+    }
+
+    /// Whether this run continues an earlier checkpointed run
+    pub fn is_resumed(&self) -> bool {
+        self.resumed_iteration > 0
     }
 
     /// Increment iteration count and check if limit reached
@@ -342,8 +766,8 @@ impl OrchestratorState {
         self.current_iteration += 1;
 
         // Calculate cycle statistics
-        let current_unique_count = self.uniqueness.total_unique_count();
-        let new_values = current_unique_count - self.previous_unique_count;
+        let current_unique_count = self.total_unique_count();
+        let new_values = current_unique_count.saturating_sub(self.previous_unique_count);
         let iteration_items = self.uniqueness.get_current_iteration_items();
         let attempted_this_cycle = iteration_items.len() as u64; // Items attempted this cycle
 
@@ -360,7 +784,15 @@ impl OrchestratorState {
             0.0 // First cycle has no delta
         };
 
-        let duplicate_values = attempted_this_cycle - new_values;
+        let duplicate_values = attempted_this_cycle.saturating_sub(new_values);
+
+        if let Some(floor) = self.min_efficiency_percent {
+            if efficiency < floor {
+                self.low_efficiency_cycles += 1;
+            } else {
+                self.low_efficiency_cycles = 0;
+            }
+        }
 
         // Create cycle stats
         let cycle_stats = CycleStats {
@@ -467,9 +899,40 @@ impl OrchestratorState {
             }
         }
 
+        if self.target_count_reached() {
+            process_info!(
+                ProcessId::current(),
+                "🎯 Reached {} of {} target unique attributes, stopping",
+                current_unique_count,
+                self.target_unique_count.unwrap_or_default()
+            );
+            return true;
+        }
+
+        if self.efficiency_floor_reached() {
+            process_info!(
+                ProcessId::current(),
+                "📉 Cycle efficiency below {:.2}% for {} consecutive cycles, stopping",
+                self.min_efficiency_percent.unwrap_or_default(),
+                self.low_efficiency_cycles
+            );
+            return true;
+        }
+
         false // Continue running
     }
 
+    /// Whether the unique attribute count has met `target_unique_count`
+    pub fn target_count_reached(&self) -> bool {
+        self.target_unique_count
+            .is_some_and(|target| self.total_unique_count() >= target)
+    }
+
+    /// Whether cycle efficiency has stayed below `min_efficiency_percent` for the full patience
+    pub fn efficiency_floor_reached(&self) -> bool {
+        self.min_efficiency_percent.is_some() && self.low_efficiency_cycles >= self.efficiency_patience
+    }
+
     /// Check if we should stop due to iteration limit
     pub fn should_stop_iterations(&self) -> bool {
         if let Some(limit) = self.cli_iterations {
@@ -505,12 +968,233 @@ impl OrchestratorState {
         self.context.optimization_targets.optimization_mode = optimization_mode;
         self.context.optimization_targets.max_cost_per_minute = constraints.max_cost_per_minute;
         self.context.optimization_targets.min_uam = constraints.target_uam;
+        self.max_runtime = constraints.max_runtime_seconds.map(Duration::from_secs);
+        self.min_efficiency_percent = constraints.min_efficiency_percent;
+        self.efficiency_patience = constraints
+            .efficiency_patience
+            .unwrap_or(shared::types::DEFAULT_EFFICIENCY_PATIENCE)
+            .max(1);
+        self.low_efficiency_cycles = 0;
+        self.target_unique_count = constraints.target_unique_count;
+        self.generation_started = Some(Instant::now());
+        self.completion_reason = None;
+        self.stop_after_iteration = false;
+    }
+
+    /// Whether the running generation has outlived `max_runtime_seconds`
+    pub fn runtime_limit_reached(&self) -> bool {
+        match (self.max_runtime, self.generation_started) {
+            (Some(limit), Some(started)) => {
+                self.context.topic.is_some() && self.completion_reason.is_none() && started.elapsed() >= limit
+            }
+            _ => false,
+        }
+    }
+
+    /// Clear per-topic progress so the next batch topic starts fresh
+    ///
+    /// Producers, queued start commands, routing defaults, the run seed and
+    /// tuned limits carry over.
+    pub fn reset_for_next_topic(&mut self) {
+        self.uniqueness = UniquenessTracker::new();
+        self.uniqueness.set_normalization_mode(self.context.normalization);
+        self.performance = PerformanceTracker::new()
+            .with_half_life(self.performance.half_life())
+            .with_smoothing_factor(self.performance.smoothing_factor());
+        self.context.topic = None;
+        self.start_time = Instant::now();
+        self.started_at = chrono::Utc::now();
+        self.current_iteration = 0;
+        self.resumed_iteration = 0;
+        self.previous_unique_count = 0;
+        self.raw_attribute_count = 0;
+        self.cycle_history.clear();
+        self.generation_started = None;
+        self.completion_reason = None;
+        self.stop_after_iteration = false;
+        self.low_efficiency_cycles = 0;
+        self.optimization_plan = None;
+        // Producers must receive the new topic's (empty) bloom filter
+        for producer in self.producers.values_mut() {
+            producer.last_sync_version = None;
+        }
+    }
+
+    /// Record why the current generation finished
+    pub fn set_completion_reason(&mut self, reason: CompletionReason) {
+        self.completion_reason = Some(reason);
+    }
+
+    /// Stop generation once the iteration in progress has been written out
+    pub fn request_stop_after_iteration(&mut self) {
+        self.stop_after_iteration = true;
+    }
+
+    /// Whether a manual stop is waiting for the iteration in progress
+    pub fn stop_after_iteration_requested(&self) -> bool {
+        self.stop_after_iteration
+    }
+
+    /// Why the current generation finished, if it has
+    pub fn completion_reason(&self) -> Option<&CompletionReason> {
+        self.completion_reason.as_ref()
+    }
+
+    /// Replace the uniqueness tracker with one persisted by an earlier run
+    ///
+    /// Returns the number of restored values, which are excluded from the next
+    /// cycle's new-value count. Corrupt or version-mismatched files are ignored
+    /// with a warning and leave the current tracker in place.
+    pub fn load_uniqueness(&mut self, path: &Path) -> usize {
+        match UniquenessTracker::load_from_disk(path) {
+            Ok(Some(tracker)) => {
+                self.uniqueness = tracker;
+                self.uniqueness.set_normalization_mode(self.context.normalization);
+                self.previous_unique_count = self.uniqueness.total_unique_count();
+                self.uniqueness.total_unique_count() as usize
+            }
+            Ok(None) => 0,
+            Err(e) => {
+                process_warn!(
+                    ProcessId::current(),
+                    "⚠️ Ignoring uniqueness file {}: {}",
+                    path.display(),
+                    e
+                );
+                0
+            }
+        }
+    }
+
+    /// Count known attributes as seen for the current topic
+    ///
+    /// Returns how many were new to the tracker. They are excluded from the next
+    /// cycle's new-value count, so seeding never shows up as generated output.
+    pub fn seed_known_attributes(&mut self, values: &[String]) -> usize {
+        let added = self.uniqueness.seed_known(values);
+        self.previous_unique_count += added as u64;
+        added
+    }
+
+    /// Persist the uniqueness tracker so a later run on the same topic can resume
+    pub fn save_uniqueness(&self, path: &Path) -> OrchestratorResult<()> {
+        self.uniqueness.save_to_disk(path)
+    }
+
+    /// Start generating several topics at once, each with its own producers
+    ///
+    /// There is no single current topic afterwards: batches are routed to their
+    /// topic with `add_topic_attributes`.
+    pub fn start_concurrent_generation(
+        &mut self,
+        topics: Vec<(String, Vec<ProcessId>)>,
+        optimization_mode: shared::OptimizationMode,
+        constraints: shared::GenerationConstraints,
+    ) {
+        self.start_generation(String::new(), optimization_mode, constraints);
+        self.context.topic = None;
+        self.topics = topics
+            .into_iter()
+            .map(|(topic, producers)| (topic, TopicState::new(self.context.normalization, producers)))
+            .collect();
+    }
+
+    /// Whether several topics are being generated at once
+    pub fn is_concurrent(&self) -> bool {
+        !self.topics.is_empty()
+    }
+
+    /// Names of the concurrently generated topics
+    pub fn concurrent_topics(&self) -> impl Iterator<Item = &str> {
+        self.topics.keys().map(String::as_str)
+    }
+
+    /// Progress of a concurrently generated topic
+    pub fn topic_state(&self, topic: &str) -> Option<&TopicState> {
+        self.topics.get(topic)
+    }
+
+    /// Keep the attributes of a batch that are new to its own topic
+    ///
+    /// Returns None when the topic is not running, either because it is unknown
+    /// or because it already completed.
+    pub fn add_topic_attributes(
+        &mut self,
+        topic: &str,
+        producer_id: ProcessId,
+        attributes: Vec<String>,
+        provider_metadata: &shared::ProviderMetadata,
+        estimated_cost: f64,
+    ) -> Option<Vec<String>> {
+        let dedup_enabled = self.context.dedup_enabled;
+        let topic_state = self.topics.get_mut(topic).filter(|topic_state| !topic_state.completed)?;
+        let unique_attributes = if dedup_enabled {
+            topic_state.uniqueness.filter_unique(attributes.clone()).unwrap_or_default()
+        } else {
+            topic_state.uniqueness.record_without_dedup(&attributes);
+            attributes.clone()
+        };
+        self.raw_attribute_count += attributes.len() as u64;
+
+        self.performance.record_contribution(
+            self.stable_producer_id(&producer_id),
+            provider_metadata.provider_id,
+            unique_attributes.len() as u64,
+            attributes.len() as u64,
+            provider_metadata.tokens.clone(),
+            estimated_cost,
+        );
+
+        Some(unique_attributes)
+    }
+
+    /// Close the current iteration of a concurrent topic
+    ///
+    /// Returns the iteration's attributes for output.txt and whether the topic just
+    /// reached the iteration limit, which marks it completed.
+    pub fn finish_topic_iteration(&mut self, topic: &str) -> Option<(Vec<String>, bool)> {
+        let limit = self.cli_iterations;
+        let topic_state = self.topics.get_mut(topic)?;
+        let iteration_items = topic_state.uniqueness.get_current_iteration_items().to_vec();
+        topic_state.uniqueness.start_next_iteration();
+        topic_state.current_iteration += 1;
+
+        let limit_reached = limit.is_some_and(|limit| topic_state.current_iteration >= limit);
+        topic_state.completed |= limit_reached;
+        Some((iteration_items, limit_reached))
+    }
+
+    /// Topic a producer generates for: the current topic, or the running concurrent topic it was assigned
+    pub fn producer_topic(&self, producer_id: &ProcessId) -> Option<String> {
+        if let Some(topic) = &self.context.topic {
+            return Some(topic.clone());
+        }
+        self.topics
+            .iter()
+            .find(|(_, topic_state)| !topic_state.completed && topic_state.producers.contains(producer_id))
+            .map(|(topic, _)| topic.clone())
+    }
+
+    /// Whether every concurrent topic has completed
+    pub fn all_topics_completed(&self) -> bool {
+        self.is_concurrent() && self.topics.values().all(TopicState::is_completed)
+    }
+
+    /// Persist a concurrent topic's uniqueness tracker
+    pub fn save_topic_uniqueness(&self, topic: &str, path: &Path) -> OrchestratorResult<()> {
+        match self.topics.get(topic) {
+            Some(topic_state) => topic_state.uniqueness.save_to_disk(path),
+            None => Ok(()),
+        }
     }
 
     /// Stop generation
     pub fn stop_generation(&mut self) {
         self.context.topic = None;
+        self.topics.clear();
+        self.stop_after_iteration = false;
         self.producers.clear();
+        self.producer_aliases.clear();
     }
 
     /// Add a producer to tracking
@@ -522,32 +1206,78 @@ impl OrchestratorState {
             last_sync_version: None,
             consecutive_failures: 0,
             started_for_current_topic: false,
+            config: None,
+            category: None,
         };
+        // A newly added producer reports under its own id until it replaces another
+        self.producer_aliases.remove(&producer_id);
         self.producers.insert(producer_id, producer_state);
     }
 
+    /// Swap a failed producer for its replacement, keeping the failed producer's stable id
+    ///
+    /// When the replacement was spawned under a different id, metrics and dashboard
+    /// events for it are reported under the id of the producer it replaced, so the
+    /// pool keeps the same set of ids across restarts.
+    pub fn replace_producer(
+        &mut self,
+        failed_producer_id: &ProcessId,
+        replacement_id: ProcessId,
+        process_id: u32,
+        status: shared::ProcessStatus,
+    ) {
+        let stable_id = self.stable_producer_id(failed_producer_id);
+        self.remove_producer(failed_producer_id);
+        self.add_producer(replacement_id.clone(), process_id, status);
+        for topic_state in self.topics.values_mut() {
+            for producer in topic_state.producers.iter_mut().filter(|producer| *producer == failed_producer_id) {
+                *producer = replacement_id.clone();
+            }
+        }
+        if replacement_id != stable_id {
+            self.producer_aliases.insert(replacement_id, stable_id);
+        }
+    }
+
+    /// Id a producer is reported under: the original id for replacements, otherwise its own
+    pub fn stable_producer_id(&self, producer_id: &ProcessId) -> ProcessId {
+        self.producer_aliases
+            .get(producer_id)
+            .unwrap_or(producer_id)
+            .clone()
+    }
+
+    /// Stable ids of all tracked producers, in id order
+    pub fn stable_producer_ids(&self) -> Vec<ProcessId> {
+        let mut ids: Vec<ProcessId> = self.producers.keys().map(|id| self.stable_producer_id(id)).collect();
+        ids.sort_by_key(|id| match id {
+            ProcessId::Producer(n) => *n,
+            _ => u32::MAX,
+        });
+        ids
+    }
+
     /// Add attributes from producer (compatibility method)
     pub fn add_attributes(
         &mut self,
         producer_id: shared::ProcessId,
         attributes: Vec<String>,
         provider_metadata: &shared::ProviderMetadata,
+        estimated_cost: f64,
     ) -> Vec<String> {
-        let unique_attributes = match self.uniqueness.filter_unique(attributes.clone()) {
-            Ok(attrs) => attrs,
-            Err(_) => Vec::new(),
-        };
+        let unique_attributes = self.accept_attributes(&attributes).unwrap_or_default();
 
         // Record performance
         let unique_count = unique_attributes.len() as u64;
         let total_count = attributes.len() as u64;
 
         self.performance.record_contribution(
-            producer_id,
+            self.stable_producer_id(&producer_id),
             provider_metadata.provider_id,
             unique_count,
             total_count,
             provider_metadata.tokens.clone(),
+            estimated_cost,
         );
 
         unique_attributes
@@ -567,6 +1297,78 @@ impl OrchestratorState {
         }
     }
 
+    /// Remember the configuration carried by a command the producer accepted
+    pub fn record_delivered_command(&mut self, producer_id: &ProcessId, command: &OrchestratorCommand) {
+        let Some(producer) = self.producers.get_mut(producer_id) else {
+            return;
+        };
+        match command {
+            OrchestratorCommand::Start {
+                prompt,
+                routing_strategy,
+                generation_config,
+                category,
+                ..
+            } => {
+                producer.config = Some(ProducerConfig {
+                    prompt: prompt.clone(),
+                    routing_strategy: routing_strategy.clone(),
+                    generation_config: generation_config.clone(),
+                });
+                producer.category = category.clone();
+            }
+            OrchestratorCommand::UpdateConfig {
+                prompt,
+                routing_strategy,
+                generation_config,
+                ..
+            } => {
+                if let Some(config) = &mut producer.config {
+                    if let Some(prompt) = prompt {
+                        config.prompt = prompt.clone();
+                    }
+                    if let Some(routing_strategy) = routing_strategy {
+                        config.routing_strategy = routing_strategy.clone();
+                    }
+                    if let Some(generation_config) = generation_config {
+                        config.generation_config = generation_config.clone();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the optimizer reconfigured producers less than `cooldown` ago
+    pub fn reconfigured_within(&self, cooldown: Duration) -> bool {
+        self.last_reconfigured.is_some_and(|at| at.elapsed() < cooldown)
+    }
+
+    /// Record that the optimizer just reconfigured producers
+    pub fn mark_reconfigured(&mut self) {
+        self.last_reconfigured = Some(Instant::now());
+    }
+
+    /// Record the plan producers are now configured from
+    pub fn set_optimization_plan(&mut self, plan: OptimizationPlan) {
+        self.optimization_plan = Some(plan);
+    }
+
+    /// Plan behind the current producer configuration, if the optimizer has planned yet
+    pub fn optimization_plan(&self) -> Option<&OptimizationPlan> {
+        self.optimization_plan.as_ref()
+    }
+
+    /// Configuration last delivered to a producer, if known
+    pub fn producer_config(&self, producer_id: &ProcessId) -> Option<&ProducerConfig> {
+        self.producers.get(producer_id).and_then(|producer| producer.config.as_ref())
+    }
+
+    /// Prompt partition a producer was started on, if prompts are split per provider
+    pub fn producer_category(&self, producer_id: &ProcessId) -> Option<&str> {
+        self.producers.get(producer_id).and_then(|producer| producer.category.as_deref())
+    }
+
     /// Check if producer has been started for current topic
     pub fn is_producer_started(&self, producer_id: &ProcessId) -> bool {
         self.producers.get(producer_id)
@@ -577,6 +1379,7 @@ impl OrchestratorState {
     /// Remove a failed producer from tracking
     pub fn remove_producer(&mut self, producer_id: &ProcessId) {
         self.producers.remove(producer_id);
+        self.producer_aliases.remove(producer_id);
     }
 
 
@@ -643,6 +1446,7 @@ impl OrchestratorState {
     pub fn generate_provider_performance_stats(&self) -> Vec<ProviderPerformanceStats> {
         let performance_stats = self.performance.get_current_stats();
         let total_duration_minutes = self.start_time.elapsed().as_secs_f64() / 60.0;
+        let tuned_limits = self.tuned_limits();
 
         performance_stats
             .by_provider
@@ -657,13 +1461,55 @@ impl OrchestratorState {
                     total_requests: (metrics.request_rate * total_duration_minutes) as u64,
                     total_unique_attributes: (metrics.uam * total_duration_minutes) as u64,
                     total_cost: metrics.cost_per_minute * total_duration_minutes,
-                    average_response_time_ms: 0.0, // TODO: Add response time tracking
-                    success_rate: 1.0,             // TODO: Add success rate tracking
+                    average_response_time_ms: metrics.avg_response_time_ms,
+                    success_rate: metrics.success_rate,
+                    tuned_limits: tuned_limits.get(provider_id).copied(),
                 }
             })
             .collect()
     }
 
+    /// Write the current checkpoint to `<topic>/checkpoint.json`
+    pub async fn save_checkpoint(&self, file_system: &dyn crate::traits::FileSystem) -> OrchestratorResult<()> {
+        if let Some(checkpoint) = self.checkpoint() {
+            let json_content = serde_json::to_string_pretty(&checkpoint)?;
+            file_system
+                .write_file(&format!("{}/{}", checkpoint.topic, CHECKPOINT_FILE), json_content.as_bytes())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Summarize the current topic's run
+    pub fn run_summary(&self, completion_reason: CompletionReason) -> Option<RunSummary> {
+        Some(RunSummary {
+            topic: self.context.topic.clone()?,
+            total_iterations: self.current_iteration,
+            final_unique_count: self.total_unique_count(),
+            completion_reason,
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            runtime_seconds: self.start_time.elapsed().as_secs_f64(),
+            cycle_performance: self.generate_cycle_performance_summary(),
+        })
+    }
+
+    /// Write the run summary to `<topic>/summary.json`
+    pub async fn export_run_summary(
+        &self,
+        file_system: &dyn crate::traits::FileSystem,
+        completion_reason: CompletionReason,
+    ) -> OrchestratorResult<()> {
+        if let Some(summary) = self.run_summary(completion_reason) {
+            let json_content = serde_json::to_string_pretty(&summary)?;
+            file_system
+                .write_file(&format!("{}/{}", summary.topic, SUMMARY_FILE), json_content.as_bytes())
+                .await?;
+            process_debug!(ProcessId::current(), "📊 Exported run summary to {}/{}", summary.topic, SUMMARY_FILE);
+        }
+        Ok(())
+    }
+
     /// Export cycle performance to JSON file
     pub async fn export_cycle_performance(
         &self,
@@ -683,6 +1529,17 @@ impl OrchestratorState {
         Ok(())
     }
 
+    /// Build a prompt record stamped with the current iteration
+    pub fn prompt_record(&self, source: &str, provider: Option<ProviderId>, prompt: &str) -> PromptRecord {
+        PromptRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            iteration: self.current_iteration,
+            provider,
+            prompt: prompt.to_string(),
+            source: source.to_string(),
+        }
+    }
+
     /// Export provider performance to JSON file
     pub async fn export_provider_performance(
         &self,
@@ -722,6 +1579,8 @@ impl Default for GenerationContext {
                 ],
             },
             requires_bloom_filter: true,
+            normalization: NormalizationMode::default(),
+            dedup_enabled: true,
             optimization_targets: OptimizationTargets {
                 min_uam: 5.0,
                 max_cost_per_minute: 1.0,
@@ -745,6 +1604,25 @@ fn convert_to_producer_metrics(metrics: crate::core::performance::PerformanceMet
     }
 }
 
+/// Requests completed between two cumulative usage reports from the same producer
+fn usage_since(previous: Option<&ProviderUsageStats>, current: &ProviderUsageStats) -> RequestOutcomes {
+    let totals = |usage: &ProviderUsageStats| {
+        let completed = usage.successful_responses + usage.failed_responses;
+        (usage.successful_responses, usage.failed_responses, usage.avg_response_time_ms * completed as f64)
+    };
+    let (successes, failures, total_ms) = totals(current);
+    // Counters that went backwards mean the producer restarted and began counting again
+    let (prev_successes, prev_failures, prev_total_ms) = match previous.map(totals) {
+        Some(prev) if prev.0 <= successes && prev.1 <= failures => prev,
+        _ => (0, 0, 0.0),
+    };
+    RequestOutcomes {
+        successes: successes - prev_successes,
+        failures: failures - prev_failures,
+        total_response_time_ms: (total_ms - prev_total_ms).max(0.0),
+    }
+}
+
 /// Convert internal provider metrics to shared type
 fn convert_to_provider_metrics(metrics: crate::core::performance::PerformanceMetrics) -> shared::ProviderMetrics {
     shared::ProviderMetrics {
@@ -753,8 +1631,8 @@ fn convert_to_provider_metrics(metrics: crate::core::performance::PerformanceMet
         cost_per_minute: metrics.cost_per_minute,
         unique_per_dollar: metrics.unique_per_dollar,
         unique_per_1k_tokens: metrics.unique_per_1k_tokens,
-        avg_response_time_ms: 0.0,                 // TODO: Calculate from metadata
-        success_rate: 1.0,                         // TODO: Track from requests
+        avg_response_time_ms: metrics.avg_response_time_ms,
+        success_rate: metrics.success_rate,
         status: shared::ProviderStatus::Available, // TODO: Determine from recent activity
     }
 }
@@ -3,25 +3,147 @@
 //! This module handles deduplication of attributes using both
 //! exact matching (HashSet) and probabilistic filtering (Bloom filter).
 
-use crate::error::OrchestratorResult;
-use growable_bloom_filter::GrowableBloom;
+use crate::error::{OrchestratorError, OrchestratorResult};
+use growable_bloom_filter::{GrowableBloom, GrowableBloomBuilder};
+use serde::{Deserialize, Serialize};
 use serde_json;
+use shared::messages::metrics::BloomFilterStats;
 use std::collections::HashSet;
+use std::path::Path;
+
+/// Magic bytes identifying a persisted uniqueness file
+const UNIQUENESS_FILE_MAGIC: &[u8; 4] = b"IGUQ";
+
+/// Version of the persisted uniqueness format (bump on layout changes)
+pub const UNIQUENESS_FILE_VERSION: u32 = 2;
+
+/// False-positive rate the first bloom filter layer is sized for
+pub const BLOOM_TARGET_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Expected items the first bloom filter layer is sized for
+const DEFAULT_BLOOM_CAPACITY: usize = 100_000;
+
+/// Each new bloom filter layer holds this many times the items of the previous one
+const BLOOM_GROWTH_FACTOR: usize = 2;
+
+/// Each new bloom filter layer targets this fraction of the previous layer's error rate
+///
+/// Layer error rates form a geometric series, so the combined false-positive
+/// rate stays below `BLOOM_TARGET_FALSE_POSITIVE_RATE / (1 - BLOOM_TIGHTENING_RATIO)`
+/// however many layers are added.
+const BLOOM_TIGHTENING_RATIO: f64 = 0.5;
+
+/// Estimated false-positive rate above which the orchestrator warns about filter saturation
+pub const BLOOM_FALSE_POSITIVE_WARN_THRESHOLD: f64 = 0.01;
+
+/// Payload written after the magic/version header
+///
+/// Values are stored under the key of the mode they were seen with; a loaded
+/// tracker starts in `Exact` and is re-keyed by `set_normalization_mode`. The
+/// bloom filter is not stored: it is rebuilt from these values on load.
+#[derive(Serialize, Deserialize)]
+struct PersistedUniqueness {
+    /// Exact set of seen values
+    unique_items: Vec<String>,
+}
+
+/// How items are normalized before they are compared for uniqueness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalizationMode {
+    /// Compare items exactly as produced
+    #[default]
+    Exact,
+    /// Ignore letter case (Unicode-aware)
+    CaseInsensitive,
+    /// Trim surrounding whitespace and collapse internal runs to a single space
+    Trimmed,
+    /// Both case-insensitive and trimmed
+    CaseInsensitiveTrimmed,
+}
+
+impl NormalizationMode {
+    /// Apply the normalization to an item, producing its deduplication key
+    pub fn normalize(&self, item: &str) -> String {
+        match self {
+            NormalizationMode::Exact => item.to_string(),
+            NormalizationMode::CaseInsensitive => item.to_lowercase(),
+            NormalizationMode::Trimmed => Self::collapse_whitespace(item),
+            NormalizationMode::CaseInsensitiveTrimmed => Self::collapse_whitespace(&item.to_lowercase()),
+        }
+    }
+
+    fn collapse_whitespace(item: &str) -> String {
+        item.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl std::fmt::Display for NormalizationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NormalizationMode::Exact => write!(f, "exact"),
+            NormalizationMode::CaseInsensitive => write!(f, "case-insensitive"),
+            NormalizationMode::Trimmed => write!(f, "trimmed"),
+            NormalizationMode::CaseInsensitiveTrimmed => write!(f, "case-insensitive-trimmed"),
+        }
+    }
+}
+
+impl std::str::FromStr for NormalizationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exact" => Ok(NormalizationMode::Exact),
+            "case-insensitive" => Ok(NormalizationMode::CaseInsensitive),
+            "trimmed" => Ok(NormalizationMode::Trimmed),
+            "case-insensitive-trimmed" => Ok(NormalizationMode::CaseInsensitiveTrimmed),
+            _ => Err(format!(
+                "Unknown normalization mode: {s} (expected exact, case-insensitive, trimmed or case-insensitive-trimmed)"
+            )),
+        }
+    }
+}
+
+/// Read newline-delimited known attributes, trimming entries and skipping blank lines
+pub fn read_seed_file(path: &Path) -> OrchestratorResult<Vec<String>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        OrchestratorError::config(format!("Failed to read seed file {}: {}", path.display(), e))
+    })?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
 
 /// Manages uniqueness checking and bloom filter distribution
 pub struct UniquenessTracker {
-    /// Exact set of unique items (source of truth)
+    /// Exact set of normalized unique items (source of truth)
     unique_items: HashSet<String>,
 
+    /// Normalization applied before items are compared
+    normalization: NormalizationMode,
+
     /// Unique items found in current iteration
     current_iteration_items: Vec<String>,
 
-    /// Bloom filter for fast probabilistic checking
+    /// Scalable bloom filter for fast probabilistic checking
+    ///
+    /// Once the newest layer holds as many items as it was sized for, the
+    /// filter adds a larger layer with a tighter error rate; lookups check
+    /// every layer. The exact `unique_items` set stays the source of truth.
     bloom_filter: GrowableBloom,
 
     /// Current bloom filter version (increments on updates)
     bloom_version: u64,
 
+    /// Number of items the first bloom filter layer is sized for
+    bloom_capacity: usize,
+
+    /// Whether the estimated false-positive rate was above the warning threshold at the last check
+    false_positive_warned: bool,
+
     /// Statistics for optimization
     stats: UniquenessStats,
 }
@@ -36,43 +158,78 @@ pub struct UniquenessStats {
     pub false_positive_rate: f64,
 }
 
+impl Default for UniquenessTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UniquenessTracker {
     /// Create new uniqueness tracker
     pub fn new() -> Self {
         Self {
             unique_items: HashSet::new(),
+            normalization: NormalizationMode::default(),
             current_iteration_items: Vec::new(),
-            bloom_filter: Self::create_bloom_filter(100_000), // 100K expected items
+            bloom_filter: Self::create_bloom_filter(DEFAULT_BLOOM_CAPACITY),
             bloom_version: 0,
+            bloom_capacity: DEFAULT_BLOOM_CAPACITY,
+            false_positive_warned: false,
             stats: UniquenessStats::default(),
         }
     }
 
+    /// Size the first bloom filter layer for this many items
+    pub fn with_bloom_capacity(mut self, capacity: usize) -> Self {
+        self.bloom_capacity = capacity.max(1);
+        self.rebuild_bloom_filter();
+        self
+    }
+
     /// Reset state for new topic
     pub fn reset(&mut self) {
         self.unique_items.clear();
         self.current_iteration_items.clear();
-        self.bloom_filter = Self::create_bloom_filter(100_000);
+        self.bloom_filter = Self::create_bloom_filter(self.bloom_capacity);
         self.bloom_version = 0;
+        self.false_positive_warned = false;
         self.stats = UniquenessStats::default();
     }
 
+    /// Current normalization mode
+    pub fn normalization_mode(&self) -> NormalizationMode {
+        self.normalization
+    }
+
+    /// Change the normalization mode, re-keying any items already seen
+    pub fn set_normalization_mode(&mut self, mode: NormalizationMode) {
+        if mode == self.normalization {
+            return;
+        }
+        self.normalization = mode;
+        self.unique_items = self.unique_items.iter().map(|item| mode.normalize(item)).collect();
+        self.rebuild_bloom_filter();
+    }
+
     /// Filter out non-unique items from a batch
+    ///
+    /// Items are compared by their normalized key, but the first-seen spelling is returned.
     pub fn filter_unique(&mut self, items: Vec<String>) -> OrchestratorResult<Vec<String>> {
         let mut unique_items = Vec::new();
         let mut bloom_updated = false;
 
         for item in items {
             self.stats.total_items_processed += 1;
+            let key = self.normalization.normalize(&item);
 
             // First check bloom filter for quick rejection
-            if self.bloom_filter.contains(&item) {
+            if self.bloom_filter.contains(&key) {
                 self.stats.bloom_filter_hits += 1;
 
                 // Bloom filter says it might exist, check exact set
-                if !self.unique_items.contains(&item) {
+                if !self.unique_items.contains(&key) {
                     // False positive - item is actually unique
-                    self.add_unique_item(item.clone())?;
+                    self.add_unique_item(key, item.clone())?;
                     unique_items.push(item);
                     bloom_updated = true;
                 }
@@ -80,7 +237,7 @@ impl UniquenessTracker {
             } else {
                 // Bloom filter says it's definitely unique
                 self.stats.bloom_filter_misses += 1;
-                self.add_unique_item(item.clone())?;
+                self.add_unique_item(key, item.clone())?;
                 unique_items.push(item);
                 bloom_updated = true;
             }
@@ -95,23 +252,95 @@ impl UniquenessTracker {
         Ok(unique_items)
     }
 
-    /// Add a unique item to both storage and bloom filter
-    fn add_unique_item(&mut self, item: String) -> OrchestratorResult<()> {
-        if self.unique_items.insert(item.clone()) {
-            self.bloom_filter.insert(&item);
-            self.stats.unique_items_found += 1;
+    /// Count known items as already seen without reporting them as new
+    ///
+    /// Items are keyed with the current normalization. Returns how many were not
+    /// seen before; they are synced to producers but never appear in iteration output.
+    pub fn seed_known(&mut self, items: &[String]) -> usize {
+        let mut added = 0;
+        for item in items {
+            let key = self.normalization.normalize(item);
+            if self.unique_items.insert(key.clone()) {
+                self.bloom_filter.insert(&key);
+                added += 1;
+            }
+        }
+        if added > 0 {
+            self.bloom_version += 1;
+            self.update_false_positive_rate();
+        }
+        added
+    }
 
-            // Track for current iteration
-            self.current_iteration_items.push(item.clone());
+    /// Track items for the current iteration without checking or remembering them
+    pub fn record_without_dedup(&mut self, items: &[String]) {
+        self.stats.total_items_processed += items.len() as u64;
+        self.current_iteration_items.extend_from_slice(items);
+    }
 
-            // Check if we need to rebuild bloom filter for efficiency
-            if self.should_rebuild_bloom_filter() {
-                self.rebuild_bloom_filter()?;
-            }
+    /// Add a unique item's key to both storage and bloom filter
+    fn add_unique_item(&mut self, key: String, item: String) -> OrchestratorResult<()> {
+        if self.unique_items.insert(key.clone()) {
+            self.bloom_filter.insert(&key);
+            self.stats.unique_items_found += 1;
+
+            // Track for current iteration (original spelling)
+            self.current_iteration_items.push(item);
         }
         Ok(())
     }
 
+    /// Estimated chance that a never-seen value tests positive in any bloom filter layer
+    ///
+    /// Layers fill in order, each up to its sized capacity. A layer sized for
+    /// `c` items at error rate `p` has `k = ceil(log2(1/p))` slices of
+    /// `s = c / ln 2` bits, and holding `n` items it matches a new value with
+    /// probability `(1 - e^(-n/s))^k`; the layers' rates combine as independent
+    /// chances of a match.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let mut remaining = self.bloom_filter.len() as f64;
+        let mut capacity = self.bloom_capacity as f64;
+        let mut error_rate = BLOOM_TARGET_FALSE_POSITIVE_RATE;
+        let mut no_match = 1.0;
+
+        while remaining > 0.0 {
+            let items = remaining.min(capacity);
+            let slices = (1.0 / error_rate).log2().ceil();
+            let slice_bits = (capacity / std::f64::consts::LN_2).ceil();
+            no_match *= 1.0 - (1.0 - (-items / slice_bits).exp()).powf(slices);
+
+            remaining -= items;
+            capacity *= BLOOM_GROWTH_FACTOR as f64;
+            error_rate *= BLOOM_TIGHTENING_RATIO;
+        }
+
+        1.0 - no_match
+    }
+
+    /// Report the estimated false-positive rate the first time it rises above the warning threshold
+    ///
+    /// Returns None while the rate stays on the same side of the threshold, so
+    /// callers warn once per crossing rather than on every batch.
+    pub fn false_positive_pressure_crossed(&mut self) -> Option<f64> {
+        let rate = self.estimated_false_positive_rate();
+        let above = rate > BLOOM_FALSE_POSITIVE_WARN_THRESHOLD;
+        let crossed = above && !self.false_positive_warned;
+        self.false_positive_warned = above;
+        crossed.then_some(rate)
+    }
+
+    /// Deduplication statistics, including the estimated bloom filter false-positive rate
+    pub fn bloom_filter_stats(&self) -> BloomFilterStats {
+        let total_items = self.stats.total_items_processed as usize;
+        let unique_items = self.stats.unique_items_found as usize;
+        let duplicate_items = total_items.saturating_sub(unique_items);
+        BloomFilterStats {
+            false_positive_rate: self.stats.false_positive_rate,
+            estimated_false_positive_rate: self.estimated_false_positive_rate(),
+            ..BloomFilterStats::new(total_items, unique_items, duplicate_items, duplicate_items)
+        }
+    }
+
     /// Get serialized bloom filter data for distribution
     pub fn get_bloom_filter_data(&self) -> Option<Vec<u8>> {
         // Serialize the actual bloom filter using serde_json
@@ -124,6 +353,13 @@ impl UniquenessTracker {
         }
     }
 
+    /// Normalized values seen so far, sorted for a stable sync payload
+    pub fn get_seen_values(&self) -> Vec<String> {
+        let mut values: Vec<String> = self.unique_items.iter().cloned().collect();
+        values.sort();
+        values
+    }
+
     /// Get current bloom filter version
     pub fn get_bloom_version(&self) -> u64 {
         self.bloom_version
@@ -132,7 +368,7 @@ impl UniquenessTracker {
     /// Check if bloom filter should be distributed (e.g., after significant updates)
     pub fn should_distribute_bloom_filter(&self) -> bool {
         // Distribute every 100 new unique items or every version increment
-        self.stats.unique_items_found % 100 == 0 && self.stats.unique_items_found > 0
+        self.stats.unique_items_found.is_multiple_of(100) && self.stats.unique_items_found > 0
     }
 
     /// Get total count of unique items
@@ -155,42 +391,82 @@ impl UniquenessTracker {
         self.current_iteration_items.clear();
     }
 
-    /// Create a new bloom filter with given capacity
-    fn create_bloom_filter(expected_items: usize) -> GrowableBloom {
-        // 1% false positive rate, with capacity for expected items
-        GrowableBloom::new(0.01, expected_items)
+    /// Persist the exact seen-value set to disk
+    pub fn save_to_disk(&self, path: &Path) -> OrchestratorResult<()> {
+        let payload = PersistedUniqueness {
+            unique_items: self.unique_items.iter().cloned().collect(),
+        };
+
+        let mut data = Vec::with_capacity(8);
+        data.extend_from_slice(UNIQUENESS_FILE_MAGIC);
+        data.extend_from_slice(&UNIQUENESS_FILE_VERSION.to_le_bytes());
+        data.extend(bincode::serialize(&payload)?);
+
+        // Write to a temporary file first so a crash never leaves a truncated file behind
+        let tmp_path = path.with_extension("bin.tmp");
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
     }
 
-    /// Check if bloom filter should be rebuilt for efficiency
-    fn should_rebuild_bloom_filter(&self) -> bool {
-        let current_size = self.unique_items.len();
+    /// Load a tracker previously written by `save_to_disk`
+    ///
+    /// Returns `Ok(None)` when no file exists, and an error for corrupt or
+    /// version-mismatched files.
+    pub fn load_from_disk(path: &Path) -> OrchestratorResult<Option<Self>> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        if data.len() < 8 || &data[..4] != UNIQUENESS_FILE_MAGIC {
+            return Err(OrchestratorError::uniqueness("Not a uniqueness file (missing header)"));
+        }
+        let version = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        if version != UNIQUENESS_FILE_VERSION {
+            return Err(OrchestratorError::uniqueness(format!(
+                "Unsupported uniqueness file version {version} (expected {UNIQUENESS_FILE_VERSION})"
+            )));
+        }
 
-        // Rebuild if:
-        // 1. We have more than 2x the items we planned for
-        // 2. False positive rate is too high (>5%)
-        current_size > 200_000 || self.stats.false_positive_rate > 0.05
+        let payload: PersistedUniqueness = bincode::deserialize(&data[8..])?;
+        let unique_items: HashSet<String> = payload.unique_items.into_iter().collect();
+
+        let mut tracker = Self {
+            stats: UniquenessStats {
+                unique_items_found: unique_items.len() as u64,
+                ..UniquenessStats::default()
+            },
+            unique_items,
+            ..Self::new()
+        };
+        tracker.rebuild_bloom_filter();
+        tracker.bloom_version = 0;
+        Ok(Some(tracker))
     }
 
-    /// Rebuild bloom filter with optimal size
-    fn rebuild_bloom_filter(&mut self) -> OrchestratorResult<()> {
-        let current_size = self.unique_items.len();
-        let new_capacity = std::cmp::max(current_size * 2, 100_000);
-
-        // Create new bloom filter with better capacity
-        self.bloom_filter = Self::create_bloom_filter(new_capacity);
+    /// Create a new scalable bloom filter whose first layer holds `expected_items`
+    fn create_bloom_filter(expected_items: usize) -> GrowableBloom {
+        GrowableBloomBuilder::new()
+            .estimated_insertions(expected_items)
+            .desired_error_ratio(BLOOM_TARGET_FALSE_POSITIVE_RATE)
+            .growth_factor(BLOOM_GROWTH_FACTOR)
+            .tightening_ratio(BLOOM_TIGHTENING_RATIO)
+            .build()
+    }
 
-        // Re-insert all unique items
+    /// Rebuild the bloom filter from the exact seen-value set
+    fn rebuild_bloom_filter(&mut self) {
+        self.bloom_filter = Self::create_bloom_filter(self.bloom_capacity);
         for item in &self.unique_items {
             self.bloom_filter.insert(item);
         }
 
-        // Update version and reset stats
         self.bloom_version += 1;
         self.stats.bloom_filter_hits = 0;
         self.stats.bloom_filter_misses = 0;
         self.stats.false_positive_rate = 0.0;
-
-        Ok(())
     }
 
     /// Update false positive rate calculation
@@ -268,4 +544,218 @@ mod tests {
         assert_eq!(tracker.total_unique_count(), 0);
         assert_eq!(tracker.get_bloom_version(), 0);
     }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("uniqueness.bin");
+
+        let mut tracker = UniquenessTracker::new();
+        tracker
+            .filter_unique(vec!["lion".to_string(), "tiger".to_string()])
+            .unwrap();
+        tracker.save_to_disk(&path).unwrap();
+
+        let mut restored = UniquenessTracker::load_from_disk(&path).unwrap().unwrap();
+        assert_eq!(restored.total_unique_count(), 2);
+        assert!(restored.get_current_iteration_items().is_empty());
+
+        // Previously seen values are still duplicates after the reload
+        let unique = restored
+            .filter_unique(vec!["lion".to_string(), "bear".to_string()])
+            .unwrap();
+        assert_eq!(unique, vec!["bear".to_string()]);
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = UniquenessTracker::load_from_disk(&dir.path().join("missing.bin")).unwrap();
+        assert!(loaded.is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_corrupt_and_mismatched_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let corrupt = dir.path().join("corrupt.bin");
+        std::fs::write(&corrupt, b"not a uniqueness file").unwrap();
+        assert!(UniquenessTracker::load_from_disk(&corrupt).is_err());
+
+        let truncated = dir.path().join("truncated.bin");
+        let mut data = UNIQUENESS_FILE_MAGIC.to_vec();
+        data.extend_from_slice(&UNIQUENESS_FILE_VERSION.to_le_bytes());
+        data.extend_from_slice(&[0xff, 0xff]);
+        std::fs::write(&truncated, data).unwrap();
+        assert!(UniquenessTracker::load_from_disk(&truncated).is_err());
+
+        let future = dir.path().join("future.bin");
+        let mut data = UNIQUENESS_FILE_MAGIC.to_vec();
+        data.extend_from_slice(&(UNIQUENESS_FILE_VERSION + 1).to_le_bytes());
+        std::fs::write(&future, data).unwrap();
+        assert!(UniquenessTracker::load_from_disk(&future).is_err());
+    }
+
+    #[test]
+    fn test_case_insensitive_trimmed_keeps_first_spelling() {
+        let mut tracker = UniquenessTracker::new();
+        tracker.set_normalization_mode(NormalizationMode::CaseInsensitiveTrimmed);
+
+        let unique = tracker
+            .filter_unique(vec!["Red Fox".to_string(), "red fox".to_string(), "  red   FOX ".to_string()])
+            .unwrap();
+        assert_eq!(unique, vec!["Red Fox".to_string()]);
+        assert_eq!(tracker.get_current_iteration_items(), ["Red Fox".to_string()]);
+        assert_eq!(tracker.total_unique_count(), 1);
+    }
+
+    #[test]
+    fn test_unicode_case_folding() {
+        let mut tracker = UniquenessTracker::new();
+        tracker.set_normalization_mode(NormalizationMode::CaseInsensitive);
+
+        let unique = tracker
+            .filter_unique(vec![
+                "Ÿellow Ärmel".to_string(),
+                "ÿellow ärmel".to_string(),
+                "ΣΟΦΙΑ".to_string(),
+                "σοφια".to_string(),
+            ])
+            .unwrap();
+        assert_eq!(unique, vec!["Ÿellow Ärmel".to_string(), "ΣΟΦΙΑ".to_string()]);
+
+        // Case folding alone does not collapse whitespace
+        let unique = tracker.filter_unique(vec!["ÿellow  ärmel".to_string()]).unwrap();
+        assert_eq!(unique.len(), 1);
+    }
+
+    #[test]
+    fn test_trimmed_collapses_internal_whitespace() {
+        let mut tracker = UniquenessTracker::new();
+        tracker.set_normalization_mode(NormalizationMode::Trimmed);
+
+        let unique = tracker
+            .filter_unique(vec![
+                "red fox".to_string(),
+                " red\tfox\n".to_string(),
+                "red   fox".to_string(),
+                "Red fox".to_string(),
+            ])
+            .unwrap();
+        assert_eq!(unique, vec!["red fox".to_string(), "Red fox".to_string()]);
+    }
+
+    #[test]
+    fn test_exact_mode_is_default() {
+        let mut tracker = UniquenessTracker::new();
+        assert_eq!(tracker.normalization_mode(), NormalizationMode::Exact);
+        let unique = tracker
+            .filter_unique(vec!["Red Fox".to_string(), "red fox".to_string()])
+            .unwrap();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn test_changing_mode_rekeys_seen_items() {
+        let mut tracker = UniquenessTracker::new();
+        tracker
+            .filter_unique(vec!["Red Fox".to_string(), "red fox".to_string()])
+            .unwrap();
+        assert_eq!(tracker.total_unique_count(), 2);
+
+        tracker.set_normalization_mode(NormalizationMode::CaseInsensitive);
+        assert_eq!(tracker.total_unique_count(), 1);
+        assert!(tracker.filter_unique(vec!["RED FOX".to_string()]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_seeded_values_are_duplicates_but_never_emitted() {
+        let mut tracker = UniquenessTracker::new();
+        tracker.set_normalization_mode(NormalizationMode::CaseInsensitive);
+        let version = tracker.get_bloom_version();
+
+        let seeds = vec!["Eiffel Tower".to_string(), "louvre".to_string(), "EIFFEL TOWER".to_string()];
+        assert_eq!(tracker.seed_known(&seeds), 2);
+        assert_eq!(tracker.total_unique_count(), 2);
+        assert!(tracker.get_current_iteration_items().is_empty());
+        assert!(tracker.get_bloom_version() > version);
+
+        let unique = tracker
+            .filter_unique(vec!["eiffel tower".to_string(), "Louvre".to_string(), "Notre-Dame".to_string()])
+            .unwrap();
+        assert_eq!(unique, vec!["Notre-Dame".to_string()]);
+        assert_eq!(tracker.get_current_iteration_items(), ["Notre-Dame".to_string()]);
+    }
+
+    #[test]
+    fn test_read_seed_file_trims_and_skips_blank_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seeds.txt");
+        std::fs::write(&path, "  apple \n\n\tbanana\n   \ncherry").unwrap();
+
+        assert_eq!(read_seed_file(&path).unwrap(), vec!["apple", "banana", "cherry"]);
+        assert!(read_seed_file(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_normalization_mode_parsing() {
+        for mode in [
+            NormalizationMode::Exact,
+            NormalizationMode::CaseInsensitive,
+            NormalizationMode::Trimmed,
+            NormalizationMode::CaseInsensitiveTrimmed,
+        ] {
+            assert_eq!(mode.to_string().parse::<NormalizationMode>().unwrap(), mode);
+        }
+        assert!("fuzzy".parse::<NormalizationMode>().is_err());
+    }
+
+    fn numbered(prefix: &str, range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("{prefix}-{i}")).collect()
+    }
+
+    #[test]
+    fn test_false_positive_estimate_crosses_threshold_as_layers_fill() {
+        let mut tracker = UniquenessTracker::new().with_bloom_capacity(1_000);
+        assert_eq!(tracker.estimated_false_positive_rate(), 0.0);
+
+        // Within the first layer's capacity the estimate stays under the target rate
+        tracker.filter_unique(numbered("item", 0..1_000)).unwrap();
+        let first_layer_full = tracker.estimated_false_positive_rate();
+        assert!(first_layer_full > 0.0 && first_layer_full < BLOOM_TARGET_FALSE_POSITIVE_RATE);
+        assert_eq!(tracker.false_positive_pressure_crossed(), None);
+
+        // Filling a second layer pushes the combined rate over the warning threshold, reported once
+        tracker.filter_unique(numbered("item", 1_000..4_000)).unwrap();
+        let rate = tracker.false_positive_pressure_crossed().unwrap();
+        assert!(rate > BLOOM_FALSE_POSITIVE_WARN_THRESHOLD);
+        assert_eq!(tracker.false_positive_pressure_crossed(), None);
+
+        let stats = tracker.bloom_filter_stats();
+        assert_eq!(stats.unique_items, 4_000);
+        assert_eq!(stats.estimated_false_positive_rate, rate);
+
+        tracker.reset();
+        assert_eq!(tracker.estimated_false_positive_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_bloom_filter_grows_layers_and_keeps_false_positives_bounded() {
+        let mut tracker = UniquenessTracker::new().with_bloom_capacity(1_000);
+        let inserted = tracker.filter_unique(numbered("item", 0..64_000)).unwrap();
+        assert_eq!(inserted.len(), 64_000);
+        assert!(tracker.bloom_filter.capacity() >= 64_000);
+
+        // Layer error rates shrink geometrically, so the combined rate has a fixed ceiling
+        let bound = BLOOM_TARGET_FALSE_POSITIVE_RATE / (1.0 - BLOOM_TIGHTENING_RATIO);
+        let probes = numbered("probe", 0..50_000);
+        let false_positives = probes.iter().filter(|probe| tracker.bloom_filter.contains(probe)).count();
+        let measured = false_positives as f64 / probes.len() as f64;
+        assert!(measured < bound, "measured false-positive rate {measured} exceeds {bound}");
+        assert!(tracker.estimated_false_positive_rate() < bound);
+
+        // The exact set still decides: nothing new is rejected and nothing seen is accepted twice
+        assert_eq!(tracker.filter_unique(probes).unwrap().len(), 50_000);
+        assert!(tracker.filter_unique(numbered("item", 0..1_000)).unwrap().is_empty());
+    }
 }
@@ -4,6 +4,8 @@
 //! coordinates multiple producer processes to generate unique attributes efficiently
 //! while managing costs and optimizing performance.
 
+pub mod cli;
+pub mod config;
 pub mod core;
 pub mod error;
 pub mod optimization;
@@ -12,8 +14,9 @@ pub mod services;
 pub mod traits;
 
 // Re-export commonly used types
-pub use core::{OrchestratorState, PerformanceTracker, UniquenessTracker};
+pub use config::OrchestratorConfig;
+pub use core::{NormalizationMode, OrchestratorState, PerformanceTracker, UniquenessTracker};
 pub use error::{OrchestratorError, OrchestratorResult};
 pub use optimization::{OptimizerStrategy, OptimizationContext, OptimizationResult};
-pub use orchestrator::Orchestrator;
+pub use orchestrator::{CommandRetryPolicy, LoopIntervals, Orchestrator, MIN_LOOP_INTERVAL};
 pub use traits::{ApiKeySource, Communicator, FileSystem, ProcessManager};
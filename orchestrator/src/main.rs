@@ -3,77 +3,35 @@
 //! This demonstrates how to use the orchestrator with real service implementations
 //! and proper dependency injection.
 
-use clap::Parser;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::signal;
 
 use orchestrator::{
+    cli::Args,
     services::{RealApiKeySource, RealCommunicator, RealFileSystem, RealProcessManager},
-    optimization::strategies::BasicOptimizer,
-    Orchestrator, OrchestratorResult,
+    optimization::strategies::{AdaptiveOptimizer, BasicOptimizer},
+    optimization::{OptimizerStrategy, PromptTemplates},
+    core::read_seed_file,
+    LoopIntervals, NormalizationMode, Orchestrator, OrchestratorResult,
 };
-use shared::{logging, process_debug, process_info, ProcessId};
-
-/// Orchestrator for managing LLM-based unique attribute generation
-#[derive(Parser)]
-#[command(name = "orchestrator")]
-#[command(about = "Orchestrates multiple producer processes for unique attribute generation")]
-pub struct Args {
-    /// Tracing endpoint URL (if set, all spawned processes will also trace here)
-    #[arg(long)]
-    pub trace_ep: Option<String>,
-
-    /// Log level (trace, debug, info, warn, error)
-    #[arg(long, default_value = "info")]
-    pub log_level: String,
-
-    /// CLI mode: Topic for generation (when provided, starts producers immediately without webserver)
-    #[arg(long)]
-    pub topic: Option<String>,
-
-    /// CLI mode: Number of producers to spawn (only used with --topic)
-    #[arg(long, default_value = "5")]
-    pub producers: u32,
-
-    /// CLI mode: Number of iterations to run (only used with --topic, runs indefinitely if not specified)
-    #[arg(long)]
-    pub iterations: Option<u32>,
-
-    /// CLI mode: Request size (number of items to request per API call)
-    #[arg(long, default_value = "60")]
-    pub request_size: usize,
-
-    /// Output directory (relative or absolute path, defaults to ./output/<topic>)
-    #[arg(long)]
-    pub output: Option<String>,
-
-    /// Routing strategy type (backoff, roundrobin, priority, weighted)
-    #[arg(long)]
-    pub routing_strategy: Option<String>,
-
-    /// Strategy-specific routing configuration (e.g., "openai" for backoff, "openai+anthropic" for roundrobin, "openai:0.7+anthropic:0.3" for weighted)
-    #[arg(long)]
-    pub routing_config: Option<String>,
-
-    /// Webserver bind address
-    #[arg(long)]
-    pub webserver_addr: Option<String>,
-
-    /// Producer communication bind address  
-    #[arg(long)]
-    pub producer_addr: Option<String>,
-}
+use shared::{logging, process_debug, process_info, OptimizationMode, ProcessId};
 
 #[tokio::main]
 async fn main() -> OrchestratorResult<()> {
-    // Parse command line arguments
-    let args = Args::parse();
+    // Parse command line arguments, filling unset flags from --config
+    let args = Args::parse_with_config();
 
     // Determine operating mode
-    let cli_mode = args.topic.is_some();
+    let cli_mode = args.topic.is_some() || args.topics_file.is_some();
+    let optimization_mode = parse_optimization_mode(&args)?;
+    let mut topics = match &args.topics_file {
+        Some(path) => read_topics_file(path)?,
+        None => args.topic.iter().cloned().collect(),
+    };
     // Check if using random provider (legacy check for test mode)
-    let use_only_random = args.routing_config.as_ref().map_or(false, |c| c == "random");
+    let use_only_random = args.routing_config.as_deref() == Some("random");
 
     // Initialize process ID singleton for orchestrator
     ProcessId::init_orchestrator();
@@ -83,10 +41,16 @@ async fn main() -> OrchestratorResult<()> {
         .trace_ep
         .as_ref()
         .map(|url| shared::logging::TracingEndpoint::new(url.clone()));
-    shared::logging::init_tracing_with_endpoint_and_level(trace_endpoint, Some(&args.log_level));
+    let log_options = shared::logging::LogOptions {
+        log_level: Some(args.log_level.clone()),
+        use_stderr: args.stdout_output,
+        plain: args.plain_logs,
+        format: args.log_format,
+    };
+    shared::logging::init_tracing_with_options(trace_endpoint, &log_options);
 
     if cli_mode {
-        let topic = args.topic.as_ref().unwrap();
+        let topic = &topics[0];
         let output_dir = args.output.clone().unwrap_or_else(|| format!("./output/{}", topic));
 
         process_info!(ProcessId::current(), "🖥️  Starting orchestrator in CLI mode");
@@ -107,6 +71,9 @@ async fn main() -> OrchestratorResult<()> {
             args.request_size,
             output_dir
         );
+        if topics.len() > 1 {
+            process_info!(ProcessId::current(), "📦 Running {} topics from the topics file", topics.len());
+        }
     } else {
         logging::log_startup(ProcessId::current(), "orchestrator service (web mode)");
         if use_only_random {
@@ -125,23 +92,98 @@ async fn main() -> OrchestratorResult<()> {
     };
     let communicator = RealCommunicator::new();
 
-    // Configure output directory
+    // Configure output directory; producers keep their dead letters alongside the topic output
+    let output_base = match (cli_mode, &args.output) {
+        (true, Some(output)) => PathBuf::from(output),
+        _ => PathBuf::from("./output"),
+    };
     let file_system = if cli_mode {
-        let output_dir = args.output.clone().unwrap_or_else(|| "./output".to_string());
-        RealFileSystem::with_base_dir(PathBuf::from(output_dir))
+        RealFileSystem::with_base_dir(output_base.clone())
+            .with_stdout_output(args.stdout_output)
+            .with_output_buffering(args.buffer_output)
     } else {
         RealFileSystem::new()
     };
 
+    if !(0.0..=1.0).contains(&args.verification_sample_rate) {
+        return Err(orchestrator::OrchestratorError::config(format!(
+            "--verification-sample-rate must be between 0.0 and 1.0, got {}",
+            args.verification_sample_rate
+        )));
+    }
     let process_manager = RealProcessManager::new()
         .with_trace_endpoint(args.trace_ep.clone())
-        .with_log_level(args.log_level.clone());
+        .with_log_level(args.log_level.clone())
+        .with_plain_logs(args.plain_logs)
+        .with_log_format(args.log_format)
+        .with_examples_file(args.examples_file.clone())
+        .with_pricing(args.pricing.clone())
+        .with_dead_letter_dir(Some(output_base))
+        .with_auto_tune(args.auto_tune)
+        .with_filtered_prompt_rewording(args.reword_filtered_prompts)
+        .with_request_cache(args.enable_request_cache.then_some(args.request_cache_ttl_ms))
+        .with_verification(args.verification_provider.clone(), args.verification_sample_rate)
+        .with_reserved_stdout(args.stdout_output)
+        .with_warm_pool_size(args.warm_pool_size);
 
-    // Create basic optimizer for the CLI
-    let optimizer = BasicOptimizer::new();
+    // Create the selected optimizer
+    let templates = match &args.prompt_templates {
+        Some(path) => PromptTemplates::load(path)?,
+        None => PromptTemplates::default(),
+    };
+    if let (Some(min), Some(max)) = (args.min_attribute_length, args.max_attribute_length) {
+        if min > max {
+            return Err(orchestrator::OrchestratorError::config(format!(
+                "--min-attribute-length ({min}) must not exceed --max-attribute-length ({max})"
+            )));
+        }
+    }
+    let (min_length, max_length) = (args.min_attribute_length, args.max_attribute_length);
+    let seen_hint = args.include_seen_hint.then_some(args.seen_hint_count);
+    let optimizer: Box<dyn OptimizerStrategy> = match args.optimizer.as_str() {
+        "adaptive" => Box::new(
+            AdaptiveOptimizer::new()
+                .with_templates(templates)
+                .with_attribute_length_bounds(min_length, max_length)
+                .with_seen_hint(seen_hint),
+        ),
+        _ => Box::new(
+            BasicOptimizer::new()
+                .with_templates(templates)
+                .with_attribute_length_bounds(min_length, max_length)
+                .with_seen_hint(seen_hint),
+        ),
+    };
 
     // Create orchestrator with dependency injection
-    let mut orchestrator = Orchestrator::new(api_keys, communicator, file_system, process_manager, optimizer);
+    let mut orchestrator = Orchestrator::new(api_keys, communicator, file_system, process_manager, optimizer)
+        .with_min_free_space(args.min_free_space_mb.saturating_mul(1024 * 1024))
+        .with_max_runtime(args.max_runtime_seconds)
+        .with_efficiency_floor(args.min_efficiency_percent, args.efficiency_patience)
+        .with_target_count(args.target_count)
+        .with_compress_sync(args.compress_sync)
+        .with_seed_values(match &args.seed_file {
+            Some(path) => read_seed_file(path)?,
+            None => Vec::new(),
+        })
+        .with_loop_intervals(LoopIntervals {
+            metrics: Duration::from_secs(args.metrics_interval_secs),
+            health: Duration::from_secs(args.health_interval_secs),
+            flush: Duration::from_secs(args.output_flush_secs),
+        });
+    if let Some(seed) = args.seed {
+        orchestrator.set_run_seed(seed).await;
+    }
+    if args.normalization != NormalizationMode::Exact {
+        orchestrator.set_normalization_mode(args.normalization).await;
+    }
+    if args.no_dedup {
+        orchestrator.set_dedup_enabled(false).await;
+    }
+    orchestrator
+        .set_performance_half_life(Some(Duration::from_secs(args.performance_half_life_secs)))
+        .await;
+    orchestrator.set_metrics_smoothing(args.metrics_smoothing).await;
 
     // Configure bind addresses
     let webserver_addr: SocketAddr = args
@@ -163,20 +205,48 @@ async fn main() -> OrchestratorResult<()> {
         orchestrator.initialize_cli_mode(producer_addr).await?;
         
         // Set default routing strategy from args (convert new format to legacy for now)
-        let legacy_provider = args.routing_config.as_ref().map(|c| c.clone());
+        let legacy_provider = args.routing_config.clone();
         orchestrator.set_default_routing_strategy(args.routing_strategy.clone(), legacy_provider).await?;
 
-        // Start generation immediately with provided topic
-        let topic = args.topic.unwrap();
-        orchestrator
-            .start_cli_generation(topic, args.producers, args.iterations, args.request_size, args.routing_strategy, args.routing_config)
-            .await?;
+        if args.concurrent_topics {
+            orchestrator
+                .start_concurrent_generation(
+                    topics,
+                    args.producers,
+                    args.iterations,
+                    args.request_size,
+                    optimization_mode,
+                    args.routing_strategy,
+                    args.routing_config,
+                )
+                .await?;
+        } else {
+            // Start generation immediately with the first topic; the rest follow as a batch
+            let topic = topics.remove(0);
+            if !topics.is_empty() {
+                orchestrator.queue_batch_topics(topics, args.request_size, args.routing_strategy.clone(), args.routing_config.clone());
+            }
+            if args.resume {
+                orchestrator.resume_from_checkpoint(&topic).await?;
+            }
+            orchestrator
+                .start_cli_generation(
+                    topic,
+                    args.producers,
+                    args.iterations,
+                    args.request_size,
+                    optimization_mode,
+                    args.routing_strategy,
+                    args.routing_config,
+                )
+                .await?;
+        }
     } else {
         // WebServer mode: Initialize with webserver
         orchestrator.initialize(webserver_addr, producer_addr).await?;
         
         // Set default routing strategy from args/env (convert new format to legacy for now)
-        let legacy_provider = args.routing_config.as_ref().map(|c| c.clone());
+        let legacy_provider = args.routing_config.clone();
         orchestrator.set_default_routing_strategy(args.routing_strategy, legacy_provider).await?;
     }
 
@@ -217,3 +287,111 @@ async fn main() -> OrchestratorResult<()> {
     logging::log_success(ProcessId::current(), "Orchestrator stopped gracefully");
     Ok(())
 }
+
+/// Build the CLI optimization mode from `--optimization-mode` and its numeric arguments
+fn parse_optimization_mode(args: &Args) -> OrchestratorResult<OptimizationMode> {
+    let value = |name: &str, value: Option<f64>, allow_zero: bool| -> OrchestratorResult<f64> {
+        match value {
+            Some(v) if v.is_finite() && (v > 0.0 || (allow_zero && v == 0.0)) => Ok(v),
+            Some(v) => Err(orchestrator::OrchestratorError::config(format!(
+                "--{name} must be {}, got {v}",
+                if allow_zero { "non-negative" } else { "positive" }
+            ))),
+            None => Err(orchestrator::OrchestratorError::config(format!(
+                "--optimization-mode {} requires --{name}",
+                args.optimization_mode
+            ))),
+        }
+    };
+
+    let mode = match args.optimization_mode.as_str() {
+        "maximize-uam" => OptimizationMode::MaximizeUAM {
+            budget_per_minute: value("budget-per-minute", args.budget_per_minute, false)?,
+        },
+        "minimize-cost" => OptimizationMode::MinimizeCost {
+            target_uam: value("target-uam", args.target_uam, false)?,
+        },
+        "weighted" => {
+            let uam_weight = value("uam-weight", args.uam_weight, true)?;
+            let cost_weight = value("cost-weight", args.cost_weight, true)?;
+            let token_weight = value("token-weight", args.token_weight, true)?;
+            if uam_weight + cost_weight + token_weight == 0.0 {
+                return Err(orchestrator::OrchestratorError::config("At least one optimization weight must be positive"));
+            }
+            OptimizationMode::Weighted {
+                uam_weight,
+                cost_weight,
+                token_weight,
+            }
+        }
+        "efficiency" => OptimizationMode::MaximizeEfficiency,
+        other => {
+            return Err(orchestrator::OrchestratorError::config(format!("Unknown optimization mode '{other}'")));
+        }
+    };
+    Ok(mode)
+}
+
+/// Read newline-delimited topics, ignoring blank lines
+fn read_topics_file(path: &Path) -> OrchestratorResult<Vec<String>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        orchestrator::OrchestratorError::config(format!("Failed to read topics file {}: {}", path.display(), e))
+    })?;
+    let topics: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    if topics.is_empty() {
+        return Err(orchestrator::OrchestratorError::config(format!(
+            "Topics file {} contains no topics",
+            path.display()
+        )));
+    }
+    Ok(topics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(args: &[&str]) -> OrchestratorResult<OptimizationMode> {
+        let args = Args::try_parse_with_config(std::iter::once("orchestrator").chain(args.iter().copied())).unwrap();
+        parse_optimization_mode(&args)
+    }
+
+    #[test]
+    fn test_parse_optimization_mode_builds_each_mode() {
+        assert!(matches!(mode(&[]).unwrap(), OptimizationMode::MaximizeEfficiency));
+        assert!(matches!(
+            mode(&["--optimization-mode", "maximize-uam", "--budget-per-minute", "0.5"]).unwrap(),
+            OptimizationMode::MaximizeUAM { budget_per_minute } if budget_per_minute == 0.5
+        ));
+        assert!(matches!(
+            mode(&["--optimization-mode", "minimize-cost", "--target-uam", "30"]).unwrap(),
+            OptimizationMode::MinimizeCost { target_uam } if target_uam == 30.0
+        ));
+        let weighted = ["--optimization-mode", "weighted", "--uam-weight", "1", "--cost-weight", "0"];
+        assert!(matches!(
+            mode(&[&weighted[..], &["--token-weight", "0.5"]].concat()).unwrap(),
+            OptimizationMode::Weighted { uam_weight, cost_weight, token_weight }
+                if uam_weight == 1.0 && cost_weight == 0.0 && token_weight == 0.5
+        ));
+    }
+
+    #[test]
+    fn test_parse_optimization_mode_rejects_invalid_values() {
+        let error = mode(&["--optimization-mode", "maximize-uam", "--budget-per-minute", "0"]).unwrap_err();
+        assert!(error.to_string().contains("--budget-per-minute must be positive"), "{error}");
+
+        let weighted = ["--optimization-mode", "weighted", "--uam-weight", "0", "--cost-weight", "0"];
+        let error = mode(&[&weighted[..], &["--token-weight", "0"]].concat()).unwrap_err();
+        assert!(error.to_string().contains("At least one optimization weight must be positive"), "{error}");
+
+        let mut args = Args::try_parse_with_config(["orchestrator"]).unwrap();
+        args.optimization_mode = "fastest".to_string();
+        let error = parse_optimization_mode(&args).unwrap_err();
+        assert!(error.to_string().contains("Unknown optimization mode 'fastest'"), "{error}");
+    }
+}
@@ -6,6 +6,8 @@
 pub mod traits;
 pub mod types;
 pub mod strategies;
+pub mod templates;
 
 pub use traits::OptimizerStrategy;
+pub use templates::PromptTemplates;
 pub use types::*;
\ No newline at end of file
@@ -3,6 +3,7 @@
 //! This implements sophisticated optimization with prompt rotation, UAM decline detection,
 //! and performance-based adaptation using interior mutability for state tracking.
 
+use super::super::templates::PromptTemplates;
 use super::super::traits::OptimizerStrategy;
 use super::super::types::*;
 use crate::error::OrchestratorResult;
@@ -25,6 +26,13 @@ pub struct AdaptiveOptimizer {
     
     /// Mutable state for tracking performance and adaptations (interior mutability)
     state: RwLock<AdaptiveState>,
+
+    /// Character bounds producers apply to normalized attributes
+    min_attribute_length: Option<usize>,
+    max_attribute_length: Option<usize>,
+
+    /// Recent attributes listed in prompts, None leaves the hint out
+    seen_hint_count: Option<usize>,
 }
 
 /// Configuration for adaptive behavior
@@ -64,6 +72,7 @@ struct AdaptiveState {
 
 /// Performance snapshot for trend analysis
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 struct PerformanceSnapshot {
     timestamp: Instant,
     overall_uam: f64,
@@ -74,24 +83,19 @@ struct PerformanceSnapshot {
 
 /// Template with performance tracking
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 struct PromptTemplate {
     id: String,
     template: String,
-    strategy_type: TemplateStrategy,
     usage_count: u32,
     total_uam: f64,
     last_used: Option<Instant>,
 }
 
-/// Different template strategies
-#[derive(Debug, Clone)]
-enum TemplateStrategy {
-    Concrete,
-    Creative,
-    Technical,
-    Functional,
-    Structural,
-    Contextual,
+impl Default for AdaptiveOptimizer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AdaptiveOptimizer {
@@ -102,7 +106,7 @@ impl AdaptiveOptimizer {
     
     /// Create adaptive optimizer with custom configuration
     pub fn with_config(config: AdaptationConfig) -> Self {
-        let templates = Self::create_default_templates();
+        let templates = Self::create_templates(&PromptTemplates::default());
         
         Self {
             name: "Adaptive".to_string(),
@@ -114,39 +118,64 @@ impl AdaptiveOptimizer {
                 last_adaptation: None,
                 current_adaptation_level: AdaptationLevel::None,
             }),
+            min_attribute_length: None,
+            max_attribute_length: None,
+            seen_hint_count: None,
         }
     }
     
-    /// Create default set of prompt templates
-    fn create_default_templates() -> Vec<PromptTemplate> {
-        vec![
-            PromptTemplate {
-                id: "concrete".to_string(),
-                template: "Generate highly specific, concrete physical objects and components for '{topic}'. Focus on: materials, parts, structural elements, tools, equipment. Be extremely detailed and specific. Examples: 'reinforced steel beam', 'marble entrance column', 'bronze door handle'. Avoid abstract concepts.".to_string(),
-                strategy_type: TemplateStrategy::Concrete,
-                usage_count: 0,
-                total_uam: 0.0,
-                last_used: None,
-            },
-            PromptTemplate {
-                id: "creative".to_string(),
-                template: "Think creatively and generate unique, unconventional aspects of '{topic}'. Explore unexpected angles, hidden elements, rarely considered components. Push boundaries while staying factual. Examples: 'secret passage mechanism', 'weathering pattern', 'acoustic property'. Be innovative and surprising.".to_string(),
-                strategy_type: TemplateStrategy::Creative,
-                usage_count: 0,
-                total_uam: 0.0,
-                last_used: None,
-            },
-            PromptTemplate {
-                id: "technical".to_string(),
-                template: "Generate technical, engineering, and specialized components of '{topic}'. Focus on: technical specifications, engineering elements, specialized equipment, precise terminology. Examples: 'load-bearing junction', 'thermal insulation layer', 'electrical conduit'. Use expert-level technical knowledge.".to_string(),
-                strategy_type: TemplateStrategy::Technical,
+    /// Use the given prompt templates instead of the built-in ones
+    pub fn with_templates(self, templates: PromptTemplates) -> Self {
+        self.state.write().unwrap().prompt_templates = Self::create_templates(&templates);
+        self
+    }
+
+    /// Have producers drop attributes outside these character bounds (None leaves a side open)
+    pub fn with_attribute_length_bounds(mut self, min: Option<usize>, max: Option<usize>) -> Self {
+        self.min_attribute_length = min;
+        self.max_attribute_length = max;
+        self
+    }
+
+    /// List this many recent attributes in each prompt, or leave the hint out with None
+    pub fn with_seen_hint(mut self, count: Option<usize>) -> Self {
+        self.seen_hint_count = count;
+        self
+    }
+
+    /// Tracked templates for each per-provider prompt
+    fn create_templates(templates: &PromptTemplates) -> Vec<PromptTemplate> {
+        templates
+            .per_provider
+            .iter()
+            .map(|named| PromptTemplate {
+                id: named.id.clone(),
+                template: named.template.clone(),
                 usage_count: 0,
                 total_uam: 0.0,
                 last_used: None,
-            },
-        ]
+            })
+            .collect()
     }
     
+    /// Index of the template with the highest average UAM.
+    ///
+    /// NaN averages rank lowest and ties go to the earliest template, so the
+    /// choice is deterministic and never panics on bad metrics.
+    fn best_template_index(templates: &[PromptTemplate]) -> usize {
+        let average_uam = |template: &PromptTemplate| {
+            let avg = if template.usage_count > 0 { template.total_uam / template.usage_count as f64 } else { 0.0 };
+            if avg.is_nan() { f64::NEG_INFINITY } else { avg }
+        };
+
+        templates
+            .iter()
+            .enumerate()
+            .max_by(|(idx_a, a), (idx_b, b)| average_uam(a).total_cmp(&average_uam(b)).then(idx_b.cmp(idx_a)))
+            .map(|(idx, _)| idx)
+            .unwrap_or(0)
+    }
+
     /// Analyze UAM trend from performance history
     fn analyze_uam_trend(&self, state: &AdaptiveState) -> UAMTrendAnalysis {
         if state.performance_history.len() < 3 {
@@ -237,7 +266,7 @@ impl OptimizerStrategy for AdaptiveOptimizer {
         let trend_analysis = self.analyze_uam_trend(&state);
         let needs_adaptation = matches!(trend_analysis.direction, TrendDirection::Declining) 
             && trend_analysis.confidence > 0.5
-            && state.last_adaptation.map_or(true, |last| 
+            && state.last_adaptation.is_none_or(|last| 
                 Instant::now().duration_since(last) > self.config.min_adaptation_interval
             );
         
@@ -258,7 +287,7 @@ impl OptimizerStrategy for AdaptiveOptimizer {
                 state.producer_assignments.insert(producer_id, template_idx);
                 
                 let template = &mut state.prompt_templates[template_idx];
-                let prompt = template.template.replace("{topic}", &context.topic);
+                let prompt = PromptTemplates::render(&template.template, &context.topic);
                 template.usage_count += 1;
                 template.last_used = Some(Instant::now());
                 
@@ -272,15 +301,9 @@ impl OptimizerStrategy for AdaptiveOptimizer {
             (PromptAssignments::custom(assignments), state.current_adaptation_level.clone())
         } else {
             // Use best performing template for all producers
-            let best_template = state.prompt_templates.iter()
-                .max_by(|a, b| {
-                    let avg_a = if a.usage_count > 0 { a.total_uam / a.usage_count as f64 } else { 0.0 };
-                    let avg_b = if b.usage_count > 0 { b.total_uam / b.usage_count as f64 } else { 0.0 };
-                    avg_a.partial_cmp(&avg_b).unwrap()
-                })
-                .unwrap_or(&state.prompt_templates[0]);
+            let best_template = &state.prompt_templates[Self::best_template_index(&state.prompt_templates)];
             
-            let prompt = best_template.template.replace("{topic}", &context.topic);
+            let prompt = PromptTemplates::render(&best_template.template, &context.topic);
             (PromptAssignments::uniform(prompt), AdaptationLevel::None)
         };
         
@@ -315,6 +338,12 @@ impl OptimizerStrategy for AdaptiveOptimizer {
                 AdaptationLevel::Moderate => 90,
                 _ => 100,
             },
+            extraction_format: None,
+            system_prompt: Some(super::GENERATION_SYSTEM_PROMPT.to_string()),
+            min_attribute_length: self.min_attribute_length,
+            max_attribute_length: self.max_attribute_length,
+            include_seen_hint: self.seen_hint_count.is_some(),
+            seen_hint_count: self.seen_hint_count.unwrap_or(shared::types::DEFAULT_SEEN_HINT_COUNT),
         };
         
         let assessment = OptimizationAssessment {
@@ -412,4 +441,41 @@ impl OptimizerStrategy for AdaptiveOptimizer {
             adaptation_level: state.current_adaptation_level.clone(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templates_with_uam(uams: &[(u32, f64)]) -> Vec<PromptTemplate> {
+        let defaults = AdaptiveOptimizer::create_templates(&PromptTemplates::default());
+        uams.iter()
+            .enumerate()
+            .map(|(i, &(usage_count, total_uam))| PromptTemplate {
+                usage_count,
+                total_uam,
+                ..defaults[i % defaults.len()].clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_best_template_ties_resolve_to_earliest() {
+        let templates = templates_with_uam(&[(2, 10.0), (1, 5.0), (4, 20.0)]);
+        for _ in 0..10 {
+            assert_eq!(AdaptiveOptimizer::best_template_index(&templates), 0);
+        }
+
+        let unused = templates_with_uam(&[(0, 0.0), (0, 0.0), (0, 0.0)]);
+        assert_eq!(AdaptiveOptimizer::best_template_index(&unused), 0);
+    }
+
+    #[test]
+    fn test_best_template_ignores_nan_metrics() {
+        let templates = templates_with_uam(&[(1, f64::NAN), (1, 3.0), (1, f64::NAN)]);
+        assert_eq!(AdaptiveOptimizer::best_template_index(&templates), 1);
+
+        let all_nan = templates_with_uam(&[(1, f64::NAN), (1, f64::NAN)]);
+        assert_eq!(AdaptiveOptimizer::best_template_index(&all_nan), 0);
+    }
+}
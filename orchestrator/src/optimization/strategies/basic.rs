@@ -3,6 +3,7 @@
 //! This provides a simple, reliable optimization approach that generates
 //! consistent prompts and routing strategies without complex adaptation logic.
 
+use super::super::templates::PromptTemplates;
 use super::super::traits::OptimizerStrategy;
 use super::super::types::*;
 use crate::error::OrchestratorResult;
@@ -17,6 +18,16 @@ use std::time::Instant;
 pub struct BasicOptimizer {
     /// Name for identification
     name: String,
+
+    /// Prompt templates, built-in unless loaded from a file
+    templates: PromptTemplates,
+
+    /// Character bounds producers apply to normalized attributes
+    min_attribute_length: Option<usize>,
+    max_attribute_length: Option<usize>,
+
+    /// Recent attributes listed in prompts, None leaves the hint out
+    seen_hint_count: Option<usize>,
 }
 
 impl BasicOptimizer {
@@ -24,19 +35,35 @@ impl BasicOptimizer {
     pub fn new() -> Self {
         Self {
             name: "Basic".to_string(),
+            templates: PromptTemplates::default(),
+            min_attribute_length: None,
+            max_attribute_length: None,
+            seen_hint_count: None,
         }
     }
+
+    /// Use the given prompt templates instead of the built-in ones
+    pub fn with_templates(mut self, templates: PromptTemplates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Have producers drop attributes outside these character bounds (None leaves a side open)
+    pub fn with_attribute_length_bounds(mut self, min: Option<usize>, max: Option<usize>) -> Self {
+        self.min_attribute_length = min;
+        self.max_attribute_length = max;
+        self
+    }
+
+    /// List this many recent attributes in each prompt, or leave the hint out with None
+    pub fn with_seen_hint(mut self, count: Option<usize>) -> Self {
+        self.seen_hint_count = count;
+        self
+    }
     
     /// Generate a simple, reliable prompt for the given topic
     fn generate_basic_prompt(&self, topic: &str) -> String {
-        format!(
-            "Generate highly specific, unique nouns and noun phrases for '{topic}'. \
-            Each attribute must be a concrete thing, object, component, or feature (e.g., 'steel support beam', 'marble entrance hall', 'control panel'). \
-            Focus on specific parts, components, materials, structures, or distinctive elements that exist within or relate to this topic. \
-            Avoid adjectives, descriptions, and numbers - only generate actual things/nouns using words only. Think like an expert cataloging specific items. \
-            Output one noun/noun phrase per line. \
-            IMPORTANT: Do not include any numbers, measurements, or quantities. Provide all results in English only, translating any foreign language terms to English."
-        )
+        PromptTemplates::render(&self.templates.uniform, topic)
     }
     
     /// Create a simple routing strategy based on available providers
@@ -73,6 +100,12 @@ impl BasicOptimizer {
             max_tokens: 800,
             temperature: 0.8,
             request_size: 100,
+            extraction_format: None,
+            system_prompt: Some(super::GENERATION_SYSTEM_PROMPT.to_string()),
+            min_attribute_length: self.min_attribute_length,
+            max_attribute_length: self.max_attribute_length,
+            include_seen_hint: self.seen_hint_count.is_some(),
+            seen_hint_count: self.seen_hint_count.unwrap_or(shared::types::DEFAULT_SEEN_HINT_COUNT),
         }
     }
 }
@@ -110,6 +143,8 @@ impl OptimizerStrategy for BasicOptimizer {
                     RoutingStrategy::Backoff { .. } => "backoff",
                     RoutingStrategy::PriorityOrder { .. } => "priority",
                     RoutingStrategy::Weighted { .. } => "weighted",
+                    RoutingStrategy::CircuitBreaker { .. } => "circuit-breaker",
+                    RoutingStrategy::LowestLatency { .. } => "lowest-latency",
                 }
             ),
             metadata: OptimizationMetadata {
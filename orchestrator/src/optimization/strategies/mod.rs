@@ -7,4 +7,10 @@ pub mod basic;
 pub mod adaptive;
 
 pub use basic::BasicOptimizer;
-pub use adaptive::AdaptiveOptimizer;
\ No newline at end of file
+pub use adaptive::AdaptiveOptimizer;
+
+/// Standing instructions every optimizer sends as the system prompt
+pub const GENERATION_SYSTEM_PROMPT: &str = "You catalog distinct attributes of a topic. \
+    Every entry must be unique: never repeat an entry or give a variant, synonym or translation of one already listed. \
+    Do not include any numbers, measurements, or quantities. \
+    Provide all results in English only, translating any foreign language terms to English.";
@@ -0,0 +1,137 @@
+//! Prompt templates used by the optimizers
+//!
+//! Templates can be loaded from a TOML or JSON file (chosen by extension) to replace
+//! the built-in English prompts. Every template must contain the `{topic}` placeholder,
+//! which is replaced with the topic being generated. There is one template per prompt
+//! partitioning: `uniform` is the prompt every producer shares, and `per_provider` is
+//! the set the adaptive optimizer spreads across producers when it diversifies prompts.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Placeholder every template must contain
+pub const TOPIC_PLACEHOLDER: &str = "{topic}";
+
+/// Prompt templates for each prompt partitioning
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromptTemplates {
+    /// Prompt shared by every producer
+    pub uniform: String,
+    /// Prompts assigned to producers in turn when prompts are diversified
+    pub per_provider: Vec<NamedTemplate>,
+}
+
+/// A per-provider template and the name it is reported under
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamedTemplate {
+    pub id: String,
+    pub template: String,
+}
+
+impl Default for PromptTemplates {
+    fn default() -> Self {
+        let named = |id: &str, template: &str| NamedTemplate { id: id.to_string(), template: template.to_string() };
+        Self {
+            uniform: "Generate highly specific, unique nouns and noun phrases for '{topic}'. \
+                Each attribute must be a concrete thing, object, component, or feature (e.g., 'steel support beam', 'marble entrance hall', 'control panel'). \
+                Focus on specific parts, components, materials, structures, or distinctive elements that exist within or relate to this topic. \
+                Avoid adjectives, descriptions, and numbers - only generate actual things/nouns using words only. Think like an expert cataloging specific items. \
+                Output one noun/noun phrase per line."
+                .to_string(),
+            per_provider: vec![
+                named("concrete", "Generate highly specific, concrete physical objects and components for '{topic}'. Focus on: materials, parts, structural elements, tools, equipment. Be extremely detailed and specific. Examples: 'reinforced steel beam', 'marble entrance column', 'bronze door handle'. Avoid abstract concepts."),
+                named("creative", "Think creatively and generate unique, unconventional aspects of '{topic}'. Explore unexpected angles, hidden elements, rarely considered components. Push boundaries while staying factual. Examples: 'secret passage mechanism', 'weathering pattern', 'acoustic property'. Be innovative and surprising."),
+                named("technical", "Generate technical, engineering, and specialized components of '{topic}'. Focus on: technical specifications, engineering elements, specialized equipment, precise terminology. Examples: 'load-bearing junction', 'thermal insulation layer', 'electrical conduit'. Use expert-level technical knowledge."),
+            ],
+        }
+    }
+}
+
+impl PromptTemplates {
+    /// Read and validate a template file; `.json` files are parsed as JSON, anything else as TOML
+    pub fn load(path: &Path) -> OrchestratorResult<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            OrchestratorError::config(format!("Failed to read prompt templates {}: {}", path.display(), e))
+        })?;
+        let templates: Self = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&contents).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|e| e.to_string())
+        }
+        .map_err(|e| OrchestratorError::config(format!("Invalid prompt templates {}: {}", path.display(), e)))?;
+
+        templates
+            .validate()
+            .map_err(|e| OrchestratorError::config(format!("Invalid prompt templates {}: {}", path.display(), e)))?;
+        Ok(templates)
+    }
+
+    /// Check that there is at least one per-provider template and that every template names the topic
+    pub fn validate(&self) -> Result<(), String> {
+        if self.per_provider.is_empty() {
+            return Err("per_provider needs at least one template".to_string());
+        }
+        let missing = std::iter::once(("uniform", &self.uniform))
+            .chain(self.per_provider.iter().map(|named| (named.id.as_str(), &named.template)))
+            .find(|(_, template)| !template.contains(TOPIC_PLACEHOLDER));
+        match missing {
+            Some((id, _)) => Err(format!("template '{id}' is missing the {TOPIC_PLACEHOLDER} placeholder")),
+            None => Ok(()),
+        }
+    }
+
+    /// Fill a template in for a topic
+    pub fn render(template: &str, topic: &str) -> String {
+        template.replace(TOPIC_PLACEHOLDER, topic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_templates_are_valid() {
+        let templates = PromptTemplates::default();
+        assert!(templates.validate().is_ok());
+        assert!(PromptTemplates::render(&templates.uniform, "Paris").contains("for 'Paris'"));
+    }
+
+    #[test]
+    fn test_load_reads_toml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let toml_path = dir.path().join("templates.toml");
+        std::fs::write(
+            &toml_path,
+            "uniform = \"List parts of {topic}\"\n[[per_provider]]\nid = \"parts\"\ntemplate = \"Parts of {topic}\"\n",
+        )
+        .unwrap();
+        let templates = PromptTemplates::load(&toml_path).unwrap();
+        assert_eq!(templates.uniform, "List parts of {topic}");
+        assert_eq!(templates.per_provider[0].id, "parts");
+
+        let json_path = dir.path().join("templates.json");
+        std::fs::write(&json_path, r#"{"uniform": "Things in {topic}"}"#).unwrap();
+        let templates = PromptTemplates::load(&json_path).unwrap();
+        assert_eq!(templates.uniform, "Things in {topic}");
+        assert_eq!(templates.per_provider, PromptTemplates::default().per_provider);
+    }
+
+    #[test]
+    fn test_load_rejects_template_without_topic_placeholder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("templates.toml");
+
+        std::fs::write(&path, "[[per_provider]]\nid = \"vague\"\ntemplate = \"List some things\"\n").unwrap();
+        let error = PromptTemplates::load(&path).unwrap_err().to_string();
+        assert!(error.contains("template 'vague' is missing the {topic} placeholder"), "{error}");
+
+        std::fs::write(&path, "per_provider = []\n").unwrap();
+        let error = PromptTemplates::load(&path).unwrap_err().to_string();
+        assert!(error.contains("at least one template"), "{error}");
+    }
+}
@@ -33,4 +33,24 @@ pub trait OptimizerStrategy: Send + Sync {
     /// This should return information about the optimizer's current state
     /// without exposing internal implementation details.
     async fn get_state(&self) -> OptimizerState;
-}
\ No newline at end of file
+}
+
+/// Lets the binary pick an optimizer at runtime
+#[async_trait]
+impl OptimizerStrategy for Box<dyn OptimizerStrategy> {
+    async fn optimize(&self, context: OptimizationContext) -> OrchestratorResult<OptimizationResult> {
+        (**self).optimize(context).await
+    }
+
+    async fn update_performance(&mut self, feedback: PerformanceFeedback) {
+        (**self).update_performance(feedback).await
+    }
+
+    async fn reset(&mut self) {
+        (**self).reset().await
+    }
+
+    async fn get_state(&self) -> OptimizerState {
+        (**self).get_state().await
+    }
+}
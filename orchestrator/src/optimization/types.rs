@@ -4,6 +4,7 @@
 //! keeping them separate from trait definitions and implementations.
 
 use shared::{GenerationConfig, OptimizationMode, ProviderId, RoutingStrategy};
+use shared::messages::webserver::{ExpectedImprovements, OptimizationPlan, PromptPartitioning};
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -223,6 +224,30 @@ impl PromptAssignments {
     pub fn uses_uniform_prompts(&self) -> bool {
         self.producer_specific.is_empty() && self.default_prompt.is_some()
     }
+
+    /// Every prompt partition in provider order, keyed by provider (None for the shared prompt)
+    pub fn partitions(&self) -> Vec<(Option<ProviderId>, String)> {
+        if let Some(default_prompt) = &self.default_prompt {
+            return vec![(None, default_prompt.clone())];
+        }
+        let mut partitions: Vec<_> = self
+            .producer_specific
+            .iter()
+            .map(|(provider_id, assignment)| (Some(*provider_id), assignment.prompt.clone()))
+            .collect();
+        partitions.sort_by_key(|(provider_id, _)| *provider_id);
+        partitions
+    }
+
+    /// Partition for the producer at `index`, cycling through the partitions so each producer keeps its own
+    pub fn partition_for(&self, index: usize) -> (Option<ProviderId>, String) {
+        let mut partitions = self.partitions();
+        if partitions.is_empty() {
+            return (None, "Generate unique attributes for the given topic.".to_string());
+        }
+        let index = index % partitions.len();
+        partitions.swap_remove(index)
+    }
     
     /// Create simple prompt assignments with a default prompt
     pub fn uniform(prompt: String) -> Self {
@@ -239,4 +264,38 @@ impl PromptAssignments {
             producer_specific: assignments,
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<&OptimizationResult> for OptimizationPlan {
+    fn from(result: &OptimizationResult) -> Self {
+        let assessment = &result.assessment;
+        let mut assignment_rationales: Vec<(ProviderId, String)> = result
+            .prompt_assignments
+            .producer_specific
+            .iter()
+            .map(|(provider_id, assignment)| (*provider_id, assignment.rationale.clone()))
+            .collect();
+        assignment_rationales.sort_by_key(|(provider_id, _)| *provider_id);
+
+        Self {
+            strategy_name: assessment.metadata.strategy_name.clone(),
+            rationale: assessment.rationale.clone(),
+            partitioning: if result.prompt_assignments.uses_uniform_prompts() {
+                PromptPartitioning::Uniform
+            } else {
+                PromptPartitioning::PerProvider
+            },
+            assignment_rationales,
+            routing_strategy: result.routing_strategy.clone(),
+            confidence: assessment.confidence,
+            expected_improvements: ExpectedImprovements {
+                uam_change_percent: assessment.expected_impact.uam_change_percent,
+                cost_change_percent: assessment.expected_impact.cost_change_percent,
+                quality_change_percent: assessment.expected_impact.quality_change_percent,
+                time_to_effect_seconds: assessment.expected_impact.time_to_effect_seconds,
+            },
+            techniques_applied: assessment.metadata.techniques_applied.clone(),
+            risk_factors: assessment.metadata.risk_factors.clone(),
+        }
+    }
+}
@@ -3,25 +3,92 @@
 //! This is the primary orchestrator that coordinates between webserver, producers,
 //! and manages the overall system state using dependency injection.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
-use tokio::time::{interval, Duration};
+use tokio::time::{interval, interval_at, Duration};
 
-use shared::messages::webserver::CompletionReason;
+use shared::messages::sync::compress_sync_payload;
+use shared::messages::webserver::{CompletionReason, OptimizationPlan, ProducerLifecycleEvent, StopMode, StopReport};
+use shared::types::{parse_provider_token, parse_weight_token, FormatEntry, RoutingParseError};
 use shared::{
-    logging, process_debug, process_error, process_info, GenerationConstraints, OptimizationMode, OrchestratorCommand,
-    OrchestratorUpdate, ProcessId, ProcessStatus, ProducerUpdate, ProviderId, WebServerRequest,
+    logging, process_debug, process_error, process_info, process_warn, GenerationConstraints, OptimizationMode, OrchestratorCommand,
+    OrchestratorUpdate, ProcessId, ProcessStatus, ProducerUpdate, ProviderId, SharedError, WebServerRequest,
 };
 
 use crate::{
+    core::state::{BatchSummary, Checkpoint, ProducerConfig, RunSummary, BATCH_SUMMARY_FILE, CHECKPOINT_FILE},
     core::OrchestratorState,
-    error::OrchestratorResult,
-    optimization::{OptimizerStrategy, OptimizationContext, OptimizationResult, PerformanceMetrics, OptimizationTargets, RoutingOptions, TrendDirection, PerformanceTrend},
-    traits::{ApiKeySource, Communicator, FileSystem, ProcessManager},
+    error::{OrchestratorError, OrchestratorResult},
+    optimization::{OptimizerStrategy, OptimizationContext, OptimizationResult, PerformanceMetrics, OptimizationTargets, PromptAssignments, RoutingOptions, TrendDirection, PerformanceTrend},
+    traits::{ApiKeySource, AttributeRecord, Communicator, FileSystem, ProcessManager, RestartOutcome},
 };
 
+/// Default free-space floor for the output directory (64 MiB)
+pub const DEFAULT_MIN_FREE_SPACE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Per-topic file holding the persisted uniqueness tracker
+pub const UNIQUENESS_FILE: &str = "uniqueness.bin";
+
+/// Optimizer confidence required before producers are reconfigured
+pub const RECONFIGURE_CONFIDENCE_THRESHOLD: f64 = 0.8;
+
+/// Default minimum time between optimizer-driven reconfigurations
+pub const DEFAULT_RECONFIGURE_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Retry policy for commands sent to producers
+#[derive(Debug, Clone, Copy)]
+pub struct CommandRetryPolicy {
+    /// Total delivery attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled on each subsequent retry
+    pub base_delay: Duration,
+}
+
+impl Default for CommandRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Shortest metrics, health-check or flush interval, so tiny values can't busy-loop the run loop
+pub const MIN_LOOP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the run loop reports metrics, checks process health and flushes output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopIntervals {
+    /// Time between statistics updates sent to the webserver
+    pub metrics: Duration,
+    /// Time between producer and webserver health checks
+    pub health: Duration,
+    /// Time between flushes of buffered output to disk
+    pub flush: Duration,
+}
+
+impl Default for LoopIntervals {
+    fn default() -> Self {
+        Self {
+            metrics: Duration::from_secs(3),
+            health: Duration::from_secs(10),
+            flush: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Topics still to run in a `--topics-file` batch, plus what the finished ones produced
+struct BatchRun {
+    remaining: VecDeque<String>,
+    total: usize,
+    request_size: usize,
+    routing_strategy: Option<String>,
+    routing_config: Option<String>,
+    summaries: Vec<RunSummary>,
+}
+
 /// Main orchestrator that coordinates the entire system
 pub struct Orchestrator<A, C, F, P, O>
 where
@@ -51,6 +118,41 @@ where
     /// WebServer communication address (for spawning webserver)
     webserver_addr: Option<SocketAddr>,
 
+    /// Retry policy for producer-directed commands
+    command_retry: CommandRetryPolicy,
+
+    /// Metrics and health-check intervals of the run loop
+    loop_intervals: LoopIntervals,
+
+    /// Refuse to start generation when the output directory has less free space than this
+    min_free_space_bytes: u64,
+
+    /// CLI mode: stop generation after this many seconds
+    max_runtime_seconds: Option<u64>,
+
+    /// CLI mode: stop once cycle efficiency stays below this percentage for `efficiency_patience` cycles
+    min_efficiency_percent: Option<f64>,
+    efficiency_patience: Option<u32>,
+
+    /// CLI mode: stop once this many unique attributes have been generated
+    target_unique_count: Option<u64>,
+
+    /// Minimum time between optimizer-driven producer reconfigurations
+    reconfigure_cooldown: Duration,
+
+    /// Gzip the bloom filter and seen values in SyncCheck commands
+    compress_sync: bool,
+
+    /// Known attributes counted as seen at every topic start (`--seed-file`)
+    seed_values: Vec<String>,
+
+    /// CLI mode: topics queued after the current one by `--topics-file`
+    batch: Option<BatchRun>,
+
+    /// Signals that the current batch topic finished and the next one should start
+    next_topic_tx: mpsc::Sender<()>,
+    next_topic_rx: mpsc::Receiver<()>,
+
     /// Shutdown signal
     shutdown_tx: mpsc::Sender<()>,
     shutdown_rx: mpsc::Receiver<()>,
@@ -68,6 +170,7 @@ where
     pub fn new(api_keys: A, communicator: C, file_system: F, process_manager: P, optimizer: O) -> Self {
         let state = Arc::new(Mutex::new(OrchestratorState::new()));
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let (next_topic_tx, next_topic_rx) = mpsc::channel(1);
 
         Self {
             state,
@@ -80,11 +183,120 @@ where
             producer_rx: None,
             producer_addr: None,
             webserver_addr: None,
+            command_retry: CommandRetryPolicy::default(),
+            loop_intervals: LoopIntervals::default(),
+            min_free_space_bytes: DEFAULT_MIN_FREE_SPACE_BYTES,
+            max_runtime_seconds: None,
+            min_efficiency_percent: None,
+            efficiency_patience: None,
+            target_unique_count: None,
+            reconfigure_cooldown: DEFAULT_RECONFIGURE_COOLDOWN,
+            compress_sync: false,
+            seed_values: Vec::new(),
+            batch: None,
+            next_topic_tx,
+            next_topic_rx,
             shutdown_tx,
             shutdown_rx,
         }
     }
 
+    /// Override the retry policy used for producer-directed commands
+    pub fn with_command_retry(mut self, policy: CommandRetryPolicy) -> Self {
+        self.command_retry = policy;
+        self
+    }
+
+    /// Override the run loop's intervals, raised to at least `MIN_LOOP_INTERVAL`
+    pub fn with_loop_intervals(mut self, intervals: LoopIntervals) -> Self {
+        self.loop_intervals = LoopIntervals {
+            metrics: intervals.metrics.max(MIN_LOOP_INTERVAL),
+            health: intervals.health.max(MIN_LOOP_INTERVAL),
+            flush: intervals.flush.max(MIN_LOOP_INTERVAL),
+        };
+        self
+    }
+
+    /// Minimum free space required in the output directory before generation starts (0 disables)
+    pub fn with_min_free_space(mut self, bytes: u64) -> Self {
+        self.min_free_space_bytes = bytes;
+        self
+    }
+
+    /// Wall-clock limit for CLI generation runs
+    pub fn with_max_runtime(mut self, seconds: Option<u64>) -> Self {
+        self.max_runtime_seconds = seconds;
+        self
+    }
+
+    /// Efficiency floor for CLI generation runs
+    ///
+    /// Generation stops once cycle efficiency stays below `min_percent` for
+    /// `patience` consecutive cycles (`DEFAULT_EFFICIENCY_PATIENCE` if None).
+    pub fn with_efficiency_floor(mut self, min_percent: Option<f64>, patience: Option<u32>) -> Self {
+        self.min_efficiency_percent = min_percent;
+        self.efficiency_patience = patience;
+        self
+    }
+
+    /// Unique attribute count at which CLI generation stops
+    pub fn with_target_count(mut self, target: Option<u64>) -> Self {
+        self.target_unique_count = target;
+        self
+    }
+
+    /// Gzip the bloom filter and seen values carried by SyncCheck commands
+    pub fn with_compress_sync(mut self, compress: bool) -> Self {
+        self.compress_sync = compress;
+        self
+    }
+
+    /// Treat these known attributes as already generated for every topic
+    pub fn with_seed_values(mut self, values: Vec<String>) -> Self {
+        self.seed_values = values;
+        self
+    }
+
+    /// Build a SyncCheck carrying the current bloom filter and seen values
+    pub async fn sync_check_command(&self, sync_id: u64) -> OrchestratorResult<OrchestratorCommand> {
+        let (bloom_filter, seen_values, bloom_version, requires_dedup) = {
+            let state = self.state.lock().await;
+            (
+                state.get_bloom_filter_data(),
+                state.get_seen_values(),
+                state.get_bloom_version(),
+                state.context.requires_bloom_filter,
+            )
+        };
+
+        let (bloom_filter, seen_values, compressed_seen_values) = if self.compress_sync {
+            let payload = compress_sync_payload(bloom_filter.as_deref(), seen_values.as_deref())
+                .map_err(|e| OrchestratorError::communication(format!("Failed to compress sync payload: {e}")))?;
+            process_debug!(
+                ProcessId::current(),
+                "🗜️ Compressed sync {}: {} -> {} bytes (ratio {:.2})",
+                sync_id,
+                payload.raw_bytes,
+                payload.compressed_bytes,
+                payload.ratio()
+            );
+            (payload.bloom_filter, None, payload.seen_values)
+        } else {
+            (bloom_filter, seen_values, None)
+        };
+
+        Ok(OrchestratorCommand::SyncCheck {
+            sync_id,
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            bloom_filter,
+            bloom_version: Some(bloom_version),
+            requires_dedup,
+            seen_values,
+            compressed: self.compress_sync,
+            compressed_seen_values,
+        })
+    }
+
     /// Initialize the orchestrator and start listening for messages
     pub async fn initialize(
         &mut self,
@@ -165,7 +377,11 @@ where
 
     /// Parse routing strategy and config from string parameters
     /// New format supports models: "openai:gpt-4o-mini" or "openai:gpt-4o-mini,anthropic:claude-3-sonnet"
-    fn parse_routing_strategy_and_config(&self, strategy: &str, config: &str) -> Result<shared::RoutingStrategy, String> {
+    fn parse_routing_strategy_and_config(
+        &self,
+        strategy: &str,
+        config: &str,
+    ) -> Result<shared::RoutingStrategy, RoutingParseError> {
         match strategy.to_lowercase().as_str() {
             "backoff" => {
                 // Config format: "openai" or "openai:gpt-4o-mini"
@@ -187,54 +403,55 @@ where
                 let weights = self.parse_weighted_provider_config(config)?;
                 Ok(shared::RoutingStrategy::Weighted { weights })
             }
-            _ => Err(format!("Unknown routing strategy '{}'", strategy)),
+            "circuitbreaker" => {
+                // Config format: same as roundrobin; breaker tuning uses the shared defaults
+                let providers = self.parse_provider_config_list(config)?;
+                Ok(shared::RoutingStrategy::CircuitBreaker {
+                    providers,
+                    failure_threshold: shared::types::DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+                    cooldown_seconds: shared::types::DEFAULT_CIRCUIT_COOLDOWN_SECONDS,
+                })
+            }
+            "lowestlatency" => {
+                // Config format: same as roundrobin; latency is averaged over the shared default window
+                let providers = self.parse_provider_config_list(config)?;
+                Ok(shared::RoutingStrategy::LowestLatency {
+                    providers,
+                    window_seconds: shared::types::DEFAULT_LATENCY_WINDOW_SECONDS,
+                })
+            }
+            _ => Err(RoutingParseError::UnknownStrategy { strategy: strategy.to_string() }),
         }
     }
 
     /// Parse a single provider config from string (supports "provider" or "provider:model")
-    fn parse_provider_config(&self, provider_str: &str) -> Result<shared::types::ProviderConfig, String> {
-        let parts: Vec<&str> = provider_str.split(':').collect();
-        
-        let provider = match parts[0].to_lowercase().as_str() {
-            "openai" => shared::ProviderId::OpenAI,
-            "anthropic" => shared::ProviderId::Anthropic,
-            "gemini" => shared::ProviderId::Gemini,
-            "random" => shared::ProviderId::Random,
-            _ => return Err(format!("Unknown provider '{}'", parts[0])),
-        };
-        
-        let model = if parts.len() >= 2 {
-            parts[1].to_string()
-        } else {
+    fn parse_provider_config(&self, provider_str: &str) -> Result<shared::types::ProviderConfig, RoutingParseError> {
+        match provider_str.split_once(':') {
+            // The model may name fallbacks: "openai:gpt-4o|gpt-4o-mini"
+            Some((provider, model)) => {
+                shared::types::ProviderConfig::try_from_model_spec(parse_provider_token(provider)?, model.trim())
+            }
             // Use default model for provider
-            shared::types::ProviderConfig::with_default_model(provider).model
-        };
-        
-        Ok(shared::types::ProviderConfig::new(provider, model))
-    }
-    
-    /// Parse a single provider ID from string (legacy support)
-    fn parse_provider_id(&self, provider: &str) -> Result<shared::ProviderId, String> {
-        match provider.to_lowercase().as_str() {
-            "openai" => Ok(shared::ProviderId::OpenAI),
-            "anthropic" => Ok(shared::ProviderId::Anthropic),
-            "gemini" => Ok(shared::ProviderId::Gemini),
-            "random" => Ok(shared::ProviderId::Random),
-            _ => Err(format!("Unknown provider '{}'", provider)),
+            None => Ok(shared::types::ProviderConfig::with_default_model(parse_provider_token(provider_str)?)),
         }
     }
 
     /// Parse a list of provider configs from "provider1,provider2,provider3" or "provider1:model1,provider2:model2" format
-    fn parse_provider_config_list(&self, config: &str) -> Result<Vec<shared::types::ProviderConfig>, String> {
+    fn parse_provider_config_list(
+        &self,
+        config: &str,
+    ) -> Result<Vec<shared::types::ProviderConfig>, RoutingParseError> {
         config
             .split(',')
             .map(|p| self.parse_provider_config(p.trim()))
             .collect()
     }
-    
 
     /// Parse weighted provider config from "provider1:model1:weight1,provider2:model2:weight2" or "provider1:weight1,provider2:weight2" format
-    fn parse_weighted_provider_config(&self, config: &str) -> Result<HashMap<shared::types::ProviderConfig, f32>, String> {
+    fn parse_weighted_provider_config(
+        &self,
+        config: &str,
+    ) -> Result<HashMap<shared::types::ProviderConfig, f32>, RoutingParseError> {
         let mut weights = HashMap::new();
         
         for pair in config.split(',') {
@@ -243,33 +460,63 @@ where
             let (provider_config, weight) = match parts.len() {
                 2 => {
                     // Format: "provider:weight" (use default model)
-                    let provider_id = self.parse_provider_id(parts[0].trim())?;
+                    let provider_id = parse_provider_token(parts[0])?;
                     let provider_config = shared::types::ProviderConfig::with_default_model(provider_id);
-                    let weight: f32 = parts[1].trim().parse()
-                        .map_err(|_| format!("Invalid weight '{}', must be a number", parts[1]))?;
-                    (provider_config, weight)
+                    (provider_config, parse_weight_token(parts[1])?)
                 }
                 3 => {
                     // Format: "provider:model:weight"
-                    let provider_id = self.parse_provider_id(parts[0].trim())?;
-                    let model = parts[1].trim().to_string();
-                    let provider_config = shared::types::ProviderConfig::new(provider_id, model);
-                    let weight: f32 = parts[2].trim().parse()
-                        .map_err(|_| format!("Invalid weight '{}', must be a number", parts[2]))?;
-                    (provider_config, weight)
+                    let provider_id = parse_provider_token(parts[0])?;
+                    let provider_config =
+                        shared::types::ProviderConfig::try_from_model_spec(provider_id, parts[1].trim())?;
+                    (provider_config, parse_weight_token(parts[2])?)
                 }
                 _ => {
-                    return Err(format!("Invalid weighted config format '{}', expected 'provider:weight' or 'provider:model:weight'", pair));
+                    return Err(RoutingParseError::InvalidFormat {
+                        entry: FormatEntry::ProviderModelWeight,
+                        token: pair.to_string(),
+                    });
                 }
             };
             
             weights.insert(provider_config, weight);
         }
         
+        shared::types::normalize_weight_map(&mut weights)?;
         Ok(weights)
     }
-    
 
+    /// Fix the run-level RNG seed so every randomized decision in the run is reproducible
+    pub async fn set_run_seed(&mut self, seed: u64) {
+        let mut state = self.state.lock().await;
+        state.set_run_seed(seed);
+        process_info!(ProcessId::current(), "🎲 Using run seed {}", seed);
+    }
+
+    /// Choose how attributes are normalized before deduplication
+    pub async fn set_normalization_mode(&mut self, mode: crate::core::NormalizationMode) {
+        let mut state = self.state.lock().await;
+        state.set_normalization_mode(mode);
+        process_info!(ProcessId::current(), "🔤 Deduplication normalization: {}", mode);
+    }
+
+    /// Half-life of the performance data the optimizer routes on (None or zero disables decay)
+    pub async fn set_performance_half_life(&mut self, half_life: Option<Duration>) {
+        self.state.lock().await.set_performance_half_life(half_life);
+    }
+
+    /// Weight of the newest sample in the smoothed UAM and cost per minute sent to the dashboard
+    pub async fn set_metrics_smoothing(&mut self, factor: f64) {
+        self.state.lock().await.set_metrics_smoothing(factor);
+    }
+
+    /// Keep every generated attribute, duplicates included
+    pub async fn set_dedup_enabled(&mut self, enabled: bool) {
+        self.state.lock().await.set_dedup_enabled(enabled);
+        if !enabled {
+            process_info!(ProcessId::current(), "🔁 Deduplication disabled: every generated attribute is kept");
+        }
+    }
 
     /// Set the Orchestrator's default routing strategy from args/env
     pub async fn set_default_routing_strategy(&mut self, routing_strategy: Option<String>, routing_provider: Option<String>) -> OrchestratorResult<()> {
@@ -312,30 +559,77 @@ where
         Ok(())
     }
 
+    /// Continue a crashed CLI run from the topic's checkpoint
+    ///
+    /// Returns the number of iterations the earlier run completed, or 0 when
+    /// the topic has no checkpoint. Must be called before `start_cli_generation`.
+    pub async fn resume_from_checkpoint(&mut self, topic: &str) -> OrchestratorResult<u32> {
+        let path = self.file_system.topic_file_path(topic, CHECKPOINT_FILE);
+        let Some(checkpoint) = Checkpoint::load(&self.file_system, topic).await? else {
+            process_warn!(ProcessId::current(), "⚠️ No checkpoint found at {}, starting from iteration 0", path.display());
+            return Ok(0);
+        };
+
+        let completed = checkpoint.iteration;
+        self.state.lock().await.restore_checkpoint(checkpoint);
+        process_info!(ProcessId::current(), "♻️ Resuming topic '{}' after {} completed iterations", topic, completed);
+        Ok(completed)
+    }
+
+    /// Minimum time between optimizer-driven producer reconfigurations
+    pub fn with_reconfigure_cooldown(mut self, cooldown: Duration) -> Self {
+        self.reconfigure_cooldown = cooldown;
+        self
+    }
+
+    /// Queue topics to run one after another once the first CLI topic completes
+    ///
+    /// Later topics reuse the producers spawned for the first one and take the
+    /// same request size and routing parameters.
+    pub fn queue_batch_topics(
+        &mut self,
+        topics: Vec<String>,
+        request_size: usize,
+        routing_strategy: Option<String>,
+        routing_config: Option<String>,
+    ) {
+        self.batch = Some(BatchRun {
+            total: topics.len() + 1,
+            remaining: topics.into(),
+            request_size,
+            routing_strategy,
+            routing_config,
+            summaries: Vec::new(),
+        });
+    }
+
     /// Start generation immediately for CLI mode
+    #[allow(clippy::too_many_arguments)]
     pub async fn start_cli_generation(
         &mut self,
         topic: String,
         producer_count: u32,
         iterations: Option<u32>,
         request_size: usize,
+        optimization_mode: OptimizationMode,
         routing_strategy: Option<String>,
         routing_config: Option<String>,
     ) -> OrchestratorResult<()> {
-        process_debug!(ProcessId::current(), "🚀 Starting CLI generation");
+        process_debug!(ProcessId::current(), "🚀 Starting CLI generation with {:?}", optimization_mode);
 
-        // Use default optimization mode and constraints for CLI
-        let optimization_mode = OptimizationMode::MaximizeEfficiency;
-        let constraints = GenerationConstraints {
-            max_cost_per_minute: 1.0,
-            target_uam: 100.0,
-            max_runtime_seconds: None,
-        };
+        let constraints = Self::constraints_for_mode(self.cli_constraints(), &optimization_mode);
 
         // Store iterations limit in state for tracking
         {
             let mut state = self.state.lock().await;
             state.set_cli_iterations(iterations);
+            if state.is_resumed() && state.should_stop_iterations() {
+                return Err(OrchestratorError::config(format!(
+                    "Topic '{}' already completed {} iterations",
+                    topic,
+                    state.get_current_iteration()
+                )));
+            }
         }
 
         // Log topic start with iteration budget
@@ -352,11 +646,258 @@ where
         Ok(())
     }
 
+    /// Start generating every topic at once, splitting the producers between them
+    ///
+    /// Producer `i` works on topic `i mod n`, so there must be at least one producer
+    /// per topic. Each topic keeps its own uniqueness set and output directory, and
+    /// producers report batches under their topic. Prompt optimization, checkpoints,
+    /// run summaries and the runtime limit apply to single-topic runs only.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_concurrent_generation(
+        &mut self,
+        topics: Vec<String>,
+        producer_count: u32,
+        iterations: Option<u32>,
+        request_size: usize,
+        optimization_mode: OptimizationMode,
+        routing_strategy: Option<String>,
+        routing_config: Option<String>,
+    ) -> OrchestratorResult<()> {
+        if topics.is_empty() || producer_count < topics.len() as u32 {
+            return Err(OrchestratorError::config(format!(
+                "Concurrent topics need at least one producer per topic ({} topics, {} producers)",
+                topics.len(),
+                producer_count
+            )));
+        }
+
+        self.validate_output_dir().await?;
+        for topic in &topics {
+            self.file_system.create_topic_directory(topic).await?;
+        }
+
+        let assignments: Vec<(String, Vec<ProcessId>)> = topics
+            .iter()
+            .enumerate()
+            .map(|(index, topic)| {
+                let producers = (0..producer_count)
+                    .filter(|i| *i as usize % topics.len() == index)
+                    .map(|i| ProcessId::Producer(i + 1))
+                    .collect();
+                (topic.clone(), producers)
+            })
+            .collect();
+
+        let constraints = Self::constraints_for_mode(self.cli_constraints(), &optimization_mode);
+        let resolved_routing_strategy = self
+            .resolve_routing_strategy(
+                routing_strategy,
+                routing_config,
+                &shared::RoutingStrategy::Weighted { weights: HashMap::new() },
+            )
+            .await;
+        {
+            let mut state = self.state.lock().await;
+            state.set_cli_iterations(iterations);
+            state.start_concurrent_generation(assignments.clone(), optimization_mode, constraints);
+            state.context.routing_strategy = resolved_routing_strategy;
+            for i in 0..producer_count {
+                state.add_producer(ProcessId::Producer(i + 1), i + 1, shared::ProcessStatus::Starting);
+            }
+        }
+
+        let budget_str = match iterations {
+            Some(limit) => format!("with {limit} iteration budget"),
+            None => "with no iteration budget".to_string(),
+        };
+        let api_keys = self.api_keys.get_api_keys().await?;
+        let mut start_commands = Vec::new();
+        for (topic, producers) in &assignments {
+            process_info!(
+                ProcessId::current(),
+                "✅ Topic '{}' started {} on {} producers",
+                topic,
+                budget_str,
+                producers.len()
+            );
+            let (prompt_assignments, routing_strategy, generation_config) = self
+                .plan_initial_config(topic, producers.len() as u32, request_size, &api_keys)
+                .await?;
+            for (position, producer_id) in producers.iter().enumerate() {
+                let (category, prompt) = prompt_assignments.partition_for(position);
+                start_commands.push((
+                    producer_id.clone(),
+                    topic.clone(),
+                    prompt,
+                    category,
+                    routing_strategy.clone(),
+                    generation_config.clone(),
+                ));
+            }
+        }
+
+        let producer_addr = self.producer_addr.expect("Producer address not initialized");
+        let spawn_routing_strategy =
+            start_commands.first().map(|(_, _, _, _, routing_strategy, _)| routing_strategy.clone());
+        let producer_infos = self
+            .process_manager
+            .spawn_producers(producer_count, None, api_keys, producer_addr, spawn_routing_strategy)
+            .await?;
+        if producer_infos.is_empty() {
+            return Err(self.abort_without_producers(producer_count).await);
+        }
+        for info in &producer_infos {
+            self.communicator.register_producer(info.id.clone(), info.command_address).await?;
+        }
+
+        // Each producer starts on its own topic once it reports ready
+        let mut state = self.state.lock().await;
+        for (producer_id, topic, prompt, category, routing_strategy, generation_config) in start_commands {
+            let command = OrchestratorCommand::Start {
+                command_id: 1,
+                topic,
+                prompt,
+                routing_strategy,
+                generation_config,
+                seed: state.run_seed(),
+                max_cost_per_minute: state.producer_cost_budget(),
+                dedup: state.context.dedup_enabled,
+                category: category.map(|provider| provider.to_string()),
+            };
+            state.queue_start_command(producer_id, command);
+        }
+
+        Ok(())
+    }
+
+    /// Default generation constraints for CLI runs
+    fn cli_constraints(&self) -> GenerationConstraints {
+        GenerationConstraints {
+            max_cost_per_minute: 1.0,
+            target_uam: 100.0,
+            max_runtime_seconds: self.max_runtime_seconds,
+            min_efficiency_percent: self.min_efficiency_percent,
+            efficiency_patience: self.efficiency_patience,
+            target_unique_count: self.target_unique_count,
+        }
+    }
+
+    /// Take the budget or UAM target carried by the optimization mode as the matching constraint
+    fn constraints_for_mode(mut constraints: GenerationConstraints, mode: &OptimizationMode) -> GenerationConstraints {
+        match mode {
+            OptimizationMode::MaximizeUAM { budget_per_minute } => constraints.max_cost_per_minute = *budget_per_minute,
+            OptimizationMode::MinimizeCost { target_uam } => constraints.target_uam = *target_uam,
+            OptimizationMode::MaximizeEfficiency | OptimizationMode::Weighted { .. } => {}
+        }
+        constraints
+    }
+
+    /// Switch the running producers over to the next topic of a batch
+    async fn start_next_batch_topic(&mut self) -> OrchestratorResult<()> {
+        let Some(batch) = self.batch.as_mut() else {
+            return Ok(());
+        };
+        let Some(topic) = batch.remaining.pop_front() else {
+            return Ok(());
+        };
+        let position = batch.total - batch.remaining.len();
+        let total = batch.total;
+        let request_size = batch.request_size;
+        let routing_strategy_override = batch.routing_strategy.clone();
+        let routing_config_override = batch.routing_config.clone();
+
+        // Hold producers while the state is swapped to the new topic
+        let command_id = chrono::Utc::now().timestamp_millis() as u64;
+        self.send_to_active_producers(OrchestratorCommand::Pause { command_id }).await;
+
+        let finished = {
+            let mut state = self.state.lock().await;
+            let reason = state.completion_reason().cloned().unwrap_or(CompletionReason::IterationLimitReached);
+            let summary = state.run_summary(reason);
+            state.reset_for_next_topic();
+            summary
+        };
+        if let (Some(batch), Some(summary)) = (self.batch.as_mut(), finished) {
+            batch.summaries.push(summary);
+        }
+
+        process_info!(ProcessId::current(), "📦 Batch topic {}/{}: '{}'", position, total, topic);
+
+        self.prepare_topic_output(&topic).await?;
+
+        let resolved_routing_strategy = self
+            .resolve_routing_strategy(
+                routing_strategy_override,
+                routing_config_override,
+                &shared::RoutingStrategy::Weighted { weights: HashMap::new() },
+            )
+            .await;
+        let (producer_ids, budget_str) = {
+            let mut state = self.state.lock().await;
+            // Later topics keep the optimization mode chosen for the first one
+            let optimization_mode = state.context.optimization_targets.optimization_mode.clone();
+            let constraints = Self::constraints_for_mode(self.cli_constraints(), &optimization_mode);
+            state.start_generation(topic.clone(), optimization_mode, constraints);
+            state.context.routing_strategy = resolved_routing_strategy;
+            let budget_str = match state.get_cli_iterations() {
+                Some(limit) => format!("with {limit} iteration budget"),
+                None => "with no iteration budget".to_string(),
+            };
+            (state.active_producer_ids(), budget_str)
+        };
+        process_info!(ProcessId::current(), "✅ Topic '{}' started {}", topic, budget_str);
+
+        let api_keys = self.api_keys.get_api_keys().await?;
+        let (prompt_assignments, routing_strategy, generation_config) = self
+            .plan_initial_config(&topic, producer_ids.len() as u32, request_size, &api_keys)
+            .await?;
+        self.export_prompts("start", &prompt_assignments.partitions()).await;
+
+        // Paused producers take a fresh Start, which carries the new topic and resumes them;
+        // producers still waiting to become ready start straight on the new topic
+        let mut running = Vec::new();
+        {
+            let mut state = self.state.lock().await;
+            let command_id = chrono::Utc::now().timestamp_millis() as u64;
+            for producer_id in producer_ids {
+                let (category, prompt) = prompt_assignments.partition_for(Self::producer_index(&producer_id));
+                let start = OrchestratorCommand::Start {
+                    command_id,
+                    topic: topic.clone(),
+                    prompt,
+                    routing_strategy: routing_strategy.clone(),
+                    generation_config: generation_config.clone(),
+                    seed: state.run_seed(),
+                    max_cost_per_minute: state.producer_cost_budget(),
+                    dedup: state.context.dedup_enabled,
+                    category: category.map(|provider| provider.to_string()),
+                };
+                if state.take_pending_start_command(&producer_id).is_some() {
+                    state.queue_start_command(producer_id, start);
+                } else {
+                    running.push((producer_id, start));
+                }
+            }
+        }
+
+        for (producer_id, start) in running {
+            if let Err(e) = self.send_producer_command_with_retry(producer_id.clone(), start).await {
+                process_error!(ProcessId::current(), "❌ Failed to move producer {} to topic '{}': {}", producer_id, topic, e);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Main event loop - processes messages and coordinates the system
     pub async fn run(&mut self) -> OrchestratorResult<()> {
-        let mut metrics_interval = interval(Duration::from_secs(3));
-        let mut health_interval = interval(Duration::from_secs(10));
+        let mut metrics_interval = interval(self.loop_intervals.metrics);
+        let mut health_interval = interval(self.loop_intervals.health);
         let mut optimization_interval = interval(Duration::from_secs(15)); // Optimization every 15s
+        let mut runtime_interval = interval(Duration::from_secs(1));
+        // Nothing is buffered yet, so the first flush waits a full interval
+        let flush_period = self.loop_intervals.flush;
+        let mut flush_interval = interval_at(tokio::time::Instant::now() + flush_period, flush_period);
 
         loop {
             tokio::select! {
@@ -408,6 +949,30 @@ where
                     if let Err(e) = self.optimize_and_sync().await {
                         process_error!(ProcessId::current(), "⚠️ Error during optimization: {}", e);
                     }
+                    if let Err(e) = self.sync_producers().await {
+                        process_error!(ProcessId::current(), "⚠️ Error syncing producers: {}", e);
+                    }
+                },
+
+                // Write buffered output and fsync it, independent of iteration boundaries
+                _ = flush_interval.tick() => {
+                    if let Err(e) = self.flush_and_checkpoint().await {
+                        process_error!(ProcessId::current(), "⚠️ Error flushing output to disk: {}", e);
+                    }
+                },
+
+                // Wall-clock limit from GenerationConstraints
+                _ = runtime_interval.tick() => {
+                    self.check_runtime_limit().await;
+                },
+
+                // Current batch topic finished, move on to the next one
+                Some(_) = self.next_topic_rx.recv() => {
+                    if let Err(e) = self.start_next_batch_topic().await {
+                        process_error!(ProcessId::current(), "❌ Failed to start next batch topic: {}", e);
+                        self.shutdown().await?;
+                        break;
+                    }
                 },
 
                 // Shutdown signal
@@ -445,10 +1010,32 @@ where
                     .await
             }
 
-            WebServerRequest::StopGeneration { request_id } => self.stop_generation(request_id).await,
+            WebServerRequest::StopGeneration { request_id, mode } => match mode {
+                StopMode::Immediate => self.stop_generation(request_id).await,
+                StopMode::AfterCurrentIteration => self.stop_after_current_iteration(request_id).await,
+            },
+
+            WebServerRequest::PauseGeneration { request_id } => {
+                let command_id = chrono::Utc::now().timestamp_millis() as u64;
+                self.broadcast_to_producers(request_id, OrchestratorCommand::Pause { command_id }, "paused")
+                    .await
+            }
+
+            WebServerRequest::ResumeGeneration { request_id } => {
+                let command_id = chrono::Utc::now().timestamp_millis() as u64;
+                self.broadcast_to_producers(request_id, OrchestratorCommand::Resume { command_id }, "resumed")
+                    .await
+            }
 
             WebServerRequest::GetStatus { request_id } => self.send_status_update(request_id).await,
 
+            WebServerRequest::GetOptimizationPlan { request_id } => {
+                let plan = self.state.lock().await.optimization_plan().cloned();
+                self.communicator
+                    .send_webserver_update(OrchestratorUpdate::OptimizationPlan { request_id, plan })
+                    .await
+            }
+
             WebServerRequest::UpdateConfig {
                 request_id,
                 optimization_mode,
@@ -479,7 +1066,25 @@ where
     }
 
     /// Handle producer ready signal
-    async fn handle_producer_ready(&self, producer_id: ProcessId, listen_port: u16) -> OrchestratorResult<()> {
+    async fn handle_producer_ready(
+        &self,
+        producer_id: ProcessId,
+        listen_port: u16,
+        protocol_version: u32,
+    ) -> OrchestratorResult<()> {
+        // Refuse producers built against a different IPC format before anything is sent to them
+        if protocol_version != shared::PROTOCOL_VERSION {
+            process_error!(
+                ProcessId::current(),
+                "❌ Refusing producer {} on port {}: protocol version {} is incompatible with orchestrator version {}",
+                producer_id,
+                listen_port,
+                protocol_version,
+                shared::PROTOCOL_VERSION
+            );
+            return Ok(());
+        }
+
         let producer_addr = SocketAddr::from(([127, 0, 0, 1], listen_port));
 
         // Mark producer as ready in communicator
@@ -499,6 +1104,14 @@ where
             producer_id,
             listen_port
         );
+        // Idle warm-pool producers are not part of the pool the dashboard shows until promoted
+        let stable_id = {
+            let state = self.state.lock().await;
+            state.has_producer(&producer_id).then(|| state.stable_producer_id(&producer_id))
+        };
+        if let Some(stable_id) = stable_id {
+            self.notify_producer_lifecycle(stable_id, ProducerLifecycleEvent::Started).await;
+        }
 
         // Check if there's a pending start command for this producer
         let pending_command = {
@@ -512,22 +1125,80 @@ where
                 "📤 Sending queued start command to producer {}",
                 producer_id
             );
-            self.communicator.send_producer_command(producer_id, command).await?;
+            self.send_producer_command_with_retry(producer_id, command).await?;
         }
 
         Ok(())
     }
 
-    /// Handle updates from producers
-    async fn handle_producer_update(&self, update: ProducerUpdate) -> OrchestratorResult<()> {
-        match update {
-            ProducerUpdate::AttributeBatch {
-                producer_id,
+    /// Send a command to a producer, retrying with exponential backoff.
+    ///
+    /// If every attempt fails the producer is considered unreachable: it is marked
+    /// failed and stopped so the next health check replaces it.
+    async fn send_producer_command_with_retry(
+        &self,
+        producer_id: ProcessId,
+        command: OrchestratorCommand,
+    ) -> OrchestratorResult<()> {
+        let max_attempts = self.command_retry.max_attempts.max(1);
+        let mut delay = self.command_retry.base_delay;
+        let mut attempt = 1;
+
+        loop {
+            match self
+                .communicator
+                .send_producer_command(producer_id.clone(), command.clone())
+                .await
+            {
+                Ok(()) => {
+                    self.state.lock().await.record_delivered_command(&producer_id, &command);
+                    return Ok(());
+                }
+                Err(e) if attempt < max_attempts => {
+                    process_warn!(
+                        ProcessId::current(),
+                        "⚠️ Command to producer {} failed (attempt {}/{}): {} - retrying in {}ms",
+                        producer_id,
+                        attempt,
+                        max_attempts,
+                        e,
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    process_error!(
+                        ProcessId::current(),
+                        "❌ Giving up on producer {} after {} attempts: {} - marking as failed",
+                        producer_id,
+                        max_attempts,
+                        e
+                    );
+                    {
+                        let mut state = self.state.lock().await;
+                        state.mark_producer_failed(producer_id.clone());
+                    }
+                    let _ = self.process_manager.stop_producer(producer_id).await;
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Handle updates from producers
+    async fn handle_producer_update(&self, update: ProducerUpdate) -> OrchestratorResult<()> {
+        match update {
+            ProducerUpdate::AttributeBatch {
+                producer_id,
                 batch_id: _,
                 attributes,
                 provider_metadata,
+                estimated_cost,
+                topic,
             } => {
-                self.process_attribute_batch(producer_id, attributes, provider_metadata)
+                self.process_attribute_batch(producer_id, &topic, attributes, provider_metadata, estimated_cost)
                     .await
             }
 
@@ -545,8 +1216,16 @@ where
                 producer_id,
                 status,
                 message: _,
-                performance_stats: _,
-            } => self.update_producer_status(producer_id, status).await,
+                performance_stats,
+            } => {
+                if let Some(stats) = performance_stats {
+                    let mut state = self.state.lock().await;
+                    state.record_tuned_limits(producer_id.clone(), stats.tuned_limits);
+                    state.record_provider_usage(producer_id.clone(), stats.provider_usage);
+                    state.record_verification(producer_id.clone(), stats.verification);
+                }
+                self.update_producer_status(producer_id, status).await
+            }
 
             ProducerUpdate::Pong {
                 producer_id,
@@ -563,11 +1242,16 @@ where
             ProducerUpdate::Ready {
                 producer_id,
                 listen_port,
-            } => self.handle_producer_ready(producer_id, listen_port).await,
+                protocol_version,
+            } => {
+                self.handle_producer_ready(producer_id, listen_port, protocol_version)
+                    .await
+            }
         }
     }
 
     /// Start generation process with custom request size
+    #[allow(clippy::too_many_arguments)]
     async fn start_generation_with_config(
         &self,
         _request_id: u64,
@@ -579,6 +1263,8 @@ where
         routing_strategy_override: Option<String>,
         routing_config_override: Option<String>,
     ) -> OrchestratorResult<()> {
+        Self::validate_producer_count(producer_count)?;
+
         // Get iteration budget from state and log topic start consistently
         let budget_str = {
             let state = self.state.lock().await;
@@ -589,8 +1275,8 @@ where
         };
         process_info!(ProcessId::current(), "✅ Topic '{}' started {}", topic, budget_str);
 
-        // Create topic directory
-        self.file_system.create_topic_directory(&topic).await?;
+        // Fail fast on an unusable output directory, before any producer is spawned
+        self.prepare_topic_output(&topic).await?;
 
         // Get API keys
         let api_keys = self.api_keys.get_api_keys().await?;
@@ -618,8 +1304,116 @@ where
             process_debug!(ProcessId::current(), "🏭 Initialized {} producer slots in state", producer_count);
         }
 
-        // Generate initial prompt and configuration with custom request_size
-        let (prompt, mut routing_strategy, mut generation_config) = {
+        let (prompt_assignments, routing_strategy, generation_config) =
+            self.plan_initial_config(&topic, producer_count, request_size, &api_keys).await?;
+
+        self.export_prompts("start", &prompt_assignments.partitions()).await;
+
+        // Spawn producers with the finalized routing strategy
+        let producer_addr = self.producer_addr.expect("Producer address not initialized");
+        let producer_infos = self
+            .process_manager
+            .spawn_producers(producer_count, Some(topic.clone()), api_keys.clone(), producer_addr, Some(routing_strategy.clone()))
+            .await?;
+        if producer_infos.is_empty() {
+            return Err(self.abort_without_producers(producer_count).await);
+        }
+
+        // Register producers with communicator
+        for info in &producer_infos {
+            self.communicator
+                .register_producer(info.id.clone(), info.command_address)
+                .await?;
+        }
+
+        // Note: Producers will send Ready signals when their IPC listeners are initialized
+        // No more artificial delays needed!
+
+        // Queue start commands for all producers (will be sent when they become ready)
+        {
+            let mut state = self.state.lock().await;
+            for info in &producer_infos {
+                let (category, prompt) = prompt_assignments.partition_for(Self::producer_index(&info.id));
+                let command = OrchestratorCommand::Start {
+                    command_id: 1,
+                    topic: topic.clone(),
+                    prompt,
+                    routing_strategy: routing_strategy.clone(),
+                    generation_config: generation_config.clone(),
+                    seed: state.run_seed(),
+                    max_cost_per_minute: state.producer_cost_budget(),
+                    dedup: state.context.dedup_enabled,
+                    category: category.map(|provider| provider.to_string()),
+                };
+
+                state.queue_start_command(info.id.clone(), command);
+            }
+        }
+
+        process_debug!(ProcessId::current(), "✅ Generation started successfully");
+        Ok(())
+    }
+
+    /// Reject starts that would leave the orchestrator waiting on no producers
+    fn validate_producer_count(producer_count: u32) -> OrchestratorResult<()> {
+        if producer_count == 0 {
+            return Err(OrchestratorError::config("At least one producer is required to start generation"));
+        }
+        Ok(())
+    }
+
+    /// Report that no producer could be spawned and shut down instead of waiting for batches
+    async fn abort_without_producers(&self, producer_count: u32) -> OrchestratorError {
+        let message = format!("All {producer_count} producers failed to spawn");
+        process_error!(ProcessId::current(), "❌ {}", message);
+
+        if self.webserver_rx.is_some() {
+            let _ = self
+                .communicator
+                .send_webserver_update(OrchestratorUpdate::ErrorNotification(message.clone()))
+                .await;
+        }
+
+        let mut state = self.state.lock().await;
+        self.complete_generation(&mut state, CompletionReason::AllProducersFailed).await;
+        OrchestratorError::process(message)
+    }
+
+    /// Validate the output directory and set up the topic's directory and prior uniqueness
+    async fn prepare_topic_output(&self, topic: &str) -> OrchestratorResult<()> {
+        self.validate_output_dir().await?;
+
+        // Resume deduplication from an earlier run on the same topic (before the directory is recreated)
+        {
+            let mut state = self.state.lock().await;
+            let restored = state.load_uniqueness(&self.file_system.topic_file_path(topic, UNIQUENESS_FILE));
+            if restored > 0 {
+                process_info!(ProcessId::current(), "♻️ Restored {} prior values for topic '{}'", restored, topic);
+            }
+            let seeded = state.seed_known_attributes(&self.seed_values);
+            if seeded > 0 {
+                process_info!(ProcessId::current(), "🌱 Seeded {} known values for topic '{}'", seeded, topic);
+            }
+        }
+
+        // Create topic directory, keeping the output of a resumed run
+        if !self.state.lock().await.is_resumed() {
+            self.file_system.create_topic_directory(topic).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Ask the optimizer for a topic's opening prompts, routing strategy and generation config
+    async fn plan_initial_config(
+        &self,
+        topic: &str,
+        producer_count: u32,
+        request_size: usize,
+        api_keys: &HashMap<ProviderId, String>,
+    ) -> OrchestratorResult<(PromptAssignments, shared::RoutingStrategy, shared::GenerationConfig)> {
+        // Generate initial prompts and configuration with custom request_size
+        let (prompt_assignments, mut routing_strategy, mut generation_config) = {
             let state = self.state.lock().await;
             
             // Collect active producer IDs 
@@ -627,28 +1421,18 @@ where
                 .map(|_| shared::ProviderId::Random) // Default for now, will be determined by API keys
                 .collect();
             
-            let context = self.create_optimization_context(&topic, &state, active_producers);
+            let context = self.create_optimization_context(topic, &state, active_producers);
             drop(state); // Release lock before async call
             
             let optimization_result = self.optimizer.optimize(context).await?;
+            self.state.lock().await.set_optimization_plan(OptimizationPlan::from(&optimization_result));
             
-            // Extract the prompt - handle both uniform and per-producer assignments
-            let prompt = if let Some(default_prompt) = optimization_result.prompt_assignments.default_prompt {
-                default_prompt
-            } else {
-                // For now, just use the first producer's prompt if they're all different
-                optimization_result.prompt_assignments.producer_specific
-                    .values()
-                    .next()
-                    .map(|assignment| assignment.prompt.clone())
-                    .unwrap_or_else(|| "Generate unique attributes for the given topic.".to_string())
-            };
-
-            tracing::debug!("🎯 CLI Orchestrator generated prompt: '{}'", prompt);
+            let partitions = optimization_result.prompt_assignments.partitions();
+            tracing::debug!("🎯 CLI Orchestrator generated prompts: {:?}", partitions);
             tracing::debug!("🎯 CLI Topic: '{}', Routing: {:?}", topic, optimization_result.routing_strategy);
 
             (
-                prompt,
+                optimization_result.prompt_assignments,
                 optimization_result.routing_strategy,
                 optimization_result.generation_config,
             )
@@ -665,44 +1449,47 @@ where
         // Override request_size with CLI parameter
         generation_config.request_size = request_size;
 
-        // Spawn producers with the finalized routing strategy
-        let producer_addr = self.producer_addr.expect("Producer address not initialized");
-        let producer_infos = self
-            .process_manager
-            .spawn_producers(producer_count, &topic, api_keys.clone(), producer_addr, Some(routing_strategy.clone()))
-            .await?;
+        Ok((prompt_assignments, routing_strategy, generation_config))
+    }
 
-        // Register producers with communicator
-        for info in &producer_infos {
-            self.communicator
-                .register_producer(info.id.clone(), info.command_address)
-                .await?;
+    /// Check the output directory is writable and has enough free space
+    async fn validate_output_dir(&self) -> OrchestratorResult<()> {
+        if let Err(e) = self.file_system.check_writable().await {
+            process_error!(ProcessId::current(), "❌ Cannot start generation: {}", e);
+            return Err(e);
         }
 
-        // Note: Producers will send Ready signals when their IPC listeners are initialized
-        // No more artificial delays needed!
-
-        // Queue start commands for all producers (will be sent when they become ready)
-        {
-            let mut state = self.state.lock().await;
-            for info in &producer_infos {
-                let command = OrchestratorCommand::Start {
-                    command_id: 1,
-                    topic: topic.clone(),
-                    prompt: prompt.clone(),
-                    routing_strategy: routing_strategy.clone(),
-                    generation_config: generation_config.clone(),
-                };
+        let Some(free_bytes) = self.file_system.free_space().await? else {
+            return Ok(());
+        };
 
-                state.queue_start_command(info.id.clone(), command);
-            }
+        let free_mb = free_bytes / (1024 * 1024);
+        let required_mb = self.min_free_space_bytes / (1024 * 1024);
+        if free_bytes < self.min_free_space_bytes {
+            process_error!(
+                ProcessId::current(),
+                "❌ Cannot start generation: only {} MB free in output directory, {} MB required",
+                free_mb,
+                required_mb
+            );
+            return Err(OrchestratorError::config(format!(
+                "Insufficient free space in output directory: {free_mb} MB available, {required_mb} MB required"
+            )));
+        }
+        if free_bytes < self.min_free_space_bytes.saturating_mul(2) {
+            process_warn!(
+                ProcessId::current(),
+                "⚠️ Output directory is low on space: {} MB free ({} MB required)",
+                free_mb,
+                required_mb
+            );
         }
 
-        process_debug!(ProcessId::current(), "✅ Generation started successfully");
         Ok(())
     }
 
     /// Start generation process with optional routing parameters
+    #[allow(clippy::too_many_arguments)]
     async fn start_generation(
         &self,
         request_id: u64,
@@ -728,15 +1515,83 @@ where
         ).await
     }
 
-    /// Stop generation process
+    /// Stop generation process immediately and report where it ended
     async fn stop_generation(&self, request_id: u64) -> OrchestratorResult<()> {
+        let report = self.halt_generation().await?;
+
+        // Send acknowledgment followed by the final statistics
+        let ack = OrchestratorUpdate::RequestAck {
+            request_id,
+            success: true,
+            message: Some("Generation stopped".to_string()),
+        };
+
+        self.communicator.send_webserver_update(ack).await?;
+        self.communicator
+            .send_webserver_update(OrchestratorUpdate::StopReport { request_id, report })
+            .await?;
+
+        process_debug!(ProcessId::current(), "✅ Generation stopped");
+        Ok(())
+    }
+
+    /// Stop generation once the iteration in progress has been written out
+    ///
+    /// No further optimizer updates or producer restarts are issued meanwhile, and the
+    /// producers are shut down right after the next batch reaches the output. Without a
+    /// running topic this is an immediate stop. The reply reports the statistics so far,
+    /// with no completion reason yet.
+    async fn stop_after_current_iteration(&self, request_id: u64) -> OrchestratorResult<()> {
+        let report = {
+            let mut state = self.state.lock().await;
+            let running = state.context.topic.is_some() && state.completion_reason().is_none();
+            if running {
+                state.request_stop_after_iteration();
+            }
+            running.then(|| Self::stop_report(&state, None))
+        };
+        let Some(report) = report else {
+            return self.stop_generation(request_id).await;
+        };
+
+        process_info!(ProcessId::current(), "🛑 Generation will stop after the current iteration");
+        let ack = OrchestratorUpdate::RequestAck {
+            request_id,
+            success: true,
+            message: Some("Generation stopping after current iteration".to_string()),
+        };
+        self.communicator.send_webserver_update(ack).await?;
+        self.communicator
+            .send_webserver_update(OrchestratorUpdate::StopReport { request_id, report })
+            .await
+    }
+
+    /// Statistics of the current generation for a stop reply
+    fn stop_report(state: &OrchestratorState, completion_reason: Option<CompletionReason>) -> StopReport {
+        StopReport {
+            metrics: state.get_system_metrics(),
+            total_unique_attributes: state.get_unique_attribute_count(),
+            completion_reason,
+        }
+    }
+
+    /// Shut producers down and close the current topic as manually stopped
+    ///
+    /// Returns the statistics the topic ended with. A generation that had already
+    /// completed keeps its own completion reason.
+    async fn halt_generation(&self) -> OrchestratorResult<StopReport> {
         process_debug!(ProcessId::current(), "🛑 Stopping generation...");
 
         // Stop all producers
         self.process_manager.stop_all().await?;
 
+        // Output goes to disk before the uniqueness state that records it
+        if let Err(e) = self.file_system.sync_to_disk().await {
+            process_error!(ProcessId::current(), "⚠️ Error flushing output to disk: {}", e);
+        }
+
         // Update state and send completion notification
-        {
+        let report = {
             let mut state = self.state.lock().await;
 
             // Send GenerationComplete notification before stopping
@@ -761,28 +1616,62 @@ where
                 let _ = self.communicator.send_webserver_update(completion_update).await;
             }
 
+            self.save_checkpoints(&state).await;
+            if state.context.topic.is_some() {
+                if let Err(e) = state.export_run_summary(&self.file_system, CompletionReason::ManualStop).await {
+                    process_error!(ProcessId::current(), "⚠️ Failed to export run summary: {}", e);
+                }
+            }
+
+            let completion_reason = state.completion_reason().cloned().unwrap_or(CompletionReason::ManualStop);
+            let report = Self::stop_report(&state, Some(completion_reason));
             state.stop_generation();
-        }
+            report
+        };
+
+        Ok(report)
+    }
+
+    /// Send a command to every active producer and acknowledge the webserver request
+    async fn broadcast_to_producers(
+        &self,
+        request_id: u64,
+        command: OrchestratorCommand,
+        action: &str,
+    ) -> OrchestratorResult<()> {
+        let (delivered, total) = self.send_to_active_producers(command).await;
+        process_info!(ProcessId::current(), "✅ Generation {} ({}/{} producers)", action, delivered, total);
 
-        // Send acknowledgment
         let ack = OrchestratorUpdate::RequestAck {
             request_id,
-            success: true,
-            message: Some("Generation stopped".to_string()),
+            success: delivered == total,
+            message: Some(format!("Generation {action} on {delivered}/{total} producers")),
         };
+        self.communicator.send_webserver_update(ack).await
+    }
 
-        self.communicator.send_webserver_update(ack).await?;
+    /// Send a command to every active producer, returning (delivered, attempted)
+    async fn send_to_active_producers(&self, command: OrchestratorCommand) -> (usize, usize) {
+        let producer_ids = self.state.lock().await.active_producer_ids();
 
-        process_debug!(ProcessId::current(), "✅ Generation stopped");
-        Ok(())
+        let mut delivered = 0;
+        for producer_id in &producer_ids {
+            match self.send_producer_command_with_retry(producer_id.clone(), command.clone()).await {
+                Ok(()) => delivered += 1,
+                Err(e) => process_error!(ProcessId::current(), "❌ Failed to notify producer {}: {}", producer_id, e),
+            }
+        }
+        (delivered, producer_ids.len())
     }
 
     /// Process new batch of attributes from producer
     async fn process_attribute_batch(
         &self,
         producer_id: ProcessId,
+        topic: &str,
         attributes: Vec<String>,
         provider_metadata: shared::ProviderMetadata,
+        estimated_cost: f64,
     ) -> OrchestratorResult<()> {
         process_debug!(
             ProcessId::current(),
@@ -795,15 +1684,60 @@ where
             return Ok(());
         }
 
+        if self.state.lock().await.is_concurrent() {
+            return self
+                .process_concurrent_batch(producer_id, topic, attributes, provider_metadata, estimated_cost)
+                .await;
+        }
+
+        // Batches still in flight when a topic completes belong to no topic
+        {
+            let state = self.state.lock().await;
+            if state.completion_reason().is_some() {
+                process_debug!(ProcessId::current(), "⏭️ Ignoring batch from producer {} after topic completion", producer_id);
+                return Ok(());
+            }
+            // Batches generated for the previous topic of a batch run are not counted towards the next one
+            if !topic.is_empty() && state.context.topic.as_deref() != Some(topic) {
+                process_debug!(
+                    ProcessId::current(),
+                    "⏭️ Ignoring batch from producer {} for stale topic '{}'",
+                    producer_id,
+                    topic
+                );
+                return Ok(());
+            }
+        }
+
         let unique_attributes = {
             let mut state = self.state.lock().await;
-            let unique_attrs = state.add_attributes(producer_id.clone(), attributes, &provider_metadata);
+            let unique_attrs = state.add_attributes(producer_id.clone(), attributes, &provider_metadata, estimated_cost);
 
             // Store unique attributes to filesystem
             if let Some(topic) = &state.context.topic {
                 self.file_system
                     .write_unique_attributes_with_metadata(topic, &unique_attrs, &provider_metadata)
                     .await?;
+
+                self.file_system
+                    .append_csv_rows(
+                        topic,
+                        &unique_attrs,
+                        provider_metadata.provider_id,
+                        &provider_metadata.model,
+                        provider_metadata.request_timestamp,
+                    )
+                    .await?;
+                let records: Vec<_> = unique_attrs
+                    .iter()
+                    .map(|attribute| AttributeRecord::new(attribute, &provider_metadata))
+                    .collect();
+                self.file_system.append_jsonl(topic, &records).await?;
+
+                // Tag attributes with the prompt partition their producer was assigned
+                if let Some(category) = state.producer_category(&producer_id) {
+                    self.file_system.append_to_category(topic, category, &unique_attrs).await?;
+                }
             }
 
             unique_attrs
@@ -818,13 +1752,13 @@ where
             // Always send to webserver if we have unique attributes
             let update = OrchestratorUpdate::NewAttributes {
                 attributes: unique_attributes,
-                provider_metadata: None, // TODO: Pass actual provider metadata from the batch
+                provider_metadata: Some(provider_metadata),
             };
             self.communicator.send_webserver_update(update).await?;
         }
 
         // Check if we've reached iteration limit in CLI mode
-        {
+        let stop_requested = {
             let mut state = self.state.lock().await;
 
             // Append current iteration items to output.txt before incrementing
@@ -835,88 +1769,236 @@ where
                 }
             }
 
-            if state.increment_iteration() {
+            let limit_reached = state.increment_iteration();
+
+            if limit_reached && state.should_stop_iterations() {
                 // Reached iteration limit - initiate shutdown
                 process_debug!(
                     ProcessId::current(),
                     "🏁 CLI mode: Iteration limit reached, shutting down"
                 );
+                self.complete_generation(&mut state, CompletionReason::IterationLimitReached).await;
+            } else if limit_reached && state.target_count_reached() {
+                process_debug!(ProcessId::current(), "🎯 Target unique count reached, shutting down");
+                self.complete_generation(&mut state, CompletionReason::TargetCountReached).await;
+            } else if limit_reached {
+                process_debug!(ProcessId::current(), "📉 Efficiency floor reached, shutting down");
+                self.complete_generation(&mut state, CompletionReason::EfficiencyFloorReached).await;
+            }
 
-                // Send GenerationComplete notification to webserver (if it exists)
-                if let Some(topic) = &state.context.topic {
-                    let current_iteration = state.get_current_iteration();
-                    let final_unique_count = state.get_unique_attribute_count();
-
-                    process_info!(
-                        ProcessId::current(),
-                        "✅ Topic '{}' completed after {} iterations",
-                        topic,
-                        current_iteration
-                    );
+            state.stop_after_iteration_requested() && state.completion_reason().is_none()
+        };
 
-                    if self.webserver_rx.is_some() {
-                        let completion_update = OrchestratorUpdate::GenerationComplete {
-                            timestamp: chrono::Utc::now().timestamp() as u64,
-                            topic: topic.clone(),
-                            total_iterations: current_iteration,
-                            final_unique_count,
-                            completion_reason: CompletionReason::IterationLimitReached,
-                        };
-                        let _ = self.communicator.send_webserver_update(completion_update).await;
-                    }
+        // A soft stop takes effect now that this iteration has been written out
+        if stop_requested {
+            self.halt_generation().await?;
+            process_debug!(ProcessId::current(), "✅ Generation stopped after the current iteration");
+        }
 
-                    // Export performance data
-                    if let Err(e) = state.export_cycle_performance(&self.file_system).await {
-                        process_error!(ProcessId::current(), "⚠️ Failed to export cycle performance: {}", e);
-                    }
+        Ok(())
+    }
 
-                    if let Err(e) = state.export_provider_performance(&self.file_system).await {
-                        process_error!(ProcessId::current(), "⚠️ Failed to export provider performance: {}", e);
-                    }
-                }
+    /// Process a batch for one of several concurrently generated topics
+    ///
+    /// The batch is deduplicated and written out under the topic it carries. A topic
+    /// reaching the iteration limit stops its own producers; the run shuts down once
+    /// every topic has completed.
+    async fn process_concurrent_batch(
+        &self,
+        producer_id: ProcessId,
+        topic: &str,
+        attributes: Vec<String>,
+        provider_metadata: shared::ProviderMetadata,
+        estimated_cost: f64,
+    ) -> OrchestratorResult<()> {
+        let (unique_attributes, completed) = {
+            let mut state = self.state.lock().await;
+            let Some(unique_attrs) =
+                state.add_topic_attributes(topic, producer_id.clone(), attributes, &provider_metadata, estimated_cost)
+            else {
+                process_debug!(
+                    ProcessId::current(),
+                    "⏭️ Ignoring batch from producer {} for topic '{}', which is not running",
+                    producer_id,
+                    topic
+                );
+                return Ok(());
+            };
 
-                let _ = self.shutdown_tx.send(()).await;
+            self.file_system
+                .write_unique_attributes_with_metadata(topic, &unique_attrs, &provider_metadata)
+                .await?;
+            self.file_system
+                .append_csv_rows(
+                    topic,
+                    &unique_attrs,
+                    provider_metadata.provider_id,
+                    &provider_metadata.model,
+                    provider_metadata.request_timestamp,
+                )
+                .await?;
+            let records: Vec<_> = unique_attrs
+                .iter()
+                .map(|attribute| AttributeRecord::new(attribute, &provider_metadata))
+                .collect();
+            self.file_system.append_jsonl(topic, &records).await?;
+            if let Some(category) = state.producer_category(&producer_id) {
+                self.file_system.append_to_category(topic, category, &unique_attrs).await?;
             }
-        }
 
-        Ok(())
-    }
+            let (iteration_items, limit_reached) = state.finish_topic_iteration(topic).unwrap_or_default();
+            if !iteration_items.is_empty() {
+                self.file_system.append_to_output(topic, &iteration_items).await?;
+            }
 
-    /// Update producer status and heal if needed
-    async fn update_producer_status(&self, producer_id: ProcessId, status: ProcessStatus) -> OrchestratorResult<()> {
-        let mut state = self.state.lock().await;
-        state.update_producer_status(producer_id.clone(), status);
+            let completed = limit_reached.then(|| {
+                let topic_state = state.topic_state(topic).expect("finished topic is tracked");
+                (
+                    topic_state.producers().to_vec(),
+                    topic_state.current_iteration(),
+                    topic_state.unique_count(),
+                    state.all_topics_completed(),
+                )
+            });
+            (unique_attrs, completed)
+        };
 
-        // Only send start command to producers that are running but haven't been started for current topic yet
-        if status == ProcessStatus::Running && !state.is_producer_started(&producer_id) {
+        if !unique_attributes.is_empty() {
+            let update = OrchestratorUpdate::NewAttributes {
+                attributes: unique_attributes,
+                provider_metadata: Some(provider_metadata),
+            };
+            self.communicator.send_webserver_update(update).await?;
+        }
+
+        let Some((producers, iterations, unique_count, all_completed)) = completed else {
+            return Ok(());
+        };
+        process_info!(ProcessId::current(), "✅ Topic '{}' completed after {} iterations", topic, iterations);
+        if self.webserver_rx.is_some() {
+            let completion_update = OrchestratorUpdate::GenerationComplete {
+                timestamp: chrono::Utc::now().timestamp() as u64,
+                topic: topic.to_string(),
+                total_iterations: iterations,
+                final_unique_count: unique_count as usize,
+                completion_reason: CompletionReason::IterationLimitReached,
+            };
+            let _ = self.communicator.send_webserver_update(completion_update).await;
+        }
+
+        let command_id = chrono::Utc::now().timestamp_millis() as u64;
+        for producer_id in producers {
+            if let Err(e) = self
+                .send_producer_command_with_retry(producer_id.clone(), OrchestratorCommand::Stop { command_id })
+                .await
+            {
+                process_error!(ProcessId::current(), "❌ Failed to stop producer {}: {}", producer_id, e);
+            }
+        }
+
+        if all_completed {
+            self.state.lock().await.set_completion_reason(CompletionReason::IterationLimitReached);
+            let _ = self.shutdown_tx.try_send(());
+        }
+        Ok(())
+    }
+
+    /// Finish generation once it has run longer than `max_runtime_seconds`
+    async fn check_runtime_limit(&self) {
+        let mut state = self.state.lock().await;
+        if state.runtime_limit_reached() {
+            process_debug!(ProcessId::current(), "⏱️ Maximum runtime reached, shutting down");
+            self.complete_generation(&mut state, CompletionReason::TimeLimitReached).await;
+        }
+    }
+
+    /// Announce completion, export performance data and initiate shutdown
+    async fn complete_generation(&self, state: &mut OrchestratorState, completion_reason: CompletionReason) {
+        state.set_completion_reason(completion_reason.clone());
+
+        // Send GenerationComplete notification to webserver (if it exists)
+        if let Some(topic) = &state.context.topic {
+            let current_iteration = state.get_current_iteration();
+            let final_unique_count = state.get_unique_attribute_count();
+
+            process_info!(
+                ProcessId::current(),
+                "✅ Topic '{}' completed after {} iterations",
+                topic,
+                current_iteration
+            );
+
+            if self.webserver_rx.is_some() {
+                let completion_update = OrchestratorUpdate::GenerationComplete {
+                    timestamp: chrono::Utc::now().timestamp() as u64,
+                    topic: topic.clone(),
+                    total_iterations: current_iteration,
+                    final_unique_count,
+                    completion_reason: completion_reason.clone(),
+                };
+                let _ = self.communicator.send_webserver_update(completion_update).await;
+            }
+
+            // Export performance data
+            if let Err(e) = state.export_cycle_performance(&self.file_system).await {
+                process_error!(ProcessId::current(), "⚠️ Failed to export cycle performance: {}", e);
+            }
+
+            if let Err(e) = state.export_provider_performance(&self.file_system).await {
+                process_error!(ProcessId::current(), "⚠️ Failed to export provider performance: {}", e);
+            }
+
+            if let Err(e) = state.export_run_summary(&self.file_system, completion_reason).await {
+                process_error!(ProcessId::current(), "⚠️ Failed to export run summary: {}", e);
+            }
+        }
+
+        // Batch runs move on to the next topic instead of shutting down
+        if self.batch.as_ref().is_some_and(|batch| !batch.remaining.is_empty()) {
+            let _ = self.next_topic_tx.try_send(());
+            return;
+        }
+
+        // A pending signal already covers this completion
+        let _ = self.shutdown_tx.try_send(());
+    }
+
+    /// Update producer status and heal if needed
+    async fn update_producer_status(&self, producer_id: ProcessId, status: ProcessStatus) -> OrchestratorResult<()> {
+        let mut state = self.state.lock().await;
+        state.update_producer_status(producer_id.clone(), status);
+
+        // Only send start command to producers that are running but haven't been started for current topic yet
+        if status == ProcessStatus::Running && !state.is_producer_started(&producer_id) {
             if let Some(topic) = &state.context.topic {
                 // Generate and send start command to heal the producer
                 let topic = topic.clone(); // Clone topic before dropping state lock
                 let active_producers = vec![ProviderId::Random]; // Default for single producer recovery
                 let context = self.create_optimization_context(&topic, &state, active_producers);
+                let seed = state.run_seed();
+                let cost_budget = state.producer_cost_budget();
+                let dedup = state.context.dedup_enabled;
                 
                 // Drop the state lock before async call
                 drop(state);
                 
                 if let Ok(optimization_result) = self.optimizer.optimize(context).await {
-                    // Extract the prompt - handle both uniform and per-producer assignments
-                    let prompt = if let Some(default_prompt) = optimization_result.prompt_assignments.default_prompt {
-                        default_prompt
-                    } else {
-                        // For producer recovery, just use the first producer's prompt if they're all different
-                        optimization_result.prompt_assignments.producer_specific
-                            .values()
-                            .next()
-                            .map(|assignment| assignment.prompt.clone())
-                            .unwrap_or_else(|| "Generate unique attributes for the given topic.".to_string())
-                    };
+                    // The producer keeps the prompt partition its position in the pool maps to
+                    let (category, prompt) =
+                        optimization_result.prompt_assignments.partition_for(Self::producer_index(&producer_id));
                     
+                    self.export_prompts("recovery", &[(category, prompt.clone())]).await;
+
                     let command = OrchestratorCommand::Start {
                         command_id: chrono::Utc::now().timestamp_millis() as u64,
                         topic: topic.clone(),
                         prompt,
                         routing_strategy: optimization_result.routing_strategy,
                         generation_config: optimization_result.generation_config,
+                        seed,
+                        max_cost_per_minute: cost_budget,
+                        dedup,
+                        category: category.map(|provider| provider.to_string()),
                     };
 
                     process_debug!(
@@ -933,11 +2015,7 @@ where
                     }
 
                     // Send the command directly
-                    if let Err(e) = self
-                        .communicator
-                        .send_producer_command(producer_id.clone(), command)
-                        .await
-                    {
+                    if let Err(e) = self.send_producer_command_with_retry(producer_id.clone(), command).await {
                         process_error!(
                             ProcessId::current(),
                             "❌ Failed to send start command to producer {}: {}",
@@ -960,6 +2038,26 @@ where
         Ok(())
     }
 
+    /// Send the current bloom filter and seen values to producers that have not seen this version yet
+    async fn sync_producers(&self) -> OrchestratorResult<()> {
+        let (producer_ids, bloom_version) = {
+            let state = self.state.lock().await;
+            (state.get_producers_needing_sync(), state.get_bloom_version())
+        };
+        if producer_ids.is_empty() {
+            return Ok(());
+        }
+
+        let command = self.sync_check_command(chrono::Utc::now().timestamp_millis() as u64).await?;
+        for producer_id in producer_ids {
+            match self.communicator.send_producer_command(producer_id.clone(), command.clone()).await {
+                Ok(()) => self.state.lock().await.update_producer_sync(producer_id, bloom_version),
+                Err(e) => process_warn!(ProcessId::current(), "⚠️ Failed to sync producer {}: {}", producer_id, e),
+            }
+        }
+        Ok(())
+    }
+
     /// Handle sync acknowledgment from producer
     async fn handle_sync_acknowledgment(&self, _producer_id: ProcessId) -> OrchestratorResult<()> {
         // For now, just log - could be extended for bloom filter sync tracking
@@ -980,23 +2078,48 @@ where
         error_code: String,
         message: String,
     ) -> OrchestratorResult<()> {
+        let error = SharedError::from_code(error_code, message);
+
+        // A bad response affects only its own request; only failed credentials stop every later one
+        if !error.kind.is_fatal() {
+            process_warn!(
+                ProcessId::current(),
+                "⚠️ Producer {} non-fatal error [{}]: {}",
+                producer_id,
+                error.code,
+                error.message
+            );
+            return Ok(());
+        }
+
         process_error!(
             ProcessId::current(),
             "❌ Producer {} error [{}]: {}",
             producer_id,
-            error_code,
-            message
+            error.code,
+            error.message
         );
-
-        // Update producer status to failed
-        {
+        let stable_id = {
             let mut state = self.state.lock().await;
-            state.update_producer_status(producer_id, ProcessStatus::Failed);
-        }
+            state.update_producer_status(producer_id.clone(), ProcessStatus::Failed);
+            state.stable_producer_id(&producer_id)
+        };
+        self.notify_producer_lifecycle(stable_id, ProducerLifecycleEvent::Failed).await;
 
         Ok(())
     }
 
+    /// Tell the dashboard about a producer pool transition; a failed delivery never affects the pool
+    async fn notify_producer_lifecycle(&self, producer_id: ProcessId, event: ProducerLifecycleEvent) {
+        if self.webserver_rx.is_none() {
+            return;
+        }
+        let update = OrchestratorUpdate::ProducerLifecycle { producer_id, event };
+        if let Err(e) = self.communicator.send_webserver_update(update).await {
+            process_debug!(ProcessId::current(), "Failed to send producer lifecycle update: {}", e);
+        }
+    }
+
     /// Send current system status to webserver
     async fn send_status_update(&self, request_id: u64) -> OrchestratorResult<()> {
         let (metrics, active_producers, current_topic, total_unique) = {
@@ -1016,6 +2139,7 @@ where
         };
 
         let stats_update = OrchestratorUpdate::StatisticsUpdate {
+            request_id: Some(request_id),
             timestamp: chrono::Utc::now().timestamp() as u64,
             active_producers: active_producers as u32,
             current_topic,
@@ -1037,17 +2161,31 @@ where
         optimization_mode: Option<OptimizationMode>,
         constraints: Option<GenerationConstraints>,
     ) -> OrchestratorResult<()> {
-        {
+        let cost_budget = {
             let mut state = self.state.lock().await;
 
             if let Some(mode) = optimization_mode {
                 state.context.optimization_targets.optimization_mode = mode;
             }
 
-            if let Some(constraints) = constraints {
+            if let Some(constraints) = &constraints {
                 state.context.optimization_targets.max_cost_per_minute = constraints.max_cost_per_minute;
                 state.context.optimization_targets.min_uam = constraints.target_uam;
             }
+            state.producer_cost_budget()
+        };
+
+        // Producers throttle against the new budget right away rather than at the next optimization,
+        // and a budget of 0 clears the one they were given earlier
+        if constraints.is_some() {
+            let command = OrchestratorCommand::UpdateConfig {
+                command_id: chrono::Utc::now().timestamp_millis() as u64,
+                routing_strategy: None,
+                generation_config: None,
+                prompt: None,
+                max_cost_per_minute: Some(cost_budget.unwrap_or(0.0)),
+            };
+            self.send_to_active_producers(command).await;
         }
 
         let ack = OrchestratorUpdate::RequestAck {
@@ -1067,8 +2205,9 @@ where
         let (metrics, active_producers, current_topic, total_unique) = {
             let mut state = self.state.lock().await;
 
-            // Update performance tracker
+            // Update performance tracker, advancing the moving averages once per tick
             state.performance.recalculate_stats();
+            state.performance.update_smoothed();
 
             // Just collect metrics
             let metrics = state.get_system_metrics();
@@ -1085,6 +2224,7 @@ where
                           metrics.uam, metrics.cost_per_minute);
             
             let update = OrchestratorUpdate::StatisticsUpdate {
+                request_id: None,
                 timestamp: chrono::Utc::now().timestamp() as u64,
                 active_producers: active_producers as u32,
                 current_topic,
@@ -1132,38 +2272,72 @@ where
             }
         }
 
-        // Restart failed producers
-        if !failed_producers.is_empty() {
+        // Include producers marked failed by the orchestrator itself (e.g. unreachable)
+        let stopping = {
+            let state = self.state.lock().await;
+            for producer_id in state.failed_producer_ids() {
+                if !failed_producers.contains(&producer_id) {
+                    failed_producers.push(producer_id);
+                }
+            }
+            state.stop_after_iteration_requested()
+        };
+
+        // Restart failed producers, unless generation is about to stop
+        if !failed_producers.is_empty() && !stopping {
             process_info!(
                 ProcessId::current(),
                 "🔄 Healing producer pool: restarting {} failed producers",
                 failed_producers.len()
             );
 
+            let mut restarted = 0;
+            let mut abandoned = Vec::new();
             for failed_producer_id in &failed_producers {
-                if let Err(e) = self.restart_failed_producer(failed_producer_id.clone()).await {
-                    process_error!(
-                        ProcessId::current(),
-                        "❌ Failed to restart producer {}: {}",
-                        failed_producer_id,
-                        e
-                    );
-                } else {
-                    process_info!(
-                        ProcessId::current(),
-                        "✅ Producer {} successfully restarted",
-                        failed_producer_id
-                    );
+                match self.restart_failed_producer(failed_producer_id.clone()).await {
+                    Ok(RestartOutcome::Restarted(_)) => {
+                        restarted += 1;
+                        process_info!(
+                            ProcessId::current(),
+                            "✅ Producer {} successfully restarted",
+                            failed_producer_id
+                        );
+                    }
+                    Ok(RestartOutcome::Deferred) => {
+                        process_debug!(
+                            ProcessId::current(),
+                            "⏳ Producer {} restart deferred by backoff",
+                            failed_producer_id
+                        );
+                    }
+                    Ok(RestartOutcome::GaveUp { attempts }) => abandoned.push((failed_producer_id, attempts)),
+                    Err(e) => {
+                        process_error!(
+                            ProcessId::current(),
+                            "❌ Failed to restart producer {}: {}",
+                            failed_producer_id,
+                            e
+                        );
+                    }
                 }
             }
 
-            // Send error notification to webserver (if it exists)
+            // Send error notifications to webserver (if it exists)
             if self.webserver_rx.is_some() {
-                let status_update = OrchestratorUpdate::ErrorNotification(format!(
-                    "{} producers failed and were restarted",
-                    failed_producers.len()
-                ));
-                self.communicator.send_webserver_update(status_update).await?;
+                if restarted > 0 {
+                    let status_update = OrchestratorUpdate::ErrorNotification(format!(
+                        "{} producers failed and were restarted",
+                        restarted
+                    ));
+                    self.communicator.send_webserver_update(status_update).await?;
+                }
+                for (producer_id, attempts) in abandoned {
+                    let status_update = OrchestratorUpdate::ErrorNotification(format!(
+                        "Producer {} kept failing after {} restarts and will not be restarted again",
+                        producer_id, attempts
+                    ));
+                    self.communicator.send_webserver_update(status_update).await?;
+                }
             }
         }
 
@@ -1191,34 +2365,48 @@ where
         Ok(())
     }
 
-    /// Restart a single failed producer
-    async fn restart_failed_producer(&self, failed_producer_id: ProcessId) -> OrchestratorResult<()> {
-        // Get current topic and API keys for the new producer
-        let (topic, api_keys) = {
+    /// Restart a single failed producer, unless the restart policy has given up on it
+    async fn restart_failed_producer(&self, failed_producer_id: ProcessId) -> OrchestratorResult<RestartOutcome> {
+        // Get the failed producer's topic and API keys for the new producer
+        let (topic, api_keys, stable_id) = {
             let state = self.state.lock().await;
-            let topic = state.context.topic.clone();
-            (topic, self.api_keys.get_api_keys().await?)
+            let topic = state.producer_topic(&failed_producer_id);
+            let stable_id = state.stable_producer_id(&failed_producer_id);
+            (topic, self.api_keys.get_api_keys().await?, stable_id)
         };
 
-        if let Some(topic) = topic {
-            // Spawn a single replacement producer
-            let producer_addr = self.producer_addr.expect("Producer address not initialized");
-            let producer_infos = self
-                .process_manager
-                .spawn_producers(1, &topic, api_keys, producer_addr, None)
-                .await?;
+        let Some(topic) = topic else {
+            return Ok(RestartOutcome::Deferred);
+        };
+
+        // Promote an idle producer from the warm pool if there is one, otherwise spawn a replacement
+        let producer_addr = self.producer_addr.expect("Producer address not initialized");
+        let promoted = self
+            .process_manager
+            .promote_warm_producer(failed_producer_id.clone(), api_keys.clone(), producer_addr)
+            .await?;
+        let warm = promoted.is_some();
+        let outcome = match promoted {
+            Some(info) => RestartOutcome::Restarted(info),
+            None => {
+                self.process_manager
+                    .restart_producer(failed_producer_id.clone(), &topic, api_keys, producer_addr)
+                    .await?
+            }
+        };
 
-            if let Some(new_producer_info) = producer_infos.first() {
+        match outcome {
+            RestartOutcome::Restarted(ref new_producer_info) => {
                 // Register the new producer
                 self.communicator
                     .register_producer(new_producer_info.id.clone(), new_producer_info.command_address)
                     .await?;
 
-                // Update state: remove old producer and add new one
+                // Update state: the new producer takes over the old one's stable id
                 {
                     let mut state = self.state.lock().await;
-                    state.remove_producer(&failed_producer_id);
-                    state.add_producer(
+                    state.replace_producer(
+                        &failed_producer_id,
                         new_producer_info.id.clone(),
                         new_producer_info.process_id,
                         ProcessStatus::Running,
@@ -1227,34 +2415,42 @@ where
                     // Queue start command for the new producer
                     let active_producers = vec![ProviderId::Random]; // Default for replacement producer
                     let context = self.create_optimization_context(&topic, &state, active_producers);
+                    let seed = state.run_seed();
+                    let cost_budget = state.producer_cost_budget();
+                    let dedup = state.context.dedup_enabled;
                     
                     // Drop state lock for async call
                     drop(state);
                     
                     if let Ok(optimization_result) = self.optimizer.optimize(context).await {
-                        // Extract the prompt - handle both uniform and per-producer assignments
-                        let prompt = if let Some(default_prompt) = optimization_result.prompt_assignments.default_prompt {
-                            default_prompt
-                        } else {
-                            // For replacement producer, just use the first producer's prompt if they're all different
-                            optimization_result.prompt_assignments.producer_specific
-                                .values()
-                                .next()
-                                .map(|assignment| assignment.prompt.clone())
-                                .unwrap_or_else(|| "Generate unique attributes for the given topic.".to_string())
-                        };
+                        // The replacement takes over the failed producer's prompt partition
+                        let (category, prompt) = optimization_result
+                            .prompt_assignments
+                            .partition_for(Self::producer_index(&failed_producer_id));
                         
+                        self.export_prompts("recovery", &[(category, prompt.clone())]).await;
+
                         let command = OrchestratorCommand::Start {
                             command_id: chrono::Utc::now().timestamp_millis() as u64,
                             topic: topic.clone(),
                             prompt,
                             routing_strategy: optimization_result.routing_strategy,
                             generation_config: optimization_result.generation_config,
+                            seed,
+                            max_cost_per_minute: cost_budget,
+                            dedup,
+                            category: category.map(|provider| provider.to_string()),
                         };
 
-                        // Re-acquire lock to queue command
-                        let mut state = self.state.lock().await;
-                        state.queue_start_command(new_producer_info.id.clone(), command);
+                        // A warm producer that already announced itself ready will not announce it
+                        // again, so start it now; one still booting is started by its ready signal
+                        if warm && self.communicator.is_producer_ready(new_producer_info.id.clone()).await {
+                            self.send_producer_command_with_retry(new_producer_info.id.clone(), command).await?;
+                        } else {
+                            // Re-acquire lock to queue command
+                            let mut state = self.state.lock().await;
+                            state.queue_start_command(new_producer_info.id.clone(), command);
+                        }
                     }
                 }
 
@@ -1264,10 +2460,17 @@ where
                     new_producer_info.id,
                     failed_producer_id
                 );
+                self.notify_producer_lifecycle(stable_id, ProducerLifecycleEvent::Restarted).await;
             }
+            RestartOutcome::GaveUp { .. } => {
+                // Stop tracking it so it neither counts as active nor comes back on every health check
+                self.state.lock().await.remove_producer(&failed_producer_id);
+                self.notify_producer_lifecycle(stable_id, ProducerLifecycleEvent::Stopped).await;
+            }
+            RestartOutcome::Deferred => {}
         }
 
-        Ok(())
+        Ok(outcome)
     }
 
     /// Restart a failed webserver (self-healing)
@@ -1299,11 +2502,47 @@ where
         Ok(())
     }
 
+    /// Write buffered output to disk, then checkpoint the running topics
+    async fn flush_and_checkpoint(&self) -> OrchestratorResult<()> {
+        self.file_system.sync_to_disk().await?;
+        let state = self.state.lock().await;
+        self.save_checkpoints(&state).await;
+        Ok(())
+    }
+
+    /// Persist each topic's progress and uniqueness tracker so a later run can resume it with --resume
+    ///
+    /// Callers flush output first, so a checkpoint never counts attributes that are
+    /// still waiting to be written to output.txt.
+    async fn save_checkpoints(&self, state: &OrchestratorState) {
+        if let Some(topic) = &state.context.topic {
+            if let Err(e) = state.save_checkpoint(&self.file_system).await {
+                process_error!(ProcessId::current(), "⚠️ Failed to save checkpoint: {}", e);
+            }
+            let path = self.file_system.topic_file_path(topic, UNIQUENESS_FILE);
+            if let Err(e) = state.save_uniqueness(&path) {
+                process_error!(ProcessId::current(), "⚠️ Failed to save uniqueness state to {}: {}", path.display(), e);
+            }
+        }
+        for topic in state.concurrent_topics() {
+            let path = self.file_system.topic_file_path(topic, UNIQUENESS_FILE);
+            if let Err(e) = state.save_topic_uniqueness(topic, &path) {
+                process_error!(ProcessId::current(), "⚠️ Failed to save uniqueness state to {}: {}", path.display(), e);
+            }
+        }
+    }
+
     /// Graceful shutdown
     async fn shutdown(&self) -> OrchestratorResult<()> {
         process_debug!(ProcessId::current(), "🛑 Starting graceful shutdown...");
 
+        // Flush buffered output before anything else can fail and lose it
+        if let Err(e) = self.file_system.sync_to_disk().await {
+            process_error!(ProcessId::current(), "⚠️ Error flushing output to disk: {}", e);
+        }
+
         // Export performance data and log topic completion
+        let mut final_summary = None;
         {
             let state = self.state.lock().await;
             if let Some(topic) = &state.context.topic {
@@ -1324,6 +2563,25 @@ where
                 if let Err(e) = state.export_provider_performance(&self.file_system).await {
                     process_error!(ProcessId::current(), "⚠️ Failed to export provider performance: {}", e);
                 }
+
+                let completion_reason = match state.completion_reason() {
+                    Some(reason) => reason.clone(),
+                    None if state.should_stop_iterations() => CompletionReason::IterationLimitReached,
+                    None => CompletionReason::ManualStop,
+                };
+                final_summary = state.run_summary(completion_reason.clone());
+                if let Err(e) = state.export_run_summary(&self.file_system, completion_reason).await {
+                    process_error!(ProcessId::current(), "⚠️ Failed to export run summary: {}", e);
+                }
+            }
+            self.save_checkpoints(&state).await;
+        }
+
+        if let Some(batch) = &self.batch {
+            let mut topics = batch.summaries.clone();
+            topics.extend(final_summary);
+            if let Err(e) = self.export_batch_summary(BatchSummary::new(batch.total, topics)).await {
+                process_error!(ProcessId::current(), "⚠️ Failed to export batch summary: {}", e);
             }
         }
 
@@ -1336,7 +2594,7 @@ where
         self.communicator.shutdown().await?;
         process_debug!(ProcessId::current(), "🔌 Communication channels shut down");
 
-        // Sync filesystem
+        // Sync the summaries and checkpoints written above
         self.file_system.sync_to_disk().await?;
         process_debug!(ProcessId::current(), "💽 File system synced to disk");
 
@@ -1344,6 +2602,20 @@ where
         Ok(())
     }
 
+    /// Write the combined batch summary to the output root
+    async fn export_batch_summary(&self, summary: BatchSummary) -> OrchestratorResult<()> {
+        let json_content = serde_json::to_string_pretty(&summary)?;
+        self.file_system.write_file(BATCH_SUMMARY_FILE, json_content.as_bytes()).await?;
+        process_info!(
+            ProcessId::current(),
+            "📦 Batch finished {}/{} topics with {} unique values",
+            summary.completed_topics,
+            summary.total_topics,
+            summary.total_unique_count
+        );
+        Ok(())
+    }
+
     /// Get shutdown sender for external shutdown requests
     pub fn get_shutdown_sender(&self) -> mpsc::Sender<()> {
         self.shutdown_tx.clone()
@@ -1368,8 +2640,8 @@ where
                     uam: metrics.uam,
                     cost_per_minute: metrics.cost_per_minute,
                     uniqueness_ratio: metrics.uniqueness_ratio,
-                    response_time_ms: 0.0, // Not available in legacy format
-                    success_rate: 1.0,     // Not available in legacy format
+                    response_time_ms: metrics.avg_response_time_ms,
+                    success_rate: metrics.success_rate,
                 })
             }).collect(),
             trend: PerformanceTrend {
@@ -1411,6 +2683,9 @@ where
                 Some(topic) => topic.clone(),
                 None => return Ok(()), // No active generation
             };
+            if state.stop_after_iteration_requested() {
+                return Ok(()); // Stopping once the current iteration is written out
+            }
 
             // Get actual active producer IDs from state
             let active_producers: Vec<ProviderId> = (0..state.get_active_producer_count())
@@ -1429,61 +2704,142 @@ where
             (topic, active_producers, optimization_result)
         };
 
-        // Check if optimization suggests significant changes
-        if optimization_result.assessment.confidence > 0.7 {
-            process_debug!(
+        // Only act on confident recommendations, and not more often than the cooldown allows
+        if optimization_result.assessment.confidence <= RECONFIGURE_CONFIDENCE_THRESHOLD {
+            return Ok(());
+        }
+        {
+            let mut state = self.state.lock().await;
+            if state.reconfigured_within(self.reconfigure_cooldown) {
+                process_debug!(ProcessId::current(), "⏳ Skipping optimization update during reconfigure cooldown");
+                return Ok(());
+            }
+            state.set_optimization_plan(OptimizationPlan::from(&optimization_result));
+        }
+
+        // Only target active producers whose configuration would actually change
+        let (update_commands, changes) = {
+            let state = self.state.lock().await;
+            let mut changes: Vec<&'static str> = Vec::new();
+            let commands: Vec<_> = self
+                .create_update_commands(&optimization_result, &state.active_producer_ids(), state.producer_cost_budget())
+                .into_iter()
+                .filter(|(producer_id, command)| {
+                    let OrchestratorCommand::UpdateConfig { prompt: Some(prompt), .. } = command else {
+                        return true;
+                    };
+                    let recommended = ProducerConfig {
+                        prompt: prompt.clone(),
+                        routing_strategy: optimization_result.routing_strategy.clone(),
+                        generation_config: optimization_result.generation_config.clone(),
+                    };
+                    match state.producer_config(producer_id) {
+                        Some(current) if *current == recommended => false,
+                        Some(current) => {
+                            for change in current.changes(&recommended) {
+                                if !changes.contains(&change) {
+                                    changes.push(change);
+                                }
+                            }
+                            true
+                        }
+                        None => true,
+                    }
+                })
+                .collect();
+            (commands, changes)
+        };
+
+        if update_commands.is_empty() {
+            process_debug!(ProcessId::current(), "🎯 Optimization recommends the current configuration, nothing to apply");
+            return Ok(());
+        }
+
+        self.export_prompts("optimization", &optimization_result.prompt_assignments.partitions()).await;
+
+        let total = update_commands.len();
+        let mut delivered = 0;
+        for (producer_id, command) in update_commands {
+            match self.send_producer_command_with_retry(producer_id, command).await {
+                Ok(()) => delivered += 1,
+                Err(e) => process_error!(ProcessId::current(), "❌ Failed to send optimization update: {}", e),
+            }
+        }
+
+        if delivered > 0 {
+            self.state.lock().await.mark_reconfigured();
+            let changed = if changes.is_empty() {
+                "initial configuration".to_string()
+            } else {
+                changes.join(", ")
+            };
+            process_info!(
                 ProcessId::current(),
-                "🎯 Optimization update (confidence: {:.1}%): {}",
+                "🔧 Optimizer reconfigured {}/{} producers (confidence {:.0}%, changed: {}): {}",
+                delivered,
+                total,
                 optimization_result.assessment.confidence * 100.0,
+                changed,
                 optimization_result.assessment.rationale
             );
-
-            // Send UpdateConfig commands to all active producers
-            let update_commands = self.create_update_commands(&optimization_result);
-            
-            for (producer_id, command) in update_commands {
-                if let Err(e) = self.communicator.send_producer_command(producer_id, command).await {
-                    process_error!(ProcessId::current(), "❌ Failed to send optimization update: {}", e);
-                }
-            }
         }
 
         Ok(())
     }
 
-    /// Create UpdateConfig commands from optimization results
-    fn create_update_commands(&self, result: &OptimizationResult) -> Vec<(ProcessId, OrchestratorCommand)> {
-        let mut commands = Vec::new();
-        let command_id = chrono::Utc::now().timestamp_millis() as u64;
+    /// Append the prompts sent to producers to the topic's prompts.jsonl (failures are logged, not fatal)
+    async fn export_prompts(&self, source: &str, prompts: &[(Option<ProviderId>, String)]) {
+        let (topic, content): (String, String) = {
+            let state = self.state.lock().await;
+            let Some(topic) = state.context.topic.clone() else {
+                return;
+            };
+            let content = prompts
+                .iter()
+                .filter_map(|(provider, prompt)| serde_json::to_string(&state.prompt_record(source, *provider, prompt)).ok())
+                .map(|line| line + "\n")
+                .collect();
+            (topic, content)
+        };
 
-        // For uniform prompts, send same update to all producers
-        if let Some(default_prompt) = &result.prompt_assignments.default_prompt {
-            // Send to all active producers (simplified - would get actual producer IDs from state)
-            for i in 1..=4 { // Assuming max 4 producers for now
-                let producer_id = ProcessId::Producer(i);
-                let command = OrchestratorCommand::UpdateConfig {
-                    command_id,
-                    routing_strategy: Some(result.routing_strategy.clone()),
-                    generation_config: Some(result.generation_config.clone()),
-                    prompt: Some(default_prompt.clone()),
-                };
-                commands.push((producer_id, command));
-            }
-        } else {
-            // Send producer-specific updates
-            for (i, (_provider_id, assignment)) in result.prompt_assignments.producer_specific.iter().enumerate() {
-                let producer_id = ProcessId::Producer((i + 1) as u32);
-                let command = OrchestratorCommand::UpdateConfig {
-                    command_id,
-                    routing_strategy: Some(result.routing_strategy.clone()),
-                    generation_config: Some(result.generation_config.clone()),
-                    prompt: Some(assignment.prompt.clone()),
-                };
-                commands.push((producer_id, command));
-            }
+        let filename = format!("{}/{}", topic, crate::core::state::PROMPTS_FILE);
+        if let Err(e) = self.file_system.append_file(&filename, content.as_bytes()).await {
+            process_warn!(ProcessId::current(), "⚠️ Failed to export prompts: {}", e);
         }
+    }
+
+    /// Create UpdateConfig commands from optimization results for the given producers
+    fn create_update_commands(
+        &self,
+        result: &OptimizationResult,
+        producer_ids: &[ProcessId],
+        cost_budget: Option<f64>,
+    ) -> Vec<(ProcessId, OrchestratorCommand)> {
+        let command_id = chrono::Utc::now().timestamp_millis() as u64;
+        let update = |prompt: &str| OrchestratorCommand::UpdateConfig {
+            command_id,
+            routing_strategy: Some(result.routing_strategy.clone()),
+            generation_config: Some(result.generation_config.clone()),
+            prompt: Some(prompt.to_string()),
+            max_cost_per_minute: cost_budget,
+        };
+
+        // Each producer stays on the prompt partition it was started on; uniform prompts go to all
+        producer_ids
+            .iter()
+            .map(|producer_id| {
+                let (_, prompt) = result.prompt_assignments.partition_for(Self::producer_index(producer_id));
+                (producer_id.clone(), update(&prompt))
+            })
+            .collect()
+    }
 
-        commands
+    /// Position of a producer in the pool, which picks its prompt partition
+    fn producer_index(producer_id: &ProcessId) -> usize {
+        match producer_id {
+            ProcessId::Producer(number) => (*number as usize).saturating_sub(1),
+            _ => 0,
+        }
     }
 
     /// Test webserver connection health and attempt to restore if needed
@@ -1516,3 +2872,2193 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::strategies::BasicOptimizer;
+    use crate::traits::{MockApiKeySource, MockCommunicator, MockFileSystem, MockProcessManager};
+    use shared::types::{GenerationConfig, RoutingStrategy};
+
+    type TestOrchestrator =
+        Orchestrator<MockApiKeySource, MockCommunicator, MockFileSystem, MockProcessManager, BasicOptimizer>;
+
+    fn create_test_orchestrator(communicator: MockCommunicator) -> TestOrchestrator {
+        create_test_orchestrator_with(communicator, MockProcessManager::new())
+    }
+
+    fn create_test_orchestrator_with(
+        communicator: MockCommunicator,
+        process_manager: MockProcessManager,
+    ) -> TestOrchestrator {
+        create_test_orchestrator_with_fs(communicator, MockFileSystem::new(), process_manager)
+    }
+
+    fn create_test_orchestrator_with_fs(
+        communicator: MockCommunicator,
+        file_system: MockFileSystem,
+        process_manager: MockProcessManager,
+    ) -> TestOrchestrator {
+        ProcessId::init_orchestrator();
+        Orchestrator::new(
+            MockApiKeySource::new(),
+            communicator,
+            file_system,
+            process_manager,
+            BasicOptimizer::new(),
+        )
+        .with_command_retry(CommandRetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+        })
+    }
+
+    fn create_start_command() -> OrchestratorCommand {
+        OrchestratorCommand::Start {
+            command_id: 1,
+            topic: "test".to_string(),
+            prompt: "Generate attributes".to_string(),
+            routing_strategy: RoutingStrategy::Backoff {
+                provider: shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+            },
+            generation_config: GenerationConfig {
+                model: "random".to_string(),
+                batch_size: 1,
+                context_window: 4096,
+                max_tokens: 100,
+                temperature: 0.7,
+                request_size: 10,
+                extraction_format: None,
+                system_prompt: None,
+                min_attribute_length: None,
+                max_attribute_length: None,
+                include_seen_hint: false,
+                seen_hint_count: 0,
+            },
+            seed: 42,
+            max_cost_per_minute: None,
+            dedup: true,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_routing_parse_errors_name_the_offending_token() {
+        let orchestrator = create_test_orchestrator(MockCommunicator::new());
+
+        let strategy = orchestrator
+            .parse_routing_strategy_and_config("weighted", "openai:3,anthropic:gpt-4o:1")
+            .unwrap();
+        let shared::RoutingStrategy::Weighted { weights } = strategy else {
+            panic!("expected weighted strategy");
+        };
+        assert_eq!(weights[&shared::types::ProviderConfig::with_default_model(ProviderId::OpenAI)], 0.75);
+
+        let error = orchestrator.parse_routing_strategy_and_config("fastest", "openai").unwrap_err();
+        assert!(matches!(error, RoutingParseError::UnknownStrategy { ref strategy } if strategy == "fastest"));
+        let error = orchestrator.parse_routing_strategy_and_config("roundrobin", "openai,nosuch:model").unwrap_err();
+        assert!(matches!(error, RoutingParseError::InvalidProvider { ref token, .. } if token == "nosuch"));
+        let error = orchestrator.parse_routing_strategy_and_config("backoff", "openai:").unwrap_err();
+        assert!(matches!(error, RoutingParseError::InvalidModel { provider: ProviderId::OpenAI, .. }));
+        let error = orchestrator.parse_routing_strategy_and_config("weighted", "openai:heavy").unwrap_err();
+        assert!(matches!(error, RoutingParseError::InvalidWeight { ref token, .. } if token == "heavy"));
+        let error = orchestrator.parse_routing_strategy_and_config("weighted", "openai:a:b:1").unwrap_err();
+        assert!(matches!(error, RoutingParseError::InvalidFormat { entry: FormatEntry::ProviderModelWeight, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_producer_ready_with_mismatched_protocol_version_is_rejected() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_mark_producer_ready().never();
+        communicator.expect_send_producer_command().never();
+        let orchestrator = create_test_orchestrator(communicator);
+
+        let producer_id = ProcessId::Producer(1);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(producer_id.clone(), 0, ProcessStatus::Starting);
+            state.queue_start_command(producer_id.clone(), create_start_command());
+        }
+
+        let update = ProducerUpdate::Ready {
+            producer_id: producer_id.clone(),
+            listen_port: 7001,
+            protocol_version: shared::PROTOCOL_VERSION + 1,
+        };
+        orchestrator.handle_producer_update(update).await.unwrap();
+
+        // The queued start command must not have been dispatched
+        let mut state = orchestrator.state.lock().await;
+        assert!(state.take_pending_start_command(&producer_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_producer_ready_with_matching_protocol_version_is_registered() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_mark_producer_ready().times(1).returning(|_, _| Ok(()));
+        communicator
+            .expect_send_producer_command()
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let orchestrator = create_test_orchestrator(communicator);
+
+        let producer_id = ProcessId::Producer(1);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(producer_id.clone(), 0, ProcessStatus::Starting);
+            state.queue_start_command(producer_id.clone(), create_start_command());
+        }
+
+        let update = ProducerUpdate::Ready {
+            producer_id: producer_id.clone(),
+            listen_port: 7001,
+            protocol_version: shared::PROTOCOL_VERSION,
+        };
+        orchestrator.handle_producer_update(update).await.unwrap();
+
+        let mut state = orchestrator.state.lock().await;
+        assert!(state.take_pending_start_command(&producer_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_config_sends_and_clears_producer_cost_budget() {
+        let budgets = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let sent = budgets.clone();
+        communicator.expect_send_producer_command().returning(move |_, command| {
+            if let OrchestratorCommand::UpdateConfig { max_cost_per_minute, .. } = command {
+                sent.lock().unwrap().push(max_cost_per_minute);
+            }
+            Ok(())
+        });
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let orchestrator = create_test_orchestrator(communicator);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(ProcessId::Producer(1), 1, ProcessStatus::Running);
+            state.add_producer(ProcessId::Producer(2), 2, ProcessStatus::Running);
+        }
+
+        let constraints = |max_cost_per_minute| GenerationConstraints {
+            max_cost_per_minute,
+            ..orchestrator.cli_constraints()
+        };
+        orchestrator.update_config(1, None, Some(constraints(1.0))).await.unwrap();
+        orchestrator.update_config(2, None, Some(constraints(0.0))).await.unwrap();
+
+        assert_eq!(*budgets.lock().unwrap(), vec![Some(0.5), Some(0.5), Some(0.0), Some(0.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_producer_command_is_retried_until_delivered() {
+        let mut communicator = MockCommunicator::new();
+        let mut seq = mockall::Sequence::new();
+        communicator
+            .expect_send_producer_command()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Err(OrchestratorError::communication("connection refused")));
+        communicator
+            .expect_send_producer_command()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_stop_producer().never();
+        let orchestrator = create_test_orchestrator_with(communicator, process_manager);
+
+        let producer_id = ProcessId::Producer(1);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(producer_id.clone(), 0, ProcessStatus::Running);
+        }
+
+        orchestrator
+            .send_producer_command_with_retry(producer_id.clone(), create_start_command())
+            .await
+            .unwrap();
+
+        let state = orchestrator.state.lock().await;
+        assert!(state.failed_producer_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pause_is_broadcast_to_active_producers_only() {
+        let paused = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let recorded = paused.clone();
+        communicator
+            .expect_send_producer_command()
+            .withf(|_, command| matches!(command, OrchestratorCommand::Pause { .. }))
+            .times(2)
+            .returning(move |producer_id, _| {
+                recorded.lock().unwrap().push(producer_id);
+                Ok(())
+            });
+        communicator
+            .expect_send_webserver_update()
+            .withf(|update| matches!(update, OrchestratorUpdate::RequestAck { request_id: 5, success: true, .. }))
+            .times(1)
+            .returning(|_| Ok(()));
+        let orchestrator = create_test_orchestrator(communicator);
+
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(ProcessId::Producer(1), 1, ProcessStatus::Running);
+            state.add_producer(ProcessId::Producer(2), 2, ProcessStatus::Running);
+            state.add_producer(ProcessId::Producer(3), 3, ProcessStatus::Running);
+            state.mark_producer_failed(ProcessId::Producer(3));
+        }
+
+        orchestrator
+            .handle_webserver_request(WebServerRequest::PauseGeneration { request_id: 5 })
+            .await
+            .unwrap();
+
+        assert_eq!(*paused.lock().unwrap(), vec![ProcessId::Producer(1), ProcessId::Producer(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_producer_command_marks_producer_failed() {
+        let mut communicator = MockCommunicator::new();
+        communicator
+            .expect_send_producer_command()
+            .times(3)
+            .returning(|_, _| Err(OrchestratorError::communication("connection refused")));
+        let mut process_manager = MockProcessManager::new();
+        process_manager
+            .expect_stop_producer()
+            .times(1)
+            .returning(|_| Ok(()));
+        let orchestrator = create_test_orchestrator_with(communicator, process_manager);
+
+        let producer_id = ProcessId::Producer(1);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(producer_id.clone(), 0, ProcessStatus::Running);
+        }
+
+        let result = orchestrator
+            .send_producer_command_with_retry(producer_id.clone(), create_start_command())
+            .await;
+        assert!(result.is_err());
+
+        let state = orchestrator.state.lock().await;
+        assert_eq!(state.failed_producer_ids(), vec![producer_id]);
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_clearly_when_output_dir_is_read_only() {
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_check_writable().times(1).returning(|| {
+            Err(OrchestratorError::config(
+                "Output directory './output' is not writable: Permission denied (os error 13)",
+            ))
+        });
+        file_system.expect_create_topic_directory().never();
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_spawn_producers().never();
+        let mut orchestrator =
+            create_test_orchestrator_with_fs(MockCommunicator::new(), file_system, process_manager);
+
+        let error = orchestrator
+            .start_cli_generation("test".to_string(), 2, Some(1), 10, OptimizationMode::MaximizeEfficiency, None, None)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("is not writable"), "unexpected error: {error}");
+    }
+
+    #[tokio::test]
+    async fn test_start_rejects_zero_producers() {
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_check_writable().never();
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_spawn_producers().never();
+        let mut orchestrator =
+            create_test_orchestrator_with_fs(MockCommunicator::new(), file_system, process_manager);
+
+        let error = orchestrator
+            .start_cli_generation("test".to_string(), 0, Some(1), 10, OptimizationMode::MaximizeEfficiency, None, None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("At least one producer"), "unexpected error: {error}");
+
+        let error = orchestrator
+            .handle_webserver_request(WebServerRequest::StartGeneration {
+                request_id: 1,
+                topic: "test".to_string(),
+                producer_count: 0,
+                optimization_mode: OptimizationMode::MaximizeEfficiency,
+                constraints: orchestrator.cli_constraints(),
+                iterations: None,
+                routing_strategy: None,
+                routing_config: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("At least one producer"), "unexpected error: {error}");
+        assert!(orchestrator.state.lock().await.context.topic.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_shuts_down_when_all_producers_fail_to_spawn() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_register_producer().never();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_check_writable().returning(|| Ok(()));
+        file_system.expect_free_space().returning(|| Ok(None));
+        file_system.expect_topic_file_path().returning(|topic, file| std::path::PathBuf::from(topic).join(file));
+        file_system.expect_create_topic_directory().returning(|_| Ok(()));
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_spawn_producers().times(1).returning(|_, _, _, _, _| Ok(Vec::new()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+
+        let error = orchestrator
+            .start_cli_generation("test".to_string(), 3, Some(1), 10, OptimizationMode::MaximizeEfficiency, None, None)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("All 3 producers failed to spawn"), "unexpected error: {error}");
+        assert!(matches!(
+            orchestrator.state.lock().await.completion_reason(),
+            Some(CompletionReason::AllProducersFailed)
+        ));
+        assert!(orchestrator.shutdown_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_start_refuses_when_output_dir_is_nearly_full() {
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_check_writable().returning(|| Ok(()));
+        file_system.expect_free_space().returning(|| Ok(Some(10 * 1024 * 1024)));
+        file_system.expect_create_topic_directory().never();
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_spawn_producers().never();
+        let mut orchestrator =
+            create_test_orchestrator_with_fs(MockCommunicator::new(), file_system, process_manager)
+                .with_min_free_space(100 * 1024 * 1024);
+
+        let error = orchestrator
+            .start_cli_generation("test".to_string(), 2, Some(1), 10, OptimizationMode::MaximizeEfficiency, None, None)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("10 MB available, 100 MB required"), "unexpected error: {error}");
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_collect_most_conservative_tuned_limits() {
+        let orchestrator = create_test_orchestrator(MockCommunicator::new());
+
+        let reports = [(1, 6, 1200), (2, 4, 1500)];
+        for (id, max_concurrent, max_rpm) in reports {
+            let producer_id = ProcessId::Producer(id);
+            orchestrator
+                .state
+                .lock()
+                .await
+                .add_producer(producer_id.clone(), 0, ProcessStatus::Running);
+
+            let mut tuned_limits = HashMap::new();
+            tuned_limits.insert(ProviderId::OpenAI, shared::TunedLimits { max_concurrent, max_rpm });
+            let update = ProducerUpdate::StatusUpdate {
+                producer_id,
+                status: ProcessStatus::Running,
+                message: None,
+                performance_stats: Some(shared::messages::producer::ProducerPerformanceStats {
+                    attributes_generated_last_minute: 0,
+                    unique_contributed_last_minute: 0,
+                    requests_made_last_minute: 0,
+                    provider_usage: HashMap::new(),
+                    current_batch_rate: 0.0,
+                    memory_usage_mb: None,
+                    bloom_filter_size_mb: None,
+                    tuned_limits,
+                    verification: Default::default(),
+                }),
+            };
+            orchestrator.handle_producer_update(update).await.unwrap();
+        }
+
+        let state = orchestrator.state.lock().await;
+        let limits = state.tuned_limits()[&ProviderId::OpenAI];
+        assert_eq!(limits.max_concurrent, 4);
+        assert_eq!(limits.max_rpm, 1200);
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_report_provider_success_rate_and_latency() {
+        let orchestrator = create_test_orchestrator(MockCommunicator::new());
+        let producer_id = ProcessId::Producer(1);
+        orchestrator
+            .state
+            .lock()
+            .await
+            .add_producer(producer_id.clone(), 0, ProcessStatus::Running);
+
+        // Cumulative reports: 2 successes at 100ms, then 2 more successes and 4 failures at 400ms
+        let reports = [(2, 0, 100.0), (4, 4, 300.0)];
+        for (successful_responses, failed_responses, avg_response_time_ms) in reports {
+            let usage = shared::messages::producer::ProviderUsageStats {
+                requests_sent: successful_responses + failed_responses,
+                successful_responses,
+                failed_responses,
+                unique_attributes_contributed: 0,
+                avg_response_time_ms,
+                last_used_timestamp: 0,
+            };
+            let update = ProducerUpdate::StatusUpdate {
+                producer_id: producer_id.clone(),
+                status: ProcessStatus::Running,
+                message: None,
+                performance_stats: Some(shared::messages::producer::ProducerPerformanceStats {
+                    attributes_generated_last_minute: 0,
+                    unique_contributed_last_minute: 0,
+                    requests_made_last_minute: 0,
+                    provider_usage: HashMap::from([(ProviderId::OpenAI, usage)]),
+                    current_batch_rate: 0.0,
+                    memory_usage_mb: None,
+                    bloom_filter_size_mb: None,
+                    tuned_limits: HashMap::new(),
+                    verification: Default::default(),
+                }),
+            };
+            orchestrator.handle_producer_update(update).await.unwrap();
+        }
+
+        let state = orchestrator.state.lock().await;
+        let stats = state.generate_provider_performance_stats();
+        let openai = stats.iter().find(|s| s.provider_id == ProviderId::OpenAI).unwrap();
+        assert_eq!(openai.success_rate, 0.5);
+        assert_eq!(openai.average_response_time_ms, 300.0);
+        let metrics = state.get_system_metrics();
+        assert_eq!(metrics.by_provider[&ProviderId::OpenAI].success_rate, 0.5);
+        assert_eq!(metrics.by_provider[&ProviderId::OpenAI].avg_response_time_ms, 300.0);
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_sum_verification_spot_checks() {
+        let orchestrator = create_test_orchestrator(MockCommunicator::new());
+
+        // Producer 1 reports twice; only its latest cumulative counters count
+        let reports = [(1, 1, 0.01), (1, 2, 0.02), (2, 3, 0.04)];
+        for (id, conflicts, cost) in reports {
+            let update = ProducerUpdate::StatusUpdate {
+                producer_id: ProcessId::Producer(id),
+                status: ProcessStatus::Running,
+                message: None,
+                performance_stats: Some(shared::messages::producer::ProducerPerformanceStats {
+                    attributes_generated_last_minute: 0,
+                    unique_contributed_last_minute: 0,
+                    requests_made_last_minute: 0,
+                    provider_usage: HashMap::new(),
+                    current_batch_rate: 0.0,
+                    memory_usage_mb: None,
+                    bloom_filter_size_mb: None,
+                    tuned_limits: HashMap::new(),
+                    verification: shared::VerificationStats {
+                        requests: conflicts,
+                        samples: conflicts * 3,
+                        conflicts,
+                        cost,
+                    },
+                }),
+            };
+            orchestrator.handle_producer_update(update).await.unwrap();
+        }
+
+        let verification = orchestrator.state.lock().await.get_system_metrics().verification;
+        assert_eq!(verification.requests, 5);
+        assert_eq!(verification.samples, 15);
+        assert_eq!(verification.conflicts, 5);
+        assert!((verification.cost - 0.06).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_producer_errors_fail_producer_only_when_fatal() {
+        let orchestrator = create_test_orchestrator(MockCommunicator::new());
+        let non_fatal = ProcessId::Producer(1);
+        let fatal = ProcessId::Producer(2);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(non_fatal.clone(), 0, ProcessStatus::Running);
+            state.add_producer(fatal.clone(), 0, ProcessStatus::Running);
+        }
+
+        let reports = [
+            (non_fatal.clone(), shared::ApiFailure::RateLimitExceeded),
+            (non_fatal.clone(), shared::ApiFailure::InvalidRequest),
+            (non_fatal.clone(), shared::ApiFailure::ContentFiltered),
+            (non_fatal.clone(), shared::ApiFailure::InternalError),
+            (fatal.clone(), shared::ApiFailure::InvalidApiKey),
+        ];
+        for (producer_id, failure) in reports {
+            let error = SharedError::from(failure);
+            orchestrator
+                .handle_producer_update(ProducerUpdate::Error {
+                    producer_id,
+                    error_code: error.code,
+                    message: error.message,
+                    command_id: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let state = orchestrator.state.lock().await;
+        assert_eq!(state.failed_producer_ids(), vec![fatal]);
+    }
+
+    #[tokio::test]
+    async fn test_producer_abandoned_by_restart_policy_stops_being_restarted() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        process_manager.expect_promote_warm_producer().returning(|_, _, _| Ok(None));
+        process_manager
+            .expect_restart_producer()
+            .times(1)
+            .returning(|_, _, _, _| Ok(RestartOutcome::GaveUp { attempts: 3 }));
+        let mut orchestrator = create_test_orchestrator_with(communicator, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Failed);
+            state.add_producer(ProcessId::Producer(2), 0, ProcessStatus::Running);
+        }
+
+        // The second health check no longer sees the abandoned producer, so it is not retried
+        orchestrator.check_health().await.unwrap();
+        orchestrator.check_health().await.unwrap();
+
+        let state = orchestrator.state.lock().await;
+        assert!(state.failed_producer_ids().is_empty());
+        assert_eq!(state.active_producer_ids(), vec![ProcessId::Producer(2)]);
+        assert_eq!(state.active_producer_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_healing_a_producer_emits_restarted_lifecycle_event() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let recorded = sent.clone();
+        communicator.expect_send_webserver_update().returning(move |update| {
+            recorded.lock().unwrap().push(update);
+            Ok(())
+        });
+        communicator.expect_register_producer().returning(|_, _| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        process_manager.expect_promote_warm_producer().returning(|_, _, _| Ok(None));
+        process_manager.expect_restart_producer().times(1).returning(|_, _, _, _| {
+            Ok(RestartOutcome::Restarted(crate::traits::ProducerInfo {
+                id: ProcessId::Producer(3),
+                process_id: 42,
+                listen_address: "127.0.0.1:7003".parse().unwrap(),
+                command_address: "127.0.0.1:7003".parse().unwrap(),
+            }))
+        });
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        let (_webserver_tx, webserver_rx) = mpsc::channel(1);
+        orchestrator.webserver_rx = Some(webserver_rx);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Failed);
+        }
+
+        orchestrator.check_health().await.unwrap();
+
+        let lifecycle: Vec<_> = sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|update| match update {
+                OrchestratorUpdate::ProducerLifecycle { producer_id, event } => Some((producer_id.clone(), *event)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lifecycle, vec![(ProcessId::Producer(1), ProducerLifecycleEvent::Restarted)]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_producer_of_concurrent_topic_restarts_on_its_topic() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        communicator.expect_register_producer().returning(|_, _| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        process_manager.expect_promote_warm_producer().returning(|_, _, _| Ok(None));
+        process_manager
+            .expect_restart_producer()
+            .withf(|failed, topic, _, _| *failed == ProcessId::Producer(2) && topic == "plants")
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(RestartOutcome::Restarted(crate::traits::ProducerInfo {
+                    id: ProcessId::Producer(5),
+                    process_id: 42,
+                    listen_address: "127.0.0.1:7005".parse().unwrap(),
+                    command_address: "127.0.0.1:7005".parse().unwrap(),
+                }))
+            });
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.start_concurrent_generation(
+                vec![
+                    ("animals".to_string(), vec![ProcessId::Producer(1)]),
+                    ("plants".to_string(), vec![ProcessId::Producer(2)]),
+                ],
+                OptimizationMode::MaximizeEfficiency,
+                orchestrator.cli_constraints(),
+            );
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Running);
+            state.add_producer(ProcessId::Producer(2), 0, ProcessStatus::Failed);
+        }
+
+        orchestrator.check_health().await.unwrap();
+
+        let mut state = orchestrator.state.lock().await;
+        assert_eq!(state.topic_state("plants").unwrap().producers(), [ProcessId::Producer(5)]);
+        assert_eq!(state.topic_state("animals").unwrap().producers(), [ProcessId::Producer(1)]);
+        match state.take_pending_start_command(&ProcessId::Producer(5)) {
+            Some(OrchestratorCommand::Start { topic, .. }) => assert_eq!(topic, "plants"),
+            other => panic!("expected a queued Start, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_producer_is_promoted_without_spawning() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let registered = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = registered.clone();
+        communicator.expect_register_producer().returning(move |producer_id, addr| {
+            recorded.lock().unwrap().push((producer_id, addr));
+            Ok(())
+        });
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        process_manager
+            .expect_promote_warm_producer()
+            .times(1)
+            .withf(|failed, _, _| *failed == ProcessId::Producer(1))
+            .returning(|_, _, _| {
+                Ok(Some(crate::traits::ProducerInfo {
+                    id: ProcessId::Producer(4),
+                    process_id: 44,
+                    listen_address: "127.0.0.1:7004".parse().unwrap(),
+                    command_address: "127.0.0.1:7004".parse().unwrap(),
+                }))
+            });
+        process_manager.expect_restart_producer().times(0);
+        process_manager.expect_spawn_producers().times(0);
+        communicator.expect_is_producer_ready().returning(|_| true);
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = started.clone();
+        communicator.expect_send_producer_command().returning(move |producer_id, command| {
+            recorded.lock().unwrap().push((producer_id, command));
+            Ok(())
+        });
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Failed);
+            state.add_producer(ProcessId::Producer(2), 0, ProcessStatus::Running);
+        }
+
+        orchestrator.check_health().await.unwrap();
+
+        assert_eq!(
+            *registered.lock().unwrap(),
+            vec![(ProcessId::Producer(4), "127.0.0.1:7004".parse().unwrap())]
+        );
+        // The promoted producer is already up, so it is started straight away rather than on its ready signal
+        let started = started.lock().unwrap().clone();
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].0, ProcessId::Producer(4));
+        assert!(matches!(started[0].1, OrchestratorCommand::Start { .. }));
+        let state = orchestrator.state.lock().await;
+        assert!(state.failed_producer_ids().is_empty());
+        assert_eq!(state.active_producer_count(), 2);
+        assert_eq!(state.stable_producer_id(&ProcessId::Producer(4)), ProcessId::Producer(1));
+    }
+
+    #[tokio::test]
+    async fn test_booting_warm_producer_is_started_on_its_ready_signal() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let recorded = sent.clone();
+        communicator.expect_send_webserver_update().returning(move |update| {
+            recorded.lock().unwrap().push(update);
+            Ok(())
+        });
+        communicator.expect_register_producer().returning(|_, _| Ok(()));
+        communicator.expect_mark_producer_ready().returning(|_, _| Ok(()));
+        communicator.expect_is_producer_ready().returning(|_| false);
+        communicator.expect_send_producer_command().never();
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        process_manager.expect_promote_warm_producer().times(1).returning(|_, _, _| {
+            Ok(Some(crate::traits::ProducerInfo {
+                id: ProcessId::Producer(4),
+                process_id: 44,
+                listen_address: "127.0.0.1:7004".parse().unwrap(),
+                command_address: "127.0.0.1:7004".parse().unwrap(),
+            }))
+        });
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        let (_webserver_tx, webserver_rx) = mpsc::channel(1);
+        orchestrator.webserver_rx = Some(webserver_rx);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Failed);
+        }
+
+        // An idle pool member reporting ready is not yet part of the pool the dashboard shows
+        orchestrator
+            .handle_producer_ready(ProcessId::Producer(9), 7009, shared::PROTOCOL_VERSION)
+            .await
+            .unwrap();
+        assert!(sent.lock().unwrap().is_empty());
+
+        // The promoted producer has not reported ready yet, so its Start waits for that signal
+        orchestrator.check_health().await.unwrap();
+        let mut state = orchestrator.state.lock().await;
+        assert!(matches!(
+            state.take_pending_start_command(&ProcessId::Producer(4)),
+            Some(OrchestratorCommand::Start { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_restarted_producers_keep_a_stable_id_set() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        communicator.expect_register_producer().returning(|_, _| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        // Each replacement comes back under a fresh id, as a counter-based spawner would hand out
+        let next_id = Arc::new(std::sync::atomic::AtomicU32::new(3));
+        process_manager.expect_promote_warm_producer().returning(|_, _, _| Ok(None));
+        process_manager.expect_restart_producer().times(2).returning(move |_, _, _, _| {
+            let id = next_id.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(RestartOutcome::Restarted(crate::traits::ProducerInfo {
+                id: ProcessId::Producer(id),
+                process_id: 40 + id,
+                listen_address: "127.0.0.1:7003".parse().unwrap(),
+                command_address: "127.0.0.1:7003".parse().unwrap(),
+            }))
+        });
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator
+            .api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Failed);
+            state.add_producer(ProcessId::Producer(2), 0, ProcessStatus::Running);
+        }
+
+        orchestrator.check_health().await.unwrap();
+        {
+            let mut state = orchestrator.state.lock().await;
+            assert_eq!(state.active_producer_count(), 2);
+            assert_eq!(state.stable_producer_ids(), vec![ProcessId::Producer(1), ProcessId::Producer(2)]);
+            assert_eq!(state.stable_producer_id(&ProcessId::Producer(3)), ProcessId::Producer(1));
+
+            // The replacement fails in turn; its own replacement still stands in for producer 1
+            state.update_producer_status(ProcessId::Producer(3), ProcessStatus::Failed);
+        }
+        orchestrator.check_health().await.unwrap();
+
+        let state = orchestrator.state.lock().await;
+        assert_eq!(state.active_producer_count(), 2);
+        assert_eq!(state.stable_producer_ids(), vec![ProcessId::Producer(1), ProcessId::Producer(2)]);
+        assert_eq!(state.stable_producer_id(&ProcessId::Producer(4)), ProcessId::Producer(1));
+        assert!(!state.has_producer(&ProcessId::Producer(3)));
+    }
+
+    #[test]
+    fn test_uniqueness_survives_restart_and_ignores_corrupt_file() {
+        ProcessId::init_orchestrator();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(UNIQUENESS_FILE);
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Random,
+            model: "random".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let mut first_run = OrchestratorState::new();
+        first_run.add_attributes(ProcessId::Producer(1), vec!["lion".to_string(), "tiger".to_string()], &metadata, 0.0);
+        first_run.save_uniqueness(&path).unwrap();
+
+        let mut second_run = OrchestratorState::new();
+        assert_eq!(second_run.load_uniqueness(&path), 2);
+        assert_eq!(second_run.get_unique_attribute_count(), 2);
+
+        // Restored values are known but not generated by this run
+        second_run.add_attributes(ProcessId::Producer(1), vec!["lion".to_string(), "bear".to_string()], &metadata, 0.0);
+        second_run.increment_iteration();
+        second_run.context.topic = Some("animals".to_string());
+        let history = second_run.checkpoint().unwrap().cycle_history;
+        assert_eq!(history[0].new_values, 1);
+        assert_eq!(history[0].total_values, 3);
+
+        // A corrupt file is skipped and leaves the fresh tracker untouched
+        std::fs::write(&path, b"garbage").unwrap();
+        let mut third_run = OrchestratorState::new();
+        assert_eq!(third_run.load_uniqueness(&path), 0);
+        assert_eq!(third_run.get_unique_attribute_count(), 0);
+    }
+
+    #[test]
+    fn test_seeded_values_count_as_duplicates() {
+        ProcessId::init_orchestrator();
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Random,
+            model: "random".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let constraints = GenerationConstraints {
+            max_cost_per_minute: 1.0,
+            target_uam: 100.0,
+            max_runtime_seconds: None,
+            min_efficiency_percent: None,
+            efficiency_patience: None,
+            target_unique_count: None,
+        };
+        let mut state = OrchestratorState::new();
+        state.start_generation("animals".to_string(), OptimizationMode::MaximizeEfficiency, constraints);
+        state.set_normalization_mode(crate::core::NormalizationMode::CaseInsensitive);
+        assert_eq!(state.seed_known_attributes(&["Lion".to_string(), "tiger".to_string()]), 2);
+
+        let accepted = state.add_attributes(
+            ProcessId::Producer(1),
+            vec!["lion".to_string(), "TIGER".to_string(), "zebra".to_string()],
+            &metadata,
+            0.0,
+        );
+        assert_eq!(accepted, vec!["zebra".to_string()]);
+
+        // Seeds are known but not generated, so the first cycle only reports the new value
+        state.increment_iteration();
+        let history = state.checkpoint().unwrap().cycle_history;
+        assert_eq!(history[0].new_values, 1);
+        assert_eq!(history[0].total_values, 3);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_run_only_performs_remaining_iterations() {
+        ProcessId::init_orchestrator();
+        let dir = tempfile::tempdir().unwrap();
+        let file_system = crate::services::RealFileSystem::with_base_dir(dir.path().to_path_buf());
+        let constraints = GenerationConstraints {
+            max_cost_per_minute: 1.0,
+            target_uam: 100.0,
+            max_runtime_seconds: None,
+            min_efficiency_percent: None,
+            efficiency_patience: None,
+            target_unique_count: None,
+        };
+
+        // First run crashes after 6 of 10 iterations
+        let mut crashed = OrchestratorState::new();
+        crashed.set_cli_iterations(Some(10));
+        crashed.start_generation("animals".to_string(), OptimizationMode::MaximizeEfficiency, constraints.clone());
+        file_system.create_topic_directory("animals").await.unwrap();
+        for _ in 0..6 {
+            assert!(!crashed.increment_iteration());
+        }
+        crashed.save_checkpoint(&file_system).await.unwrap();
+
+        let checkpoint = Checkpoint::load(&file_system, "animals").await.unwrap().unwrap();
+        assert_eq!(checkpoint.iteration, 6);
+        assert_eq!(checkpoint.cycle_history.len(), 6);
+
+        let mut resumed = OrchestratorState::new();
+        resumed.restore_checkpoint(checkpoint);
+        resumed.set_cli_iterations(Some(10));
+        assert!(resumed.is_resumed());
+        for _ in 0..3 {
+            assert!(!resumed.increment_iteration());
+        }
+        assert!(resumed.increment_iteration());
+        assert_eq!(resumed.get_current_iteration(), 10);
+
+        // A checkpoint cannot be resumed under a different topic
+        std::fs::copy(
+            file_system.topic_file_path("animals", CHECKPOINT_FILE),
+            dir.path().join("checkpoint_for_animals.json"),
+        )
+        .unwrap();
+        file_system.create_topic_directory("plants").await.unwrap();
+        assert!(Checkpoint::load(&file_system, "plants").await.unwrap().is_none());
+        std::fs::rename(
+            dir.path().join("checkpoint_for_animals.json"),
+            file_system.topic_file_path("plants", CHECKPOINT_FILE),
+        )
+        .unwrap();
+        assert!(Checkpoint::load(&file_system, "plants").await.is_err());
+    }
+
+    #[test]
+    fn test_declining_efficiency_stops_after_patience_cycles() {
+        ProcessId::init_orchestrator();
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Random,
+            model: "random".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let mut state = OrchestratorState::new();
+        state.start_generation(
+            "animals".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            GenerationConstraints {
+                max_cost_per_minute: 1.0,
+                target_uam: 100.0,
+                max_runtime_seconds: None,
+                min_efficiency_percent: Some(50.0),
+                efficiency_patience: Some(3),
+                target_unique_count: None,
+            },
+        );
+        let cycle = |state: &mut OrchestratorState, attributes: &[&str]| {
+            let attributes = attributes.iter().map(|a| a.to_string()).collect();
+            state.add_attributes(ProcessId::Producer(1), attributes, &metadata, 0.0);
+            state.increment_iteration()
+        };
+
+        assert!(!cycle(&mut state, &["lion", "tiger"]));
+        // Two dry cycles, then a recovery resets the count
+        assert!(!cycle(&mut state, &["lion"]));
+        assert!(!cycle(&mut state, &["tiger"]));
+        assert!(!cycle(&mut state, &["bear"]));
+        assert!(!cycle(&mut state, &["lion", "bear"]));
+        assert!(!cycle(&mut state, &[]));
+        assert!(!state.efficiency_floor_reached());
+        assert!(cycle(&mut state, &["tiger"]));
+        assert!(state.efficiency_floor_reached());
+        assert!(!state.should_stop_iterations());
+
+        let efficiencies: Vec<f64> = state.checkpoint().unwrap().cycle_history.iter().map(|c| c.efficiency).collect();
+        assert_eq!(efficiencies, vec![100.0, 0.0, 0.0, 100.0, 0.0, 0.0, 0.0]);
+
+        // Without a floor the same dry cycles never stop generation
+        let mut unlimited = OrchestratorState::new();
+        for _ in 0..10 {
+            assert!(!cycle(&mut unlimited, &[]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_stops_once_target_count_is_reached() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system.expect_append_jsonl().returning(|_, _| Ok(()));
+        file_system.expect_append_to_output().returning(|_, _| Ok(()));
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        file_system
+            .expect_topic_file_path()
+            .returning(move |_, name| topic_dir.join(name));
+        let orchestrator = create_test_orchestrator_with_fs(communicator, file_system, MockProcessManager::new())
+            .with_target_count(Some(5));
+        orchestrator.state.lock().await.start_generation(
+            "planets".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::OpenAI,
+            model: "gpt-4o-mini".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let batches = [["mercury", "venus"], ["earth", "mars"], ["jupiter", "saturn"]];
+        for (index, batch) in batches.iter().enumerate() {
+            let batch = batch.iter().map(|planet| planet.to_string()).collect();
+            orchestrator
+                .process_attribute_batch(ProcessId::Producer(1), "planets", batch, metadata.clone(), 0.0)
+                .await
+                .unwrap();
+
+            let state = orchestrator.state.lock().await;
+            if index < 2 {
+                assert_eq!(state.completion_reason(), None, "stopped early at {} attributes", state.total_unique_count());
+            } else {
+                assert_eq!(state.completion_reason(), Some(&CompletionReason::TargetCountReached));
+                assert_eq!(state.total_unique_count(), 6);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_summary_written_to_topic_directory() {
+        ProcessId::init_orchestrator();
+        let dir = tempfile::tempdir().unwrap();
+        let file_system = crate::services::RealFileSystem::with_base_dir(dir.path().to_path_buf());
+        file_system.create_topic_directory("animals").await.unwrap();
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Random,
+            model: "random".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let mut state = OrchestratorState::new();
+        state.set_cli_iterations(Some(1));
+        state.start_generation(
+            "animals".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            GenerationConstraints {
+                max_cost_per_minute: 1.0,
+                target_uam: 100.0,
+                max_runtime_seconds: None,
+                min_efficiency_percent: None,
+                efficiency_patience: None,
+                target_unique_count: None,
+            },
+        );
+        state.add_attributes(ProcessId::Producer(1), vec!["lion".to_string(), "tiger".to_string()], &metadata, 0.0);
+        assert!(state.increment_iteration());
+        state
+            .export_run_summary(&file_system, CompletionReason::IterationLimitReached)
+            .await
+            .unwrap();
+
+        let content = std::fs::read(file_system.topic_file_path("animals", crate::core::state::SUMMARY_FILE)).unwrap();
+        let summary: serde_json::Value = serde_json::from_slice(&content).unwrap();
+        assert_eq!(summary["topic"], "animals");
+        assert_eq!(summary["total_iterations"], 1);
+        assert_eq!(summary["final_unique_count"], 2);
+        assert_eq!(summary["completion_reason"], "iteration_limit_reached");
+        assert!(summary["started_at"].as_str().unwrap() <= summary["finished_at"].as_str().unwrap());
+        assert_eq!(summary["cycle_performance"]["total_cycles"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_limit_completes_generation_with_time_limit_reason() {
+        let mut communicator = MockCommunicator::new();
+        communicator
+            .expect_send_webserver_update()
+            .withf(|update| {
+                matches!(
+                    update,
+                    OrchestratorUpdate::GenerationComplete {
+                        completion_reason: CompletionReason::TimeLimitReached,
+                        ..
+                    }
+                )
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, MockProcessManager::new());
+        let (_webserver_tx, webserver_rx) = mpsc::channel(1);
+        orchestrator.webserver_rx = Some(webserver_rx);
+
+        orchestrator.state.lock().await.start_generation(
+            "animals".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            GenerationConstraints {
+                max_cost_per_minute: 1.0,
+                target_uam: 100.0,
+                max_runtime_seconds: Some(0),
+                min_efficiency_percent: None,
+                efficiency_patience: None,
+                target_unique_count: None,
+            },
+        );
+
+        orchestrator.check_runtime_limit().await;
+        // Already completed, so later ticks do nothing
+        orchestrator.check_runtime_limit().await;
+
+        assert_eq!(
+            orchestrator.state.lock().await.completion_reason(),
+            Some(&CompletionReason::TimeLimitReached)
+        );
+        assert!(orchestrator.shutdown_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_loop_sends_statistics_at_configured_interval() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let statistics_updates = Arc::new(AtomicUsize::new(0));
+        let counter = statistics_updates.clone();
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(move |update| {
+            if matches!(update, OrchestratorUpdate::StatisticsUpdate { .. }) {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(())
+        });
+        communicator.expect_send_producer_command().returning(|_, _| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        let mut orchestrator = create_test_orchestrator_with(communicator, process_manager).with_loop_intervals(
+            LoopIntervals {
+                metrics: Duration::from_millis(1),
+                health: Duration::from_secs(60),
+                flush: Duration::from_secs(60),
+            },
+        );
+        assert_eq!(orchestrator.loop_intervals.metrics, MIN_LOOP_INTERVAL);
+
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.start_generation(
+                "animals".to_string(),
+                OptimizationMode::MaximizeEfficiency,
+                GenerationConstraints {
+                    max_cost_per_minute: 1.0,
+                    target_uam: 100.0,
+                    max_runtime_seconds: None,
+                    min_efficiency_percent: None,
+                    efficiency_patience: None,
+                    target_unique_count: None,
+                },
+            );
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Running);
+        }
+
+        // Ticks at 0s, 1s and 2s on the paused clock; the 3s default would only have fired once
+        let _ = tokio::time::timeout(Duration::from_millis(2500), orchestrator.run()).await;
+        assert_eq!(statistics_updates.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_loop_flushes_output_without_an_iteration_boundary() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let flushes = Arc::new(AtomicUsize::new(0));
+        let counter = flushes.clone();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_sync_to_disk().returning(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_check_process_health().returning(|| Ok(Vec::new()));
+        let mut orchestrator =
+            create_test_orchestrator_with_fs(communicator, file_system, process_manager).with_loop_intervals(LoopIntervals {
+                metrics: Duration::from_secs(60),
+                health: Duration::from_secs(60),
+                flush: Duration::from_secs(1),
+            });
+
+        // No batch is ever processed, yet the flush fires once within 1.5s
+        let _ = tokio::time::timeout(Duration::from_millis(1500), orchestrator.run()).await;
+        assert_eq!(flushes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_is_written_after_buffered_output_is_flushed() {
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut file_system = MockFileSystem::new();
+        let log = events.clone();
+        file_system.expect_sync_to_disk().returning(move || {
+            log.lock().unwrap().push("sync".to_string());
+            Ok(())
+        });
+        let log = events.clone();
+        file_system.expect_write_file().returning(move |name, _| {
+            log.lock().unwrap().push(name.to_string());
+            Ok(())
+        });
+        let log = events.clone();
+        file_system.expect_topic_file_path().returning(move |_, name| {
+            log.lock().unwrap().push(name.to_string());
+            topic_dir.join(name)
+        });
+        let orchestrator =
+            create_test_orchestrator_with_fs(MockCommunicator::new(), file_system, MockProcessManager::new());
+        orchestrator.state.lock().await.start_generation(
+            "animals".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        orchestrator.flush_and_checkpoint().await.unwrap();
+        assert_eq!(*events.lock().unwrap(), vec!["sync", "animals/checkpoint.json", UNIQUENESS_FILE]);
+        assert!(dir.path().join(UNIQUENESS_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn test_batch_moves_producers_to_next_topic_and_writes_batch_summary() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let sent_clone = sent.clone();
+        communicator.expect_send_producer_command().returning(move |_, command| {
+            sent_clone.lock().unwrap().push(command);
+            Ok(())
+        });
+        communicator.expect_shutdown().returning(|| Ok(()));
+
+        let written = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut file_system = MockFileSystem::new();
+        let written_clone = written.clone();
+        file_system.expect_write_file().returning(move |name, content| {
+            written_clone.lock().unwrap().insert(name.to_string(), content.to_vec());
+            Ok(())
+        });
+        let appended = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let appended_clone = appended.clone();
+        file_system.expect_append_file().returning(move |name, content| {
+            appended_clone.lock().unwrap().push((name.to_string(), content.to_vec()));
+            Ok(())
+        });
+        file_system.expect_check_writable().returning(|| Ok(()));
+        file_system.expect_free_space().returning(|| Ok(None));
+        file_system
+            .expect_topic_file_path()
+            .returning(|topic, name| std::path::PathBuf::from("/nonexistent").join(topic).join(name));
+        file_system
+            .expect_create_topic_directory()
+            .withf(|topic| topic == "plants")
+            .times(1)
+            .returning(|_| Ok(()));
+        file_system.expect_sync_to_disk().returning(|| Ok(()));
+
+        let mut api_keys = MockApiKeySource::new();
+        api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_stop_all().returning(|| Ok(()));
+
+        ProcessId::init_orchestrator();
+        let mut orchestrator = Orchestrator::new(api_keys, communicator, file_system, process_manager, BasicOptimizer::new());
+        orchestrator.queue_batch_topics(vec!["plants".to_string()], 10, None, None);
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Random,
+            model: "random".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_producer(ProcessId::Producer(1), 1, ProcessStatus::Running);
+            state.set_cli_iterations(Some(1));
+            let mode = OptimizationMode::MinimizeCost { target_uam: 250.0 };
+            let constraints = TestOrchestrator::constraints_for_mode(orchestrator.cli_constraints(), &mode);
+            state.start_generation("animals".to_string(), mode, constraints);
+            state.add_attributes(ProcessId::Producer(1), vec!["lion".to_string()], &metadata, 0.0);
+            assert!(state.increment_iteration());
+            orchestrator.complete_generation(&mut state, CompletionReason::IterationLimitReached).await;
+        }
+
+        // Topics remain, so completion advances the batch rather than shutting down
+        assert!(orchestrator.next_topic_rx.try_recv().is_ok());
+        assert!(orchestrator.shutdown_rx.try_recv().is_err());
+
+        orchestrator.start_next_batch_topic().await.unwrap();
+        {
+            let state = orchestrator.state.lock().await;
+            assert_eq!(state.context.topic.as_deref(), Some("plants"));
+            assert_eq!(state.get_current_iteration(), 0);
+            assert_eq!(state.total_unique_count(), 0);
+            assert!(state.completion_reason().is_none());
+            let targets = &state.context.optimization_targets;
+            assert!(matches!(targets.optimization_mode, OptimizationMode::MinimizeCost { target_uam } if target_uam == 250.0));
+            assert_eq!(targets.min_uam, 250.0);
+        }
+        let commands = std::mem::take(&mut *sent.lock().unwrap());
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(commands[0], OrchestratorCommand::Pause { .. }));
+        assert!(matches!(
+            &commands[1],
+            OrchestratorCommand::Start { topic, generation_config, .. } if topic == "plants" && generation_config.request_size == 10
+        ));
+
+        // The next topic's start prompt is logged under its own directory, not shared with the first topic
+        let prompt_files: Vec<String> = appended.lock().unwrap().iter().map(|(name, _)| name.clone()).collect();
+        assert_eq!(prompt_files, vec!["plants/prompts.jsonl"]);
+        let record: crate::core::state::PromptRecord =
+            serde_json::from_slice(appended.lock().unwrap()[0].1.trim_ascii_end()).unwrap();
+        assert_eq!(record.source, "start");
+        assert_eq!(record.iteration, 0);
+
+        // A batch generated for the previous topic while the switch was in flight is dropped
+        orchestrator
+            .handle_producer_update(ProducerUpdate::AttributeBatch {
+                producer_id: ProcessId::Producer(1),
+                batch_id: 7,
+                attributes: vec!["zebra".to_string()],
+                provider_metadata: metadata.clone(),
+                estimated_cost: 0.0,
+                topic: "animals".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(orchestrator.state.lock().await.total_unique_count(), 0);
+
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.add_attributes(ProcessId::Producer(1), vec!["oak".to_string(), "fern".to_string()], &metadata, 0.0);
+            assert!(state.increment_iteration());
+            orchestrator.complete_generation(&mut state, CompletionReason::IterationLimitReached).await;
+        }
+        assert!(orchestrator.shutdown_rx.try_recv().is_ok());
+        orchestrator.shutdown().await.unwrap();
+
+        let content = written.lock().unwrap()[crate::core::state::BATCH_SUMMARY_FILE].clone();
+        let summary: serde_json::Value = serde_json::from_slice(&content).unwrap();
+        assert_eq!(summary["total_topics"], 2);
+        assert_eq!(summary["completed_topics"], 2);
+        assert_eq!(summary["total_unique_count"], 3);
+        assert_eq!(summary["topics"][0]["topic"], "animals");
+        assert_eq!(summary["topics"][1]["topic"], "plants");
+    }
+
+    #[tokio::test]
+    async fn test_new_attributes_forward_batch_provider_metadata() {
+        let mut communicator = MockCommunicator::new();
+        communicator
+            .expect_send_webserver_update()
+            .withf(|update| {
+                matches!(
+                    update,
+                    OrchestratorUpdate::NewAttributes { provider_metadata: Some(metadata), .. }
+                        if metadata.provider_id == ProviderId::OpenAI && metadata.model == "gpt-4o-mini"
+                )
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system
+            .expect_append_jsonl()
+            .withf(|_, records| records.len() == 2)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        file_system.expect_append_to_output().returning(|_, _| Ok(()));
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        file_system
+            .expect_topic_file_path()
+            .returning(move |_, name| topic_dir.join(name));
+        let orchestrator = create_test_orchestrator_with_fs(communicator, file_system, MockProcessManager::new());
+        orchestrator.state.lock().await.start_generation(
+            "animals".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::OpenAI,
+            model: "gpt-4o-mini".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        orchestrator
+            .process_attribute_batch(ProcessId::Producer(1), "animals", vec!["lion".to_string(), "tiger".to_string()], metadata, 0.0)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tracked_cost_matches_producer_estimate() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system.expect_append_jsonl().returning(|_, _| Ok(()));
+        file_system.expect_append_to_output().returning(|_, _| Ok(()));
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        file_system
+            .expect_topic_file_path()
+            .returning(move |_, name| topic_dir.join(name));
+        let orchestrator = create_test_orchestrator_with_fs(communicator, file_system, MockProcessManager::new());
+        orchestrator.state.lock().await.start_generation(
+            "animals".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        // Token counts a cost model would price very differently from the producer's figure
+        let update = ProducerUpdate::AttributeBatch {
+            producer_id: ProcessId::Producer(1),
+            batch_id: 1,
+            attributes: vec!["lion".to_string(), "tiger".to_string()],
+            provider_metadata: shared::ProviderMetadata {
+                provider_id: ProviderId::Anthropic,
+                model: "claude-3-haiku".to_string(),
+                response_time_ms: 800,
+                tokens: shared::types::TokenUsage {
+                    input_tokens: 1000,
+                    output_tokens: 1000,
+                },
+                request_timestamp: 0,
+            },
+            estimated_cost: 0.25,
+            topic: "animals".to_string(),
+        };
+        orchestrator.handle_producer_update(update).await.unwrap();
+
+        let stats = orchestrator.state.lock().await.get_performance_stats();
+        assert_eq!(stats.by_provider[&ProviderId::Anthropic].unique_per_dollar, 2.0 / 0.25);
+        assert_eq!(stats.by_producer[&ProcessId::Producer(1)].unique_per_dollar, 2.0 / 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_no_dedup_keeps_duplicates_in_output() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+
+        let output = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system.expect_append_jsonl().returning(|_, _| Ok(()));
+        let written = output.clone();
+        file_system.expect_append_to_output().returning(move |_, items| {
+            written.lock().unwrap().extend_from_slice(items);
+            Ok(())
+        });
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        file_system
+            .expect_topic_file_path()
+            .returning(move |_, name| topic_dir.join(name));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, MockProcessManager::new());
+        orchestrator.set_dedup_enabled(false).await;
+        orchestrator.state.lock().await.start_generation(
+            "coin flips".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::OpenAI,
+            model: "gpt-4o-mini".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        for batch in [vec!["heads", "tails", "heads"], vec!["heads"]] {
+            let batch = batch.into_iter().map(String::from).collect();
+            orchestrator
+                .process_attribute_batch(ProcessId::Producer(1), "coin flips", batch, metadata.clone(), 0.0)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(*output.lock().unwrap(), vec!["heads", "tails", "heads", "heads"]);
+        let state = orchestrator.state.lock().await;
+        assert_eq!(state.raw_attribute_count(), 4);
+        assert_eq!(state.total_unique_count(), 4);
+        assert!(state.get_bloom_filter_data().is_none());
+        assert_eq!(state.get_performance_stats().overall.uniqueness_ratio, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_stop_reply_reports_final_statistics() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let recorded = sent.clone();
+        communicator.expect_send_webserver_update().returning(move |update| {
+            recorded.lock().unwrap().push(update);
+            Ok(())
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system.expect_append_jsonl().returning(|_, _| Ok(()));
+        file_system.expect_append_to_output().returning(|_, _| Ok(()));
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        file_system.expect_sync_to_disk().returning(|| Ok(()));
+        file_system
+            .expect_topic_file_path()
+            .returning(move |_, name| topic_dir.join(name));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_stop_all().times(1).returning(|| Ok(()));
+        let orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator.state.lock().await.start_generation(
+            "planets".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::OpenAI,
+            model: "gpt-4o-mini".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let batch = vec!["mercury".to_string(), "venus".to_string(), "mercury".to_string()];
+        orchestrator
+            .process_attribute_batch(ProcessId::Producer(1), "planets", batch, metadata, 0.0)
+            .await
+            .unwrap();
+        orchestrator
+            .handle_webserver_request(WebServerRequest::StopGeneration {
+                request_id: 9,
+                mode: StopMode::Immediate,
+            })
+            .await
+            .unwrap();
+
+        let sent = sent.lock().unwrap();
+        let report = sent
+            .iter()
+            .find_map(|update| match update {
+                OrchestratorUpdate::StopReport { request_id: 9, report } => Some(report),
+                _ => None,
+            })
+            .expect("stop request is answered with a report");
+        assert_eq!(report.total_unique_attributes, 2);
+        assert_eq!(report.completion_reason, Some(CompletionReason::ManualStop));
+    }
+
+    #[tokio::test]
+    async fn test_initial_generation_config_carries_attribute_length_bounds_and_seen_hint() {
+        ProcessId::init_orchestrator();
+        let optimizer = BasicOptimizer::new()
+            .with_attribute_length_bounds(Some(3), Some(40))
+            .with_seen_hint(Some(20));
+        let orchestrator = Orchestrator::new(
+            MockApiKeySource::new(),
+            MockCommunicator::new(),
+            MockFileSystem::new(),
+            MockProcessManager::new(),
+            optimizer,
+        );
+
+        let api_keys = HashMap::from([(ProviderId::Random, "random".to_string())]);
+        let (_, _, generation_config) = orchestrator.plan_initial_config("planets", 2, 60, &api_keys).await.unwrap();
+        assert_eq!(generation_config.min_attribute_length, Some(3));
+        assert_eq!(generation_config.max_attribute_length, Some(40));
+        assert!(generation_config.include_seen_hint);
+        assert_eq!(generation_config.seen_hint_count, 20);
+    }
+
+    #[tokio::test]
+    async fn test_optimization_plan_request_reports_rationale_once_generation_starts() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let recorded = sent.clone();
+        communicator.expect_send_webserver_update().returning(move |update| {
+            recorded.lock().unwrap().push(update);
+            Ok(())
+        });
+        let orchestrator = create_test_orchestrator_with_fs(communicator, MockFileSystem::new(), MockProcessManager::new());
+        let plan_reply = |request_id: u64| {
+            sent.lock().unwrap().iter().find_map(|update| match update {
+                OrchestratorUpdate::OptimizationPlan { request_id: id, plan } if *id == request_id => Some(plan.clone()),
+                _ => None,
+            })
+        };
+
+        orchestrator
+            .handle_webserver_request(WebServerRequest::GetOptimizationPlan { request_id: 1 })
+            .await
+            .unwrap();
+        assert_eq!(plan_reply(1), Some(None), "no plan before generation starts");
+
+        orchestrator.state.lock().await.start_generation(
+            "planets".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+        let api_keys = HashMap::from([(ProviderId::OpenAI, "key".to_string())]);
+        orchestrator.plan_initial_config("planets", 2, 60, &api_keys).await.unwrap();
+        orchestrator
+            .handle_webserver_request(WebServerRequest::GetOptimizationPlan { request_id: 2 })
+            .await
+            .unwrap();
+
+        let plan = plan_reply(2).flatten().expect("plan is reported after generation starts");
+        assert!(!plan.rationale.is_empty());
+        assert!(!plan.strategy_name.is_empty());
+        assert!((0.0..=1.0).contains(&plan.confidence));
+    }
+
+    #[tokio::test]
+    async fn test_stop_after_current_iteration_writes_in_progress_batch_before_stopping() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+
+        let output = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let dir = tempfile::tempdir().unwrap();
+        let topic_dir = dir.path().to_path_buf();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system.expect_append_jsonl().returning(|_, _| Ok(()));
+        let written = output.clone();
+        file_system.expect_append_to_output().returning(move |_, items| {
+            written.lock().unwrap().extend_from_slice(items);
+            Ok(())
+        });
+        file_system.expect_write_file().returning(|_, _| Ok(()));
+        file_system.expect_sync_to_disk().returning(|| Ok(()));
+        file_system
+            .expect_topic_file_path()
+            .returning(move |_, name| topic_dir.join(name));
+        let stopped = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut process_manager = MockProcessManager::new();
+        let stop_flag = stopped.clone();
+        process_manager.expect_stop_all().times(1).returning(move || {
+            stop_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        });
+        let orchestrator = create_test_orchestrator_with_fs(communicator, file_system, process_manager);
+        orchestrator.state.lock().await.start_generation(
+            "planets".to_string(),
+            OptimizationMode::MaximizeEfficiency,
+            orchestrator.cli_constraints(),
+        );
+
+        orchestrator
+            .handle_webserver_request(WebServerRequest::StopGeneration {
+                request_id: 7,
+                mode: StopMode::AfterCurrentIteration,
+            })
+            .await
+            .unwrap();
+        assert!(!stopped.load(std::sync::atomic::Ordering::SeqCst), "Producers keep running until the iteration lands");
+        assert_eq!(orchestrator.state.lock().await.context.topic.as_deref(), Some("planets"));
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::OpenAI,
+            model: "gpt-4o-mini".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let batch = vec!["mercury".to_string(), "venus".to_string()];
+        orchestrator
+            .process_attribute_batch(ProcessId::Producer(1), "planets", batch, metadata, 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(*output.lock().unwrap(), vec!["mercury", "venus"]);
+        assert!(stopped.load(std::sync::atomic::Ordering::SeqCst));
+        let state = orchestrator.state.lock().await;
+        assert!(state.context.topic.is_none());
+        assert!(!state.stop_after_iteration_requested());
+    }
+
+    fn confident_optimization(prompt: &str) -> OptimizationResult {
+        use crate::optimization::{ExpectedImpact, OptimizationAssessment, OptimizationMetadata, PromptAssignments};
+
+        OptimizationResult {
+            prompt_assignments: PromptAssignments::uniform(prompt.to_string()),
+            routing_strategy: RoutingStrategy::Backoff {
+                provider: shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+            },
+            generation_config: GenerationConfig {
+                model: "random".to_string(),
+                batch_size: 1,
+                context_window: 4096,
+                max_tokens: 100,
+                temperature: 0.7,
+                request_size: 10,
+                extraction_format: None,
+                system_prompt: None,
+                min_attribute_length: None,
+                max_attribute_length: None,
+                include_seen_hint: false,
+                seen_hint_count: 0,
+            },
+            assessment: OptimizationAssessment {
+                confidence: 0.9,
+                expected_impact: ExpectedImpact {
+                    uam_change_percent: 0.0,
+                    cost_change_percent: 0.0,
+                    quality_change_percent: 0.0,
+                    time_to_effect_seconds: 0,
+                },
+                rationale: "test".to_string(),
+                metadata: OptimizationMetadata {
+                    strategy_name: "test".to_string(),
+                    techniques_applied: vec![],
+                    risk_factors: vec![],
+                    timestamp: std::time::Instant::now(),
+                },
+            },
+        }
+    }
+
+    /// Orchestrator whose optimizer always recommends `prompt` with the given confidence
+    fn create_optimizing_orchestrator(
+        communicator: MockCommunicator,
+        prompt: &'static str,
+        confidence: f64,
+        cooldown: Duration,
+    ) -> Orchestrator<MockApiKeySource, MockCommunicator, MockFileSystem, MockProcessManager, crate::optimization::traits::MockOptimizerStrategy> {
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_append_file().returning(|_, _| Ok(()));
+        let mut optimizer = crate::optimization::traits::MockOptimizerStrategy::new();
+        optimizer.expect_optimize().returning(move |_| {
+            let mut result = confident_optimization(prompt);
+            result.assessment.confidence = confidence;
+            Ok(result)
+        });
+
+        ProcessId::init_orchestrator();
+        Orchestrator::new(MockApiKeySource::new(), communicator, file_system, MockProcessManager::new(), optimizer)
+            .with_reconfigure_cooldown(cooldown)
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_reconfigures_only_changed_producers_after_cooldown() {
+        let updated = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let sent = updated.clone();
+        communicator.expect_send_producer_command().returning(move |producer_id, _| {
+            sent.lock().unwrap().push(producer_id);
+            Ok(())
+        });
+        let orchestrator = create_optimizing_orchestrator(communicator, "better prompt", 0.9, Duration::from_secs(60));
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 1, ProcessStatus::Running);
+            state.add_producer(ProcessId::Producer(2), 2, ProcessStatus::Running);
+            // Producer 2 already runs the recommended configuration
+            let current = confident_optimization("better prompt");
+            state.record_delivered_command(
+                &ProcessId::Producer(2),
+                &OrchestratorCommand::Start {
+                    command_id: 1,
+                    topic: "animals".to_string(),
+                    prompt: "better prompt".to_string(),
+                    routing_strategy: current.routing_strategy,
+                    generation_config: current.generation_config,
+                    seed: 0,
+                    max_cost_per_minute: None,
+                    dedup: true,
+                    category: None,
+                },
+            );
+        }
+
+        orchestrator.optimize_and_sync().await.unwrap();
+        assert_eq!(*updated.lock().unwrap(), vec![ProcessId::Producer(1)]);
+
+        // Within the cooldown nothing is sent, even to a producer that would change
+        orchestrator.state.lock().await.add_producer(ProcessId::Producer(3), 3, ProcessStatus::Running);
+        orchestrator.optimize_and_sync().await.unwrap();
+        assert_eq!(updated.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_ignores_recommendations_below_confidence_threshold() {
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_producer_command().never();
+        let orchestrator = create_optimizing_orchestrator(communicator, "risky prompt", 0.8, Duration::ZERO);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 1, ProcessStatus::Running);
+        }
+
+        orchestrator.optimize_and_sync().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prompt_record_written_per_optimization_cycle() {
+        let sent_prompts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let sent = sent_prompts.clone();
+        communicator.expect_send_producer_command().returning(move |_, command| {
+            if let OrchestratorCommand::UpdateConfig { prompt: Some(prompt), .. } = command {
+                sent.lock().unwrap().push(prompt);
+            }
+            Ok(())
+        });
+
+        let exported = Arc::new(std::sync::Mutex::new(String::new()));
+        let mut file_system = MockFileSystem::new();
+        let written = exported.clone();
+        file_system
+            .expect_append_file()
+            .withf(|filename, _| filename == "animals/prompts.jsonl")
+            .returning(move |_, content| {
+                written.lock().unwrap().push_str(std::str::from_utf8(content).unwrap());
+                Ok(())
+            });
+
+        let cycle = std::sync::atomic::AtomicUsize::new(0);
+        let mut optimizer = crate::optimization::traits::MockOptimizerStrategy::new();
+        optimizer.expect_optimize().returning(move |_| {
+            let n = cycle.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(confident_optimization(&format!("prompt for cycle {n}")))
+        });
+
+        ProcessId::init_orchestrator();
+        let orchestrator = Orchestrator::new(
+            MockApiKeySource::new(),
+            communicator,
+            file_system,
+            MockProcessManager::new(),
+            optimizer,
+        )
+        .with_reconfigure_cooldown(Duration::ZERO);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.topic = Some("animals".to_string());
+            state.add_producer(ProcessId::Producer(1), 0, ProcessStatus::Running);
+        }
+
+        orchestrator.optimize_and_sync().await.unwrap();
+        orchestrator.optimize_and_sync().await.unwrap();
+
+        let records: Vec<crate::core::state::PromptRecord> = exported
+            .lock()
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let sent_prompts = sent_prompts.lock().unwrap().clone();
+        assert_eq!(records.len(), 2, "one record per optimization cycle");
+        assert_eq!(sent_prompts, vec!["prompt for cycle 0", "prompt for cycle 1"]);
+        for (record, sent) in records.iter().zip(&sent_prompts) {
+            assert_eq!(&record.prompt, sent);
+            assert_eq!(record.source, "optimization");
+            assert_eq!(record.provider, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_partitioned_prompts_start_with_category_and_write_per_category_output() {
+        use crate::optimization::{ParameterOverrides, ProducerAssignment, PromptAssignments};
+
+        let assignment = |prompt: &str| ProducerAssignment {
+            prompt: prompt.to_string(),
+            parameter_overrides: ParameterOverrides::default(),
+            rationale: "test".to_string(),
+        };
+        let assignments = HashMap::from([
+            (ProviderId::OpenAI, assignment("List wild animals")),
+            (ProviderId::Anthropic, assignment("List farm animals")),
+        ]);
+        let mut optimizer = crate::optimization::traits::MockOptimizerStrategy::new();
+        optimizer.expect_optimize().returning(move |_| {
+            Ok(OptimizationResult {
+                prompt_assignments: PromptAssignments::custom(assignments.clone()),
+                ..confident_optimization("unused")
+            })
+        });
+        let mut api_keys = MockApiKeySource::new();
+        api_keys
+            .expect_get_api_keys()
+            .returning(|| Ok(HashMap::from([(ProviderId::Random, "random".to_string())])));
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_register_producer().returning(|_, _| Ok(()));
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let mut process_manager = MockProcessManager::new();
+        process_manager.expect_spawn_producers().returning(|count, _, _, _, _| {
+            Ok((1..=count)
+                .map(|n| crate::traits::ProducerInfo {
+                    id: ProcessId::Producer(n),
+                    process_id: n,
+                    listen_address: "127.0.0.1:7001".parse().unwrap(),
+                    command_address: "127.0.0.1:7001".parse().unwrap(),
+                })
+                .collect())
+        });
+
+        ProcessId::init_orchestrator();
+        let dir = tempfile::tempdir().unwrap();
+        let file_system = crate::services::RealFileSystem::with_base_dir(dir.path().to_path_buf());
+        let mut orchestrator = Orchestrator::new(api_keys, communicator, file_system, process_manager, optimizer);
+        orchestrator.producer_addr = Some("127.0.0.1:6001".parse().unwrap());
+        orchestrator
+            .start_cli_generation(
+                "animals".to_string(),
+                2,
+                Some(10),
+                10,
+                OptimizationMode::MaximizeEfficiency,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Each producer is started on its own partition, named after the provider it was planned for
+        {
+            let mut state = orchestrator.state.lock().await;
+            for (producer_id, expected_category, expected_prompt) in [
+                (ProcessId::Producer(1), "openai", "List wild animals"),
+                (ProcessId::Producer(2), "anthropic", "List farm animals"),
+            ] {
+                let command = state.take_pending_start_command(&producer_id).unwrap();
+                let OrchestratorCommand::Start { prompt, category, .. } = &command else {
+                    panic!("expected Start");
+                };
+                assert_eq!(category.as_deref(), Some(expected_category));
+                assert!(prompt.contains(expected_prompt), "{prompt}");
+                state.record_delivered_command(&producer_id, &command);
+            }
+        }
+
+        for (n, batch) in [(1, ["wolf", "lynx"]), (2, ["cow", "goat"])] {
+            let metadata = shared::ProviderMetadata {
+                provider_id: ProviderId::Random,
+                model: "random".to_string(),
+                response_time_ms: 1,
+                tokens: shared::types::TokenUsage::default(),
+                request_timestamp: 0,
+            };
+            let batch = batch.iter().map(|value| value.to_string()).collect();
+            orchestrator
+                .process_attribute_batch(ProcessId::Producer(n), "animals", batch, metadata, 0.0)
+                .await
+                .unwrap();
+        }
+
+        let by_category = dir.path().join("animals").join("by_category");
+        assert_eq!(std::fs::read_to_string(by_category.join("openai.txt")).unwrap(), "wolf\nlynx\n");
+        assert_eq!(std::fs::read_to_string(by_category.join("anthropic.txt")).unwrap(), "cow\ngoat\n");
+    }
+
+    #[tokio::test]
+    async fn test_compressed_sync_check_round_trips_seen_values() {
+        let plain = create_test_orchestrator(MockCommunicator::new());
+        let compressed = create_test_orchestrator(MockCommunicator::new()).with_compress_sync(true);
+        let attributes: Vec<String> = (0..500).map(|i| format!("attribute {i}")).collect();
+        for orchestrator in [&plain, &compressed] {
+            let mut state = orchestrator.state.lock().await;
+            state.context.requires_bloom_filter = true;
+            let metadata = shared::ProviderMetadata {
+                provider_id: ProviderId::Random,
+                model: "random".to_string(),
+                response_time_ms: 1,
+                tokens: shared::types::TokenUsage::default(),
+                request_timestamp: 0,
+            };
+            state.process_attribute_batch(ProcessId::Producer(1), metadata, attributes.clone(), 0.0).unwrap();
+        }
+
+        let OrchestratorCommand::SyncCheck { bloom_filter: plain_bloom, seen_values: plain_values, compressed: false, .. } =
+            plain.sync_check_command(1).await.unwrap()
+        else {
+            panic!("expected uncompressed SyncCheck");
+        };
+        let OrchestratorCommand::SyncCheck { bloom_filter, seen_values: None, compressed: true, compressed_seen_values, .. } =
+            compressed.sync_check_command(1).await.unwrap()
+        else {
+            panic!("expected compressed SyncCheck");
+        };
+        assert!(compressed_seen_values.as_ref().unwrap().len() < plain_values.as_ref().unwrap().concat().len());
+
+        let (restored_bloom, restored_values) =
+            shared::messages::sync::decompress_sync_payload(bloom_filter.as_deref(), compressed_seen_values.as_deref())
+                .unwrap();
+        assert_eq!(restored_bloom, plain_bloom);
+        assert_eq!(restored_values, plain_values);
+        assert_eq!(restored_values.unwrap().len(), attributes.len());
+    }
+
+    #[tokio::test]
+    async fn test_producers_behind_on_the_bloom_filter_get_a_compressed_sync() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        let log = sent.clone();
+        communicator.expect_send_producer_command().returning(move |producer_id, command| {
+            log.lock().unwrap().push((producer_id, command));
+            Ok(())
+        });
+        let orchestrator = create_test_orchestrator(communicator).with_compress_sync(true);
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.context.requires_bloom_filter = true;
+            state.add_producer(ProcessId::Producer(1), 1, ProcessStatus::Running);
+            let metadata = shared::ProviderMetadata {
+                provider_id: ProviderId::Random,
+                model: "random".to_string(),
+                response_time_ms: 1,
+                tokens: shared::types::TokenUsage::default(),
+                request_timestamp: 0,
+            };
+            state.process_attribute_batch(ProcessId::Producer(1), metadata, vec!["heron".to_string()], 0.0).unwrap();
+        }
+
+        orchestrator.sync_producers().await.unwrap();
+        assert!(matches!(
+            sent.lock().unwrap().as_slice(),
+            [(ProcessId::Producer(1), OrchestratorCommand::SyncCheck { compressed: true, .. })]
+        ));
+
+        // Nothing is resent until the bloom filter changes
+        orchestrator.sync_producers().await.unwrap();
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_topics_keep_separate_unique_sets() {
+        let stopped = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut communicator = MockCommunicator::new();
+        communicator.expect_send_webserver_update().returning(|_| Ok(()));
+        let stopped_clone = stopped.clone();
+        communicator.expect_send_producer_command().returning(move |producer_id, command| {
+            assert!(matches!(command, OrchestratorCommand::Stop { .. }));
+            stopped_clone.lock().unwrap().push(producer_id);
+            Ok(())
+        });
+
+        let output = Arc::new(std::sync::Mutex::new(HashMap::<String, Vec<String>>::new()));
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path().to_path_buf();
+        let mut file_system = MockFileSystem::new();
+        file_system.expect_write_unique_attributes_with_metadata().returning(|_, _, _| Ok(()));
+        file_system.expect_append_csv_rows().returning(|_, _, _, _, _| Ok(()));
+        file_system.expect_append_jsonl().returning(|_, _| Ok(()));
+        let output_clone = output.clone();
+        file_system.expect_append_to_output().returning(move |topic, items| {
+            output_clone
+                .lock()
+                .unwrap()
+                .entry(topic.to_string())
+                .or_default()
+                .extend(items.iter().cloned());
+            Ok(())
+        });
+        file_system
+            .expect_topic_file_path()
+            .returning(move |topic, name| output_dir.join(format!("{topic}-{name}")));
+        let mut orchestrator = create_test_orchestrator_with_fs(communicator, file_system, MockProcessManager::new());
+        {
+            let mut state = orchestrator.state.lock().await;
+            state.set_cli_iterations(Some(2));
+            state.start_concurrent_generation(
+                vec![
+                    ("animals".to_string(), vec![ProcessId::Producer(1)]),
+                    ("plants".to_string(), vec![ProcessId::Producer(2)]),
+                ],
+                OptimizationMode::MaximizeEfficiency,
+                orchestrator.cli_constraints(),
+            );
+        }
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Random,
+            model: "random".to_string(),
+            response_time_ms: 1,
+            tokens: shared::types::TokenUsage::default(),
+            request_timestamp: 0,
+        };
+        let batch = |producer: u32, topic: &str, attributes: &[&str]| ProducerUpdate::AttributeBatch {
+            producer_id: ProcessId::Producer(producer),
+            batch_id: 1,
+            attributes: attributes.iter().map(|a| a.to_string()).collect(),
+            provider_metadata: metadata.clone(),
+            estimated_cost: 0.0,
+            topic: topic.to_string(),
+        };
+
+        // "oak" is new to each topic even though the other topic already has it
+        orchestrator.handle_producer_update(batch(1, "animals", &["oak", "lion"])).await.unwrap();
+        orchestrator.handle_producer_update(batch(2, "plants", &["oak", "fern"])).await.unwrap();
+        orchestrator.handle_producer_update(batch(1, "animals", &["lion", "oak"])).await.unwrap();
+        {
+            let state = orchestrator.state.lock().await;
+            assert!(state.topic_state("animals").unwrap().is_completed());
+            assert!(!state.all_topics_completed());
+        }
+        assert_eq!(*stopped.lock().unwrap(), vec![ProcessId::Producer(1)]);
+        assert!(orchestrator.shutdown_rx.try_recv().is_err());
+
+        // A late batch for a finished topic is dropped
+        orchestrator.handle_producer_update(batch(1, "animals", &["tiger"])).await.unwrap();
+        orchestrator.handle_producer_update(batch(2, "plants", &["moss", "fern"])).await.unwrap();
+        assert!(orchestrator.shutdown_rx.try_recv().is_ok());
+
+        let state = orchestrator.state.lock().await;
+        assert_eq!(state.topic_state("animals").unwrap().unique_count(), 2);
+        assert_eq!(state.topic_state("plants").unwrap().unique_count(), 3);
+        assert_eq!(state.total_unique_count(), 5);
+        assert!(state.all_topics_completed());
+        let output = output.lock().unwrap();
+        assert_eq!(output["animals"], vec!["oak", "lion"]);
+        assert_eq!(output["plants"], vec!["oak", "fern", "moss"]);
+        assert_eq!(*stopped.lock().unwrap(), vec![ProcessId::Producer(1), ProcessId::Producer(2)]);
+    }
+}
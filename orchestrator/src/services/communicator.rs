@@ -33,6 +33,12 @@ pub struct RealCommunicator {
     active_listeners: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
 }
 
+impl Default for RealCommunicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RealCommunicator {
     /// Create new communicator
     pub fn new() -> Self {
@@ -304,6 +310,10 @@ impl Communicator for RealCommunicator {
         RealCommunicator::mark_producer_ready(self, producer_id, address).await
     }
 
+    async fn is_producer_ready(&self, producer_id: ProcessId) -> bool {
+        self.producer_ready.lock().await.get(&producer_id).copied().unwrap_or(false)
+    }
+
     async fn register_webserver(&self, address: SocketAddr) -> OrchestratorResult<()> {
         {
             let mut webserver_addr = self.webserver_address.lock().await;
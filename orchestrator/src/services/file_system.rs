@@ -2,20 +2,50 @@
 //!
 //! Handles file I/O operations for storing unique attributes and managing
 //! topic directories with atomic writes and proper error handling.
+//!
+//! With output buffering on, `append_to_output` collects lines in memory and
+//! `sync_to_disk` writes them out, so bursty iterations become one write per
+//! flush. Either way `sync_to_disk` fsyncs every file appended to since the
+//! last call; the orchestrator calls it on a timer and at shutdown.
 
 use async_trait::async_trait;
+use std::borrow::Cow;
+use std::io::Write;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
 use crate::error::{OrchestratorError, OrchestratorResult};
-use crate::traits::FileSystem;
-use shared::process_debug;
+use crate::traits::{AttributeRecord, FileSystem};
+use shared::{process_debug, ProviderId};
+
+/// Shared line sink that unique attributes are streamed to (stdout in production)
+type StreamWriter = Arc<Mutex<Box<dyn Write + Send>>>;
 
 /// Real file system implementation
 pub struct RealFileSystem {
     /// Base directory for all data
     base_dir: PathBuf,
+
+    /// Optional newline-delimited stream of unique attributes, written alongside output.txt
+    stream_writer: Option<StreamWriter>,
+
+    /// Hold output.txt lines in memory until the next `sync_to_disk`
+    buffer_output: bool,
+
+    /// Buffered output.txt lines by file
+    pending_output: Mutex<HashMap<PathBuf, Vec<u8>>>,
+
+    /// Files appended to since the last fsync
+    unsynced_files: Mutex<HashSet<PathBuf>>,
+}
+
+impl Default for RealFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RealFileSystem {
@@ -23,12 +53,82 @@ impl RealFileSystem {
     pub fn new() -> Self {
         Self {
             base_dir: PathBuf::from("./output"),
+            stream_writer: None,
+            buffer_output: false,
+            pending_output: Mutex::new(HashMap::new()),
+            unsynced_files: Mutex::new(HashSet::new()),
         }
     }
 
     /// Create with custom base directory
     pub fn with_base_dir(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+        Self { base_dir, ..Self::new() }
+    }
+
+    /// Buffer output.txt appends in memory until the next `sync_to_disk`
+    ///
+    /// Attributes streamed to stdout are still written immediately.
+    pub fn with_output_buffering(mut self, enabled: bool) -> Self {
+        self.buffer_output = enabled;
+        self
+    }
+
+    /// Also stream each unique attribute to stdout as a line (for use as a pipeline source)
+    pub fn with_stdout_output(self, enabled: bool) -> Self {
+        if enabled {
+            self.with_stream_writer(Box::new(std::io::stdout()))
+        } else {
+            self
+        }
+    }
+
+    /// Stream unique attributes to an arbitrary writer
+    fn with_stream_writer(mut self, writer: Box<dyn Write + Send>) -> Self {
+        self.stream_writer = Some(Arc::new(Mutex::new(writer)));
+        self
+    }
+
+    /// Write attributes to the stream writer, one per line, flushing so downstream readers see them immediately
+    fn stream_attributes(&self, attributes: &[String]) -> OrchestratorResult<()> {
+        let Some(writer) = &self.stream_writer else {
+            return Ok(());
+        };
+
+        let mut writer = writer
+            .lock()
+            .map_err(|_| OrchestratorError::config("Attribute stream writer lock poisoned"))?;
+        for attribute in attributes {
+            writeln!(writer, "{attribute}").map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+        }
+        writer.flush().map_err(|e| OrchestratorError::FileSystemError { source: e })
+    }
+
+    /// Remember a file for the next fsync
+    fn mark_unsynced(&self, path: PathBuf) {
+        self.unsynced_files.lock().unwrap().insert(path);
+    }
+
+    /// Write buffered output lines to their files
+    ///
+    /// Lines that fail to write stay buffered for the next flush.
+    async fn write_pending_output(&self) -> OrchestratorResult<()> {
+        let pending: Vec<(PathBuf, Vec<u8>)> = self.pending_output.lock().unwrap().drain().collect();
+        let mut pending = pending.into_iter();
+
+        while let Some((path, lines)) = pending.next() {
+            if let Err(e) = append_bytes(&path, &lines).await {
+                let mut buffer = self.pending_output.lock().unwrap();
+                for (path, mut lines) in std::iter::once((path, lines)).chain(pending) {
+                    // Anything buffered since the drain goes after the unwritten lines
+                    let newer = buffer.remove(&path).unwrap_or_default();
+                    lines.extend(newer);
+                    buffer.insert(path, lines);
+                }
+                return Err(e);
+            }
+            self.mark_unsynced(path);
+        }
+        Ok(())
     }
 
     /// Get topic directory path
@@ -55,6 +155,49 @@ impl RealFileSystem {
     fn output_file_path(&self, topic: &str) -> PathBuf {
         self.topic_dir_path(topic).join("output.txt")
     }
+
+    /// Get attributes.csv file path for a topic
+    fn csv_file_path(&self, topic: &str) -> PathBuf {
+        self.topic_dir_path(topic).join("attributes.csv")
+    }
+
+    /// Get attributes.jsonl file path for a topic
+    fn jsonl_file_path(&self, topic: &str) -> PathBuf {
+        self.topic_dir_path(topic).join("attributes.jsonl")
+    }
+
+    /// Get by_category/<category>.txt file path for a topic
+    fn category_file_path(&self, topic: &str, category: &str) -> PathBuf {
+        self.topic_dir_path(topic).join("by_category").join(format!("{category}.txt"))
+    }
+}
+
+/// Append bytes to a file, creating it if needed
+async fn append_bytes(path: &std::path::Path, bytes: &[u8]) -> OrchestratorResult<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+    file.write_all(bytes)
+        .await
+        .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+    file.flush()
+        .await
+        .map_err(|e| OrchestratorError::FileSystemError { source: e })
+}
+
+/// Header row written at the top of attributes.csv
+const CSV_HEADER: &str = "attribute,provider,model,timestamp\n";
+
+/// Quote a CSV field (RFC 4180) when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
 }
 
 #[async_trait]
@@ -129,6 +272,8 @@ impl FileSystem for RealFileSystem {
             .await
             .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
 
+        self.mark_unsynced(attributes_path);
+
         // Update metadata
         self.update_metadata_count(topic, attributes.len()).await?;
 
@@ -181,6 +326,8 @@ impl FileSystem for RealFileSystem {
             .await
             .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
 
+        self.mark_unsynced(attributes_path);
+
         // Update metadata
         self.update_metadata_count(topic, attributes.len()).await?;
 
@@ -225,6 +372,10 @@ impl FileSystem for RealFileSystem {
     async fn cleanup_topic(&self, topic: &str) -> OrchestratorResult<()> {
         let topic_dir = self.topic_dir_path(topic);
 
+        // Buffered lines and pending fsyncs for a deleted topic have nowhere to go
+        self.pending_output.lock().unwrap().retain(|path, _| !path.starts_with(&topic_dir));
+        self.unsynced_files.lock().unwrap().retain(|path| !path.starts_with(&topic_dir));
+
         if topic_dir.exists() {
             fs::remove_dir_all(&topic_dir)
                 .await
@@ -241,19 +392,22 @@ impl FileSystem for RealFileSystem {
     }
 
     async fn sync_to_disk(&self) -> OrchestratorResult<()> {
-        // On most systems, fsync is handled by the OS, but we can ensure
-        // any buffered writes are flushed
-
-        // Create a temporary file and sync it to force filesystem flush
-        let sync_file = self.base_dir.join(".sync_marker");
-        fs::write(&sync_file, "sync")
-            .await
-            .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
-
-        // Remove the sync marker
-        let _ = fs::remove_file(&sync_file).await;
+        self.write_pending_output().await?;
+
+        let unsynced: Vec<PathBuf> = self.unsynced_files.lock().unwrap().drain().collect();
+        for path in &unsynced {
+            let file = match fs::File::open(path).await {
+                Ok(file) => file,
+                // Removed since it was written, e.g. by a topic cleanup
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(OrchestratorError::FileSystemError { source: e }),
+            };
+            file.sync_all()
+                .await
+                .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+        }
 
-        process_debug!(shared::ProcessId::current(), "💽 File system synced to disk");
+        process_debug!(shared::ProcessId::current(), "💽 Synced {} files to disk", unsynced.len());
         Ok(())
     }
 
@@ -264,33 +418,116 @@ impl FileSystem for RealFileSystem {
 
         let output_path = self.output_file_path(topic);
 
-        // Append new attributes to output.txt (one per line)
+        // New attributes go to output.txt one per line
+        let mut lines = Vec::new();
+        for attribute in new_attributes {
+            lines.extend_from_slice(attribute.as_bytes());
+            lines.push(b'\n');
+        }
+
+        if self.buffer_output {
+            self.pending_output
+                .lock()
+                .unwrap()
+                .entry(output_path.clone())
+                .or_default()
+                .extend(lines);
+        } else {
+            append_bytes(&output_path, &lines).await?;
+            self.mark_unsynced(output_path.clone());
+        }
+
+        self.stream_attributes(new_attributes)?;
+
+        process_debug!(
+            shared::ProcessId::current(),
+            "📝 {} {} new attributes to {}",
+            if self.buffer_output { "Buffered" } else { "Appended" },
+            new_attributes.len(),
+            output_path.display()
+        );
+        Ok(())
+    }
+
+    async fn append_csv_rows(
+        &self,
+        topic: &str,
+        attributes: &[String],
+        provider: ProviderId,
+        model: &str,
+        timestamp: u64,
+    ) -> OrchestratorResult<()> {
+        if attributes.is_empty() {
+            return Ok(());
+        }
+
+        let csv_path = self.csv_file_path(topic);
         let mut file = fs::OpenOptions::new()
             .create(true)
             .append(true)
-            .open(&output_path)
+            .open(&csv_path)
             .await
             .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
 
-        for attribute in new_attributes {
-            file.write_all(attribute.as_bytes())
-                .await
-                .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
-            file.write_all(b"\n")
-                .await
-                .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+        // Header goes in on the first write to a fresh file
+        let is_empty = file
+            .metadata()
+            .await
+            .map_err(|e| OrchestratorError::FileSystemError { source: e })?
+            .len()
+            == 0;
+
+        let mut rows = String::new();
+        if is_empty {
+            rows.push_str(CSV_HEADER);
+        }
+        let provenance = format!("{},{},{}", csv_field(&provider.to_string()), csv_field(model), timestamp);
+        for attribute in attributes {
+            rows.push_str(&format!("{},{}\n", csv_field(attribute), provenance));
         }
 
+        file.write_all(rows.as_bytes())
+            .await
+            .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
         file.flush()
             .await
             .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+        self.mark_unsynced(csv_path);
+        Ok(())
+    }
 
-        process_debug!(
-            shared::ProcessId::current(),
-            "📝 Appended {} new attributes to {}",
-            new_attributes.len(),
-            output_path.display()
-        );
+    async fn append_jsonl(&self, topic: &str, records: &[AttributeRecord]) -> OrchestratorResult<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(&serde_json::to_string(record).map_err(|e| OrchestratorError::JsonError { source: e })?);
+            lines.push('\n');
+        }
+
+        // A single write keeps each line whole for readers tailing the file
+        let jsonl_path = self.jsonl_file_path(topic);
+        append_bytes(&jsonl_path, lines.as_bytes()).await?;
+        self.mark_unsynced(jsonl_path);
+        Ok(())
+    }
+
+    async fn append_to_category(&self, topic: &str, category: &str, attributes: &[String]) -> OrchestratorResult<()> {
+        if attributes.is_empty() {
+            return Ok(());
+        }
+
+        let category_path = self.category_file_path(topic, category);
+        if let Some(category_dir) = category_path.parent() {
+            fs::create_dir_all(category_dir)
+                .await
+                .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+        }
+        let lines: String = attributes.iter().map(|attribute| format!("{attribute}\n")).collect();
+        append_bytes(&category_path, lines.as_bytes()).await?;
+        self.mark_unsynced(category_path);
         Ok(())
     }
 
@@ -309,6 +546,74 @@ impl FileSystem for RealFileSystem {
         process_debug!(shared::ProcessId::current(), "📝 Wrote file: {}", file_path.display());
         Ok(())
     }
+
+    async fn append_file(&self, filename: &str, content: &[u8]) -> OrchestratorResult<()> {
+        fs::create_dir_all(&self.base_dir)
+            .await
+            .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+
+        let file_path = self.base_dir.join(filename);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)
+            .await
+            .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+
+        file.write_all(content)
+            .await
+            .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+        file.flush()
+            .await
+            .map_err(|e| OrchestratorError::FileSystemError { source: e })?;
+
+        process_debug!(shared::ProcessId::current(), "📝 Appended to file: {}", file_path.display());
+        Ok(())
+    }
+
+    async fn read_file(&self, filename: &str) -> OrchestratorResult<Option<Vec<u8>>> {
+        match fs::read(self.base_dir.join(filename)).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(OrchestratorError::FileSystemError { source: e }),
+        }
+    }
+
+    fn topic_file_path(&self, topic: &str, filename: &str) -> PathBuf {
+        self.topic_dir_path(topic).join(filename)
+    }
+
+    async fn check_writable(&self) -> OrchestratorResult<()> {
+        let not_writable = |e: std::io::Error| {
+            OrchestratorError::config(format!(
+                "Output directory '{}' is not writable: {}",
+                self.base_dir.display(),
+                e
+            ))
+        };
+
+        fs::create_dir_all(&self.base_dir).await.map_err(not_writable)?;
+
+        let probe_path = self.base_dir.join(".write_check");
+        fs::write(&probe_path, b"").await.map_err(not_writable)?;
+        let _ = fs::remove_file(&probe_path).await;
+
+        Ok(())
+    }
+
+    async fn free_space(&self) -> OrchestratorResult<Option<u64>> {
+        #[cfg(unix)]
+        {
+            let stat = nix::sys::statvfs::statvfs(&self.base_dir)
+                .map_err(|e| OrchestratorError::FileSystemError { source: e.into() })?;
+            Ok(Some(stat.blocks_available() as u64 * stat.fragment_size() as u64))
+        }
+
+        #[cfg(not(unix))]
+        {
+            Ok(None)
+        }
+    }
 }
 
 impl RealFileSystem {
@@ -428,4 +733,173 @@ mod tests {
         let result = fs.sync_to_disk().await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_buffered_output_is_written_on_sync() {
+        let (fs, _temp) = create_test_fs().await;
+        let fs = fs.with_output_buffering(true);
+        fs.create_topic_directory("buffer_test").await.unwrap();
+
+        fs.append_to_output("buffer_test", &["red fox".to_string()]).await.unwrap();
+        fs.append_to_output("buffer_test", &["barn owl".to_string()]).await.unwrap();
+        assert!(!fs.output_file_path("buffer_test").exists());
+
+        fs.sync_to_disk().await.unwrap();
+        let output = std::fs::read_to_string(fs.output_file_path("buffer_test")).unwrap();
+        assert_eq!(output, "red fox\nbarn owl\n");
+
+        // A second flush has nothing new to write
+        fs.sync_to_disk().await.unwrap();
+        let output = std::fs::read_to_string(fs.output_file_path("buffer_test")).unwrap();
+        assert_eq!(output, "red fox\nbarn owl\n");
+    }
+
+    /// Writer that captures streamed output in memory
+    #[derive(Clone, Default)]
+    struct CapturedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_to_output_streams_attributes_line_by_line() {
+        let (fs, _temp) = create_test_fs().await;
+        let captured = CapturedOutput::default();
+        let fs = fs.with_stream_writer(Box::new(captured.clone()));
+
+        fs.create_topic_directory("stream_test").await.unwrap();
+        fs.append_to_output("stream_test", &["red fox".to_string(), "grey wolf".to_string()])
+            .await
+            .unwrap();
+        fs.append_to_output("stream_test", &["barn owl".to_string()]).await.unwrap();
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().collect::<Vec<_>>(), vec!["red fox", "grey wolf", "barn owl"]);
+        assert!(output.ends_with('\n'));
+
+        // Files are still written when streaming
+        let file_output = tokio::fs::read_to_string(fs.output_file_path("stream_test")).await.unwrap();
+        assert_eq!(file_output, output);
+    }
+
+    #[tokio::test]
+    async fn test_check_writable_creates_missing_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("nested").join("output");
+        let fs = RealFileSystem::with_base_dir(base_dir.clone());
+
+        fs.check_writable().await.unwrap();
+        assert!(base_dir.is_dir());
+        assert_eq!(std::fs::read_dir(&base_dir).unwrap().count(), 0);
+        assert!(fs.free_space().await.unwrap().is_some_and(|free| free > 0));
+    }
+
+    #[tokio::test]
+    async fn test_check_writable_fails_for_unusable_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let blocker = temp_dir.path().join("not_a_dir");
+        std::fs::write(&blocker, b"").unwrap();
+        let fs = RealFileSystem::with_base_dir(blocker.join("output"));
+
+        let error = fs.check_writable().await.unwrap_err().to_string();
+        assert!(error.contains("is not writable"), "unexpected error: {error}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_writable_fails_for_read_only_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let read_only = temp_dir.path().join("read_only");
+        std::fs::create_dir(&read_only).unwrap();
+        std::fs::set_permissions(&read_only, std::fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Privileged users (e.g. root in containers) bypass permission bits entirely
+        if std::fs::write(read_only.join("probe"), b"").is_ok() {
+            return;
+        }
+
+        let fs = RealFileSystem::with_base_dir(read_only.clone());
+        let error = fs.check_writable().await.unwrap_err().to_string();
+        assert!(error.contains("is not writable"), "unexpected error: {error}");
+
+        std::fs::set_permissions(&read_only, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_append_csv_rows_quotes_adversarial_attributes() {
+        let (fs, _temp) = create_test_fs().await;
+        fs.create_topic_directory("csv_test").await.unwrap();
+
+        let attributes = [
+            "plain",
+            "comma, separated",
+            "say \"cheese\"",
+            "line\nbreak",
+            "carriage\rreturn",
+            "\",\"\n",
+        ]
+        .map(String::from);
+        // Two batches, so the second append finds the header already written
+        for batch in attributes.chunks(3) {
+            fs.append_csv_rows("csv_test", batch, ProviderId::OpenAI, "gpt-4o-mini", 1700000000)
+                .await
+                .unwrap();
+        }
+
+        let content = std::fs::read_to_string(fs.csv_file_path("csv_test")).unwrap();
+        let expected = "attribute,provider,model,timestamp\n\
+            plain,openai,gpt-4o-mini,1700000000\n\
+            \"comma, separated\",openai,gpt-4o-mini,1700000000\n\
+            \"say \"\"cheese\"\"\",openai,gpt-4o-mini,1700000000\n\
+            \"line\nbreak\",openai,gpt-4o-mini,1700000000\n\
+            \"carriage\rreturn\",openai,gpt-4o-mini,1700000000\n\
+            \"\"\",\"\"\n\",openai,gpt-4o-mini,1700000000\n";
+        assert_eq!(content, expected);
+
+        // The header is only written once
+        assert_eq!(content.matches("attribute,provider").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_append_jsonl_writes_one_parseable_record_per_line() {
+        let (fs, _temp) = create_test_fs().await;
+        fs.create_topic_directory("jsonl_test").await.unwrap();
+
+        let metadata = shared::ProviderMetadata {
+            provider_id: ProviderId::Anthropic,
+            model: "claude-3-5-haiku".to_string(),
+            response_time_ms: 250,
+            tokens: shared::TokenUsage {
+                input_tokens: 120,
+                output_tokens: 45,
+            },
+            request_timestamp: 1700000000,
+        };
+        let attributes = ["plain", "line\nbreak", "say \"cheese\""];
+        let batch: Vec<_> = attributes.iter().map(|attribute| AttributeRecord::new(attribute, &metadata)).collect();
+        fs.append_jsonl("jsonl_test", &batch).await.unwrap();
+
+        let content = std::fs::read_to_string(fs.jsonl_file_path("jsonl_test")).unwrap();
+        let records: Vec<AttributeRecord> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), attributes.len());
+        for (record, attribute) in records.iter().zip(attributes) {
+            assert_eq!(record.attribute, attribute);
+            assert_eq!(record.provider, ProviderId::Anthropic);
+            assert_eq!(record.model, "claude-3-5-haiku");
+            assert_eq!(record.tokens.total(), 165);
+            assert_eq!(record.timestamp, 1700000000);
+        }
+    }
 }
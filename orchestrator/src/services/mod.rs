@@ -13,4 +13,4 @@ pub mod process_output_handler;
 pub use api_keys::RealApiKeySource;
 pub use communicator::RealCommunicator;
 pub use file_system::RealFileSystem;
-pub use process_manager::RealProcessManager;
+pub use process_manager::{RealProcessManager, RestartPolicy};
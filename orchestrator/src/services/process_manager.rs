@@ -4,16 +4,101 @@
 //! with health checking and graceful shutdown capabilities.
 
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 
 use crate::error::{OrchestratorError, OrchestratorResult};
 use crate::services::process_output_handler;
-use crate::traits::{ProcessHealthInfo, ProcessManager, ProcessStatus, ProducerInfo, WebServerInfo};
-use shared::{process_debug, process_error, ProviderId};
+use crate::traits::{ProcessHealthInfo, ProcessManager, ProcessStatus, ProducerInfo, RestartOutcome, WebServerInfo};
+use shared::logging::LogFormat;
+use shared::{process_debug, process_error, process_warn, ProviderId};
+
+/// Limits on respawning a producer that keeps failing
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    /// Restarts allowed within `window` before the producer is given up on
+    pub max_attempts: u32,
+    /// Delay after a restart before the next one; doubled for each further restart in the window
+    pub backoff: Duration,
+    /// How long a restart keeps counting towards `max_attempts`
+    pub window: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+            window: Duration::from_secs(300),
+        }
+    }
+}
+
+/// What the restart policy allows for a failed producer right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestartDecision {
+    Restart,
+    Wait,
+    GiveUp { attempts: u32 },
+}
+
+/// Recent restarts per producer slot, used to stop crash loops
+#[derive(Debug, Default)]
+struct RestartTracker {
+    restarts: HashMap<shared::ProcessId, VecDeque<Instant>>,
+    given_up: HashSet<shared::ProcessId>,
+}
+
+impl RestartTracker {
+    /// Decide whether a failed producer may be restarted at `now`
+    fn decide(&mut self, policy: &RestartPolicy, producer_id: &shared::ProcessId, now: Instant) -> RestartDecision {
+        if self.given_up.contains(producer_id) {
+            return RestartDecision::GiveUp {
+                attempts: policy.max_attempts,
+            };
+        }
+
+        let restarts = self.restarts.entry(producer_id.clone()).or_default();
+        while restarts
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) > policy.window)
+        {
+            restarts.pop_front();
+        }
+
+        let attempts = restarts.len() as u32;
+        if attempts >= policy.max_attempts {
+            self.restarts.remove(producer_id);
+            self.given_up.insert(producer_id.clone());
+            return RestartDecision::GiveUp { attempts };
+        }
+
+        let ready = restarts.back().is_none_or(|last| {
+            let delay = policy
+                .backoff
+                .saturating_mul(1u32.checked_shl(attempts - 1).unwrap_or(u32::MAX));
+            now.saturating_duration_since(*last) >= delay
+        });
+        if ready { RestartDecision::Restart } else { RestartDecision::Wait }
+    }
+
+    /// Count a restart of the producer's slot
+    fn record(&mut self, producer_id: &shared::ProcessId, now: Instant) {
+        self.restarts.entry(producer_id.clone()).or_default().push_back(now);
+    }
+
+    /// Move a slot's restart history to the producer now filling it under a new id
+    fn hand_over(&mut self, from: &shared::ProcessId, to: &shared::ProcessId) {
+        if let Some(restarts) = self.restarts.remove(from) {
+            self.restarts.insert(to.clone(), restarts);
+        }
+    }
+}
 
 /// Real process manager implementation
 pub struct RealProcessManager {
@@ -31,6 +116,51 @@ pub struct RealProcessManager {
 
     /// Log level to pass to spawned processes
     log_level: String,
+
+    /// Ask spawned processes for plain ASCII logs
+    plain_logs: bool,
+
+    /// Console log format for spawned processes
+    log_format: LogFormat,
+
+    /// Few-shot examples file passed to spawned producers
+    examples_file: Option<String>,
+
+    /// Per-model token price file passed to spawned producers
+    pricing: Option<String>,
+
+    /// Output directory under which producers keep each topic's dead_letter.log
+    dead_letter_dir: Option<PathBuf>,
+
+    /// Ask spawned producers to auto-tune provider concurrency
+    auto_tune: bool,
+
+    /// Ask spawned producers to retry content-filtered requests with a reworded prompt
+    reword_filtered_prompts: bool,
+
+    /// TTL in milliseconds of the producers' request cache; None leaves it off
+    request_cache_ttl_ms: Option<u64>,
+
+    /// Provider producers re-check sampled attributes with, and the fraction they sample
+    verification: Option<(String, f32)>,
+
+    /// Keep the orchestrator's stdout for attribute data by sending child stdout to stderr
+    reserve_stdout: bool,
+
+    /// Limits on respawning failed producers
+    restart_policy: RestartPolicy,
+
+    /// Restart history per producer slot
+    restart_tracker: Mutex<RestartTracker>,
+
+    /// Idle producers to keep spawned for replacing failed ones (0 disables the pool)
+    warm_pool_size: usize,
+
+    /// Spawned but unstarted producers, oldest first
+    warm_pool: Mutex<VecDeque<ProcessHandle>>,
+
+    /// Id for the next warm producer, kept above every spawned slot
+    next_warm_id: Mutex<u32>,
 }
 
 /// Handle for a managed process
@@ -55,6 +185,12 @@ enum ProcessType {
     WebServer,
 }
 
+impl Default for RealProcessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RealProcessManager {
     /// Create new process manager with default settings
     pub fn new() -> Self {
@@ -64,6 +200,21 @@ impl RealProcessManager {
             next_port: Arc::new(Mutex::new(9000)), // Start ports from 9000 to avoid conflicts
             trace_endpoint: None,
             log_level: "info".to_string(), // Default log level
+            plain_logs: false,
+            log_format: LogFormat::default(),
+            examples_file: None,
+            pricing: None,
+            dead_letter_dir: None,
+            auto_tune: false,
+            reword_filtered_prompts: false,
+            request_cache_ttl_ms: None,
+            verification: None,
+            reserve_stdout: false,
+            restart_policy: RestartPolicy::default(),
+            restart_tracker: Mutex::new(RestartTracker::default()),
+            warm_pool_size: 0,
+            warm_pool: Mutex::new(VecDeque::new()),
+            next_warm_id: Mutex::new(1),
         }
     }
 
@@ -79,6 +230,78 @@ impl RealProcessManager {
         self
     }
 
+    /// Configure plain ASCII logging for spawned processes (fluent API)
+    pub fn with_plain_logs(mut self, plain_logs: bool) -> Self {
+        self.plain_logs = plain_logs;
+        self
+    }
+
+    /// Configure the console log format of spawned processes (fluent API)
+    pub fn with_log_format(mut self, log_format: LogFormat) -> Self {
+        self.log_format = log_format;
+        self
+    }
+
+    /// Configure few-shot examples file for producers (fluent API)
+    pub fn with_examples_file(mut self, examples_file: Option<String>) -> Self {
+        self.examples_file = examples_file;
+        self
+    }
+
+    /// Configure the token price file producers estimate costs with (fluent API)
+    pub fn with_pricing(mut self, pricing: Option<String>) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Configure where producers log responses that yield no attributes (fluent API)
+    pub fn with_dead_letter_dir(mut self, dead_letter_dir: Option<PathBuf>) -> Self {
+        self.dead_letter_dir = dead_letter_dir;
+        self
+    }
+
+    /// Configure concurrency auto-tuning for producers (fluent API)
+    pub fn with_auto_tune(mut self, auto_tune: bool) -> Self {
+        self.auto_tune = auto_tune;
+        self
+    }
+
+    /// Configure rewording of content-filtered requests for producers (fluent API)
+    pub fn with_filtered_prompt_rewording(mut self, enabled: bool) -> Self {
+        self.reword_filtered_prompts = enabled;
+        self
+    }
+
+    /// Configure the producers' request cache TTL in milliseconds, None to leave it off (fluent API)
+    pub fn with_request_cache(mut self, ttl_ms: Option<u64>) -> Self {
+        self.request_cache_ttl_ms = ttl_ms;
+        self
+    }
+
+    /// Configure attribute spot checks by a verification provider for producers, None to leave them off (fluent API)
+    pub fn with_verification(mut self, provider: Option<String>, sample_rate: f32) -> Self {
+        self.verification = provider.map(|provider| (provider, sample_rate));
+        self
+    }
+
+    /// Redirect child process stdout to stderr (fluent API)
+    pub fn with_reserved_stdout(mut self, reserve_stdout: bool) -> Self {
+        self.reserve_stdout = reserve_stdout;
+        self
+    }
+
+    /// Configure how often failed producers are respawned (fluent API)
+    pub fn with_restart_policy(mut self, restart_policy: RestartPolicy) -> Self {
+        self.restart_policy = restart_policy;
+        self
+    }
+
+    /// Keep this many idle producers spawned so failed ones are replaced without a cold start (fluent API)
+    pub fn with_warm_pool_size(mut self, warm_pool_size: usize) -> Self {
+        self.warm_pool_size = warm_pool_size;
+        self
+    }
+
     /// Configure base port (fluent API)
     pub fn with_base_port(mut self, base_port: u16) -> Self {
         self.next_port = Arc::new(Mutex::new(base_port));
@@ -128,23 +351,51 @@ impl RealProcessManager {
 
         // Add log level
         cmd.arg("--log-level").arg(&self.log_level);
+        if self.plain_logs {
+            cmd.arg("--plain-logs");
+        }
+        if self.log_format != LogFormat::Text {
+            cmd.arg("--log-format").arg(self.log_format.to_string());
+        }
+        if let Some(ref examples_file) = self.examples_file {
+            cmd.arg("--examples-file").arg(examples_file);
+        }
+        if let Some(ref pricing) = self.pricing {
+            cmd.arg("--pricing").arg(pricing);
+        }
+        if let Some(ref dead_letter_dir) = self.dead_letter_dir {
+            cmd.arg("--dead-letter-dir").arg(dead_letter_dir);
+        }
+        if self.auto_tune {
+            cmd.arg("--auto-tune");
+        }
+        if self.reword_filtered_prompts {
+            cmd.arg("--reword-filtered-prompts");
+        }
+        if let Some(ttl_ms) = self.request_cache_ttl_ms {
+            cmd.arg("--enable-request-cache").arg("--request-cache-ttl-ms").arg(ttl_ms.to_string());
+        }
+        if let Some((ref provider, sample_rate)) = self.verification {
+            cmd.arg("--verification-provider").arg(provider);
+            cmd.arg("--verification-sample-rate").arg(sample_rate.to_string());
+        }
 
         // Pass structured routing configuration to producer
         if let Some(routing) = routing_strategy {
             let routing_config = match routing {
                 shared::RoutingStrategy::Backoff { provider } => {
-                    format!("strategy:backoff,provider:{},model:{}", provider.provider, provider.model)
+                    format!("strategy:backoff,provider:{},model:{}", provider.provider, provider.model_spec())
                 },
                 shared::RoutingStrategy::RoundRobin { providers } => {
                     let provider_list = providers.iter()
-                        .map(|pc| format!("{}:{}", pc.provider, pc.model))
+                        .map(|pc| format!("{}:{}", pc.provider, pc.model_spec()))
                         .collect::<Vec<_>>()
                         .join(",");
                     format!("strategy:roundrobin,providers:{}", provider_list)
                 },
                 shared::RoutingStrategy::PriorityOrder { providers } => {
                     let provider_list = providers.iter()
-                        .map(|pc| format!("{}:{}", pc.provider, pc.model))
+                        .map(|pc| format!("{}:{}", pc.provider, pc.model_spec()))
                         .collect::<Vec<_>>()
                         .join(",");
                     format!("strategy:priority,providers:{}", provider_list)
@@ -152,12 +403,29 @@ impl RealProcessManager {
                 shared::RoutingStrategy::Weighted { weights } => {
                     let weights_str = weights.iter()
                         .map(|(provider_config, weight)| {
-                            format!("{}:{}:{}", provider_config.provider, provider_config.model, weight)
+                            format!("{}:{}:{}", provider_config.provider, provider_config.model_spec(), weight)
                         })
                         .collect::<Vec<_>>()
                         .join(",");
                     format!("strategy:weighted,weights:{}", weights_str)
                 }
+                shared::RoutingStrategy::CircuitBreaker { providers, failure_threshold, cooldown_seconds } => {
+                    let provider_list = providers.iter()
+                        .map(|pc| format!("{}:{}", pc.provider, pc.model_spec()))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    format!(
+                        "strategy:circuitbreaker,threshold:{},cooldown:{},providers:{}",
+                        failure_threshold, cooldown_seconds, provider_list
+                    )
+                }
+                shared::RoutingStrategy::LowestLatency { providers, window_seconds } => {
+                    let provider_list = providers.iter()
+                        .map(|pc| format!("{}:{}", pc.provider, pc.model_spec()))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    format!("strategy:lowestlatency,window:{},providers:{}", window_seconds, provider_list)
+                }
             };
             
             cmd.arg("--routing-config").arg(routing_config);
@@ -178,6 +446,9 @@ impl RealProcessManager {
                 ProviderId::OpenAI => "OPENAI_API_KEY",
                 ProviderId::Anthropic => "ANTHROPIC_API_KEY",
                 ProviderId::Gemini => "GOOGLE_API_KEY",
+                ProviderId::Mistral => "MISTRAL_API_KEY",
+                ProviderId::Cohere => "COHERE_API_KEY",
+                ProviderId::AzureOpenAI => "AZURE_OPENAI_API_KEY",
                 ProviderId::Random => continue, // Skip Random provider - it doesn't need env var
             };
             cmd.env(env_var, api_key);
@@ -188,11 +459,13 @@ impl RealProcessManager {
         process_output_handler::configure_child_stdio(
             &mut cmd, 
             has_trace_endpoint, 
+            self.reserve_stdout,
             &format!("producer_{}", producer_id)
         );
 
-        // Spawn process
+        // Spawn process; a handle dropped without being stopped still takes its producer down
         let mut child = cmd
+            .kill_on_drop(true)
             .spawn()
             .map_err(|e| OrchestratorError::process(format!("Failed to spawn producer: {e}")))?;
         
@@ -227,6 +500,53 @@ impl RealProcessManager {
         Ok(ProcessHandle { child, info })
     }
 
+    /// Spawn idle producers until the warm pool holds `warm_pool_size`
+    ///
+    /// Spawning returns as soon as the process is launched, so the new producers boot in
+    /// the background while the caller carries on.
+    async fn fill_warm_pool(&self, api_keys: &HashMap<ProviderId, String>, orchestrator_addr: SocketAddr) {
+        let missing = self.warm_pool_size.saturating_sub(self.warm_pool.lock().await.len());
+        for _ in 0..missing {
+            let producer_id = {
+                let mut next = self.next_warm_id.lock().await;
+                let id = *next;
+                *next += 1;
+                id
+            };
+            match self
+                .spawn_single_producer(producer_id, "", api_keys, orchestrator_addr, None)
+                .await
+            {
+                Ok(handle) => self.warm_pool.lock().await.push_back(handle),
+                Err(e) => {
+                    process_error!(
+                        shared::ProcessId::current(),
+                        "⚠️ Failed to spawn warm producer {}: {}",
+                        producer_id,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Take the oldest warm producer that is still running, discarding any that exited while idle
+    async fn take_warm_producer(&self) -> Option<ProcessHandle> {
+        let mut pool = self.warm_pool.lock().await;
+        while let Some(mut handle) = pool.pop_front() {
+            if Self::is_process_running(&mut handle.child) {
+                return Some(handle);
+            }
+            process_warn!(
+                shared::ProcessId::current(),
+                "🧊 Warm producer (PID: {}) exited while idle; discarding it",
+                handle.info.process_id
+            );
+        }
+        None
+    }
+
     /// Check if a process is still running
     fn is_process_running(child: &mut Child) -> bool {
         match child.try_wait() {
@@ -242,7 +562,7 @@ impl ProcessManager for RealProcessManager {
     async fn spawn_producers(
         &self,
         count: u32,
-        topic: &str,
+        topic: Option<String>,
         api_keys: HashMap<ProviderId, String>,
         orchestrator_addr: std::net::SocketAddr,
         routing_strategy: Option<shared::RoutingStrategy>,
@@ -259,7 +579,7 @@ impl ProcessManager for RealProcessManager {
             let producer_id = i + 1;
 
             match self
-                .spawn_single_producer(producer_id, topic, &api_keys, orchestrator_addr, routing_strategy.as_ref())
+                .spawn_single_producer(producer_id, topic.as_deref().unwrap_or_default(), &api_keys, orchestrator_addr, routing_strategy.as_ref())
                 .await
             {
                 Ok(handle) => {
@@ -299,12 +619,28 @@ impl ProcessManager for RealProcessManager {
             }
         }
 
-        process_debug!(
-            shared::ProcessId::current(),
-            "🚀 Spawned {} producers for topic '{}'",
-            producer_infos.len(),
-            topic
-        );
+        // Warm producers take ids after every slot so a promotion never collides with one
+        if self.warm_pool_size > 0 {
+            {
+                let mut next = self.next_warm_id.lock().await;
+                *next = (*next).max(count + 1);
+            }
+            self.fill_warm_pool(&api_keys, orchestrator_addr).await;
+        }
+
+        match topic {
+            Some(topic) => process_debug!(
+                shared::ProcessId::current(),
+                "🚀 Spawned {} producers for topic '{}'",
+                producer_infos.len(),
+                topic
+            ),
+            None => process_debug!(
+                shared::ProcessId::current(),
+                "🚀 Spawned {} producers for concurrent topics",
+                producer_infos.len()
+            ),
+        }
         Ok(producer_infos)
     }
 
@@ -331,6 +667,11 @@ impl ProcessManager for RealProcessManager {
             .arg("--static-dir")
             .arg(static_dir.to_string_lossy().as_ref());
 
+        // The dead-letter directory is the output base, which is where topic downloads are read from
+        if let Some(ref output_dir) = self.dead_letter_dir {
+            cmd.arg("--output-dir").arg(output_dir);
+        }
+
         // Add tracing endpoint if configured
         if let Some(ref trace_ep) = self.trace_endpoint {
             cmd.arg("--trace-ep").arg(trace_ep);
@@ -338,10 +679,16 @@ impl ProcessManager for RealProcessManager {
 
         // Add log level
         cmd.arg("--log-level").arg(&self.log_level);
+        if self.plain_logs {
+            cmd.arg("--plain-logs");
+        }
+        if self.log_format != LogFormat::Text {
+            cmd.arg("--log-format").arg(self.log_format.to_string());
+        }
 
         // Configure stdio based on tracing endpoint availability
         let has_trace_endpoint = self.trace_endpoint.is_some();
-        process_output_handler::configure_child_stdio(&mut cmd, has_trace_endpoint, "webserver");
+        process_output_handler::configure_child_stdio(&mut cmd, has_trace_endpoint, self.reserve_stdout, "webserver");
 
         let mut child = cmd
             .spawn()
@@ -384,6 +731,119 @@ impl ProcessManager for RealProcessManager {
         Ok(webserver_info)
     }
 
+    async fn promote_warm_producer(
+        &self,
+        failed_producer_id: shared::ProcessId,
+        api_keys: HashMap<ProviderId, String>,
+        orchestrator_addr: SocketAddr,
+    ) -> OrchestratorResult<Option<ProducerInfo>> {
+        if self.warm_pool_size == 0 {
+            return Ok(None);
+        }
+
+        // Backoff and give-up decisions are left to restart_producer
+        let now = Instant::now();
+        let decision = self
+            .restart_tracker
+            .lock()
+            .await
+            .decide(&self.restart_policy, &failed_producer_id, now);
+        if decision != RestartDecision::Restart {
+            return Ok(None);
+        }
+        let Some(handle) = self.take_warm_producer().await else {
+            return Ok(None);
+        };
+        let ProcessType::Producer(ref promoted_id) = handle.info.process_type else {
+            return Ok(None);
+        };
+        let promoted_id = promoted_id.clone();
+
+        // The promoted producer inherits the slot's restart history so crash loops are still caught
+        {
+            let mut tracker = self.restart_tracker.lock().await;
+            tracker.record(&failed_producer_id, now);
+            tracker.hand_over(&failed_producer_id, &promoted_id);
+        }
+
+        let info = ProducerInfo {
+            id: promoted_id.clone(),
+            process_id: handle.info.process_id,
+            listen_address: handle.info.listen_address,
+            command_address: handle.info.command_address,
+        };
+        {
+            let mut active = self.active_producers.lock().await;
+            if let Some(mut failed) = active.remove(&failed_producer_id) {
+                let _ = failed.child.start_kill();
+            }
+            active.insert(promoted_id.clone(), handle);
+        }
+        process_debug!(
+            shared::ProcessId::current(),
+            "🔥 Promoted warm producer {} to replace {}",
+            promoted_id,
+            failed_producer_id
+        );
+
+        self.fill_warm_pool(&api_keys, orchestrator_addr).await;
+        Ok(Some(info))
+    }
+
+    async fn restart_producer(
+        &self,
+        failed_producer_id: shared::ProcessId,
+        topic: &str,
+        api_keys: HashMap<ProviderId, String>,
+        orchestrator_addr: SocketAddr,
+    ) -> OrchestratorResult<RestartOutcome> {
+        let shared::ProcessId::Producer(slot) = failed_producer_id else {
+            return Err(OrchestratorError::process(format!(
+                "Cannot restart {failed_producer_id}: not a producer"
+            )));
+        };
+
+        let now = Instant::now();
+        let decision = self
+            .restart_tracker
+            .lock()
+            .await
+            .decide(&self.restart_policy, &failed_producer_id, now);
+        match decision {
+            RestartDecision::Restart => {}
+            RestartDecision::Wait => return Ok(RestartOutcome::Deferred),
+            RestartDecision::GiveUp { attempts } => {
+                self.stop_producer(failed_producer_id.clone()).await?;
+                process_warn!(
+                    shared::ProcessId::current(),
+                    "🪦 Producer {} failed after {} restarts within {}s; not restarting",
+                    failed_producer_id,
+                    attempts,
+                    self.restart_policy.window.as_secs()
+                );
+                return Ok(RestartOutcome::GaveUp { attempts });
+            }
+        }
+
+        // A producer reported as failed may still be running (hung), so stop it before reusing its slot
+        self.stop_producer(failed_producer_id.clone()).await?;
+
+        // The replacement takes over the failed producer's slot so its restart history carries over
+        let handle = self
+            .spawn_single_producer(slot, topic, &api_keys, orchestrator_addr, None)
+            .await?;
+        self.restart_tracker.lock().await.record(&failed_producer_id, now);
+
+        let info = ProducerInfo {
+            id: failed_producer_id.clone(),
+            process_id: handle.info.process_id,
+            listen_address: handle.info.listen_address,
+            command_address: handle.info.command_address,
+        };
+        self.active_producers.lock().await.insert(failed_producer_id, handle);
+        Ok(RestartOutcome::Restarted(info))
+    }
+
     async fn check_process_health(&self) -> OrchestratorResult<Vec<ProcessHealthInfo>> {
         let mut health_infos = Vec::new();
 
@@ -476,6 +936,12 @@ impl ProcessManager for RealProcessManager {
     }
 
     async fn stop_all(&self) -> OrchestratorResult<()> {
+        // Idle producers have no work to finish
+        for mut handle in self.warm_pool.lock().await.drain(..) {
+            let _ = handle.child.kill().await;
+            let _ = handle.child.wait().await;
+        }
+
         // Stop all producers gracefully
         {
             let mut producers = self.active_producers.lock().await;
@@ -555,7 +1021,7 @@ mod tests {
         let empty_keys = HashMap::new();
         let addr = "127.0.0.1:6001".parse().unwrap();
 
-        let result = manager.spawn_producers(1, "test", empty_keys, addr, None).await;
+        let result = manager.spawn_producers(1, Some("test".to_string()), empty_keys, addr, None).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No API keys"));
     }
@@ -568,4 +1034,117 @@ mod tests {
         let result = manager.stop_all().await;
         assert!(result.is_ok());
     }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_given_up_producer_process_is_killed() {
+        let manager = RealProcessManager::new().with_restart_policy(RestartPolicy {
+            max_attempts: 0,
+            backoff: Duration::ZERO,
+            window: Duration::from_secs(60),
+        });
+        let producer = shared::ProcessId::Producer(1);
+        let addr: SocketAddr = "127.0.0.1:7001".parse().unwrap();
+        let child = tokio::process::Command::new("sleep").arg("60").spawn().unwrap();
+        let pid = child.id().unwrap();
+        let handle = ProcessHandle {
+            child,
+            info: ProcessInfo {
+                process_id: pid,
+                listen_address: addr,
+                command_address: addr,
+                start_time: Instant::now(),
+                process_type: ProcessType::Producer(producer.clone()),
+            },
+        };
+        manager.active_producers.lock().await.insert(producer.clone(), handle);
+
+        let outcome = manager
+            .restart_producer(producer.clone(), "test", HashMap::new(), "127.0.0.1:6001".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, RestartOutcome::GaveUp { attempts: 0 }));
+        assert!(manager.active_producers.lock().await.is_empty());
+        // The hung process was killed and reaped rather than left running
+        assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+    }
+
+    #[test]
+    fn test_producer_failing_past_max_attempts_is_not_restarted() {
+        let policy = RestartPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+            window: Duration::from_secs(60),
+        };
+        let producer = shared::ProcessId::Producer(2);
+        let mut tracker = RestartTracker::default();
+        let start = Instant::now();
+
+        // Restarts back off 1s, then 2s, then the fourth failure exhausts the policy
+        let mut now = start;
+        for delay in [0, 1, 2] {
+            now += Duration::from_secs(delay);
+            if delay > 0 {
+                assert_eq!(
+                    tracker.decide(&policy, &producer, now - Duration::from_millis(1)),
+                    RestartDecision::Wait
+                );
+            }
+            assert_eq!(tracker.decide(&policy, &producer, now), RestartDecision::Restart);
+            tracker.record(&producer, now);
+        }
+        now += Duration::from_secs(10);
+        assert_eq!(
+            tracker.decide(&policy, &producer, now),
+            RestartDecision::GiveUp { attempts: 3 }
+        );
+
+        // Giving up is permanent, while other slots keep their own budget
+        assert_eq!(
+            tracker.decide(&policy, &producer, now + Duration::from_secs(600)),
+            RestartDecision::GiveUp { attempts: 3 }
+        );
+        assert_eq!(
+            tracker.decide(&policy, &shared::ProcessId::Producer(1), now),
+            RestartDecision::Restart
+        );
+    }
+
+    #[test]
+    fn test_promoted_producer_inherits_restart_history() {
+        let policy = RestartPolicy {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+            window: Duration::from_secs(60),
+        };
+        let failed = shared::ProcessId::Producer(1);
+        let promoted = shared::ProcessId::Producer(6);
+        let mut tracker = RestartTracker::default();
+        let now = Instant::now();
+
+        tracker.record(&failed, now);
+        tracker.hand_over(&failed, &promoted);
+
+        // The warm replacement failing in turn counts against the same slot's budget
+        assert_eq!(tracker.decide(&policy, &promoted, now), RestartDecision::GiveUp { attempts: 1 });
+    }
+
+    #[test]
+    fn test_restarts_outside_window_are_forgotten() {
+        let policy = RestartPolicy {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+            window: Duration::from_secs(60),
+        };
+        let producer = shared::ProcessId::Producer(1);
+        let mut tracker = RestartTracker::default();
+        let start = Instant::now();
+
+        tracker.record(&producer, start);
+        assert_eq!(
+            tracker.decide(&policy, &producer, start + Duration::from_secs(61)),
+            RestartDecision::Restart
+        );
+    }
 }
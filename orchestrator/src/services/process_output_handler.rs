@@ -1,7 +1,8 @@
 //! Helper to handle child process stdout/stderr output
 //! 
 //! This module ensures child process output is properly handled:
-//! - If no tracing endpoint: forward to parent's stdout/stderr
+//! - If no tracing endpoint: forward to parent's stdout/stderr (stdout goes to stderr
+//!   when the parent's stdout is reserved for attribute output)
 //! - If tracing endpoint exists: let child process handle its own logging
 
 use std::process::Stdio;
@@ -13,6 +14,7 @@ use shared::{ProcessId, process_debug};
 pub fn configure_child_stdio(
     cmd: &mut tokio::process::Command,
     has_trace_endpoint: bool,
+    reserve_stdout: bool,
     process_name: &str,
 ) {
     if has_trace_endpoint {
@@ -27,6 +29,17 @@ pub fn configure_child_stdio(
             "📡 {} will send traces to endpoint (output not forwarded to parent)",
             process_name
         );
+    } else if reserve_stdout {
+        // Parent stdout carries attribute data, so child logs all go to stderr
+        cmd.stdout(Stdio::from(std::io::stderr()))
+           .stderr(Stdio::inherit())
+           .stdin(Stdio::null());
+
+        process_debug!(
+            ProcessId::current(),
+            "🔗 {} output will be forwarded to parent stderr",
+            process_name
+        );
     } else {
         // Without tracing endpoint: inherit parent's stdout/stderr
         cmd.stdout(Stdio::inherit())
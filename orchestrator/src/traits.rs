@@ -4,8 +4,10 @@
 //! Each trait is mockable for comprehensive testing.
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 
 use crate::error::OrchestratorResult;
@@ -57,6 +59,9 @@ pub trait Communicator: Send + Sync {
     /// Mark producer as ready to receive commands
     async fn mark_producer_ready(&self, producer_id: ProcessId, address: SocketAddr) -> OrchestratorResult<()>;
 
+    /// Whether a producer has reported ready and can take commands
+    async fn is_producer_ready(&self, producer_id: ProcessId) -> bool;
+
     /// Register webserver address for update sending
     async fn register_webserver(&self, address: SocketAddr) -> OrchestratorResult<()>;
 
@@ -97,8 +102,63 @@ pub trait FileSystem: Send + Sync {
     /// Append new unique attributes to output.txt file (one per line)
     async fn append_to_output(&self, topic: &str, new_attributes: &[String]) -> OrchestratorResult<()>;
 
+    /// Append a batch's attributes, which share their provenance, to the topic's attributes.csv
+    async fn append_csv_rows(
+        &self,
+        topic: &str,
+        attributes: &[String],
+        provider: ProviderId,
+        model: &str,
+        timestamp: u64,
+    ) -> OrchestratorResult<()>;
+
+    /// Append a batch of attribute records, one JSON line each, to the topic's attributes.jsonl
+    async fn append_jsonl(&self, topic: &str, records: &[AttributeRecord]) -> OrchestratorResult<()>;
+
+    /// Append attributes to the topic's by_category/<category>.txt (one per line)
+    async fn append_to_category(&self, topic: &str, category: &str, attributes: &[String]) -> OrchestratorResult<()>;
+
     /// Write arbitrary file content (for JSON exports)
     async fn write_file(&self, filename: &str, content: &[u8]) -> OrchestratorResult<()>;
+
+    /// Append content to a file, creating it if needed (for JSONL exports)
+    async fn append_file(&self, filename: &str, content: &[u8]) -> OrchestratorResult<()>;
+
+    /// Read a file written with `write_file` (None if it does not exist)
+    async fn read_file(&self, filename: &str) -> OrchestratorResult<Option<Vec<u8>>>;
+
+    /// Path of a file inside a topic's directory
+    fn topic_file_path(&self, topic: &str, filename: &str) -> PathBuf;
+
+    /// Create the output directory if needed and verify it accepts writes
+    async fn check_writable(&self) -> OrchestratorResult<()>;
+
+    /// Bytes available to us in the output directory (None if unknown on this platform)
+    async fn free_space(&self) -> OrchestratorResult<Option<u64>>;
+}
+
+/// A unique attribute with the provenance of the request that produced it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeRecord {
+    pub attribute: String,
+    pub provider: ProviderId,
+    pub model: String,
+    /// Token usage of the whole request the attribute came from
+    pub tokens: shared::TokenUsage,
+    /// When the request was made (Unix seconds)
+    pub timestamp: u64,
+}
+
+impl AttributeRecord {
+    pub fn new(attribute: &str, provider_metadata: &shared::ProviderMetadata) -> Self {
+        Self {
+            attribute: attribute.to_string(),
+            provider: provider_metadata.provider_id,
+            model: provider_metadata.model.clone(),
+            tokens: provider_metadata.tokens.clone(),
+            timestamp: provider_metadata.request_timestamp,
+        }
+    }
 }
 
 /// Process management service  
@@ -106,10 +166,12 @@ pub trait FileSystem: Send + Sync {
 #[async_trait]
 pub trait ProcessManager: Send + Sync {
     /// Spawn producer processes
+    ///
+    /// `topic` is None when the producers are split between concurrent topics.
     async fn spawn_producers(
         &self,
         count: u32,
-        topic: &str,
+        topic: Option<String>,
         api_keys: HashMap<ProviderId, String>,
         orchestrator_addr: SocketAddr,
         routing_strategy: Option<shared::RoutingStrategy>,
@@ -118,6 +180,27 @@ pub trait ProcessManager: Send + Sync {
     /// Spawn webserver process
     async fn spawn_webserver(&self, port: u16, orchestrator_addr: SocketAddr) -> OrchestratorResult<WebServerInfo>;
 
+    /// Replace a failed producer with an idle one from the warm pool, subject to the restart policy
+    ///
+    /// Returns None when the pool is empty or the policy does not allow a restart yet, in
+    /// which case the caller falls back to `restart_producer`. The promoted producer has
+    /// its own id and still needs a Start command.
+    async fn promote_warm_producer(
+        &self,
+        failed_producer_id: ProcessId,
+        api_keys: HashMap<ProviderId, String>,
+        orchestrator_addr: SocketAddr,
+    ) -> OrchestratorResult<Option<ProducerInfo>>;
+
+    /// Replace a failed producer in its slot, subject to the restart policy
+    async fn restart_producer(
+        &self,
+        failed_producer_id: ProcessId,
+        topic: &str,
+        api_keys: HashMap<ProviderId, String>,
+        orchestrator_addr: SocketAddr,
+    ) -> OrchestratorResult<RestartOutcome>;
+
     /// Check health of all managed processes
     async fn check_process_health(&self) -> OrchestratorResult<Vec<ProcessHealthInfo>>;
 
@@ -140,6 +223,17 @@ pub struct ProducerInfo {
     pub command_address: SocketAddr,
 }
 
+/// What happened when a failed producer was due for a restart
+#[derive(Debug, Clone)]
+pub enum RestartOutcome {
+    /// A replacement producer was spawned
+    Restarted(ProducerInfo),
+    /// Still backing off from the previous restart; retried on a later health check
+    Deferred,
+    /// Restart limit reached, so the producer is permanently failed
+    GaveUp { attempts: u32 },
+}
+
 /// Information about spawned webserver
 #[derive(Debug, Clone)]
 pub struct WebServerInfo {
@@ -0,0 +1,205 @@
+//! In-memory file system for asserting on orchestrator output without touching disk
+
+use async_trait::async_trait;
+use orchestrator::traits::{AttributeRecord, FileSystem};
+use orchestrator::OrchestratorResult;
+use shared::ProviderId;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Header row the real file system writes at the top of attributes.csv
+const CSV_HEADER: &str = "attribute,provider,model,timestamp\n";
+
+/// File system that keeps every write in memory for later inspection
+///
+/// Paths are relative to the output directory, laid out like `RealFileSystem`
+/// (`<topic>/output.txt`, `<topic>/attributes.csv`, `cycle_performance.json`, ...).
+#[derive(Debug, Default)]
+pub struct MockFileSystem {
+    files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    appended: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    directories: Mutex<Vec<PathBuf>>,
+}
+
+#[allow(dead_code)] // Not every test binary uses every inspection method
+impl MockFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of every file's current content
+    pub fn written_files(&self) -> HashMap<PathBuf, Vec<u8>> {
+        self.files.lock().unwrap().clone()
+    }
+
+    /// Current content of one file as UTF-8 (None if it was never written)
+    pub fn file_contents(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path.as_ref())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Lines appended to a file, in order, ignoring whole-file writes
+    pub fn appended_lines(&self, path: impl AsRef<Path>) -> Vec<String> {
+        self.appended
+            .lock()
+            .unwrap()
+            .get(path.as_ref())
+            .map(|bytes| String::from_utf8_lossy(bytes).lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Directories created so far, in creation order
+    pub fn created_directories(&self) -> Vec<PathBuf> {
+        self.directories.lock().unwrap().clone()
+    }
+
+    fn topic_dir(topic: &str) -> PathBuf {
+        if topic.is_empty() {
+            PathBuf::from("default")
+        } else {
+            PathBuf::from(topic)
+        }
+    }
+
+    fn write(&self, path: PathBuf, content: &[u8]) {
+        self.appended.lock().unwrap().remove(&path);
+        self.files.lock().unwrap().insert(path, content.to_vec());
+    }
+
+    fn append(&self, path: PathBuf, content: &[u8]) {
+        self.files.lock().unwrap().entry(path.clone()).or_default().extend_from_slice(content);
+        self.appended.lock().unwrap().entry(path).or_default().extend_from_slice(content);
+    }
+}
+
+#[async_trait]
+impl FileSystem for MockFileSystem {
+    async fn create_topic_directory(&self, topic: &str) -> OrchestratorResult<()> {
+        self.directories.lock().unwrap().push(Self::topic_dir(topic));
+        Ok(())
+    }
+
+    async fn write_unique_attributes(&self, topic: &str, attributes: &[String]) -> OrchestratorResult<()> {
+        let lines: String = attributes
+            .iter()
+            .map(|attribute| format!("{}\n", serde_json::json!({ "attribute": attribute })))
+            .collect();
+        self.append(Self::topic_dir(topic).join("output.json"), lines.as_bytes());
+        Ok(())
+    }
+
+    async fn write_unique_attributes_with_metadata(
+        &self,
+        topic: &str,
+        attributes: &[String],
+        provider_metadata: &shared::types::ProviderMetadata,
+    ) -> OrchestratorResult<()> {
+        let lines: String = attributes
+            .iter()
+            .map(|attribute| {
+                let entry = serde_json::json!({
+                    "attribute": attribute,
+                    "model": provider_metadata.model,
+                    "provider": provider_metadata.provider_id.to_string().to_lowercase(),
+                });
+                format!("{entry}\n")
+            })
+            .collect();
+        self.append(Self::topic_dir(topic).join("output.json"), lines.as_bytes());
+        Ok(())
+    }
+
+    async fn read_topic_attributes(&self, topic: &str) -> OrchestratorResult<Vec<String>> {
+        let content = self.file_contents(Self::topic_dir(topic).join("output.json")).unwrap_or_default();
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter_map(|entry| entry.get("attribute").and_then(|v| v.as_str()).map(str::to_string))
+            .collect())
+    }
+
+    async fn cleanup_topic(&self, topic: &str) -> OrchestratorResult<()> {
+        let topic_dir = Self::topic_dir(topic);
+        self.files.lock().unwrap().retain(|path, _| !path.starts_with(&topic_dir));
+        self.appended.lock().unwrap().retain(|path, _| !path.starts_with(&topic_dir));
+        self.directories.lock().unwrap().retain(|dir| dir != &topic_dir);
+        Ok(())
+    }
+
+    async fn sync_to_disk(&self) -> OrchestratorResult<()> {
+        Ok(())
+    }
+
+    async fn append_to_output(&self, topic: &str, new_attributes: &[String]) -> OrchestratorResult<()> {
+        let lines: String = new_attributes.iter().map(|attribute| format!("{attribute}\n")).collect();
+        self.append(Self::topic_dir(topic).join("output.txt"), lines.as_bytes());
+        Ok(())
+    }
+
+    async fn append_csv_rows(
+        &self,
+        topic: &str,
+        attributes: &[String],
+        provider: ProviderId,
+        model: &str,
+        timestamp: u64,
+    ) -> OrchestratorResult<()> {
+        let path = Self::topic_dir(topic).join("attributes.csv");
+        if !self.files.lock().unwrap().contains_key(&path) {
+            self.append(path.clone(), CSV_HEADER.as_bytes());
+        }
+        let rows: String = attributes
+            .iter()
+            .map(|attribute| format!("{attribute},{provider},{model},{timestamp}\n"))
+            .collect();
+        self.append(path, rows.as_bytes());
+        Ok(())
+    }
+
+    async fn append_jsonl(&self, topic: &str, records: &[AttributeRecord]) -> OrchestratorResult<()> {
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(&serde_json::to_string(record)?);
+            lines.push('\n');
+        }
+        self.append(Self::topic_dir(topic).join("attributes.jsonl"), lines.as_bytes());
+        Ok(())
+    }
+
+    async fn append_to_category(&self, topic: &str, category: &str, attributes: &[String]) -> OrchestratorResult<()> {
+        let lines: String = attributes.iter().map(|attribute| format!("{attribute}\n")).collect();
+        let path = Self::topic_dir(topic).join("by_category").join(format!("{category}.txt"));
+        self.append(path, lines.as_bytes());
+        Ok(())
+    }
+
+    async fn write_file(&self, filename: &str, content: &[u8]) -> OrchestratorResult<()> {
+        self.write(PathBuf::from(filename), content);
+        Ok(())
+    }
+
+    async fn append_file(&self, filename: &str, content: &[u8]) -> OrchestratorResult<()> {
+        self.append(PathBuf::from(filename), content);
+        Ok(())
+    }
+
+    async fn read_file(&self, filename: &str) -> OrchestratorResult<Option<Vec<u8>>> {
+        Ok(self.files.lock().unwrap().get(Path::new(filename)).cloned())
+    }
+
+    fn topic_file_path(&self, topic: &str, filename: &str) -> PathBuf {
+        Self::topic_dir(topic).join(filename)
+    }
+
+    async fn check_writable(&self) -> OrchestratorResult<()> {
+        Ok(())
+    }
+
+    async fn free_space(&self) -> OrchestratorResult<Option<u64>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,5 @@
+//! Shared utilities for orchestrator integration tests
+
+pub mod file_system;
+
+pub use file_system::MockFileSystem;
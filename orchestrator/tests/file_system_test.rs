@@ -0,0 +1,77 @@
+//! Output exports asserted against the in-memory file system
+
+mod common;
+
+use common::MockFileSystem;
+use orchestrator::traits::FileSystem;
+use orchestrator::OrchestratorState;
+use shared::{GenerationConstraints, OptimizationMode, ProcessId, ProviderId};
+
+fn metadata() -> shared::ProviderMetadata {
+    shared::ProviderMetadata {
+        provider_id: ProviderId::Random,
+        model: "random".to_string(),
+        response_time_ms: 1,
+        tokens: shared::types::TokenUsage::default(),
+        request_timestamp: 0,
+    }
+}
+
+#[tokio::test]
+async fn test_export_cycle_performance_writes_cycles_as_json() {
+    ProcessId::init_orchestrator();
+    let file_system = MockFileSystem::new();
+    let mut state = OrchestratorState::new();
+    state.start_generation(
+        "animals".to_string(),
+        OptimizationMode::MaximizeEfficiency,
+        GenerationConstraints {
+            max_cost_per_minute: 1.0,
+            target_uam: 100.0,
+            max_runtime_seconds: None,
+            min_efficiency_percent: None,
+            efficiency_patience: None,
+            target_unique_count: None,
+        },
+    );
+
+    // Nothing to export before the first cycle completes
+    state.export_cycle_performance(&file_system).await.unwrap();
+    assert!(file_system.written_files().is_empty());
+
+    state.add_attributes(ProcessId::Producer(1), vec!["lion".to_string(), "tiger".to_string()], &metadata(), 0.0);
+    state.increment_iteration();
+    state.add_attributes(ProcessId::Producer(1), vec!["lion".to_string(), "otter".to_string()], &metadata(), 0.0);
+    state.increment_iteration();
+    state.export_cycle_performance(&file_system).await.unwrap();
+
+    assert_eq!(file_system.written_files().len(), 1);
+    let json: serde_json::Value =
+        serde_json::from_str(&file_system.file_contents("cycle_performance.json").unwrap()).unwrap();
+    assert_eq!(json["topic"], "animals");
+    assert_eq!(json["total_cycles"], 2);
+    assert_eq!(json["cycles"][0]["new_values"], 2);
+    assert_eq!(json["cycles"][1]["new_values"], 1);
+    assert_eq!(json["summary"]["total_unique_attributes"], 3);
+}
+
+#[tokio::test]
+async fn test_topic_appends_are_recorded_per_file() {
+    let file_system = MockFileSystem::new();
+    file_system.create_topic_directory("animals").await.unwrap();
+    file_system
+        .append_to_output("animals", &["lion".to_string(), "otter".to_string()])
+        .await
+        .unwrap();
+    file_system
+        .append_csv_rows("animals", &["lion".to_string()], ProviderId::Random, "random", 42)
+        .await
+        .unwrap();
+
+    assert_eq!(file_system.created_directories(), vec![std::path::PathBuf::from("animals")]);
+    assert_eq!(file_system.appended_lines("animals/output.txt"), vec!["lion", "otter"]);
+    assert_eq!(
+        file_system.appended_lines("animals/attributes.csv"),
+        vec!["attribute,provider,model,timestamp", "lion,random,random,42"]
+    );
+}
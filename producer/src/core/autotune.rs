@@ -0,0 +1,259 @@
+//! AIMD auto-tuning of per-provider concurrency
+//!
+//! Each provider gets an additive-increase / multiplicative-decrease controller.
+//! Concurrency grows by a fixed step after every clean window (high success rate,
+//! stable latency) and is cut by a factor when errors or latency rise.
+
+use shared::{ProviderId, TunedLimits};
+use std::collections::HashMap;
+
+/// Tuning parameters shared by all provider controllers
+#[derive(Debug, Clone)]
+pub struct AimdConfig {
+    /// Lowest concurrency the controller will back off to
+    pub min_concurrency: usize,
+    /// Highest concurrency the controller will probe
+    pub max_concurrency: usize,
+    /// Concurrency added after a clean window
+    pub increase_step: usize,
+    /// Multiplier applied to concurrency after a bad window
+    pub decrease_factor: f64,
+    /// Minimum success rate for a window to count as clean
+    pub success_threshold: f64,
+    /// Allowed average latency relative to the best clean window
+    pub latency_tolerance: f64,
+    /// Number of outcomes evaluated per window
+    pub window: usize,
+}
+
+impl Default for AimdConfig {
+    fn default() -> Self {
+        Self {
+            min_concurrency: 1,
+            max_concurrency: 32,
+            increase_step: 1,
+            decrease_factor: 0.5,
+            success_threshold: 0.95,
+            latency_tolerance: 1.5,
+            window: 10,
+        }
+    }
+}
+
+/// AIMD controller for a single provider
+#[derive(Debug, Clone)]
+pub struct AimdController {
+    config: AimdConfig,
+    concurrency: usize,
+    successes: usize,
+    failures: usize,
+    latency_sum_ms: u64,
+    /// Lowest average latency seen in a clean window
+    baseline_latency_ms: Option<f64>,
+    /// Highest concurrency that completed a clean window and has not failed since
+    optimal_concurrency: usize,
+    /// Average latency of the most recent clean window
+    clean_latency_ms: Option<f64>,
+}
+
+impl AimdController {
+    pub fn new(config: AimdConfig) -> Self {
+        let concurrency = config.min_concurrency.max(1);
+        Self {
+            config,
+            concurrency,
+            successes: 0,
+            failures: 0,
+            latency_sum_ms: 0,
+            baseline_latency_ms: None,
+            optimal_concurrency: 0,
+            clean_latency_ms: None,
+        }
+    }
+
+    /// Current concurrency limit
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Highest concurrency measured as safe so far
+    pub fn optimal_concurrency(&self) -> usize {
+        self.optimal_concurrency
+    }
+
+    /// Record one request outcome, adjusting the limit when a window completes
+    ///
+    /// `launched_at` is the concurrency the request was issued under; outcomes of
+    /// requests launched above the current limit predate a back-off and are ignored.
+    pub fn record(&mut self, launched_at: usize, success: bool, latency_ms: u64) {
+        if launched_at > self.concurrency {
+            return;
+        }
+
+        if success {
+            self.successes += 1;
+            self.latency_sum_ms += latency_ms;
+        } else {
+            self.failures += 1;
+        }
+
+        if self.successes + self.failures >= self.config.window.max(1) {
+            self.evaluate_window();
+        }
+    }
+
+    fn evaluate_window(&mut self) {
+        let total = self.successes + self.failures;
+        let success_rate = self.successes as f64 / total as f64;
+        let avg_latency = if self.successes > 0 {
+            Some(self.latency_sum_ms as f64 / self.successes as f64)
+        } else {
+            None
+        };
+        let latency_stable = match (avg_latency, self.baseline_latency_ms) {
+            (Some(avg), Some(baseline)) => avg <= baseline * self.config.latency_tolerance,
+            _ => true,
+        };
+
+        if success_rate >= self.config.success_threshold && latency_stable {
+            self.optimal_concurrency = self.optimal_concurrency.max(self.concurrency);
+            self.clean_latency_ms = avg_latency.or(self.clean_latency_ms);
+            if let Some(avg) = avg_latency {
+                self.baseline_latency_ms = Some(self.baseline_latency_ms.map_or(avg, |b| b.min(avg)));
+            }
+            self.concurrency = (self.concurrency + self.config.increase_step).min(self.config.max_concurrency);
+        } else {
+            // A level that failed can no longer be considered optimal
+            if self.concurrency <= self.optimal_concurrency {
+                self.optimal_concurrency = self.concurrency.saturating_sub(1);
+            }
+            let reduced = (self.concurrency as f64 * self.config.decrease_factor).floor() as usize;
+            self.concurrency = reduced.max(self.config.min_concurrency).max(1);
+        }
+
+        self.successes = 0;
+        self.failures = 0;
+        self.latency_sum_ms = 0;
+    }
+
+    /// Measured limits, or None until a clean window has completed
+    pub fn tuned_limits(&self) -> Option<TunedLimits> {
+        let latency = self.clean_latency_ms?;
+        if self.optimal_concurrency == 0 {
+            return None;
+        }
+        // Little's law: throughput = concurrency / latency
+        let max_rpm = (self.optimal_concurrency as f64 * 60_000.0 / latency.max(1.0)) as u32;
+        Some(TunedLimits {
+            max_concurrent: self.optimal_concurrency,
+            max_rpm,
+        })
+    }
+}
+
+/// Per-provider AIMD controllers
+#[derive(Debug, Clone, Default)]
+pub struct AutoTuner {
+    config: AimdConfig,
+    controllers: HashMap<ProviderId, AimdController>,
+}
+
+impl AutoTuner {
+    pub fn new(config: AimdConfig) -> Self {
+        Self {
+            config,
+            controllers: HashMap::new(),
+        }
+    }
+
+    fn controller(&mut self, provider: ProviderId) -> &mut AimdController {
+        let config = &self.config;
+        self.controllers
+            .entry(provider)
+            .or_insert_with(|| AimdController::new(config.clone()))
+    }
+
+    /// Record a request outcome for a provider
+    pub fn record(&mut self, provider: ProviderId, launched_at: usize, success: bool, latency_ms: u64) {
+        self.controller(provider).record(launched_at, success, latency_ms);
+    }
+
+    /// Concurrency usable across the given providers (the most conservative limit)
+    pub fn concurrency_for(&mut self, providers: &[ProviderId]) -> usize {
+        providers
+            .iter()
+            .map(|provider| self.controller(*provider).concurrency())
+            .min()
+            .unwrap_or(self.config.min_concurrency.max(1))
+    }
+
+    /// Measured limits for every provider that has completed a clean window
+    pub fn tuned_limits(&self) -> HashMap<ProviderId, TunedLimits> {
+        self.controllers
+            .iter()
+            .filter_map(|(provider, controller)| controller.tuned_limits().map(|limits| (*provider, limits)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mock provider that fails every request once concurrency exceeds a threshold
+    struct ThresholdProvider {
+        threshold: usize,
+    }
+
+    impl ThresholdProvider {
+        fn call(&self, in_flight: usize) -> (bool, u64) {
+            (in_flight <= self.threshold, 100 + in_flight as u64)
+        }
+    }
+
+    #[test]
+    fn test_controller_settles_below_error_threshold() {
+        let provider = ThresholdProvider { threshold: 6 };
+        let mut tuner = AutoTuner::new(AimdConfig::default());
+        let mut peak = 0;
+
+        for _ in 0..500 {
+            let batch = tuner.concurrency_for(&[ProviderId::OpenAI]);
+            peak = peak.max(batch);
+            for _ in 0..batch {
+                let (success, latency) = provider.call(batch);
+                tuner.record(ProviderId::OpenAI, batch, success, latency);
+            }
+        }
+
+        // The controller probes at most one step past the threshold
+        assert!(peak <= provider.threshold + 1);
+        let limits = tuner.tuned_limits()[&ProviderId::OpenAI];
+        assert_eq!(limits.max_concurrent, provider.threshold);
+        assert!(limits.max_rpm > 0);
+    }
+
+    #[test]
+    fn test_controller_backs_off_when_latency_rises() {
+        let mut controller = AimdController::new(AimdConfig {
+            window: 2,
+            ..AimdConfig::default()
+        });
+        controller.record(1, true, 100);
+        controller.record(1, true, 100);
+        assert_eq!(controller.concurrency(), 2);
+
+        controller.record(2, true, 400);
+        controller.record(2, true, 400);
+        assert_eq!(controller.concurrency(), 1);
+        assert_eq!(controller.optimal_concurrency(), 1);
+    }
+
+    #[test]
+    fn test_no_limits_before_clean_window() {
+        let mut tuner = AutoTuner::new(AimdConfig::default());
+        tuner.record(ProviderId::Random, 1, false, 0);
+        assert_eq!(tuner.concurrency_for(&[ProviderId::Random, ProviderId::Gemini]), 1);
+        assert!(tuner.tuned_limits().is_empty());
+    }
+}
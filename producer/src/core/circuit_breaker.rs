@@ -0,0 +1,183 @@
+//! Per-provider circuit breakers for the CircuitBreaker routing strategy
+//!
+//! A provider's circuit opens after a run of consecutive failures and stays open
+//! for a cooldown. Once the cooldown elapses the circuit is half-open: a single
+//! probe request is let through, closing the circuit on success and re-opening
+//! it on failure.
+
+use shared::ProviderId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Breaker state for a single provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests flow normally
+    Closed,
+    /// Requests are skipped until the given instant
+    Open { until: Instant },
+    /// Cooldown elapsed; one probe decides whether the circuit closes
+    HalfOpen,
+}
+
+/// State change caused by recording an outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerTransition {
+    Opened,
+    Closed,
+}
+
+#[derive(Debug, Clone)]
+struct Breaker {
+    state: BreakerState,
+    consecutive_failures: u32,
+    probe_in_flight: bool,
+}
+
+impl Default for Breaker {
+    fn default() -> Self {
+        Self {
+            state: BreakerState::Closed,
+            consecutive_failures: 0,
+            probe_in_flight: false,
+        }
+    }
+}
+
+/// Circuit breakers for every provider the producer has routed to
+#[derive(Debug, Clone, Default)]
+pub struct CircuitState {
+    breakers: HashMap<ProviderId, Breaker>,
+}
+
+impl CircuitState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current state of a provider's breaker, moving open circuits to half-open once their cooldown elapses
+    pub fn state(&mut self, provider: ProviderId, now: Instant) -> BreakerState {
+        let breaker = self.breakers.entry(provider).or_default();
+        if let BreakerState::Open { until } = breaker.state {
+            if now >= until {
+                breaker.state = BreakerState::HalfOpen;
+                breaker.probe_in_flight = false;
+            }
+        }
+        breaker.state
+    }
+
+    /// Whether a request may be sent to the provider right now
+    pub fn is_available(&mut self, provider: ProviderId, now: Instant) -> bool {
+        match self.state(provider, now) {
+            BreakerState::Closed => true,
+            BreakerState::Open { .. } => false,
+            BreakerState::HalfOpen => !self.breakers[&provider].probe_in_flight,
+        }
+    }
+
+    /// Note that a request is being sent; a half-open circuit allows only one
+    pub fn begin_request(&mut self, provider: ProviderId) {
+        let breaker = self.breakers.entry(provider).or_default();
+        if breaker.state == BreakerState::HalfOpen {
+            breaker.probe_in_flight = true;
+        }
+    }
+
+    /// Record a request outcome, returning the transition it caused (if any)
+    pub fn record(
+        &mut self,
+        provider: ProviderId,
+        success: bool,
+        failure_threshold: u32,
+        cooldown: Duration,
+        now: Instant,
+    ) -> Option<BreakerTransition> {
+        let breaker = self.breakers.entry(provider).or_default();
+        match (breaker.state, success) {
+            (BreakerState::Closed, true) => {
+                breaker.consecutive_failures = 0;
+                None
+            }
+            (BreakerState::Closed, false) => {
+                breaker.consecutive_failures += 1;
+                if breaker.consecutive_failures >= failure_threshold.max(1) {
+                    breaker.state = BreakerState::Open { until: now + cooldown };
+                    Some(BreakerTransition::Opened)
+                } else {
+                    None
+                }
+            }
+            (BreakerState::HalfOpen, true) => {
+                *breaker = Breaker::default();
+                Some(BreakerTransition::Closed)
+            }
+            (BreakerState::HalfOpen, false) => {
+                breaker.state = BreakerState::Open { until: now + cooldown };
+                breaker.probe_in_flight = false;
+                Some(BreakerTransition::Opened)
+            }
+            // Late outcomes of requests sent before the circuit opened
+            (BreakerState::Open { .. }, _) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_opens_after_threshold_and_probes_after_cooldown() {
+        let mut circuits = CircuitState::new();
+        let start = Instant::now();
+
+        assert_eq!(circuits.record(ProviderId::OpenAI, false, 3, COOLDOWN, start), None);
+        assert_eq!(circuits.record(ProviderId::OpenAI, false, 3, COOLDOWN, start), None);
+        assert_eq!(
+            circuits.record(ProviderId::OpenAI, false, 3, COOLDOWN, start),
+            Some(BreakerTransition::Opened)
+        );
+        assert!(!circuits.is_available(ProviderId::OpenAI, start + Duration::from_secs(10)));
+
+        // After the cooldown exactly one probe is allowed
+        let later = start + COOLDOWN;
+        assert!(circuits.is_available(ProviderId::OpenAI, later));
+        circuits.begin_request(ProviderId::OpenAI);
+        assert!(!circuits.is_available(ProviderId::OpenAI, later));
+
+        assert_eq!(
+            circuits.record(ProviderId::OpenAI, true, 3, COOLDOWN, later),
+            Some(BreakerTransition::Closed)
+        );
+        assert_eq!(circuits.state(ProviderId::OpenAI, later), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_circuit() {
+        let mut circuits = CircuitState::new();
+        let start = Instant::now();
+        circuits.record(ProviderId::Gemini, false, 1, COOLDOWN, start);
+
+        let later = start + COOLDOWN;
+        assert!(circuits.is_available(ProviderId::Gemini, later));
+        circuits.begin_request(ProviderId::Gemini);
+        assert_eq!(
+            circuits.record(ProviderId::Gemini, false, 1, COOLDOWN, later),
+            Some(BreakerTransition::Opened)
+        );
+        assert!(!circuits.is_available(ProviderId::Gemini, later + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let mut circuits = CircuitState::new();
+        let now = Instant::now();
+        circuits.record(ProviderId::Anthropic, false, 2, COOLDOWN, now);
+        circuits.record(ProviderId::Anthropic, true, 2, COOLDOWN, now);
+        assert_eq!(circuits.record(ProviderId::Anthropic, false, 2, COOLDOWN, now), None);
+        assert!(circuits.is_available(ProviderId::Anthropic, now));
+    }
+}
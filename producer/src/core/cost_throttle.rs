@@ -0,0 +1,68 @@
+//! Self-throttling against the producer's share of the cost budget
+//!
+//! The orchestrator splits `max_cost_per_minute` evenly across producers and sends
+//! each its share. The producer compares that share with what it spent over the last
+//! minute and stretches its request interval once spending passes
+//! `THROTTLE_THRESHOLD` of the share, so the budget is approached gradually instead
+//! of being overshot and corrected from the orchestrator after the fact.
+
+use std::time::Duration;
+
+/// Share of the budget spent before the request interval starts to stretch
+pub const THROTTLE_THRESHOLD: f64 = 0.8;
+
+/// How much the interval grows per unit of budget usage past the threshold (4x at the budget)
+const SLOWDOWN_PER_USAGE: f64 = 15.0;
+
+/// Longest interval the throttle will impose between requests
+pub const MAX_THROTTLED_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Request interval for a producer spending `cost_per_minute` against `budget`
+///
+/// Returns `base` unchanged without a positive budget or below the threshold; past it
+/// the interval grows linearly with usage and is capped at `MAX_THROTTLED_INTERVAL`.
+pub fn throttled_interval(base: Duration, cost_per_minute: f64, budget: Option<f64>) -> Duration {
+    let Some(budget) = budget.filter(|budget| budget.is_finite() && *budget > 0.0) else {
+        return base;
+    };
+    let usage = cost_per_minute / budget;
+    if !usage.is_finite() || usage <= THROTTLE_THRESHOLD {
+        return base;
+    }
+
+    let factor = 1.0 + SLOWDOWN_PER_USAGE * (usage - THROTTLE_THRESHOLD);
+    let throttled = base.as_secs_f64() * factor;
+    Duration::from_secs_f64(throttled.min(MAX_THROTTLED_INTERVAL.as_secs_f64())).max(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn test_interval_unchanged_without_budget_or_below_threshold() {
+        assert_eq!(throttled_interval(BASE, 10.0, None), BASE);
+        assert_eq!(throttled_interval(BASE, 10.0, Some(0.0)), BASE);
+        assert_eq!(throttled_interval(BASE, 0.5, Some(1.0)), BASE);
+        assert_eq!(throttled_interval(BASE, 0.8, Some(1.0)), BASE);
+    }
+
+    #[test]
+    fn test_interval_grows_as_spending_nears_budget() {
+        let near = throttled_interval(BASE, 0.9, Some(1.0));
+        let at = throttled_interval(BASE, 1.0, Some(1.0));
+        let over = throttled_interval(BASE, 2.0, Some(1.0));
+
+        assert!(near > BASE);
+        assert!(at > near);
+        assert!(over > at);
+        assert!((at.as_secs_f64() - 0.4).abs() < 1e-6, "4x at the budget, got {at:?}");
+    }
+
+    #[test]
+    fn test_interval_is_capped() {
+        assert_eq!(throttled_interval(BASE, 1e9, Some(1.0)), MAX_THROTTLED_INTERVAL);
+    }
+}
@@ -0,0 +1,66 @@
+//! Dead-letter log for responses that yielded no attributes
+//!
+//! A successful response from which nothing could be extracted usually means the
+//! model ignored the requested format. Rather than discarding it silently, its raw
+//! content is appended to `<output>/<topic>/dead_letter.log` (the orchestrator's
+//! topic directory layout) as one JSON object per line, so prompt and format
+//! problems can be inspected after the run.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::ProviderId;
+use std::io;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+
+/// File name of the dead-letter log inside a topic's output directory
+pub const DEAD_LETTER_FILE: &str = "dead_letter.log";
+
+/// One response that produced no attributes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub timestamp: DateTime<Utc>,
+    pub topic: String,
+    pub provider: ProviderId,
+    pub model: String,
+    pub finish_reason: Option<String>,
+    pub content: String,
+}
+
+/// Appends dead letters under an output directory, one file per topic
+#[derive(Debug, Clone)]
+pub struct DeadLetterLog {
+    output_dir: PathBuf,
+}
+
+impl DeadLetterLog {
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+        }
+    }
+
+    /// Log file for a topic; an empty topic maps to "default" like the orchestrator's output
+    pub fn path(&self, topic: &str) -> PathBuf {
+        let topic_dir = if topic.is_empty() { "default" } else { topic };
+        self.output_dir.join(topic_dir).join(DEAD_LETTER_FILE)
+    }
+
+    /// Append a dead letter to its topic's log, creating the directory if needed
+    pub async fn record(&self, letter: &DeadLetter) -> io::Result<()> {
+        let path = self.path(&letter.topic);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let mut line = serde_json::to_string(letter)?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+}
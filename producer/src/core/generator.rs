@@ -12,6 +12,7 @@ pub struct CommandGenerator {
     last_request: Instant,
     request_interval: Duration,
     routing_strategy: RoutingStrategy,
+    seed: u64,
 }
 
 impl CommandGenerator {
@@ -22,9 +23,16 @@ impl CommandGenerator {
             last_request: Instant::now() - interval, // Allow immediate first request
             request_interval: interval,
             routing_strategy,
+            seed: rand::random(),
         }
     }
 
+    /// Use a fixed run seed for the simulated Start command
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
     /// Generate the next command for test mode (pure function)
     pub fn next_command(&mut self, base_prompt: &str) -> Option<ProducerCommand> {
         let now = Instant::now();
@@ -63,7 +71,17 @@ impl CommandGenerator {
                     max_tokens: 1000,
                     temperature: 0.8,
                     request_size: 10,
+                    extraction_format: None,
+                    system_prompt: None,
+                    min_attribute_length: None,
+                    max_attribute_length: None,
+                    include_seen_hint: false,
+                    seen_hint_count: 0,
                 },
+                seed: self.seed,
+                max_cost_per_minute: None,
+                dedup: true,
+                category: None,
             })
         } else {
             None // Let the main loop handle request generation
@@ -2,11 +2,18 @@
 
 use crate::types::{ApiResponse, ProcessedAttribute, ProducerMetrics};
 use chrono::Utc;
-use shared::ProviderId;
+use shared::messages::producer::ProviderUsageStats;
+use shared::{ApiFailure, ProviderId};
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
+/// Window over which the producer's cost rate is measured
+const COST_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Latency a failed response counts as for lowest-latency routing
+pub const FAILED_RESPONSE_LATENCY_MS: u64 = 30_000;
+
 /// Performance metrics calculator and tracker
 pub struct Metrics {
     /// Current metrics state
@@ -18,6 +25,15 @@ pub struct Metrics {
     /// Per-provider statistics
     provider_stats: HashMap<ProviderId, ProviderStats>,
 
+    /// Recent response times per provider (failures at `FAILED_RESPONSE_LATENCY_MS` or more), with when they were recorded
+    recent_latencies: HashMap<ProviderId, VecDeque<(Instant, u64)>>,
+
+    /// Extracted and new value counts of recent processed responses
+    recent_uniqueness: VecDeque<(u64, u64)>,
+
+    /// Costs incurred within the last minute, with when they were recorded
+    recent_costs: VecDeque<(Instant, f64)>,
+
     /// Start time for uptime calculation
     start_time: Option<Instant>,
 
@@ -38,6 +54,8 @@ struct ProviderStats {
     total_cost: f64,
     success_count: u64,
     error_count: u64,
+    unique_attributes: u64,
+    last_used_ms: u64,
 }
 
 impl ProviderStats {
@@ -71,6 +89,9 @@ impl Metrics {
             current_metrics: ProducerMetrics::new(),
             response_times: VecDeque::with_capacity(window_size),
             provider_stats: HashMap::new(),
+            recent_latencies: HashMap::new(),
+            recent_uniqueness: VecDeque::with_capacity(window_size),
+            recent_costs: VecDeque::new(),
             start_time: None,
             total_cost: 0.0,
             history_window: window_size,
@@ -109,12 +130,31 @@ impl Metrics {
         stats.responses_received += 1;
         stats.total_response_time_ms += response.response_time_ms;
         stats.total_tokens_used += response.tokens_used.total();
+        stats.last_used_ms = Utc::now().timestamp_millis() as u64;
+
+        if response.is_truncated() {
+            self.current_metrics.truncated_responses += 1;
+        }
+        if response.failure == Some(ApiFailure::ContentFiltered) {
+            self.current_metrics.content_filtered += 1;
+        }
+        if response.cached {
+            self.current_metrics.cache_hits += 1;
+        }
 
-        if response.success {
+        // A failure counts as a slow response, so a provider that keeps failing stops looking fastest
+        let latency_ms = if response.success {
             stats.success_count += 1;
+            response.response_time_ms
         } else {
             stats.error_count += 1;
+            response.response_time_ms.max(FAILED_RESPONSE_LATENCY_MS)
+        };
+        let latencies = self.recent_latencies.entry(response.provider).or_default();
+        if latencies.len() >= self.history_window {
+            latencies.pop_front();
         }
+        latencies.push_back((Instant::now(), latency_ms));
 
         debug!(
             "Recorded response from {:?}: {}ms, {} tokens (input: {}, output: {}), success: {}",
@@ -142,9 +182,19 @@ impl Metrics {
     }
 
     /// Record processing statistics (more efficient approach)
-    pub fn record_processing_stats(&mut self, stats: &crate::core::processor::ProcessingStats) {
+    pub fn record_processing_stats(&mut self, provider: ProviderId, stats: &crate::core::processor::ProcessingStats) {
         self.current_metrics.attributes_extracted += stats.total_extracted as u64;
         self.current_metrics.unique_attributes += stats.new_values.len() as u64;
+        if stats.is_empty() {
+            self.current_metrics.empty_responses += 1;
+        }
+        self.provider_stats.entry(provider).or_default().unique_attributes += stats.new_values.len() as u64;
+
+        if self.recent_uniqueness.len() >= self.history_window {
+            self.recent_uniqueness.pop_front();
+        }
+        self.recent_uniqueness
+            .push_back((stats.total_extracted as u64, stats.new_values.len() as u64));
 
         debug!(
             "Recorded {} attributes processed ({} new, {} duplicates)",
@@ -163,10 +213,28 @@ impl Metrics {
         let stats = self.provider_stats.entry(provider).or_default();
         stats.total_cost += cost;
 
+        let now = Instant::now();
+        self.prune_recent_costs(now);
+        self.recent_costs.push_back((now, cost));
+
         debug!("Recorded cost for {:?}: ${:.4}", provider, cost);
         self.update_timestamp();
     }
 
+    /// Record a verification spot-check, kept apart from generation requests and cost
+    pub fn record_verification(&mut self, provider: ProviderId, samples: usize, conflicts: u64, cost: f64) {
+        self.current_metrics.verification_requests += 1;
+        self.current_metrics.verification_samples += samples as u64;
+        self.current_metrics.verification_conflicts += conflicts;
+        self.current_metrics.verification_cost += cost;
+
+        debug!(
+            "Recorded verification by {:?}: {} sampled, {} conflicts, ${:.4}",
+            provider, samples, conflicts, cost
+        );
+        self.update_timestamp();
+    }
+
     /// Get current metrics snapshot
     pub fn get_current_metrics(&self) -> ProducerMetrics {
         let mut metrics = self.current_metrics.clone();
@@ -199,6 +267,26 @@ impl Metrics {
             .collect()
     }
 
+    /// Cumulative per-provider request outcomes, as reported to the orchestrator
+    pub fn get_provider_usage(&self) -> HashMap<ProviderId, ProviderUsageStats> {
+        self.provider_stats
+            .iter()
+            .map(|(provider, stats)| {
+                (
+                    *provider,
+                    ProviderUsageStats {
+                        requests_sent: stats.requests_sent,
+                        successful_responses: stats.success_count,
+                        failed_responses: stats.error_count,
+                        unique_attributes_contributed: stats.unique_attributes,
+                        avg_response_time_ms: stats.avg_response_time_ms(),
+                        last_used_timestamp: stats.last_used_ms,
+                    },
+                )
+            })
+            .collect()
+    }
+
     /// Get performance insights
     pub fn get_insights(&self) -> Vec<PerformanceInsight> {
         let mut insights = Vec::new();
@@ -264,11 +352,71 @@ impl Metrics {
         insights
     }
 
+    /// Tokens consumed by a provider's responses so far
+    pub fn tokens_used(&self, provider: ProviderId) -> u64 {
+        self.provider_stats.get(&provider).map_or(0, |stats| stats.total_tokens_used)
+    }
+
+    /// Rolling average response time of a provider's successful responses (None without data in the window)
+    pub fn avg_response_time(&self, provider: ProviderId, window: Duration) -> Option<f64> {
+        let latencies = self.recent_latencies.get(&provider)?;
+        let cutoff = Instant::now().checked_sub(window);
+        let recent: Vec<u64> = latencies
+            .iter()
+            .filter(|(at, _)| cutoff.is_none_or(|cutoff| *at >= cutoff))
+            .map(|(_, ms)| *ms)
+            .collect();
+        if recent.is_empty() {
+            None
+        } else {
+            Some(recent.iter().sum::<u64>() as f64 / recent.len() as f64)
+        }
+    }
+
+    /// Share of new values among those extracted by the last `responses` processed responses
+    /// (None when they extracted nothing)
+    pub fn uniqueness_ratio(&self, responses: usize) -> Option<f64> {
+        let (extracted, new) = self
+            .recent_uniqueness
+            .iter()
+            .rev()
+            .take(responses)
+            .fold((0, 0), |(extracted, new), (e, n)| (extracted + e, new + n));
+        if extracted == 0 {
+            None
+        } else {
+            Some(new as f64 / extracted as f64)
+        }
+    }
+
+    /// Cost incurred over the last minute, the producer's current spend rate in USD per minute
+    pub fn cost_per_minute(&self) -> f64 {
+        let cutoff = Instant::now().checked_sub(COST_RATE_WINDOW);
+        self.recent_costs
+            .iter()
+            .filter(|(at, _)| cutoff.is_none_or(|cutoff| *at >= cutoff))
+            .map(|(_, cost)| cost)
+            .sum()
+    }
+
+    /// Drop recorded costs that have left the one-minute rate window
+    fn prune_recent_costs(&mut self, now: Instant) {
+        let Some(cutoff) = now.checked_sub(COST_RATE_WINDOW) else {
+            return;
+        };
+        while self.recent_costs.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.recent_costs.pop_front();
+        }
+    }
+
     /// Reset all metrics
     pub fn reset(&mut self) {
         self.current_metrics = ProducerMetrics::new();
         self.response_times.clear();
         self.provider_stats.clear();
+        self.recent_latencies.clear();
+        self.recent_uniqueness.clear();
+        self.recent_costs.clear();
         self.start_time = None;
         self.total_cost = 0.0;
 
@@ -363,6 +511,11 @@ mod tests {
             timestamp: Utc::now(),
             success,
             error_message: if success { None } else { Some("Test error".to_string()) },
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
         }
     }
 
@@ -528,6 +681,42 @@ mod tests {
         assert_eq!(anthropic_stats.success_rate, 0.0);
         assert_eq!(anthropic_stats.avg_response_time_ms, 500.0);
         assert_eq!(anthropic_stats.total_tokens_used, 150);
+
+        assert_eq!(metrics.tokens_used(ProviderId::Anthropic), 150);
+        assert_eq!(metrics.tokens_used(ProviderId::Gemini), 0);
+    }
+
+    #[test]
+    fn test_avg_response_time_counts_failures_as_slow() {
+        let mut metrics = Metrics::new();
+        let window = Duration::from_secs(60);
+
+        metrics.record_response_received(&create_test_response(ProviderId::OpenAI, true, 200, 10));
+        metrics.record_response_received(&create_test_response(ProviderId::OpenAI, true, 400, 10));
+        assert_eq!(metrics.avg_response_time(ProviderId::OpenAI, window), Some(300.0));
+
+        // A fast failure is recorded at the failure penalty, not its own response time
+        metrics.record_response_received(&create_test_response(ProviderId::OpenAI, false, 5, 0));
+        let expected = (200 + 400 + FAILED_RESPONSE_LATENCY_MS) as f64 / 3.0;
+        assert_eq!(metrics.avg_response_time(ProviderId::OpenAI, window), Some(expected));
+        assert_eq!(metrics.avg_response_time(ProviderId::Gemini, window), None);
+
+        // Samples older than the window no longer count
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(metrics.avg_response_time(ProviderId::OpenAI, Duration::from_millis(10)), None);
+    }
+
+    #[test]
+    fn test_cost_per_minute_sums_the_last_minute() {
+        let mut metrics = Metrics::new();
+        assert_eq!(metrics.cost_per_minute(), 0.0);
+
+        metrics.record_cost(ProviderId::OpenAI, 0.25);
+        metrics.record_cost(ProviderId::Anthropic, 0.5);
+        assert!((metrics.cost_per_minute() - 0.75).abs() < 1e-9);
+
+        metrics.reset();
+        assert_eq!(metrics.cost_per_minute(), 0.0);
     }
 
     #[test]
@@ -612,4 +801,26 @@ mod tests {
         // Token efficiency: (5 unique * 1000) / 2000 tokens = 2.5
         assert_eq!(current.token_efficiency(), 2.5);
     }
+
+    #[test]
+    fn test_uniqueness_ratio_covers_recent_responses() {
+        use crate::core::processor::ProcessingStats;
+
+        let mut metrics = Metrics::new();
+        assert_eq!(metrics.uniqueness_ratio(5), None);
+
+        let stats = |total_extracted: usize, new: usize| ProcessingStats {
+            total_extracted,
+            duplicate_count: total_extracted - new,
+            rejected_by_length: 0,
+            provider: ProviderId::Random,
+            new_values: (0..new).map(|i| i.to_string()).collect(),
+        };
+        metrics.record_processing_stats(ProviderId::Random, &stats(10, 10));
+        metrics.record_processing_stats(ProviderId::Random, &stats(10, 2));
+        metrics.record_processing_stats(ProviderId::Random, &stats(10, 0));
+
+        assert_eq!(metrics.uniqueness_ratio(2), Some(0.1));
+        assert_eq!(metrics.uniqueness_ratio(100), Some(0.4));
+    }
 }
@@ -1,15 +1,27 @@
 //! Producer core business logic
 
+pub mod autotune;
+pub mod circuit_breaker;
+pub mod cost_throttle;
+pub mod dead_letter;
 pub mod generator;
 pub mod metrics;
 pub mod processor;
 pub mod producer;
 pub mod prompt;
+pub mod rate_limiter;
+pub mod request_size;
 pub mod utils;
 
+pub use autotune::{AimdConfig, AutoTuner};
+pub use circuit_breaker::CircuitState;
+pub use cost_throttle::throttled_interval;
+pub use dead_letter::{DeadLetter, DeadLetterLog};
 pub use generator::CommandGenerator;
 pub use metrics::Metrics;
 pub use processor::Processor;
 pub use producer::Producer;
-pub use prompt::PromptHandler;
+pub use prompt::{FewShotConfig, PromptHandler};
+pub use rate_limiter::RateLimiter;
+pub use request_size::{RequestSizeConfig, RequestSizeTuner};
 pub use utils::{build_api_request, load_routing_strategy, select_provider, should_retry_request};
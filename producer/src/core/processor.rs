@@ -4,7 +4,8 @@ use crate::error::ProducerResult;
 use crate::types::ApiResponse;
 use growable_bloom_filter::GrowableBloom;
 use serde_json;
-use shared::ProviderId;
+use shared::{ExtractionFormat, GenerationConfig, ProviderId};
+use std::collections::HashSet;
 use tracing::{debug, info};
 
 /// Response processor for simple attribute extraction and deduplication
@@ -17,6 +18,12 @@ pub struct Processor {
 
     /// Count of duplicate values encountered locally
     duplicate_count: usize,
+
+    /// Values never reported as new, e.g. few-shot examples shown in the prompt
+    reserved_values: HashSet<String>,
+
+    /// Drop values already seen; off with the orchestrator's --no-dedup
+    dedup_enabled: bool,
 }
 
 impl Processor {
@@ -29,6 +36,8 @@ impl Processor {
             bloom_filter,
             seen_values: Vec::new(),
             duplicate_count: 0,
+            reserved_values: HashSet::new(),
+            dedup_enabled: true,
         }
     }
 
@@ -41,38 +50,58 @@ impl Processor {
             bloom_filter,
             seen_values: Vec::new(),
             duplicate_count: 0,
+            reserved_values: HashSet::new(),
+            dedup_enabled: true,
         }
     }
 
     /// Process API response and extract values, returning statistics
     pub fn process_response(&mut self, response: ApiResponse) -> ProducerResult<ProcessingStats> {
+        self.process_response_with_format(response, None)
+    }
+
+    /// Process API response laid out in the given format (None splits on lines and commas)
+    pub fn process_response_with_format(
+        &mut self,
+        response: ApiResponse,
+        format: Option<ExtractionFormat>,
+    ) -> ProducerResult<ProcessingStats> {
+        self.process_response_within(response, format, LengthBounds::default())
+    }
+
+    /// Process API response using a generation config's extraction format and attribute length bounds
+    pub fn process_response_with_config(
+        &mut self,
+        response: ApiResponse,
+        config: Option<&GenerationConfig>,
+    ) -> ProducerResult<ProcessingStats> {
+        let format = config.and_then(|gc| gc.extraction_format);
+        let bounds = config.map(LengthBounds::from_config).unwrap_or_default();
+        self.process_response_within(response, format, bounds)
+    }
+
+    fn process_response_within(
+        &mut self,
+        response: ApiResponse,
+        format: Option<ExtractionFormat>,
+        bounds: LengthBounds,
+    ) -> ProducerResult<ProcessingStats> {
         if !response.success {
             debug!("Skipping processing for failed response from {:?}", response.provider);
             return Ok(ProcessingStats::empty());
         }
 
-        // Split on newlines and commas, but preserve spaces within attribute names
-        let extracted_items: Vec<String> = response.content
-            .split(|c: char| c == '\n' || c == '\r' || c == ',')
-            .map(|item| {
-                // Clean up each item: trim, lowercase, preserve spaces and letters only (exclude numbers)
-                let cleaned = item.trim()
-                    .to_lowercase()
-                    .chars()
-                    .filter(|c| c.is_alphabetic() || c.is_whitespace()) // Only letters and spaces, no numbers
-                    .collect::<String>();
-                
-                // Normalize multiple spaces to single spaces and trim
-                let normalized = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
-                
-                // Remove any leading/trailing numbers or number patterns that might remain
-                normalized
-                    .split_whitespace()
-                    .filter(|word| !word.chars().all(|c| c.is_numeric())) // Remove pure number words
-                    .filter(|word| !word.starts_with(char::is_numeric)) // Remove words starting with numbers
-                    .collect::<Vec<_>>()
-                    .join(" ")
-            })
+        // A response cut off by max_tokens ends mid-item unless it stopped on a separator
+        let content = if response.is_truncated() {
+            debug!("Dropping partial final item from truncated {:?} response", response.provider);
+            Self::drop_partial_tail(&response.content, format)
+        } else {
+            response.content.as_str()
+        };
+
+        let extracted_items: Vec<String> = Self::split_items(content, format)
+            .iter()
+            .map(|item| Self::normalize_item(item))
             .filter(|item| {
                 !item.is_empty() 
                 && item.len() > 2 
@@ -81,6 +110,11 @@ impl Processor {
             })
             .collect();
 
+        // Configured bounds apply to the normalized item, so stray whitespace and punctuation don't count
+        let before_bounds = extracted_items.len();
+        let extracted_items: Vec<String> = extracted_items.into_iter().filter(|item| bounds.contains(item)).collect();
+        let rejected_by_length = before_bounds - extracted_items.len();
+
         // Process extracted items and check uniqueness (functional approach)
         let (new_values, duplicate_count) = self.filter_new_values(&extracted_items);
 
@@ -92,28 +126,158 @@ impl Processor {
         let stats = ProcessingStats {
             total_extracted: extracted_items.len(),
             duplicate_count,
+            rejected_by_length,
             provider: response.provider,
             new_values,
         };
 
         debug!(
-            "Processed {:?} response: {} total, {} new, {} duplicates",
+            "Processed {:?} response: {} total, {} new, {} duplicates, {} rejected by length",
             response.provider,
             stats.total_extracted,
             stats.new_values.len(),
-            stats.duplicate_count
+            stats.duplicate_count,
+            stats.rejected_by_length
         );
 
         Ok(stats)
     }
 
+    /// Split response content into raw items, preserving spaces within attribute names
+    pub fn split_items(content: &str, format: Option<ExtractionFormat>) -> Vec<String> {
+        match format {
+            None => content.split(['\n', '\r', ',']).map(str::to_string).collect(),
+            Some(ExtractionFormat::Lines) => content.lines().map(str::to_string).collect(),
+            Some(ExtractionFormat::CommaSeparated) => content.split(',').map(str::to_string).collect(),
+            Some(ExtractionFormat::NumberedList) => Self::split_numbered_list(content),
+            Some(ExtractionFormat::JsonArray) => Self::parse_json_array(content),
+        }
+    }
+
+    /// Cut content back to its last separator so a half-written final item is ignored
+    fn drop_partial_tail(content: &str, format: Option<ExtractionFormat>) -> &str {
+        let separators: &[char] = match format {
+            None => &['\n', '\r', ','],
+            Some(ExtractionFormat::Lines | ExtractionFormat::NumberedList) => &['\n', '\r'],
+            Some(ExtractionFormat::CommaSeparated) => &[','],
+            // An unterminated array is repaired at its last complete element instead
+            Some(ExtractionFormat::JsonArray) => return content,
+        };
+        content.rfind(separators).map_or("", |end| &content[..end])
+    }
+
+    /// Lines carrying a list marker, with the marker removed
+    ///
+    /// Falls back to every line when the model ignored the numbering entirely.
+    fn split_numbered_list(content: &str) -> Vec<String> {
+        let marked: Vec<String> = content
+            .lines()
+            .filter_map(Self::strip_list_marker)
+            .map(str::to_string)
+            .collect();
+        if marked.is_empty() {
+            content.lines().map(str::to_string).collect()
+        } else {
+            marked
+        }
+    }
+
+    /// Strip a "1." / "2)" / "3:" number or a "-" / "*" / "•" bullet from the start of a line
+    fn strip_list_marker(line: &str) -> Option<&str> {
+        let line = line.trim_start();
+        if let Some(rest) = line.strip_prefix(['-', '*', '•']) {
+            return Some(rest.trim());
+        }
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return None;
+        }
+        line[digits..].strip_prefix(['.', ')', ':']).map(str::trim)
+    }
+
+    /// String elements of the first JSON array in the content, ignoring surrounding prose or code fences
+    fn parse_json_array(content: &str) -> Vec<String> {
+        let strings = |values: Vec<serde_json::Value>| -> Vec<String> {
+            values
+                .into_iter()
+                .filter_map(|value| match value {
+                    serde_json::Value::String(item) => Some(item),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        for (start, _) in content.match_indices('[') {
+            let mut stream = serde_json::Deserializer::from_str(&content[start..]).into_iter::<Vec<serde_json::Value>>();
+            if let Some(Ok(values)) = stream.next() {
+                return strings(values);
+            }
+        }
+
+        // An unterminated array (e.g. truncated output) keeps the elements before its last comma
+        let repaired = content.find('[').and_then(|start| {
+            let end = start + content[start..].rfind(',')?;
+            serde_json::from_str::<Vec<serde_json::Value>>(&format!("{}]", &content[start..end])).ok()
+        });
+        match repaired {
+            Some(values) => strings(values),
+            None => {
+                debug!("No JSON array found in response");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Normalize a raw item the way extracted attributes are stored
+    pub fn normalize_item(item: &str) -> String {
+        // Clean up each item: trim, lowercase, preserve spaces and letters only (exclude numbers)
+        let cleaned = item.trim()
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphabetic() || c.is_whitespace()) // Only letters and spaces, no numbers
+            .collect::<String>();
+
+        // Normalize multiple spaces to single spaces and trim
+        let normalized = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        // Remove any leading/trailing numbers or number patterns that might remain
+        normalized
+            .split_whitespace()
+            .filter(|word| !word.chars().all(|c| c.is_numeric())) // Remove pure number words
+            .filter(|word| !word.starts_with(char::is_numeric)) // Remove words starting with numbers
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Treat values as already seen without counting them as generated
+    ///
+    /// Reserved values survive bloom filter syncs and resets.
+    pub fn reserve_values(&mut self, values: &[String]) {
+        self.reserved_values
+            .extend(values.iter().map(|value| Self::normalize_item(value)).filter(|value| !value.is_empty()));
+    }
+
+    /// Keep or drop values that were already seen
+    pub fn set_dedup_enabled(&mut self, enabled: bool) {
+        self.dedup_enabled = enabled;
+    }
+
+    /// Whether values that were already seen are dropped
+    pub fn dedup_enabled(&self) -> bool {
+        self.dedup_enabled
+    }
+
     /// Filter extracted values into new vs duplicate (functional approach)
     fn filter_new_values(&mut self, values: &[String]) -> (Vec<String>, usize) {
+        if !self.dedup_enabled {
+            return (values.to_vec(), 0);
+        }
+
         let mut new_values = Vec::new();
         let mut duplicate_count = 0;
 
         for value in values {
-            if self.bloom_filter.contains(value) {
+            if self.reserved_values.contains(value) || self.bloom_filter.contains(value) {
                 // Bloom filter says it might be a duplicate
                 duplicate_count += 1;
             } else {
@@ -134,6 +298,11 @@ impl Processor {
         self.seen_values.push(value.to_string());
     }
 
+    /// Up to `count` of the most recently seen values, newest first
+    pub fn recent_values(&self, count: usize) -> Vec<String> {
+        self.seen_values.iter().rev().take(count).cloned().collect()
+    }
+
     /// Get processor statistics
     pub fn get_stats(&self) -> ProcessorStats {
         ProcessorStats {
@@ -210,11 +379,36 @@ pub struct ProcessorStats {
     pub bloom_filter_false_positive_rate: f64,
 }
 
+/// Inclusive bounds on attribute length in characters; an unset end is unbounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LengthBounds {
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+}
+
+impl LengthBounds {
+    /// Bounds configured by the orchestrator for generated attributes
+    pub fn from_config(config: &GenerationConfig) -> Self {
+        Self {
+            min: config.min_attribute_length,
+            max: config.max_attribute_length,
+        }
+    }
+
+    /// Whether an item's length lies within the bounds
+    pub fn contains(&self, item: &str) -> bool {
+        let length = item.chars().count();
+        self.min.is_none_or(|min| length >= min) && self.max.is_none_or(|max| length <= max)
+    }
+}
+
 /// Statistics from processing a single API response
 #[derive(Debug, Clone)]
 pub struct ProcessingStats {
     pub total_extracted: usize,
     pub duplicate_count: usize,
+    /// Items dropped for falling outside the configured attribute length bounds
+    pub rejected_by_length: usize,
     pub provider: ProviderId,
     pub new_values: Vec<String>,
 }
@@ -224,6 +418,7 @@ impl ProcessingStats {
         Self {
             total_extracted: 0,
             duplicate_count: 0,
+            rejected_by_length: 0,
             provider: ProviderId::Random, // Default provider
             new_values: Vec::new(),
         }
@@ -261,6 +456,11 @@ mod tests {
             timestamp: Utc::now(),
             success: true,
             error_message: None,
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
         }
     }
 
@@ -281,6 +481,117 @@ mod tests {
         assert!(stats.new_values.contains(&"green".to_string()));
     }
 
+    #[test]
+    fn test_length_bounds_keep_only_in_range_items() {
+        let mut processor = Processor::new();
+        let config = GenerationConfig {
+            model: "random".to_string(),
+            batch_size: 1,
+            context_window: 4096,
+            max_tokens: 100,
+            temperature: 0.7,
+            request_size: 10,
+            extraction_format: Some(ExtractionFormat::Lines),
+            system_prompt: None,
+            min_attribute_length: Some(4),
+            max_attribute_length: Some(12),
+            include_seen_hint: false,
+            seen_hint_count: 0,
+        };
+        // Bounds are measured after trimming and normalization
+        let content = "fig
+  pear  
+kiwi!
+blood orange
+passion fruit
+sugar apple tree".to_string();
+
+        let response = create_test_response(ProviderId::OpenAI, content);
+        let stats = processor.process_response_with_config(response, Some(&config)).unwrap();
+        assert_eq!(stats.new_values, vec!["pear", "kiwi", "blood orange"]);
+        assert_eq!(stats.total_extracted, 3);
+        assert_eq!(stats.rejected_by_length, 3);
+    }
+
+    #[test]
+    fn test_truncated_response_drops_partial_last_item() {
+        let mut processor = Processor::new();
+        let content = "apple\nbanana\ncherry\nstrawberry shortc".to_string();
+
+        let mut response = create_test_response(ProviderId::OpenAI, content.clone());
+        response.finish_reason = Some(crate::types::FINISH_REASON_LENGTH.to_string());
+        let stats = processor.process_response(response).unwrap();
+        assert_eq!(stats.total_extracted, 3);
+        assert!(!stats.new_values.contains(&"strawberry shortc".to_string()));
+
+        // The same content with a normal stop keeps every item
+        let mut response = create_test_response(ProviderId::OpenAI, content);
+        response.finish_reason = Some("stop".to_string());
+        let stats = Processor::new().process_response(response).unwrap();
+        assert_eq!(stats.total_extracted, 4);
+    }
+
+    #[test]
+    fn test_lines_format_keeps_commas_inside_entries() {
+        let content = "salt, pepper\r\nbasil\n\nthyme";
+        let items = Processor::split_items(content, Some(ExtractionFormat::Lines));
+        assert_eq!(items, vec!["salt, pepper", "basil", "", "thyme"]);
+
+        // Default parsing also splits on the comma
+        assert_eq!(Processor::split_items(content, None).len(), 6);
+    }
+
+    #[test]
+    fn test_numbered_list_format_skips_unnumbered_lines() {
+        let content = "Here are some animals:\n1. otter\n2) red panda\n  3: lynx\n- heron\n* quokka\n• ibis\nHope this helps!";
+        let items = Processor::split_items(content, Some(ExtractionFormat::NumberedList));
+        assert_eq!(items, vec!["otter", "red panda", "lynx", "heron", "quokka", "ibis"]);
+
+        // A model that ignored the numbering still yields its lines
+        let items = Processor::split_items("otter\nlynx", Some(ExtractionFormat::NumberedList));
+        assert_eq!(items, vec!["otter", "lynx"]);
+    }
+
+    #[test]
+    fn test_json_array_format_tolerates_noise() {
+        let fenced = "Sure! [see below]\n```json\n[\"otter\", \"red panda\", 3, \"lynx\"]\n```\nEnjoy.";
+        let items = Processor::split_items(fenced, Some(ExtractionFormat::JsonArray));
+        assert_eq!(items, vec!["otter", "red panda", "lynx"]);
+
+        // An unterminated array keeps its complete elements
+        let unterminated = "[\"otter\", \"lynx\", \"hero";
+        let items = Processor::split_items(unterminated, Some(ExtractionFormat::JsonArray));
+        assert_eq!(items, vec!["otter", "lynx"]);
+
+        assert!(Processor::split_items("no array here", Some(ExtractionFormat::JsonArray)).is_empty());
+        assert!(Processor::split_items("{\"items\": 3", Some(ExtractionFormat::JsonArray)).is_empty());
+    }
+
+    #[test]
+    fn test_comma_separated_format() {
+        let mut processor = Processor::new();
+        let response = create_test_response(ProviderId::OpenAI, "otter, red panda,,lynx\n".to_string());
+        let stats = processor
+            .process_response_with_format(response, Some(ExtractionFormat::CommaSeparated))
+            .unwrap();
+        assert_eq!(stats.new_values, vec!["otter", "red panda", "lynx"]);
+
+        // Newlines are not separators, so a line break joins two words into one entry
+        let items = Processor::split_items("otter\nlynx, heron", Some(ExtractionFormat::CommaSeparated));
+        assert_eq!(items.len(), 2);
+    }
+
+    #[test]
+    fn test_truncated_json_array_drops_partial_item() {
+        let mut processor = Processor::new();
+        let mut response = create_test_response(ProviderId::OpenAI, "[\"otter\", \"lynx\", \"red pa".to_string());
+        response.finish_reason = Some(crate::types::FINISH_REASON_LENGTH.to_string());
+        let stats = processor
+            .process_response_with_format(response, Some(ExtractionFormat::JsonArray))
+            .unwrap();
+        assert_eq!(stats.new_values, vec!["otter", "lynx"]);
+    }
+
     #[test]
     fn test_newline_separated_list() {
         let mut processor = Processor::new();
@@ -326,6 +637,18 @@ mod tests {
         assert_eq!(stats2.duplicate_count, 2);
     }
 
+    #[test]
+    fn test_repeats_are_kept_when_dedup_is_disabled() {
+        let mut processor = Processor::new();
+        processor.set_dedup_enabled(false);
+        processor.update_bloom_filter(None, vec!["unique".to_string()]);
+
+        let response = create_test_response(ProviderId::OpenAI, "unique, word, word".to_string());
+        let stats = processor.process_response(response).unwrap();
+        assert_eq!(stats.new_values, vec!["unique", "word", "word"]);
+        assert_eq!(stats.duplicate_count, 0);
+    }
+
     #[test]
     fn test_bloom_filter_update() {
         let mut processor = Processor::new();
@@ -7,23 +7,36 @@
 //! - Unified event loop for both modes
 //! - Composition over inheritance
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, RwLock, Semaphore};
 use tokio::time::interval;
 use uuid::Uuid;
 
 use crate::core::generator::CommandGenerator;
-use crate::core::utils::{build_api_request_with_config, should_retry_request};
-use crate::core::{Metrics, Processor, PromptHandler};
+use crate::core::utils::{
+    build_api_request_with_config, build_verification_prompt, count_verification_conflicts, jittered_backoff,
+    needs_model_fallback, reword_filtered_request, sample_for_verification, should_retry_request,
+};
+use crate::core::autotune::{AimdConfig, AutoTuner};
+use crate::core::circuit_breaker::BreakerTransition;
+use crate::core::cost_throttle::throttled_interval;
+use crate::core::utils::{
+    circuit_settings, exhausted_providers, route_around_providers, select_lowest_latency_config,
+    select_provider_config_with_circuits, strategy_providers,
+};
+use crate::core::{DeadLetter, DeadLetterLog, FewShotConfig, Metrics, Processor, PromptHandler, RateLimiter, RequestSizeConfig, RequestSizeTuner};
 use crate::error::{ProducerError, ProducerResult};
 use crate::traits::{ApiClient, Communicator};
 use crate::types::{ApiRequest, ApiResponse, CommandSource, ExecutionConfig, ExecutionMode, ProducerState};
 use shared::messages::producer::{ProducerPerformanceStats, ProducerSyncStatus};
-use shared::types::{GenerationConfig, ProcessStatus, RoutingStrategy};
+use shared::messages::sync::{decompress_sync_payload, SyncPayload};
+use shared::types::{GenerationConfig, ProcessStatus, ProviderConfig, RoutingStrategy, VerificationStats};
 use shared::{process_debug, process_error, process_info, process_warn};
-use shared::{ProcessId, ProducerCommand, ProducerUpdate, ProviderId};
+use shared::{ApiFailure, ProcessId, ProducerCommand, ProducerUpdate, ProviderId, SharedError};
 
 // ============================================================================
 // Producer Implementation
@@ -46,12 +59,15 @@ where
     processor: Arc<RwLock<Processor>>,
     metrics: Arc<RwLock<Metrics>>,
     prompt_handler: Arc<PromptHandler>,
+    auto_tuner: Option<Arc<RwLock<AutoTuner>>>,
 
     // Runtime state
     state: Arc<RwLock<ProducerState>>,
 
     // Control channels
     shutdown_tx: mpsc::Sender<()>,
+    /// Tells the request processor to stop starting new requests
+    drain_tx: watch::Sender<bool>,
     shutdown_rx: Option<mpsc::Receiver<()>>,
 }
 
@@ -63,6 +79,15 @@ where
     /// Create producer from unified configuration
     pub fn new(config: ExecutionConfig, api_client: A, communicator: C) -> Self {
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let mut state = ProducerState::new(config.producer_config.clone());
+        state.rate_limiter = RateLimiter::new(&config.rate_limits, Instant::now());
+        if config.auto_tune_request_size {
+            state.request_size_tuner = Some(RequestSizeTuner::new(RequestSizeConfig {
+                min_size: config.request_size_min,
+                max_size: config.request_size_max,
+                ..RequestSizeConfig::default()
+            }));
+        }
 
         Producer {
             api_client: Arc::new(api_client),
@@ -70,13 +95,30 @@ where
             processor: Arc::new(RwLock::new(Processor::new())),
             metrics: Arc::new(RwLock::new(Metrics::new())),
             prompt_handler: Arc::new(PromptHandler::new()),
-            state: Arc::new(RwLock::new(ProducerState::new(config.producer_config.clone()))),
+            auto_tuner: None,
+            state: Arc::new(RwLock::new(state)),
             config,
             shutdown_tx,
             shutdown_rx: Some(shutdown_rx),
+            drain_tx: watch::channel(false).0,
         }
     }
 
+    /// Inject few-shot examples into prompts and treat them as already seen
+    pub fn with_few_shot(mut self, few_shot: FewShotConfig) -> Self {
+        let mut processor = Processor::new();
+        processor.reserve_values(&few_shot.all_examples());
+        self.processor = Arc::new(RwLock::new(processor));
+        self.prompt_handler = Arc::new(PromptHandler::new().with_few_shot(few_shot));
+        self
+    }
+
+    /// Auto-tune per-provider concurrency with an AIMD controller
+    pub fn with_auto_tune(mut self, config: AimdConfig) -> Self {
+        self.auto_tuner = Some(Arc::new(RwLock::new(AutoTuner::new(config))));
+        self
+    }
+
     /// Main unified run loop - same for both test and production modes
     pub async fn run(&mut self) -> ProducerResult<()> {
         // Test tracing immediately when producer starts running
@@ -106,8 +148,7 @@ where
 
         // Cleanup
         status_reporter.abort();
-        request_processor.abort();
-        self.cleanup().await?;
+        self.cleanup(request_processor).await?;
 
         result
     }
@@ -133,6 +174,7 @@ where
                 let ready_msg = shared::ProducerUpdate::Ready {
                     producer_id: ProcessId::current().clone(),
                     listen_port,
+                    protocol_version: shared::PROTOCOL_VERSION,
                 };
 
                 process_info!(ProcessId::current(), "📤 Sending ready signal to orchestrator");
@@ -182,6 +224,7 @@ where
                 // Check for commands (unified handling)
                 _ = command_interval.tick() => {
                     if let Some(command) = self.get_next_command(&mut command_source).await? {
+                        let is_stop = matches!(command, ProducerCommand::Stop { .. });
                         if let Err(e) = self.handle_command(command).await {
                             process_error!(ProcessId::current(),"❌ Error handling command: {}", e);
 
@@ -192,6 +235,12 @@ where
                                 break;
                             }
                         }
+
+                        // Standalone runs end once the simulator has issued its final Stop
+                        if is_stop && matches!(command_source, CommandSource::Simulator(_)) {
+                            process_info!(ProcessId::current(), "🏁 Standalone run complete");
+                            break;
+                        }
                     }
                 }
             }
@@ -230,9 +279,14 @@ where
 
         match command {
             ProducerCommand::Start {
+                topic,
                 prompt,
                 routing_strategy,
                 generation_config,
+                seed,
+                max_cost_per_minute,
+                dedup,
+                category,
                 ..
             } => {
                 process_debug!(ProcessId::current(), "🎯 Producer received Start command with prompt: '{}'", prompt);
@@ -240,11 +294,33 @@ where
 
                 let mut state = self.state.write().await;
                 if !state.is_running {
+                    // Each producer gets its own reproducible streams derived from the run seed
+                    let producer_seed = shared::derive_seed(seed, Self::seed_stream());
+                    state.rng = StdRng::seed_from_u64(shared::derive_seed(producer_seed, 0));
+                    // --random-seed pins the Random provider's output across runs, still distinct per producer
+                    let client_seed = match self.config.random_seed {
+                        Some(random_seed) => shared::derive_seed(random_seed, Self::seed_stream()),
+                        None => shared::derive_seed(producer_seed, 1),
+                    };
+                    self.api_client.reseed(client_seed);
+                    process_debug!(ProcessId::current(), "🎲 Producer seeded from run seed {}", seed);
+
+                    // Remembered so dead letters, and attributes reported back, name the topic they came from
+                    state.config.topic = topic;
                     state.current_prompt = Some(prompt.clone());
                     state.routing_strategy = Some(routing_strategy);
                     state.generation_config = Some(generation_config);
+                    state.cost_budget_per_minute = max_cost_per_minute;
+                    if let Some(tuner) = state.request_size_tuner.as_mut() {
+                        tuner.reset();
+                    }
                     state.start();
+                    drop(state);
+                    self.processor.write().await.set_dedup_enabled(dedup);
                     process_info!(ProcessId::current(), "✅ Producer started with prompt: {}", prompt);
+                    if let Some(category) = category {
+                        process_info!(ProcessId::current(), "🗂️ Producer assigned to prompt partition '{}'", category);
+                    }
                 }
             }
 
@@ -256,10 +332,30 @@ where
                 }
             }
 
+            ProducerCommand::Pause { .. } => {
+                let mut state = self.state.write().await;
+                if state.is_running {
+                    state.pause();
+                    process_info!(ProcessId::current(), "⏸️ Producer paused");
+                }
+            }
+
+            ProducerCommand::Resume { .. } => {
+                let mut state = self.state.write().await;
+                if !state.is_running {
+                    if state.resume() {
+                        process_info!(ProcessId::current(), "▶️ Producer resumed");
+                    } else {
+                        process_warn!(ProcessId::current(), "⚠️ Resume ignored: producer has not been started");
+                    }
+                }
+            }
+
             ProducerCommand::UpdateConfig {
                 prompt,
                 routing_strategy,
                 generation_config,
+                max_cost_per_minute,
                 ..
             } => {
                 let mut state = self.state.write().await;
@@ -275,6 +371,22 @@ where
                 if let Some(new_config) = generation_config {
                     process_debug!(ProcessId::current(), "🔄 Producer updating generation config");
                     state.generation_config = Some(new_config);
+                    if let Some(tuner) = state.request_size_tuner.as_mut() {
+                        tuner.reset();
+                    }
+                }
+                if let Some(budget) = max_cost_per_minute {
+                    if budget > 0.0 {
+                        process_debug!(
+                            ProcessId::current(),
+                            "🔄 Producer updating cost budget to ${:.4}/min",
+                            budget
+                        );
+                        state.cost_budget_per_minute = Some(budget);
+                    } else {
+                        process_debug!(ProcessId::current(), "🔄 Producer clearing its cost budget");
+                        state.cost_budget_per_minute = None;
+                    }
                 }
                 process_info!(ProcessId::current(), "🔄 Updated producer configuration");
             }
@@ -284,8 +396,15 @@ where
                 bloom_version,
                 bloom_filter,
                 seen_values,
+                compressed,
+                compressed_seen_values,
                 ..
             } => {
+                let (bloom_filter, seen_values) = if compressed {
+                    Self::decompress_sync(bloom_filter, compressed_seen_values)?
+                } else {
+                    (bloom_filter, seen_values)
+                };
                 self.handle_sync_check(sync_id, bloom_version, bloom_filter, seen_values)
                     .await?;
             }
@@ -298,6 +417,33 @@ where
         Ok(())
     }
 
+    /// Stream index used to derive this producer's seed from the run seed
+    fn seed_stream() -> u64 {
+        match ProcessId::current() {
+            ProcessId::Producer(n) => *n as u64,
+            _ => 0,
+        }
+    }
+
+    /// Restore a gzip-compressed SyncCheck payload
+    fn decompress_sync(
+        bloom_filter: Option<Vec<u8>>,
+        compressed_seen_values: Option<Vec<u8>>,
+    ) -> ProducerResult<SyncPayload> {
+        let compressed_bytes =
+            bloom_filter.as_ref().map_or(0, Vec::len) + compressed_seen_values.as_ref().map_or(0, Vec::len);
+        let (bloom_filter, seen_values) =
+            decompress_sync_payload(bloom_filter.as_deref(), compressed_seen_values.as_deref())
+                .map_err(|e| ProducerError::serialization(format!("Failed to decompress sync payload: {e}")))?;
+        process_debug!(
+            ProcessId::current(),
+            "🗜️ Decompressed sync payload: {} bytes -> {} seen values",
+            compressed_bytes,
+            seen_values.as_ref().map_or(0, Vec::len)
+        );
+        Ok((bloom_filter, seen_values))
+    }
+
     /// Handle sync check command
     async fn handle_sync_check(
         &self,
@@ -380,21 +526,39 @@ where
         let prompt_handler = self.prompt_handler.clone();
         let communicator = self.communicator.clone();
         let config = self.config.clone();
+        let auto_tuner = self.auto_tuner.clone();
+        let mut drain_rx = self.drain_tx.subscribe();
 
         let handle = tokio::spawn(async move {
             let mut request_interval = interval(config.request_interval);
-
-            loop {
-                request_interval.tick().await;
+            // Every in-flight request holds a permit, so at most max_concurrent_requests run at once
+            let max_concurrent = config.producer_config.max_concurrent_requests.max(1);
+            let permits = Arc::new(Semaphore::new(max_concurrent));
+            let mut requests = tokio::task::JoinSet::new();
+
+            'requests: loop {
+                // Draining stops new requests; requests already in flight finish below first
+                tokio::select! {
+                    biased;
+                    _ = drain_rx.changed() => break 'requests,
+                    Some(result) = requests.join_next(), if !requests.is_empty() => {
+                        if Self::handle_request_result(result, &communicator, &config).await {
+                            return;
+                        }
+                        continue;
+                    }
+                    _ = request_interval.tick() => {}
+                }
 
                 // Get current state
-                let (is_running, prompt, routing_strategy, generation_config) = {
+                let (is_running, prompt, routing_strategy, generation_config, cost_budget) = {
                     let state = state.read().await;
                     (
                         state.is_running,
                         state.current_prompt.clone(),
                         state.routing_strategy.clone(),
                         state.generation_config.clone(),
+                        state.cost_budget_per_minute,
                     )
                 };
 
@@ -408,33 +572,76 @@ where
 
                 let base_prompt = prompt.unwrap();
 
-                // Process request using pure functions
-                if let Err(e) = Self::process_single_request(
-                    &api_client,
-                    &processor,
-                    &metrics,
-                    &prompt_handler,
-                    &communicator,
-                    &state,
-                    &routing_strategy,
-                    &generation_config,
-                    &base_prompt,
-                    &config,
-                )
-                .await
-                {
-                    process_error!(ProcessId::current(), "❌ Request processing failed: {}", e);
+                // Spending close to this producer's budget share pushes the next tick further out
+                let cost_per_minute = metrics.read().await.cost_per_minute();
+                let next_interval = throttled_interval(config.request_interval, cost_per_minute, cost_budget);
+                if next_interval > config.request_interval {
+                    process_debug!(
+                        ProcessId::current(),
+                        "🐢 Spending ${:.4}/min against a ${:.4}/min budget, next request in {:?}",
+                        cost_per_minute,
+                        cost_budget.unwrap_or_default(),
+                        next_interval
+                    );
+                    request_interval.reset_after(next_interval);
+                }
 
-                    // In orchestrator mode, if we can't communicate with orchestrator after retries, terminate
-                    if matches!(config.mode, ExecutionMode::Production { .. })
-                        && e.to_string().contains("Failed to send update after")
-                    {
-                        process_error!(
+                // Auto-tuning decides how many requests stay in flight; otherwise one starts per tick
+                let launches = match auto_tuner {
+                    Some(ref tuner) => tuner
+                        .write()
+                        .await
+                        .concurrency_for(&strategy_providers(&routing_strategy))
+                        .saturating_sub(requests.len()),
+                    None => 1,
+                };
+
+                // Process requests using pure functions
+                for _ in 0..launches {
+                    let Ok(permit) = permits.clone().try_acquire_owned() else {
+                        process_debug!(
                             ProcessId::current(),
-                            "💀 Producer terminating: Cannot communicate with orchestrator after retries"
+                            "⏳ {} requests already in flight, waiting for one to finish",
+                            max_concurrent
                         );
                         break;
-                    }
+                    };
+                    let launched_at = requests.len() + 1;
+                    let api_client = api_client.clone();
+                    let processor = processor.clone();
+                    let metrics = metrics.clone();
+                    let prompt_handler = prompt_handler.clone();
+                    let communicator = communicator.clone();
+                    let state = state.clone();
+                    let auto_tuner = auto_tuner.clone();
+                    let routing_strategy = routing_strategy.clone();
+                    let generation_config = generation_config.clone();
+                    let base_prompt = base_prompt.clone();
+                    let config = config.clone();
+                    requests.spawn(async move {
+                        let _permit = permit;
+                        Self::process_single_request(
+                            &api_client,
+                            &processor,
+                            &metrics,
+                            &prompt_handler,
+                            &communicator,
+                            &state,
+                            &auto_tuner,
+                            launched_at,
+                            &routing_strategy,
+                            &generation_config,
+                            &base_prompt,
+                            &config,
+                        )
+                        .await
+                    });
+                }
+            }
+
+            while let Some(result) = requests.join_next().await {
+                if Self::handle_request_result(result, &communicator, &config).await {
+                    return;
                 }
             }
         });
@@ -442,7 +649,43 @@ where
         Ok(handle)
     }
 
+    /// Log a finished request and report its error; returns true when the producer must stop
+    async fn handle_request_result(
+        result: Result<ProducerResult<()>, tokio::task::JoinError>,
+        communicator: &Arc<RwLock<C>>,
+        config: &ExecutionConfig,
+    ) -> bool {
+        let e = match result {
+            Ok(Ok(())) => return false,
+            Ok(Err(e)) => e,
+            Err(join_error) => {
+                process_error!(ProcessId::current(), "❌ Request task failed: {}", join_error);
+                return false;
+            }
+        };
+        process_error!(ProcessId::current(), "❌ Request processing failed: {}", e);
+
+        // In orchestrator mode, if we can't communicate with orchestrator after retries, terminate
+        if matches!(config.mode, ExecutionMode::Production { .. })
+            && e.to_string().contains(crate::services::communicator::ORCHESTRATOR_UNREACHABLE)
+        {
+            process_error!(
+                ProcessId::current(),
+                "💀 Producer terminating: Cannot communicate with orchestrator after retries"
+            );
+            return true;
+        }
+
+        if matches!(config.mode, ExecutionMode::Production { .. }) {
+            if let Err(report_error) = Self::report_error(communicator, SharedError::from(&e)).await {
+                process_debug!(ProcessId::current(), "Failed to report request error: {}", report_error);
+            }
+        }
+        false
+    }
+
     /// Process a single request (pure function composition)
+    #[allow(clippy::too_many_arguments)]
     async fn process_single_request(
         api_client: &Arc<A>,
         processor: &Arc<RwLock<Processor>>,
@@ -450,18 +693,131 @@ where
         prompt_handler: &Arc<PromptHandler>,
         communicator: &Arc<RwLock<C>>,
         state: &Arc<RwLock<ProducerState>>,
+        auto_tuner: &Option<Arc<RwLock<AutoTuner>>>,
+        launched_at: usize,
         routing_strategy: &Option<RoutingStrategy>,
         generation_config: &Option<GenerationConfig>,
         base_prompt: &str,
         config: &ExecutionConfig,
     ) -> ProducerResult<()> {
+        // Route around providers whose token budget is spent
+        let exhausted = {
+            let metrics_guard = metrics.read().await;
+            exhausted_providers(&config.token_budgets, |provider| metrics_guard.tokens_used(provider))
+        };
+        let routed_strategy;
+        let routing_strategy = if exhausted.is_empty() {
+            routing_strategy
+        } else {
+            match route_around_providers(routing_strategy, &exhausted) {
+                Some(strategy) => {
+                    routed_strategy = Some(strategy);
+                    &routed_strategy
+                }
+                None => return Self::stop_on_exhausted_budgets(communicator, state, config, &exhausted).await,
+            }
+        };
+
         // Build enhanced prompt with provider config
         let fallback_config = shared::types::ProviderConfig::with_default_model(ProviderId::Random);
-        let provider_config = crate::core::utils::select_provider_config(routing_strategy, fallback_config);
+        let latencies: HashMap<ProviderId, f64> = match routing_strategy {
+            Some(RoutingStrategy::LowestLatency { providers, window_seconds }) => {
+                let metrics_guard = metrics.read().await;
+                let window = Duration::from_secs(*window_seconds);
+                providers
+                    .iter()
+                    .filter_map(|config| {
+                        metrics_guard
+                            .avg_response_time(config.provider, window)
+                            .map(|ms| (config.provider, ms))
+                    })
+                    .collect()
+            }
+            _ => HashMap::new(),
+        };
+        let provider_config = {
+            let mut state_guard = state.write().await;
+            let state_guard = &mut *state_guard;
+            let now = Instant::now();
+
+            // Fall back to the next eligible provider while the routed one is over its local rate limit
+            let limited = state_guard.rate_limiter.limited_providers(now);
+            let unlimited_strategy;
+            let routing_strategy = if limited.is_empty() {
+                routing_strategy
+            } else {
+                match route_around_providers(routing_strategy, &limited) {
+                    Some(strategy) => {
+                        unlimited_strategy = Some(strategy);
+                        &unlimited_strategy
+                    }
+                    None => {
+                        process_debug!(
+                            ProcessId::current(),
+                            limit_source = "local",
+                            providers = ?limited,
+                            "⏳ Request deferred: all routed providers are at their local rate limit"
+                        );
+                        return Ok(());
+                    }
+                }
+            };
+
+            let selected = match routing_strategy {
+                Some(RoutingStrategy::LowestLatency { providers, .. }) => Some(select_lowest_latency_config(
+                    providers,
+                    &latencies,
+                    fallback_config,
+                    &mut state_guard.routing_cursor,
+                )),
+                _ => select_provider_config_with_circuits(
+                    routing_strategy,
+                    fallback_config,
+                    &mut state_guard.rng,
+                    &mut state_guard.routing_cursor,
+                    &mut state_guard.circuits,
+                    now,
+                ),
+            };
+            if let Some(ref config) = selected {
+                state_guard.rate_limiter.try_acquire(config.provider, now);
+                if !limited.is_empty() {
+                    process_debug!(
+                        ProcessId::current(),
+                        limit_source = "local",
+                        providers = ?limited,
+                        "⏳ Rate-limited providers skipped, routing to {}",
+                        config.provider
+                    );
+                }
+            }
+            selected
+        };
+        let Some(provider_config) = provider_config else {
+            process_debug!(ProcessId::current(), "🔌 All provider circuits open, skipping request");
+            return Ok(());
+        };
+
+        // Ask for the auto-tuned number of items instead of the configured request size
+        let configured_request_size = generation_config.as_ref().map(|gc| gc.request_size);
+        let tuned_config;
+        let generation_config = match (configured_request_size, &state.read().await.request_size_tuner) {
+            (Some(configured), Some(tuner)) => {
+                tuned_config = generation_config.clone().map(|gc| GenerationConfig {
+                    request_size: tuner.request_size(configured),
+                    ..gc
+                });
+                &tuned_config
+            }
+            _ => generation_config,
+        };
         let enhanced_prompt = prompt_handler
             .build_enhanced_prompt(base_prompt, provider_config.provider, generation_config.as_ref(), state, processor)
             .await;
 
+        // Attributes are reported under the topic they were requested for
+        let topic = state.read().await.config.topic.clone();
+
         // Create request with provider config
         let request = build_api_request_with_config(&provider_config, generation_config, enhanced_prompt, Uuid::new_v4());
 
@@ -472,7 +828,77 @@ where
         }
 
         // Make API call with retries
-        let response = Self::make_request_with_retries(api_client, request, config.max_retries).await?;
+        let result =
+            Self::make_request_with_retries(api_client, request.clone(), &provider_config.fallback_models, config).await;
+
+        // A response blocked by a content filter is counted, then optionally retried once with a reworded prompt
+        let result = match result {
+            Ok(ref response)
+                if config.reword_filtered_prompts && response.failure == Some(ApiFailure::ContentFiltered) =>
+            {
+                process_warn!(
+                    ProcessId::current(),
+                    provider = %provider_config.provider,
+                    "🛡️ {} content filter blocked the response, retrying with a reworded prompt",
+                    provider_config.provider
+                );
+                {
+                    let mut metrics_guard = metrics.write().await;
+                    metrics_guard.record_response_received(response);
+                    metrics_guard.record_request_sent(provider_config.provider);
+                }
+                let reworded = reword_filtered_request(&request);
+                Self::make_request_with_retries(api_client, reworded, &provider_config.fallback_models, config).await
+            }
+            result => result,
+        };
+
+        // Feed the outcome to the auto-tuner before surfacing any error
+        if let Some(tuner) = auto_tuner {
+            let (success, latency_ms) = match result {
+                Ok(ref response) => (response.success, response.response_time_ms),
+                Err(_) => (false, 0),
+            };
+            tuner
+                .write()
+                .await
+                .record(provider_config.provider, launched_at, success, latency_ms);
+        }
+
+        // Consecutive failures open the provider's circuit under the CircuitBreaker strategy
+        if let Some((failure_threshold, cooldown)) = circuit_settings(routing_strategy) {
+            let success = matches!(result, Ok(ref response) if response.success);
+            let transition = state.write().await.circuits.record(
+                provider_config.provider,
+                success,
+                failure_threshold,
+                cooldown,
+                Instant::now(),
+            );
+            match transition {
+                Some(BreakerTransition::Opened) => process_warn!(
+                    ProcessId::current(),
+                    "🔌 Circuit breaker opened for provider {} (cooldown {}s)",
+                    provider_config.provider,
+                    cooldown.as_secs()
+                ),
+                Some(BreakerTransition::Closed) => process_info!(
+                    ProcessId::current(),
+                    "🔌 Circuit breaker closed for provider {}",
+                    provider_config.provider
+                ),
+                None => {}
+            }
+        }
+        let response = result?;
+
+        // Price the request once; the same figure is recorded locally and sent to the orchestrator.
+        // Cache hits never reached the provider, so they cost nothing.
+        let cost = if response.success && !response.cached {
+            api_client.estimate_cost(provider_config.provider, &response.tokens_used)
+        } else {
+            0.0
+        };
 
         // Record response
         {
@@ -480,24 +906,63 @@ where
             metrics_guard.record_response_received(&response);
 
             if response.success {
-                let cost = api_client.estimate_cost(provider_config.provider, &response.tokens_used);
                 metrics_guard.record_cost(provider_config.provider, cost);
             }
         }
 
+        // Report why the request failed so the orchestrator can tell transient from fatal errors
+        if !response.success && matches!(config.mode, ExecutionMode::Production { .. }) {
+            let error = SharedError::from(response.failure.unwrap_or(ApiFailure::InternalError));
+            let error = match response.error_message {
+                Some(ref message) => error.with_message(message.clone()),
+                None => error,
+            };
+            Self::report_error(communicator, error).await?;
+        }
+
+        if response.failure == Some(ApiFailure::ContentFiltered) {
+            process_warn!(
+                ProcessId::current(),
+                provider = %provider_config.provider,
+                "🛡️ {} content filter blocked the response: {}",
+                provider_config.provider,
+                response.error_message.as_deref().unwrap_or("no reason given")
+            );
+        }
+
+        if response.is_truncated() {
+            process_warn!(
+                ProcessId::current(),
+                provider = %provider_config.provider,
+                "✂️ Response from {} truncated at max_tokens, dropping its partial last item",
+                provider_config.provider
+            );
+        }
+
         // Process response if successful
         if response.success {
             let mut processor_guard = processor.write().await;
-            let processing_stats = processor_guard.process_response(response.clone())?;
+            let processing_stats =
+                processor_guard.process_response_with_config(response.clone(), generation_config.as_ref())?;
+            process_info!(
+                ProcessId::current(),
+                provider = %provider_config.provider,
+                new_values = processing_stats.new_values.len(),
+                "📦 Batch from {}: {} new values",
+                provider_config.provider,
+                processing_stats.new_values.len()
+            );
 
             if processing_stats.has_new_values() {
                 // Send attributes to orchestrator if connected, otherwise just log
                 if matches!(config.mode, ExecutionMode::Production { .. }) {
                     Self::send_attributes_to_orchestrator(
                         communicator,
+                        &topic,
                         &processing_stats.new_values,
                         provider_config.provider,
                         &response,
+                        cost,
                     )
                     .await?;
                 } else {
@@ -510,32 +975,251 @@ where
                     );
                 }
 
-                // Record stats
+            }
+            drop(processor_guard);
+
+            if processing_stats.is_empty() {
+                Self::record_dead_letter(state, config, &provider_config, &response).await;
+            }
+
+            // Responses with only duplicates still count towards the uniqueness ratio
+            let uniqueness_ratio = {
                 let mut metrics_guard = metrics.write().await;
-                metrics_guard.record_processing_stats(&processing_stats);
+                metrics_guard.record_processing_stats(provider_config.provider, &processing_stats);
+                let state_guard = state.read().await;
+                state_guard
+                    .request_size_tuner
+                    .as_ref()
+                    .map(|tuner| metrics_guard.uniqueness_ratio(tuner.window()))
+            };
+            if let (Some(configured), Some(ratio)) = (configured_request_size, uniqueness_ratio) {
+                Self::tune_request_size(state, configured, ratio).await;
             }
+
+            Self::verify_sample(api_client, metrics, state, generation_config, config, &processing_stats.new_values)
+                .await;
         }
 
         Ok(())
     }
 
-    /// Make API request with exponential backoff (pure function)
-    async fn make_request_with_retries(
+    /// Keep a successful response that yielded no attributes instead of silently dropping it
+    async fn record_dead_letter(
+        state: &Arc<RwLock<ProducerState>>,
+        config: &ExecutionConfig,
+        provider_config: &ProviderConfig,
+        response: &ApiResponse,
+    ) {
+        process_warn!(
+            ProcessId::current(),
+            provider = %provider_config.provider,
+            "📭 No attributes extracted from {} response ({} chars)",
+            provider_config.provider,
+            response.content.len()
+        );
+        let Some(ref dir) = config.dead_letter_dir else {
+            return;
+        };
+
+        let letter = DeadLetter {
+            timestamp: response.timestamp,
+            topic: state.read().await.config.topic.clone(),
+            provider: provider_config.provider,
+            model: response.model.clone().unwrap_or_else(|| provider_config.model.clone()),
+            finish_reason: response.finish_reason.clone(),
+            content: response.content.clone(),
+        };
+        let log = DeadLetterLog::new(dir);
+        if let Err(e) = log.record(&letter).await {
+            process_warn!(
+                ProcessId::current(),
+                "⚠️ Failed to write dead letter to {}: {}",
+                log.path(&letter.topic).display(),
+                e
+            );
+        }
+    }
+
+    /// Feed the rolling uniqueness ratio to the request-size tuner, tracing any change
+    async fn tune_request_size(state: &Arc<RwLock<ProducerState>>, configured: usize, uniqueness_ratio: Option<f64>) {
+        let change = match state.write().await.request_size_tuner.as_mut() {
+            Some(tuner) => tuner.record(configured, uniqueness_ratio),
+            None => return,
+        };
+        if let Some(change) = change {
+            process_info!(
+                ProcessId::current(),
+                previous = change.previous,
+                request_size = change.current,
+                uniqueness_ratio = change.uniqueness_ratio,
+                "📏 Request size tuned from {} to {} (uniqueness {:.2})",
+                change.previous,
+                change.current,
+                change.uniqueness_ratio
+            );
+        }
+    }
+
+    /// Re-check a sample of newly accepted attributes with the verification provider
+    ///
+    /// The verification request runs in the background so it never holds up the request that produced the sample.
+    async fn verify_sample(
         api_client: &Arc<A>,
+        metrics: &Arc<RwLock<Metrics>>,
+        state: &Arc<RwLock<ProducerState>>,
+        generation_config: &Option<GenerationConfig>,
+        config: &ExecutionConfig,
+        new_values: &[String],
+    ) {
+        let Some(ref verification_provider) = config.verification_provider else {
+            return;
+        };
+        let (sampled, topic) = {
+            let mut state_guard = state.write().await;
+            let state_guard = &mut *state_guard;
+            let sampled = sample_for_verification(new_values, config.verification_sample_rate, &mut state_guard.rng);
+            (sampled, state_guard.config.topic.clone())
+        };
+        if sampled.is_empty() {
+            return;
+        }
+
+        // Verification is a single low-temperature attempt; it never feeds routing, budgets or UAM
+        let mut request = build_api_request_with_config(
+            verification_provider,
+            generation_config,
+            build_verification_prompt(&topic, &sampled),
+            Uuid::new_v4(),
+        );
+        request.temperature = 0.0;
+        let verification_provider = verification_provider.provider;
+        let api_client = api_client.clone();
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            Self::record_verification(&api_client, &metrics, verification_provider, request, &sampled).await;
+        });
+    }
+
+    /// Send a verification request and record how many sampled attributes it disputed
+    async fn record_verification(
+        api_client: &Arc<A>,
+        metrics: &Arc<RwLock<Metrics>>,
+        verification_provider: ProviderId,
         request: ApiRequest,
-        max_retries: u32,
+        sampled: &[String],
+    ) {
+        let response = match api_client.send_request(request).await {
+            Ok(response) if response.success => response,
+            Ok(response) => {
+                process_debug!(
+                    ProcessId::current(),
+                    "🔎 Verification by {} failed: {}",
+                    verification_provider,
+                    response.error_message.as_deref().unwrap_or("unknown error")
+                );
+                return;
+            }
+            Err(e) => {
+                process_debug!(ProcessId::current(), "🔎 Verification by {} failed: {}", verification_provider, e);
+                return;
+            }
+        };
+
+        let conflicts = count_verification_conflicts(sampled, &response.content);
+        let cost = api_client.estimate_cost(verification_provider, &response.tokens_used);
+        metrics
+            .write()
+            .await
+            .record_verification(verification_provider, sampled.len(), conflicts, cost);
+        if conflicts > 0 {
+            process_info!(
+                ProcessId::current(),
+                "🔎 {} of {} sampled attributes disputed by verification provider {}",
+                conflicts,
+                sampled.len(),
+                verification_provider
+            );
+        }
+    }
+
+    /// Stop issuing requests once every routable provider has spent its token budget
+    async fn stop_on_exhausted_budgets(
+        communicator: &Arc<RwLock<C>>,
+        state: &Arc<RwLock<ProducerState>>,
+        config: &ExecutionConfig,
+        exhausted: &[ProviderId],
+    ) -> ProducerResult<()> {
+        {
+            let mut state_guard = state.write().await;
+            // Concurrent requests may all notice; only the first reports it
+            if !state_guard.is_running {
+                return Ok(());
+            }
+            state_guard.is_running = false;
+        }
+
+        let providers: Vec<String> = exhausted.iter().map(|provider| provider.to_string()).collect();
+        let message = format!("Token budget exhausted for all providers ({})", providers.join(", "));
+        process_warn!(ProcessId::current(), "💸 {}, no further requests will be issued", message);
+
+        if matches!(config.mode, ExecutionMode::Production { .. }) {
+            let update = ProducerUpdate::StatusUpdate {
+                producer_id: ProcessId::current().clone(),
+                status: ProcessStatus::Stopped,
+                message: Some(message),
+                performance_stats: None,
+            };
+            communicator.read().await.send_update(update).await?;
+        }
+        Ok(())
+    }
+
+    /// Make API request with jittered exponential backoff (pure function)
+    ///
+    /// When the model is unavailable or the prompt overflows its context window, the
+    /// request steps down to the next of `fallback_models` straight away instead of
+    /// waiting to retry the same model. Stepping down does not use up a retry.
+    async fn make_request_with_retries(
+        api_client: &Arc<A>,
+        mut request: ApiRequest,
+        fallback_models: &[String],
+        config: &ExecutionConfig,
     ) -> ProducerResult<ApiResponse> {
-        let mut last_error = None;
+        let max_retries = config.max_retries;
+        let mut fallbacks = fallback_models.iter();
+        let mut attempt = 0;
 
-        for attempt in 0..=max_retries {
+        loop {
             match api_client.send_request(request.clone()).await {
-                Ok(response) => {
+                Ok(mut response) => {
                     if response.success {
+                        // Record the model that actually answered, even if the client did not
+                        if response.model.is_none() {
+                            response.model = request.model;
+                        }
                         return Ok(response);
                     }
 
+                    if needs_model_fallback(&response) {
+                        if let Some(model) = fallbacks.next() {
+                            process_warn!(
+                                ProcessId::current(),
+                                provider = %request.provider,
+                                "🔽 {} model {} failed ({}), falling back to {}",
+                                request.provider,
+                                request.model.as_deref().unwrap_or("default"),
+                                response.error_message.as_deref().unwrap_or("unavailable"),
+                                model
+                            );
+                            request.model = Some(model.clone());
+                            continue;
+                        }
+                    }
+
                     // Check if we should retry
-                    if let Some(delay) = should_retry_request(&response, attempt, max_retries) {
+                    if let Some(delay) =
+                        should_retry_request(&response, attempt, max_retries, config.retry_base_ms, config.retry_max_ms)
+                    {
                         process_warn!(
                             ProcessId::current(),
                             "⏳ API error (attempt {}), retrying in {}ms",
@@ -543,36 +1227,38 @@ where
                             delay.as_millis()
                         );
                         tokio::time::sleep(delay).await;
+                        attempt += 1;
                         continue;
                     }
 
                     return Ok(response);
                 }
                 Err(e) => {
-                    last_error = Some(e);
-                    if attempt < max_retries {
-                        let delay = Duration::from_millis(100 * (1 << attempt));
-                        process_warn!(
-                            ProcessId::current(),
-                            "⏳ Network error (attempt {}), retrying in {}ms",
-                            attempt + 1,
-                            delay.as_millis()
-                        );
-                        tokio::time::sleep(delay).await;
+                    if attempt >= max_retries {
+                        return Err(e);
                     }
+                    let delay = jittered_backoff(attempt, config.retry_base_ms, config.retry_max_ms, &mut rand::thread_rng());
+                    process_warn!(
+                        ProcessId::current(),
+                        "⏳ Network error (attempt {}), retrying in {}ms",
+                        attempt + 1,
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
                 }
             }
         }
-
-        Err(last_error.unwrap_or_else(|| ProducerError::api("unknown", "Max retries exceeded")))
     }
 
     /// Send attributes to orchestrator (pure function)
     async fn send_attributes_to_orchestrator(
         communicator: &Arc<RwLock<C>>,
+        topic: &str,
         attributes: &[String],
         provider: ProviderId,
         api_response: &crate::types::ApiResponse,
+        estimated_cost: f64,
     ) -> ProducerResult<()> {
         let producer_id = ProcessId::current().clone();
 
@@ -582,11 +1268,16 @@ where
             batch_id: chrono::Utc::now().timestamp_millis() as u64,
             provider_metadata: shared::types::ProviderMetadata {
                 provider_id: provider,
-                model: format!("{provider:?}-model").to_lowercase(),
+                model: api_response
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| format!("{provider:?}-model").to_lowercase()),
                 response_time_ms: api_response.response_time_ms,
                 tokens: api_response.tokens_used.clone(),
                 request_timestamp: api_response.timestamp.timestamp_millis() as u64,
             },
+            estimated_cost,
+            topic: topic.to_string(),
         };
 
         let communicator = communicator.read().await;
@@ -600,11 +1291,23 @@ where
         Ok(())
     }
 
+    /// Send a structured error report to the orchestrator
+    async fn report_error(communicator: &Arc<RwLock<C>>, error: SharedError) -> ProducerResult<()> {
+        let update = ProducerUpdate::Error {
+            producer_id: ProcessId::current().clone(),
+            error_code: error.code,
+            message: error.message,
+            command_id: None,
+        };
+        communicator.read().await.send_update(update).await
+    }
+
     /// Start status reporter (same for both modes)
     fn start_status_reporter(&self) -> tokio::task::JoinHandle<()> {
         let communicator = self.communicator.clone();
         let state = self.state.clone();
         let metrics = self.metrics.clone();
+        let auto_tuner = self.auto_tuner.clone();
         let status_interval_duration = self.config.status_report_interval;
         let has_orchestrator = matches!(self.config.mode, ExecutionMode::Production { .. });
 
@@ -624,9 +1327,14 @@ where
                     (state.is_running, state.current_prompt.clone())
                 };
 
-                let current_metrics = {
+                let (current_metrics, provider_usage) = {
                     let metrics = metrics.read().await;
-                    metrics.get_current_metrics()
+                    (metrics.get_current_metrics(), metrics.get_provider_usage())
+                };
+
+                let tuned_limits = match auto_tuner {
+                    Some(ref tuner) => tuner.read().await.tuned_limits(),
+                    None => HashMap::new(),
                 };
 
                 let status_update = ProducerUpdate::StatusUpdate {
@@ -641,10 +1349,17 @@ where
                         attributes_generated_last_minute: current_metrics.attributes_extracted,
                         unique_contributed_last_minute: current_metrics.unique_attributes,
                         requests_made_last_minute: current_metrics.requests_sent,
-                        provider_usage: HashMap::new(),
+                        provider_usage,
                         current_batch_rate: current_metrics.attributes_per_minute(),
                         memory_usage_mb: None,
                         bloom_filter_size_mb: None,
+                        tuned_limits,
+                        verification: VerificationStats {
+                            requests: current_metrics.verification_requests,
+                            samples: current_metrics.verification_samples,
+                            conflicts: current_metrics.verification_conflicts,
+                            cost: current_metrics.verification_cost,
+                        },
                     }),
                 };
 
@@ -665,8 +1380,8 @@ where
         })
     }
 
-    /// Clean shutdown
-    async fn cleanup(&self) -> ProducerResult<()> {
+    /// Clean shutdown: let in-flight requests deliver their attributes, then disconnect
+    async fn cleanup(&self, request_processor: tokio::task::JoinHandle<()>) -> ProducerResult<()> {
         {
             let mut state = self.state.write().await;
             if state.is_running {
@@ -674,6 +1389,20 @@ where
             }
         }
 
+        let _ = self.drain_tx.send(true);
+        let abort_handle = request_processor.abort_handle();
+        match tokio::time::timeout(self.config.drain_timeout, request_processor).await {
+            Ok(_) => process_info!(ProcessId::current(), "🚰 In-flight requests drained"),
+            Err(_) => {
+                process_warn!(
+                    ProcessId::current(),
+                    "⏱️ In-flight requests still running after {}ms, abandoning them",
+                    self.config.drain_timeout.as_millis()
+                );
+                abort_handle.abort();
+            }
+        }
+
         if matches!(self.config.mode, ExecutionMode::Production { .. }) {
             let communicator = self.communicator.read().await;
             let _ = communicator.disconnect().await;
@@ -722,3 +1451,912 @@ where
 
     Ok(Producer::new(config, api_client, communicator))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct UnusedApiClient;
+
+    #[async_trait]
+    impl ApiClient for UnusedApiClient {
+        async fn send_request(&self, _request: ApiRequest) -> ProducerResult<ApiResponse> {
+            Err(ProducerError::config("no requests expected"))
+        }
+
+        async fn health_check(&self, _provider: ProviderId) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn estimate_cost(&self, _provider: ProviderId, _tokens: &shared::TokenUsage) -> f64 {
+            0.0
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingCommunicator {
+        sent: Arc<std::sync::Mutex<Vec<ProducerUpdate>>>,
+        commands: Arc<std::sync::Mutex<Option<mpsc::Sender<ProducerCommand>>>>,
+    }
+
+    #[async_trait]
+    impl Communicator for RecordingCommunicator {
+        async fn initialize(&mut self) -> ProducerResult<()> {
+            Ok(())
+        }
+
+        async fn get_commands(&mut self) -> ProducerResult<mpsc::Receiver<ProducerCommand>> {
+            let (tx, rx) = mpsc::channel(1);
+            *self.commands.lock().unwrap() = Some(tx);
+            Ok(rx)
+        }
+
+        async fn send_update(&self, update: ProducerUpdate) -> ProducerResult<()> {
+            self.sent.lock().unwrap().push(update);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn get_listen_port(&self) -> Option<u16> {
+            None
+        }
+
+        async fn disconnect(&self) -> ProducerResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Communicator whose first `failures` updates are lost, as while the orchestrator restarts
+    struct FlakyCommunicator {
+        failures: std::sync::atomic::AtomicU32,
+        sent: Arc<std::sync::Mutex<Vec<ProducerUpdate>>>,
+    }
+
+    #[async_trait]
+    impl Communicator for FlakyCommunicator {
+        async fn initialize(&mut self) -> ProducerResult<()> {
+            Ok(())
+        }
+
+        async fn get_commands(&mut self) -> ProducerResult<mpsc::Receiver<ProducerCommand>> {
+            Ok(mpsc::channel(1).1)
+        }
+
+        async fn send_update(&self, update: ProducerUpdate) -> ProducerResult<()> {
+            let remaining = self.failures.load(std::sync::atomic::Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, std::sync::atomic::Ordering::SeqCst);
+                return Err(ProducerError::ipc("Failed to connect: connection refused"));
+            }
+            self.sent.lock().unwrap().push(update);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn get_listen_port(&self) -> Option<u16> {
+            None
+        }
+
+        async fn disconnect(&self) -> ProducerResult<()> {
+            Ok(())
+        }
+    }
+
+    fn start_command(seed: u64) -> ProducerCommand {
+        ProducerCommand::Start {
+            command_id: 1,
+            topic: "animals".to_string(),
+            prompt: "List animals".to_string(),
+            routing_strategy: RoutingStrategy::Backoff {
+                provider: shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+            },
+            generation_config: GenerationConfig {
+                model: "random".to_string(),
+                batch_size: 1,
+                context_window: 4096,
+                max_tokens: 100,
+                temperature: 0.7,
+                request_size: 10,
+                extraction_format: None,
+                system_prompt: None,
+                min_attribute_length: None,
+                max_attribute_length: None,
+                include_seen_hint: false,
+                seen_hint_count: 0,
+            },
+            seed,
+            max_cost_per_minute: None,
+            dedup: true,
+            category: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_producer_keeps_running_while_orchestrator_connection_recovers() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(
+            Some("127.0.0.1:6001".to_string()),
+            "animals".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let communicator = Arc::new(RwLock::new(FlakyCommunicator {
+            failures: std::sync::atomic::AtomicU32::new(1),
+            sent: sent.clone(),
+        }));
+        type TestProducer = Producer<UnusedApiClient, FlakyCommunicator>;
+
+        // A delivery failure the communicator recovers from does not stop the producer
+        let lost = Ok(Err(ProducerError::ipc("Failed to connect: connection refused")));
+        assert!(!TestProducer::handle_request_result(lost, &communicator, &config).await);
+
+        // Once the connection is back, the next error reaches the orchestrator
+        let failed = Ok(Err(ProducerError::config("bad response")));
+        assert!(!TestProducer::handle_request_result(failed, &communicator, &config).await);
+        assert!(matches!(sent.lock().unwrap().as_slice(), [ProducerUpdate::Error { .. }]));
+
+        // Only exhausting every reconnection attempt terminates it
+        let unreachable = Ok(Err(ProducerError::ipc(format!(
+            "{} 5 reconnection attempts: connection refused",
+            crate::services::communicator::ORCHESTRATOR_UNREACHABLE
+        ))));
+        assert!(TestProducer::handle_request_result(unreachable, &communicator, &config).await);
+    }
+
+    #[tokio::test]
+    async fn test_paused_producer_keeps_state_and_answers_ping() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(
+            Some("127.0.0.1:6001".to_string()),
+            "animals".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let communicator = RecordingCommunicator::default();
+        let sent = communicator.sent.clone();
+        let producer = Producer::new(config, UnusedApiClient, communicator);
+
+        producer.handle_command(start_command(7)).await.unwrap();
+
+        producer.handle_command(ProducerCommand::Pause { command_id: 2 }).await.unwrap();
+        {
+            let state = producer.state.read().await;
+            assert!(!state.is_running);
+            assert_eq!(state.current_prompt.as_deref(), Some("List animals"));
+        }
+
+        producer.handle_command(ProducerCommand::Ping { ping_id: 9 }).await.unwrap();
+        assert!(matches!(
+            sent.lock().unwrap().as_slice(),
+            [ProducerUpdate::Pong { ping_id: 9, .. }]
+        ));
+
+        producer.handle_command(ProducerCommand::Resume { command_id: 3 }).await.unwrap();
+        assert!(producer.state.read().await.is_running);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_sets_and_clears_cost_budget() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(None, "animals".to_string(), None, None, None).unwrap();
+        let producer = Producer::new(config, UnusedApiClient, RecordingCommunicator::default());
+        let update_budget = |max_cost_per_minute| ProducerCommand::UpdateConfig {
+            command_id: 1,
+            routing_strategy: None,
+            generation_config: None,
+            prompt: None,
+            max_cost_per_minute,
+        };
+
+        producer.handle_command(update_budget(Some(0.5))).await.unwrap();
+        assert_eq!(producer.state.read().await.cost_budget_per_minute, Some(0.5));
+
+        // None leaves the budget alone, 0 clears it
+        producer.handle_command(update_budget(None)).await.unwrap();
+        assert_eq!(producer.state.read().await.cost_budget_per_minute, Some(0.5));
+        producer.handle_command(update_budget(Some(0.0))).await.unwrap();
+        assert_eq!(producer.state.read().await.cost_budget_per_minute, None);
+    }
+
+    #[tokio::test]
+    async fn test_random_seed_pins_random_provider_output_across_run_seeds() {
+        ProcessId::init_producer(1);
+        let random_output = |run_seed: u64| async move {
+            let config = ExecutionConfig::from_args_and_env(
+                Some("127.0.0.1:6001".to_string()),
+                "animals".to_string(),
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .with_random_seed(Some(42));
+            let api_client = crate::services::RealApiClient::new(HashMap::new(), 30000);
+            let producer = Producer::new(config, api_client, RecordingCommunicator::default());
+            producer.handle_command(start_command(run_seed)).await.unwrap();
+
+            let request = build_api_request_with_config(
+                &shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+                &None,
+                "List animals".to_string(),
+                Uuid::new_v4(),
+            );
+            producer.api_client.send_request(request).await.unwrap().content
+        };
+
+        let first = random_output(1).await;
+        assert!(!first.is_empty());
+        assert_eq!(first, random_output(2).await);
+
+        // The seed is mixed with the producer's stream, so producers don't all repeat one another
+        let unmixed = crate::services::RealApiClient::new(HashMap::new(), 30000);
+        unmixed.reseed(42);
+        let request = build_api_request_with_config(
+            &shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+            &None,
+            "List animals".to_string(),
+            Uuid::new_v4(),
+        );
+        assert_ne!(first, unmixed.send_request(request).await.unwrap().content);
+    }
+
+    #[derive(Default)]
+    struct RecordingApiClient {
+        providers: std::sync::Mutex<Vec<ProviderId>>,
+        prompts: std::sync::Mutex<Vec<String>>,
+        content: String,
+    }
+
+    #[async_trait]
+    impl ApiClient for RecordingApiClient {
+        async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+            self.providers.lock().unwrap().push(request.provider);
+            self.prompts.lock().unwrap().push(request.prompt);
+            Ok(ApiResponse {
+                provider: request.provider,
+                request_id: request.request_id,
+                content: self.content.clone(),
+                tokens_used: shared::TokenUsage::default(),
+                response_time_ms: 1,
+                timestamp: chrono::Utc::now(),
+                success: true,
+                error_message: None,
+                retry_after_ms: None,
+                failure: None,
+                finish_reason: None,
+                model: None,
+                cached: false,
+            })
+        }
+
+        async fn health_check(&self, _provider: ProviderId) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn estimate_cost(&self, provider: ProviderId, _tokens: &shared::TokenUsage) -> f64 {
+            if provider == ProviderId::OpenAI { 0.01 } else { 0.0 }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_provider_falls_back_then_defers() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(None, "animals".to_string(), None, None, None)
+            .unwrap()
+            .with_rate_limits(HashMap::from([(ProviderId::OpenAI, 1), (ProviderId::Random, 1)]));
+        let producer = Producer::new(config, RecordingApiClient::default(), RecordingCommunicator::default());
+        let strategy = Some(RoutingStrategy::PriorityOrder {
+            providers: vec![
+                shared::types::ProviderConfig::with_default_model(ProviderId::OpenAI),
+                shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+            ],
+        });
+
+        for _ in 0..3 {
+            Producer::process_single_request(
+                &producer.api_client,
+                &producer.processor,
+                &producer.metrics,
+                &producer.prompt_handler,
+                &producer.communicator,
+                &producer.state,
+                &None,
+                1,
+                &strategy,
+                &None,
+                "List animals",
+                &producer.config,
+            )
+            .await
+            .unwrap();
+        }
+
+        // OpenAI's single request is spent, so the next goes to Random and the third is deferred
+        assert_eq!(
+            *producer.api_client.providers.lock().unwrap(),
+            vec![ProviderId::OpenAI, ProviderId::Random]
+        );
+    }
+
+    /// Serves only the "small" model, reporting any other as unavailable
+    #[derive(Default)]
+    struct SmallModelOnlyClient {
+        models: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait]
+    impl ApiClient for SmallModelOnlyClient {
+        async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+            self.models.lock().unwrap().push(request.model.clone());
+            let success = request.model.as_deref() == Some("small");
+            Ok(ApiResponse {
+                provider: request.provider,
+                request_id: request.request_id,
+                content: if success { "lion\ntiger".to_string() } else { String::new() },
+                tokens_used: shared::TokenUsage::default(),
+                response_time_ms: 1,
+                timestamp: chrono::Utc::now(),
+                success,
+                error_message: (!success).then(|| "HTTP 404: model not found".to_string()),
+                retry_after_ms: None,
+                failure: (!success).then_some(ApiFailure::ModelUnavailable),
+                finish_reason: None,
+                model: None,
+                cached: false,
+            })
+        }
+
+        async fn health_check(&self, _provider: ProviderId) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn estimate_cost(&self, _provider: ProviderId, _tokens: &shared::TokenUsage) -> f64 {
+            0.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_model_falls_back_to_next_model() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(
+            Some("127.0.0.1:6001".to_string()),
+            "animals".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let communicator = RecordingCommunicator::default();
+        let sent = communicator.sent.clone();
+        let producer = Producer::new(config, SmallModelOnlyClient::default(), communicator);
+        let strategy = Some(RoutingStrategy::Backoff {
+            provider: shared::types::ProviderConfig::from_model_spec(ProviderId::OpenAI, "large|small"),
+        });
+
+        Producer::process_single_request(
+            &producer.api_client,
+            &producer.processor,
+            &producer.metrics,
+            &producer.prompt_handler,
+            &producer.communicator,
+            &producer.state,
+            &None,
+            1,
+            &strategy,
+            &None,
+            "List animals",
+            &producer.config,
+        )
+        .await
+        .unwrap();
+
+        // The configured model fails once and the fallback is tried straight away
+        assert_eq!(
+            *producer.api_client.models.lock().unwrap(),
+            vec![None, Some("small".to_string())]
+        );
+        let updates = sent.lock().unwrap();
+        let model = updates.iter().find_map(|update| match update {
+            ProducerUpdate::AttributeBatch { provider_metadata, .. } => Some(provider_metadata.model.clone()),
+            _ => None,
+        });
+        assert_eq!(model.as_deref(), Some("small"));
+        assert!(!updates.iter().any(|update| matches!(update, ProducerUpdate::Error { .. })));
+    }
+
+    struct SlowApiClient {
+        started: Arc<tokio::sync::Notify>,
+    }
+
+    #[async_trait]
+    impl ApiClient for SlowApiClient {
+        async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+            self.started.notify_one();
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(ApiResponse {
+                provider: request.provider,
+                request_id: request.request_id,
+                content: "red panda, snow leopard".to_string(),
+                tokens_used: shared::TokenUsage {
+                    input_tokens: 120,
+                    output_tokens: 80,
+                },
+                response_time_ms: 300,
+                timestamp: chrono::Utc::now(),
+                success: true,
+                error_message: None,
+                retry_after_ms: None,
+                failure: None,
+                finish_reason: None,
+                model: None,
+                cached: false,
+            })
+        }
+
+        async fn health_check(&self, _provider: ProviderId) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn estimate_cost(&self, _provider: ProviderId, tokens: &shared::TokenUsage) -> f64 {
+            tokens.input_tokens as f64 * 0.00001 + tokens.output_tokens as f64 * 0.00002
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_delivers_attributes_from_in_flight_request() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(
+            Some("127.0.0.1:6001".to_string()),
+            "animals".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_drain_timeout(Duration::from_secs(5));
+        let started = Arc::new(tokio::sync::Notify::new());
+        let communicator = RecordingCommunicator::default();
+        let sent = communicator.sent.clone();
+        let commands = communicator.commands.clone();
+        let mut producer = Producer::new(config, SlowApiClient { started: started.clone() }, communicator);
+        let shutdown = producer.shutdown_sender();
+        let run = tokio::spawn(async move { producer.run().await });
+
+        let commands_tx = loop {
+            if let Some(tx) = commands.lock().unwrap().clone() {
+                break tx;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        commands_tx.send(start_command(7)).await.unwrap();
+
+        // Shut down while the slow response is still outstanding
+        started.notified().await;
+        shutdown.send(()).await.unwrap();
+        run.await.unwrap().unwrap();
+
+        let delivered = sent.lock().unwrap().iter().find_map(|update| match update {
+            ProducerUpdate::AttributeBatch {
+                attributes,
+                provider_metadata,
+                estimated_cost,
+                ..
+            } if attributes.contains(&"red panda".to_string()) => Some((provider_metadata.clone(), *estimated_cost)),
+            _ => None,
+        });
+        let (provider_metadata, estimated_cost) = delivered.expect("in-flight batch should be flushed before disconnecting");
+
+        // The batch carries the producer's own pricing and latency rather than leaving them to be re-derived
+        let expected_cost = SlowApiClient { started }.estimate_cost(ProviderId::Random, &provider_metadata.tokens);
+        assert_eq!(provider_metadata.tokens.input_tokens, 120);
+        assert_eq!(provider_metadata.response_time_ms, 300);
+        assert_eq!(estimated_cost, expected_cost);
+    }
+
+    /// Answers slowly while recording how many requests overlap
+    #[derive(Default)]
+    struct ConcurrencyProbeClient {
+        in_flight: std::sync::atomic::AtomicUsize,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+        completed: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ApiClient for ConcurrencyProbeClient {
+        async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+            use std::sync::atomic::Ordering;
+            let now_in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now_in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.completed.fetch_add(1, Ordering::SeqCst);
+            Ok(ApiResponse {
+                provider: request.provider,
+                request_id: request.request_id,
+                content: format!("animal {}", request.request_id),
+                tokens_used: shared::TokenUsage::default(),
+                response_time_ms: 100,
+                timestamp: chrono::Utc::now(),
+                success: true,
+                error_message: None,
+                retry_after_ms: None,
+                failure: None,
+                finish_reason: None,
+                model: None,
+                cached: false,
+            })
+        }
+
+        async fn health_check(&self, _provider: ProviderId) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn estimate_cost(&self, _provider: ProviderId, _tokens: &shared::TokenUsage) -> f64 {
+            0.0
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_in_flight_requests_are_capped_by_max_concurrent_requests() {
+        ProcessId::init_producer(1);
+        let mut config = ExecutionConfig::from_args_and_env(
+            Some("127.0.0.1:6001".to_string()),
+            "animals".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_max_concurrent_requests(3);
+        config.request_interval = Duration::from_millis(5);
+        let client = ConcurrencyProbeClient::default();
+        let peak = client.peak.clone();
+        let completed = client.completed.clone();
+        let communicator = RecordingCommunicator::default();
+        let commands = communicator.commands.clone();
+        let mut producer = Producer::new(config, client, communicator);
+        let shutdown = producer.shutdown_sender();
+        let run = tokio::spawn(async move { producer.run().await });
+
+        let commands_tx = loop {
+            if let Some(tx) = commands.lock().unwrap().clone() {
+                break tx;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        commands_tx.send(start_command(7)).await.unwrap();
+
+        // Requests start every few milliseconds but each takes 100ms, so they pile up against the cap
+        while completed.load(std::sync::atomic::Ordering::SeqCst) < 9 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        shutdown.send(()).await.unwrap();
+        run.await.unwrap().unwrap();
+
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    /// Run a producer against a client costing $0.01 per request and count the requests it sends
+    async fn requests_sent_under_budget(budget: Option<f64>, run_for: Duration) -> usize {
+        let mut config = ExecutionConfig::from_args_and_env(
+            Some("127.0.0.1:6001".to_string()),
+            "animals".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        config.request_interval = Duration::from_millis(20);
+        let communicator = RecordingCommunicator::default();
+        let commands = communicator.commands.clone();
+        let mut producer = Producer::new(config, RecordingApiClient::default(), communicator);
+        let shutdown = producer.shutdown_sender();
+        let run = tokio::spawn(async move {
+            producer.run().await.unwrap();
+            producer
+        });
+
+        let commands_tx = loop {
+            if let Some(tx) = commands.lock().unwrap().clone() {
+                break tx;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        let start = match start_command(7) {
+            ProducerCommand::Start {
+                command_id,
+                topic,
+                prompt,
+                generation_config,
+                seed,
+                ..
+            } => ProducerCommand::Start {
+                command_id,
+                topic,
+                prompt,
+                routing_strategy: RoutingStrategy::Backoff {
+                    provider: shared::types::ProviderConfig::with_default_model(ProviderId::OpenAI),
+                },
+                generation_config,
+                seed,
+                max_cost_per_minute: budget,
+                dedup: true,
+                category: None,
+            },
+            _ => unreachable!(),
+        };
+        commands_tx.send(start).await.unwrap();
+
+        tokio::time::sleep(run_for).await;
+        shutdown.send(()).await.unwrap();
+        let producer = run.await.unwrap();
+        let sent = producer.api_client.providers.lock().unwrap().len();
+        sent
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_low_cost_budget_stretches_request_interval() {
+        ProcessId::init_producer(1);
+        let run_for = Duration::from_millis(400);
+        let unthrottled = requests_sent_under_budget(None, run_for).await;
+        // A budget of one request per minute is spent by the first response
+        let throttled = requests_sent_under_budget(Some(0.01), run_for).await;
+
+        let unthrottled_interval = run_for / unthrottled.max(1) as u32;
+        let throttled_interval = run_for / throttled.max(1) as u32;
+        assert!(unthrottled >= 8, "expected steady requests without a budget, got {unthrottled}");
+        assert!(
+            throttled_interval >= unthrottled_interval * 3,
+            "budget should stretch the interval: {throttled_interval:?} vs {unthrottled_interval:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verification_sampling_stays_out_of_generation_metrics() {
+        ProcessId::init_producer(1);
+        let strategy = Some(RoutingStrategy::Backoff {
+            provider: shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+        });
+        let run_request = |sample_rate: f32| {
+            let strategy = strategy.clone();
+            async move {
+                let config = ExecutionConfig::from_args_and_env(None, "animals".to_string(), None, None, None)
+                    .unwrap()
+                    .with_verification(shared::types::ProviderConfig::with_default_model(ProviderId::OpenAI), sample_rate);
+                let api_client = RecordingApiClient {
+                    content: "otter, lynx, heron".to_string(),
+                    ..Default::default()
+                };
+                let producer = Producer::new(config, api_client, RecordingCommunicator::default());
+                Producer::process_single_request(
+                    &producer.api_client,
+                    &producer.processor,
+                    &producer.metrics,
+                    &producer.prompt_handler,
+                    &producer.communicator,
+                    &producer.state,
+                    &None,
+                    1,
+                    &strategy,
+                    &None,
+                    "List animals",
+                    &producer.config,
+                )
+                .await
+                .unwrap();
+
+                // The verification request runs in the background
+                for _ in 0..100 {
+                    let verified = producer.metrics.read().await.get_current_metrics().verification_requests > 0;
+                    if sample_rate == 0.0 || verified {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                let providers = producer.api_client.providers.lock().unwrap().clone();
+                let metrics = producer.metrics.read().await.get_current_metrics();
+                (providers, metrics)
+            }
+        };
+
+        let (providers, metrics) = run_request(0.0).await;
+        assert_eq!(providers, vec![ProviderId::Random]);
+        assert_eq!(metrics.verification_requests, 0);
+
+        // Every attribute is sampled and the recording client echoes them all back as rejected
+        let (providers, metrics) = run_request(1.0).await;
+        assert_eq!(providers, vec![ProviderId::Random, ProviderId::OpenAI]);
+        assert_eq!(metrics.verification_requests, 1);
+        assert_eq!(metrics.verification_samples, 3);
+        assert_eq!(metrics.verification_conflicts, 3);
+        assert_eq!(metrics.verification_cost, 0.01);
+        assert_eq!(metrics.requests_sent, 1);
+        assert_eq!(metrics.unique_attributes, 3);
+        assert_eq!(metrics.total_cost, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_request_size_shrinks_when_responses_repeat() {
+        ProcessId::init_producer(1);
+        let strategy = Some(RoutingStrategy::Backoff {
+            provider: shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+        });
+        let generation_config = match start_command(1) {
+            ProducerCommand::Start { generation_config, .. } => Some(GenerationConfig {
+                request_size: 60,
+                ..generation_config
+            }),
+            _ => unreachable!(),
+        };
+        let config = ExecutionConfig::from_args_and_env(None, "animals".to_string(), None, None, None)
+            .unwrap()
+            .with_request_size_tuning(20, 100);
+        // Every response repeats the same values, so only the first one is new
+        let api_client = RecordingApiClient {
+            content: "otter, lynx, heron".to_string(),
+            ..Default::default()
+        };
+        let producer = Producer::new(config, api_client, RecordingCommunicator::default());
+
+        for _ in 0..10 {
+            Producer::process_single_request(
+                &producer.api_client,
+                &producer.processor,
+                &producer.metrics,
+                &producer.prompt_handler,
+                &producer.communicator,
+                &producer.state,
+                &None,
+                1,
+                &strategy,
+                &generation_config,
+                "List animals",
+                &producer.config,
+            )
+            .await
+            .unwrap();
+        }
+
+        let tuned = producer.state.read().await.request_size_tuner.as_ref().unwrap().request_size(60);
+        assert_eq!(tuned, 33);
+        let prompts = producer.api_client.prompts.lock().unwrap();
+        assert!(prompts[0].starts_with("Generate 60 new entries"));
+        assert!(prompts[5].starts_with("Generate 45 new entries"));
+        assert!(prompts[9].starts_with("Generate 45 new entries"));
+    }
+
+    #[tokio::test]
+    async fn test_response_without_attributes_goes_to_dead_letter_log() {
+        ProcessId::init_producer(1);
+        let dir = tempfile::tempdir().unwrap();
+        let config = ExecutionConfig::from_args_and_env(None, "animals".to_string(), None, None, None)
+            .unwrap()
+            .with_dead_letter_dir(Some(dir.path().to_path_buf()));
+        // Numbers are never accepted as attributes, so nothing can be extracted
+        let api_client = RecordingApiClient {
+            content: "1. 42\n2. 1999".to_string(),
+            ..Default::default()
+        };
+        let producer = Producer::new(config, api_client, RecordingCommunicator::default());
+        let strategy = Some(RoutingStrategy::Backoff {
+            provider: shared::types::ProviderConfig::with_default_model(ProviderId::Random),
+        });
+
+        Producer::process_single_request(
+            &producer.api_client,
+            &producer.processor,
+            &producer.metrics,
+            &producer.prompt_handler,
+            &producer.communicator,
+            &producer.state,
+            &None,
+            1,
+            &strategy,
+            &None,
+            "List animals",
+            &producer.config,
+        )
+        .await
+        .unwrap();
+
+        let log = std::fs::read_to_string(DeadLetterLog::new(dir.path()).path("animals")).unwrap();
+        let letters: Vec<DeadLetter> = log.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].topic, "animals");
+        assert_eq!(letters[0].provider, ProviderId::Random);
+        assert_eq!(letters[0].model, "random");
+        assert_eq!(letters[0].content, "1. 42\n2. 1999");
+
+        let metrics = producer.metrics.read().await.get_current_metrics();
+        assert_eq!(metrics.empty_responses, 1);
+        assert_eq!(metrics.responses_received, 1);
+    }
+
+    /// Blocks the first request it sees with a content filter and answers every later one
+    #[derive(Default)]
+    struct FilteringApiClient {
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ApiClient for FilteringApiClient {
+        async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+            let mut prompts = self.prompts.lock().unwrap();
+            let blocked = prompts.is_empty();
+            prompts.push(request.prompt);
+            Ok(ApiResponse {
+                provider: request.provider,
+                request_id: request.request_id,
+                content: if blocked { String::new() } else { "otter, lynx".to_string() },
+                tokens_used: shared::TokenUsage::default(),
+                response_time_ms: 1,
+                timestamp: chrono::Utc::now(),
+                success: !blocked,
+                error_message: blocked.then(|| "response blocked (SAFETY)".to_string()),
+                retry_after_ms: None,
+                failure: blocked.then_some(ApiFailure::ContentFiltered),
+                finish_reason: None,
+                model: None,
+                cached: false,
+            })
+        }
+
+        async fn health_check(&self, _provider: ProviderId) -> ProducerResult<bool> {
+            Ok(true)
+        }
+
+        fn estimate_cost(&self, _provider: ProviderId, _tokens: &shared::TokenUsage) -> f64 {
+            0.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_content_filtered_request_is_retried_with_reworded_prompt() {
+        ProcessId::init_producer(1);
+        let config = ExecutionConfig::from_args_and_env(None, "animals".to_string(), None, None, None)
+            .unwrap()
+            .with_filtered_prompt_rewording(true);
+        let producer = Producer::new(config, FilteringApiClient::default(), RecordingCommunicator::default());
+        let strategy = Some(RoutingStrategy::Backoff {
+            provider: shared::types::ProviderConfig::with_default_model(ProviderId::Gemini),
+        });
+
+        Producer::process_single_request(
+            &producer.api_client,
+            &producer.processor,
+            &producer.metrics,
+            &producer.prompt_handler,
+            &producer.communicator,
+            &producer.state,
+            &None,
+            1,
+            &strategy,
+            &None,
+            "List animals",
+            &producer.config,
+        )
+        .await
+        .unwrap();
+
+        let prompts = producer.api_client.prompts.lock().unwrap().clone();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].ends_with(&prompts[0]) && prompts[1].len() > prompts[0].len());
+
+        let metrics = producer.metrics.read().await.get_current_metrics();
+        assert_eq!(metrics.content_filtered, 1);
+        assert_eq!(metrics.responses_received, 2);
+        assert_eq!(metrics.unique_attributes, 2);
+    }
+}
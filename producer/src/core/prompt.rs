@@ -2,9 +2,74 @@
 
 use crate::core::processor::Processor;
 use crate::types::ProducerState;
+use serde::Deserialize;
 use shared::{GenerationConfig, ProviderId, process_debug, ProcessId};
 use std::collections::HashMap;
 
+/// Default number of few-shot examples shown per prompt
+pub const DEFAULT_FEW_SHOT_COUNT: usize = 5;
+
+/// Estimated tokens the recently-seen hint may add to a prompt
+pub const SEEN_HINT_TOKEN_BUDGET: u32 = 500;
+
+/// Few-shot example attributes injected into prompts
+///
+/// Lookup prefers provider-specific examples, then topic-specific ones, then
+/// the general list. Provider keys use their CLI names (e.g. "openai").
+#[derive(Debug, Clone, Deserialize)]
+pub struct FewShotConfig {
+    /// Maximum number of examples shown per prompt
+    #[serde(default = "default_few_shot_count")]
+    pub count: usize,
+    /// Examples used when no topic or provider specific list matches
+    #[serde(default)]
+    pub examples: Vec<String>,
+    /// Examples keyed by topic
+    #[serde(default)]
+    pub topics: HashMap<String, Vec<String>>,
+    /// Examples keyed by provider name
+    #[serde(default)]
+    pub providers: HashMap<String, Vec<String>>,
+}
+
+fn default_few_shot_count() -> usize {
+    DEFAULT_FEW_SHOT_COUNT
+}
+
+impl Default for FewShotConfig {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_FEW_SHOT_COUNT,
+            examples: Vec::new(),
+            topics: HashMap::new(),
+            providers: HashMap::new(),
+        }
+    }
+}
+
+impl FewShotConfig {
+    /// Examples to show for a topic/provider pair, limited to `count`
+    pub fn examples_for(&self, topic: &str, provider: ProviderId) -> Vec<String> {
+        self.providers
+            .get(&provider.to_string())
+            .or_else(|| self.topics.get(topic))
+            .unwrap_or(&self.examples)
+            .iter()
+            .take(self.count)
+            .cloned()
+            .collect()
+    }
+
+    /// Every example that may be shown, so dedup can treat them as seen
+    pub fn all_examples(&self) -> Vec<String> {
+        std::iter::once(&self.examples)
+            .chain(self.topics.values())
+            .chain(self.providers.values())
+            .flat_map(|examples| examples.iter().take(self.count).cloned())
+            .collect()
+    }
+}
+
 /// Provider context window and token limits
 #[derive(Debug, Clone)]
 pub struct ProviderLimits {
@@ -23,6 +88,9 @@ pub struct PromptHandler {
 
     /// Minimum number of exclusions to include (if available)
     min_exclusions: usize,
+
+    /// Few-shot examples shown to the model
+    few_shot: FewShotConfig,
 }
 
 impl PromptHandler {
@@ -74,9 +142,16 @@ impl PromptHandler {
             provider_limits,
             max_exclusion_percentage: 0.3, // Use up to 30% of context window for exclusions
             min_exclusions: 10,            // Always include at least 10 exclusions if available
+            few_shot: FewShotConfig::default(),
         }
     }
 
+    /// Configure few-shot examples injected into prompts
+    pub fn with_few_shot(mut self, few_shot: FewShotConfig) -> Self {
+        self.few_shot = few_shot;
+        self
+    }
+
     /// Calculate optimal number of exclusions based on provider and generation config
     pub fn calculate_optimal_exclusions(
         &self,
@@ -122,19 +197,37 @@ impl PromptHandler {
         // Calculate optimal exclusion count for this provider/config
         let optimal_exclusions = self.calculate_optimal_exclusions(provider, generation_config);
 
-        // Get orchestrator seen values from state
-        let orchestrator_seen_values = {
+        // Get orchestrator seen values and topic from state
+        let (orchestrator_seen_values, topic) = {
             let state_guard = state.read().await;
-            state_guard.seen_values_from_orchestrator.clone()
+            (state_guard.seen_values_from_orchestrator.clone(), state_guard.config.topic.clone())
         };
 
         // Get seen values from orchestrator (authoritative source) and processor stats
-        let (combined_seen_values, processor_stats) = {
+        let (combined_seen_values, processor_stats, recent_values, dedup_enabled) = {
             let processor_guard = processor.read().await;
             let stats = processor_guard.get_stats();
-            (orchestrator_seen_values.unwrap_or(Vec::new()).clone(), stats)
+            let recent_values = match generation_config {
+                Some(gc) if gc.include_seen_hint => processor_guard.recent_values(gc.seen_hint_count),
+                _ => Vec::new(),
+            };
+            (orchestrator_seen_values.unwrap_or(Vec::new()).clone(), stats, recent_values, processor_guard.dedup_enabled())
         };
 
+        // Few-shot examples are already known to dedup, so they never count as generated
+        let examples = self.few_shot.examples_for(&topic, provider);
+        let examples_section = if examples.is_empty() {
+            String::new()
+        } else if dedup_enabled {
+            format!(
+                "Example entries (already known, do NOT repeat these):\n{}\n\n",
+                examples.join("\n")
+            )
+        } else {
+            format!("Example entries:\n{}\n\n", examples.join("\n"))
+        };
+        let seen_hint_section = self.seen_hint_section(&recent_values, provider);
+
         // Build exclusion list with optimal size
         let (existing_entries, bloom_info) = if combined_seen_values.is_empty() {
             ("None".to_string(), String::new())
@@ -164,8 +257,24 @@ impl PromptHandler {
             (format!("{}\n{}", recent_entries.join("\n"), exclusion_info), bloom_info)
         };
 
-        // Get request size from generation config
+        // Get request size and answer layout from generation config
         let request_size = generation_config.map(|gc| gc.request_size).unwrap_or(100);
+        let format_instruction = generation_config
+            .and_then(|gc| gc.extraction_format)
+            .map_or("One entry per line", |format| format.prompt_instruction());
+
+        // Without dedup repeats are kept, so the model isn't asked to avoid earlier entries
+        let (seen_hint_section, previous_section, reminders) = if dedup_enabled {
+            (
+                seen_hint_section,
+                format!("Previous entries:\n{existing_entries}{bloom_info}\n"),
+                format!(
+                    "- Your entries should be entirely unique from the previous\n- Entries should be specific\n- {format_instruction}\n- Do NOT repeat any previously seen entries, even with slight variations"
+                ),
+            )
+        } else {
+            (String::new(), String::new(), format!("- Entries should be specific\n- {format_instruction}"))
+        };
 
         // Debug log the prompt components
         process_debug!(ProcessId::current(), "🎯 Prompt building - base_prompt: '{}'", base_prompt);
@@ -178,18 +287,13 @@ impl PromptHandler {
 CRITICAL FORMATTING REQUIREMENTS:
 - Words must be strictly alphanumeric (letters and numbers only)
 - Words must be lowercase
-- One entry per line
+- {format_instruction}
 - No punctuation, spaces, or special characters
 - Examples: "parismuseum", "tokyotower", "londonbridge"
 
 Only generate canonical names, in English when available. Omit any descriptions of the entries.
-Previous entries:
-{existing_entries}{bloom_info}
-Remember:
-- Your entries should be entirely unique from the previous
-- Entries should be specific
-- One entry per line
-- Do NOT repeat any previously seen entries, even with slight variations"#
+{examples_section}{seen_hint_section}{previous_section}Remember:
+{reminders}"#
         );
 
         // Debug log the final prompt (truncated for readability)
@@ -203,6 +307,28 @@ Remember:
         enhanced_prompt
     }
 
+    /// "Avoid these" list of recently generated values, cut off at the seen-hint token budget
+    fn seen_hint_section(&self, recent_values: &[String], provider: ProviderId) -> String {
+        let mut tokens = 0;
+        let hinted: Vec<&str> = recent_values
+            .iter()
+            .take_while(|value| {
+                tokens += self.estimate_tokens(value, provider).max(1);
+                tokens <= SEEN_HINT_TOKEN_BUDGET
+            })
+            .map(String::as_str)
+            .collect();
+
+        if hinted.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "Recently generated (avoid these and close variants):\n{}\n\n",
+                hinted.join("\n")
+            )
+        }
+    }
+
     /// Update provider limits (for configuration changes)
     pub fn update_provider_limits(&mut self, provider: ProviderId, limits: ProviderLimits) {
         self.provider_limits.insert(provider, limits);
@@ -241,6 +367,9 @@ impl Default for PromptHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{ApiResponse, ProducerConfig, ProducerState};
+    use shared::ExtractionFormat;
+    use tokio::sync::RwLock;
 
     #[test]
     fn test_exclusion_calculation() {
@@ -254,6 +383,12 @@ mod tests {
             max_tokens: 1000,
             temperature: 0.7,
             request_size: 80,
+            extraction_format: None,
+            system_prompt: None,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 0,
         };
 
         let high_output_config = GenerationConfig {
@@ -263,6 +398,12 @@ mod tests {
             max_tokens: 10000,
             temperature: 0.7,
             request_size: 120,
+            extraction_format: None,
+            system_prompt: None,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 0,
         };
 
         let low_exclusions = handler.calculate_optimal_exclusions(ProviderId::OpenAI, Some(&low_output_config));
@@ -285,6 +426,12 @@ mod tests {
             max_tokens: 1000,
             temperature: 0.7,
             request_size: 75,
+            extraction_format: None,
+            system_prompt: None,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 0,
         };
 
         let openai_exclusions = handler.calculate_optimal_exclusions(ProviderId::OpenAI, Some(&config));
@@ -306,4 +453,195 @@ mod tests {
         assert!(estimated_tokens >= word_count); // At least one token per word
         assert!(estimated_tokens <= word_count * 2); // But not too many more
     }
+
+    #[tokio::test]
+    async fn test_few_shot_examples_in_prompt_but_not_output() {
+        ProcessId::init_producer(1);
+
+        let few_shot = FewShotConfig {
+            count: 2,
+            examples: vec!["Red Panda".to_string(), "axolotl".to_string(), "narwhal".to_string()],
+            ..FewShotConfig::default()
+        };
+        let handler = PromptHandler::new().with_few_shot(few_shot.clone());
+
+        let state = RwLock::new(ProducerState::new(ProducerConfig::new(
+            "127.0.0.1:6001".parse().unwrap(),
+            "animals".to_string(),
+        )));
+        let mut processor = Processor::new();
+        processor.reserve_values(&few_shot.all_examples());
+        let processor = RwLock::new(processor);
+
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::Random, None, &state, &processor)
+            .await;
+        assert!(prompt.contains("Red Panda"));
+        assert!(prompt.contains("axolotl"));
+        assert!(!prompt.contains("narwhal"), "count limits the examples shown");
+
+        let response = ApiResponse {
+            provider: ProviderId::Random,
+            request_id: uuid::Uuid::new_v4(),
+            content: "red panda\naxolotl\nquokka".to_string(),
+            tokens_used: shared::TokenUsage::default(),
+            response_time_ms: 10,
+            timestamp: chrono::Utc::now(),
+            success: true,
+            error_message: None,
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
+        };
+        let stats = processor.write().await.process_response(response).unwrap();
+        assert_eq!(stats.new_values, vec!["quokka".to_string()]);
+        assert_eq!(processor.read().await.get_stats().total_unique_attributes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_prompt_only_forbids_repeats_when_dedup_is_enabled() {
+        ProcessId::init_producer(1);
+
+        let handler = PromptHandler::new();
+        let state = RwLock::new(ProducerState::new(ProducerConfig::new(
+            "127.0.0.1:6001".parse().unwrap(),
+            "animals".to_string(),
+        )));
+        state.write().await.seen_values_from_orchestrator = Some(vec!["aardvark".to_string()]);
+        let processor = RwLock::new(Processor::new());
+
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::Random, None, &state, &processor)
+            .await;
+        assert!(prompt.contains("Do NOT repeat"));
+        assert!(prompt.contains("Previous entries:\naardvark"));
+
+        processor.write().await.set_dedup_enabled(false);
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::Random, None, &state, &processor)
+            .await;
+        assert!(!prompt.contains("Do NOT repeat"));
+        assert!(!prompt.contains("aardvark"));
+        assert!(prompt.ends_with("Remember:\n- Entries should be specific\n- One entry per line"));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_requests_configured_extraction_format() {
+        ProcessId::init_producer(1);
+
+        let handler = PromptHandler::new();
+        let state = RwLock::new(ProducerState::new(ProducerConfig::new(
+            "127.0.0.1:6001".parse().unwrap(),
+            "animals".to_string(),
+        )));
+        let processor = RwLock::new(Processor::new());
+        let config = GenerationConfig {
+            model: "gpt-4o-mini".to_string(),
+            batch_size: 1,
+            context_window: 4096,
+            max_tokens: 1000,
+            temperature: 0.7,
+            request_size: 20,
+            extraction_format: Some(ExtractionFormat::JsonArray),
+            system_prompt: None,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 0,
+        };
+
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::OpenAI, Some(&config), &state, &processor)
+            .await;
+        assert!(prompt.contains(ExtractionFormat::JsonArray.prompt_instruction()));
+        assert!(!prompt.contains("One entry per line"));
+
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::OpenAI, None, &state, &processor)
+            .await;
+        assert!(prompt.contains("One entry per line"));
+    }
+
+    #[tokio::test]
+    async fn test_seen_hint_lists_recent_values_when_enabled() {
+        ProcessId::init_producer(1);
+
+        let handler = PromptHandler::new();
+        let state = RwLock::new(ProducerState::new(ProducerConfig::new(
+            "127.0.0.1:6001".parse().unwrap(),
+            "animals".to_string(),
+        )));
+        let processor = RwLock::new(Processor::new());
+        let response = ApiResponse {
+            provider: ProviderId::Random,
+            request_id: uuid::Uuid::new_v4(),
+            content: "aardvark\nbison\ncapybara\ndingo".to_string(),
+            tokens_used: shared::TokenUsage::default(),
+            response_time_ms: 10,
+            timestamp: chrono::Utc::now(),
+            success: true,
+            error_message: None,
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
+        };
+        processor.write().await.process_response(response).unwrap();
+
+        let mut config = GenerationConfig {
+            model: "random".to_string(),
+            batch_size: 1,
+            context_window: 4096,
+            max_tokens: 1000,
+            temperature: 0.7,
+            request_size: 20,
+            extraction_format: None,
+            system_prompt: None,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 2,
+        };
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::Random, Some(&config), &state, &processor)
+            .await;
+        assert!(!prompt.contains("Recently generated"));
+
+        // Only the newest values are sampled
+        config.include_seen_hint = true;
+        let prompt = handler
+            .build_enhanced_prompt("animals", ProviderId::Random, Some(&config), &state, &processor)
+            .await;
+        assert!(prompt.contains("Recently generated (avoid these and close variants):\ndingo\ncapybara\n"));
+        assert!(!prompt.contains("bison"));
+    }
+
+    #[test]
+    fn test_seen_hint_is_capped_by_token_budget() {
+        let handler = PromptHandler::new();
+        let values: Vec<String> = (0..2000).map(|i| format!("value{i}")).collect();
+
+        let section = handler.seen_hint_section(&values, ProviderId::Random);
+        let listed = section.lines().skip(1).filter(|line| !line.is_empty()).count();
+        assert_eq!(listed, SEEN_HINT_TOKEN_BUDGET as usize);
+        assert_eq!(handler.seen_hint_section(&[], ProviderId::Random), "");
+    }
+
+    #[test]
+    fn test_few_shot_lookup_precedence() {
+        let mut few_shot = FewShotConfig {
+            examples: vec!["general".to_string()],
+            ..FewShotConfig::default()
+        };
+        few_shot.topics.insert("animals".to_string(), vec!["topical".to_string()]);
+        few_shot.providers.insert("openai".to_string(), vec!["specific".to_string()]);
+
+        assert_eq!(few_shot.examples_for("animals", ProviderId::OpenAI), vec!["specific"]);
+        assert_eq!(few_shot.examples_for("animals", ProviderId::Gemini), vec!["topical"]);
+        assert_eq!(few_shot.examples_for("cities", ProviderId::Gemini), vec!["general"]);
+        assert_eq!(few_shot.all_examples().len(), 3);
+    }
 }
@@ -0,0 +1,124 @@
+//! Client-side per-provider rate limiting
+//!
+//! Each limited provider gets a token bucket holding up to a minute's worth of
+//! requests that refills continuously at its requests-per-minute rate. A request
+//! is only sent when it can take a whole token, so the producer stays under
+//! provider caps instead of discovering them through 429 responses.
+
+use shared::ProviderId;
+use std::collections::HashMap;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_minute: u32, now: Instant) -> Self {
+        let capacity = f64::from(requests_per_minute.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = self.last_refill.max(now);
+    }
+}
+
+/// Token buckets for every provider with a requests-per-minute cap
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    buckets: HashMap<ProviderId, TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Start every listed provider with a full bucket; unlisted providers are unlimited
+    pub fn new(requests_per_minute: &HashMap<ProviderId, u32>, now: Instant) -> Self {
+        Self {
+            buckets: requests_per_minute
+                .iter()
+                .map(|(provider, rpm)| (*provider, TokenBucket::new(*rpm, now)))
+                .collect(),
+        }
+    }
+
+    /// Whether a request to the provider would exceed its limit right now
+    pub fn is_limited(&mut self, provider: ProviderId, now: Instant) -> bool {
+        match self.buckets.get_mut(&provider) {
+            Some(bucket) => {
+                bucket.refill(now);
+                bucket.tokens < 1.0
+            }
+            None => false,
+        }
+    }
+
+    /// Providers that currently have no request to spare, in a stable order
+    pub fn limited_providers(&mut self, now: Instant) -> Vec<ProviderId> {
+        let mut providers: Vec<ProviderId> = self.buckets.keys().copied().collect();
+        providers.retain(|provider| self.is_limited(*provider, now));
+        providers.sort();
+        providers
+    }
+
+    /// Take one request from the provider's bucket, returning false if it is empty
+    pub fn try_acquire(&mut self, provider: ProviderId, now: Instant) -> bool {
+        if self.is_limited(provider, now) {
+            return false;
+        }
+        if let Some(bucket) = self.buckets.get_mut(&provider) {
+            bucket.tokens -= 1.0;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_drains_and_refills_at_rate() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(&HashMap::from([(ProviderId::OpenAI, 2)]), start);
+
+        assert!(limiter.try_acquire(ProviderId::OpenAI, start));
+        assert!(limiter.try_acquire(ProviderId::OpenAI, start));
+        assert!(!limiter.try_acquire(ProviderId::OpenAI, start));
+        assert_eq!(limiter.limited_providers(start), vec![ProviderId::OpenAI]);
+
+        // Two requests per minute refill one token every 30 seconds
+        assert!(limiter.is_limited(ProviderId::OpenAI, start + Duration::from_secs(29)));
+        assert!(limiter.try_acquire(ProviderId::OpenAI, start + Duration::from_secs(30)));
+        assert!(!limiter.try_acquire(ProviderId::OpenAI, start + Duration::from_secs(30)));
+
+        // Refill never exceeds a minute's worth of requests
+        let later = start + Duration::from_secs(600);
+        assert!(limiter.try_acquire(ProviderId::OpenAI, later));
+        assert!(limiter.try_acquire(ProviderId::OpenAI, later));
+        assert!(!limiter.try_acquire(ProviderId::OpenAI, later));
+    }
+
+    #[test]
+    fn test_unlisted_providers_are_unlimited() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::new(&HashMap::from([(ProviderId::Anthropic, 1)]), now);
+
+        assert!(limiter.try_acquire(ProviderId::Anthropic, now));
+        for _ in 0..100 {
+            assert!(limiter.try_acquire(ProviderId::Random, now));
+        }
+        assert_eq!(limiter.limited_providers(now), vec![ProviderId::Anthropic]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Request-size auto-tuning driven by the observed uniqueness ratio
+//!
+//! Large requests are cheap per item while the model keeps finding new values,
+//! but once most of a response is duplicates the extra items are wasted tokens.
+//! After every window of processed responses the tuner shrinks the request size
+//! when uniqueness falls and grows it back when uniqueness recovers, always
+//! staying within the configured bounds.
+
+/// Tuning parameters for the request-size controller
+#[derive(Debug, Clone)]
+pub struct RequestSizeConfig {
+    /// Smallest request size the tuner will shrink to
+    pub min_size: usize,
+    /// Largest request size the tuner will grow to
+    pub max_size: usize,
+    /// Uniqueness ratio below which the request size shrinks
+    pub shrink_below: f64,
+    /// Uniqueness ratio above which the request size grows
+    pub grow_above: f64,
+    /// Multiplier applied to the request size when shrinking
+    pub shrink_factor: f64,
+    /// Multiplier applied to the request size when growing
+    pub grow_factor: f64,
+    /// Number of processed responses evaluated per adjustment
+    pub window: usize,
+}
+
+impl Default for RequestSizeConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 10,
+            max_size: 200,
+            shrink_below: 0.3,
+            grow_above: 0.7,
+            shrink_factor: 0.75,
+            grow_factor: 1.25,
+            window: 5,
+        }
+    }
+}
+
+/// A change the tuner made to the effective request size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestSizeChange {
+    pub previous: usize,
+    pub current: usize,
+    pub uniqueness_ratio: f64,
+}
+
+/// Bounded controller for the number of items asked for per request
+#[derive(Debug, Clone)]
+pub struct RequestSizeTuner {
+    config: RequestSizeConfig,
+    /// Tuned size, or None until the first adjustment since the last reset
+    size: Option<usize>,
+    responses: usize,
+}
+
+impl RequestSizeTuner {
+    pub fn new(config: RequestSizeConfig) -> Self {
+        let max_size = config.max_size.max(config.min_size).max(1);
+        Self {
+            config: RequestSizeConfig { max_size, ..config },
+            size: None,
+            responses: 0,
+        }
+    }
+
+    /// Number of recent responses the uniqueness ratio should cover
+    pub fn window(&self) -> usize {
+        self.config.window.max(1)
+    }
+
+    /// Request size to use given the size the orchestrator configured
+    pub fn request_size(&self, configured: usize) -> usize {
+        self.size
+            .unwrap_or_else(|| configured.clamp(self.config.min_size.max(1), self.config.max_size))
+    }
+
+    /// Forget any tuned size so a new generation config starts from its own request size
+    pub fn reset(&mut self) {
+        self.size = None;
+        self.responses = 0;
+    }
+
+    /// Count one processed response, adjusting the size when a window completes
+    ///
+    /// `uniqueness_ratio` is the share of new values across the last `window()`
+    /// responses; None (nothing extracted yet) leaves the size unchanged.
+    pub fn record(&mut self, configured: usize, uniqueness_ratio: Option<f64>) -> Option<RequestSizeChange> {
+        self.responses += 1;
+        if self.responses < self.window() {
+            return None;
+        }
+        self.responses = 0;
+
+        let ratio = uniqueness_ratio?;
+        let previous = self.request_size(configured);
+        let scaled = if ratio < self.config.shrink_below {
+            (previous as f64 * self.config.shrink_factor).floor() as usize
+        } else if ratio > self.config.grow_above {
+            // Always grow by at least one so small sizes can recover
+            ((previous as f64 * self.config.grow_factor).ceil() as usize).max(previous + 1)
+        } else {
+            previous
+        };
+        let current = scaled.clamp(self.config.min_size.max(1), self.config.max_size);
+        self.size = Some(current);
+
+        (current != previous).then_some(RequestSizeChange {
+            previous,
+            current,
+            uniqueness_ratio: ratio,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuner() -> RequestSizeTuner {
+        RequestSizeTuner::new(RequestSizeConfig {
+            min_size: 20,
+            max_size: 100,
+            window: 2,
+            ..RequestSizeConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_size_only_changes_at_window_boundaries_and_respects_bounds() {
+        let mut tuner = tuner();
+        assert_eq!(tuner.request_size(60), 60);
+        assert_eq!(tuner.request_size(500), 100);
+
+        assert_eq!(tuner.record(60, Some(0.1)), None);
+        let change = tuner.record(60, Some(0.1)).unwrap();
+        assert_eq!((change.previous, change.current), (60, 45));
+
+        for _ in 0..10 {
+            tuner.record(60, Some(0.0));
+        }
+        assert_eq!(tuner.request_size(60), 20);
+
+        for _ in 0..20 {
+            tuner.record(60, Some(1.0));
+        }
+        assert_eq!(tuner.request_size(60), 100);
+    }
+
+    #[test]
+    fn test_steady_uniqueness_and_missing_ratio_keep_size() {
+        let mut tuner = tuner();
+        tuner.record(60, Some(0.5));
+        assert_eq!(tuner.record(60, Some(0.5)), None);
+        tuner.record(60, None);
+        assert_eq!(tuner.record(60, None), None);
+        assert_eq!(tuner.request_size(60), 60);
+
+        tuner.record(60, Some(0.0));
+        tuner.record(60, Some(0.0));
+        tuner.reset();
+        assert_eq!(tuner.request_size(80), 80);
+    }
+}
@@ -1,10 +1,12 @@
 //! Pure utility functions for producer operations
 
 use chrono::Utc;
-use std::collections::HashMap;
-use std::time::Duration;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use crate::core::circuit_breaker::{BreakerState, CircuitState};
 use crate::types::{ApiRequest, ApiResponse};
 use shared::types::{GenerationConfig, RoutingStrategy, ProviderConfig};
 use shared::ProviderId;
@@ -18,74 +20,259 @@ pub fn load_routing_strategy() -> RoutingStrategy {
     })
 }
 
+/// Providers a routing strategy may select (pure function)
+pub fn strategy_providers(routing_strategy: &Option<RoutingStrategy>) -> Vec<ProviderId> {
+    let mut providers: Vec<ProviderId> = match routing_strategy {
+        Some(RoutingStrategy::RoundRobin { providers })
+        | Some(RoutingStrategy::PriorityOrder { providers })
+        | Some(RoutingStrategy::CircuitBreaker { providers, .. })
+        | Some(RoutingStrategy::LowestLatency { providers, .. }) => {
+            providers.iter().map(|config| config.provider).collect()
+        }
+        Some(RoutingStrategy::Weighted { weights }) => weights.keys().map(|config| config.provider).collect(),
+        Some(RoutingStrategy::Backoff { provider }) => vec![provider.provider],
+        None => vec![ProviderId::Random],
+    };
+    providers.sort();
+    providers.dedup();
+    providers
+}
+
 /// Select provider based on routing strategy (pure function)
+///
+/// Without a rotation cursor to carry over, round-robin starts from the first provider.
 pub fn select_provider(routing_strategy: &Option<RoutingStrategy>, fallback: ProviderId) -> ProviderId {
-    match routing_strategy {
-        Some(RoutingStrategy::RoundRobin { providers }) if !providers.is_empty() => {
-            let index = (Utc::now().timestamp_millis() / 1000) as usize % providers.len();
-            providers[index].provider
-        }
-        Some(RoutingStrategy::Backoff { provider }) => provider.provider,
-        Some(RoutingStrategy::PriorityOrder { providers }) => providers.first().map(|p| p.provider).unwrap_or(fallback),
-        Some(RoutingStrategy::Weighted { weights }) => select_weighted_provider_config(weights).map(|pc| pc.provider).unwrap_or(fallback),
-        Some(RoutingStrategy::RoundRobin { .. }) => fallback, // Empty providers case
-        None => fallback,
-    }
+    select_provider_config(routing_strategy, ProviderConfig::with_default_model(fallback)).provider
 }
 
 /// Select provider config based on routing strategy (pure function)
 pub fn select_provider_config(routing_strategy: &Option<RoutingStrategy>, fallback: ProviderConfig) -> ProviderConfig {
+    select_provider_config_with_rng(routing_strategy, fallback, &mut rand::thread_rng(), &mut 0)
+}
+
+/// Next provider in rotation, advancing `cursor` by one
+fn rotate<'a, T>(items: &'a [T], cursor: &mut usize) -> &'a T {
+    let item = &items[*cursor % items.len()];
+    *cursor = cursor.wrapping_add(1);
+    item
+}
+
+/// Select provider config drawing every randomized choice from `rng`
+///
+/// Round-robin style choices take turns by advancing `cursor` once per call, so the
+/// caller keeps it between requests. With a seeded `rng` the sequence of routing
+/// decisions is reproducible.
+pub fn select_provider_config_with_rng<R: Rng>(
+    routing_strategy: &Option<RoutingStrategy>,
+    fallback: ProviderConfig,
+    rng: &mut R,
+    cursor: &mut usize,
+) -> ProviderConfig {
     match routing_strategy {
-        Some(RoutingStrategy::RoundRobin { providers }) if !providers.is_empty() => {
-            let index = (Utc::now().timestamp_millis() / 1000) as usize % providers.len();
-            providers[index].clone()
+        Some(RoutingStrategy::RoundRobin { providers }) | Some(RoutingStrategy::CircuitBreaker { providers, .. })
+            if !providers.is_empty() =>
+        {
+            rotate(providers, cursor).clone()
         }
         Some(RoutingStrategy::Backoff { provider }) => provider.clone(),
         Some(RoutingStrategy::PriorityOrder { providers }) => providers.first().cloned().unwrap_or(fallback),
-        Some(RoutingStrategy::Weighted { weights }) => select_weighted_provider_config(weights).unwrap_or(fallback),
-        Some(RoutingStrategy::RoundRobin { .. }) => fallback, // Empty providers case
+        Some(RoutingStrategy::Weighted { weights }) => {
+            pick_weighted(weights, weighted_roll(weights, rng)).unwrap_or(fallback)
+        }
+        // Without latency data every provider is equally untried
+        Some(RoutingStrategy::LowestLatency { providers, .. }) => {
+            select_lowest_latency_config(providers, &HashMap::new(), fallback, cursor)
+        }
+        Some(RoutingStrategy::RoundRobin { .. }) | Some(RoutingStrategy::CircuitBreaker { .. }) => fallback, // Empty providers case
         None => fallback,
     }
 }
 
-/// Select provider based on weights (pure function) - legacy support
-pub fn select_weighted_provider(weights: &HashMap<ProviderId, f32>) -> Option<ProviderId> {
-    let total_weight: f32 = weights.values().sum();
-    if total_weight == 0.0 {
-        return None;
+/// Select provider config, skipping providers whose circuit is open
+///
+/// Strategies other than CircuitBreaker ignore the circuits. A half-open circuit
+/// gets its single probe before load spreads over the closed ones; returns None
+/// when every circuit is open.
+pub fn select_provider_config_with_circuits<R: Rng>(
+    routing_strategy: &Option<RoutingStrategy>,
+    fallback: ProviderConfig,
+    rng: &mut R,
+    cursor: &mut usize,
+    circuits: &mut CircuitState,
+    now: Instant,
+) -> Option<ProviderConfig> {
+    let providers = match routing_strategy {
+        Some(RoutingStrategy::CircuitBreaker { providers, .. }) if !providers.is_empty() => providers,
+        _ => return Some(select_provider_config_with_rng(routing_strategy, fallback, rng, cursor)),
+    };
+
+    let probe = providers.iter().find(|config| {
+        circuits.state(config.provider, now) == BreakerState::HalfOpen && circuits.is_available(config.provider, now)
+    });
+    let selected = match probe {
+        Some(config) => config.clone(),
+        None => {
+            let closed: Vec<&ProviderConfig> = providers
+                .iter()
+                .filter(|config| circuits.state(config.provider, now) == BreakerState::Closed)
+                .collect();
+            if closed.is_empty() {
+                return None;
+            }
+            (*rotate(&closed, cursor)).clone()
+        }
+    };
+    circuits.begin_request(selected.provider);
+    Some(selected)
+}
+
+/// Pick the provider with the lowest rolling latency (pure function)
+///
+/// Providers without recent latency data are tried first, taking turns the way
+/// round-robin does, so every provider gets measured.
+pub fn select_lowest_latency_config(
+    providers: &[ProviderConfig],
+    latencies: &HashMap<ProviderId, f64>,
+    fallback: ProviderConfig,
+    cursor: &mut usize,
+) -> ProviderConfig {
+    let untried: Vec<&ProviderConfig> = providers
+        .iter()
+        .filter(|config| !latencies.contains_key(&config.provider))
+        .collect();
+    if !untried.is_empty() {
+        return (*rotate(&untried, cursor)).clone();
     }
 
-    let random_value = (Utc::now().timestamp_millis() % 1000) as f32;
-    let target = random_value % total_weight;
+    providers
+        .iter()
+        .min_by(|a, b| latencies[&a.provider].total_cmp(&latencies[&b.provider]))
+        .cloned()
+        .unwrap_or(fallback)
+}
 
-    let mut accumulated = 0.0;
-    for (provider, weight) in weights {
-        accumulated += weight;
-        if target < accumulated {
-            return Some(*provider);
-        }
+/// Providers whose token budget is used up (pure function)
+pub fn exhausted_providers(budgets: &HashMap<ProviderId, u64>, tokens_used: impl Fn(ProviderId) -> u64) -> Vec<ProviderId> {
+    let mut exhausted: Vec<ProviderId> = budgets
+        .iter()
+        .filter(|(provider, budget)| tokens_used(**provider) >= **budget)
+        .map(|(provider, _)| *provider)
+        .collect();
+    exhausted.sort();
+    exhausted
+}
+
+/// Routing strategy with the excluded providers removed (pure function)
+///
+/// No strategy means backoff to Random. Returns None when no provider is left to
+/// route to.
+pub fn route_around_providers(
+    routing_strategy: &Option<RoutingStrategy>,
+    excluded: &[ProviderId],
+) -> Option<RoutingStrategy> {
+    let strategy = routing_strategy
+        .clone()
+        .unwrap_or_else(|| RoutingStrategy::Backoff { provider: ProviderConfig::with_default_model(ProviderId::Random) });
+    if excluded.is_empty() {
+        return Some(strategy);
     }
-    None
+
+    let keep = |config: &ProviderConfig| !excluded.contains(&config.provider);
+    let routed = match strategy {
+        RoutingStrategy::RoundRobin { providers } => RoutingStrategy::RoundRobin {
+            providers: providers.into_iter().filter(keep).collect(),
+        },
+        RoutingStrategy::PriorityOrder { providers } => RoutingStrategy::PriorityOrder {
+            providers: providers.into_iter().filter(keep).collect(),
+        },
+        RoutingStrategy::Weighted { weights } => RoutingStrategy::Weighted {
+            weights: weights.into_iter().filter(|(config, _)| keep(config)).collect(),
+        },
+        RoutingStrategy::Backoff { provider } => RoutingStrategy::Backoff { provider },
+        RoutingStrategy::CircuitBreaker {
+            providers,
+            failure_threshold,
+            cooldown_seconds,
+        } => RoutingStrategy::CircuitBreaker {
+            providers: providers.into_iter().filter(keep).collect(),
+            failure_threshold,
+            cooldown_seconds,
+        },
+        RoutingStrategy::LowestLatency { providers, window_seconds } => RoutingStrategy::LowestLatency {
+            providers: providers.into_iter().filter(keep).collect(),
+            window_seconds,
+        },
+    };
+
+    let routed = Some(routed);
+    let remaining = strategy_providers(&routed);
+    if remaining.is_empty() || remaining.iter().all(|provider| excluded.contains(provider)) {
+        return None;
+    }
+    routed
+}
+
+/// Failure threshold and cooldown of a CircuitBreaker strategy (pure function)
+pub fn circuit_settings(routing_strategy: &Option<RoutingStrategy>) -> Option<(u32, Duration)> {
+    match routing_strategy {
+        Some(RoutingStrategy::CircuitBreaker {
+            failure_threshold,
+            cooldown_seconds,
+            ..
+        }) => Some((*failure_threshold, Duration::from_secs(*cooldown_seconds))),
+        _ => None,
+    }
+}
+
+/// Select provider based on weights (pure function) - legacy support
+pub fn select_weighted_provider(weights: &HashMap<ProviderId, f32>) -> Option<ProviderId> {
+    pick_weighted(weights, weighted_roll(weights, &mut rand::thread_rng()))
 }
 
 /// Select provider config based on weights (pure function)
 pub fn select_weighted_provider_config(weights: &HashMap<ProviderConfig, f32>) -> Option<ProviderConfig> {
-    let total_weight: f32 = weights.values().sum();
-    if total_weight == 0.0 {
-        return None;
+    pick_weighted(weights, weighted_roll(weights, &mut rand::thread_rng()))
+}
+
+/// Roll used for weighted selection, uniform over the total weight so picks are proportional
+fn weighted_roll<K, R: Rng>(weights: &HashMap<K, f32>, rng: &mut R) -> f32 {
+    let total: f32 = weights.values().filter(|weight| weight.is_finite() && **weight > 0.0).sum();
+    if total > 0.0 {
+        rng.gen_range(0.0..total)
+    } else {
+        0.0
     }
+}
 
-    let random_value = (Utc::now().timestamp_millis() % 1000) as f32;
-    let target = random_value % total_weight;
+/// Pick a weighted entry for the given roll (pure function)
+///
+/// Entries are walked in key order rather than hash order so equal weights always
+/// resolve the same way. NaN, infinite and negative weights count as zero.
+/// A non-finite roll selects the first bucket.
+pub fn pick_weighted<K: Ord + Clone>(weights: &HashMap<K, f32>, roll: f32) -> Option<K> {
+    let mut entries: Vec<(&K, f32)> = weights
+        .iter()
+        .map(|(key, &weight)| (key, if weight.is_finite() && weight > 0.0 { weight } else { 0.0 }))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
 
+    let total_weight: f32 = entries.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    let roll = if roll.is_finite() { roll.abs() } else { 0.0 };
+    let target = roll % total_weight;
     let mut accumulated = 0.0;
-    for (provider_config, weight) in weights {
+    for (key, weight) in &entries {
         accumulated += weight;
         if target < accumulated {
-            return Some(provider_config.clone());
+            return Some((*key).clone());
         }
     }
-    None
+
+    // Float rounding can leave target just past the last bucket
+    entries.iter().rev().find(|(_, weight)| *weight > 0.0).map(|(key, _)| (*key).clone())
 }
 
 /// Build API request from provider config and generation config (pure function)
@@ -99,10 +286,13 @@ pub fn build_api_request_with_config(
         .as_ref()
         .map(|gc| (gc.max_tokens, gc.temperature))
         .unwrap_or((150, 0.7));
+    let system_prompt = generation_config.as_ref().and_then(|gc| gc.system_prompt.clone());
 
     ApiRequest {
         provider: provider_config.provider,
         prompt: enhanced_prompt,
+        system_prompt,
+        model: None,
         max_tokens,
         temperature,
         request_id,
@@ -123,10 +313,13 @@ pub fn build_api_request(
         .as_ref()
         .map(|gc| (gc.max_tokens, gc.temperature))
         .unwrap_or((150, 0.7));
+    let system_prompt = generation_config.as_ref().and_then(|gc| gc.system_prompt.clone());
 
     ApiRequest {
         provider,
         prompt: enhanced_prompt,
+        system_prompt,
+        model: None,
         max_tokens,
         temperature,
         request_id,
@@ -134,22 +327,117 @@ pub fn build_api_request(
     }
 }
 
+/// Pick each accepted attribute for re-verification with probability `sample_rate` (pure function)
+pub fn sample_for_verification<R: Rng>(attributes: &[String], sample_rate: f32, rng: &mut R) -> Vec<String> {
+    if sample_rate.is_nan() || sample_rate <= 0.0 {
+        return Vec::new();
+    }
+    attributes
+        .iter()
+        .filter(|_| rng.gen_range(0.0..1.0) < sample_rate)
+        .cloned()
+        .collect()
+}
+
+/// Ask the verification provider to name any sampled attributes that do not belong (pure function)
+pub fn build_verification_prompt(topic: &str, attributes: &[String]) -> String {
+    let listed: Vec<String> = attributes.iter().map(|attribute| format!("- {attribute}")).collect();
+    format!(
+        "Topic: {topic}\n\
+         Review these proposed attributes of the topic. Reply with only the ones that are NOT valid, \
+         distinct attributes of the topic, one per line, exactly as written. Reply NONE if all are valid.\n\n{}",
+        listed.join("\n")
+    )
+}
+
+/// Count sampled attributes the verification reply rejected (pure function)
+pub fn count_verification_conflicts(attributes: &[String], reply: &str) -> u64 {
+    let rejected: HashSet<String> = reply
+        .split(['\n', ','])
+        .map(|item| item.trim().trim_start_matches(['-', '*']).trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect();
+    attributes
+        .iter()
+        .filter(|attribute| rejected.contains(&attribute.trim().to_lowercase()))
+        .count() as u64
+}
+
+/// Preamble added to a prompt whose response a provider's content filter blocked
+const FILTERED_PROMPT_PREAMBLE: &str =
+    "Keep every item factual, neutral and suitable for a general audience, leaving out anything sensitive.";
+
+/// Reword a request whose response was blocked by a content filter, for a single retry (pure function)
+pub fn reword_filtered_request(request: &ApiRequest) -> ApiRequest {
+    ApiRequest {
+        prompt: format!("{FILTERED_PROMPT_PREAMBLE}\n\n{}", request.prompt),
+        request_id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        ..request.clone()
+    }
+}
+
 /// Process API response and extract business logic (pure function)
-pub fn should_retry_request(response: &ApiResponse, attempt: u32, max_retries: u32) -> Option<Duration> {
+pub fn should_retry_request(
+    response: &ApiResponse,
+    attempt: u32,
+    max_retries: u32,
+    retry_base_ms: u64,
+    retry_max_ms: u64,
+) -> Option<Duration> {
     if attempt >= max_retries || response.success {
         return None;
     }
 
-    let error_msg = response.error_message.as_deref().unwrap_or("");
-    let is_retryable = error_msg.contains("rate limit") || error_msg.contains("timeout") || error_msg.contains("503");
+    let is_retryable = match response.failure {
+        Some(failure) => failure.kind().is_retryable(),
+        None => {
+            let error_msg = response.error_message.as_deref().unwrap_or("");
+            error_msg.contains("rate limit") || error_msg.contains("timeout") || error_msg.contains("503")
+        }
+    };
 
     if is_retryable {
-        Some(Duration::from_millis(100 * (1 << attempt))) // Exponential backoff
+        Some(match response.retry_after_ms {
+            Some(retry_after_ms) => Duration::from_millis(retry_after_ms),
+            None => jittered_backoff(attempt, retry_base_ms, retry_max_ms, &mut rand::thread_rng()),
+        })
     } else {
         None
     }
 }
 
+/// Phrases providers use when a prompt does not fit the model's context window
+const CONTEXT_ERROR_MARKERS: [&str; 5] = [
+    "context_length_exceeded",
+    "context length",
+    "context window",
+    "prompt is too long",
+    "too many tokens",
+];
+
+/// Whether a failed response should move on to a fallback model rather than retry the same one (pure function)
+///
+/// That is the case when the model is unavailable or the request overflowed its context window.
+pub fn needs_model_fallback(response: &ApiResponse) -> bool {
+    if response.success {
+        return false;
+    }
+    if response.failure == Some(shared::ApiFailure::ModelUnavailable) {
+        return true;
+    }
+    let error_msg = response.error_message.as_deref().unwrap_or("").to_lowercase();
+    CONTEXT_ERROR_MARKERS.iter().any(|marker| error_msg.contains(marker))
+}
+
+/// Full-jitter exponential backoff: uniform in `[0, min(retry_max_ms, retry_base_ms * 2^attempt)]`
+pub fn jittered_backoff<R: Rng>(attempt: u32, retry_base_ms: u64, retry_max_ms: u64, rng: &mut R) -> Duration {
+    let cap = retry_base_ms
+        .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+        .min(retry_max_ms);
+    Duration::from_millis(rng.gen_range(0..=cap))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,6 +445,8 @@ mod tests {
 
     #[test]
     fn test_select_provider_strategies() {
+        let fallback = || ProviderConfig::with_default_model(ProviderId::Random);
+
         // Test backoff strategy
         let backoff_strategy = Some(RoutingStrategy::Backoff {
             provider: ProviderConfig::with_default_model(ProviderId::Anthropic),
@@ -173,8 +463,12 @@ mod tests {
                 ProviderConfig::with_default_model(ProviderId::OpenAI),
             ],
         });
-        let selected = select_provider(&round_robin_strategy, ProviderId::Random);
-        assert!(selected == ProviderId::Gemini || selected == ProviderId::OpenAI);
+        let mut rng = rand::thread_rng();
+        let mut cursor = 0;
+        let picks: Vec<_> = (0..4)
+            .map(|_| select_provider_config_with_rng(&round_robin_strategy, fallback(), &mut rng, &mut cursor).provider)
+            .collect();
+        assert_eq!(picks, vec![ProviderId::Gemini, ProviderId::OpenAI, ProviderId::Gemini, ProviderId::OpenAI]);
 
         // Test no strategy (fallback)
         let no_strategy: Option<RoutingStrategy> = None;
@@ -192,17 +486,87 @@ mod tests {
             timestamp: Utc::now(),
             success: false,
             error_message: Some("rate limit exceeded".to_string()),
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
         };
 
         // Should retry on rate limit
-        assert!(should_retry_request(&response, 0, 3).is_some());
+        assert!(should_retry_request(&response, 0, 3, 100, 10_000).is_some());
 
         // Should not retry after max attempts
-        assert!(should_retry_request(&response, 3, 3).is_none());
+        assert!(should_retry_request(&response, 3, 3, 100, 10_000).is_none());
+
+        // A Retry-After hint replaces the computed backoff
+        response.retry_after_ms = Some(2_500);
+        assert_eq!(
+            should_retry_request(&response, 0, 3, 100, 10_000),
+            Some(Duration::from_millis(2_500))
+        );
+
+        // A classified failure decides over the message text
+        response.failure = Some(shared::ApiFailure::InvalidApiKey);
+        assert!(should_retry_request(&response, 0, 3, 100, 10_000).is_none());
+        response.failure = Some(shared::ApiFailure::ModelUnavailable);
+        response.error_message = Some("HTTP 529".to_string());
+        assert!(should_retry_request(&response, 0, 3, 100, 10_000).is_some());
 
         // Should not retry on success
         response.success = true;
-        assert!(should_retry_request(&response, 0, 3).is_none());
+        assert!(should_retry_request(&response, 0, 3, 100, 10_000).is_none());
+    }
+
+    #[test]
+    fn test_needs_model_fallback_on_unavailable_model_or_context_overflow() {
+        let mut response = ApiResponse {
+            provider: ProviderId::OpenAI,
+            request_id: Uuid::new_v4(),
+            content: "".to_string(),
+            tokens_used: TokenUsage::default(),
+            response_time_ms: 0,
+            timestamp: Utc::now(),
+            success: false,
+            error_message: Some("HTTP 503".to_string()),
+            retry_after_ms: None,
+            failure: Some(shared::ApiFailure::ModelUnavailable),
+            finish_reason: None,
+            model: None,
+            cached: false,
+        };
+        assert!(needs_model_fallback(&response));
+
+        // Context overflows arrive as plain invalid requests, recognised by their message
+        response.failure = Some(shared::ApiFailure::InvalidRequest);
+        response.error_message = Some("This model's maximum Context Length is 8192 tokens".to_string());
+        assert!(needs_model_fallback(&response));
+
+        // Rate limits are retried on the same model
+        response.failure = Some(shared::ApiFailure::RateLimitExceeded);
+        response.error_message = Some("rate limit exceeded".to_string());
+        assert!(!needs_model_fallback(&response));
+
+        response.failure = Some(shared::ApiFailure::ModelUnavailable);
+        response.success = true;
+        assert!(!needs_model_fallback(&response));
+    }
+
+    #[test]
+    fn test_jittered_backoff_stays_within_bounds() {
+        let mut rng = rand::thread_rng();
+        for attempt in 0..12 {
+            let cap = (100u64 << attempt).min(2_000);
+            let delays: Vec<u64> = (0..500)
+                .map(|_| jittered_backoff(attempt, 100, 2_000, &mut rng).as_millis() as u64)
+                .collect();
+            assert!(delays.iter().all(|&d| d <= cap), "attempt {} exceeded cap {}", attempt, cap);
+            // Full jitter spreads retries rather than pinning them to the cap
+            assert!(delays.iter().any(|&d| d < cap), "attempt {} never jittered", attempt);
+        }
+
+        // Huge attempt counts saturate at the max rather than overflowing
+        assert!(jittered_backoff(200, 100, 2_000, &mut rng) <= Duration::from_millis(2_000));
     }
 
     #[test]
@@ -218,4 +582,243 @@ mod tests {
             _ => panic!("Expected backoff strategy in test mode"),
         }
     }
+
+    #[test]
+    fn test_pick_weighted_is_deterministic_for_equal_and_nan_weights() {
+        let openai = ProviderConfig::with_default_model(ProviderId::OpenAI);
+        let anthropic = ProviderConfig::with_default_model(ProviderId::Anthropic);
+        let gemini = ProviderConfig::with_default_model(ProviderId::Gemini);
+
+        let mut equal = HashMap::new();
+        equal.insert(gemini.clone(), 1.0);
+        equal.insert(openai.clone(), 1.0);
+        equal.insert(anthropic.clone(), 1.0);
+
+        // Bucket order follows provider declaration order, independent of hash order
+        assert_eq!(pick_weighted(&equal, 0.5), Some(openai.clone()));
+        assert_eq!(pick_weighted(&equal, 1.5), Some(anthropic.clone()));
+        assert_eq!(pick_weighted(&equal, 2.5), Some(gemini.clone()));
+        for _ in 0..10 {
+            assert_eq!(pick_weighted(&equal.clone(), 0.5), Some(openai.clone()));
+        }
+
+        let mut with_nan = HashMap::new();
+        with_nan.insert(openai.clone(), f32::NAN);
+        with_nan.insert(anthropic.clone(), 2.0);
+        with_nan.insert(gemini.clone(), f32::INFINITY);
+        for roll in [0.0, 0.7, 1.9, 999.0, f32::NAN] {
+            assert_eq!(pick_weighted(&with_nan, roll), Some(anthropic.clone()));
+        }
+
+        let mut all_nan = HashMap::new();
+        all_nan.insert(openai, f32::NAN);
+        assert_eq!(pick_weighted(&all_nan, 1.0), None);
+    }
+
+    #[test]
+    fn test_weighted_selection_follows_two_to_one_weights() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let openai = ProviderConfig::with_default_model(ProviderId::OpenAI);
+        let anthropic = ProviderConfig::with_default_model(ProviderId::Anthropic);
+        let mut weights = HashMap::new();
+        weights.insert(openai.clone(), 2.0);
+        weights.insert(anthropic.clone(), 1.0);
+        let strategy = Some(RoutingStrategy::Weighted { weights });
+        let fallback = ProviderConfig::with_default_model(ProviderId::Random);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let picks = 3000;
+        let openai_picks = (0..picks)
+            .filter(|_| select_provider_config_with_rng(&strategy, fallback.clone(), &mut rng, &mut 0) == openai)
+            .count();
+
+        let share = openai_picks as f64 / picks as f64;
+        assert!((share - 2.0 / 3.0).abs() < 0.05, "OpenAI share {} should be about 2/3", share);
+    }
+
+    #[test]
+    fn test_same_seed_gives_identical_routing_decisions() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut weights = HashMap::new();
+        weights.insert(ProviderConfig::with_default_model(ProviderId::OpenAI), 0.5);
+        weights.insert(ProviderConfig::with_default_model(ProviderId::Anthropic), 0.3);
+        weights.insert(ProviderConfig::with_default_model(ProviderId::Gemini), 0.2);
+        let strategies = [
+            Some(RoutingStrategy::Weighted { weights }),
+            Some(RoutingStrategy::RoundRobin {
+                providers: vec![
+                    ProviderConfig::with_default_model(ProviderId::OpenAI),
+                    ProviderConfig::with_default_model(ProviderId::Gemini),
+                    ProviderConfig::with_default_model(ProviderId::Random),
+                ],
+            }),
+        ];
+        let fallback = ProviderConfig::with_default_model(ProviderId::Random);
+
+        let decisions = |run_seed: u64| {
+            let mut rng = StdRng::seed_from_u64(shared::derive_seed(run_seed, 1));
+            let mut cursor = 0;
+            (0..100)
+                .map(|i| select_provider_config_with_rng(&strategies[i % 2], fallback.clone(), &mut rng, &mut cursor).provider)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(decisions(42), decisions(42));
+        assert_ne!(decisions(42), decisions(43));
+    }
+
+    #[test]
+    fn test_lowest_latency_routing_prefers_fastest_provider() {
+        use crate::core::Metrics;
+
+        let providers = vec![
+            ProviderConfig::with_default_model(ProviderId::OpenAI),
+            ProviderConfig::with_default_model(ProviderId::Anthropic),
+            ProviderConfig::with_default_model(ProviderId::Gemini),
+        ];
+        let fallback = ProviderConfig::with_default_model(ProviderId::Random);
+        let window = Duration::from_secs(60);
+        let mut cursor = 0;
+        let mut metrics = Metrics::new();
+
+        let latencies_of = |metrics: &Metrics| -> HashMap<ProviderId, f64> {
+            providers
+                .iter()
+                .filter_map(|config| metrics.avg_response_time(config.provider, window).map(|ms| (config.provider, ms)))
+                .collect()
+        };
+        let record = |metrics: &mut Metrics, provider: ProviderId, response_time_ms: u64, success: bool| {
+            metrics.record_response_received(&ApiResponse {
+                provider,
+                request_id: Uuid::new_v4(),
+                content: String::new(),
+                tokens_used: shared::TokenUsage::default(),
+                response_time_ms,
+                timestamp: Utc::now(),
+                success,
+                error_message: None,
+                retry_after_ms: None,
+                failure: None,
+                finish_reason: None,
+                model: None,
+                cached: false,
+            });
+        };
+        let respond = |metrics: &mut Metrics, provider: ProviderId, response_time_ms: u64| {
+            record(metrics, provider, response_time_ms, true)
+        };
+
+        // Providers without data take turns before any measured one is picked
+        respond(&mut metrics, ProviderId::OpenAI, 900);
+        let untried: Vec<_> = (0..4)
+            .map(|_| select_lowest_latency_config(&providers, &latencies_of(&metrics), fallback.clone(), &mut cursor))
+            .map(|config| config.provider)
+            .collect();
+        assert_eq!(untried, vec![ProviderId::Anthropic, ProviderId::Gemini, ProviderId::Anthropic, ProviderId::Gemini]);
+
+        respond(&mut metrics, ProviderId::Anthropic, 300);
+        respond(&mut metrics, ProviderId::Anthropic, 500);
+        respond(&mut metrics, ProviderId::Gemini, 450);
+        let selected = select_lowest_latency_config(&providers, &latencies_of(&metrics), fallback.clone(), &mut cursor);
+        assert_eq!(selected.provider, ProviderId::Anthropic);
+
+        // A slow sample shifts the rolling average past the next provider
+        respond(&mut metrics, ProviderId::Anthropic, 1000);
+        let selected = select_lowest_latency_config(&providers, &latencies_of(&metrics), fallback.clone(), &mut cursor);
+        assert_eq!(selected.provider, ProviderId::Gemini);
+
+        // A provider that fails quickly is penalized instead of keeping its fast average
+        record(&mut metrics, ProviderId::Gemini, 50, false);
+        let selected = select_lowest_latency_config(&providers, &latencies_of(&metrics), fallback, &mut cursor);
+        assert_eq!(selected.provider, ProviderId::Anthropic);
+    }
+
+    #[test]
+    fn test_circuit_breaker_routing_skips_open_providers() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let strategy = Some(RoutingStrategy::CircuitBreaker {
+            providers: vec![
+                ProviderConfig::with_default_model(ProviderId::OpenAI),
+                ProviderConfig::with_default_model(ProviderId::Anthropic),
+            ],
+            failure_threshold: 2,
+            cooldown_seconds: 30,
+        });
+        let (threshold, cooldown) = circuit_settings(&strategy).unwrap();
+        let fallback = ProviderConfig::with_default_model(ProviderId::Random);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut cursor = 0;
+        let mut circuits = CircuitState::new();
+        let now = Instant::now();
+
+        for _ in 0..threshold {
+            circuits.record(ProviderId::OpenAI, false, threshold, cooldown, now);
+        }
+        for _ in 0..20 {
+            let selected =
+                select_provider_config_with_circuits(&strategy, fallback.clone(), &mut rng, &mut cursor, &mut circuits, now).unwrap();
+            assert_eq!(selected.provider, ProviderId::Anthropic);
+        }
+
+        // With every circuit open there is nothing to route to
+        for _ in 0..threshold {
+            circuits.record(ProviderId::Anthropic, false, threshold, cooldown, now);
+        }
+        assert!(select_provider_config_with_circuits(&strategy, fallback.clone(), &mut rng, &mut cursor, &mut circuits, now).is_none());
+
+        // Once the cooldown elapses the first provider is probed exactly once
+        let later = now + cooldown;
+        let probe = select_provider_config_with_circuits(&strategy, fallback.clone(), &mut rng, &mut cursor, &mut circuits, later);
+        assert_eq!(probe.unwrap().provider, ProviderId::OpenAI);
+        let second = select_provider_config_with_circuits(&strategy, fallback, &mut rng, &mut cursor, &mut circuits, later);
+        assert_eq!(second.unwrap().provider, ProviderId::Anthropic);
+    }
+
+    #[test]
+    fn test_exhausted_budgets_are_routed_around() {
+        let budgets = HashMap::from([(ProviderId::OpenAI, 100_000), (ProviderId::Anthropic, 20_000)]);
+        let used = HashMap::from([(ProviderId::OpenAI, 40_000), (ProviderId::Anthropic, 20_000)]);
+        let exhausted = exhausted_providers(&budgets, |provider| used.get(&provider).copied().unwrap_or(0));
+        assert_eq!(exhausted, vec![ProviderId::Anthropic]);
+
+        let strategy = Some(RoutingStrategy::RoundRobin {
+            providers: vec![
+                ProviderConfig::with_default_model(ProviderId::OpenAI),
+                ProviderConfig::with_default_model(ProviderId::Anthropic),
+            ],
+        });
+        let routed = route_around_providers(&strategy, &exhausted);
+        for _ in 0..20 {
+            assert_eq!(select_provider(&routed, ProviderId::Random), ProviderId::OpenAI);
+        }
+
+        // Nothing is left once every provider is exhausted
+        assert!(route_around_providers(&strategy, &[ProviderId::OpenAI, ProviderId::Anthropic]).is_none());
+        let backoff = Some(RoutingStrategy::Backoff {
+            provider: ProviderConfig::with_default_model(ProviderId::Anthropic),
+        });
+        assert!(route_around_providers(&backoff, &exhausted).is_none());
+        assert!(route_around_providers(&None, &[ProviderId::Random]).is_none());
+        assert!(route_around_providers(&None, &exhausted).is_some());
+    }
+
+    #[test]
+    fn test_verification_sampling_and_conflicts() {
+        use rand::SeedableRng;
+
+        let attributes: Vec<String> = ["otter", "lynx", "heron"].iter().map(|a| a.to_string()).collect();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        assert!(sample_for_verification(&attributes, 0.0, &mut rng).is_empty());
+        assert!(sample_for_verification(&attributes, f32::NAN, &mut rng).is_empty());
+        assert_eq!(sample_for_verification(&attributes, 1.0, &mut rng), attributes);
+
+        let prompt = build_verification_prompt("animals", &attributes);
+        assert!(prompt.contains("Topic: animals") && prompt.contains("- lynx"));
+
+        assert_eq!(count_verification_conflicts(&attributes, "NONE"), 0);
+        assert_eq!(count_verification_conflicts(&attributes, "- Lynx\n* heron\n"), 2);
+    }
 }
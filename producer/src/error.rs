@@ -2,7 +2,7 @@
 
 use thiserror::Error;
 use std::collections::HashMap;
-use shared::ProviderId;
+use shared::{ApiFailure, ProviderId, SharedError};
 
 /// Result type for producer operations
 pub type ProducerResult<T> = Result<T, ProducerError>;
@@ -25,6 +25,9 @@ pub enum ProducerError {
         message: String,
     },
 
+    #[error("Response from {provider} blocked by content filter: {reason}")]
+    ContentFiltered { provider: ProviderId, reason: String },
+
     #[error("Processing error: {message}")]
     ProcessingError { message: String },
 
@@ -86,4 +89,22 @@ impl ProducerError {
             message: message.into(),
         }
     }
+
+    /// Classify the error the way it is reported to the orchestrator
+    pub fn api_failure(&self) -> ApiFailure {
+        match self {
+            Self::RateLimit { status, .. } => ApiFailure::from_http_status(*status),
+            Self::RequestError(e) if e.is_timeout() => ApiFailure::NetworkTimeout,
+            Self::ApiError { .. } | Self::RequestError(_) => ApiFailure::ModelUnavailable,
+            Self::ConfigError { .. } => ApiFailure::InvalidRequest,
+            Self::ContentFiltered { .. } => ApiFailure::ContentFiltered,
+            _ => ApiFailure::InternalError,
+        }
+    }
+}
+
+impl From<&ProducerError> for SharedError {
+    fn from(error: &ProducerError) -> Self {
+        SharedError::from(error.api_failure()).with_message(error.to_string())
+    }
 }
@@ -11,7 +11,7 @@ pub mod traits;
 pub mod types;
 
 // Re-export commonly used types
-pub use core::{Metrics, Processor, Producer};
+pub use core::{FewShotConfig, Metrics, Processor, Producer};
 pub use error::{ProducerError, ProducerResult};
 pub use services::{RealApiClient, RealCommunicator};
 pub use traits::{ApiClient, Communicator};
@@ -2,12 +2,18 @@
 
 use clap::Parser;
 use producer::types::ExecutionConfig;
-use producer::{Producer, ProducerConfig, RealApiClient, RealCommunicator};
-use shared::types::RoutingStrategy;
+use producer::core::AimdConfig;
+use producer::services::PricingTable;
+use producer::{FewShotConfig, Producer, ProducerConfig, RealApiClient, RealCommunicator};
+use shared::types::{
+    parse_provider_token, parse_setting, parse_weight_token, FormatEntry, ProviderConfig, RoutingParseError,
+    RoutingStrategy,
+};
 use shared::{logging, process_debug, process_error, process_info, process_warn, ProcessId, ProviderId};
 use std::collections::HashMap;
 use std::env;
 use std::error::Error;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "producer")]
@@ -25,6 +31,14 @@ struct Args {
     #[arg(long, default_value = "info")]
     log_level: String,
 
+    /// Plain ASCII logs without emoji or colors
+    #[arg(long)]
+    plain_logs: bool,
+
+    /// Console log format (text, json)
+    #[arg(long, default_value = "text")]
+    log_format: shared::logging::LogFormat,
+
     /// Orchestrator address for IPC communication (if not provided, runs in standalone mode)
     #[arg(long)]
     orchestrator_addr: Option<String>,
@@ -49,6 +63,18 @@ struct Args {
     #[arg(long, default_value = "60")]
     request_size: usize,
 
+    /// Shrink the request size when responses repeat known values and grow it when they are mostly new
+    #[arg(long)]
+    auto_tune_request_size: bool,
+
+    /// Smallest request size --auto-tune-request-size may shrink to
+    #[arg(long, default_value_t = producer::types::DEFAULT_REQUEST_SIZE_MIN)]
+    request_size_min: usize,
+
+    /// Largest request size --auto-tune-request-size may grow to
+    #[arg(long, default_value_t = producer::types::DEFAULT_REQUEST_SIZE_MAX)]
+    request_size_max: usize,
+
     /// Test mode: Model to use (default: gpt-4o-mini)
     #[arg(long, default_value = "gpt-4o-mini")]
     model: String,
@@ -61,14 +87,56 @@ struct Args {
     #[arg(long, default_value = "30000")]
     timeout_ms: u64,
 
-    /// Maximum concurrent requests (production mode)
+    /// Maximum provider response size in bytes; larger responses are rejected
+    #[arg(long, default_value_t = producer::services::api_client::DEFAULT_MAX_RESPONSE_BYTES)]
+    max_response_bytes: usize,
+
+    /// JSON file with few-shot example attributes shown in prompts
+    #[arg(long)]
+    examples_file: Option<String>,
+
+    /// Output directory for <topic>/dead_letter.log, where responses yielding no attributes are kept
+    /// (standalone mode defaults to ./output; disabled under an orchestrator unless it passes one)
+    #[arg(long)]
+    dead_letter_dir: Option<PathBuf>,
+
+    /// JSON file with per-model token prices used for cost estimates
+    #[arg(long)]
+    pricing: Option<String>,
+
+    /// Retry a request blocked by a provider's content filter once with a reworded prompt
+    #[arg(long)]
+    reword_filtered_prompts: bool,
+
+    /// Directory where every provider request/response pair is written, for replay as a test fixture
+    #[arg(long)]
+    record_responses: Option<PathBuf>,
+
+    /// Answer repeats of a recent successful request from memory instead of calling the provider
+    #[arg(long)]
+    enable_request_cache: bool,
+
+    /// How long a cached response may answer repeat requests (with --enable-request-cache)
+    #[arg(long, default_value_t = producer::services::request_cache::DEFAULT_REQUEST_CACHE_TTL_MS)]
+    request_cache_ttl_ms: u64,
+
+    /// Maximum requests in flight at once
     #[arg(long, default_value = "10")]
     max_concurrent: usize,
 
+    /// Auto-tune per-provider concurrency (AIMD) up to --max-concurrent
+    #[arg(long)]
+    auto_tune: bool,
+
     /// OpenAI API key (can also be set via OPENAI_API_KEY env var or .env file)
     #[arg(long)]
     openai_key: Option<String>,
 
+    /// Base URL for OpenAI-compatible traffic, e.g. a local Ollama or LM Studio server
+    /// (can also be set via OPENAI_BASE_URL env var)
+    #[arg(long)]
+    openai_base_url: Option<String>,
+
     /// Anthropic API key (can also be set via ANTHROPIC_API_KEY env var)
     #[arg(long)]
     anthropic_key: Option<String>,
@@ -77,11 +145,24 @@ struct Args {
     #[arg(long)]
     gemini_key: Option<String>,
 
+    /// Mistral API key (can also be set via MISTRAL_API_KEY env var)
+    #[arg(long)]
+    mistral_key: Option<String>,
+
+    /// Cohere API key (can also be set via COHERE_API_KEY env var)
+    #[arg(long)]
+    cohere_key: Option<String>,
+
+    /// Azure OpenAI API key (can also be set via AZURE_OPENAI_API_KEY env var); the resource and
+    /// deployment come from AZURE_OPENAI_RESOURCE_NAME and AZURE_OPENAI_DEPLOYMENT
+    #[arg(long)]
+    azure_openai_key: Option<String>,
+
     /// Random API key (not required - Random provider works without API key)
     #[arg(long)]
     random_key: Option<String>,
 
-    /// Routing strategy: backoff, roundrobin, priority, weighted (default: backoff)
+    /// Routing strategy: backoff, roundrobin, priority, weighted, circuitbreaker, lowestlatency (default: backoff)
     #[arg(long, default_value = "backoff")]
     routing_strategy: String,
 
@@ -89,7 +170,8 @@ struct Args {
     #[arg(long)]
     routing_provider: Option<String>,
 
-    /// Comma-separated list of providers for roundrobin/priority strategies
+    /// Comma-separated providers for list-based strategies, each "provider" or "provider:model"
+    /// (e.g. "openai:gpt-4o,anthropic"; bare providers use their default model)
     #[arg(long)]
     routing_providers: Option<String>,
 
@@ -100,109 +182,207 @@ struct Args {
     /// Maximum requests for testing (limits how many generation cycles to run)
     #[arg(long)]
     max_requests: Option<u32>,
+
+    /// Per-provider token caps for the run (format: "openai:100000,anthropic:20000")
+    #[arg(long)]
+    token_budgets: Option<String>,
+
+    /// Per-provider request caps per minute, enforced client-side (format: "openai:60,anthropic:30")
+    #[arg(long)]
+    rate_limits: Option<String>,
+
+    /// Base delay in milliseconds for jittered retry backoff
+    #[arg(long, default_value_t = producer::types::DEFAULT_RETRY_BASE_MS)]
+    retry_base_ms: u64,
+
+    /// Maximum delay in milliseconds for a single retry backoff
+    #[arg(long, default_value_t = producer::types::DEFAULT_RETRY_MAX_MS)]
+    retry_max_ms: u64,
+
+    /// Seed for the Random provider so its generated attributes repeat across runs
+    #[arg(long)]
+    random_seed: Option<u64>,
+
+    /// Milliseconds to wait on shutdown for in-flight requests to deliver their attributes
+    #[arg(long, default_value_t = producer::types::DEFAULT_DRAIN_TIMEOUT_MS)]
+    drain_timeout_ms: u64,
+
+    /// Reconnection attempts when the orchestrator stops accepting updates, before the producer exits
+    #[arg(long, default_value_t = producer::services::communicator::DEFAULT_RECONNECT_ATTEMPTS)]
+    reconnect_attempts: u32,
+
+    /// Delay in milliseconds before the first reconnection attempt; doubles after each failure
+    #[arg(long, default_value_t = producer::services::communicator::DEFAULT_RECONNECT_BASE_MS)]
+    reconnect_base_ms: u64,
+
+    /// Provider that re-checks sampled attributes (format: "provider" or "provider:model")
+    #[arg(long)]
+    verification_provider: Option<String>,
+
+    /// Fraction of accepted attributes re-checked by the verification provider (0.0 to 1.0)
+    #[arg(long, default_value_t = 0.0)]
+    verification_sample_rate: f32,
 }
 
 /// Parse routing configuration from orchestrator with models
-fn parse_routing_config(routing_config: &str) -> Result<RoutingStrategy, String> {
-    let parts: Vec<&str> = routing_config.split(',').collect();
+fn parse_routing_config(routing_config: &str) -> Result<RoutingStrategy, RoutingParseError> {
     let mut strategy_type = None;
     let mut provider = None;
     let mut model = None;
-    let mut providers = None;
-    let mut weights = None;
+    let mut providers: Option<String> = None;
+    let mut weights: Option<String> = None;
+    let mut threshold = None;
+    let mut cooldown = None;
+    let mut window = None;
+    // List key that bare "provider:model" entries continue (lists are comma-separated too)
+    let mut open_list = None;
 
-    for part in parts {
-        let kv: Vec<&str> = part.split(':').collect();
-        if kv.len() < 2 {
-            return Err(format!("Invalid routing config format: '{}'", part));
-        }
-        
-        match kv[0] {
-            "strategy" => strategy_type = Some(kv[1]),
-            "provider" => provider = Some(kv[1]),
-            "model" => model = Some(kv[1]),
-            "providers" => providers = Some(kv[1]),
-            "weights" => weights = Some(kv[1]),
-            _ => return Err(format!("Unknown routing config key: '{}'", kv[0])),
+    for part in routing_config.split(',') {
+        let Some((key, value)) = part.split_once(':') else {
+            return Err(RoutingParseError::InvalidFormat {
+                entry: FormatEntry::RoutingConfig,
+                token: part.to_string(),
+            });
+        };
+
+        match key {
+            "strategy" => strategy_type = Some(value),
+            "provider" => provider = Some(value),
+            "model" => model = Some(value),
+            "providers" => {
+                providers = Some(value.to_string());
+                open_list = Some("providers");
+                continue;
+            }
+            "weights" => {
+                weights = Some(value.to_string());
+                open_list = Some("weights");
+                continue;
+            }
+            "threshold" => threshold = Some(value),
+            "cooldown" => cooldown = Some(value),
+            "window" => window = Some(value),
+            _ => {
+                let list = match open_list {
+                    Some("providers") => providers.as_mut(),
+                    Some("weights") => weights.as_mut(),
+                    _ => None,
+                };
+                match list {
+                    Some(list) => {
+                        list.push(',');
+                        list.push_str(part);
+                        continue;
+                    }
+                    None => return Err(RoutingParseError::UnknownKey { key: key.to_string() }),
+                }
+            }
         }
+        open_list = None;
     }
 
+    let missing = |strategy: &str, field: &'static str| RoutingParseError::MissingField {
+        strategy: strategy.to_string(),
+        field,
+    };
     let strategy = match strategy_type {
         Some("backoff") => {
-            let provider_id = provider
-                .ok_or("backoff strategy requires provider")?
-                .parse()
-                .map_err(|e| format!("Invalid provider: {}", e))?;
-            let model_name = model
-                .ok_or("backoff strategy requires model")?
-                .to_string();
-            let provider_config = shared::types::ProviderConfig::new(provider_id, model_name);
+            let provider_id = parse_provider_token(provider.ok_or_else(|| missing("backoff", "provider"))?)?;
+            let model_spec = model.ok_or_else(|| missing("backoff", "model"))?;
+            let provider_config = ProviderConfig::try_from_model_spec(provider_id, model_spec)?;
             RoutingStrategy::Backoff { provider: provider_config }
         },
         Some("roundrobin") => {
             let provider_configs = parse_provider_config_list(
-                providers.ok_or("roundrobin strategy requires providers")?
+                providers.as_deref().ok_or_else(|| missing("roundrobin", "providers"))?
             )?;
             RoutingStrategy::RoundRobin { providers: provider_configs }
         },
         Some("priority") => {
             let provider_configs = parse_provider_config_list(
-                providers.ok_or("priority strategy requires providers")?
+                providers.as_deref().ok_or_else(|| missing("priority", "providers"))?
             )?;
             RoutingStrategy::PriorityOrder { providers: provider_configs }
         },
         Some("weighted") => {
             let weight_pairs = parse_weighted_provider_configs(
-                weights.ok_or("weighted strategy requires weights")?
+                weights.as_deref().ok_or_else(|| missing("weighted", "weights"))?
             )?;
             RoutingStrategy::Weighted { weights: weight_pairs }
         },
-        Some(s) => return Err(format!("Unknown strategy: '{}'", s)),
-        None => return Err("Missing strategy in routing config".to_string()),
+        Some("circuitbreaker") => {
+            let provider_configs = parse_provider_config_list(
+                providers.as_deref().ok_or_else(|| missing("circuitbreaker", "providers"))?
+            )?;
+            let failure_threshold = match threshold {
+                Some(value) => parse_setting("threshold", value)?,
+                None => shared::types::DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+            };
+            let cooldown_seconds = match cooldown {
+                Some(value) => parse_setting("cooldown", value)?,
+                None => shared::types::DEFAULT_CIRCUIT_COOLDOWN_SECONDS,
+            };
+            RoutingStrategy::CircuitBreaker { providers: provider_configs, failure_threshold, cooldown_seconds }
+        },
+        Some("lowestlatency") => {
+            let provider_configs = parse_provider_config_list(
+                providers.as_deref().ok_or_else(|| missing("lowestlatency", "providers"))?
+            )?;
+            let window_seconds = match window {
+                Some(value) => parse_setting("window", value)?,
+                None => shared::types::DEFAULT_LATENCY_WINDOW_SECONDS,
+            };
+            RoutingStrategy::LowestLatency { providers: provider_configs, window_seconds }
+        },
+        Some(s) => return Err(RoutingParseError::UnknownConfigStrategy { strategy: s.to_string() }),
+        None => return Err(RoutingParseError::MissingStrategy),
     };
 
     Ok(strategy)
 }
 
 /// Parse provider config list from "provider1:model1,provider2:model2" format
-fn parse_provider_config_list(providers_str: &str) -> Result<Vec<shared::types::ProviderConfig>, String> {
+fn parse_provider_config_list(providers_str: &str) -> Result<Vec<ProviderConfig>, RoutingParseError> {
     providers_str
         .split(',')
         .map(|p| {
             let parts: Vec<&str> = p.split(':').collect();
             if parts.len() != 2 {
-                return Err(format!("Invalid provider:model format: '{}'", p));
+                return Err(RoutingParseError::InvalidFormat {
+                    entry: FormatEntry::ProviderModel,
+                    token: p.to_string(),
+                });
             }
-            let provider_id = parts[0].parse()
-                .map_err(|e| format!("Invalid provider '{}': {}", parts[0], e))?;
-            let model = parts[1].to_string();
-            Ok(shared::types::ProviderConfig::new(provider_id, model))
+            let provider_id = parse_provider_token(parts[0])?;
+            ProviderConfig::try_from_model_spec(provider_id, parts[1])
         })
         .collect()
 }
 
 /// Parse weighted provider configs from "provider1:model1:weight1,provider2:model2:weight2" format
-fn parse_weighted_provider_configs(weights_str: &str) -> Result<HashMap<shared::types::ProviderConfig, f32>, String> {
-    weights_str
+fn parse_weighted_provider_configs(weights_str: &str) -> Result<HashMap<ProviderConfig, f32>, RoutingParseError> {
+    let mut weights = weights_str
         .split(',')
         .map(|pair| {
             let parts: Vec<&str> = pair.split(':').collect();
             if parts.len() != 3 {
-                return Err(format!("Invalid weight format: '{}', expected 'provider:model:weight'", pair));
+                return Err(RoutingParseError::InvalidFormat {
+                    entry: FormatEntry::ProviderModelWeight,
+                    token: pair.to_string(),
+                });
             }
-            let provider_id = parts[0].parse()
-                .map_err(|e| format!("Invalid provider '{}': {}", parts[0], e))?;
-            let model = parts[1].to_string();
-            let weight: f32 = parts[2].parse()
-                .map_err(|e| format!("Invalid weight '{}': {}", parts[2], e))?;
-            let provider_config = shared::types::ProviderConfig::new(provider_id, model);
+            let provider_id = parse_provider_token(parts[0])?;
+            let weight = parse_weight_token(parts[2])?;
+            let provider_config = ProviderConfig::try_from_model_spec(provider_id, parts[1])?;
             Ok((provider_config, weight))
         })
-        .collect()
+        .collect::<Result<HashMap<_, _>, RoutingParseError>>()?;
+    shared::types::normalize_weight_map(&mut weights)?;
+    Ok(weights)
 }
 
 /// Parse routing strategy from command-line arguments with env fallback
-fn parse_routing_strategy(args: &Args, use_test_provider: bool) -> Result<RoutingStrategy, String> {
+fn parse_routing_strategy(args: &Args, use_test_provider: bool) -> Result<RoutingStrategy, RoutingParseError> {
     // First check if we should use environment-based routing entirely
     let strategy_type = if args.routing_strategy == "backoff" && args.routing_provider.is_none() 
         && args.routing_providers.is_none() && args.routing_weights.is_none() {
@@ -212,114 +392,197 @@ fn parse_routing_strategy(args: &Args, use_test_provider: bool) -> Result<Routin
         args.routing_strategy.clone()
     };
     
+    let missing_providers = |strategy: &str| RoutingParseError::MissingProviders {
+        strategy: strategy.to_string(),
+        argument: "--routing-providers or ROUTING_PROVIDERS env".to_string(),
+    };
+    let missing_weights = || RoutingParseError::MissingWeights {
+        argument: "--routing-weights or ROUTING_WEIGHTS env".to_string(),
+    };
+
     match strategy_type.to_lowercase().as_str() {
         "backoff" => {
             let provider_id = if let Some(ref provider_str) = args.routing_provider {
                 // Command-line arg takes priority
-                provider_str.parse()
-                    .map_err(|e| format!("Invalid routing provider '{}': {}", provider_str, e))?
+                parse_provider_token(provider_str)?
             } else if use_test_provider {
                 // Test mode defaults to random
                 ProviderId::Random
             } else {
                 // Production mode: try to get from environment or default to random
                 match env::var("ROUTING_PRIMARY_PROVIDER") {
-                    Ok(p) => p.parse()
-                        .map_err(|e| format!("Invalid ROUTING_PRIMARY_PROVIDER '{}': {}", p, e))?,
+                    Ok(p) => parse_provider_token(&p)?,
                     Err(_) => ProviderId::Random,
                 }
             };
-            let provider_config = shared::types::ProviderConfig::with_default_model(provider_id);
+            let provider_config = ProviderConfig::with_default_model(provider_id);
             Ok(RoutingStrategy::Backoff { provider: provider_config })
         }
         "roundrobin" => {
-            let provider_ids = if args.routing_providers.is_some() {
+            let providers = if args.routing_providers.is_some() {
                 parse_provider_list(&args.routing_providers)?
             } else {
                 // Try environment variable
                 let env_providers = env::var("ROUTING_PROVIDERS").ok();
                 parse_provider_list(&env_providers)?
             };
-            if provider_ids.is_empty() {
-                return Err("--routing-providers or ROUTING_PROVIDERS env must be specified for roundrobin strategy".to_string());
+            if providers.is_empty() {
+                return Err(missing_providers("roundrobin"));
             }
-            let providers = provider_ids.into_iter().map(|id| shared::types::ProviderConfig::with_default_model(id)).collect();
             Ok(RoutingStrategy::RoundRobin { providers })
         }
         "priority" => {
-            let provider_ids = if args.routing_providers.is_some() {
+            let providers = if args.routing_providers.is_some() {
                 parse_provider_list(&args.routing_providers)?
             } else {
                 // Try environment variable
                 let env_providers = env::var("ROUTING_PROVIDERS").ok();
                 parse_provider_list(&env_providers)?
             };
-            if provider_ids.is_empty() {
-                return Err("--routing-providers or ROUTING_PROVIDERS env must be specified for priority strategy".to_string());
+            if providers.is_empty() {
+                return Err(missing_providers("priority"));
             }
-            let providers = provider_ids.into_iter().map(|id| shared::types::ProviderConfig::with_default_model(id)).collect();
             Ok(RoutingStrategy::PriorityOrder { providers })
         }
         "weighted" => {
             let weights_str = if let Some(ref w) = args.routing_weights {
                 w.clone()
             } else {
-                env::var("ROUTING_WEIGHTS")
-                    .map_err(|_| "--routing-weights or ROUTING_WEIGHTS env must be specified for weighted strategy".to_string())?
+                env::var("ROUTING_WEIGHTS").map_err(|_| missing_weights())?
             };
             
             let provider_weights = parse_weights(&weights_str)?;
             if provider_weights.is_empty() {
-                return Err("No valid weights provided".to_string());
+                return Err(missing_weights());
             }
             let weights = provider_weights.into_iter().map(|(id, weight)| {
-                (shared::types::ProviderConfig::with_default_model(id), weight)
+                (ProviderConfig::with_default_model(id), weight)
             }).collect();
             Ok(RoutingStrategy::Weighted { weights })
         }
-        _ => Err(format!("Unknown routing strategy '{}'. Valid options: backoff, roundrobin, priority, weighted", args.routing_strategy)),
+        "circuitbreaker" => {
+            let providers = if args.routing_providers.is_some() {
+                parse_provider_list(&args.routing_providers)?
+            } else {
+                // Try environment variable
+                let env_providers = env::var("ROUTING_PROVIDERS").ok();
+                parse_provider_list(&env_providers)?
+            };
+            if providers.is_empty() {
+                return Err(missing_providers("circuitbreaker"));
+            }
+            Ok(RoutingStrategy::CircuitBreaker {
+                providers,
+                failure_threshold: shared::types::DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+                cooldown_seconds: shared::types::DEFAULT_CIRCUIT_COOLDOWN_SECONDS,
+            })
+        }
+        "lowestlatency" => {
+            let providers = if args.routing_providers.is_some() {
+                parse_provider_list(&args.routing_providers)?
+            } else {
+                // Try environment variable
+                let env_providers = env::var("ROUTING_PROVIDERS").ok();
+                parse_provider_list(&env_providers)?
+            };
+            if providers.is_empty() {
+                return Err(missing_providers("lowestlatency"));
+            }
+            Ok(RoutingStrategy::LowestLatency {
+                providers,
+                window_seconds: shared::types::DEFAULT_LATENCY_WINDOW_SECONDS,
+            })
+        }
+        _ => Err(RoutingParseError::UnknownStrategy { strategy: args.routing_strategy.clone() }),
     }
 }
 
-/// Parse comma-separated provider list
-fn parse_provider_list(providers_str: &Option<String>) -> Result<Vec<ProviderId>, String> {
+/// Parse comma-separated provider list (entries: "provider" or "provider:model")
+fn parse_provider_list(providers_str: &Option<String>) -> Result<Vec<ProviderConfig>, RoutingParseError> {
     match providers_str {
-        Some(s) => s
-            .split(',')
-            .map(|p| p.trim().parse())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Invalid provider in list: {}", e)),
+        Some(s) => s.split(',').map(parse_provider_config).collect(),
         None => Ok(Vec::new()),
     }
 }
 
+/// Parse per-provider token budgets (format: "provider1:tokens1,provider2:tokens2")
+fn parse_token_budgets(budgets_str: &str) -> Result<HashMap<ProviderId, u64>, String> {
+    let mut budgets = HashMap::new();
+
+    for pair in budgets_str.split(',') {
+        let (provider, tokens) = pair
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid token budget '{}'. Expected 'provider:tokens'", pair))?;
+
+        let provider: ProviderId = provider.trim().parse()
+            .map_err(|e| format!("Invalid provider '{}': {}", provider, e))?;
+        let tokens: u64 = tokens.trim().parse()
+            .map_err(|e| format!("Invalid token budget '{}': {}", tokens, e))?;
+
+        budgets.insert(provider, tokens);
+    }
+
+    Ok(budgets)
+}
+
+/// Parse per-provider requests-per-minute limits (format: "provider1:rpm1,provider2:rpm2")
+fn parse_rate_limits(limits_str: &str) -> Result<HashMap<ProviderId, u32>, String> {
+    let mut limits = HashMap::new();
+
+    for pair in limits_str.split(',') {
+        let (provider, rpm) = pair
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid rate limit '{}'. Expected 'provider:requests_per_minute'", pair))?;
+
+        let provider: ProviderId = provider.trim().parse()
+            .map_err(|e| format!("Invalid provider '{}': {}", provider, e))?;
+        let rpm: u32 = rpm.trim().parse()
+            .map_err(|e| format!("Invalid rate limit '{}': {}", rpm, e))?;
+        if rpm == 0 {
+            return Err(format!("Rate limit for {} must be at least 1 request per minute", provider));
+        }
+
+        limits.insert(provider, rpm);
+    }
+
+    Ok(limits)
+}
+
+/// Parse one provider with an optional model (format: "provider", "provider:model" or "provider:model|fallback")
+fn parse_provider_config(provider_str: &str) -> Result<ProviderConfig, RoutingParseError> {
+    let (provider, model) = match provider_str.split_once(':') {
+        Some((provider, model)) => (provider, Some(model.trim())),
+        None => (provider_str, None),
+    };
+    let provider = parse_provider_token(provider)?;
+
+    match model {
+        Some(model) if !model.is_empty() => ProviderConfig::try_from_model_spec(provider, model),
+        _ => Ok(ProviderConfig::with_default_model(provider)),
+    }
+}
+
 /// Parse provider weights (format: "provider1:weight1,provider2:weight2")
-fn parse_weights(weights_str: &str) -> Result<HashMap<ProviderId, f32>, String> {
+fn parse_weights(weights_str: &str) -> Result<HashMap<ProviderId, f32>, RoutingParseError> {
     let mut weights = HashMap::new();
     
     for pair in weights_str.split(',') {
         let parts: Vec<&str> = pair.split(':').collect();
         if parts.len() != 2 {
-            return Err(format!("Invalid weight format '{}'. Expected 'provider:weight'", pair));
+            return Err(RoutingParseError::InvalidFormat {
+                entry: FormatEntry::ProviderWeight,
+                token: pair.to_string(),
+            });
         }
         
-        let provider: ProviderId = parts[0].trim().parse()
-            .map_err(|e| format!("Invalid provider '{}': {}", parts[0], e))?;
-        let weight: f32 = parts[1].trim().parse()
-            .map_err(|e| format!("Invalid weight '{}': {}", parts[1], e))?;
-        
-        if weight < 0.0 || weight > 1.0 {
-            return Err(format!("Weight {} must be between 0.0 and 1.0", weight));
-        }
+        let provider = parse_provider_token(parts[0])?;
+        let weight = parse_weight_token(parts[1])?;
         
         weights.insert(provider, weight);
     }
     
-    // Validate weights sum to approximately 1.0
-    let sum: f32 = weights.values().sum();
-    if (sum - 1.0).abs() > 0.01 {
-        return Err(format!("Weights sum to {:.3}, but should sum to 1.0", sum));
-    }
+    // Weights are relative proportions, so "openai:2,anthropic:1" is a 2:1 split
+    shared::types::normalize_weight_map(&mut weights)?;
     
     Ok(weights)
 }
@@ -337,19 +600,30 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .as_ref()
         .map(|url| shared::logging::TracingEndpoint::new(url.clone()));
     
-    // Debug: Print tracing configuration
-    if let Some(ref endpoint) = trace_endpoint {
-        println!("🔍 Producer tracing endpoint: {}", endpoint.url);
-        println!("🔍 Producer log level: {}", args.log_level);
-    } else {
-        println!("🔍 Producer: No tracing endpoint configured");
+    // Debug: Print tracing configuration (JSON output keeps stdout to log lines only)
+    let prefix = if args.plain_logs { "" } else { "🔍 " };
+    if args.log_format == shared::logging::LogFormat::Text {
+        if let Some(ref endpoint) = trace_endpoint {
+            println!("{}Producer tracing endpoint: {}", prefix, endpoint.url);
+            println!("{}Producer log level: {}", prefix, args.log_level);
+        } else {
+            println!("{}Producer: No tracing endpoint configured", prefix);
+        }
     }
-    
-    shared::logging::init_tracing_with_endpoint_and_level(trace_endpoint, Some(&args.log_level));
+
+    let log_options = shared::logging::LogOptions {
+        log_level: Some(args.log_level.clone()),
+        plain: args.plain_logs,
+        format: args.log_format,
+        ..Default::default()
+    };
+    shared::logging::init_tracing_with_options(trace_endpoint, &log_options);
     
     // Test trace immediately after initialization
     tracing::info!("🧪 Producer tracing test - this should appear in traces");
-    println!("🔍 Producer: Tracing initialized, test message sent");
+    if args.log_format == shared::logging::LogFormat::Text {
+        println!("{}Producer: Tracing initialized, test message sent", prefix);
+    }
 
     // Determine operating mode based on orchestrator address availability (like webserver)
     let standalone_mode = args.orchestrator_addr.is_none();
@@ -377,12 +651,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
             RoutingStrategy::RoundRobin { providers } => providers.iter().any(|p| p.provider == ProviderId::Random),
             RoutingStrategy::PriorityOrder { providers } => providers.iter().any(|p| p.provider == ProviderId::Random),
             RoutingStrategy::Weighted { weights } => weights.keys().any(|p| p.provider == ProviderId::Random),
+            RoutingStrategy::CircuitBreaker { providers, .. } | RoutingStrategy::LowestLatency { providers, .. } => {
+                providers.iter().any(|p| p.provider == ProviderId::Random)
+            }
         }
     }
 
     // Create producer configuration
-    let topic = if args.topic.is_some() {
-        args.topic.as_ref().unwrap().clone()
+    let topic = if let Some(topic) = &args.topic {
+        topic.clone()
     } else if standalone_mode {
         "Standalone test topic".to_string()
     } else {
@@ -444,9 +721,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
 
     // Try to load .env file if it exists (for test mode convenience)
-    if let Ok(_) = dotenvy::dotenv() {
+    if dotenvy::dotenv().is_ok() {
         process_debug!(ProcessId::current(), "📄 Loaded .env file from current directory");
-    } else if let Ok(_) = dotenvy::from_path("../.env") {
+    } else if dotenvy::from_path("../.env").is_ok() {
         process_debug!(ProcessId::current(), "📄 Loaded .env file from parent directory");
     } else {
         process_debug!(
@@ -493,6 +770,27 @@ async fn main() -> Result<(), Box<dyn Error>> {
             process_warn!(ProcessId::current(), "⚠️ Gemini API key not provided");
         }
 
+        if let Some(key) = args.mistral_key.as_ref().cloned().or_else(|| env::var("MISTRAL_API_KEY").ok()) {
+            api_keys.insert(ProviderId::Mistral, key);
+            process_debug!(ProcessId::current(), "Mistral API key configured");
+        } else {
+            process_warn!(ProcessId::current(), "⚠️ Mistral API key not provided");
+        }
+
+        if let Some(key) = args.cohere_key.as_ref().cloned().or_else(|| env::var("COHERE_API_KEY").ok()) {
+            api_keys.insert(ProviderId::Cohere, key);
+            process_debug!(ProcessId::current(), "Cohere API key configured");
+        } else {
+            process_warn!(ProcessId::current(), "⚠️ Cohere API key not provided");
+        }
+
+        if let Some(key) = args.azure_openai_key.as_ref().cloned().or_else(|| env::var("AZURE_OPENAI_API_KEY").ok()) {
+            api_keys.insert(ProviderId::AzureOpenAI, key);
+            process_debug!(ProcessId::current(), "Azure OpenAI API key configured");
+        } else {
+            process_warn!(ProcessId::current(), "⚠️ Azure OpenAI API key not provided");
+        }
+
         // Random provider (always available as fallback)
         if let Some(key) = args.random_key.as_ref().cloned().or_else(|| env::var("RANDOM_API_KEY").ok()) {
             api_keys.insert(ProviderId::Random, key);
@@ -515,7 +813,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             );
             process_warn!(
                 ProcessId::current(),
-                "   Consider setting at least one of: OPENAI_API_KEY, ANTHROPIC_API_KEY, GEMINI_API_KEY"
+                "   Consider setting at least one of: OPENAI_API_KEY, ANTHROPIC_API_KEY, GEMINI_API_KEY, MISTRAL_API_KEY, COHERE_API_KEY, AZURE_OPENAI_API_KEY"
             );
         }
     }
@@ -527,14 +825,33 @@ async fn main() -> Result<(), Box<dyn Error>> {
     config.request_size = args.request_size;
 
     // Create services
-    let api_client = RealApiClient::new(config.api_keys.clone(), args.timeout_ms);
+    let mut api_client = RealApiClient::new(config.api_keys.clone(), args.timeout_ms)
+        .with_max_response_bytes(args.max_response_bytes);
+    if let Some(base_url) = args.openai_base_url.clone().or_else(|| env::var("OPENAI_BASE_URL").ok()) {
+        process_info!(ProcessId::current(), "🏠 Sending OpenAI requests to {}", base_url);
+        api_client = api_client.with_base_url(ProviderId::OpenAI, base_url);
+    }
+    if let Some(ref path) = args.pricing {
+        let pricing = PricingTable::load(path)?;
+        process_info!(ProcessId::current(), "💲 Loaded prices for {} models from {}", pricing.len(), path);
+        api_client = api_client.with_pricing(pricing);
+    }
+    if let Some(ref dir) = args.record_responses {
+        process_info!(ProcessId::current(), "📼 Recording provider responses to {}", dir.display());
+        api_client = api_client.with_response_recording(dir);
+    }
+    if args.enable_request_cache {
+        process_info!(ProcessId::current(), "♻️ Caching responses for {}ms", args.request_cache_ttl_ms);
+        api_client = api_client.with_request_cache(std::time::Duration::from_millis(args.request_cache_ttl_ms));
+    }
     let communicator = if standalone_mode {
         RealCommunicator::new_standalone(ProcessId::current().clone())
     } else if let Some(port) = args.listen_port {
         RealCommunicator::with_listen_port(orchestrator_addr, port, ProcessId::current().clone())
     } else {
         RealCommunicator::new(orchestrator_addr, ProcessId::current().clone())
-    };
+    }
+    .with_reconnect(args.reconnect_attempts, std::time::Duration::from_millis(args.reconnect_base_ms));
 
     // Use routing strategy from orchestrator if available, otherwise parse from command-line arguments
     let routing_strategy = if let Some(strategy) = routing_strategy_from_orchestrator {
@@ -559,13 +876,95 @@ async fn main() -> Result<(), Box<dyn Error>> {
         orchestrator_endpoint,
         config.topic.clone(),
         Some(2),                                       // 2 second interval
-        args.max_requests.or_else(|| if standalone_mode { Some(50) } else { None }), // Use command-line arg or default to 50 for standalone
+        args.max_requests.or(if standalone_mode { Some(50) } else { None }), // Use command-line arg or default to 50 for standalone
         Some(routing_strategy),
     )
     .map_err(|e| format!("Failed to create execution config: {}", e))?;
+    let execution_config = match args.token_budgets {
+        Some(ref budgets) => {
+            let budgets = parse_token_budgets(budgets).map_err(|e| format!("Invalid --token-budgets: {}", e))?;
+            process_info!(ProcessId::current(), "💸 Token budgets: {:?}", budgets);
+            execution_config.with_token_budgets(budgets)
+        }
+        None => execution_config,
+    };
+    let execution_config = match args.rate_limits {
+        Some(ref limits) => {
+            let limits = parse_rate_limits(limits).map_err(|e| format!("Invalid --rate-limits: {}", e))?;
+            process_info!(ProcessId::current(), "⏳ Rate limits (requests/min): {:?}", limits);
+            execution_config.with_rate_limits(limits)
+        }
+        None => execution_config,
+    };
+    if !(0.0..=1.0).contains(&args.verification_sample_rate) {
+        return Err(format!(
+            "Invalid --verification-sample-rate {}: must be between 0.0 and 1.0",
+            args.verification_sample_rate
+        )
+        .into());
+    }
+    let execution_config = match args.verification_provider {
+        Some(ref provider) => {
+            let provider = parse_provider_config(provider)
+                .map_err(|e| format!("Invalid --verification-provider: {}", e))?;
+            process_info!(
+                ProcessId::current(),
+                "🔎 Verifying {:.0}% of accepted attributes with {}:{}",
+                args.verification_sample_rate * 100.0,
+                provider.provider,
+                provider.model
+            );
+            execution_config.with_verification(provider, args.verification_sample_rate)
+        }
+        None => execution_config,
+    };
+    if args.request_size_min > args.request_size_max {
+        return Err(format!(
+            "Invalid request size bounds: --request-size-min {} exceeds --request-size-max {}",
+            args.request_size_min, args.request_size_max
+        )
+        .into());
+    }
+    let execution_config = if args.auto_tune_request_size {
+        process_info!(
+            ProcessId::current(),
+            "📏 Auto-tuning request size between {} and {}",
+            args.request_size_min,
+            args.request_size_max
+        );
+        execution_config.with_request_size_tuning(args.request_size_min, args.request_size_max)
+    } else {
+        execution_config
+    };
+    let execution_config = execution_config
+        .with_retry_backoff(args.retry_base_ms, args.retry_max_ms)
+        .with_random_seed(args.random_seed)
+        .with_max_concurrent_requests(args.max_concurrent)
+        .with_drain_timeout(std::time::Duration::from_millis(args.drain_timeout_ms))
+        .with_filtered_prompt_rewording(args.reword_filtered_prompts)
+        .with_dead_letter_dir(
+            args.dead_letter_dir
+                .clone()
+                .or_else(|| standalone_mode.then(|| PathBuf::from("./output"))),
+        );
 
     // Create producer
     let mut producer = Producer::new(execution_config, api_client, communicator);
+    if let Some(ref path) = args.examples_file {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read examples file '{}': {}", path, e))?;
+        let few_shot: FewShotConfig = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid examples file '{}': {}", path, e))?;
+        process_info!(ProcessId::current(), "📚 Loaded few-shot examples from {} (count: {})", path, few_shot.count);
+        producer = producer.with_few_shot(few_shot);
+    }
+    if args.auto_tune {
+        process_info!(ProcessId::current(), "🎛️ Auto-tuning provider concurrency up to {}", args.max_concurrent);
+        producer = producer.with_auto_tune(AimdConfig {
+            max_concurrency: args.max_concurrent.max(1),
+            ..AimdConfig::default()
+        });
+    }
 
     // Set up signal handling for graceful shutdown
     let shutdown_sender = producer.shutdown_sender();
@@ -614,3 +1013,58 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routing_config_errors_identify_the_problem() {
+        assert_eq!(
+            parse_routing_config("strategy:backoff,colour:blue"),
+            Err(RoutingParseError::UnknownKey { key: "colour".to_string() })
+        );
+        assert_eq!(parse_routing_config("provider:openai,model:gpt-4o"), Err(RoutingParseError::MissingStrategy));
+        assert_eq!(
+            parse_routing_config("strategy:fastest"),
+            Err(RoutingParseError::UnknownConfigStrategy { strategy: "fastest".to_string() })
+        );
+        assert_eq!(
+            parse_routing_config("strategy:backoff,provider:openai"),
+            Err(RoutingParseError::MissingField { strategy: "backoff".to_string(), field: "model" })
+        );
+
+        let error = parse_routing_config("strategy").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid routing config format: 'strategy'");
+
+        let error = parse_routing_config("strategy:weighted,weights:openai:0.5").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid weight format: 'openai:0.5', expected 'provider:model:weight'");
+
+        let error = parse_routing_config("strategy:circuitbreaker,providers:openai:gpt-4o,threshold:often").unwrap_err();
+        assert!(matches!(
+            error,
+            RoutingParseError::InvalidSetting { ref setting, ref token, .. } if setting == "threshold" && token == "often"
+        ));
+    }
+
+    #[test]
+    fn test_routing_args_without_providers_name_the_missing_argument() {
+        for var in ["ROUTING_STRATEGY", "ROUTING_PROVIDERS", "ROUTING_WEIGHTS"] {
+            env::remove_var(var);
+        }
+
+        let args = Args::parse_from(["producer", "--routing-strategy", "roundrobin"]);
+        let error = parse_routing_strategy(&args, true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "--routing-providers or ROUTING_PROVIDERS env must be specified for roundrobin strategy"
+        );
+
+        let args = Args::parse_from(["producer", "--routing-strategy", "weighted"]);
+        let error = parse_routing_strategy(&args, true).unwrap_err();
+        assert!(matches!(error, RoutingParseError::MissingWeights { .. }));
+
+        let args = Args::parse_from(["producer", "--routing-strategy", "weighted", "--routing-weights", "openai:0"]);
+        assert_eq!(parse_routing_strategy(&args, true), Err(RoutingParseError::ZeroWeights));
+    }
+}
@@ -1,21 +1,82 @@
 //! HTTP API client for external providers
 
 use crate::error::{ProducerError, ProducerResult};
+use crate::services::pricing::{ModelPrice, PricingTable};
+use crate::services::recording::ResponseRecorder;
+use crate::services::request_cache::{CacheLookup, RequestCache};
 use crate::traits::ApiClient;
-use crate::types::{ApiRequest, ApiResponse};
+use crate::types::{ApiRequest, ApiResponse, FINISH_REASON_LENGTH};
 use async_trait::async_trait;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{Rng, SeedableRng};
 use reqwest::Client;
 use serde_json::{json, Value};
-use shared::{process_debug, process_info, process_error, ProcessId, ProviderId, TokenUsage};
-use std::collections::HashMap;
+use shared::{process_debug, process_info, process_error, process_warn, ApiFailure, ProcessId, ProviderId, TokenUsage};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use std::cmp;
 use std::env;
 use chrono::Utc;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 
+/// Default upper bound on a provider response body (10 MiB)
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Azure OpenAI REST API version used when AZURE_OPENAI_API_VERSION is unset
+pub const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
+
+/// Azure OpenAI resource and deployment that AzureOpenAI requests are sent to
+///
+/// Azure addresses models by deployment name in the URL rather than by a `model`
+/// field in the body, and pins the API surface with an `api-version` query parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureOpenAIConfig {
+    /// Resource name, the `{resource}` in `https://{resource}.openai.azure.com`
+    pub resource_name: String,
+    /// Deployment name the requests are routed to
+    pub deployment: String,
+    /// Value of the `api-version` query parameter
+    pub api_version: String,
+}
+
+impl AzureOpenAIConfig {
+    pub fn new(resource_name: impl Into<String>, deployment: impl Into<String>) -> Self {
+        Self {
+            resource_name: resource_name.into(),
+            deployment: deployment.into(),
+            api_version: DEFAULT_AZURE_API_VERSION.to_string(),
+        }
+    }
+
+    pub fn with_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Load from AZURE_OPENAI_RESOURCE_NAME, AZURE_OPENAI_DEPLOYMENT and AZURE_OPENAI_API_VERSION
+    ///
+    /// Returns None unless both the resource and the deployment are set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| env::var(name).ok().map(|value| value.trim().to_string()).filter(|v| !v.is_empty());
+        let config = Self::new(var("AZURE_OPENAI_RESOURCE_NAME")?, var("AZURE_OPENAI_DEPLOYMENT")?);
+        Some(match var("AZURE_OPENAI_API_VERSION") {
+            Some(api_version) => config.with_api_version(api_version),
+            None => config,
+        })
+    }
+
+    /// Chat completions URL for this deployment under the given base URL
+    fn chat_completions_url(&self, base_url: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            base_url, self.deployment, self.api_version
+        )
+    }
+}
+
 /// Word dictionary for Random provider (Shakespeare words from Hamlet)
 const RANDOM_WORDS: &[&str] = &[
     "abate",
@@ -3133,6 +3194,21 @@ pub struct RealApiClient {
     api_models: HashMap<ProviderId, String>,
     #[allow(dead_code)]
     request_timeout_ms: u64,
+    max_response_bytes: usize,
+    /// Base URL overrides, e.g. an OpenAI-compatible local server for OpenAI traffic
+    base_urls: HashMap<ProviderId, String>,
+    /// Source for Random provider output and backoff jitter; reseeded per run
+    rng: Mutex<StdRng>,
+    /// Resource and deployment for AzureOpenAI traffic; requests fail without it
+    azure: Option<AzureOpenAIConfig>,
+    /// Per-model prices from a pricing file; None uses the built-in prices silently
+    pricing: Option<PricingTable>,
+    /// Models already warned about as missing from `pricing`
+    unpriced_models: Mutex<HashSet<(ProviderId, String)>>,
+    /// Where request/response pairs are written for later replay; None records nothing
+    recorder: Option<ResponseRecorder>,
+    /// Recent responses reused for identical requests; None sends every request
+    request_cache: Option<RequestCache>,
 }
 
 impl RealApiClient {
@@ -3151,6 +3227,14 @@ impl RealApiClient {
             api_keys,
             api_models,
             request_timeout_ms,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            base_urls: HashMap::new(),
+            rng: Mutex::new(StdRng::from_entropy()),
+            azure: AzureOpenAIConfig::from_env(),
+            pricing: None,
+            unpriced_models: Mutex::new(HashSet::new()),
+            recorder: None,
+            request_cache: None,
         }
     }
 
@@ -3169,7 +3253,78 @@ impl RealApiClient {
             api_keys,
             api_models,
             request_timeout_ms,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            base_urls: HashMap::new(),
+            rng: Mutex::new(StdRng::from_entropy()),
+            azure: AzureOpenAIConfig::from_env(),
+            pricing: None,
+            unpriced_models: Mutex::new(HashSet::new()),
+            recorder: None,
+            request_cache: None,
+        }
+    }
+
+    /// Limit how many bytes of a provider response are read before it is rejected
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Route AzureOpenAI requests to the given resource and deployment
+    pub fn with_azure_openai(mut self, azure: AzureOpenAIConfig) -> Self {
+        self.azure = Some(azure);
+        self
+    }
+
+    /// Price requests from a pricing table instead of the built-in per-provider prices
+    pub fn with_pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Record every request/response pair under `dir` for replay by `RecordedApiClient`
+    pub fn with_response_recording(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.recorder = Some(ResponseRecorder::new(dir));
+        self
+    }
+
+    /// Answer repeats of a successful request from memory for `ttl` instead of calling the provider again
+    pub fn with_request_cache(mut self, ttl: Duration) -> Self {
+        self.request_cache = Some(RequestCache::new(ttl));
+        self
+    }
+
+    /// Price of the model this client sends a provider's requests to
+    ///
+    /// Models missing from the pricing table fall back to the built-in price,
+    /// with a warning the first time each one is seen.
+    fn model_price(&self, provider: ProviderId) -> ModelPrice {
+        let Some(ref pricing) = self.pricing else {
+            return ModelPrice::builtin(provider);
+        };
+        let model = self.get_model(provider);
+        if let Some(price) = pricing.price(provider, model) {
+            return price;
         }
+        if self.unpriced_models.lock().unwrap().insert((provider, model.to_string())) {
+            process_warn!(
+                ProcessId::current(),
+                "💲 No price for {}/{} in pricing table, using built-in {} prices",
+                provider,
+                model,
+                provider
+            );
+        }
+        ModelPrice::builtin(provider)
+    }
+
+    /// Send a provider's traffic to a different base URL (e.g. "http://localhost:11434/v1")
+    ///
+    /// Requests without an API key for an overridden provider are sent unauthenticated,
+    /// which is what local OpenAI-compatible servers expect.
+    pub fn with_base_url(mut self, provider: ProviderId, base_url: impl Into<String>) -> Self {
+        self.base_urls.insert(provider, base_url.into().trim_end_matches('/').to_string());
+        self
     }
 
     /// Load API keys from environment variables
@@ -3192,6 +3347,21 @@ impl RealApiClient {
             keys.insert(ProviderId::Gemini, key.trim().to_string());
         }
         
+        // Load Mistral key
+        if let Ok(key) = env::var("MISTRAL_API_KEY") {
+            keys.insert(ProviderId::Mistral, key.trim().to_string());
+        }
+        
+        // Load Cohere key
+        if let Ok(key) = env::var("COHERE_API_KEY") {
+            keys.insert(ProviderId::Cohere, key.trim().to_string());
+        }
+        
+        // Load Azure OpenAI key
+        if let Ok(key) = env::var("AZURE_OPENAI_API_KEY") {
+            keys.insert(ProviderId::AzureOpenAI, key.trim().to_string());
+        }
+        
         // Load Random provider key
         if let Ok(key) = env::var("RANDOM_API_KEY") {
             keys.insert(ProviderId::Random, key.trim().to_string());
@@ -3215,6 +3385,12 @@ impl RealApiClient {
         let gemini_model = env::var("GEMINI_API_MODEL").unwrap_or_else(|_| "gemini-2.5-flash".to_string());
         models.insert(ProviderId::Gemini, gemini_model.trim().to_string());
         
+        let mistral_model = env::var("MISTRAL_API_MODEL").unwrap_or_else(|_| "mistral-small-latest".to_string());
+        models.insert(ProviderId::Mistral, mistral_model.trim().to_string());
+        
+        let cohere_model = env::var("COHERE_API_MODEL").unwrap_or_else(|_| "command-r".to_string());
+        models.insert(ProviderId::Cohere, cohere_model.trim().to_string());
+        
         let random_model = env::var("RANDOM_API_MODEL").unwrap_or_else(|_| "random".to_string());
         models.insert(ProviderId::Random, random_model.trim().to_string());
         
@@ -3223,6 +3399,10 @@ impl RealApiClient {
 
     /// Get the model name for a provider
     fn get_model(&self, provider: ProviderId) -> &str {
+        // Azure serves whichever model the deployment was created with
+        if let (ProviderId::AzureOpenAI, Some(azure)) = (provider, &self.azure) {
+            return &azure.deployment;
+        }
         self.api_models.get(&provider)
             .map(|s| s.as_str())
             .unwrap_or_else(|| {
@@ -3231,20 +3411,54 @@ impl RealApiClient {
                     ProviderId::OpenAI => "gpt-4o-mini",
                     ProviderId::Anthropic => "claude-3-5-sonnet-20241022", 
                     ProviderId::Gemini => "gemini-2.5-flash",
+                    ProviderId::Mistral => "mistral-small-latest",
+                    ProviderId::Cohere => "command-r",
+                    ProviderId::AzureOpenAI => "gpt-4o-mini",
                     ProviderId::Random => "random",
                 }
             })
     }
 
-    /// Get API endpoint URL for provider
+    /// Model a request is sent to: its own override, else the provider's configured model
+    fn request_model<'a>(&'a self, provider: ProviderId, request: &'a ApiRequest) -> &'a str {
+        request.model.as_deref().unwrap_or_else(|| self.get_model(provider))
+    }
+
+    /// Get API endpoint URL for provider's configured model
+    #[cfg(test)]
     fn get_endpoint_url(&self, provider: ProviderId) -> String {
+        self.model_endpoint_url(provider, self.get_model(provider))
+    }
+
+    /// Get API endpoint URL for a provider's model (only Gemini puts the model in the URL)
+    fn model_endpoint_url(&self, provider: ProviderId, model: &str) -> String {
+        if let Some(base_url) = self.base_urls.get(&provider) {
+            return match provider {
+                ProviderId::Anthropic => format!("{}/messages", base_url),
+                ProviderId::Gemini => format!("{}/models/{}:generateContent", base_url, model),
+                ProviderId::Cohere => format!("{}/chat", base_url),
+                ProviderId::AzureOpenAI => match &self.azure {
+                    Some(azure) => azure.chat_completions_url(base_url),
+                    None => format!("{}/chat/completions", base_url),
+                },
+                _ => format!("{}/chat/completions", base_url),
+            };
+        }
+
         match provider {
             ProviderId::OpenAI => "https://api.openai.com/v1/chat/completions".to_string(),
             ProviderId::Anthropic => "https://api.anthropic.com/v1/messages".to_string(),
             ProviderId::Gemini => {
-                let model = self.get_model(provider);
                 format!("https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent", model)
             },
+            ProviderId::Mistral => "https://api.mistral.ai/v1/chat/completions".to_string(),
+            ProviderId::Cohere => "https://api.cohere.com/v2/chat".to_string(),
+            ProviderId::AzureOpenAI => match &self.azure {
+                Some(azure) => {
+                    azure.chat_completions_url(&format!("https://{}.openai.azure.com", azure.resource_name))
+                }
+                None => "azure://unconfigured".to_string(), // build_headers rejects the request
+            },
             ProviderId::Random => "local://random".to_string(), // Not used for HTTP requests
         }
     }
@@ -3259,6 +3473,10 @@ impl RealApiClient {
                 // Random provider doesn't need headers or API keys
                 return Ok(headers);
             }
+            _ if !self.api_keys.contains_key(&provider) && self.base_urls.contains_key(&provider) => {
+                // Keyless local endpoint
+                return Ok(headers);
+            }
             _ => {
                 let api_key = self
                     .api_keys
@@ -3288,6 +3506,37 @@ impl RealApiClient {
                     ProviderId::Gemini => {
                         // Gemini uses API key as URL parameter
                     }
+                    ProviderId::Mistral => {
+                        let auth_value = format!("Bearer {api_key}");
+                        headers.insert(
+                            "Authorization",
+                            auth_value
+                                .parse()
+                                .map_err(|_| ProducerError::config("Invalid Mistral API key format"))?,
+                        );
+                    }
+                    ProviderId::Cohere => {
+                        let auth_value = format!("Bearer {api_key}");
+                        headers.insert(
+                            "Authorization",
+                            auth_value
+                                .parse()
+                                .map_err(|_| ProducerError::config("Invalid Cohere API key format"))?,
+                        );
+                    }
+                    ProviderId::AzureOpenAI => {
+                        if self.azure.is_none() {
+                            return Err(ProducerError::config(
+                                "Azure OpenAI requires AZURE_OPENAI_RESOURCE_NAME and AZURE_OPENAI_DEPLOYMENT",
+                            ));
+                        }
+                        headers.insert(
+                            "api-key",
+                            api_key
+                                .parse()
+                                .map_err(|_| ProducerError::config("Invalid Azure OpenAI API key format"))?,
+                        );
+                    }
                     ProviderId::Random => unreachable!(), // Already handled above
                 }
             }
@@ -3305,38 +3554,77 @@ impl RealApiClient {
             request.prompt
         );
         
+        // Chat-style providers take the system prompt as its own message ahead of the user prompt
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = &request.system_prompt {
+            messages.push(json!({ "role": "system", "content": system_prompt }));
+        }
+        messages.push(json!({ "role": "user", "content": request.prompt }));
+
         match provider {
             ProviderId::OpenAI => json!({
-                "model": self.get_model(provider),  // Load from environment
-                "messages": [{
-                    "role": "user",
-                    "content": request.prompt
-                }],
+                "model": self.request_model(provider, request),  // Override or configured model
+                "messages": messages,
                 "max_tokens": request.max_tokens,
                 "temperature": request.temperature,
                 "n": 1,
                 "stream": false
             }),
-            ProviderId::Anthropic => json!({
-                "model": self.get_model(provider),  // Load from environment
+            ProviderId::Anthropic => {
+                let mut body = json!({
+                    "model": self.request_model(provider, request),  // Override or configured model
+                    "max_tokens": request.max_tokens,
+                    "temperature": request.temperature,
+                    "messages": [{
+                        "role": "user",
+                        "content": request.prompt
+                    }],
+                    "stream": false
+                });
+                // Anthropic takes system instructions as a top-level field, not a message
+                if let Some(system_prompt) = &request.system_prompt {
+                    body["system"] = json!(system_prompt);
+                }
+                body
+            }
+            ProviderId::Gemini => {
+                let mut body = json!({
+                    "contents": [{
+                        "parts": [{
+                            "text": request.prompt
+                        }]
+                    }],
+                    "generationConfig": {
+                        "maxOutputTokens": request.max_tokens,
+                        "temperature": request.temperature
+                    }
+                });
+                if let Some(system_prompt) = &request.system_prompt {
+                    body["systemInstruction"] = json!({ "parts": [{ "text": system_prompt }] });
+                }
+                body
+            }
+            ProviderId::Mistral => json!({
+                "model": self.request_model(provider, request),  // Override or configured model
+                "messages": messages,
                 "max_tokens": request.max_tokens,
                 "temperature": request.temperature,
-                "messages": [{
-                    "role": "user",
-                    "content": request.prompt
-                }],
                 "stream": false
             }),
-            ProviderId::Gemini => json!({
-                "contents": [{
-                    "parts": [{
-                        "text": request.prompt
-                    }]
-                }],
-                "generationConfig": {
-                    "maxOutputTokens": request.max_tokens,
-                    "temperature": request.temperature
-                }
+            ProviderId::Cohere => json!({
+                "model": self.request_model(provider, request),  // Override or configured model
+                "messages": messages,
+                "max_tokens": request.max_tokens,
+                "temperature": request.temperature,
+                "stream": false
+            }),
+            // The deployment in the URL selects the model, so the body carries none
+            ProviderId::AzureOpenAI => json!({
+                "messages": messages,
+                "max_tokens": request.max_tokens,
+                "temperature": request.temperature,
+                "n": 1,
+                "stream": false
             }),
             ProviderId::Random => json!({
                 // Random provider doesn't use request body for HTTP
@@ -3361,6 +3649,21 @@ impl RealApiClient {
                 .as_str()
                 .ok_or_else(|| ProducerError::api("Gemini", "Invalid response format"))
                 .map(|s| s.to_string()),
+            ProviderId::Mistral => response["choices"][0]["message"]["content"]
+                .as_str()
+                .ok_or_else(|| ProducerError::api("Mistral", "Invalid response format"))
+                .map(|s| s.to_string()),
+            ProviderId::AzureOpenAI => response["choices"][0]["message"]["content"]
+                .as_str()
+                .ok_or_else(|| match response["choices"][0]["finish_reason"].as_str() {
+                    Some("content_filter") => ProducerError::api("AzureOpenAI", "Response blocked by content filter"),
+                    _ => ProducerError::api("AzureOpenAI", "Invalid response format"),
+                })
+                .map(|s| s.to_string()),
+            ProviderId::Cohere => response["message"]["content"][0]["text"]
+                .as_str()
+                .ok_or_else(|| ProducerError::api("Cohere", "Invalid response format"))
+                .map(|s| s.to_string()),
             ProviderId::Random => {
                 // For Random provider, this should not be called since we handle it directly
                 Err(ProducerError::api(
@@ -3371,10 +3674,52 @@ impl RealApiClient {
         }
     }
 
+    /// Why a provider's safety filter blocked a response, if it did
+    ///
+    /// Gemini reports a blocked prompt through `promptFeedback.blockReason` and a blocked
+    /// answer as a candidate without text whose `finishReason` is `SAFETY` or similar,
+    /// which would otherwise pass for a successful empty response.
+    fn extract_safety_block(&self, provider: ProviderId, response: &Value) -> Option<String> {
+        if provider != ProviderId::Gemini {
+            return None;
+        }
+        if let Some(reason) = response["promptFeedback"]["blockReason"].as_str() {
+            return Some(format!("prompt blocked ({})", reason));
+        }
+
+        let candidate = &response["candidates"][0];
+        let has_text = candidate["content"]["parts"]
+            .as_array()
+            .is_some_and(|parts| parts.iter().any(|part| part["text"].as_str().is_some_and(|text| !text.trim().is_empty())));
+        match candidate["finishReason"].as_str() {
+            Some(reason @ ("SAFETY" | "PROHIBITED_CONTENT" | "BLOCKLIST" | "SPII")) if !has_text => {
+                Some(format!("response blocked ({})", reason))
+            }
+            _ => None,
+        }
+    }
+
+    /// Extract why the provider stopped, mapping each provider's max-tokens signal to `FINISH_REASON_LENGTH`
+    fn extract_finish_reason(&self, provider: ProviderId, response: &Value) -> Option<String> {
+        let reason = match provider {
+            ProviderId::OpenAI | ProviderId::Mistral | ProviderId::AzureOpenAI => {
+                response["choices"][0]["finish_reason"].as_str()
+            }
+            ProviderId::Anthropic => response["stop_reason"].as_str(),
+            ProviderId::Gemini => response["candidates"][0]["finishReason"].as_str(),
+            ProviderId::Cohere => response["finish_reason"].as_str(),
+            ProviderId::Random => None,
+        }?;
+        match reason {
+            "length" | "model_length" | "max_tokens" | "MAX_TOKENS" => Some(FINISH_REASON_LENGTH.to_string()),
+            other => Some(other.to_lowercase()),
+        }
+    }
+
     /// Extract detailed token usage from provider response
     fn extract_tokens(&self, provider: ProviderId, response: &Value) -> TokenUsage {
         match provider {
-            ProviderId::OpenAI => {
+            ProviderId::OpenAI | ProviderId::Mistral | ProviderId::AzureOpenAI => {
                 let input_tokens = response["usage"]["prompt_tokens"].as_u64().unwrap_or(0);
                 let output_tokens = response["usage"]["completion_tokens"].as_u64().unwrap_or(0);
                 TokenUsage { input_tokens, output_tokens }
@@ -3384,6 +3729,17 @@ impl RealApiClient {
                 let output_tokens = response["usage"]["output_tokens"].as_u64().unwrap_or(0);
                 TokenUsage { input_tokens, output_tokens }
             }
+            ProviderId::Cohere => {
+                // Prefer the tokens actually processed, falling back to the billed counts
+                let usage = &response["usage"];
+                let count = |field: &str| {
+                    usage["tokens"][field]
+                        .as_u64()
+                        .or_else(|| usage["billed_units"][field].as_u64())
+                        .unwrap_or(0)
+                };
+                TokenUsage { input_tokens: count("input_tokens"), output_tokens: count("output_tokens") }
+            }
             ProviderId::Gemini => {
                 // Gemini doesn't always return token counts, estimate from content length
                 let content = self.extract_content(provider, response).unwrap_or_default();
@@ -3393,24 +3749,18 @@ impl RealApiClient {
                 let output_tokens = total_estimate - input_tokens;
                 TokenUsage { input_tokens, output_tokens }
             }
-            ProviderId::Random => {
-                // Random provider token usage estimation (roughly 1 token per word)
-                let total_estimate = response["word_count"].as_u64().unwrap_or(100);
-                // Random provider: assume 50% input, 50% output for estimation
-                let input_tokens = total_estimate / 2;
-                let output_tokens = total_estimate - input_tokens;
-                TokenUsage { input_tokens, output_tokens }
-            }
+            // Random responses are built locally with exact usage and never parsed here
+            ProviderId::Random => TokenUsage::default(),
         }
     }
 
     /// Generate random words for Random provider
     fn generate_random_words(&self, max_tokens: u32) -> String {
         let word_count = cmp::min(max_tokens as usize, RANDOM_WORDS.len());
-        let mut rng = thread_rng();
+        let mut rng = self.rng.lock().unwrap();
 
         // Select random words without replacement
-        let selected_words: Vec<&str> = RANDOM_WORDS.choose_multiple(&mut rng, word_count).copied().collect();
+        let selected_words: Vec<&str> = RANDOM_WORDS.choose_multiple(&mut *rng, word_count).copied().collect();
 
         // Join with newlines so processor can extract individual attributes
         selected_words.join("\n")
@@ -3429,7 +3779,7 @@ impl RealApiClient {
         let response_time_ms = start_time.elapsed().as_millis() as u64;
 
         // Simulate realistic token usage for input/output, respecting max_tokens limit
-        let input_tokens = (request.prompt.len() / 4) as u32; // Rough estimate: 4 chars per token
+        let input_tokens = (request.combined_prompt().len() / 4) as u32; // Rough estimate: 4 chars per token
         let output_tokens = word_count;
         let total_tokens = cmp::min(input_tokens + output_tokens, request.max_tokens);
 
@@ -3448,6 +3798,11 @@ impl RealApiClient {
             timestamp: Utc::now(),
             success: true,
             error_message: None,
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
         })
     }
 }
@@ -3458,11 +3813,36 @@ impl RealApiClient {
         match provider {
             ProviderId::OpenAI => self.extract_openai_backoff_ms(body),
             ProviderId::Anthropic => self.extract_anthropic_backoff_ms(headers),
-            ProviderId::Gemini => self.extract_gemini_backoff_ms(headers, body),
+            // Mistral, Cohere and Azure OpenAI, like Gemini, only signal backoff through the standard retry-after header
+            ProviderId::Gemini | ProviderId::Mistral | ProviderId::Cohere | ProviderId::AzureOpenAI => {
+                self.extract_gemini_backoff_ms(headers, body)
+            }
             ProviderId::Random => None,
         }
     }
 
+    /// Classify an unsuccessful response, applying provider-specific error conventions
+    pub fn classify_failure(&self, provider: ProviderId, status: u16, body: &str) -> ApiFailure {
+        match provider {
+            // Cohere reports trial-key and per-minute caps in the message, not always with a 429
+            ProviderId::Cohere => {
+                let message = serde_json::from_str::<Value>(body)
+                    .ok()
+                    .and_then(|json| json["message"].as_str().map(str::to_lowercase))
+                    .unwrap_or_else(|| body.to_lowercase());
+                if ["rate limit", "too many requests", "api calls / minute"]
+                    .iter()
+                    .any(|phrase| message.contains(phrase))
+                {
+                    ApiFailure::RateLimitExceeded
+                } else {
+                    ApiFailure::from_http_status(status)
+                }
+            }
+            _ => ApiFailure::from_http_status(status),
+        }
+    }
+
     /// Extract OpenAI backoff from error message: "Please try again in 442ms"
     fn extract_openai_backoff_ms(&self, body: &str) -> Option<u32> {
         if let Ok(json) = serde_json::from_str::<Value>(body) {
@@ -3521,8 +3901,6 @@ impl RealApiClient {
 
     /// Calculate exponential backoff with jitter
     pub fn calculate_exponential_backoff_ms(&self, attempt: u32) -> u32 {
-        use rand::Rng;
-        
         let base_delay_ms = 1000u32; // 1 second base
         let max_delay_ms = 60_000u32; // 60 seconds max
         let multiplier: f64 = 2.0;
@@ -3532,8 +3910,7 @@ impl RealApiClient {
         let clamped_delay = exponential_delay.min(max_delay_ms);
         
         // Add jitter (±10%)
-        let mut rng = rand::thread_rng();
-        let jitter = rng.gen_range(0.9..1.1);
+        let jitter = self.rng.lock().unwrap().gen_range(0.9..1.1);
         (clamped_delay as f64 * jitter) as u32
     }
 
@@ -3547,14 +3924,16 @@ impl RealApiClient {
             return Err(ProducerError::api("Random", "Should not reach execute_request"));
         }
         
-        let url = self.get_endpoint_url(request.provider);
+        let url = self.model_endpoint_url(request.provider, self.request_model(request.provider, request));
         let headers = self.build_headers(request.provider)?;
         let body = self.build_request_body(request.provider, request);
         
         let request_builder = if request.provider == ProviderId::Gemini {
-            let api_key = self.api_keys.get(&request.provider)
-                .ok_or_else(|| ProducerError::config("Missing Gemini API key"))?;
-            self.client.post(&url).query(&[("key", api_key)])
+            match self.api_keys.get(&request.provider) {
+                Some(api_key) => self.client.post(&url).query(&[("key", api_key)]),
+                None if self.base_urls.contains_key(&request.provider) => self.client.post(&url),
+                None => return Err(ProducerError::config("Missing Gemini API key")),
+            }
         } else {
             self.client.post(&url)
         };
@@ -3567,7 +3946,7 @@ impl RealApiClient {
         );
         
         let response = request_builder.headers(headers).json(&body).send().await
-            .map_err(|e| ProducerError::api(&request.provider.to_string(), &format!("Request failed: {}", e)))?;
+            .map_err(|e| ProducerError::api(request.provider.to_string(), format!("Request failed: {}", e)))?;
             
         let response_time_ms = start_time.elapsed().as_millis() as u64;
         Ok((response, response_time_ms))
@@ -3584,7 +3963,10 @@ impl RealApiClient {
         
         if !status.is_success() {
             let headers = response.headers().clone();
-            let body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            let body = match self.read_body_limited(request.provider, response).await {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(_) => "Unknown error".to_string(),
+            };
             
             process_debug!(
                 ProcessId::current(),
@@ -3604,11 +3986,20 @@ impl RealApiClient {
             });
         }
         
-        let response_json: Value = response.json().await
-            .map_err(|e| ProducerError::api(&request.provider.to_string(), &format!("JSON parsing error: {}", e)))?;
-            
+        let body = self.read_body_limited(request.provider, response).await?;
+        let response_json: Value = serde_json::from_slice(&body)
+            .map_err(|e| ProducerError::api(request.provider.to_string(), format!("JSON parsing error: {}", e)))?;
+
+        if let Some(reason) = self.extract_safety_block(request.provider, &response_json) {
+            return Err(ProducerError::ContentFiltered {
+                provider: request.provider,
+                reason,
+            });
+        }
+
         let content = self.extract_content(request.provider, &response_json)?;
         let tokens_used = self.extract_tokens(request.provider, &response_json);
+        let finish_reason = self.extract_finish_reason(request.provider, &response_json);
         
         process_debug!(
             ProcessId::current(),
@@ -3629,9 +4020,48 @@ impl RealApiClient {
             timestamp: Utc::now(),
             success: true,
             error_message: None,
+            retry_after_ms: None,
+            failure: None,
+            finish_reason,
+            model: Some(self.request_model(request.provider, request).to_string()),
+            cached: false,
         })
     }
 
+    /// Read the response body, aborting as soon as it grows past `max_response_bytes`
+    async fn read_body_limited(&self, provider: ProviderId, mut response: reqwest::Response) -> ProducerResult<Vec<u8>> {
+        let limit = self.max_response_bytes;
+        let too_large = || {
+            ProducerError::api(
+                provider.to_string(),
+                format!("Response exceeded maximum size of {} bytes", limit),
+            )
+        };
+
+        // Reject up front when the server announces an oversized body
+        if response.content_length().is_some_and(|len| len > limit as u64) {
+            return Err(too_large());
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await
+            .map_err(|e| ProducerError::api(provider.to_string(), format!("Failed to read response: {}", e)))?
+        {
+            if body.len() + chunk.len() > limit {
+                process_error!(
+                    ProcessId::current(),
+                    "❌ Response from {:?} exceeded {} bytes - aborting read",
+                    provider,
+                    limit
+                );
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+
     /// Convert HeaderMap to HashMap for error passing
     pub fn headers_to_hashmap(&self, headers: &HeaderMap) -> HashMap<String, String> {
         let mut map = HashMap::new();
@@ -3655,12 +4085,26 @@ impl RealApiClient {
         }
         headers
     }
-}
 
-#[async_trait]
-impl ApiClient for RealApiClient {
-    /// Send request with automatic retry on rate limits
-    async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+    /// Send a request with retries, recording the final response if enabled
+    async fn send_and_record(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+        let Some(ref recorder) = self.recorder else {
+            return self.send_with_retries(request).await;
+        };
+        let response = self.send_with_retries(request.clone()).await?;
+        if let Err(e) = recorder.record(&request, &response).await {
+            process_warn!(
+                ProcessId::current(),
+                "⚠️ Failed to record response to {}: {}",
+                recorder.path(&request).display(),
+                e
+            );
+        }
+        Ok(response)
+    }
+
+    /// Send a request, retrying rate-limited attempts with backoff
+    async fn send_with_retries(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
         // Handle Random provider directly (no HTTP needed)
         if request.provider == ProviderId::Random {
             return self.handle_random_request(request, Instant::now()).await;
@@ -3675,6 +4119,7 @@ impl ApiClient for RealApiClient {
                     match self.parse_response(&request, response, response_time_ms).await {
                         Ok(api_response) => return Ok(api_response),
                         Err(ProducerError::RateLimit { provider, status, headers, body, .. }) => {
+                            let retry_after_ms = self.extract_backoff_ms(provider, status, &self.hashmap_to_headers(&headers), &body);
                             if attempt >= max_retries {
                                 return Ok(ApiResponse {
                                     provider: request.provider,
@@ -3685,11 +4130,15 @@ impl ApiClient for RealApiClient {
                                     timestamp: Utc::now(),
                                     success: false,
                                     error_message: Some(format!("Rate limit exceeded after {} retries", max_retries)),
+                                    retry_after_ms: retry_after_ms.map(u64::from),
+                                    failure: Some(self.classify_failure(provider, status, &body)),
+                                    finish_reason: None,
+                                    model: None,
+                                    cached: false,
                                 });
                             }
                             
-                            let backoff_ms = self.extract_backoff_ms(provider, status, &self.hashmap_to_headers(&headers), &body)
-                                .unwrap_or_else(|| self.calculate_exponential_backoff_ms(attempt));
+                            let backoff_ms = retry_after_ms.unwrap_or_else(|| self.calculate_exponential_backoff_ms(attempt));
                                 
                             process_info!(
                                 ProcessId::current(),
@@ -3710,6 +4159,11 @@ impl ApiClient for RealApiClient {
                                 timestamp: Utc::now(),
                                 success: false,
                                 error_message: Some(e.to_string()),
+                                retry_after_ms: None,
+                                failure: Some(e.api_failure()),
+                                finish_reason: None,
+                                model: None,
+                                cached: false,
                             });
                         }
                     }
@@ -3730,30 +4184,63 @@ impl ApiClient for RealApiClient {
                         timestamp: Utc::now(),
                         success: false,
                         error_message: Some(e.to_string()),
+                        retry_after_ms: None,
+                        failure: Some(e.api_failure()),
+                        finish_reason: None,
+                        model: None,
+                        cached: false,
                     });
                 }
             }
         }
     }
+}
+
+#[async_trait]
+impl ApiClient for RealApiClient {
+    /// Send request with automatic retry on rate limits, recording the final response if enabled
+    ///
+    /// With the request cache on, a repeat of a recent successful request is answered from it.
+    async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+        let Some(ref cache) = self.request_cache else {
+            return self.send_and_record(request).await;
+        };
+        loop {
+            match cache.lookup(&request) {
+                CacheLookup::Hit(response) => {
+                    process_debug!(ProcessId::current(), "♻️ Answered {:?} request from the request cache", request.provider);
+                    return Ok(response);
+                }
+                CacheLookup::Pending(mut receiver) => {
+                    // Closed without an answer when the identical request failed; look again
+                    if let Ok(shared) = receiver.wait_for(Option::is_some).await {
+                        if let Some(response) = shared.as_ref() {
+                            process_debug!(ProcessId::current(), "♻️ Answered {:?} request from an identical one in flight", request.provider);
+                            return Ok(RequestCache::answer(&request, response));
+                        }
+                    }
+                }
+                CacheLookup::Miss(in_flight) => {
+                    let response = self.send_and_record(request.clone()).await?;
+                    in_flight.complete(&request, &response);
+                    return Ok(response);
+                }
+            }
+        }
+    }
 
     async fn health_check(&self, provider: ProviderId) -> ProducerResult<bool> {
         // Simple health check - verify we have API key for all providers (including Random for consistency)
-        Ok(self.api_keys.contains_key(&provider))
+        // A base URL override counts too, since local endpoints usually need no key
+        Ok(self.api_keys.contains_key(&provider) || self.base_urls.contains_key(&provider))
     }
 
-    fn estimate_cost(&self, provider: ProviderId, tokens: &TokenUsage) -> f64 {
-        // Updated cost estimates (per 1K tokens) based on 2025 pricing
-        let (input_cost_per_1k, output_cost_per_1k) = match provider {
-            ProviderId::OpenAI => (0.00015, 0.0006),   // GPT-4o-mini: $0.15/1M input, $0.60/1M output
-            ProviderId::Anthropic => (0.003, 0.015),   // Claude-3.5 Sonnet: $3/1M input, $15/1M output  
-            ProviderId::Gemini => (0.000075, 0.0003),  // Gemini 2.5 Flash: $0.075/1M input, $0.30/1M output
-            ProviderId::Random => (0.0001, 0.0001),    // Random provider minimal cost for testing
-        };
+    fn reseed(&self, seed: u64) {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+    }
 
-        let input_cost = (tokens.input_tokens as f64 / 1000.0) * input_cost_per_1k;
-        let output_cost = (tokens.output_tokens as f64 / 1000.0) * output_cost_per_1k;
-        
-        input_cost + output_cost
+    fn estimate_cost(&self, provider: ProviderId, tokens: &TokenUsage) -> f64 {
+        self.model_price(provider).cost(tokens)
     }
 }
 
@@ -3767,6 +4254,9 @@ mod tests {
         keys.insert(ProviderId::OpenAI, "test-openai-key".to_string());
         keys.insert(ProviderId::Anthropic, "test-anthropic-key".to_string());
         keys.insert(ProviderId::Gemini, "test-gemini-key".to_string());
+        keys.insert(ProviderId::Mistral, "test-mistral-key".to_string());
+        keys.insert(ProviderId::Cohere, "test-cohere-key".to_string());
+        keys.insert(ProviderId::AzureOpenAI, "test-azure-key".to_string());
         keys.insert(ProviderId::Random, "random".to_string());
         keys
     }
@@ -3775,6 +4265,8 @@ mod tests {
         ApiRequest {
             provider,
             prompt: "Test prompt".to_string(),
+            system_prompt: None,
+            model: None,
             max_tokens: 100,
             temperature: 0.7,
             request_id: Uuid::new_v4(),
@@ -3830,6 +4322,21 @@ mod tests {
         assert!((client.estimate_cost(ProviderId::OpenAI, &tokens_mixed) - expected_openai_mixed).abs() < 0.000001);
     }
 
+    #[test]
+    fn test_cost_estimation_uses_pricing_table_for_active_model() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+        let openai_model = client.get_model(ProviderId::OpenAI).to_string();
+        let pricing = PricingTable::new().with_price(ProviderId::OpenAI, openai_model, ModelPrice::new(0.01, 0.02));
+        let client = client.with_pricing(pricing);
+
+        let tokens = TokenUsage { input_tokens: 1000, output_tokens: 1000 };
+        assert!((client.estimate_cost(ProviderId::OpenAI, &tokens) - 0.03).abs() < 1e-12);
+
+        // Models missing from the table keep the built-in price
+        let builtin = ModelPrice::builtin(ProviderId::Anthropic).cost(&tokens);
+        assert_eq!(client.estimate_cost(ProviderId::Anthropic, &tokens), builtin);
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         let client = RealApiClient::new(create_test_api_keys(), 30000);
@@ -3916,4 +4423,390 @@ mod tests {
         assert_eq!(tokens.input_tokens, 0);
         assert_eq!(tokens.output_tokens, 0);
     }
+
+    async fn serve_body(body: String) -> (wiremock::MockServer, String) {
+        use wiremock::{matchers::method, Mock, ResponseTemplate};
+
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .mount(&server)
+            .await;
+        let uri = server.uri();
+        (server, uri)
+    }
+
+    #[tokio::test]
+    async fn test_oversized_response_is_rejected_at_limit() {
+        ProcessId::init_producer(1);
+        let client = RealApiClient::new(create_test_api_keys(), 30000).with_max_response_bytes(1024);
+        let content = "x".repeat(4096);
+        let body = json!({ "choices": [{ "message": { "content": content } }] }).to_string();
+        let (_server, uri) = serve_body(body).await;
+
+        let response = client.client.get(&uri).send().await.unwrap();
+        let result = client
+            .parse_response(&create_test_request(ProviderId::OpenAI), response, 5)
+            .await;
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("exceeded maximum size of 1024 bytes"), "unexpected error: {error}");
+    }
+
+    #[tokio::test]
+    async fn test_mistral_chat_completion_is_parsed() {
+        ProcessId::init_producer(1);
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+        let body = json!({
+            "choices": [{ "message": { "role": "assistant", "content": "otter\nlynx" } }],
+            "usage": { "prompt_tokens": 42, "completion_tokens": 7, "total_tokens": 49 }
+        })
+        .to_string();
+        let (_server, uri) = serve_body(body).await;
+
+        let response = client.client.get(&uri).send().await.unwrap();
+        let api_response = client
+            .parse_response(&create_test_request(ProviderId::Mistral), response, 5)
+            .await
+            .unwrap();
+
+        assert!(api_response.success);
+        assert_eq!(api_response.content, "otter\nlynx");
+        assert_eq!(api_response.tokens_used.input_tokens, 42);
+        assert_eq!(api_response.tokens_used.output_tokens, 7);
+        assert!(client.estimate_cost(ProviderId::Mistral, &api_response.tokens_used) > 0.0);
+    }
+
+    #[test]
+    fn test_mistral_request_format() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+
+        assert_eq!(
+            client.get_endpoint_url(ProviderId::Mistral),
+            "https://api.mistral.ai/v1/chat/completions"
+        );
+        let headers = client.build_headers(ProviderId::Mistral).unwrap();
+        assert_eq!(headers["Authorization"], "Bearer test-mistral-key");
+
+        let body = client.build_request_body(ProviderId::Mistral, &create_test_request(ProviderId::Mistral));
+        assert_eq!(body["model"], "mistral-small-latest");
+        assert_eq!(body["messages"][0]["content"], "Test prompt");
+        assert_eq!(body["max_tokens"], 100);
+    }
+
+    #[tokio::test]
+    async fn test_cohere_chat_response_is_parsed() {
+        ProcessId::init_producer(1);
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+        let body = json!({
+            "id": "c14c80c3",
+            "finish_reason": "COMPLETE",
+            "message": { "role": "assistant", "content": [{ "type": "text", "text": "puffin\ntern" }] },
+            "usage": {
+                "billed_units": { "input_tokens": 40, "output_tokens": 6 },
+                "tokens": { "input_tokens": 45, "output_tokens": 6 }
+            }
+        })
+        .to_string();
+        let (_server, uri) = serve_body(body).await;
+
+        let response = client.client.get(&uri).send().await.unwrap();
+        let api_response = client
+            .parse_response(&create_test_request(ProviderId::Cohere), response, 5)
+            .await
+            .unwrap();
+
+        assert!(api_response.success);
+        assert_eq!(api_response.content, "puffin\ntern");
+        assert_eq!(api_response.tokens_used.input_tokens, 45);
+        assert_eq!(api_response.tokens_used.output_tokens, 6);
+        assert!(client.estimate_cost(ProviderId::Cohere, &api_response.tokens_used) > 0.0);
+    }
+
+    #[test]
+    fn test_cohere_request_format() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+
+        assert_eq!(client.get_endpoint_url(ProviderId::Cohere), "https://api.cohere.com/v2/chat");
+        let headers = client.build_headers(ProviderId::Cohere).unwrap();
+        assert_eq!(headers["Authorization"], "Bearer test-cohere-key");
+
+        let body = client.build_request_body(ProviderId::Cohere, &create_test_request(ProviderId::Cohere));
+        assert_eq!(body["model"], "command-r");
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["messages"][0]["content"], "Test prompt");
+        assert_eq!(body["max_tokens"], 100);
+    }
+
+    #[test]
+    fn test_system_prompt_is_sent_with_the_system_role() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+        let request = ApiRequest {
+            system_prompt: Some("Answer in English only".to_string()),
+            ..create_test_request(ProviderId::OpenAI)
+        };
+
+        for provider in [ProviderId::OpenAI, ProviderId::AzureOpenAI, ProviderId::Mistral, ProviderId::Cohere] {
+            let body = client.build_request_body(provider, &request);
+            assert_eq!(body["messages"][0]["role"], "system", "{provider}");
+            assert_eq!(body["messages"][0]["content"], "Answer in English only", "{provider}");
+            assert_eq!(body["messages"][1]["role"], "user", "{provider}");
+            assert_eq!(body["messages"][1]["content"], "Test prompt", "{provider}");
+        }
+
+        let anthropic = client.build_request_body(ProviderId::Anthropic, &request);
+        assert_eq!(anthropic["system"], "Answer in English only");
+        assert_eq!(anthropic["messages"][0]["role"], "user");
+        let gemini = client.build_request_body(ProviderId::Gemini, &request);
+        assert_eq!(gemini["systemInstruction"]["parts"][0]["text"], "Answer in English only");
+
+        // Without a system prompt only the user message is sent
+        let body = client.build_request_body(ProviderId::OpenAI, &create_test_request(ProviderId::OpenAI));
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert!(client
+            .build_request_body(ProviderId::Anthropic, &create_test_request(ProviderId::Anthropic))
+            .get("system")
+            .is_none());
+        assert_eq!(request.combined_prompt(), "Answer in English only\n\nTest prompt");
+    }
+
+    #[test]
+    fn test_azure_openai_request_targets_deployment() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000)
+            .with_azure_openai(AzureOpenAIConfig::new("contoso", "attr-gen").with_api_version("2024-06-01"));
+
+        assert_eq!(
+            client.get_endpoint_url(ProviderId::AzureOpenAI),
+            "https://contoso.openai.azure.com/openai/deployments/attr-gen/chat/completions?api-version=2024-06-01"
+        );
+        let headers = client.build_headers(ProviderId::AzureOpenAI).unwrap();
+        assert_eq!(headers["api-key"], "test-azure-key");
+        assert!(!headers.contains_key("Authorization"));
+
+        let body = client.build_request_body(ProviderId::AzureOpenAI, &create_test_request(ProviderId::AzureOpenAI));
+        assert!(body.get("model").is_none());
+        assert_eq!(body["messages"][0]["content"], "Test prompt");
+        assert_eq!(body["max_tokens"], 100);
+    }
+
+    #[tokio::test]
+    async fn test_azure_openai_response_is_parsed() {
+        ProcessId::init_producer(1);
+        let client = RealApiClient::new(create_test_api_keys(), 30000)
+            .with_azure_openai(AzureOpenAIConfig::new("contoso", "attr-gen"));
+        let body = json!({
+            "id": "chatcmpl-9x",
+            "object": "chat.completion",
+            "model": "gpt-4o-mini-2024-07-18",
+            "prompt_filter_results": [{ "prompt_index": 0, "content_filter_results": {} }],
+            "choices": [{
+                "index": 0,
+                "finish_reason": "stop",
+                "message": { "role": "assistant", "content": "ibis\nkiwi" },
+                "content_filter_results": {}
+            }],
+            "usage": { "prompt_tokens": 52, "completion_tokens": 4, "total_tokens": 56 }
+        })
+        .to_string();
+        let (_server, uri) = serve_body(body).await;
+
+        let response = client.client.get(&uri).send().await.unwrap();
+        let api_response = client
+            .parse_response(&create_test_request(ProviderId::AzureOpenAI), response, 5)
+            .await
+            .unwrap();
+
+        assert_eq!(api_response.content, "ibis\nkiwi");
+        assert_eq!(api_response.tokens_used.input_tokens, 52);
+        assert_eq!(api_response.tokens_used.output_tokens, 4);
+        assert!(client.estimate_cost(ProviderId::AzureOpenAI, &api_response.tokens_used) > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_gemini_safety_block_is_classified_as_content_filtered() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, ResponseTemplate};
+
+        ProcessId::init_producer(1);
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "candidates": [{
+                    "content": { "role": "model" },
+                    "finishReason": "SAFETY",
+                    "index": 0,
+                    "safetyRatings": [{ "category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH" }]
+                }],
+                "usageMetadata": { "promptTokenCount": 18, "totalTokenCount": 18 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = RealApiClient::new(create_test_api_keys(), 30000).with_base_url(ProviderId::Gemini, server.uri());
+        let response = client.send_request(create_test_request(ProviderId::Gemini)).await.unwrap();
+
+        assert!(!response.success);
+        assert!(response.content.is_empty());
+        assert_eq!(response.failure, Some(ApiFailure::ContentFiltered));
+        let message = response.error_message.unwrap();
+        assert!(message.contains("SAFETY"), "unexpected error: {message}");
+
+        // A blocked candidate that still carries text is kept
+        let partial = json!({ "candidates": [{ "content": { "parts": [{ "text": "ibis" }] }, "finishReason": "SAFETY" }] });
+        assert_eq!(client.extract_safety_block(ProviderId::Gemini, &partial), None);
+        let prompt_blocked = json!({ "promptFeedback": { "blockReason": "PROHIBITED_CONTENT" } });
+        assert!(client.extract_safety_block(ProviderId::Gemini, &prompt_blocked).is_some());
+    }
+
+    #[test]
+    fn test_cohere_rate_limit_message_is_classified() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+        let trial_limit = r#"{"message":"You are using a Trial key, which is limited to 10 API calls / minute."}"#;
+
+        assert_eq!(
+            client.classify_failure(ProviderId::Cohere, 400, trial_limit),
+            ApiFailure::RateLimitExceeded
+        );
+        assert_eq!(
+            client.classify_failure(ProviderId::Cohere, 401, r#"{"message":"invalid api token"}"#),
+            ApiFailure::from_http_status(401)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_openai_base_url_override_reaches_local_server_without_key() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        ProcessId::init_producer(1);
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "content": "heron\nibis" } }],
+                "usage": { "prompt_tokens": 3, "completion_tokens": 2 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = RealApiClient::new(HashMap::new(), 30000)
+            .with_base_url(ProviderId::OpenAI, format!("{}/v1/", server.uri()));
+        assert_eq!(
+            client.get_endpoint_url(ProviderId::OpenAI),
+            format!("{}/v1/chat/completions", server.uri())
+        );
+        assert!(client.health_check(ProviderId::OpenAI).await.unwrap());
+
+        let response = client.send_request(create_test_request(ProviderId::OpenAI)).await.unwrap();
+        assert!(response.success, "error: {:?}", response.error_message);
+        assert_eq!(response.content, "heron\nibis");
+
+        let received = server.received_requests().await.unwrap();
+        assert!(received[0]
+            .headers
+            .keys()
+            .all(|name| !name.as_str().eq_ignore_ascii_case("authorization")));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_request_is_answered_from_cache() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, ResponseTemplate};
+
+        ProcessId::init_producer(1);
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "content": "heron\nibis" } }],
+                "usage": { "prompt_tokens": 3, "completion_tokens": 2 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = RealApiClient::new(HashMap::new(), 30000)
+            .with_base_url(ProviderId::OpenAI, server.uri())
+            .with_request_cache(Duration::from_secs(60));
+        let first = client.send_request(create_test_request(ProviderId::OpenAI)).await.unwrap();
+        let repeat = create_test_request(ProviderId::OpenAI);
+        let second = client.send_request(repeat.clone()).await.unwrap();
+
+        assert!(!first.cached);
+        assert!(second.cached && second.success);
+        assert_eq!(second.content, first.content);
+        assert_eq!(second.request_id, repeat.request_id);
+        assert_eq!(client.estimate_cost(ProviderId::OpenAI, &second.tokens_used), 0.0);
+
+        let mut metrics = crate::core::Metrics::new();
+        metrics.record_response_received(&first);
+        metrics.record_response_received(&second);
+        assert_eq!(metrics.get_current_metrics().cache_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_identical_requests_in_flight_are_sent_once() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, ResponseTemplate};
+
+        ProcessId::init_producer(1);
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                        "choices": [{ "message": { "content": "heron\nibis" } }],
+                        "usage": { "prompt_tokens": 3, "completion_tokens": 2 }
+                    }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = RealApiClient::new(HashMap::new(), 30000)
+            .with_base_url(ProviderId::OpenAI, server.uri())
+            .with_request_cache(Duration::from_secs(60));
+        let (first, second) = tokio::join!(
+            client.send_request(create_test_request(ProviderId::OpenAI)),
+            client.send_request(create_test_request(ProviderId::OpenAI)),
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert!(first.success && second.success);
+        assert_eq!(first.content, second.content);
+        assert_eq!([first.cached, second.cached].iter().filter(|cached| **cached).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_response_within_limit_is_parsed() {
+        ProcessId::init_producer(1);
+        let client = RealApiClient::new(create_test_api_keys(), 30000).with_max_response_bytes(1024);
+        let body = json!({ "choices": [{ "message": { "content": "cat\ndog" } }] }).to_string();
+        let (_server, uri) = serve_body(body).await;
+
+        let response = client.client.get(&uri).send().await.unwrap();
+        let api_response = client
+            .parse_response(&create_test_request(ProviderId::OpenAI), response, 5)
+            .await
+            .unwrap();
+
+        assert!(api_response.success);
+        assert_eq!(api_response.content, "cat\ndog");
+    }
+
+    #[test]
+    fn test_reseed_makes_random_output_and_jitter_reproducible() {
+        let client = RealApiClient::new(create_test_api_keys(), 30000);
+        let sample = |seed: u64| {
+            client.reseed(seed);
+            let words = client.generate_random_words(20);
+            let backoffs: Vec<u32> = (0..5).map(|attempt| client.calculate_exponential_backoff_ms(attempt)).collect();
+            (words, backoffs)
+        };
+
+        assert_eq!(sample(7), sample(7));
+        assert_ne!(sample(7).0, sample(8).0);
+    }
 }
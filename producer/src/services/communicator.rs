@@ -6,11 +6,24 @@ use async_trait::async_trait;
 use shared::{ProducerCommand, ProducerUpdate};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{mpsc, Mutex, RwLock};
 use shared::{process_debug, process_error, process_info, process_warn, ProcessId};
 
+/// Reconnection attempts made once an update can't be delivered, before the producer gives up
+pub const DEFAULT_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Delay before the first reconnection attempt in milliseconds; it doubles after each failure
+pub const DEFAULT_RECONNECT_BASE_MS: u64 = 250;
+
+/// Longest wait between two reconnection attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// Start of the error returned once reconnection has given up; the producer terminates on it
+pub const ORCHESTRATOR_UNREACHABLE: &str = "Failed to send update after";
+
 /// Connection state for the communicator
 #[derive(Clone)]
 pub struct ConnectionState {
@@ -27,6 +40,9 @@ pub struct RealCommunicator {
     listen_port: Option<u16>,
     standalone_mode: bool,
     producer_id: shared::ProcessId,
+    reconnect_attempts: u32,
+    reconnect_base_delay: Duration,
+    reconnecting: Arc<Mutex<()>>,
 }
 
 impl RealCommunicator {
@@ -45,6 +61,9 @@ impl RealCommunicator {
             listen_port: None,
             standalone_mode: false,
             producer_id,
+            reconnect_attempts: DEFAULT_RECONNECT_ATTEMPTS,
+            reconnect_base_delay: Duration::from_millis(DEFAULT_RECONNECT_BASE_MS),
+            reconnecting: Arc::new(Mutex::new(())),
         }
     }
 
@@ -65,6 +84,9 @@ impl RealCommunicator {
             listen_port: None,
             standalone_mode: true,
             producer_id,
+            reconnect_attempts: DEFAULT_RECONNECT_ATTEMPTS,
+            reconnect_base_delay: Duration::from_millis(DEFAULT_RECONNECT_BASE_MS),
+            reconnecting: Arc::new(Mutex::new(())),
         }
     }
 
@@ -83,9 +105,19 @@ impl RealCommunicator {
             listen_port: Some(listen_port),
             standalone_mode: false,
             producer_id,
+            reconnect_attempts: DEFAULT_RECONNECT_ATTEMPTS,
+            reconnect_base_delay: Duration::from_millis(DEFAULT_RECONNECT_BASE_MS),
+            reconnecting: Arc::new(Mutex::new(())),
         }
     }
 
+    /// Set how often and how patiently to reconnect when the orchestrator stops accepting updates
+    pub fn with_reconnect(mut self, attempts: u32, base_delay: Duration) -> Self {
+        self.reconnect_attempts = attempts;
+        self.reconnect_base_delay = base_delay;
+        self
+    }
+
     /// Get the listen port (if any)
     pub fn get_listen_port(&self) -> Option<u16> {
         self.listen_port
@@ -252,6 +284,73 @@ impl RealCommunicator {
         );
         Err(last_error.unwrap_or_else(|| ProducerError::ipc("Max retries exceeded")))
     }
+
+    /// Deliver one update over a fresh connection, without retrying
+    async fn send_once(&self, update: &ProducerUpdate) -> ProducerResult<()> {
+        let mut stream = TcpStream::connect(self.connection.orchestrator_addr)
+            .await
+            .map_err(|e| ProducerError::ipc(format!("Failed to connect: {e}")))?;
+        Self::write(&mut stream, update).await
+    }
+
+    /// Wait for the orchestrator to come back, re-announce this producer, then deliver the update
+    ///
+    /// Attempts back off exponentially from the configured base delay. A restarted
+    /// orchestrator no longer knows this producer, so `Ready` is sent before the update.
+    async fn reconnect_and_send(&self, update: ProducerUpdate, error: ProducerError) -> ProducerResult<()> {
+        // One caller reconnects at a time; the others find the orchestrator back once it is done
+        let _reconnecting = self.reconnecting.lock().await;
+        if self.send_once(&update).await.is_ok() {
+            return Ok(());
+        }
+
+        let mut last_error = error;
+        let mut delay = self.reconnect_base_delay;
+        for attempt in 1..=self.reconnect_attempts {
+            process_warn!(
+                ProcessId::current(),
+                "🔌 Orchestrator unreachable, reconnecting in {}ms (attempt {}/{}): {}",
+                delay.as_millis(),
+                attempt,
+                self.reconnect_attempts,
+                last_error
+            );
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+
+            match self.announce_and_send(&update).await {
+                Ok(()) => {
+                    process_info!(ProcessId::current(), "✅ Reconnected to orchestrator after {} attempts", attempt);
+                    return Ok(());
+                }
+                Err(e) => last_error = e,
+            }
+        }
+
+        process_error!(
+            ProcessId::current(),
+            "❌ Gave up reconnecting to orchestrator after {} attempts",
+            self.reconnect_attempts
+        );
+        Err(ProducerError::ipc(format!(
+            "{ORCHESTRATOR_UNREACHABLE} {} reconnection attempts: {last_error}",
+            self.reconnect_attempts
+        )))
+    }
+
+    /// Send `Ready` (when listening for commands) followed by the update
+    async fn announce_and_send(&self, update: &ProducerUpdate) -> ProducerResult<()> {
+        let announce = self.listen_port.filter(|_| !matches!(update, ProducerUpdate::Ready { .. }));
+        if let Some(listen_port) = announce {
+            let ready = ProducerUpdate::Ready {
+                producer_id: self.producer_id.clone(),
+                listen_port,
+                protocol_version: shared::PROTOCOL_VERSION,
+            };
+            self.send_once(&ready).await?;
+        }
+        self.send_once(update).await
+    }
 }
 
 #[async_trait]
@@ -319,6 +418,7 @@ impl Communicator for RealCommunicator {
             let ready_msg = shared::ProducerUpdate::Ready {
                 producer_id: self.producer_id.clone(),
                 listen_port: port,
+                protocol_version: shared::PROTOCOL_VERSION,
             };
 
             process_info!(ProcessId::current(), "📤 Sending ready signal to orchestrator");
@@ -376,8 +476,11 @@ impl Communicator for RealCommunicator {
             process_debug!(ProcessId::current(), "📤 Ignoring update in standalone mode: {:?}", update);
             Ok(())
         } else {
-            // IPC mode: Send update to orchestrator with retry logic
-            self.send_update_with_retries(update, 3).await
+            // IPC mode: Send update to orchestrator with retry logic, reconnecting if it went away
+            match self.send_update_with_retries(update.clone(), 3).await {
+                Ok(()) => Ok(()),
+                Err(e) => self.reconnect_and_send(update, e).await,
+            }
         }
     }
 
@@ -435,15 +538,49 @@ mod tests {
     async fn test_send_update_without_connection() {
         let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 6001);
         let producer_id = ProcessId::Producer(1);
-        let communicator = RealCommunicator::new(addr, producer_id);
+        let communicator = RealCommunicator::new(addr, producer_id).with_reconnect(1, Duration::from_millis(10));
 
         let update = shared::ProducerUpdate::Pong {
             producer_id: ProcessId::Producer(1),
             ping_id: 1,
         };
 
-        // Should fail when not connected
-        assert!(communicator.send_update(update).await.is_err());
+        // Should fail when not connected, with the error the producer terminates on
+        let error = communicator.send_update(update).await.unwrap_err();
+        assert!(error.to_string().contains(ORCHESTRATOR_UNREACHABLE));
+    }
+
+    #[tokio::test]
+    async fn test_send_update_reconnects_and_reannounces_when_orchestrator_returns() {
+        ProcessId::init_producer(1);
+        // Bound but not listening: connections are refused, yet no other test can take the port
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = socket.local_addr().unwrap();
+        let communicator = RealCommunicator::with_listen_port(addr, 7123, ProcessId::Producer(1))
+            .with_reconnect(10, Duration::from_millis(50));
+
+        // The orchestrator comes back while the producer is backing off
+        let orchestrator = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(400)).await;
+            let listener = socket.listen(16).unwrap();
+            let mut received = Vec::new();
+            while received.len() < 2 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                received.push(RealCommunicator::read::<ProducerUpdate>(&mut stream).await.unwrap());
+            }
+            received
+        });
+
+        let update = ProducerUpdate::Pong {
+            producer_id: ProcessId::Producer(1),
+            ping_id: 5,
+        };
+        communicator.send_update(update).await.unwrap();
+
+        let received = orchestrator.await.unwrap();
+        assert!(matches!(received[0], ProducerUpdate::Ready { listen_port: 7123, .. }));
+        assert!(matches!(received[1], ProducerUpdate::Pong { ping_id: 5, .. }));
     }
 
     #[tokio::test]
@@ -2,6 +2,12 @@
 
 pub mod api_client;
 pub mod communicator;
+pub mod pricing;
+pub mod recording;
+pub mod request_cache;
 
-pub use api_client::RealApiClient;
+pub use api_client::{AzureOpenAIConfig, RealApiClient};
 pub use communicator::RealCommunicator;
+pub use pricing::{ModelPrice, PricingTable};
+pub use recording::{RecordedApiClient, ResponseRecorder};
+pub use request_cache::RequestCache;
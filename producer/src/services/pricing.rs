@@ -0,0 +1,156 @@
+//! Per-model token prices used for cost estimates
+//!
+//! A pricing file is a JSON object keyed by provider CLI name, then model:
+//!
+//! ```json
+//! { "openai": { "gpt-4o-mini": { "input_per_1k": 0.00015, "output_per_1k": 0.0006 } } }
+//! ```
+//!
+//! Models missing from the table fall back to the built-in per-provider prices.
+
+use crate::error::{ProducerError, ProducerResult};
+use serde::Deserialize;
+use shared::{ProviderId, TokenUsage};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// USD price per 1,000 input and output tokens
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_1k: f64,
+    pub output_per_1k: f64,
+}
+
+impl ModelPrice {
+    pub fn new(input_per_1k: f64, output_per_1k: f64) -> Self {
+        Self { input_per_1k, output_per_1k }
+    }
+
+    /// Built-in price for a provider's default model (2025 list prices)
+    pub fn builtin(provider: ProviderId) -> Self {
+        match provider {
+            ProviderId::OpenAI => Self::new(0.00015, 0.0006), // GPT-4o-mini: $0.15/1M input, $0.60/1M output
+            ProviderId::Anthropic => Self::new(0.003, 0.015), // Claude-3.5 Sonnet: $3/1M input, $15/1M output
+            ProviderId::Gemini => Self::new(0.000075, 0.0003), // Gemini 2.5 Flash: $0.075/1M input, $0.30/1M output
+            ProviderId::Mistral => Self::new(0.0002, 0.0006), // Mistral Small: $0.20/1M input, $0.60/1M output
+            ProviderId::Cohere => Self::new(0.00015, 0.0006), // Command R: $0.15/1M input, $0.60/1M output
+            ProviderId::AzureOpenAI => Self::new(0.000165, 0.00066), // Azure GPT-4o-mini global: $0.165/1M input, $0.66/1M output
+            ProviderId::Random => Self::new(0.0001, 0.0001), // Random provider minimal cost for testing
+        }
+    }
+
+    /// Cost of the given token usage at this price
+    pub fn cost(&self, tokens: &TokenUsage) -> f64 {
+        (tokens.input_tokens as f64 / 1000.0) * self.input_per_1k
+            + (tokens.output_tokens as f64 / 1000.0) * self.output_per_1k
+    }
+
+    fn validate(&self, provider: ProviderId, model: &str) -> ProducerResult<()> {
+        for (name, value) in [("input_per_1k", self.input_per_1k), ("output_per_1k", self.output_per_1k)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ProducerError::config(format!(
+                    "Invalid {} for {}/{}: {} (must be a non-negative number)",
+                    name, provider, model, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Token prices per provider and model
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PricingTable {
+    prices: HashMap<ProviderId, HashMap<String, ModelPrice>>,
+}
+
+impl PricingTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the price of one model
+    pub fn with_price(mut self, provider: ProviderId, model: impl Into<String>, price: ModelPrice) -> Self {
+        self.prices.entry(provider).or_default().insert(model.into(), price);
+        self
+    }
+
+    /// Parse and validate a pricing table from JSON
+    pub fn from_json(json: &str) -> ProducerResult<Self> {
+        let raw: HashMap<String, HashMap<String, ModelPrice>> = serde_json::from_str(json)?;
+        let mut table = Self::new();
+        for (provider_name, models) in raw {
+            let provider: ProviderId = provider_name
+                .parse()
+                .map_err(|e| ProducerError::config(format!("Invalid pricing provider '{}': {}", provider_name, e)))?;
+            for (model, price) in models {
+                price.validate(provider, &model)?;
+                table = table.with_price(provider, model, price);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Read and validate a pricing file
+    pub fn load(path: impl AsRef<Path>) -> ProducerResult<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ProducerError::config(format!("Failed to read pricing file '{}': {}", path.display(), e)))?;
+        Self::from_json(&contents)
+            .map_err(|e| ProducerError::config(format!("Invalid pricing file '{}': {}", path.display(), e)))
+    }
+
+    /// Price of a model, if the table lists it
+    pub fn price(&self, provider: ProviderId, model: &str) -> Option<ModelPrice> {
+        self.prices.get(&provider)?.get(model).copied()
+    }
+
+    /// Number of models priced across all providers
+    pub fn len(&self) -> usize {
+        self.prices.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_table_prices_listed_models() {
+        let table = PricingTable::from_json(
+            r#"{
+                "openai": { "gpt-4o": { "input_per_1k": 0.0025, "output_per_1k": 0.01 } },
+                "azure": { "my-deployment": { "input_per_1k": 0.001, "output_per_1k": 0.002 } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(table.len(), 2);
+        let price = table.price(ProviderId::OpenAI, "gpt-4o").unwrap();
+        let tokens = TokenUsage { input_tokens: 2000, output_tokens: 500 };
+        assert!((price.cost(&tokens) - 0.01).abs() < 1e-12);
+        assert_eq!(
+            table.price(ProviderId::AzureOpenAI, "my-deployment"),
+            Some(ModelPrice::new(0.001, 0.002))
+        );
+        assert_eq!(table.price(ProviderId::OpenAI, "gpt-4o-mini"), None);
+    }
+
+    #[test]
+    fn test_invalid_tables_are_rejected() {
+        let unknown_provider = r#"{ "acme": { "m": { "input_per_1k": 0.1, "output_per_1k": 0.1 } } }"#;
+        assert!(PricingTable::from_json(unknown_provider).is_err());
+
+        let negative = r#"{ "openai": { "gpt-4o": { "input_per_1k": -0.1, "output_per_1k": 0.1 } } }"#;
+        assert!(PricingTable::from_json(negative).is_err());
+
+        let missing_field = r#"{ "openai": { "gpt-4o": { "input_per_1k": 0.1 } } }"#;
+        assert!(PricingTable::from_json(missing_field).is_err());
+
+        assert!(PricingTable::load("/nonexistent/pricing.json").is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Recorded provider traffic for offline test fixtures
+//!
+//! `RealApiClient::with_response_recording` appends every request/response pair it
+//! handles to `<dir>/<prompt hash>.jsonl`, one JSON object per line. `RecordedApiClient`
+//! loads such a directory and answers requests from it, matching on the same prompt
+//! hash, so real provider behavior can be snapshotted once and replayed deterministically.
+
+use crate::error::{ProducerError, ProducerResult};
+use crate::services::pricing::ModelPrice;
+use crate::traits::ApiClient;
+use crate::types::{ApiRequest, ApiResponse};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::{ProviderId, TokenUsage};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+
+/// Extension of the per-prompt recording files
+const RECORDING_EXTENSION: &str = "jsonl";
+
+/// Stable hash of what a provider sees: provider, model override, system prompt and prompt
+///
+/// Request ids, timestamps and sampling settings are left out so a replayed run with
+/// the same prompts finds the recording. FNV-1a keeps the value stable across builds.
+pub fn prompt_hash(request: &ApiRequest) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let fields = [
+        request.provider.to_string(),
+        request.model.clone().unwrap_or_default(),
+        request.system_prompt.clone().unwrap_or_default(),
+        request.prompt.clone(),
+    ];
+    for field in &fields {
+        // A separator byte keeps ("ab", "c") and ("a", "bc") apart
+        for byte in field.bytes().chain(std::iter::once(0xff)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// One request and the response the provider gave it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub request: ApiRequest,
+    pub response: ApiResponse,
+}
+
+/// Appends request/response pairs under a directory, one file per prompt hash
+#[derive(Debug)]
+pub struct ResponseRecorder {
+    dir: PathBuf,
+    /// Serializes appends so concurrent requests never interleave lines
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl ResponseRecorder {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Recording file for a request
+    pub fn path(&self, request: &ApiRequest) -> PathBuf {
+        self.dir.join(format!("{}.{}", prompt_hash(request), RECORDING_EXTENSION))
+    }
+
+    /// Append an exchange to its prompt's recording, creating the directory if needed
+    pub async fn record(&self, request: &ApiRequest, response: &ApiResponse) -> io::Result<()> {
+        let exchange = RecordedExchange {
+            request: request.clone(),
+            response: response.clone(),
+        };
+        let mut line = serde_json::to_string(&exchange)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(request))
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+/// API client that replays responses recorded by `ResponseRecorder`
+///
+/// Responses for a prompt are returned in recorded order, starting over once they run
+/// out. A prompt with no recording is an error naming its hash and the directory.
+#[derive(Debug)]
+pub struct RecordedApiClient {
+    dir: PathBuf,
+    responses: HashMap<String, Vec<ApiResponse>>,
+    /// Index of the next response to replay for each prompt hash
+    cursors: Mutex<HashMap<String, usize>>,
+}
+
+impl RecordedApiClient {
+    /// Load every recording in a directory
+    pub fn load(dir: impl AsRef<Path>) -> ProducerResult<Self> {
+        let dir = dir.as_ref();
+        let mut responses: HashMap<String, Vec<ApiResponse>> = HashMap::new();
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(RECORDING_EXTENSION) {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path)?;
+            for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                let exchange: RecordedExchange = serde_json::from_str(line).map_err(|e| {
+                    ProducerError::serialization(format!("Invalid recording in {}: {}", path.display(), e))
+                })?;
+                responses
+                    .entry(prompt_hash(&exchange.request))
+                    .or_default()
+                    .push(exchange.response);
+            }
+        }
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            responses,
+            cursors: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Number of distinct prompts with a recording
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+}
+
+#[async_trait]
+impl ApiClient for RecordedApiClient {
+    async fn send_request(&self, request: ApiRequest) -> ProducerResult<ApiResponse> {
+        let hash = prompt_hash(&request);
+        let Some(recorded) = self.responses.get(&hash) else {
+            return Err(ProducerError::api(
+                request.provider.to_string(),
+                format!(
+                    "No recorded response for prompt hash {} in {} (record it with --record-responses)",
+                    hash,
+                    self.dir.display()
+                ),
+            ));
+        };
+
+        let index = {
+            let mut cursors = self.cursors.lock().unwrap();
+            let cursor = cursors.entry(hash).or_insert(0);
+            let index = *cursor % recorded.len();
+            *cursor += 1;
+            index
+        };
+
+        let mut response = recorded[index].clone();
+        response.request_id = request.request_id;
+        response.timestamp = Utc::now();
+        Ok(response)
+    }
+
+    async fn health_check(&self, provider: ProviderId) -> ProducerResult<bool> {
+        Ok(self.responses.values().flatten().any(|response| response.provider == provider))
+    }
+
+    fn estimate_cost(&self, provider: ProviderId, tokens: &TokenUsage) -> f64 {
+        ModelPrice::builtin(provider).cost(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::RealApiClient;
+    use serde_json::json;
+    use shared::ProcessId;
+    use uuid::Uuid;
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn request(prompt: &str) -> ApiRequest {
+        ApiRequest {
+            provider: ProviderId::OpenAI,
+            prompt: prompt.to_string(),
+            system_prompt: None,
+            model: None,
+            max_tokens: 100,
+            temperature: 0.7,
+            request_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    async fn answer(server: &MockServer, prompt: &str, content: &str) {
+        Mock::given(method("POST"))
+            .and(body_string_contains(prompt))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "content": content }, "finish_reason": "stop" }],
+                "usage": { "prompt_tokens": 12, "completion_tokens": 4 }
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_recorded_responses_replay_identically() {
+        ProcessId::init_producer(1);
+        let server = MockServer::start().await;
+        answer(&server, "birds", "heron\nibis").await;
+        answer(&server, "cats", "lynx\nocelot").await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let client = RealApiClient::new(HashMap::new(), 30000)
+            .with_base_url(ProviderId::OpenAI, server.uri())
+            .with_response_recording(dir.path());
+        let mut live = Vec::new();
+        for prompt in ["birds", "cats", "birds"] {
+            live.push(client.send_request(request(prompt)).await.unwrap());
+        }
+
+        let replay = RecordedApiClient::load(dir.path()).unwrap();
+        assert_eq!(replay.len(), 2);
+        assert!(replay.health_check(ProviderId::OpenAI).await.unwrap());
+        for (prompt, recorded) in ["birds", "cats", "birds"].into_iter().zip(&live) {
+            let request = request(prompt);
+            let replayed = replay.send_request(request.clone()).await.unwrap();
+            assert_eq!(replayed.request_id, request.request_id);
+            assert_eq!(replayed.content, recorded.content);
+            assert_eq!(replayed.success, recorded.success);
+            assert_eq!(replayed.tokens_used.input_tokens, recorded.tokens_used.input_tokens);
+            assert_eq!(replayed.tokens_used.output_tokens, recorded.tokens_used.output_tokens);
+            assert_eq!(replayed.finish_reason, recorded.finish_reason);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unrecorded_prompt_is_a_clear_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = ResponseRecorder::new(dir.path());
+        let recorded = request("birds");
+        let response = ApiResponse {
+            provider: ProviderId::OpenAI,
+            request_id: recorded.request_id,
+            content: "heron".to_string(),
+            tokens_used: TokenUsage { input_tokens: 1, output_tokens: 1 },
+            response_time_ms: 5,
+            timestamp: Utc::now(),
+            success: true,
+            error_message: None,
+            retry_after_ms: None,
+            failure: None,
+            finish_reason: None,
+            model: None,
+            cached: false,
+        };
+        recorder.record(&recorded, &response).await.unwrap();
+
+        let replay = RecordedApiClient::load(dir.path()).unwrap();
+        let missing = request("dogs");
+        let error = replay.send_request(missing.clone()).await.unwrap_err().to_string();
+        assert!(error.contains(&prompt_hash(&missing)), "unexpected error: {error}");
+        assert!(error.contains("No recorded response"), "unexpected error: {error}");
+    }
+}
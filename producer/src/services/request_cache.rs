@@ -0,0 +1,124 @@
+//! Short-lived cache of provider responses for repeated requests
+//!
+//! Producers working from the same prompt can send byte-identical requests within
+//! moments of each other. With the cache enabled, `RealApiClient` answers a repeat
+//! of a successful request from memory until its TTL runs out, keyed like response
+//! recordings by provider, model, system prompt and prompt. A request that matches
+//! one still in flight waits for its answer instead of being sent again. Cached
+//! answers are flagged and carry no token usage, so they count as cache hits
+//! rather than cost.
+
+use crate::services::recording::prompt_hash;
+use crate::types::{ApiRequest, ApiResponse};
+use chrono::Utc;
+use shared::TokenUsage;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Default time a cached response stays valid
+pub const DEFAULT_REQUEST_CACHE_TTL_MS: u64 = 5_000;
+
+/// Successful responses by prompt hash, each with when it was stored
+#[derive(Debug)]
+pub struct RequestCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, ApiResponse)>>,
+    /// Requests being sent, by prompt hash; identical requests subscribe to their answer
+    in_flight: Mutex<HashMap<String, watch::Receiver<Option<ApiResponse>>>>,
+}
+
+/// How a request is answered
+pub enum CacheLookup<'a> {
+    /// From a recent response
+    Hit(ApiResponse),
+    /// By an identical request already being sent; the answer arrives on the receiver,
+    /// which closes without one if that request fails
+    Pending(watch::Receiver<Option<ApiResponse>>),
+    /// By sending it; completing the guard shares the response with identical requests
+    Miss(InFlight<'a>),
+}
+
+/// A request being sent on behalf of every identical request that arrives meanwhile
+pub struct InFlight<'a> {
+    cache: &'a RequestCache,
+    key: String,
+    sender: watch::Sender<Option<ApiResponse>>,
+}
+
+impl RequestCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached answer to a request, re-addressed to it and flagged as cached
+    pub fn get(&self, request: &ApiRequest) -> Option<ApiResponse> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (stored_at, _)| now.saturating_duration_since(*stored_at) < self.ttl);
+
+        let (_, response) = entries.get(&prompt_hash(request))?;
+        Some(Self::answer(request, response))
+    }
+
+    /// Answer a request from the cache, from an identical request in flight, or by sending it
+    pub fn lookup(&self, request: &ApiRequest) -> CacheLookup<'_> {
+        if let Some(response) = self.get(request) {
+            return CacheLookup::Hit(response);
+        }
+
+        let key = prompt_hash(request);
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(receiver) = in_flight.get(&key) {
+            return CacheLookup::Pending(receiver.clone());
+        }
+        let (sender, receiver) = watch::channel(None);
+        in_flight.insert(key.clone(), receiver);
+        CacheLookup::Miss(InFlight { cache: self, key, sender })
+    }
+
+    /// Remember a response for repeats of its request; failures are never cached
+    pub fn insert(&self, request: &ApiRequest, response: &ApiResponse) {
+        if response.success {
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(prompt_hash(request), (Instant::now(), response.clone()));
+        }
+    }
+
+    /// A stored response re-addressed to another request and flagged as cached
+    pub fn answer(request: &ApiRequest, response: &ApiResponse) -> ApiResponse {
+        ApiResponse {
+            request_id: request.request_id,
+            tokens_used: TokenUsage::default(),
+            response_time_ms: 0,
+            timestamp: Utc::now(),
+            cached: true,
+            ..response.clone()
+        }
+    }
+}
+
+impl InFlight<'_> {
+    /// Cache a successful response and hand it to the requests waiting on it
+    ///
+    /// A failure is not shared, so the waiting requests are sent on their own.
+    pub fn complete(self, request: &ApiRequest, response: &ApiResponse) {
+        self.cache.insert(request, response);
+        if response.success {
+            self.sender.send_replace(Some(response.clone()));
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.cache.in_flight.lock().unwrap().remove(&self.key);
+    }
+}
@@ -39,4 +39,7 @@ pub trait ApiClient: Send + Sync {
 
     /// Get estimated cost for request
     fn estimate_cost(&self, provider: shared::ProviderId, tokens: &shared::TokenUsage) -> f64;
+
+    /// Reseed any randomized client behavior (e.g. simulated output, retry jitter)
+    fn reseed(&self, _seed: u64) {}
 }
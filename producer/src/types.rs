@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use shared::{OptimizationMode, ProviderId, TokenUsage};
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 /// Producer configuration
@@ -42,12 +43,28 @@ impl ProducerConfig {
 pub struct ApiRequest {
     pub provider: ProviderId,
     pub prompt: String,
+    /// Instructions for the system role, kept apart from the user prompt
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Model to send the request to instead of the client's configured model for the provider
+    #[serde(default)]
+    pub model: Option<String>,
     pub max_tokens: u32,
     pub temperature: f32,
     pub request_id: Uuid,
     pub timestamp: DateTime<Utc>,
 }
 
+impl ApiRequest {
+    /// System and user prompt as one text, for providers without message roles
+    pub fn combined_prompt(&self) -> String {
+        match &self.system_prompt {
+            Some(system_prompt) => format!("{}\n\n{}", system_prompt, self.prompt),
+            None => self.prompt.clone(),
+        }
+    }
+}
+
 /// API response from external providers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse {
@@ -59,6 +76,31 @@ pub struct ApiResponse {
     pub timestamp: DateTime<Utc>,
     pub success: bool,
     pub error_message: Option<String>,
+    /// Provider-supplied delay before retrying, taken from a Retry-After style hint
+    #[serde(default)]
+    pub retry_after_ms: Option<u64>,
+    /// Classified cause of an unsuccessful response
+    #[serde(default)]
+    pub failure: Option<shared::ApiFailure>,
+    /// Why the provider stopped generating, normalized so `FINISH_REASON_LENGTH` means max_tokens was hit
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Model that served the request, when known
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Answered from the request cache without reaching the provider
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// Finish reason for a response cut off by the max_tokens limit
+pub const FINISH_REASON_LENGTH: &str = "length";
+
+impl ApiResponse {
+    /// Whether the provider stopped mid-output because it ran out of tokens
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason.as_deref() == Some(FINISH_REASON_LENGTH)
+    }
 }
 
 /// Processed attributes extracted from responses
@@ -85,6 +127,22 @@ pub struct ProducerMetrics {
     pub success_rate: f64,
     pub uptime_seconds: u64,
     pub last_updated: DateTime<Utc>,
+    /// Spot-check requests sent to the verification provider
+    pub verification_requests: u64,
+    /// Accepted attributes sampled for re-verification
+    pub verification_samples: u64,
+    /// Sampled attributes the verification provider rejected
+    pub verification_conflicts: u64,
+    /// Spend on verification, kept out of `total_cost`
+    pub verification_cost: f64,
+    /// Responses cut off by the max_tokens limit
+    pub truncated_responses: u64,
+    /// Successful responses from which no attributes could be extracted
+    pub empty_responses: u64,
+    /// Responses blocked by a provider's content filter
+    pub content_filtered: u64,
+    /// Responses answered from the request cache, which cost nothing
+    pub cache_hits: u64,
 }
 
 impl ProducerMetrics {
@@ -134,6 +192,18 @@ pub struct ProducerState {
     /// Seen values from orchestrator for bloom filter synchronization
     pub seen_values_from_orchestrator: Option<Vec<String>>,
     pub last_sync_version: Option<u64>,
+    /// Source for routing decisions; seeded from the run seed on Start
+    pub rng: rand::rngs::StdRng,
+    /// Position in the provider rotation for round-robin style routing
+    pub routing_cursor: usize,
+    /// Per-provider circuit breakers for the CircuitBreaker routing strategy
+    pub circuits: crate::core::CircuitState,
+    /// Client-side requests-per-minute limits consulted before routing
+    pub rate_limiter: crate::core::RateLimiter,
+    /// Uniqueness-driven request size controller; None when auto-tuning is off
+    pub request_size_tuner: Option<crate::core::RequestSizeTuner>,
+    /// This producer's share of the cost budget in USD per minute; None leaves requests unthrottled
+    pub cost_budget_per_minute: Option<f64>,
 }
 
 impl ProducerState {
@@ -149,6 +219,12 @@ impl ProducerState {
             metrics: ProducerMetrics::new(),
             seen_values_from_orchestrator: None,
             last_sync_version: None,
+            rng: rand::SeedableRng::from_entropy(),
+            routing_cursor: 0,
+            circuits: crate::core::CircuitState::new(),
+            rate_limiter: crate::core::RateLimiter::default(),
+            request_size_tuner: None,
+            cost_budget_per_minute: None,
         }
     }
 
@@ -165,6 +241,20 @@ impl ProducerState {
         self.should_stop = true;
     }
 
+    /// Stop issuing requests while keeping prompt, routing and sync state
+    pub fn pause(&mut self) {
+        self.is_running = false;
+    }
+
+    /// Continue issuing requests after a pause; returns false if there is nothing to resume
+    pub fn resume(&mut self) -> bool {
+        if self.should_stop || self.current_prompt.is_none() {
+            return false;
+        }
+        self.is_running = true;
+        true
+    }
+
     /// Get current uptime in seconds
     pub fn uptime_seconds(&self) -> u64 {
         if let Some(start_time) = self.start_time {
@@ -232,6 +322,21 @@ use shared::ProducerCommand;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
+/// Default base delay for retry backoff
+pub const DEFAULT_RETRY_BASE_MS: u64 = 100;
+
+/// Default cap on a single retry backoff
+pub const DEFAULT_RETRY_MAX_MS: u64 = 10_000;
+
+/// Default time shutdown waits for in-flight requests to finish
+pub const DEFAULT_DRAIN_TIMEOUT_MS: u64 = 10_000;
+
+/// Default lower bound for auto-tuned request sizes
+pub const DEFAULT_REQUEST_SIZE_MIN: usize = 10;
+
+/// Default upper bound for auto-tuned request sizes
+pub const DEFAULT_REQUEST_SIZE_MAX: usize = 200;
+
 /// Unified configuration that handles both test and production modes
 #[derive(Debug, Clone)]
 pub struct ExecutionConfig {
@@ -241,6 +346,32 @@ pub struct ExecutionConfig {
     pub max_retries: u32,
     pub status_report_interval: Duration,
     pub routing_strategy: RoutingStrategy,
+    /// Per-provider token caps; providers without an entry are unlimited
+    pub token_budgets: HashMap<ProviderId, u64>,
+    /// Per-provider request caps per minute; providers without an entry are unlimited
+    pub rate_limits: HashMap<ProviderId, u32>,
+    /// Base delay for jittered exponential retry backoff
+    pub retry_base_ms: u64,
+    /// Upper bound on any single retry backoff
+    pub retry_max_ms: u64,
+    /// Fixed seed for the Random provider, overriding the run-derived one
+    pub random_seed: Option<u64>,
+    /// How long shutdown waits for in-flight requests to deliver their attributes
+    pub drain_timeout: Duration,
+    /// Provider that re-checks a sample of accepted attributes; disabled when None
+    pub verification_provider: Option<ProviderConfig>,
+    /// Fraction of accepted attributes sent for re-verification (0.0 disables sampling)
+    pub verification_sample_rate: f32,
+    /// Adjust the request size each cycle from the observed uniqueness ratio
+    pub auto_tune_request_size: bool,
+    /// Smallest request size auto-tuning may shrink to
+    pub request_size_min: usize,
+    /// Largest request size auto-tuning may grow to
+    pub request_size_max: usize,
+    /// Output directory for dead letters (responses that yielded no attributes); disabled when None
+    pub dead_letter_dir: Option<PathBuf>,
+    /// Retry a request blocked by a content filter once with a reworded prompt
+    pub reword_filtered_prompts: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -259,7 +390,7 @@ pub enum CommandSource {
 }
 
 use crate::error::{ProducerError, ProducerResult};
-use shared::types::RoutingStrategy;
+use shared::types::{ProviderConfig, RoutingStrategy};
 
 impl ExecutionConfig {
     /// Parse command line arguments and environment to create unified config
@@ -293,10 +424,87 @@ impl ExecutionConfig {
             request_interval: Duration::from_secs(request_interval_secs.unwrap_or(2)),
             max_retries: 3,
             status_report_interval: Duration::from_secs(2),
-            routing_strategy: routing_strategy.unwrap_or_else(|| Self::get_routing_strategy()),
+            routing_strategy: routing_strategy.unwrap_or_else(Self::get_routing_strategy),
+            token_budgets: HashMap::new(),
+            rate_limits: HashMap::new(),
+            retry_base_ms: DEFAULT_RETRY_BASE_MS,
+            retry_max_ms: DEFAULT_RETRY_MAX_MS,
+            random_seed: None,
+            drain_timeout: Duration::from_millis(DEFAULT_DRAIN_TIMEOUT_MS),
+            verification_provider: None,
+            verification_sample_rate: 0.0,
+            auto_tune_request_size: false,
+            request_size_min: DEFAULT_REQUEST_SIZE_MIN,
+            request_size_max: DEFAULT_REQUEST_SIZE_MAX,
+            dead_letter_dir: None,
+            reword_filtered_prompts: false,
         })
     }
 
+    /// Cap the tokens each listed provider may consume during the run
+    pub fn with_token_budgets(mut self, token_budgets: HashMap<ProviderId, u64>) -> Self {
+        self.token_budgets = token_budgets;
+        self
+    }
+
+    /// Cap the requests per minute the producer sends to each listed provider
+    pub fn with_rate_limits(mut self, rate_limits: HashMap<ProviderId, u32>) -> Self {
+        self.rate_limits = rate_limits;
+        self
+    }
+
+    /// Set the base and maximum delay for retry backoff
+    pub fn with_retry_backoff(mut self, retry_base_ms: u64, retry_max_ms: u64) -> Self {
+        self.retry_base_ms = retry_base_ms;
+        self.retry_max_ms = retry_max_ms;
+        self
+    }
+
+    /// Pin the Random provider's output so runs with the same request size are identical
+    pub fn with_random_seed(mut self, random_seed: Option<u64>) -> Self {
+        self.random_seed = random_seed;
+        self
+    }
+
+    /// Cap how many API requests may be in flight at once
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.producer_config.max_concurrent_requests = max_concurrent_requests.max(1);
+        self
+    }
+
+    /// Bound how long shutdown waits for in-flight requests
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Re-check a sampled fraction of accepted attributes with a second, usually cheaper, provider
+    pub fn with_verification(mut self, provider: ProviderConfig, sample_rate: f32) -> Self {
+        self.verification_provider = Some(provider);
+        self.verification_sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Shrink or grow the request size with the uniqueness ratio, within the given bounds
+    pub fn with_request_size_tuning(mut self, min_size: usize, max_size: usize) -> Self {
+        self.auto_tune_request_size = true;
+        self.request_size_min = min_size.max(1);
+        self.request_size_max = max_size.max(self.request_size_min);
+        self
+    }
+
+    /// Log responses that yield no attributes to `<dir>/<topic>/dead_letter.log`
+    pub fn with_dead_letter_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.dead_letter_dir = dir;
+        self
+    }
+
+    /// Retry content-filtered requests once with a reworded prompt instead of giving up on them
+    pub fn with_filtered_prompt_rewording(mut self, enabled: bool) -> Self {
+        self.reword_filtered_prompts = enabled;
+        self
+    }
+
     /// Get routing strategy from environment variables
     /// This replaces the old provider detection logic with explicit environment configuration
     pub fn get_routing_strategy() -> RoutingStrategy {
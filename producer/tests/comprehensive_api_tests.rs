@@ -41,6 +41,8 @@ fn create_test_request(provider: ProviderId, prompt: &str) -> ApiRequest {
     ApiRequest {
         provider,
         prompt: prompt.to_string(),
+        system_prompt: None,
+        model: None,
         max_tokens: 150,
         temperature: 0.7,
         request_id: Uuid::new_v4(),
@@ -111,7 +111,7 @@ mod backoff_extraction_tests {
         let backoff_ms = backoff.unwrap();
         
         // Should be around 15 seconds (15000ms), allow tolerance for timing
-        assert!(backoff_ms >= 14000 && backoff_ms <= 16000, "Backoff should be ~15s, got {}ms", backoff_ms);
+        assert!((14000..=16000).contains(&backoff_ms), "Backoff should be ~15s, got {}ms", backoff_ms);
         
         // Test past timestamp (should get minimum delay)
         let past_timestamp = (Utc::now().timestamp() - 5).to_string();
@@ -184,7 +184,7 @@ mod exponential_backoff_tests {
         let attempts = vec![0, 1, 2, 3, 4];
         let expected_base_delays = vec![1000, 2000, 4000, 8000, 16000]; // Base delays in ms
         
-        for (attempt, expected_base) in attempts.into_iter().zip(expected_base_delays.into_iter()) {
+        for (attempt, expected_base) in attempts.into_iter().zip(expected_base_delays) {
             let backoff = client.calculate_exponential_backoff_ms(attempt);
             
             // Allow 10% jitter on either side
@@ -233,7 +233,7 @@ mod exponential_backoff_tests {
         // All values should be in reasonable range around 2000ms
         for value in values {
             assert!(
-                value >= 1800 && value <= 2200,
+                (1800..=2200).contains(&value),
                 "Value {} should be in range 1800-2200ms", value
             );
         }
@@ -246,7 +246,7 @@ mod exponential_backoff_tests {
         // First attempt should have reasonable minimum
         let backoff = client.calculate_exponential_backoff_ms(0);
         assert!(
-            backoff >= 900 && backoff <= 1100,
+            (900..=1100).contains(&backoff),
             "First attempt should be ~1000ms ±10%, got {}ms", backoff
         );
     }
@@ -488,7 +488,7 @@ mod integration_simulation_tests {
         
         // Use exponential backoff for attempt 1
         let exp_backoff = client.calculate_exponential_backoff_ms(1);
-        assert!(exp_backoff >= 1800 && exp_backoff <= 2200); // ~2000ms ±10%
+        assert!((1800..=2200).contains(&exp_backoff)); // ~2000ms ±10%
         
         // 3. Simulate multiple retry attempts
         for attempt in 0..5 {
@@ -18,6 +18,11 @@ pub fn create_success_response(provider: ProviderId, content: String, tokens: u3
         timestamp: Utc::now(),
         success: true,
         error_message: None,
+        retry_after_ms: None,
+        failure: None,
+        finish_reason: None,
+        model: None,
+        cached: false,
     }
 }
 
@@ -32,6 +37,11 @@ pub fn create_error_response(provider: ProviderId, error: String) -> ApiResponse
         timestamp: Utc::now(),
         success: false,
         error_message: Some(error),
+        retry_after_ms: None,
+        failure: None,
+        finish_reason: None,
+        model: None,
+        cached: false,
     }
 }
 
@@ -40,6 +50,8 @@ pub fn create_test_request(provider: ProviderId, prompt: String) -> ApiRequest {
     producer::types::ApiRequest {
         provider,
         prompt,
+        system_prompt: None,
+        model: None,
         max_tokens: 150,
         temperature: 0.7,
         request_id: Uuid::new_v4(),
@@ -31,7 +31,17 @@ impl CommandFactory {
                 max_tokens: 150,
                 temperature: 0.7,
                 request_size: 50,
+                extraction_format: None,
+                system_prompt: None,
+                min_attribute_length: None,
+                max_attribute_length: None,
+                include_seen_hint: false,
+                seen_hint_count: 0,
             },
+            seed: 42,
+            max_cost_per_minute: None,
+            dedup: true,
+            category: None,
         }
     }
 
@@ -54,7 +64,17 @@ impl CommandFactory {
                 max_tokens: 1000,
                 temperature: 0.8,
                 request_size: 100,
+                extraction_format: None,
+                system_prompt: None,
+                min_attribute_length: None,
+                max_attribute_length: None,
+                include_seen_hint: false,
+                seen_hint_count: 0,
             },
+            seed: 42,
+            max_cost_per_minute: None,
+            dedup: true,
+            category: None,
         }
     }
 
@@ -70,6 +90,7 @@ impl CommandFactory {
             routing_strategy: None,
             generation_config: None,
             prompt: new_prompt,
+            max_cost_per_minute: None,
         }
     }
 
@@ -82,6 +103,8 @@ impl CommandFactory {
             bloom_version: Some(1),
             requires_dedup,
             seen_values: None,
+            compressed: false,
+            compressed_seen_values: None,
         }
     }
 
@@ -99,6 +122,8 @@ impl CommandFactory {
             bloom_version: Some(1),
             requires_dedup,
             seen_values,
+            compressed: false,
+            compressed_seen_values: None,
         }
     }
 
@@ -133,6 +158,8 @@ impl UpdateFactory {
                 response_time_ms: 500,
                 request_timestamp: chrono::Utc::now().timestamp_millis() as u64,
             },
+            estimated_cost: 0.0000375,
+            topic: "test topic".to_string(),
         }
     }
 
@@ -152,6 +179,8 @@ impl UpdateFactory {
                 current_batch_rate: 25.0,
                 memory_usage_mb: Some(64),
                 bloom_filter_size_mb: Some(2.5),
+                tuned_limits: HashMap::new(),
+                verification: Default::default(),
             })
         } else {
             None
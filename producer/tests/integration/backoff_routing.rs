@@ -21,8 +21,16 @@ async fn test_backoff_routing_strategy() {
             prompt,
             routing_strategy,
             generation_config,
+            seed,
+            max_cost_per_minute,
+            dedup,
+            category,
         } => {
             assert_eq!(*command_id, 1);
+            assert_eq!(*seed, 42);
+            assert_eq!(*max_cost_per_minute, None);
+            assert!(*dedup);
+            assert_eq!(*category, None);
             assert_eq!(topic, "Japanese dishes");
             assert_eq!(prompt, "Generate unique Japanese dishes");
 
@@ -48,7 +56,7 @@ async fn test_backoff_routing_strategy() {
 /// Test Backoff routing strategy with different providers
 #[tokio::test]
 async fn test_backoff_routing_different_providers() {
-    let providers = vec![ProviderId::OpenAI, ProviderId::Anthropic, ProviderId::Gemini];
+    let providers = [ProviderId::OpenAI, ProviderId::Anthropic, ProviderId::Gemini];
 
     for (i, provider) in providers.iter().enumerate() {
         let start_command =
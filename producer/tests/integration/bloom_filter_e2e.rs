@@ -103,7 +103,7 @@ async fn test_bloom_filter_large_dataset_e2e() {
     let _ = tracing_subscriber::fmt::try_init();
 
     // Create diverse data for meaningful testing
-    let base_words = vec!["apple", "banana", "cherry", "date", "elderberry"];
+    let base_words = ["apple", "banana", "cherry", "date", "elderberry"];
     let normalized_data: Vec<String> = (0..5000)
         .map(|i| base_words[i % base_words.len()].to_string())
         .collect();
@@ -198,7 +198,7 @@ async fn test_bloom_filter_false_positive_rate() {
     let mut false_positives = 0;
 
     // Generate unique test words using different prefixes to ensure uniqueness after normalization
-    let prefixes = vec!["computer", "device", "gadget", "machine", "tool", "hardware", "software", "system"];
+    let prefixes = ["computer", "device", "gadget", "machine", "tool", "hardware", "software", "system"];
     
     for i in 0..test_size {
         let prefix = &prefixes[i % prefixes.len()];
@@ -345,5 +345,10 @@ fn create_test_response(content: String) -> producer::types::ApiResponse {
         timestamp: Utc::now(),
         success: true,
         error_message: None,
+        retry_after_ms: None,
+        failure: None,
+        finish_reason: None,
+        model: None,
+        cached: false,
     }
 }
@@ -19,8 +19,16 @@ async fn test_start_command_handling() {
             prompt,
             routing_strategy,
             generation_config,
+            seed,
+            max_cost_per_minute,
+            dedup,
+            category,
         } => {
             assert_eq!(*command_id, 1);
+            assert_eq!(*seed, 42);
+            assert_eq!(*max_cost_per_minute, None);
+            assert!(*dedup);
+            assert_eq!(*category, None);
             assert_eq!(topic, "Japanese cuisine dishes");
             assert_eq!(prompt, "Generate unique Japanese dishes");
 
@@ -118,8 +126,10 @@ async fn test_update_config_command_handling() {
             prompt,
             routing_strategy,
             generation_config,
+            max_cost_per_minute,
         } => {
             assert_eq!(*command_id, 3);
+            assert_eq!(*max_cost_per_minute, None);
             assert_eq!(prompt, &Some("Generate Italian pasta dishes".to_string()));
             assert!(routing_strategy.is_none());
             assert!(generation_config.is_none());
@@ -263,13 +273,11 @@ async fn test_command_sequencing() {
     let producer_id = ProcessIdFactory::create();
 
     // Scenario 1: Start -> UpdateConfig -> Stop
-    let commands = vec![
-        CommandFactory::start_command(1, "topic1", "prompt1"),
+    let commands = [CommandFactory::start_command(1, "topic1", "prompt1"),
         CommandFactory::update_config_command(2, Some("updated prompt".to_string())),
-        CommandFactory::stop_command(3),
-    ];
+        CommandFactory::stop_command(3)];
 
-    let expected_responses = vec![
+    let expected_responses = [
         ProcessStatus::Running, // After Start
         ProcessStatus::Running, // After UpdateConfig
         ProcessStatus::Stopped, // After Stop
@@ -371,11 +379,9 @@ async fn test_attribute_batch_processing() {
     let producer_id = ProcessIdFactory::create();
 
     // Create attribute batches from different providers
-    let providers = vec![
-        shared::types::ProviderId::OpenAI,
+    let providers = [shared::types::ProviderId::OpenAI,
         shared::types::ProviderId::Anthropic,
-        shared::types::ProviderId::Gemini,
-    ];
+        shared::types::ProviderId::Gemini];
 
     for (batch_id, provider) in providers.iter().enumerate() {
         let attributes = (0..10).map(|i| format!("attribute_{}_{}", batch_id, i)).collect();
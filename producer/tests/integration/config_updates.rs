@@ -40,6 +40,7 @@ async fn test_config_updates() {
         prompt: None,
         routing_strategy: Some(new_strategy.clone()),
         generation_config: None,
+        max_cost_per_minute: None,
     };
     
     match &routing_update {
@@ -63,6 +64,12 @@ async fn test_config_updates() {
         max_tokens: 200,
         temperature: 0.9,
         request_size: 15,
+        extraction_format: None,
+        system_prompt: None,
+        min_attribute_length: None,
+        max_attribute_length: None,
+        include_seen_hint: false,
+        seen_hint_count: 0,
     };
     
     let config_update = ProducerCommand::UpdateConfig {
@@ -70,6 +77,7 @@ async fn test_config_updates() {
         prompt: None,
         routing_strategy: None,
         generation_config: Some(new_config.clone()),
+        max_cost_per_minute: None,
     };
     
     match &config_update {
@@ -100,7 +108,14 @@ async fn test_config_update_serialization() {
             max_tokens: 150,
             temperature: 0.7,
             request_size: 10,
+            extraction_format: None,
+            system_prompt: None,
+            min_attribute_length: None,
+            max_attribute_length: None,
+            include_seen_hint: false,
+            seen_hint_count: 0,
         }),
+        max_cost_per_minute: None,
     };
     
     // Serialize to JSON
@@ -128,7 +143,7 @@ async fn test_config_update_serialization() {
 /// Test multiple sequential UpdateConfig commands
 #[tokio::test] 
 async fn test_sequential_config_updates() {
-    let commands = vec![
+    let commands = [
         // Start with initial config
         CommandFactory::start_command(1, "sequential-test", "Initial prompt"),
         
@@ -146,6 +161,7 @@ async fn test_sequential_config_updates() {
                 ],
             }),
             generation_config: None,
+            max_cost_per_minute: None,
         },
         
         // Update generation config
@@ -160,7 +176,14 @@ async fn test_sequential_config_updates() {
                 max_tokens: 300,
                 temperature: 0.8,
                 request_size: 25,
+                extraction_format: None,
+                system_prompt: None,
+                min_attribute_length: None,
+                max_attribute_length: None,
+                include_seen_hint: false,
+                seen_hint_count: 0,
             }),
+            max_cost_per_minute: None,
         },
         
         // Final prompt update
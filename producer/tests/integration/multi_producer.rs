@@ -175,11 +175,9 @@ async fn test_orchestrator_multi_producer_commands() {
     let producer_ids: Vec<ProcessId> = (0..3).map(|_| ProcessIdFactory::create()).collect();
 
     // Create test commands for each producer
-    let commands = vec![
-        CommandFactory::start_command(1, "topic1", "prompt1"),
+    let commands = [CommandFactory::start_command(1, "topic1", "prompt1"),
         CommandFactory::start_command(2, "topic2", "prompt2"),
-        CommandFactory::start_command(3, "topic3", "prompt3"),
-    ];
+        CommandFactory::start_command(3, "topic3", "prompt3")];
 
     // Verify commands are created correctly
     for (i, command) in commands.iter().enumerate() {
@@ -199,8 +197,7 @@ async fn test_orchestrator_multi_producer_commands() {
     }
 
     // Create test updates from producers
-    let updates = vec![
-        UpdateFactory::status_update(
+    let updates = [UpdateFactory::status_update(
             producer_ids[0].clone(),
             ProcessStatus::Running,
             Some("Producer 1 running".to_string()),
@@ -217,8 +214,7 @@ async fn test_orchestrator_multi_producer_commands() {
             1,
             vec!["attr1".to_string(), "attr2".to_string()],
             shared::types::ProviderId::OpenAI,
-        ),
-    ];
+        )];
 
     // Verify updates are created correctly
     assert_eq!(updates.len(), 3);
@@ -321,8 +317,7 @@ async fn test_multi_producer_error_handling() {
     let producer_ids: Vec<ProcessId> = (0..3).map(|_| ProcessIdFactory::create()).collect();
 
     // Create various error scenarios
-    let errors = vec![
-        UpdateFactory::error(producer_ids[0].clone(), "API_ERROR", "OpenAI API key invalid", Some(1)),
+    let errors = [UpdateFactory::error(producer_ids[0].clone(), "API_ERROR", "OpenAI API key invalid", Some(1)),
         UpdateFactory::error(
             producer_ids[1].clone(),
             "NETWORK_ERROR",
@@ -334,8 +329,7 @@ async fn test_multi_producer_error_handling() {
             "PROCESSING_ERROR",
             "Failed to process response",
             None,
-        ),
-    ];
+        )];
 
     // Verify error messages
     for (i, error) in errors.iter().enumerate() {
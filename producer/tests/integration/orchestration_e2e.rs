@@ -17,7 +17,7 @@ async fn test_complete_producer_lifecycle() {
     assert_eq!(manager.running_count().await, 1);
 
     // Step 2: Simulate orchestrator commands throughout lifecycle
-    let commands = vec![
+    let commands = [
         // Start generation
         CommandFactory::start_command(1, &topic, "Generate test data"),
         // Health check
@@ -36,7 +36,7 @@ async fn test_complete_producer_lifecycle() {
     assert_eq!(commands.len(), 6);
 
     // Step 3: Simulate producer responses
-    let expected_updates = vec![
+    let expected_updates = [
         // Response to Start
         UpdateFactory::status_update(
             producer_id.clone(),
@@ -323,6 +323,7 @@ async fn test_performance_monitoring() {
         ProviderUsageStats {
             requests_sent: 50,
             successful_responses: 48,
+            failed_responses: 2,
             unique_attributes_contributed: 960,
             avg_response_time_ms: 750.5,
             last_used_timestamp: chrono::Utc::now().timestamp_millis() as u64,
@@ -333,6 +334,7 @@ async fn test_performance_monitoring() {
         ProviderUsageStats {
             requests_sent: 25,
             successful_responses: 25,
+            failed_responses: 0,
             unique_attributes_contributed: 500,
             avg_response_time_ms: 1200.0,
             last_used_timestamp: chrono::Utc::now().timestamp_millis() as u64,
@@ -347,6 +349,8 @@ async fn test_performance_monitoring() {
         current_batch_rate: 25.5,
         memory_usage_mb: Some(128),
         bloom_filter_size_mb: Some(4.2),
+        tuned_limits: HashMap::new(),
+        verification: Default::default(),
     };
 
     let performance_update = shared::ProducerUpdate::StatusUpdate {
@@ -0,0 +1,27 @@
+//! Log Format Command-Line Test
+//!
+//! This test verifies that `--log-format json` keeps the producer's stdout to JSON log lines only.
+
+use std::process::Command;
+
+#[test]
+fn test_json_log_format_writes_only_json_to_stdout() {
+    let output = Command::new("cargo")
+        .args(["run", "--",
+               "--log-format", "json",
+               "--topic", "test",
+               "--provider", "random",
+               "--max-requests", "1"])
+        .output()
+        .expect("Failed to execute producer");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "Producer should run successfully");
+    assert!(!stdout.trim().is_empty(), "Producer should log to stdout");
+
+    // Every line must parse so log collectors never see banner text mixed into the stream
+    for line in stdout.lines() {
+        let parsed: Result<serde_json::Value, _> = serde_json::from_str(line);
+        assert!(parsed.is_ok(), "stdout line is not JSON: {line}");
+    }
+}
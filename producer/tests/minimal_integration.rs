@@ -53,7 +53,7 @@ fn test_processor_creation() {
 
     assert_eq!(stats.total_unique_attributes, 0);
     assert_eq!(stats.bloom_filter_false_positive_rate, 0.01);
-    assert_eq!(stats.bloom_filter_enabled, true); // Always enabled now
+    assert!(stats.bloom_filter_enabled); // Always enabled now
     assert_eq!(stats.duplicate_count, 0);
     assert_eq!(stats.total_processed, 0);
 
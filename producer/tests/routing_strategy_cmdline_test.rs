@@ -16,7 +16,7 @@ fn test_routing_strategy_cmdline_backoff() {
     
     // Test backoff strategy with Random provider for testing
     let output = Command::new("cargo")
-        .args(&["run", "--", 
+        .args(["run", "--", 
                "--routing-strategy", "backoff",
                "--routing-provider", "random",
                "--topic", "test",
@@ -34,7 +34,7 @@ fn test_routing_strategy_cmdline_backoff() {
     }
     
     assert!(output.status.success(), "Producer should run successfully");
-    assert!(stdout.contains("Routing strategy: Backoff { provider: Random }"));
+    assert!(stdout.contains("Routing strategy: Backoff { provider: ProviderConfig { provider: Random"));
 }
 
 #[tokio::test]
@@ -43,7 +43,7 @@ async fn test_routing_strategy_cmdline_roundrobin() {
     
     // Test round-robin strategy
     let output = Command::new("cargo")
-        .args(&["run", "--", 
+        .args(["run", "--", 
                "--routing-strategy", "roundrobin",
                "--routing-providers", "random",
                "--topic", "test",
@@ -64,7 +64,7 @@ async fn test_routing_strategy_cmdline_priority() {
     
     // Test priority strategy (cheapest providers first)
     let output = Command::new("cargo")
-        .args(&["run", "--", 
+        .args(["run", "--", 
                "--routing-strategy", "priority",
                "--routing-providers", "random",
                "--topic", "test",
@@ -79,13 +79,72 @@ async fn test_routing_strategy_cmdline_priority() {
     assert!(stdout.contains("Routing strategy: PriorityOrder"));
 }
 
+#[tokio::test]
+async fn test_routing_strategy_cmdline_roundrobin_mixed_models() {
+    println!("🔄 Testing command-line routing strategy: Round-robin with per-provider models");
+
+    // Bare providers keep their default model, provider:model entries use the given one
+    let output = Command::new("cargo")
+        .args(["run", "--",
+               "--routing-strategy", "roundrobin",
+               "--routing-providers", "random, random:seeded-model",
+               "--topic", "test",
+               "--provider", "random",
+               "--max-requests", "1"])
+        .output()
+        .expect("Failed to execute producer");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "Producer should run successfully");
+    assert!(stdout.contains(
+        "Routing strategy: RoundRobin { providers: [ProviderConfig { provider: Random, model: \"random\", fallback_models: [] }, \
+         ProviderConfig { provider: Random, model: \"seeded-model\", fallback_models: [] }] }"
+    ));
+}
+
+#[tokio::test]
+async fn test_routing_strategy_cmdline_priority_mixed_models() {
+    println!("📋 Testing command-line routing strategy: Priority with per-provider models");
+
+    let output = Command::new("cargo")
+        .args(["run", "--",
+               "--routing-strategy", "priority",
+               "--routing-providers", "random:seeded-model,random",
+               "--topic", "test",
+               "--provider", "random",
+               "--max-requests", "1"])
+        .output()
+        .expect("Failed to execute producer");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "Producer should run successfully");
+    assert!(stdout.contains(
+        "Routing strategy: PriorityOrder { providers: [ProviderConfig { provider: Random, model: \"seeded-model\", fallback_models: [] }, \
+         ProviderConfig { provider: Random, model: \"random\", fallback_models: [] }] }"
+    ));
+
+    // An unknown provider is still rejected
+    let output = Command::new("cargo")
+        .args(["run", "--",
+               "--routing-strategy", "priority",
+               "--routing-providers", "random,nosuch:model",
+               "--topic", "test",
+               "--provider", "random",
+               "--max-requests", "1"])
+        .output()
+        .expect("Failed to execute producer");
+    assert!(!output.status.success(), "Unknown provider should fail");
+}
+
 #[tokio::test]
 async fn test_routing_strategy_cmdline_weighted() {
     println!("⚖️ Testing command-line routing strategy: Weighted");
     
     // Test weighted strategy
     let output = Command::new("cargo")
-        .args(&["run", "--", 
+        .args(["run", "--", 
                "--routing-strategy", "weighted",
                "--routing-weights", "random:1.0",
                "--topic", "test",
@@ -156,6 +215,8 @@ CRITICAL FORMATTING REQUIREMENTS:
 - Examples: \"eiffeltower\", \"louvremuseum\", \"notredame\"
 
 Only generate canonical names, in English when available. Omit any descriptions of the entries.".to_string(),
+        system_prompt: None,
+        model: None,
         max_tokens: 150,
         temperature: 0.7,
         request_id: Uuid::new_v4(),
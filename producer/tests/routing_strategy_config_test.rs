@@ -17,7 +17,7 @@
 //!
 //! Note: Tests in this file must run sequentially due to environment variable manipulation.
 
-use shared::types::RoutingStrategy;
+use shared::types::{FormatEntry, RoutingParseError, RoutingStrategy};
 use shared::{ProviderId, TokenUsage};
 use std::env;
 use std::sync::Mutex;
@@ -31,7 +31,7 @@ async fn test_routing_strategy_env_configuration() {
     println!("🎯 Testing routing strategy environment configuration");
     
     // Clean slate - remove any existing routing env vars first
-    for var in ["ROUTING_STRATEGY", "ROUTING_PRIMARY_PROVIDER", "ROUTING_PROVIDERS", "ROUTING_WEIGHTS"] {
+    for var in ["ROUTING_STRATEGY", "ROUTING_CONFIG"] {
         env::remove_var(var);
     }
     
@@ -48,7 +48,7 @@ async fn test_routing_strategy_env_configuration() {
     match strategy {
         RoutingStrategy::Backoff { provider } => {
             println!("   Got provider: {:?}", provider);
-            // .env file sets ROUTING_PRIMARY_PROVIDER=random
+            // Without ROUTING_STRATEGY the default is backoff to random
             assert_eq!(provider.provider, ProviderId::Random);
             println!("✅ .env configuration correctly uses Random provider: {:?}", provider);
         }
@@ -60,10 +60,10 @@ async fn test_routing_strategy_env_configuration() {
     
     // Temporarily clear routing environment variables
     let original_strategy = env::var("ROUTING_STRATEGY").ok();
-    let original_provider = env::var("ROUTING_PRIMARY_PROVIDER").ok();
+    let original_config = env::var("ROUTING_CONFIG").ok();
     
     env::remove_var("ROUTING_STRATEGY");
-    env::remove_var("ROUTING_PRIMARY_PROVIDER");
+    env::remove_var("ROUTING_CONFIG");
     
     let strategy = RoutingStrategy::from_env().unwrap();
     match strategy {
@@ -79,8 +79,8 @@ async fn test_routing_strategy_env_configuration() {
     if let Some(strategy) = original_strategy {
         env::set_var("ROUTING_STRATEGY", strategy);
     }
-    if let Some(provider) = original_provider {
-        env::set_var("ROUTING_PRIMARY_PROVIDER", provider);
+    if let Some(config) = original_config {
+        env::set_var("ROUTING_CONFIG", config);
     }
     
     println!("🎉 Routing strategy environment configuration test completed!");
@@ -92,7 +92,7 @@ async fn test_routing_strategy_validation() {
     println!("🔍 Testing routing strategy validation");
     
     // Clean up environment first
-    for var in ["ROUTING_STRATEGY", "ROUTING_PRIMARY_PROVIDER", "ROUTING_PROVIDERS", "ROUTING_WEIGHTS"] {
+    for var in ["ROUTING_STRATEGY", "ROUTING_CONFIG"] {
         env::remove_var(var);
     }
     
@@ -101,7 +101,8 @@ async fn test_routing_strategy_validation() {
     match RoutingStrategy::from_env() {
         Err(e) => {
             println!("✅ Invalid strategy correctly rejected: {}", e);
-            assert!(e.contains("Unknown routing strategy"));
+            assert!(matches!(e, RoutingParseError::UnknownStrategy { ref strategy } if strategy == "invalid_strategy"));
+            assert!(e.to_string().contains("Unknown routing strategy"));
         }
         Ok(strategy) => panic!("Should have rejected invalid strategy, but got: {:?}", strategy),
     }
@@ -111,18 +112,19 @@ async fn test_routing_strategy_validation() {
     
     // Test missing providers for roundrobin
     env::set_var("ROUTING_STRATEGY", "roundrobin");
-    env::remove_var("ROUTING_PROVIDERS");
+    env::set_var("ROUTING_CONFIG", "");
     match RoutingStrategy::from_env() {
         Err(e) => {
             println!("✅ Missing providers correctly rejected: {}", e);
-            assert!(e.contains("ROUTING_PROVIDERS"));
+            assert!(matches!(e, RoutingParseError::InvalidFormat { entry: FormatEntry::ProviderConfig, .. }));
+            assert!(e.to_string().contains("provider:model"));
         }
-        Ok(_) => panic!("Should have required ROUTING_PROVIDERS"),
+        Ok(_) => panic!("Should have required providers in ROUTING_CONFIG"),
     }
     
     // Test valid roundrobin configuration
     env::set_var("ROUTING_STRATEGY", "roundrobin");
-    env::set_var("ROUTING_PROVIDERS", "openai,anthropic,random");
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini,anthropic:claude-3-sonnet,random:random");
     match RoutingStrategy::from_env() {
         Ok(RoutingStrategy::RoundRobin { providers }) => {
             println!("✅ Valid roundrobin config accepted: {:?}", providers);
@@ -136,11 +138,89 @@ async fn test_routing_strategy_validation() {
     
     // Clean up
     env::remove_var("ROUTING_STRATEGY");
-    env::remove_var("ROUTING_PROVIDERS");
+    env::remove_var("ROUTING_CONFIG");
     
     println!("🎉 Routing strategy validation test completed!");
 }
 
+#[tokio::test]
+async fn test_weighted_routing_normalizes_relative_weights() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+
+    env::set_var("ROUTING_STRATEGY", "weighted");
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini:2,anthropic:claude-3-sonnet:1");
+    let strategy = RoutingStrategy::from_env().unwrap();
+    match &strategy {
+        RoutingStrategy::Weighted { weights } => {
+            let weight_of = |provider| weights.iter().find(|(config, _)| config.provider == provider).unwrap().1;
+            assert!((weight_of(ProviderId::OpenAI) - 2.0 / 3.0).abs() < 1e-6);
+            assert!((weight_of(ProviderId::Anthropic) - 1.0 / 3.0).abs() < 1e-6);
+        }
+        other => panic!("Expected weighted, got: {:?}", other),
+    }
+    // Normalized weights also pass the strict unit-sum check
+    assert!(strategy.validate_unit_weights().is_ok());
+
+    // Negative weights and a zero total are still rejected
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini:2,anthropic:claude-3-sonnet:-1");
+    assert_eq!(RoutingStrategy::from_env().unwrap_err(), RoutingParseError::NegativeWeight { weight: -1.0 });
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini:0,anthropic:claude-3-sonnet:0");
+    assert_eq!(RoutingStrategy::from_env().unwrap_err(), RoutingParseError::ZeroWeights);
+
+    for var in ["ROUTING_STRATEGY", "ROUTING_CONFIG"] {
+        env::remove_var(var);
+    }
+
+    // The strict check remains available for callers that want unit weights
+    let mut weights = std::collections::HashMap::new();
+    weights.insert(shared::types::ProviderConfig::with_default_model(ProviderId::OpenAI), 2.0);
+    weights.insert(shared::types::ProviderConfig::with_default_model(ProviderId::Anthropic), 1.0);
+    let mut strategy = RoutingStrategy::Weighted { weights };
+    assert!(strategy.validate_unit_weights().is_err());
+    strategy.normalize_weights().unwrap();
+    assert!(strategy.validate_unit_weights().is_ok());
+}
+
+#[tokio::test]
+async fn test_routing_env_errors_name_the_offending_token() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let from_env = |strategy: &str, config: &str| {
+        env::set_var("ROUTING_STRATEGY", strategy);
+        env::set_var("ROUTING_CONFIG", config);
+        RoutingStrategy::from_env().unwrap_err()
+    };
+
+    let error = from_env("backoff", "nosuch:model");
+    assert!(matches!(error, RoutingParseError::InvalidProvider { ref token, .. } if token == "nosuch"));
+    assert!(error.to_string().starts_with("Invalid provider 'nosuch'"));
+
+    let error = from_env("roundrobin", "openai:gpt-4o-mini,anthropic:");
+    assert_eq!(
+        error,
+        RoutingParseError::InvalidModel { provider: ProviderId::Anthropic, token: String::new() }
+    );
+
+    let error = from_env("weighted", "openai:gpt-4o-mini:heavy");
+    assert!(matches!(error, RoutingParseError::InvalidWeight { ref token, .. } if token == "heavy"));
+    assert!(error.to_string().starts_with("Invalid weight 'heavy'"));
+
+    let error = from_env("weighted", "openai:gpt-4o-mini");
+    assert_eq!(error.to_string(), "Invalid weighted config format 'openai:gpt-4o-mini'. Expected 'provider:model:weight'");
+
+    env::set_var("ROUTING_FAILURE_THRESHOLD", "many");
+    let error = from_env("circuitbreaker", "openai:gpt-4o-mini");
+    assert!(matches!(
+        error,
+        RoutingParseError::InvalidSetting { ref setting, ref token, .. }
+            if setting == "ROUTING_FAILURE_THRESHOLD" && token == "many"
+    ));
+    env::remove_var("ROUTING_FAILURE_THRESHOLD");
+
+    for var in ["ROUTING_STRATEGY", "ROUTING_CONFIG"] {
+        env::remove_var(var);
+    }
+}
+
 /// Test all supported routing strategy configurations with environment variables
 /// This serves as documentation for users on how to configure different strategies
 #[tokio::test] 
@@ -149,14 +229,14 @@ async fn test_all_routing_strategy_configurations() {
     println!("📋 Testing all routing strategy configuration examples");
     
     // Clean up any existing environment variables first
-    for var in ["ROUTING_STRATEGY", "ROUTING_PRIMARY_PROVIDER", "ROUTING_PROVIDERS", "ROUTING_WEIGHTS"] {
+    for var in ["ROUTING_STRATEGY", "ROUTING_CONFIG"] {
         env::remove_var(var);
     }
     
     // Configuration Example 1: Backoff Strategy (Production)
     println!("Example 1: Backoff Strategy");
     env::set_var("ROUTING_STRATEGY", "backoff");
-    env::set_var("ROUTING_PRIMARY_PROVIDER", "openai");
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini");
     
     let strategy = RoutingStrategy::from_env()
         .map_err(|e| format!("Failed to parse backoff strategy: {}", e))
@@ -172,7 +252,7 @@ async fn test_all_routing_strategy_configurations() {
     // Configuration Example 2: Round-Robin Strategy
     println!("Example 2: Round-Robin Strategy");
     env::set_var("ROUTING_STRATEGY", "roundrobin");
-    env::set_var("ROUTING_PROVIDERS", "openai,anthropic,gemini");
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini,anthropic:claude-3-sonnet,gemini:gemini-pro");
     
     let strategy = RoutingStrategy::from_env().unwrap();
     match strategy {
@@ -186,7 +266,7 @@ async fn test_all_routing_strategy_configurations() {
     // Configuration Example 3: Priority Order Strategy
     println!("Example 3: Priority Order Strategy");
     env::set_var("ROUTING_STRATEGY", "priority");
-    env::set_var("ROUTING_PROVIDERS", "gemini,openai,anthropic,random");
+    env::set_var("ROUTING_CONFIG", "gemini:gemini-pro,openai:gpt-4o-mini,anthropic:claude-3-sonnet,random:random");
     
     let strategy = RoutingStrategy::from_env().unwrap();
     match strategy {
@@ -201,8 +281,7 @@ async fn test_all_routing_strategy_configurations() {
     // Configuration Example 4: Weighted Strategy
     println!("Example 4: Weighted Strategy");
     env::set_var("ROUTING_STRATEGY", "weighted");
-    env::set_var("ROUTING_WEIGHTS", "openai:0.5,anthropic:0.3,random:0.2");
-    env::remove_var("ROUTING_PROVIDERS"); // Not needed for weighted
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini:0.5,anthropic:claude-3-sonnet:0.3,random:random:0.2");
     
     let strategy = RoutingStrategy::from_env().unwrap();
     match strategy {
@@ -225,7 +304,7 @@ async fn test_all_routing_strategy_configurations() {
     }
     
     // Clean up all environment variables
-    for var in ["ROUTING_STRATEGY", "ROUTING_PRIMARY_PROVIDER", "ROUTING_PROVIDERS", "ROUTING_WEIGHTS"] {
+    for var in ["ROUTING_STRATEGY", "ROUTING_CONFIG"] {
         env::remove_var(var);
     }
     
@@ -234,19 +313,19 @@ async fn test_all_routing_strategy_configurations() {
     println!("📖 Configuration Reference:");
     println!("   # Backoff (single provider):");
     println!("   ROUTING_STRATEGY=backoff");
-    println!("   ROUTING_PRIMARY_PROVIDER=openai");
+    println!("   ROUTING_CONFIG=openai:gpt-4o-mini");
     println!();
     println!("   # Round-robin (load balancing):");
     println!("   ROUTING_STRATEGY=roundrobin");
-    println!("   ROUTING_PROVIDERS=openai,anthropic,gemini");
+    println!("   ROUTING_CONFIG=openai:gpt-4o-mini,anthropic:claude-3-sonnet,gemini:gemini-pro");
     println!();
     println!("   # Priority order (failover):");
     println!("   ROUTING_STRATEGY=priority");
-    println!("   ROUTING_PROVIDERS=gemini,openai,anthropic,random");
+    println!("   ROUTING_CONFIG=gemini:gemini-pro,openai:gpt-4o-mini,anthropic:claude-3-sonnet,random:random");
     println!();
     println!("   # Weighted distribution:");
     println!("   ROUTING_STRATEGY=weighted");
-    println!("   ROUTING_WEIGHTS=openai:0.5,anthropic:0.3,random:0.2");
+    println!("   ROUTING_CONFIG=openai:gpt-4o-mini:0.5,anthropic:claude-3-sonnet:0.3,random:random:0.2");
     println!();
     println!("🎉 All routing strategy configuration tests completed!");
 }
@@ -255,6 +334,7 @@ async fn test_all_routing_strategy_configurations() {
 /// This is the only E2E test that makes actual API calls to verify the routing system works
 #[tokio::test]
 #[ignore] // Use `cargo test -- --ignored` to run this test
+#[allow(clippy::await_holding_lock)] // Guard serializes env var access for the whole test
 async fn test_e2e_backoff_openai_paris_attractions() {
     let _guard = TEST_MUTEX.lock().unwrap();
     use producer::{RealApiClient, ApiClient};
@@ -289,7 +369,7 @@ async fn test_e2e_backoff_openai_paris_attractions() {
     
     // Configure routing strategy for OpenAI backoff
     env::set_var("ROUTING_STRATEGY", "backoff");
-    env::set_var("ROUTING_PRIMARY_PROVIDER", "openai");
+    env::set_var("ROUTING_CONFIG", "openai:gpt-4o-mini");
     
     println!("🎯 Testing routing strategy configuration:");
     let routing_strategy = RoutingStrategy::from_env().unwrap();
@@ -349,6 +429,8 @@ Remember:
 - Entries should be specific
 - One entry per line
 - Do NOT repeat any previously seen entries, even with slight variations".to_string(),
+        system_prompt: None,
+        model: None,
         max_tokens: 150,
         temperature: 0.7,
         request_id: Uuid::new_v4(),
@@ -425,7 +507,7 @@ Remember:
     
     // Clean up environment variables
     env::remove_var("ROUTING_STRATEGY");
-    env::remove_var("ROUTING_PRIMARY_PROVIDER");
+    env::remove_var("ROUTING_CONFIG");
     
     println!("🧹 Environment variables cleaned up");
 }
\ No newline at end of file
@@ -9,13 +9,13 @@ pub mod types;
 
 // Re-export commonly used types
 pub use types::{
-    ApiFailure, GenerationConfig, GenerationConstraints, OptimizationMode, ProcessId, ProcessStatus, ProducerMetrics,
+    ApiFailure, ErrorKind, ExtractionFormat, GenerationConfig, GenerationConstraints, OptimizationMode, ProcessId, ProcessStatus, ProducerMetrics,
     ProviderId, ProviderMetadata, ProviderMetrics, ProviderRequestMetadata, ProviderStatus, RequestConfig,
-    RoutingStrategy, SharedError, SystemMetrics, TokenUsage,
+    FormatEntry, RoutingParseError, RoutingStrategy, SharedError, SystemMetrics, TokenUsage, VerificationStats, derive_seed,
 };
 
 // Re-export message types
 pub use messages::{
-    producer::{OrchestratorCommand, ProducerCommand, ProducerResponse, ProducerUpdate},
+    producer::{OrchestratorCommand, ProducerCommand, ProducerResponse, ProducerUpdate, TunedLimits, PROTOCOL_VERSION},
     webserver::{OrchestratorUpdate, WebServerRequest},
 };
@@ -28,7 +28,7 @@ impl TracingEndpoint {
 }
 
 /// Structured trace event for HTTP endpoint
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TraceEvent {
     pub timestamp: DateTime<Utc>,
     pub level: String,
@@ -222,13 +222,175 @@ impl<'a> tracing::field::Visit for TraceVisitor<'a> {
     }
 }
 
+/// Line format of console log output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with event fields (including `process`) as structured keys
+    Json,
+}
+
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format: {s} (expected text or json)")),
+        }
+    }
+}
+
+/// Output options for the tracing subscriber
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    /// Log level (trace, debug, info, warn, error), defaults to info
+    pub log_level: Option<String>,
+
+    /// Write log output to stderr, keeping stdout free for data (e.g. streamed attributes)
+    pub use_stderr: bool,
+
+    /// Strip emoji/decorative symbols and ANSI colors, producing plain ASCII log lines
+    pub plain: bool,
+
+    /// Console line format
+    pub format: LogFormat,
+}
+
+impl LogOptions {
+    /// Print a configuration notice through the same channel and style as the logs
+    ///
+    /// JSON output stays machine-readable, so notices are skipped there.
+    fn announce(&self, message: &str) {
+        if self.format == LogFormat::Json {
+            return;
+        }
+        let message = if self.plain { strip_decoration(message) } else { message.to_string() };
+        if self.use_stderr {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+    }
+}
+
+/// Remove emoji and other decorative non-ASCII symbols from a log line
+///
+/// Non-ASCII letters such as "ü" in attribute values are kept, and the space a
+/// removed prefix leaves behind (as in "🚀 Starting") is dropped too.
+pub fn strip_decoration(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut skipped = false;
+
+    for c in line.chars() {
+        if c.is_ascii() || c.is_alphanumeric() {
+            let dangling_space = skipped && c == ' ' && (plain.is_empty() || plain.ends_with(char::is_whitespace));
+            if dangling_space {
+                continue;
+            }
+            plain.push(c);
+            skipped = false;
+        } else {
+            skipped = true;
+        }
+    }
+
+    plain
+}
+
+/// Writer adapter that strips decoration from everything written through it
+pub struct PlainWriter<W> {
+    inner: W,
+}
+
+impl<W> PlainWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for PlainWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // The fmt layer hands over one complete event per write, so lines are never split
+        let plain = strip_decoration(&String::from_utf8_lossy(buf));
+        self.inner.write_all(plain.as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Build the writer used by the fmt layer
+fn log_writer(options: &LogOptions) -> tracing_subscriber::fmt::writer::BoxMakeWriter {
+    use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+    match (options.use_stderr, options.plain) {
+        (true, true) => BoxMakeWriter::new(|| PlainWriter::new(std::io::stderr())),
+        (true, false) => BoxMakeWriter::new(std::io::stderr),
+        (false, true) => BoxMakeWriter::new(|| PlainWriter::new(std::io::stdout())),
+        (false, false) => BoxMakeWriter::new(std::io::stdout),
+    }
+}
+
+/// Build the console layer in the configured format
+///
+/// Text output keeps the existing styles (`compact` for the layer that sits next
+/// to HTTP tracing); JSON output nests event fields under `fields`.
+fn console_layer<S>(
+    options: &LogOptions,
+    writer: tracing_subscriber::fmt::writer::BoxMakeWriter,
+    with_target: bool,
+    compact: bool,
+) -> Box<dyn tracing_subscriber::Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    use tracing_subscriber::{Layer, fmt};
+
+    let layer = fmt::layer()
+        .with_target(with_target)
+        .with_thread_ids(false)
+        .with_file(false)
+        .with_line_number(false)
+        .with_writer(writer);
+
+    match options.format {
+        LogFormat::Json => layer.with_ansi(false).json().boxed(),
+        LogFormat::Text if compact => layer.with_ansi(!options.plain).compact().boxed(),
+        LogFormat::Text => layer.with_ansi(!options.plain).boxed(),
+    }
+}
+
 /// Initialize tracing subscriber with optional endpoint and log level
 pub fn init_tracing_with_endpoint_and_level(endpoint: Option<TracingEndpoint>, log_level: Option<&str>) {
-    use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+    let options = LogOptions {
+        log_level: log_level.map(str::to_string),
+        ..LogOptions::default()
+    };
+    init_tracing_with_options(endpoint, &options);
+}
+
+/// Initialize tracing subscriber with optional endpoint and output options
+pub fn init_tracing_with_options(endpoint: Option<TracingEndpoint>, options: &LogOptions) {
+    use tracing_subscriber::{EnvFilter, prelude::*};
 
     // Use the same filtering logic as stdout tracing for consistency
     let process_id = ProcessId::current();
-    let base_level = log_level.unwrap_or("info");
+    let base_level = options.log_level.as_deref().unwrap_or("info");
 
     let level_filter = match process_id {
         ProcessId::Orchestrator => {
@@ -246,18 +408,13 @@ pub fn init_tracing_with_endpoint_and_level(endpoint: Option<TracingEndpoint>, l
 
     match endpoint {
         Some(endpoint) => {
-            println!("📡 Tracing endpoint configured: {}", endpoint.url);
-            println!("📊 Log level: {level_filter}");
+            options.announce(&format!("📡 Tracing endpoint configured: {}", endpoint.url));
+            options.announce(&format!("📊 Log level: {level_filter}"));
 
             let http_layer = HttpTracingLayer::new(endpoint);
 
             // Also add a minimal stdout layer for immediate feedback
-            let fmt_layer = fmt::layer()
-                .with_target(true)
-                .with_thread_ids(false)
-                .with_file(false)
-                .with_line_number(false)
-                .compact();
+            let fmt_layer = console_layer(options, log_writer(options), true, true);
 
             tracing_subscriber::registry()
                 .with(env_filter)
@@ -266,8 +423,8 @@ pub fn init_tracing_with_endpoint_and_level(endpoint: Option<TracingEndpoint>, l
                 .init();
         }
         None => {
-            println!("📊 Log level: {level_filter}");
-            init_tracing_stdout_with_level(log_level);
+            options.announce(&format!("📊 Log level: {level_filter}"));
+            init_tracing_fmt(options);
         }
     }
 }
@@ -297,14 +454,14 @@ pub fn init_tracing() {
 }
 
 fn init_tracing_stdout() {
-    init_tracing_stdout_with_level(None);
+    init_tracing_fmt(&LogOptions::default());
 }
 
-fn init_tracing_stdout_with_level(log_level: Option<&str>) {
-    use tracing_subscriber::{EnvFilter, fmt};
+fn init_tracing_fmt(options: &LogOptions) {
+    use tracing_subscriber::{EnvFilter, prelude::*};
 
     let process_id = ProcessId::current();
-    let base_level = log_level.unwrap_or("info");
+    let base_level = options.log_level.as_deref().unwrap_or("info");
 
     let env_filter = match process_id {
         ProcessId::Orchestrator => {
@@ -318,12 +475,9 @@ fn init_tracing_stdout_with_level(log_level: Option<&str>) {
         }
     };
 
-    fmt()
-        .with_env_filter(EnvFilter::new(&env_filter))
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_file(false)
-        .with_line_number(false)
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&env_filter))
+        .with(console_layer(options, log_writer(options), false, false))
         .init();
 }
 
@@ -455,4 +609,95 @@ mod tests {
         assert_eq!(orchestrator.to_string(), "orchestrator");
         assert_eq!(webserver.to_string(), "webserver");
     }
+
+    #[test]
+    fn test_strip_decoration_removes_emoji_prefixes() {
+        assert_eq!(strip_decoration("🚀 Starting producer"), "Starting producer");
+        assert_eq!(strip_decoration("INFO 🖥️  Starting in CLI mode"), "INFO Starting in CLI mode");
+        assert_eq!(strip_decoration("⚠️ Failed to parse: bad"), "Failed to parse: bad");
+        assert_eq!(strip_decoration("✅ Done ✅"), "Done ");
+        assert_eq!(strip_decoration("Found München"), "Found München");
+    }
+
+    /// In-memory log sink shared between a subscriber and the test
+    #[derive(Clone, Default)]
+    struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_plain_writer_produces_ascii_log_lines() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || PlainWriter::new(writer.clone()))
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            info!(process = %ProcessId::Orchestrator, "🚀 Starting {}", "orchestrator");
+            error!(process = %ProcessId::Orchestrator, "❌ Producer {} failed", 1);
+            info!(process = %ProcessId::Orchestrator, "🔄 Healing producer pool: 📊 2 of 3 healthy");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for line in &lines {
+            assert!(line.is_ascii(), "non-ASCII decoration in log line: {line}");
+        }
+        assert!(lines[0].contains("Starting orchestrator"));
+        assert!(lines[2].contains("Healing producer pool: 2 of 3 healthy"));
+    }
+
+    #[test]
+    fn test_json_format_writes_parseable_lines_with_process_fields() {
+        use tracing_subscriber::fmt::writer::BoxMakeWriter;
+        use tracing_subscriber::prelude::*;
+
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!("TEXT".parse::<LogFormat>(), Ok(LogFormat::Text));
+        assert!("yaml".parse::<LogFormat>().is_err());
+
+        let options = LogOptions {
+            format: LogFormat::Json,
+            ..LogOptions::default()
+        };
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(console_layer(
+            &options,
+            BoxMakeWriter::new(move || writer.clone()),
+            true,
+            false,
+        ));
+
+        tracing::subscriber::with_default(subscriber, || {
+            info!(process = %ProcessId::Producer(3), provider = "openai", new_values = 7, "📦 Batch from {}", "openai");
+            error!(process = %ProcessId::Orchestrator, "❌ Producer {} failed", 1);
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> =
+            output.lines().map(|line| serde_json::from_str(line).expect("log line is JSON")).collect();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0]["level"], "INFO");
+        assert!(lines[0]["timestamp"].is_string());
+        assert_eq!(lines[0]["fields"]["process"], ProcessId::Producer(3).to_string());
+        assert_eq!(lines[0]["fields"]["provider"], "openai");
+        assert_eq!(lines[0]["fields"]["new_values"], 7);
+        assert_eq!(lines[0]["fields"]["message"], "📦 Batch from openai");
+        assert_eq!(lines[1]["level"], "ERROR");
+        assert_eq!(lines[1]["fields"]["process"], "orchestrator");
+    }
 }
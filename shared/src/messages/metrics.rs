@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Bloom filter statistics for tracking deduplication performance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BloomFilterStats {
     pub total_items: usize,
     pub unique_items: usize,
@@ -11,6 +11,9 @@ pub struct BloomFilterStats {
     pub filtered_count: usize,
     pub filter_size_bytes: usize,
     pub false_positive_rate: f64,
+    /// False-positive rate predicted from inserted items versus the filter's sized capacity
+    #[serde(default)]
+    pub estimated_false_positive_rate: f64,
 }
 
 impl BloomFilterStats {
@@ -22,6 +25,7 @@ impl BloomFilterStats {
             filtered_count,
             filter_size_bytes: 0,
             false_positive_rate: 0.0,
+            estimated_false_positive_rate: 0.0,
         }
     }
 }
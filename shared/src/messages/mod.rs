@@ -2,4 +2,5 @@
 
 pub mod metrics;
 pub mod producer;
+pub mod sync;
 pub mod webserver;
@@ -3,6 +3,10 @@
 use crate::types::{GenerationConfig, ProcessId, ProcessStatus, ProviderMetadata, RoutingStrategy};
 use serde::{Deserialize, Serialize};
 
+/// IPC protocol version spoken by this build, exchanged in the producer `Ready` handshake.
+/// Bump whenever the orchestrator ↔ producer message format changes incompatibly.
+pub const PROTOCOL_VERSION: u32 = 14;
+
 /// Commands sent from Orchestrator to Producer
 pub type ProducerCommand = OrchestratorCommand;
 
@@ -16,6 +20,17 @@ pub enum OrchestratorCommand {
         prompt: String,
         routing_strategy: RoutingStrategy,
         generation_config: GenerationConfig,
+        /// Run-level RNG seed; each producer derives its own stream via `derive_seed`
+        seed: u64,
+        /// This producer's share of the cost budget in USD per minute; None leaves it unthrottled
+        #[serde(default)]
+        max_cost_per_minute: Option<f64>,
+        /// Drop values already seen and ask the model for unseen ones; false keeps every value (--no-dedup)
+        #[serde(default = "dedup_by_default")]
+        dedup: bool,
+        /// Prompt partition this producer was assigned when prompts are split per provider; None when all share one
+        #[serde(default)]
+        category: Option<String>,
     },
 
     /// Update configuration during operation
@@ -24,6 +39,9 @@ pub enum OrchestratorCommand {
         routing_strategy: Option<RoutingStrategy>,
         generation_config: Option<GenerationConfig>,
         prompt: Option<String>,
+        /// New share of the cost budget in USD per minute; None keeps the current one and 0 clears it
+        #[serde(default)]
+        max_cost_per_minute: Option<f64>,
     },
 
     /// Sync check with optional bloom filter (health check + dedup sync)
@@ -34,15 +52,29 @@ pub enum OrchestratorCommand {
         bloom_version: Option<u64>,
         requires_dedup: bool,
         seen_values: Option<Vec<String>>,
+        /// `bloom_filter` and `compressed_seen_values` are gzip-compressed (see `messages::sync`)
+        compressed: bool,
+        /// Seen values when `compressed`; `seen_values` is then None
+        compressed_seen_values: Option<Vec<u8>>,
     },
 
     /// Stop generation
     Stop { command_id: u64 },
 
+    /// Stop issuing requests but keep the producer and its state alive
+    Pause { command_id: u64 },
+
+    /// Continue issuing requests after a Pause
+    Resume { command_id: u64 },
+
     /// Ping for health check
     Ping { ping_id: u64 },
 }
 
+fn dedup_by_default() -> bool {
+    true
+}
+
 /// Response sent from Producer to Orchestrator
 pub type ProducerResponse = ProducerUpdate;
 
@@ -55,6 +87,12 @@ pub enum ProducerUpdate {
         batch_id: u64,
         attributes: Vec<String>,
         provider_metadata: ProviderMetadata,
+        /// Cost of the request in USD, priced once by the producer from its token usage
+        #[serde(default)]
+        estimated_cost: f64,
+        /// Topic the producer was generating for, so concurrently running topics stay apart
+        #[serde(default)]
+        topic: String,
     },
 
     /// Sync acknowledgment (responds to SyncCheck)
@@ -85,7 +123,11 @@ pub enum ProducerUpdate {
     },
 
     /// Producer ready signal - sent when IPC listener is initialized
-    Ready { producer_id: ProcessId, listen_port: u16 },
+    Ready {
+        producer_id: ProcessId,
+        listen_port: u16,
+        protocol_version: u32,
+    },
 }
 
 /// Status of producer regarding sync operations
@@ -121,6 +163,20 @@ pub struct ProducerPerformanceStats {
     /// Memory and resource usage
     pub memory_usage_mb: Option<u64>,
     pub bloom_filter_size_mb: Option<f64>,
+
+    /// Limits measured by the producer's auto-tuner, suitable for pinning
+    pub tuned_limits: std::collections::HashMap<crate::types::ProviderId, TunedLimits>,
+
+    /// Cumulative verification spot checks
+    #[serde(default)]
+    pub verification: crate::types::VerificationStats,
+}
+
+/// Concurrency and request rate settled on by auto-tuning for one provider
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TunedLimits {
+    pub max_concurrent: usize,
+    pub max_rpm: u32,
 }
 
 /// Usage statistics for a specific provider by this producer
@@ -128,6 +184,7 @@ pub struct ProducerPerformanceStats {
 pub struct ProviderUsageStats {
     pub requests_sent: u64,
     pub successful_responses: u64,
+    pub failed_responses: u64,
     pub unique_attributes_contributed: u64,
     pub avg_response_time_ms: f64,
     pub last_used_timestamp: u64,
@@ -0,0 +1,103 @@
+//! Optional gzip compression for `SyncCheck` payloads
+//!
+//! On large topics the seen values dominate the sync message, so the orchestrator can
+//! compress them together with the bloom filter and producers restore them on receipt.
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{self, Read, Write};
+
+/// Uncompressed bloom filter and seen values as carried by a `SyncCheck`
+pub type SyncPayload = (Option<Vec<u8>>, Option<Vec<String>>);
+
+/// Compressed bloom filter and seen values, with sizes for reporting the ratio
+#[derive(Debug, Clone)]
+pub struct CompressedSyncPayload {
+    pub bloom_filter: Option<Vec<u8>>,
+    pub seen_values: Option<Vec<u8>>,
+    pub raw_bytes: usize,
+    pub compressed_bytes: usize,
+}
+
+impl CompressedSyncPayload {
+    /// Compressed size as a fraction of the raw size (1.0 when there was nothing to compress)
+    pub fn ratio(&self) -> f64 {
+        if self.raw_bytes == 0 {
+            1.0
+        } else {
+            self.compressed_bytes as f64 / self.raw_bytes as f64
+        }
+    }
+}
+
+/// Gzip the bloom filter and seen values carried by a `SyncCheck`
+pub fn compress_sync_payload(
+    bloom_filter: Option<&[u8]>,
+    seen_values: Option<&[String]>,
+) -> io::Result<CompressedSyncPayload> {
+    let encoded_values = seen_values.map(serde_json::to_vec).transpose()?;
+    let raw_bytes = bloom_filter.map_or(0, <[u8]>::len) + encoded_values.as_ref().map_or(0, Vec::len);
+
+    let bloom_filter = bloom_filter.map(gzip).transpose()?;
+    let seen_values = encoded_values.as_deref().map(gzip).transpose()?;
+    let compressed_bytes = bloom_filter.as_ref().map_or(0, Vec::len) + seen_values.as_ref().map_or(0, Vec::len);
+
+    Ok(CompressedSyncPayload {
+        bloom_filter,
+        seen_values,
+        raw_bytes,
+        compressed_bytes,
+    })
+}
+
+/// Restore the bloom filter and seen values produced by `compress_sync_payload`
+pub fn decompress_sync_payload(
+    bloom_filter: Option<&[u8]>,
+    seen_values: Option<&[u8]>,
+) -> io::Result<SyncPayload> {
+    let bloom_filter = bloom_filter.map(gunzip).transpose()?;
+    let seen_values = seen_values
+        .map(|data| Ok::<_, io::Error>(serde_json::from_slice(&gunzip(data)?)?))
+        .transpose()?;
+    Ok((bloom_filter, seen_values))
+}
+
+fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+fn gunzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_payload_round_trips_exactly() {
+        let seen_values: Vec<String> = (0..2_000)
+            .map(|i| format!("attribute {i}"))
+            .chain(["", "comma, quote \" and\nnewline", "émoji 🦊"].map(String::from))
+            .collect();
+        let bloom_filter: Vec<u8> = (0..4_096u32).map(|i| (i % 7) as u8).collect();
+
+        let compressed = compress_sync_payload(Some(&bloom_filter), Some(&seen_values)).unwrap();
+        assert!(compressed.ratio() < 0.5, "ratio {}", compressed.ratio());
+
+        let (restored_bloom, restored_values) =
+            decompress_sync_payload(compressed.bloom_filter.as_deref(), compressed.seen_values.as_deref()).unwrap();
+        assert_eq!(restored_bloom, Some(bloom_filter));
+        assert_eq!(restored_values, Some(seen_values));
+
+        let empty = compress_sync_payload(None, None).unwrap();
+        assert_eq!(empty.ratio(), 1.0);
+        assert_eq!(decompress_sync_payload(None, None).unwrap(), (None, None));
+        assert!(decompress_sync_payload(Some(b"not gzip"), None).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! WebServer ↔ Orchestrator communication messages
 
-use crate::types::{GenerationConstraints, OptimizationMode, SystemMetrics};
+use crate::types::{GenerationConstraints, OptimizationMode, ProcessId, ProviderId, RoutingStrategy, SystemMetrics};
 use serde::{Deserialize, Serialize};
 
 
@@ -20,11 +20,20 @@ pub enum WebServerRequest {
     },
 
     /// Stop current generation
-    StopGeneration { request_id: u64 },
+    StopGeneration { request_id: u64, mode: StopMode },
+
+    /// Pause current generation without tearing down producers
+    PauseGeneration { request_id: u64 },
+
+    /// Resume paused generation
+    ResumeGeneration { request_id: u64 },
 
     /// Request current system status
     GetStatus { request_id: u64 },
 
+    /// Request the optimizer's current plan and its rationale
+    GetOptimizationPlan { request_id: u64 },
+
     /// Update system configuration
     UpdateConfig {
         request_id: u64,
@@ -57,6 +66,8 @@ pub enum OrchestratorUpdate {
 
     /// Real-time statistics update
     StatisticsUpdate {
+        /// Set when answering a `GetStatus` request, None for periodic updates
+        request_id: Option<u64>,
         timestamp: u64,
         active_producers: u32,
         current_topic: Option<String>,
@@ -72,13 +83,108 @@ pub enum OrchestratorUpdate {
         final_unique_count: usize,
         completion_reason: CompletionReason,
     },
+
+    /// A producer joined, failed, was replaced or left the pool
+    ProducerLifecycle {
+        producer_id: ProcessId,
+        event: ProducerLifecycleEvent,
+    },
+
+    /// Final statistics answering a `StopGeneration` request
+    StopReport { request_id: u64, report: StopReport },
+
+    /// Answer to `GetOptimizationPlan`; None before the optimizer has planned anything
+    OptimizationPlan {
+        request_id: u64,
+        plan: Option<OptimizationPlan>,
+    },
 }
 
-/// Reason for generation completion
+/// The optimizer's current plan and why it chose it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptimizationPlan {
+    /// Optimizer strategy that produced the plan
+    pub strategy_name: String,
+    /// Human-readable explanation of the plan
+    pub rationale: String,
+    /// How prompts are split across producers
+    pub partitioning: PromptPartitioning,
+    /// Why each provider got its own prompt, empty for a uniform prompt
+    pub assignment_rationales: Vec<(ProviderId, String)>,
+    pub routing_strategy: RoutingStrategy,
+    /// Confidence in the plan (0.0-1.0)
+    pub confidence: f64,
+    pub expected_improvements: ExpectedImprovements,
+    pub techniques_applied: Vec<String>,
+    pub risk_factors: Vec<String>,
+}
+
+/// How an optimization plan splits prompts across producers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptPartitioning {
+    /// Every producer uses the same prompt
+    Uniform,
+    /// Producers get prompts tailored to their provider
+    PerProvider,
+}
+
+/// Changes the optimizer expects its plan to bring
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedImprovements {
+    pub uam_change_percent: f64,
+    pub cost_change_percent: f64,
+    pub quality_change_percent: f64,
+    /// Seconds until the changes should show in the metrics
+    pub time_to_effect_seconds: u64,
+}
+
+/// Where a generation stood when it was asked to stop
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopReport {
+    pub metrics: SystemMetrics,
+    pub total_unique_attributes: usize,
+    /// None while an `AfterCurrentIteration` stop waits for the iteration to finish
+    pub completion_reason: Option<CompletionReason>,
+}
+
+/// Producer pool transition reported to the dashboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProducerLifecycleEvent {
+    /// Producer reported ready and is running
+    Started,
+    /// Producer reported an unrecoverable error
+    Failed,
+    /// Failed producer was replaced by a new process
+    Restarted,
+    /// Producer left the pool for good after exhausting its restarts
+    Stopped,
+}
+
+/// How a manual stop treats the iteration in progress
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopMode {
+    /// Shut producers down right away, discarding work still in flight
+    #[default]
+    Immediate,
+    /// Send no further commands and shut producers down once the next batch is written out
+    AfterCurrentIteration,
+}
+
+/// Reason for generation completion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CompletionReason {
     /// Reached iteration limit
     IterationLimitReached,
+    /// Exceeded `GenerationConstraints::max_runtime_seconds`
+    TimeLimitReached,
+    /// Cycle efficiency stayed below `GenerationConstraints::min_efficiency_percent`
+    EfficiencyFloorReached,
+    /// Reached `GenerationConstraints::target_unique_count` unique attributes
+    TargetCountReached,
     /// Manual stop requested
     ManualStop,
     /// All producers failed
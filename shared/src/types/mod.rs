@@ -66,12 +66,28 @@ impl Default for ProcessId {
     }
 }
 
+/// Derive an independent, reproducible RNG seed for `stream` from a run-level seed.
+///
+/// Uses the SplitMix64 finalizer so neighbouring streams (e.g. producer 1 and 2)
+/// get unrelated seeds.
+pub fn derive_seed(run_seed: u64, stream: u64) -> u64 {
+    let mut z = run_seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 /// LLM providers available in the system
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Ordering follows declaration order and is used for deterministic tie-breaking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ProviderId {
     OpenAI,
     Anthropic,
     Gemini,
+    Mistral,
+    Cohere,
+    AzureOpenAI,
     Random,
 }
 
@@ -81,6 +97,9 @@ impl fmt::Display for ProviderId {
             ProviderId::OpenAI => write!(f, "openai"),
             ProviderId::Anthropic => write!(f, "anthropic"),
             ProviderId::Gemini => write!(f, "gemini"),
+            ProviderId::Mistral => write!(f, "mistral"),
+            ProviderId::Cohere => write!(f, "cohere"),
+            ProviderId::AzureOpenAI => write!(f, "azure"),
             ProviderId::Random => write!(f, "random"),
         }
     }
@@ -94,6 +113,9 @@ impl std::str::FromStr for ProviderId {
             "openai" => Ok(ProviderId::OpenAI),
             "anthropic" => Ok(ProviderId::Anthropic),
             "gemini" | "google" => Ok(ProviderId::Gemini),
+            "mistral" => Ok(ProviderId::Mistral),
+            "cohere" => Ok(ProviderId::Cohere),
+            "azure" | "azure_openai" | "azureopenai" => Ok(ProviderId::AzureOpenAI),
             "random" => Ok(ProviderId::Random),
             _ => Err(format!("Unknown provider: {s}")),
         }
@@ -178,21 +200,78 @@ pub struct GenerationConstraints {
 
     /// Maximum total runtime (seconds)
     pub max_runtime_seconds: Option<u64>,
+
+    /// Stop once cycle efficiency (new values per attempt, in percent) stays below this
+    #[serde(default)]
+    pub min_efficiency_percent: Option<f64>,
+
+    /// Consecutive low-efficiency cycles tolerated before stopping
+    /// (`DEFAULT_EFFICIENCY_PATIENCE` if omitted)
+    #[serde(default)]
+    pub efficiency_patience: Option<u32>,
+
+    /// Stop once this many unique attributes have been generated
+    #[serde(default)]
+    pub target_unique_count: Option<u64>,
 }
 
+/// Consecutive cycles below `min_efficiency_percent` before generation stops
+pub const DEFAULT_EFFICIENCY_PATIENCE: u32 = 3;
+
 /// Provider configuration with model
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ProviderConfig {
     pub provider: ProviderId,
     pub model: String,
+    /// Models tried in order when `model` is unavailable or its context window is too small
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
 }
 
+/// Separator between a model and its fallbacks in a model spec ("gpt-4o|gpt-4o-mini")
+pub const MODEL_FALLBACK_SEPARATOR: char = '|';
+
 impl ProviderConfig {
     pub fn new(provider: ProviderId, model: impl Into<String>) -> Self {
         Self {
             provider,
             model: model.into(),
+            fallback_models: Vec::new(),
+        }
+    }
+
+    /// Parse a model spec naming the model followed by its fallbacks, e.g. "gpt-4o|gpt-4o-mini"
+    pub fn from_model_spec(provider: ProviderId, spec: &str) -> Self {
+        let mut models = spec.split(MODEL_FALLBACK_SEPARATOR).map(|model| model.trim().to_string());
+        let model = models.next().unwrap_or_default();
+        Self::new(provider, model).with_fallback_models(models.filter(|model| !model.is_empty()))
+    }
+
+    /// Like `from_model_spec`, but rejects a spec without a primary model
+    pub fn try_from_model_spec(provider: ProviderId, spec: &str) -> Result<Self, RoutingParseError> {
+        let config = Self::from_model_spec(provider, spec);
+        if config.model.is_empty() {
+            return Err(RoutingParseError::InvalidModel {
+                provider,
+                token: spec.to_string(),
+            });
         }
+        Ok(config)
+    }
+
+    /// Model spec accepted by `from_model_spec`; just the model when there are no fallbacks
+    pub fn model_spec(&self) -> String {
+        std::iter::once(&self.model)
+            .chain(&self.fallback_models)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(&MODEL_FALLBACK_SEPARATOR.to_string())
+    }
+
+    /// Step down to these models, in order, when the configured model cannot serve a request
+    pub fn with_fallback_models<S: Into<String>>(mut self, models: impl IntoIterator<Item = S>) -> Self {
+        self.fallback_models = models.into_iter().map(Into::into).collect();
+        self
     }
     
     pub fn with_default_model(provider: ProviderId) -> Self {
@@ -200,6 +279,9 @@ impl ProviderConfig {
             ProviderId::OpenAI => "gpt-4o-mini",
             ProviderId::Anthropic => "claude-3-sonnet",
             ProviderId::Gemini => "gemini-pro",
+            ProviderId::Mistral => "mistral-small-latest",
+            ProviderId::Cohere => "command-r",
+            ProviderId::AzureOpenAI => "gpt-4o-mini",
             ProviderId::Random => "random",
         };
         Self::new(provider, model)
@@ -207,7 +289,7 @@ impl ProviderConfig {
 }
 
 /// Routing strategy for distributing work to providers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RoutingStrategy {
     /// Round-robin through providers with their models
     RoundRobin { providers: Vec<ProviderConfig> },
@@ -220,72 +302,296 @@ pub enum RoutingStrategy {
 
     /// Single provider with exponential backoff (ideal for test mode)
     Backoff { provider: ProviderConfig },
+
+    /// Spread across providers, skipping any with too many consecutive failures until a cooldown elapses
+    CircuitBreaker {
+        providers: Vec<ProviderConfig>,
+        failure_threshold: u32,
+        cooldown_seconds: u64,
+    },
+
+    /// Prefer the provider with the lowest average response time over the last `window_seconds`
+    LowestLatency {
+        providers: Vec<ProviderConfig>,
+        window_seconds: u64,
+    },
+}
+
+/// Consecutive failures that open a provider's circuit when not configured
+pub const DEFAULT_CIRCUIT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Seconds an open circuit waits before probing the provider again when not configured
+pub const DEFAULT_CIRCUIT_COOLDOWN_SECONDS: u64 = 30;
+
+/// Seconds of response times averaged by LowestLatency routing when not configured
+pub const DEFAULT_LATENCY_WINDOW_SECONDS: u64 = 60;
+
+/// Why a routing strategy or its configuration could not be parsed
+///
+/// Each variant names the offending token or argument so callers can report it and
+/// tests can match the kind of failure; `Display` keeps the established messages.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RoutingParseError {
+    #[error("Unknown routing strategy '{strategy}'. Valid options: backoff, roundrobin, priority, weighted, circuitbreaker, lowestlatency")]
+    UnknownStrategy { strategy: String },
+
+    /// A routing config whose `strategy` key names an unknown strategy
+    #[error("Unknown strategy: '{strategy}'")]
+    UnknownConfigStrategy { strategy: String },
+
+    #[error("Missing strategy in routing config")]
+    MissingStrategy,
+
+    /// A `key:value` routing config entry with a key that is not recognised
+    #[error("Unknown routing config key: '{key}'")]
+    UnknownKey { key: String },
+
+    /// An entry that does not have the expected `:`-separated shape
+    #[error("{}", .entry.message(.token))]
+    InvalidFormat { entry: FormatEntry, token: String },
+
+    #[error("Invalid provider '{token}': {reason}")]
+    InvalidProvider { token: String, reason: String },
+
+    #[error("Invalid model '{token}' for provider {provider}")]
+    InvalidModel { provider: ProviderId, token: String },
+
+    #[error("Invalid weight '{token}': {reason}")]
+    InvalidWeight { token: String, reason: String },
+
+    #[error("Weight {weight} must be a non-negative number")]
+    NegativeWeight { weight: f32 },
+
+    #[error("Weights must not all be zero")]
+    ZeroWeights,
+
+    #[error("Weight {weight} must be between 0.0 and 1.0")]
+    WeightOutOfRange { weight: f32 },
+
+    #[error("Weights sum to {sum:.3}, but should sum to 1.0")]
+    WeightsDoNotSumToOne { sum: f32 },
+
+    /// A numeric strategy setting (threshold, cooldown, window) that does not parse
+    #[error("Invalid {setting} '{token}': {reason}")]
+    InvalidSetting { setting: String, token: String, reason: String },
+
+    /// A strategy that routes across providers was given none; `argument` names where they were expected
+    #[error("{argument} must be specified for {strategy} strategy")]
+    MissingProviders { strategy: String, argument: String },
+
+    /// ROUTING_CONFIG names no providers for a strategy that routes across them
+    #[error("ROUTING_CONFIG must specify providers for {strategy} strategy")]
+    MissingConfigProviders { strategy: String },
+
+    #[error("{argument} must be specified for weighted strategy")]
+    MissingWeights { argument: String },
+
+    #[error("ROUTING_CONFIG must specify weighted providers for weighted strategy")]
+    MissingConfigWeights,
+
+    /// A routing config is missing a key its strategy requires
+    #[error("{strategy} strategy requires {field}")]
+    MissingField { strategy: String, field: &'static str },
+}
+
+/// Kind of `:`-separated entry in a `RoutingParseError::InvalidFormat`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatEntry {
+    /// `key:value` entry of a producer routing config
+    RoutingConfig,
+    /// `provider:model` entry of a producer routing config's provider list
+    ProviderModel,
+    /// `provider:model:weight` entry of a producer routing config's weights
+    ProviderModelWeight,
+    /// `provider:weight` entry of `--routing-weights`
+    ProviderWeight,
+    /// `provider:model` entry of ROUTING_CONFIG
+    ProviderConfig,
+    /// `provider:model:weight` entry of ROUTING_CONFIG
+    WeightedConfig,
+}
+
+impl FormatEntry {
+    /// Shape the entry should have
+    pub fn expected(self) -> &'static str {
+        match self {
+            Self::RoutingConfig => "key:value",
+            Self::ProviderModel | Self::ProviderConfig => "provider:model",
+            Self::ProviderModelWeight | Self::WeightedConfig => "provider:model:weight",
+            Self::ProviderWeight => "provider:weight",
+        }
+    }
+
+    fn message(self, token: &str) -> String {
+        match self {
+            Self::RoutingConfig => format!("Invalid routing config format: '{}'", token),
+            Self::ProviderModel => format!("Invalid provider:model format: '{}'", token),
+            Self::ProviderModelWeight => {
+                format!("Invalid weight format: '{}', expected '{}'", token, self.expected())
+            }
+            Self::ProviderWeight => format!("Invalid weight format '{}'. Expected '{}'", token, self.expected()),
+            Self::ProviderConfig => {
+                format!("Invalid provider config format '{}'. Expected '{}'", token, self.expected())
+            }
+            Self::WeightedConfig => {
+                format!("Invalid weighted config format '{}'. Expected '{}'", token, self.expected())
+            }
+        }
+    }
+}
+
+/// Parse a provider token, reporting it as `InvalidProvider` on failure
+pub fn parse_provider_token(token: &str) -> Result<ProviderId, RoutingParseError> {
+    token.trim().parse().map_err(|reason| RoutingParseError::InvalidProvider {
+        token: token.to_string(),
+        reason,
+    })
+}
+
+/// Parse a weight token, reporting it as `InvalidWeight` on failure
+pub fn parse_weight_token(token: &str) -> Result<f32, RoutingParseError> {
+    token.trim().parse().map_err(|e: std::num::ParseFloatError| RoutingParseError::InvalidWeight {
+        token: token.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Parse a numeric strategy setting such as a threshold, reporting it as `InvalidSetting` on failure
+pub fn parse_setting<T>(setting: &str, token: &str) -> Result<T, RoutingParseError>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    token.trim().parse().map_err(|e: T::Err| RoutingParseError::InvalidSetting {
+        setting: setting.to_string(),
+        token: token.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Scale weights so they sum to 1.0, keeping their proportions (`2:1` becomes `0.667:0.333`)
+///
+/// Rejects negative or non-finite weights and a zero total.
+pub fn normalize_weight_map<K>(weights: &mut HashMap<K, f32>) -> Result<(), RoutingParseError> {
+    if let Some(&weight) = weights.values().find(|weight| !weight.is_finite() || **weight < 0.0) {
+        return Err(RoutingParseError::NegativeWeight { weight });
+    }
+
+    let sum: f32 = weights.values().sum();
+    if sum <= 0.0 {
+        return Err(RoutingParseError::ZeroWeights);
+    }
+
+    for weight in weights.values_mut() {
+        *weight /= sum;
+    }
+    Ok(())
 }
 
 impl RoutingStrategy {
+    /// Rescale Weighted weights to sum to 1.0; other strategies are left untouched
+    pub fn normalize_weights(&mut self) -> Result<(), RoutingParseError> {
+        match self {
+            Self::Weighted { weights } => normalize_weight_map(weights),
+            _ => Ok(()),
+        }
+    }
+
+    /// Strict check that every Weighted weight is within 0.0-1.0 and they sum to 1.0
+    pub fn validate_unit_weights(&self) -> Result<(), RoutingParseError> {
+        let Self::Weighted { weights } = self else {
+            return Ok(());
+        };
+        if let Some(&weight) = weights.values().find(|weight| !(0.0..=1.0).contains(*weight)) {
+            return Err(RoutingParseError::WeightOutOfRange { weight });
+        }
+        let sum: f32 = weights.values().sum();
+        if (sum - 1.0).abs() > 0.01 {
+            return Err(RoutingParseError::WeightsDoNotSumToOne { sum });
+        }
+        Ok(())
+    }
+
     /// Load routing strategy from environment variables
     /// 
     /// Environment variables:
-    /// - ROUTING_STRATEGY: roundrobin|priority|weighted|backoff (default: fallback to backoff/random)
+    /// - ROUTING_STRATEGY: roundrobin|priority|weighted|backoff|circuitbreaker|lowestlatency (default: fallback to backoff/random)
     /// - ROUTING_CONFIG: Provider configuration string (format: "provider:model" or "provider1:model1,provider2:model2")
-    pub fn from_env() -> Result<Self, String> {
+    /// - ROUTING_FAILURE_THRESHOLD / ROUTING_COOLDOWN_SECONDS: circuitbreaker tuning (defaults: 3 failures, 30s)
+    /// - ROUTING_WINDOW_SECONDS: lowestlatency averaging window (default: 60s)
+    pub fn from_env() -> Result<Self, RoutingParseError> {
         use std::env;
         
         // If no routing strategy is set, fallback to backoff with random (test mode)
         let strategy_type = match env::var("ROUTING_STRATEGY") {
             Ok(strategy) => strategy.to_lowercase(),
-            Err(_) => return Ok(Self::Backoff { provider: ProviderConfig { provider: ProviderId::Random, model: "random".to_string() } }),
+            Err(_) => return Ok(Self::Backoff { provider: ProviderConfig::new(ProviderId::Random, "random") }),
         };
         
         let routing_config = env::var("ROUTING_CONFIG")
             .unwrap_or_else(|_| "random:random".to_string());
+        let providers_for = |strategy: &str| -> Result<Vec<ProviderConfig>, RoutingParseError> {
+            let providers = Self::parse_provider_config_list(&routing_config)?;
+            if providers.is_empty() {
+                return Err(RoutingParseError::MissingConfigProviders { strategy: strategy.to_string() });
+            }
+            Ok(providers)
+        };
         
         match strategy_type.as_str() {
             "backoff" => {
                 let provider_config = Self::parse_provider_config(&routing_config)?;
                 Ok(Self::Backoff { provider: provider_config })
             }
-            "roundrobin" => {
-                let providers = Self::parse_provider_config_list(&routing_config)?;
-                if providers.is_empty() {
-                    return Err("ROUTING_CONFIG must specify providers for roundrobin strategy".to_string());
-                }
-                Ok(Self::RoundRobin { providers })
-            }
-            "priority" => {
-                let providers = Self::parse_provider_config_list(&routing_config)?;
-                if providers.is_empty() {
-                    return Err("ROUTING_CONFIG must specify providers for priority strategy".to_string());
-                }
-                Ok(Self::PriorityOrder { providers })
-            }
+            "roundrobin" => Ok(Self::RoundRobin { providers: providers_for("roundrobin")? }),
+            "priority" => Ok(Self::PriorityOrder { providers: providers_for("priority")? }),
             "weighted" => {
                 let weights = Self::parse_weighted_provider_config(&routing_config)?;
                 if weights.is_empty() {
-                    return Err("ROUTING_CONFIG must specify weighted providers for weighted strategy".to_string());
+                    return Err(RoutingParseError::MissingConfigWeights);
                 }
                 Ok(Self::Weighted { weights })
             }
-            _ => Err(format!("Unknown routing strategy '{}'. Valid options: backoff, roundrobin, priority, weighted", strategy_type)),
+            "circuitbreaker" => {
+                let providers = providers_for("circuitbreaker")?;
+                let failure_threshold = match env::var("ROUTING_FAILURE_THRESHOLD") {
+                    Ok(value) => parse_setting("ROUTING_FAILURE_THRESHOLD", &value)?,
+                    Err(_) => DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+                };
+                let cooldown_seconds = match env::var("ROUTING_COOLDOWN_SECONDS") {
+                    Ok(value) => parse_setting("ROUTING_COOLDOWN_SECONDS", &value)?,
+                    Err(_) => DEFAULT_CIRCUIT_COOLDOWN_SECONDS,
+                };
+                Ok(Self::CircuitBreaker { providers, failure_threshold, cooldown_seconds })
+            }
+            "lowestlatency" => {
+                let providers = providers_for("lowestlatency")?;
+                let window_seconds = match env::var("ROUTING_WINDOW_SECONDS") {
+                    Ok(value) => parse_setting("ROUTING_WINDOW_SECONDS", &value)?,
+                    Err(_) => DEFAULT_LATENCY_WINDOW_SECONDS,
+                };
+                Ok(Self::LowestLatency { providers, window_seconds })
+            }
+            _ => Err(RoutingParseError::UnknownStrategy { strategy: strategy_type }),
         }
     }
     
-    /// Parse single provider config from format "provider:model"
-    fn parse_provider_config(config: &str) -> Result<ProviderConfig, String> {
+    /// Parse single provider config from format "provider:model" (the model may list fallbacks, "model|fallback")
+    fn parse_provider_config(config: &str) -> Result<ProviderConfig, RoutingParseError> {
         let parts: Vec<&str> = config.split(':').collect();
         if parts.len() != 2 {
-            return Err(format!("Invalid provider config format '{}'. Expected 'provider:model'", config));
+            return Err(RoutingParseError::InvalidFormat {
+                entry: FormatEntry::ProviderConfig,
+                token: config.to_string(),
+            });
         }
         
-        let provider: ProviderId = parts[0].trim().parse()
-            .map_err(|e| format!("Invalid provider '{}': {}", parts[0], e))?;
-        let model = parts[1].trim().to_string();
-        
-        Ok(ProviderConfig { provider, model })
+        let provider = parse_provider_token(parts[0])?;
+        ProviderConfig::try_from_model_spec(provider, parts[1].trim())
     }
     
     /// Parse comma-separated provider config list from format "provider1:model1,provider2:model2"
-    fn parse_provider_config_list(config: &str) -> Result<Vec<ProviderConfig>, String> {
+    fn parse_provider_config_list(config: &str) -> Result<Vec<ProviderConfig>, RoutingParseError> {
         config
             .split(',')
             .map(|item| Self::parse_provider_config(item.trim()))
@@ -293,34 +599,27 @@ impl RoutingStrategy {
     }
     
     /// Parse weighted provider config from format "provider1:model1:weight1,provider2:model2:weight2"
-    fn parse_weighted_provider_config(config: &str) -> Result<HashMap<ProviderConfig, f32>, String> {
+    fn parse_weighted_provider_config(config: &str) -> Result<HashMap<ProviderConfig, f32>, RoutingParseError> {
         let mut weights = HashMap::new();
         
         for item in config.split(',') {
             let parts: Vec<&str> = item.split(':').collect();
             if parts.len() != 3 {
-                return Err(format!("Invalid weighted config format '{}'. Expected 'provider:model:weight'", item));
+                return Err(RoutingParseError::InvalidFormat {
+                    entry: FormatEntry::WeightedConfig,
+                    token: item.to_string(),
+                });
             }
             
-            let provider: ProviderId = parts[0].trim().parse()
-                .map_err(|e| format!("Invalid provider '{}': {}", parts[0], e))?;
-            let model = parts[1].trim().to_string();
-            let weight: f32 = parts[2].trim().parse()
-                .map_err(|e| format!("Invalid weight '{}': {}", parts[2], e))?;
+            let provider = parse_provider_token(parts[0])?;
+            let weight = parse_weight_token(parts[2])?;
             
-            if weight < 0.0 || weight > 1.0 {
-                return Err(format!("Weight {} must be between 0.0 and 1.0", weight));
-            }
-            
-            let provider_config = ProviderConfig { provider, model };
+            let provider_config = ProviderConfig::try_from_model_spec(provider, parts[1].trim())?;
             weights.insert(provider_config, weight);
         }
         
-        // Validate weights sum to approximately 1.0
-        let sum: f32 = weights.values().sum();
-        if (sum - 1.0).abs() > 0.01 {
-            return Err(format!("Weights sum to {:.3}, but should sum to 1.0", sum));
-        }
+        // Weights are relative proportions, so "openai:m:2,anthropic:m:1" is a 2:1 split
+        normalize_weight_map(&mut weights)?;
         
         Ok(weights)
     }
@@ -328,7 +627,7 @@ impl RoutingStrategy {
 }
 
 /// Generation configuration for providers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GenerationConfig {
     pub model: String,
     pub batch_size: u32,
@@ -336,6 +635,81 @@ pub struct GenerationConfig {
     pub max_tokens: u32,
     pub temperature: f32,
     pub request_size: usize, // Number of words/items to request
+    /// Layout the model is asked to answer in; None keeps the lenient line-or-comma parsing
+    #[serde(default)]
+    pub extraction_format: Option<ExtractionFormat>,
+    /// Standing instructions sent as the system message; providers without roles get it prepended to the prompt
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Attributes shorter than this many characters after normalization are dropped
+    #[serde(default)]
+    pub min_attribute_length: Option<usize>,
+    /// Attributes longer than this many characters after normalization are dropped
+    #[serde(default)]
+    pub max_attribute_length: Option<usize>,
+    /// List recently generated attributes in the prompt so the model steers away from them
+    #[serde(default)]
+    pub include_seen_hint: bool,
+    /// Most recent attributes listed when `include_seen_hint` is set
+    #[serde(default = "default_seen_hint_count")]
+    pub seen_hint_count: usize,
+}
+
+/// Recent attributes listed in the seen hint when not configured
+pub const DEFAULT_SEEN_HINT_COUNT: usize = 50;
+
+fn default_seen_hint_count() -> usize {
+    DEFAULT_SEEN_HINT_COUNT
+}
+
+/// Layout of attribute lists in provider responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtractionFormat {
+    /// One entry per line
+    Lines,
+    /// One entry per line with a "1." / "2)" style prefix
+    NumberedList,
+    /// A JSON array of strings
+    JsonArray,
+    /// Entries separated by commas
+    CommaSeparated,
+}
+
+impl ExtractionFormat {
+    /// Formatting instruction added to prompts so the model answers in this layout
+    pub fn prompt_instruction(&self) -> &'static str {
+        match self {
+            ExtractionFormat::Lines => "One entry per line",
+            ExtractionFormat::NumberedList => "One entry per line, numbered as \"1. entry\"",
+            ExtractionFormat::JsonArray => "Respond with a single JSON array of strings, e.g. [\"entry\", \"entry\"]",
+            ExtractionFormat::CommaSeparated => "All entries on a single line, separated by commas",
+        }
+    }
+}
+
+impl std::fmt::Display for ExtractionFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ExtractionFormat::Lines => write!(f, "lines"),
+            ExtractionFormat::NumberedList => write!(f, "numbered"),
+            ExtractionFormat::JsonArray => write!(f, "json"),
+            ExtractionFormat::CommaSeparated => write!(f, "comma"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExtractionFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lines" => Ok(ExtractionFormat::Lines),
+            "numbered" | "numbered_list" | "numberedlist" => Ok(ExtractionFormat::NumberedList),
+            "json" | "json_array" | "jsonarray" => Ok(ExtractionFormat::JsonArray),
+            "comma" | "comma_separated" | "commaseparated" => Ok(ExtractionFormat::CommaSeparated),
+            _ => Err(format!("Unknown extraction format: {s}")),
+        }
+    }
 }
 
 /// Performance metrics for a producer
@@ -364,6 +738,29 @@ pub struct ProviderMetrics {
     pub status: ProviderStatus,
 }
 
+/// Spot checks of accepted attributes by a secondary verification provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerificationStats {
+    /// Verification requests answered
+    pub requests: u64,
+    /// Accepted attributes sampled for re-verification
+    pub samples: u64,
+    /// Sampled attributes the verification provider rejected
+    pub conflicts: u64,
+    /// Spend on verification, not counted in generation cost
+    pub cost: f64,
+}
+
+impl VerificationStats {
+    /// Add another set of counters to these
+    pub fn add(&mut self, other: &VerificationStats) {
+        self.requests += other.requests;
+        self.samples += other.samples;
+        self.conflicts += other.conflicts;
+        self.cost += other.cost;
+    }
+}
+
 /// System-wide performance metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
@@ -374,12 +771,26 @@ pub struct SystemMetrics {
     pub unique_per_dollar: f64,
     pub unique_per_1k_tokens: f64,
 
+    /// Exponential moving averages of `uam` and `cost_per_minute`, steadier for display
+    #[serde(default)]
+    pub uam_smoothed: f64,
+    #[serde(default)]
+    pub cost_per_minute_smoothed: f64,
+
     /// Breakdown by producer
     pub by_producer: HashMap<String, ProducerMetrics>,
 
     /// Breakdown by provider
     pub by_provider: HashMap<ProviderId, ProviderMetrics>,
 
+    /// Dedup bloom filter statistics, including how saturated the filter is
+    #[serde(default)]
+    pub bloom_filter: crate::messages::metrics::BloomFilterStats,
+
+    /// Verification spot checks summed across producers
+    #[serde(default)]
+    pub verification: VerificationStats,
+
     /// System state
     pub active_producers: u32,
     pub current_topic: Option<String>,
@@ -395,8 +806,12 @@ impl Default for SystemMetrics {
             tokens_per_minute: 0.0,
             unique_per_dollar: 0.0,
             unique_per_1k_tokens: 0.0,
+            uam_smoothed: 0.0,
+            cost_per_minute_smoothed: 0.0,
             by_producer: HashMap::new(),
             by_provider: HashMap::new(),
+            bloom_filter: Default::default(),
+            verification: VerificationStats::default(),
             active_producers: 0,
             current_topic: None,
             uptime_seconds: 0,
@@ -426,7 +841,7 @@ impl Default for RequestConfig {
 }
 
 /// API failure types for error handling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ApiFailure {
     RateLimitExceeded,
     InvalidApiKey,
@@ -434,6 +849,8 @@ pub enum ApiFailure {
     ModelUnavailable,
     InvalidRequest,
     InternalError,
+    /// The provider's safety filter blocked the response
+    ContentFiltered,
 }
 
 impl fmt::Display for ApiFailure {
@@ -445,17 +862,127 @@ impl fmt::Display for ApiFailure {
             ApiFailure::ModelUnavailable => write!(f, "model unavailable"),
             ApiFailure::InvalidRequest => write!(f, "invalid request"),
             ApiFailure::InternalError => write!(f, "internal error"),
+            ApiFailure::ContentFiltered => write!(f, "content filtered"),
+        }
+    }
+}
+
+impl ApiFailure {
+    const ALL: [ApiFailure; 7] = [
+        ApiFailure::RateLimitExceeded,
+        ApiFailure::InvalidApiKey,
+        ApiFailure::NetworkTimeout,
+        ApiFailure::ModelUnavailable,
+        ApiFailure::InvalidRequest,
+        ApiFailure::InternalError,
+        ApiFailure::ContentFiltered,
+    ];
+
+    /// Stable machine-readable code, safe to send across processes
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiFailure::RateLimitExceeded => "rate_limit_exceeded",
+            ApiFailure::InvalidApiKey => "invalid_api_key",
+            ApiFailure::NetworkTimeout => "network_timeout",
+            ApiFailure::ModelUnavailable => "model_unavailable",
+            ApiFailure::InvalidRequest => "invalid_request",
+            ApiFailure::InternalError => "internal_error",
+            ApiFailure::ContentFiltered => "content_filtered",
+        }
+    }
+
+    /// Failure with the given code, if it is one of ours
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|failure| failure.code() == code)
+    }
+
+    /// Classify an unsuccessful HTTP status from a provider
+    pub fn from_http_status(status: u16) -> Self {
+        match status {
+            429 => ApiFailure::RateLimitExceeded,
+            401 | 403 => ApiFailure::InvalidApiKey,
+            408 | 504 => ApiFailure::NetworkTimeout,
+            404 | 502 | 503 | 529 => ApiFailure::ModelUnavailable,
+            400..=499 => ApiFailure::InvalidRequest,
+            _ => ApiFailure::InternalError,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ApiFailure::RateLimitExceeded => ErrorKind::RateLimited,
+            ApiFailure::InvalidApiKey => ErrorKind::Authentication,
+            ApiFailure::NetworkTimeout => ErrorKind::Timeout,
+            ApiFailure::ModelUnavailable => ErrorKind::Unavailable,
+            ApiFailure::InvalidRequest => ErrorKind::InvalidRequest,
+            ApiFailure::InternalError => ErrorKind::Internal,
+            // Resending the same prompt is blocked again; only a reworded one may get through.
+            // Like any rejected request this is not fatal: the producer keeps generating.
+            ApiFailure::ContentFiltered => ErrorKind::InvalidRequest,
         }
     }
 }
 
+/// Category of a shared error, so callers can decide how to react without parsing codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorKind {
+    RateLimited,
+    Authentication,
+    Timeout,
+    Unavailable,
+    InvalidRequest,
+    Internal,
+}
+
+impl ErrorKind {
+    /// Whether the same request may succeed later without intervention
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorKind::RateLimited | ErrorKind::Timeout | ErrorKind::Unavailable)
+    }
+
+    /// Whether no later request can succeed until the credentials are fixed
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, ErrorKind::Authentication)
+    }
+}
+
 /// Shared error type for cross-package compatibility
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SharedError {
+    pub kind: ErrorKind,
     pub code: String,
     pub message: String,
 }
 
+impl SharedError {
+    /// Rebuild an error from a code received over IPC; unknown codes are internal errors
+    pub fn from_code(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let code = code.into();
+        let kind = ApiFailure::from_code(&code).map_or(ErrorKind::Internal, |failure| failure.kind());
+        Self {
+            kind,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Replace the human-readable message, keeping kind and code
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+impl From<ApiFailure> for SharedError {
+    fn from(failure: ApiFailure) -> Self {
+        Self {
+            kind: failure.kind(),
+            code: failure.code().to_string(),
+            message: failure.to_string(),
+        }
+    }
+}
+
 impl fmt::Display for SharedError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.code, self.message)
@@ -463,3 +990,106 @@ impl fmt::Display for SharedError {
 }
 
 impl std::error::Error for SharedError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weighted(weights: &[(ProviderId, f32)]) -> RoutingStrategy {
+        RoutingStrategy::Weighted {
+            weights: weights
+                .iter()
+                .map(|&(provider, weight)| (ProviderConfig::with_default_model(provider), weight))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_token_parse_errors_carry_the_offending_token() {
+        let error = parse_provider_token("nosuch").unwrap_err();
+        assert!(matches!(error, RoutingParseError::InvalidProvider { ref token, .. } if token == "nosuch"));
+        assert!(error.to_string().starts_with("Invalid provider 'nosuch': "));
+
+        let error = parse_weight_token("heavy").unwrap_err();
+        assert!(matches!(error, RoutingParseError::InvalidWeight { ref token, .. } if token == "heavy"));
+        assert!(error.to_string().starts_with("Invalid weight 'heavy': "));
+
+        let error = parse_setting::<u32>("threshold", "-3").unwrap_err();
+        assert!(matches!(
+            error,
+            RoutingParseError::InvalidSetting { ref setting, ref token, .. } if setting == "threshold" && token == "-3"
+        ));
+        assert!(error.to_string().starts_with("Invalid threshold '-3': "));
+        assert_eq!(parse_setting::<u32>("threshold", " 5 "), Ok(5));
+
+        let error = ProviderConfig::try_from_model_spec(ProviderId::OpenAI, "|gpt-4o").unwrap_err();
+        assert_eq!(
+            error,
+            RoutingParseError::InvalidModel { provider: ProviderId::OpenAI, token: "|gpt-4o".to_string() }
+        );
+        assert_eq!(error.to_string(), "Invalid model '|gpt-4o' for provider openai");
+    }
+
+    #[test]
+    fn test_weight_errors_report_the_offending_value() {
+        let mut strategy = weighted(&[(ProviderId::OpenAI, 2.0), (ProviderId::Anthropic, -1.0)]);
+        let error = strategy.normalize_weights().unwrap_err();
+        assert_eq!(error, RoutingParseError::NegativeWeight { weight: -1.0 });
+        assert_eq!(error.to_string(), "Weight -1 must be a non-negative number");
+
+        let mut strategy = weighted(&[(ProviderId::OpenAI, 0.0)]);
+        assert_eq!(strategy.normalize_weights(), Err(RoutingParseError::ZeroWeights));
+
+        let error = weighted(&[(ProviderId::OpenAI, 1.5)]).validate_unit_weights().unwrap_err();
+        assert_eq!(error, RoutingParseError::WeightOutOfRange { weight: 1.5 });
+        assert_eq!(error.to_string(), "Weight 1.5 must be between 0.0 and 1.0");
+
+        let error = weighted(&[(ProviderId::OpenAI, 0.5), (ProviderId::Anthropic, 0.25)])
+            .validate_unit_weights()
+            .unwrap_err();
+        assert_eq!(error, RoutingParseError::WeightsDoNotSumToOne { sum: 0.75 });
+        assert_eq!(error.to_string(), "Weights sum to 0.750, but should sum to 1.0");
+    }
+
+    #[test]
+    fn test_missing_argument_messages() {
+        let error = RoutingParseError::MissingConfigProviders { strategy: "roundrobin".to_string() };
+        assert_eq!(error.to_string(), "ROUTING_CONFIG must specify providers for roundrobin strategy");
+
+        let error = RoutingParseError::MissingProviders {
+            strategy: "priority".to_string(),
+            argument: "--routing-providers or ROUTING_PROVIDERS env".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "--routing-providers or ROUTING_PROVIDERS env must be specified for priority strategy"
+        );
+
+        let error = RoutingParseError::MissingWeights {
+            argument: "--routing-weights or ROUTING_WEIGHTS env".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "--routing-weights or ROUTING_WEIGHTS env must be specified for weighted strategy"
+        );
+        assert_eq!(
+            RoutingParseError::MissingConfigWeights.to_string(),
+            "ROUTING_CONFIG must specify weighted providers for weighted strategy"
+        );
+
+        assert_eq!(RoutingParseError::MissingStrategy.to_string(), "Missing strategy in routing config");
+        let error = RoutingParseError::UnknownKey { key: "colour".to_string() };
+        assert_eq!(error.to_string(), "Unknown routing config key: 'colour'");
+
+        let error = RoutingParseError::MissingField { strategy: "backoff".to_string(), field: "model" };
+        assert_eq!(error.to_string(), "backoff strategy requires model");
+    }
+
+    #[test]
+    fn test_only_invalid_api_keys_are_fatal() {
+        for failure in ApiFailure::ALL {
+            assert_eq!(failure.kind().is_fatal(), failure == ApiFailure::InvalidApiKey, "{failure}");
+        }
+        assert!(!ApiFailure::ContentFiltered.kind().is_retryable());
+    }
+}
@@ -2,20 +2,17 @@
 //!
 //! Provides a flexible builder pattern for constructing orchestrator configurations
 
-use super::fault_tolerance::FaultToleranceConfig;
 use super::{OrchestratorConfig, OrchestratorMode};
 use std::time::Duration;
 
 pub struct OrchestratorConfigBuilder {
     config: OrchestratorConfig,
-    pub fault_tolerance_config: Option<FaultToleranceConfig>,
 }
 
 impl OrchestratorConfigBuilder {
     pub fn new() -> Self {
         Self {
             config: OrchestratorConfig::default(),
-            fault_tolerance_config: None,
         }
     }
 
@@ -115,6 +112,25 @@ impl OrchestratorConfigBuilder {
         self
     }
 
+    /// Seed the run so Random provider output repeats across runs
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.config.random_seed = Some(seed);
+        self
+    }
+
+    /// Maximize unique attributes per minute while spending at most `budget` USD per minute
+    pub fn budget_per_minute(mut self, budget: f64) -> Self {
+        self.config.optimization_mode = Some("maximize-uam".to_string());
+        self.config.budget_per_minute = Some(budget);
+        self
+    }
+
+    /// Check producer health this often, bounding how long a crash goes unnoticed
+    pub fn health_interval(mut self, interval: Duration) -> Self {
+        self.config.health_interval_secs = Some(interval.as_secs().max(1));
+        self
+    }
+
     // Comprehensive fluent routing configuration API aligned with new terminology
 
     /// Configure backoff routing strategy with a single provider:model pair
@@ -371,8 +387,7 @@ impl OrchestratorConfigBuilder {
     }
 
     /// Build the configuration
-    pub fn build(mut self) -> OrchestratorConfig {
-        self.config.fault_tolerance = self.fault_tolerance_config;
+    pub fn build(self) -> OrchestratorConfig {
         self.config
     }
 }
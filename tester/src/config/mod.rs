@@ -3,8 +3,6 @@
 //! This module provides configuration structures and builders for orchestrator setup.
 
 pub mod builder;
-pub mod builder_extensions;
-pub mod fault_tolerance;
 pub mod orchestrator;
 
 // Re-export main types
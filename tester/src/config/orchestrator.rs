@@ -2,23 +2,17 @@
 //!
 //! Configuration structure for orchestrator command-line arguments
 
-use super::fault_tolerance::FaultToleranceConfig;
 use std::time::Duration;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum OrchestratorMode {
     /// CLI mode - runs for specific iterations then exits
+    #[default]
     Cli,
     /// WebServer mode - runs continuously, controlled via HTTP API
     WebServer,
 }
 
-impl Default for OrchestratorMode {
-    fn default() -> Self {
-        OrchestratorMode::Cli
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct OrchestratorConfig {
     pub mode: OrchestratorMode,
@@ -33,9 +27,16 @@ pub struct OrchestratorConfig {
     pub producer_addr: Option<String>,
     pub log_level: String,
     pub max_duration: Duration,
-    pub fault_tolerance: Option<FaultToleranceConfig>,
     pub routing_strategy: Option<String>,
     pub routing_config: Option<String>,
+    /// Run seed passed as `--seed` so Random provider output is reproducible
+    pub random_seed: Option<u64>,
+    /// Optimization mode passed as `--optimization-mode` (e.g. "maximize-uam")
+    pub optimization_mode: Option<String>,
+    /// Spend cap passed as `--budget-per-minute` for the maximize-uam mode
+    pub budget_per_minute: Option<f64>,
+    /// Seconds between producer health checks passed as `--health-interval-secs`
+    pub health_interval_secs: Option<u64>,
 }
 
 impl Default for OrchestratorConfig {
@@ -53,9 +54,12 @@ impl Default for OrchestratorConfig {
             producer_addr: None,
             log_level: "debug".to_string(),        // Default to debug for testing
             max_duration: Duration::from_secs(60), // Default 1 minute timeout
-            fault_tolerance: None,
             routing_strategy: None,
             routing_config: None,
+            random_seed: None,
+            optimization_mode: None,
+            budget_per_minute: None,
+            health_interval_secs: None,
         }
     }
 }
@@ -114,6 +118,26 @@ impl OrchestratorConfig {
             args.push(config.clone());
         }
 
+        if let Some(seed) = self.random_seed {
+            args.push("--seed".to_string());
+            args.push(seed.to_string());
+        }
+
+        if let Some(ref mode) = self.optimization_mode {
+            args.push("--optimization-mode".to_string());
+            args.push(mode.clone());
+        }
+
+        if let Some(budget) = self.budget_per_minute {
+            args.push("--budget-per-minute".to_string());
+            args.push(budget.to_string());
+        }
+
+        if let Some(interval) = self.health_interval_secs {
+            args.push("--health-interval-secs".to_string());
+            args.push(interval.to_string());
+        }
+
         // Add mode-specific arguments
         match self.mode {
             OrchestratorMode::WebServer => {
@@ -9,7 +9,7 @@
 //!
 //! ## Quick Start
 //!
-//! ```rust
+//! ```rust,ignore
 //! use tester::*;
 //!
 //! // Configure orchestrator (CLI mode by default)
@@ -43,9 +43,9 @@ pub use runtime::ServiceConstellation;
 pub use testing::Topic;
 
 // Supporting types
-pub use runtime::{CleanupManager, CollectedEvent, TraceQuery, TracingCollector};
+pub use runtime::{CleanupManager, CollectedEvent, FaultInjector, TraceQuery, TraceRecord, TracingCollector};
 pub use scenarios::TestScenarios;
-pub use testing::{AssertionResult, TracingAssertions};
+pub use testing::{AssertionResult, Replay, TracingAssertions};
 pub use testing::{OutputComparison, OutputData, OutputLoader, OutputMetadata};
 
 // Re-export web server testing function for convenience
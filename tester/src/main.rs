@@ -5,12 +5,14 @@
 //! - Sets up tracing collector to capture all trace events
 //! - Provides Topic-centric API for clean test assertions
 //! - Manages service lifecycle and cleanup
+//! - Records traces to NDJSON and replays them offline
 
 use clap::Parser;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::time::timeout;
 
-use tester::{ServiceConstellation, TestScenarios, TracingCollector};
+use tester::{Replay, ServiceConstellation, TestScenarios, TracingCollector};
 
 #[derive(Parser)]
 #[command(name = "tester")]
@@ -35,6 +37,14 @@ struct Args {
     /// Enable verbose tracing output
     #[arg(long)]
     verbose: bool,
+
+    /// Write every collected trace event to this NDJSON file when the run ends
+    #[arg(long, value_name = "PATH")]
+    export_trace: Option<PathBuf>,
+
+    /// Re-run assertions against a recorded NDJSON trace instead of starting services
+    #[arg(long, value_name = "PATH", conflicts_with = "export_trace")]
+    replay: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -44,6 +54,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing for the tester itself
     init_tester_tracing(args.verbose);
 
+    if let Some(path) = &args.replay {
+        return replay_trace(path).await;
+    }
+
     tracing::info!("🧪 Starting E2E test framework");
     tracing::info!("Scenario: {}, Timeout: {}s", args.scenario, args.timeout_secs);
 
@@ -65,6 +79,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )
     .await;
 
+    // Export before reporting so failed and timed-out runs can be replayed too
+    if let Some(path) = &args.export_trace {
+        collector.export_ndjson(path)?;
+    }
+
     match test_result {
         Ok(Ok(())) => {
             tracing::info!("✅ Test scenario '{}' completed successfully", args.scenario);
@@ -97,6 +116,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+async fn replay_trace(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("📼 Replaying recorded trace {}", path.display());
+
+    let replay = Replay::load(path)?;
+    replay.print_timeline();
+
+    if replay.verify().await {
+        tracing::info!("✅ Replayed trace passed all checks");
+        Ok(())
+    } else {
+        Err("Replayed trace failed checks".into())
+    }
+}
+
 async fn run_test_scenario(
     constellation: &mut ServiceConstellation,
     scenarios: &TestScenarios,
@@ -207,12 +207,11 @@ impl CleanupManager {
     fn is_current_process(&self, process_name: &str) -> bool {
         // Simple heuristic: if we're looking for "tester" and our binary name contains "tester"
         if process_name == "tester" {
-            if let Ok(exe_path) = std::env::current_exe() {
-                if let Some(file_name) = exe_path.file_name() {
-                    if let Some(name_str) = file_name.to_str() {
-                        return name_str.contains("tester");
-                    }
-                }
+            if let Ok(exe_path) = std::env::current_exe()
+                && let Some(file_name) = exe_path.file_name()
+                && let Some(name_str) = file_name.to_str()
+            {
+                return name_str.contains("tester");
             }
             false
         } else {
@@ -354,19 +353,18 @@ impl CleanupManager {
                 .arg("-ti")
                 .arg(format!(":{}", port))
                 .output()
-                .and_then(|output| {
+                .map(|output| {
                     let stdout = String::from_utf8_lossy(&output.stdout);
                     for line in stdout.lines() {
-                        if let Ok(pid) = line.trim().parse::<i32>() {
-                            if pid != std::process::id() as i32 {
-                                let _ = Command::new("kill")
-                                    .arg("-9")
-                                    .arg(pid.to_string())
-                                    .output();
-                            }
+                        if let Ok(pid) = line.trim().parse::<i32>()
+                            && pid != std::process::id() as i32
+                        {
+                            let _ = Command::new("kill")
+                                .arg("-9")
+                                .arg(pid.to_string())
+                                .output();
                         }
                     }
-                    Ok(())
                 });
         }
 
@@ -385,6 +383,9 @@ impl CleanupManager {
             "ANTHROPIC_API_KEY", 
             "GEMINI_API_KEY",
             "GOOGLE_API_KEY", // Alternative name for Gemini
+            "MISTRAL_API_KEY",
+            "COHERE_API_KEY",
+            "AZURE_OPENAI_API_KEY",
             "RANDOM_API_KEY",
         ];
 
@@ -438,7 +439,7 @@ mod tests {
     fn test_process_exists_nonexistent() {
         let cleanup = CleanupManager::new();
         // PID 999999 should not exist
-        assert_eq!(cleanup.process_exists(999999).unwrap(), false);
+        assert!(!cleanup.process_exists(999999).unwrap());
     }
 
     #[test]
@@ -2,13 +2,19 @@
 //!
 //! HTTP server that collects trace events from all services in the constellation.
 //! Provides query and assertion capabilities for E2E testing.
+//! Collected events can be exported as NDJSON and re-imported for offline replay.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use axum::{Router, extract::State, http::StatusCode, response::Json, routing::post};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::ProcessId;
 use shared::logging::TraceEvent;
 use tokio::net::TcpListener;
 
@@ -18,22 +24,186 @@ pub struct TracingCollector {
     server_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CollectedEvent {
     pub trace_event: TraceEvent,
     pub received_at: SystemTime,
     pub batch_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Schema version written into every exported trace record
+pub const TRACE_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// One line of an exported NDJSON trace
+///
+/// The on-disk schema is spelled out here instead of serializing `CollectedEvent`
+/// directly, so recorded traces stay readable when the in-memory types change.
+/// `received_at` is stored as integer nanoseconds so a round trip is exact.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// Always `TRACE_EXPORT_SCHEMA_VERSION` for records this build writes
+    pub schema_version: u32,
+    /// When the event was emitted (RFC 3339)
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub process: String,
+    #[serde(default)]
+    pub fields: HashMap<String, serde_json::Value>,
+    /// When the collector received the event's batch, in nanoseconds since the Unix epoch
+    pub received_at_unix_nanos: u64,
+    pub batch_id: String,
+}
+
+impl From<&CollectedEvent> for TraceRecord {
+    fn from(event: &CollectedEvent) -> Self {
+        let received_at_unix_nanos = event
+            .received_at
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_nanos() as u64)
+            .unwrap_or(0);
+
+        TraceRecord {
+            schema_version: TRACE_EXPORT_SCHEMA_VERSION,
+            timestamp: event.trace_event.timestamp,
+            level: event.trace_event.level.clone(),
+            target: event.trace_event.target.clone(),
+            message: event.trace_event.message.clone(),
+            process: event.trace_event.process.clone(),
+            fields: event.trace_event.fields.clone(),
+            received_at_unix_nanos,
+            batch_id: event.batch_id.clone(),
+        }
+    }
+}
+
+impl TryFrom<TraceRecord> for CollectedEvent {
+    type Error = io::Error;
+
+    fn try_from(record: TraceRecord) -> Result<Self, Self::Error> {
+        if record.schema_version != TRACE_EXPORT_SCHEMA_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported trace schema version {} (expected {})",
+                    record.schema_version, TRACE_EXPORT_SCHEMA_VERSION
+                ),
+            ));
+        }
+
+        Ok(CollectedEvent {
+            trace_event: TraceEvent {
+                timestamp: record.timestamp,
+                level: record.level,
+                target: record.target,
+                message: record.message,
+                process: record.process,
+                fields: record.fields,
+            },
+            received_at: UNIX_EPOCH + Duration::from_nanos(record.received_at_unix_nanos),
+            batch_id: record.batch_id,
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct TraceQuery {
     pub process_filter: Option<String>,
+    /// Exact process match, unlike the substring `process_filter`
+    #[serde(default)]
+    pub process_id: Option<ProcessId>,
     pub level_filter: Option<String>,
     pub message_contains: Option<String>,
     pub since_seconds_ago: Option<u64>,
     pub limit: Option<usize>,
 }
 
+impl TraceQuery {
+    /// Query matching every event
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only events emitted by exactly this process
+    pub fn by_process(mut self, process_id: ProcessId) -> Self {
+        self.process_id = Some(process_id);
+        self
+    }
+
+    /// Only events at this level (case-insensitive)
+    pub fn by_level(mut self, level: &str) -> Self {
+        self.level_filter = Some(level.to_string());
+        self
+    }
+
+    /// Only events whose message contains this substring
+    pub fn containing(mut self, substring: &str) -> Self {
+        self.message_contains = Some(substring.to_string());
+        self
+    }
+
+    /// Check an event against every filter set on this query
+    fn matches(&self, event: &CollectedEvent, cutoff_time: Option<SystemTime>) -> bool {
+        // Process filter
+        if let Some(ref process_filter) = self.process_filter
+            && !event.trace_event.process.contains(process_filter)
+        {
+            return false;
+        }
+
+        if let Some(ref process_id) = self.process_id
+            && event.trace_event.process != process_id.to_string()
+        {
+            return false;
+        }
+
+        // Level filter
+        if let Some(ref level_filter) = self.level_filter
+            && !event.trace_event.level.eq_ignore_ascii_case(level_filter)
+        {
+            return false;
+        }
+
+        // Message content filter
+        if let Some(ref message_contains) = self.message_contains
+            && !event.trace_event.message.contains(message_contains)
+        {
+            return false;
+        }
+
+        // Time filter
+        if let Some(cutoff) = cutoff_time
+            && event.received_at < cutoff
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Filter, order by event timestamp and limit
+    fn apply<'a>(&self, events: impl Iterator<Item = &'a CollectedEvent>) -> Vec<CollectedEvent> {
+        let cutoff_time = self
+            .since_seconds_ago
+            .map(|seconds| SystemTime::now() - Duration::from_secs(seconds));
+
+        let mut filtered: Vec<CollectedEvent> = events
+            .filter(|event| self.matches(event, cutoff_time))
+            .cloned()
+            .collect();
+
+        // Events in one batch share `received_at`, so order by when they were emitted
+        filtered.sort_by_key(|a| (a.trace_event.timestamp, a.received_at));
+
+        if let Some(limit) = self.limit {
+            filtered.truncate(limit);
+        }
+
+        filtered
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TraceQueryResponse {
     pub events: Vec<CollectedEvent>,
@@ -83,6 +253,52 @@ impl TracingCollector {
         Ok(collector)
     }
 
+    /// Offline collector holding previously recorded events, without an HTTP server
+    pub fn from_events(events: Vec<CollectedEvent>) -> Self {
+        TracingCollector {
+            events: Arc::new(Mutex::new(events.into())),
+            server_handle: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Write every collected event to `path` as NDJSON, one `TraceRecord` per line
+    ///
+    /// Events are written in the order they were collected. Returns the number written.
+    pub fn export_ndjson(&self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let events = self.get_all_events();
+        let mut writer = BufWriter::new(File::create(path.as_ref())?);
+
+        for event in &events {
+            serde_json::to_writer(&mut writer, &TraceRecord::from(event))?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        tracing::info!("💾 Exported {} trace events to {}", events.len(), path.as_ref().display());
+        Ok(events.len())
+    }
+
+    /// Read events written by `export_ndjson`, in file order
+    ///
+    /// Blank lines are skipped; any malformed record or unknown schema version fails the import.
+    pub fn import_ndjson(path: impl AsRef<Path>) -> io::Result<Vec<CollectedEvent>> {
+        let reader = BufReader::new(File::open(path.as_ref())?);
+        let mut events = Vec::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: TraceRecord = serde_json::from_str(&line).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", index + 1, e))
+            })?;
+            events.push(CollectedEvent::try_from(record)?);
+        }
+
+        Ok(events)
+    }
+
     /// Get all events (useful for debugging)
     pub fn get_all_events(&self) -> Vec<CollectedEvent> {
         let events = self.events.lock().unwrap();
@@ -107,53 +323,7 @@ impl TracingCollector {
         let start_time = std::time::Instant::now();
         let events = self.events.lock().unwrap();
 
-        let cutoff_time = query
-            .since_seconds_ago
-            .map(|seconds| SystemTime::now() - Duration::from_secs(seconds));
-
-        let mut filtered: Vec<CollectedEvent> = events
-            .iter()
-            .filter(|event| {
-                // Process filter
-                if let Some(ref process_filter) = query.process_filter {
-                    if !event.trace_event.process.contains(process_filter) {
-                        return false;
-                    }
-                }
-
-                // Level filter
-                if let Some(ref level_filter) = query.level_filter {
-                    if event.trace_event.level.to_lowercase() != level_filter.to_lowercase() {
-                        return false;
-                    }
-                }
-
-                // Message content filter
-                if let Some(ref message_contains) = query.message_contains {
-                    if !event.trace_event.message.contains(message_contains) {
-                        return false;
-                    }
-                }
-
-                // Time filter
-                if let Some(cutoff) = cutoff_time {
-                    if event.received_at < cutoff {
-                        return false;
-                    }
-                }
-
-                true
-            })
-            .cloned()
-            .collect();
-
-        // Sort by timestamp (oldest first - chronological order)
-        filtered.sort_by(|a, b| a.received_at.cmp(&b.received_at));
-
-        // Apply limit
-        if let Some(limit) = query.limit {
-            filtered.truncate(limit);
-        }
+        let filtered = query.apply(events.iter());
 
         let query_time = start_time.elapsed();
         tracing::debug!(
@@ -206,6 +376,7 @@ impl TracingCollector {
         while start_time.elapsed() < timeout {
             let query = TraceQuery {
                 process_filter: Some(process.to_string()),
+                process_id: None,
                 level_filter: None,
                 message_contains: None,
                 since_seconds_ago: None,
@@ -232,6 +403,7 @@ impl TracingCollector {
         while start_time.elapsed() < timeout {
             let query = TraceQuery {
                 process_filter: None,
+                process_id: None,
                 level_filter: None,
                 message_contains: Some(message_contains.to_string()),
                 since_seconds_ago: None,
@@ -327,7 +499,7 @@ impl TracingCollector {
         let mut all_events: Vec<CollectedEvent> = events.iter().cloned().collect();
 
         // Sort by timestamp (oldest first - chronological order)
-        all_events.sort_by(|a, b| a.received_at.cmp(&b.received_at));
+        all_events.sort_by_key(|a| a.received_at);
 
         all_events
     }
@@ -401,54 +573,120 @@ async fn query_traces(
     let events_store = events.lock().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let total_count = events_store.len();
 
-    let cutoff_time = query
-        .since_seconds_ago
-        .map(|seconds| SystemTime::now() - Duration::from_secs(seconds));
+    let filtered = query.apply(events_store.iter());
 
-    let mut filtered: Vec<CollectedEvent> = events_store
-        .iter()
-        .filter(|event| {
-            if let Some(ref process_filter) = query.process_filter {
-                if !event.trace_event.process.contains(process_filter) {
-                    return false;
-                }
-            }
+    let query_time_ms = start_time.elapsed().as_millis() as u64;
 
-            if let Some(ref level_filter) = query.level_filter {
-                if event.trace_event.level.to_lowercase() != level_filter.to_lowercase() {
-                    return false;
-                }
-            }
+    Ok(Json(TraceQueryResponse {
+        events: filtered,
+        total_count,
+        query_time_ms,
+    }))
+}
 
-            if let Some(ref message_contains) = query.message_contains {
-                if !event.trace_event.message.contains(message_contains) {
-                    return false;
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn event(process: &str, level: &str, message: &str, second: u32) -> CollectedEvent {
+        CollectedEvent {
+            trace_event: TraceEvent {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, second).unwrap(),
+                level: level.to_string(),
+                target: "test".to_string(),
+                message: message.to_string(),
+                process: process.to_string(),
+                fields: std::collections::HashMap::new(),
+            },
+            received_at: SystemTime::now(),
+            batch_id: "batch".to_string(),
+        }
+    }
 
-            if let Some(cutoff) = cutoff_time {
-                if event.received_at < cutoff {
-                    return false;
-                }
-            }
+    fn collector_with(events: Vec<CollectedEvent>) -> TracingCollector {
+        TracingCollector::from_events(events)
+    }
 
-            true
-        })
-        .cloned()
-        .collect();
+    #[test]
+    fn test_query_filters_by_process_level_and_message() {
+        let collector = collector_with(vec![
+            event("producer_10", "WARN", "producer restarted", 1),
+            event("producer_1", "WARN", "producer restarted", 4),
+            event("producer_1", "INFO", "producer restarted", 2),
+            event("producer_1", "warn", "rate limited", 3),
+            event("orchestrator", "WARN", "producer restarted", 0),
+        ]);
+
+        let by_process = collector.query(&TraceQuery::new().by_process(ProcessId::Producer(1)));
+        assert_eq!(by_process.len(), 3);
+        assert!(by_process.iter().all(|e| e.trace_event.process == "producer_1"));
+
+        let warnings = collector.query(&TraceQuery::new().by_process(ProcessId::Producer(1)).by_level("warn"));
+        let messages: Vec<&str> = warnings.iter().map(|e| e.trace_event.message.as_str()).collect();
+        assert_eq!(messages, vec!["rate limited", "producer restarted"]);
+
+        let restarts = collector.query(
+            &TraceQuery::new()
+                .by_process(ProcessId::Producer(1))
+                .by_level("WARN")
+                .containing("restarted"),
+        );
+        assert_eq!(restarts.len(), 1);
+        assert_eq!(restarts[0].trace_event.timestamp.timestamp() % 60, 4);
 
-    // Sort by timestamp (oldest first - chronological order)
-    filtered.sort_by(|a, b| a.received_at.cmp(&b.received_at));
+        let all_warnings = collector.query(&TraceQuery::new().by_level("warn"));
+        let seconds: Vec<i64> = all_warnings
+            .iter()
+            .map(|e| e.trace_event.timestamp.timestamp() % 60)
+            .collect();
+        assert_eq!(seconds, vec![0, 1, 3, 4]);
+    }
 
-    if let Some(limit) = query.limit {
-        filtered.truncate(limit);
+    #[test]
+    fn test_export_then_import_yields_identical_events() {
+        let mut with_fields = event("producer_2", "INFO", "📦 Batch from openai", 5);
+        with_fields
+            .trace_event
+            .fields
+            .insert("provider".to_string(), serde_json::json!("openai"));
+        with_fields
+            .trace_event
+            .fields
+            .insert("count".to_string(), serde_json::json!(12));
+        with_fields.batch_id = "other-batch".to_string();
+
+        // Stored order, not timestamp order, is what gets preserved
+        let events = vec![
+            event("orchestrator", "INFO", "✅ Topic 'cats' started with 3 iteration budget", 9),
+            with_fields,
+            event("producer_1", "ERROR", "request failed: \"quoted\"\nsecond line", 2),
+        ];
+        let collector = collector_with(events.clone());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.ndjson");
+        assert_eq!(collector.export_ndjson(&path).unwrap(), 3);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 3);
+        let first: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+        assert_eq!(first["schema_version"], TRACE_EXPORT_SCHEMA_VERSION);
+        assert!(first["received_at_unix_nanos"].is_u64());
+
+        let imported = TracingCollector::import_ndjson(&path).unwrap();
+        assert_eq!(imported, events);
     }
 
-    let query_time_ms = start_time.elapsed().as_millis() as u64;
+    #[test]
+    fn test_import_rejects_unknown_schema_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.ndjson");
+        let mut record = TraceRecord::from(&event("orchestrator", "INFO", "hello", 0));
+        record.schema_version = TRACE_EXPORT_SCHEMA_VERSION + 1;
+        std::fs::write(&path, serde_json::to_string(&record).unwrap()).unwrap();
 
-    Ok(Json(TraceQueryResponse {
-        events: filtered,
-        total_count,
-        query_time_ms,
-    }))
+        let error = TracingCollector::import_ndjson(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
 }
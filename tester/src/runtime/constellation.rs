@@ -5,6 +5,7 @@
 
 use crate::config::OrchestratorConfig;
 use crate::runtime::cleanup::CleanupManager;
+use shared::ProcessId;
 use std::process::{Child, Command};
 use std::time::Duration;
 use tokio::time::sleep;
@@ -12,6 +13,8 @@ use tokio::time::sleep;
 pub struct ServiceConstellation {
     trace_endpoint: String,
     orchestrator: Option<Child>,
+    /// Configuration of the last started orchestrator, reused by `restart_orchestrator`
+    last_config: Option<OrchestratorConfig>,
     cleanup_manager: CleanupManager,
 }
 
@@ -20,6 +23,7 @@ impl ServiceConstellation {
         Self {
             trace_endpoint,
             orchestrator: None,
+            last_config: None,
             cleanup_manager: CleanupManager::new(),
         }
     }
@@ -41,6 +45,58 @@ impl ServiceConstellation {
             return Err(format!("Invalid configuration for {} mode", mode_str).into());
         }
 
+        self.spawn_orchestrator(config).await
+    }
+
+    /// Stop the running orchestrator and start it again with the same configuration
+    ///
+    /// Unlike `start_orchestrator` this skips the pre-test cleanup, so output written
+    /// before the restart is kept.
+    pub async fn restart_orchestrator(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.last_config.clone().ok_or("No orchestrator has been started")?;
+
+        tracing::info!("🔄 Restarting orchestrator");
+        self.shutdown().await?;
+        self.spawn_orchestrator(config).await
+    }
+
+    /// Kill one producer process with SIGKILL, returning the PIDs that were signalled
+    ///
+    /// Producers are spawned by the orchestrator rather than by the constellation, so
+    /// they are found by their `--id` argument. Only the producer binary is matched,
+    /// not the `cargo run` wrapper around it, so the orchestrator sees a crashed producer.
+    #[cfg(unix)]
+    pub async fn kill_producer(&self, producer_id: &ProcessId) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+        use nix::sys::signal::{self, Signal};
+        use nix::unistd::Pid;
+
+        let pids = find_producer_pids(producer_id)?;
+        if pids.is_empty() {
+            return Err(format!("No running process found for {}", producer_id).into());
+        }
+
+        for &pid in &pids {
+            tracing::warn!("💀 Killing {} (PID: {})", producer_id, pid);
+            signal::kill(Pid::from_raw(pid as i32), Signal::SIGKILL)?;
+        }
+
+        Ok(pids)
+    }
+
+    /// Killing a producer needs unix signals; elsewhere the fault cannot be injected
+    #[cfg(not(unix))]
+    pub async fn kill_producer(&self, producer_id: &ProcessId) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+        Err(format!("Cannot kill {}: fault injection is only supported on unix", producer_id).into())
+    }
+
+    /// Spawn the orchestrator binary, remembering its configuration for restarts
+    async fn spawn_orchestrator(&mut self, config: OrchestratorConfig) -> Result<(), Box<dyn std::error::Error>> {
+        let mode_str = match config.mode {
+            crate::config::OrchestratorMode::Cli => "CLI",
+            crate::config::OrchestratorMode::WebServer => "WebServer",
+        };
+        self.last_config = Some(config.clone());
+
         // Build config with trace endpoint
         let mut final_config = config;
         if final_config.trace_endpoint.is_none() {
@@ -230,6 +286,33 @@ impl ServiceConstellation {
     }
 }
 
+/// PIDs of running producer binaries with the given id
+#[cfg(unix)]
+fn find_producer_pids(producer_id: &ProcessId) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+    let ProcessId::Producer(id) = producer_id else {
+        return Err(format!("{} is not a producer", producer_id).into());
+    };
+
+    // The orchestrator passes the numeric id followed by further arguments, so the
+    // trailing space keeps producer 1 from matching producer 10
+    let output = Command::new("pgrep")
+        .arg("-f") // Match full command line
+        .arg(format!("producer --id {} ", id))
+        .output()?;
+
+    if !output.status.success() {
+        // pgrep returns non-zero when no processes found
+        return Ok(Vec::new());
+    }
+
+    let pids = String::from_utf8(output.stdout)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(pids)
+}
+
 impl Drop for ServiceConstellation {
     fn drop(&mut self) {
         // Emergency cleanup - force kill any remaining processes
@@ -5,10 +5,12 @@
 pub mod cleanup;
 pub mod collector;
 pub mod constellation;
-pub mod fault_injector_stub;
+pub mod fault_injector;
 
 // Re-export main types
 pub use cleanup::CleanupManager;
-pub use collector::{CollectedEvent, CollectorStats, TraceQuery, TracingCollector};
+pub use collector::{
+    CollectedEvent, CollectorStats, TRACE_EXPORT_SCHEMA_VERSION, TraceQuery, TraceRecord, TracingCollector,
+};
 pub use constellation::ServiceConstellation;
-pub use fault_injector_stub::FaultInjector;
+pub use fault_injector::{FaultInjector, InjectionEvent};
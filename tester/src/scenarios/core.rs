@@ -2,7 +2,8 @@
 //!
 //! Essential system functionality tests
 
-use crate::{OrchestratorConfig, ServiceConstellation, Topic, TracingCollector};
+use crate::{FaultInjector, OrchestratorConfig, ServiceConstellation, Topic, TracingCollector};
+use shared::{ProcessId, ProviderId};
 use std::time::Duration;
 
 /// Test basic orchestrator + producer functionality
@@ -25,6 +26,7 @@ pub async fn basic(
             "Should start with budget"
         );
         assert!(topic.assert_completed().await, "Should complete");
+        assert!(topic.assert_provider_used(ProviderId::Random), "Should route to the random provider");
         tracing::info!("✅ Basic: PASSED");
     } else {
         return Err("Basic test failed".into());
@@ -50,9 +52,10 @@ pub async fn load(
 
     constellation.start_orchestrator(config).await?;
 
-    if let Some(topic) = Topic::wait_for_topic("load", collector, Duration::from_secs(60)).await {
+    if let Some(topic) = Topic::wait_for_topic("load", collector, Duration::from_secs(150)).await {
         assert!(topic.assert_completed().await, "Should complete under load");
         assert!(topic.assert_min_attributes(200), "Should generate many attributes");
+        assert!(topic.assert_provider_used(ProviderId::OpenAI), "Should route to OpenAI");
         tracing::info!("✅ Load: PASSED");
     } else {
         return Err("Load test failed".into());
@@ -61,7 +64,7 @@ pub async fn load(
     Ok(())
 }
 
-/// Test healing functionality (system heals when producers fail)
+/// Test healing functionality (a producer killed mid-run is detected and restarted)
 pub async fn healing(
     collector: TracingCollector,
     constellation: &mut ServiceConstellation,
@@ -70,17 +73,25 @@ pub async fn healing(
 
     let config = OrchestratorConfig::builder()
         .topic("healing")
-        .with_openai_provider() // Use OpenAI provider (we have API key)
-        .producers(3) // Reduced for more manageable testing
-        .iterations(Some(8)) // Reduced for faster testing
+        .with_random_provider() // Deterministic and free, the fault is injected instead
+        .producers(3)
+        .iterations(Some(30)) // Long enough for the kill to land mid-run
+        .health_interval(Duration::from_secs(1))
         .log_level("debug")
         .build();
 
     constellation.start_orchestrator(config).await?;
 
+    let producer = ProcessId::Producer(1);
+    let mut injector = FaultInjector::new().kill_after(Duration::from_secs(3));
+    let kill = injector.kill_producer(constellation, &producer).await?;
+
     if let Some(topic) = Topic::wait_for_topic("healing", collector, Duration::from_secs(90)).await {
         assert!(topic.assert_completed().await, "Should complete despite failures");
-        // Note: healing is automatic in the system - producers naturally fail and get restarted
+        assert!(
+            topic.assert_producer_healed(&producer, kill.timestamp, Duration::from_secs(15)),
+            "Should detect and restart the killed producer"
+        );
         tracing::info!("✅ Healing: PASSED");
     } else {
         return Err("Healing test failed".into());
@@ -131,6 +142,122 @@ pub async fn single_start_command(
     Ok(())
 }
 
+/// Test that round-robin routing spreads batches evenly across its providers
+///
+/// Pairs the random provider with OpenAI so only one API key is needed. Each producer
+/// rotates independently and failed requests produce no batch, so the balance check
+/// allows a wide margin rather than demanding an exact split.
+pub async fn roundrobin(
+    collector: TracingCollector,
+    constellation: &mut ServiceConstellation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("🧪 Round-robin: batches spread across providers");
+
+    if dotenv::dotenv().is_err() {
+        tracing::warn!("⚠️ No .env file found in current directory");
+    }
+    if std::env::var("OPENAI_API_KEY").is_err() {
+        return Err("OPENAI_API_KEY environment variable is required for roundrobin test. Check .env file.".into());
+    }
+
+    let config = OrchestratorConfig::builder()
+        .topic("roundrobin_test")
+        .with_round_robin_strategy("random:random,openai:gpt-4o-mini")
+        .producers(2)
+        .iterations(Some(6))
+        .log_level("info")
+        .build();
+
+    constellation.start_orchestrator(config).await?;
+
+    if let Some(topic) = Topic::wait_for_topic("roundrobin_test", collector, Duration::from_secs(120)).await {
+        assert!(topic.assert_completed().await, "Should complete");
+        assert!(topic.assert_provider_used(ProviderId::Random), "Should route to the random provider");
+        assert!(topic.assert_provider_used(ProviderId::OpenAI), "Should route to OpenAI");
+        assert!(topic.assert_balanced_within(0.5), "Should split batches roughly evenly");
+        tracing::info!("✅ Round-robin: PASSED");
+    } else {
+        return Err("Round-robin test failed".into());
+    }
+
+    Ok(())
+}
+
+/// Test that a maximize-UAM run makes producers throttle against the cost budget
+///
+/// The cap is set below the cost of a single Random request, so every producer's share
+/// is exhausted by its first batch and its following requests must be spread out. The
+/// Random vocabulary runs dry well before the producers' one-minute spend window would
+/// settle, so the run is too short to compare cost per minute against the cap directly;
+/// the throttled ticks producers report are checked instead (see
+/// `Topic::assert_cost_throttled`), and a budget that never reaches them fails.
+pub async fn budget(
+    collector: TracingCollector,
+    constellation: &mut ServiceConstellation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("🧪 Budget: producers throttle against the cost budget");
+
+    const BUDGET_PER_MINUTE: f64 = 0.0002;
+
+    let config = OrchestratorConfig::builder()
+        .topic("budget_test")
+        .with_random_provider()
+        .producers(2)
+        .iterations(Some(4))
+        .budget_per_minute(BUDGET_PER_MINUTE)
+        .log_level("debug") // Throttled producer ticks are logged at debug
+        .build();
+
+    constellation.start_orchestrator(config).await?;
+
+    if let Some(topic) = Topic::wait_for_topic("budget_test", collector, Duration::from_secs(90)).await {
+        assert!(topic.assert_completed().await, "Should complete");
+        assert!(
+            topic.assert_cost_throttled(BUDGET_PER_MINUTE),
+            "Producers should throttle against the budget"
+        );
+        tracing::info!("✅ Budget: PASSED");
+    } else {
+        return Err("Budget test failed".into());
+    }
+
+    Ok(())
+}
+
+/// Test that deduplication holds end-to-end, leaving no repeated line in output.txt
+///
+/// The random provider draws every response from one fixed word list, and each request
+/// asks for a large share of it, so producers collide with each other and with earlier
+/// iterations almost immediately. Runs started with `--no-dedup` keep duplicates on
+/// purpose; `Topic::output_has_no_duplicates` detects that from the trace and skips.
+pub async fn dedup(
+    collector: TracingCollector,
+    constellation: &mut ServiceConstellation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("🧪 Dedup: no duplicate attributes in output");
+
+    let config = OrchestratorConfig::builder()
+        .topic("dedup_test")
+        .with_random_provider()
+        .producers(3)
+        .iterations(Some(10))
+        .log_level("info")
+        .build();
+
+    constellation.start_orchestrator(config).await?;
+
+    if let Some(topic) = Topic::wait_for_topic("dedup_test", collector, Duration::from_secs(150)).await {
+        assert!(topic.assert_completed().await, "Should complete");
+        assert!(topic.assert_min_attributes(50), "Should generate attributes");
+        assert!(topic.output_has_no_duplicates(), "Output should hold each attribute once");
+        tracing::info!("✅ Dedup: PASSED");
+    } else {
+        return Err("Dedup test failed".into());
+    }
+
+    Ok(())
+}
+
 /// Test end-to-end scenario with OpenAI (real API)
 pub async fn e2e_openai(
     _collector: TracingCollector,
@@ -243,6 +370,7 @@ pub async fn trace_capture(
     if has_orchestrator {
         let orchestrator_events = collector.query(&crate::runtime::collector::TraceQuery {
             process_filter: Some("orchestrator".to_string()),
+            process_id: None,
             level_filter: None,
             message_contains: None,
             since_seconds_ago: None,
@@ -257,6 +385,7 @@ pub async fn trace_capture(
     if has_producers {
         let producer_events = collector.query(&crate::runtime::collector::TraceQuery {
             process_filter: Some("producer_".to_string()),
+            process_id: None,
             level_filter: None,
             message_contains: None,
             since_seconds_ago: None,
@@ -273,6 +402,7 @@ pub async fn trace_capture(
     if has_webserver {
         let webserver_events = collector.query(&crate::runtime::collector::TraceQuery {
             process_filter: Some("webserver".to_string()),
+            process_id: None,
             level_filter: None,
             message_contains: None,
             since_seconds_ago: None,
@@ -301,7 +431,7 @@ pub async fn real_api(
     tracing::info!("🧪 Real API: Testing OpenAI integration with backoff strategy");
 
     // Load .env file to ensure API keys are available (same as orchestrator does)
-    if let Err(_) = dotenv::dotenv() {
+    if dotenv::dotenv().is_err() {
         tracing::warn!("⚠️ No .env file found in current directory");
     }
 
@@ -324,6 +454,7 @@ pub async fn real_api(
     // Wait for real API calls (should be faster with 1 producer and 2 iterations)
     if let Some(topic) = Topic::wait_for_topic("paris attractions", collector, Duration::from_secs(120)).await {
         assert!(topic.assert_completed().await, "Should complete successfully with real API");
+        assert!(topic.assert_provider_used(ProviderId::OpenAI), "Should route to OpenAI");
 
         // Check output file exists and contains real content
         let output_path = "./output/paris attractions/output.txt";
@@ -41,6 +41,9 @@ impl TestScenarios {
             "single_start" => core::single_start_command(collector, constellation).await,
             "trace_capture" => core::trace_capture(collector, constellation).await,
             "real_api" => core::real_api(collector, constellation).await,
+            "budget" => core::budget(collector, constellation).await,
+            "roundrobin" => core::roundrobin(collector, constellation).await,
+            "dedup" => core::dedup(collector, constellation).await,
 
             // Web/HTTP interface tests
             "server" => web::server(collector, constellation).await,
@@ -100,7 +103,7 @@ impl TestScenarios {
     pub fn available_scenarios() -> Vec<&'static str> {
         vec![
             // Individual tests
-            "basic", "load", "healing", "single_start", "trace_capture", "real_api", // Core functionality
+            "basic", "load", "healing", "single_start", "trace_capture", "real_api", "budget", "roundrobin", "dedup", // Core functionality
             "server",  // Web interface
             "minimal", "empty", // Edge cases
             // Test suites
@@ -72,6 +72,7 @@ impl TracingAssertions {
         if found {
             let query = TraceQuery {
                 process_filter: Some(process.to_string()),
+                process_id: None,
                 message_contains: Some(message_contains.to_string()),
                 level_filter: None,
                 since_seconds_ago: None,
@@ -179,6 +180,7 @@ impl TracingAssertions {
         for process in &expected_processes {
             let query = TraceQuery {
                 process_filter: Some(process.to_string()),
+                process_id: None,
                 message_contains: Some("Tracing endpoint configured".to_string()),
                 level_filter: None,
                 since_seconds_ago: Some(30),
@@ -271,6 +273,7 @@ impl TracingAssertions {
 
         let query = TraceQuery {
             process_filter: None,
+            process_id: None,
             level_filter: Some("ERROR".to_string()),
             message_contains: None,
             since_seconds_ago: Some(since_seconds_ago),
@@ -313,6 +316,7 @@ impl TracingAssertions {
 
         let query = TraceQuery {
             process_filter: Some(process.to_string()),
+            process_id: None,
             message_contains: Some(message_contains.to_string()),
             level_filter: None,
             since_seconds_ago: Some(time_window_seconds),
@@ -354,6 +358,7 @@ impl TracingAssertions {
     pub fn print_recent_events(&self, limit: usize) {
         let query = TraceQuery {
             process_filter: None,
+            process_id: None,
             level_filter: None,
             message_contains: None,
             since_seconds_ago: Some(30),
@@ -381,6 +386,7 @@ impl TracingAssertions {
         if self.collector.wait_for_topic_completion(topic, timeout).await {
             let completion_events = self.collector.query(&crate::runtime::TraceQuery {
                 process_filter: Some("orchestrator".to_string()),
+                process_id: None,
                 level_filter: None,
                 message_contains: Some(format!("✅ Topic '{}' completed after", topic)),
                 since_seconds_ago: None,
@@ -392,7 +398,7 @@ impl TracingAssertions {
                     .with_details(format!("Completion message: {}", event.trace_event.message))
             } else {
                 AssertionResult::failure(
-                    format!("Topic completion detected but completion event not found"),
+                    "Topic completion detected but completion event not found".to_string(),
                     Some("This is an unexpected internal error".to_string()),
                 )
             }
@@ -419,7 +425,7 @@ impl TracingAssertions {
         );
 
         // Wait for all producers to receive their start commands
-        let start_command_message = format!("Sending start command to producer");
+        let start_command_message = "Sending start command to producer".to_string();
         let found = self.collector.wait_for_message(&start_command_message, timeout).await;
 
         if !found {
@@ -432,7 +438,8 @@ impl TracingAssertions {
         // Query for all start command events (look for the "first time" pattern from our fix)
         let query = TraceQuery {
             process_filter: Some("orchestrator".to_string()),
-            message_contains: Some(format!("Sending start command to producer")),
+            process_id: None,
+            message_contains: Some("Sending start command to producer".to_string()),
             level_filter: None,
             since_seconds_ago: Some(60), // Look at last minute
             limit: None,
@@ -461,10 +468,10 @@ impl TracingAssertions {
             
             for event in &topic_start_events {
                 // Extract producer ID from message like "Sending start command to producer producer_1"
-                if let Some(producer_part) = event.trace_event.message.split("to producer ").nth(1) {
-                    if let Some(producer_id) = producer_part.split(' ').next() {
-                        *producer_start_counts.entry(producer_id.to_string()).or_insert(0) += 1;
-                    }
+                if let Some(producer_part) = event.trace_event.message.split("to producer ").nth(1)
+                    && let Some(producer_id) = producer_part.split(' ').next()
+                {
+                    *producer_start_counts.entry(producer_id.to_string()).or_insert(0) += 1;
                 }
             }
 
@@ -503,9 +510,7 @@ impl TracingAssertions {
                     "Expected {} Start commands for topic '{}', but found {}",
                     expected_count, topic, actual_count
                 ),
-                Some(format!(
-                    "This could indicate missing producers or duplicate start commands"
-                )),
+                Some("This could indicate missing producers or duplicate start commands".to_string()),
             )
         }
     }
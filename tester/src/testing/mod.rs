@@ -4,9 +4,11 @@
 
 pub mod assertions;
 pub mod output;
+pub mod replay;
 pub mod topic;
 
 // Re-export main types
 pub use assertions::{AssertionResult, TracingAssertions};
 pub use output::{OutputComparison, OutputData, OutputLoader, OutputMetadata};
+pub use replay::Replay;
 pub use topic::Topic;
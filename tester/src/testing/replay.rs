@@ -0,0 +1,213 @@
+//! Trace Replay
+//!
+//! Rebuilds a finished run from a trace exported with `TracingCollector::export_ndjson`,
+//! so its cycle timeline can be inspected and its assertions re-run without live services.
+
+use crate::runtime::{CollectedEvent, TraceQuery, TracingCollector};
+use crate::testing::Topic;
+use orchestrator::core::state::CycleStats;
+use std::io;
+use std::path::Path;
+
+/// Marker of the per-iteration statistics line the orchestrator logs
+const CYCLE_STATS_MARKER: &str = "CYCLE_STATS:";
+
+/// A recorded run loaded back into an offline collector
+#[derive(Debug, Clone)]
+pub struct Replay {
+    collector: TracingCollector,
+}
+
+impl Replay {
+    /// Load a trace written by `TracingCollector::export_ndjson`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let events = TracingCollector::import_ndjson(path.as_ref())?;
+        tracing::info!("📼 Loaded {} trace events from {}", events.len(), path.as_ref().display());
+        Ok(Self::from_events(events))
+    }
+
+    /// Replay events already in memory
+    pub fn from_events(events: Vec<CollectedEvent>) -> Self {
+        Self {
+            collector: TracingCollector::from_events(events),
+        }
+    }
+
+    /// Offline collector holding the recorded events
+    pub fn collector(&self) -> &TracingCollector {
+        &self.collector
+    }
+
+    /// Reconstruct the orchestrator's per-iteration statistics from its CYCLE_STATS events
+    ///
+    /// Cycles are returned in emission order. `duration_seconds` is measured from the
+    /// first recorded event, since the orchestrator's own start time is not in the trace.
+    pub fn cycle_timeline(&self) -> Vec<CycleStats> {
+        let events = self.collector.query(&TraceQuery::new());
+        let Some(first) = events.first() else {
+            return Vec::new();
+        };
+        let start = first.trace_event.timestamp;
+
+        events
+            .iter()
+            .filter_map(|event| {
+                let mut stats = parse_cycle_stats(&event.trace_event.message)?;
+                stats.timestamp = event.trace_event.timestamp.to_rfc3339();
+                stats.duration_seconds = (event.trace_event.timestamp - start)
+                    .to_std()
+                    .map(|elapsed| elapsed.as_secs_f64())
+                    .unwrap_or(0.0);
+                Some(stats)
+            })
+            .collect()
+    }
+
+    /// Topic recorded in the trace, ready for assertions
+    ///
+    /// Output files are not part of the trace, so output assertions see no attributes.
+    pub fn topic(&self) -> Option<Topic> {
+        let topic_name = self.collector.extract_topic()?;
+        Topic::from_trace(&topic_name, self.collector.clone(), None)
+    }
+
+    /// Re-run the standard checks against the recorded run
+    ///
+    /// The topic must have completed, no ERROR event may appear anywhere in the trace,
+    /// and the cycle timeline must advance monotonically. Errors are counted over the
+    /// whole recording because the live assertions' "last N seconds" window is relative
+    /// to the current time, which means nothing for a replayed trace.
+    pub async fn verify(&self) -> bool {
+        let Some(topic) = self.topic() else {
+            tracing::error!("❌ No topic found in recorded trace");
+            return false;
+        };
+        let mut passed = topic.assert_completed().await;
+
+        let errors = self.collector.query(&TraceQuery::new().by_level("ERROR"));
+        if errors.is_empty() {
+            tracing::info!("✅ No error messages in recorded trace");
+        } else {
+            for event in errors.iter().take(5) {
+                tracing::error!("❌ {}: {}", event.trace_event.process, event.trace_event.message);
+            }
+            tracing::error!("❌ Found {} error messages in recorded trace", errors.len());
+            passed = false;
+        }
+
+        let timeline = self.cycle_timeline();
+        let monotonic = timeline
+            .windows(2)
+            .all(|pair| pair[1].iteration > pair[0].iteration && pair[1].total_values >= pair[0].total_values);
+        if monotonic {
+            tracing::info!("✅ Cycle timeline is consistent ({} cycles)", timeline.len());
+        } else {
+            tracing::error!("❌ Cycle timeline goes backwards");
+            passed = false;
+        }
+
+        passed
+    }
+
+    /// Print the reconstructed cycle timeline
+    pub fn print_timeline(&self) {
+        println!("\n📈 Cycle timeline:");
+        for cycle in self.cycle_timeline() {
+            println!(
+                "  [{:>7.2}s] iteration {:>3}: total={} new={} duplicates={} efficiency={:.2}% (Δ {:+.2}%)",
+                cycle.duration_seconds,
+                cycle.iteration,
+                cycle.total_values,
+                cycle.new_values,
+                cycle.duplicate_values,
+                cycle.efficiency,
+                cycle.efficiency_delta
+            );
+        }
+    }
+}
+
+/// Parse the `key=value` pairs of a CYCLE_STATS message, leaving timing fields empty
+fn parse_cycle_stats(message: &str) -> Option<CycleStats> {
+    let (_, pairs) = message.split_once(CYCLE_STATS_MARKER)?;
+    let value = |key: &str| {
+        pairs.split(',').find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == key).then(|| value.trim_end_matches('%'))
+        })
+    };
+
+    Some(CycleStats {
+        iteration: value("iteration")?.parse().ok()?,
+        total_values: value("total_values")?.parse().ok()?,
+        new_values: value("new_values")?.parse().ok()?,
+        duplicate_values: value("duplicate_values")?.parse().ok()?,
+        efficiency: value("efficiency")?.parse().ok()?,
+        efficiency_delta: value("efficiency_delta")?.parse().ok()?,
+        timestamp: String::new(),
+        duration_seconds: 0.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use shared::logging::TraceEvent;
+    use std::time::SystemTime;
+
+    fn event(level: &str, message: &str, second: u32) -> CollectedEvent {
+        CollectedEvent {
+            trace_event: TraceEvent {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, second).unwrap(),
+                level: level.to_string(),
+                target: "orchestrator".to_string(),
+                message: message.to_string(),
+                process: "orchestrator".to_string(),
+                fields: std::collections::HashMap::new(),
+            },
+            received_at: SystemTime::now(),
+            batch_id: "batch".to_string(),
+        }
+    }
+
+    fn recorded_run(extra: Vec<CollectedEvent>) -> Replay {
+        let mut events = vec![
+            event("INFO", "✅ Topic 'cats' started with 2 iteration budget", 0),
+            event(
+                "INFO",
+                "📊 CYCLE_STATS: iteration=1, total_values=10, new_values=10, duplicate_values=0, efficiency=100.00%, efficiency_delta=0.00%",
+                2,
+            ),
+            event(
+                "INFO",
+                "📊 CYCLE_STATS: iteration=2, total_values=14, new_values=4, duplicate_values=6, efficiency=40.00%, efficiency_delta=-60.00%",
+                5,
+            ),
+            event("INFO", "✅ Topic 'cats' completed after 2 iterations", 6),
+        ];
+        events.extend(extra);
+        Replay::from_events(events)
+    }
+
+    #[test]
+    fn test_cycle_timeline_is_rebuilt_from_cycle_stats_events() {
+        let timeline = recorded_run(Vec::new()).cycle_timeline();
+
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[1].iteration, 2);
+        assert_eq!(timeline[1].total_values, 14);
+        assert_eq!(timeline[1].duplicate_values, 6);
+        assert_eq!(timeline[1].efficiency, 40.0);
+        assert_eq!(timeline[1].efficiency_delta, -60.0);
+        assert_eq!(timeline[0].duration_seconds, 2.0);
+        assert_eq!(timeline[1].duration_seconds, 5.0);
+        assert_eq!(timeline[1].timestamp, "2025-01-01T00:00:05+00:00");
+    }
+
+    #[tokio::test]
+    async fn test_verify_fails_on_recorded_errors() {
+        assert!(recorded_run(Vec::new()).verify().await);
+        assert!(!recorded_run(vec![event("ERROR", "producer crashed", 3)]).verify().await);
+    }
+}
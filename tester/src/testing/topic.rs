@@ -4,18 +4,35 @@
 //! centered around topic execution and assertion.
 
 use crate::{
-    runtime::{CollectedEvent, TracingCollector},
+    runtime::{CollectedEvent, TraceQuery, TracingCollector},
     testing::{OutputData, OutputLoader, TracingAssertions},
 };
+use chrono::{DateTime, Utc};
+use orchestrator::NormalizationMode;
+use shared::{ProcessId, ProviderId};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::time::Duration;
 
+/// Prefix of the per-batch event producers emit, tagged with a `provider` field
+const PROVIDER_BATCH_MARKER: &str = "📦 Batch from";
+
+/// Text preceding the budget share in the line producers log for a throttled tick
+const COST_THROTTLE_MARKER: &str = "/min against a $";
+
+/// Prefix of the line the orchestrator logs when a non-exact normalization mode is set
+const NORMALIZATION_MARKER: &str = "Deduplication normalization: ";
+
+/// Prefix of the line the orchestrator logs when run with `--no-dedup`
+const DEDUP_DISABLED_MARKER: &str = "Deduplication disabled";
+
 /// Main interface for E2E testing scenarios
 ///
 /// Represents a topic execution with access to traces and output data
 #[derive(Debug)]
 pub struct Topic {
     name: String,
-    _collector: TracingCollector,
+    collector: TracingCollector,
     assertions: TracingAssertions,
     trace_events: Vec<CollectedEvent>,
     output_data: Option<OutputData>,
@@ -34,14 +51,6 @@ impl Topic {
             return None;
         }
 
-        // Extract trace events for this topic
-        let trace_events = collector.get_topic_trace_subset(topic_name);
-
-        if trace_events.is_empty() {
-            tracing::error!("❌ No trace events found for topic '{}'", topic_name);
-            return None;
-        }
-
         // Try to load output data
         let output_data = match OutputLoader::load_from_topic(topic_name) {
             Ok(data) => {
@@ -54,6 +63,21 @@ impl Topic {
             }
         };
 
+        Self::from_trace(topic_name, collector, output_data)
+    }
+
+    /// Build a Topic from events the collector already holds, without waiting
+    ///
+    /// Used for live runs once the topic has completed and for replaying recorded traces.
+    pub fn from_trace(topic_name: &str, collector: TracingCollector, output_data: Option<OutputData>) -> Option<Self> {
+        // Extract trace events for this topic
+        let trace_events = collector.get_topic_trace_subset(topic_name);
+
+        if trace_events.is_empty() {
+            tracing::error!("❌ No trace events found for topic '{}'", topic_name);
+            return None;
+        }
+
         let assertions = TracingAssertions::new(collector.clone());
 
         tracing::info!(
@@ -64,7 +88,7 @@ impl Topic {
 
         Some(Topic {
             name: topic_name.to_string(),
-            _collector: collector,
+            collector,
             assertions,
             trace_events,
             output_data,
@@ -113,7 +137,7 @@ impl Topic {
 
                     return Some(Topic {
                         name: topic_name.to_string(),
-                        _collector: collector,
+                        collector,
                         assertions,
                         trace_events,
                         output_data,
@@ -230,6 +254,118 @@ impl Topic {
         }
     }
 
+    /// Assert that producers stretched their request interval to stay within the budget
+    ///
+    /// Producers log every throttled tick with their share of the budget, so this fails
+    /// when no producer throttled or one throttled against more than the whole budget.
+    pub fn assert_cost_throttled(&self, budget: f64) -> bool {
+        let shares = self.cost_throttle_shares();
+        if shares.is_empty() {
+            tracing::error!("❌ No producer throttled its requests against the ${:.4}/min budget", budget);
+            return false;
+        }
+
+        // Shares are logged rounded to four decimals
+        match shares.iter().find(|share| **share > budget + 0.00005) {
+            Some(share) => {
+                tracing::error!(
+                    "❌ Producer throttled against a ${:.4}/min share of a ${:.4}/min budget",
+                    share,
+                    budget
+                );
+                false
+            }
+            None => {
+                tracing::info!(
+                    "✅ Producers throttled {} requests against the ${:.4}/min budget",
+                    shares.len(),
+                    budget
+                );
+                true
+            }
+        }
+    }
+
+    /// Budget share reported with each throttled producer tick, in order
+    pub fn cost_throttle_shares(&self) -> Vec<f64> {
+        self.trace_events
+            .iter()
+            .filter_map(|event| {
+                let message = &event.trace_event.message;
+                let start = message.find(COST_THROTTLE_MARKER)? + COST_THROTTLE_MARKER.len();
+                message[start..].split('/').next()?.parse().ok()
+            })
+            .collect()
+    }
+
+    /// Number of successful batches each provider produced, from producer batch events
+    pub fn provider_counts(&self) -> HashMap<ProviderId, usize> {
+        let mut counts = HashMap::new();
+        for event in self.trace_events_containing(PROVIDER_BATCH_MARKER) {
+            let provider = event
+                .trace_event
+                .fields
+                .get("provider")
+                .and_then(|value| value.as_str())
+                .and_then(|value| ProviderId::from_str(value).ok());
+            if let Some(provider) = provider {
+                *counts.entry(provider).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Assert that the provider produced at least one batch
+    pub fn assert_provider_used(&self, provider: ProviderId) -> bool {
+        match self.provider_counts().get(&provider) {
+            Some(count) => {
+                tracing::info!("✅ Provider {} produced {} batches", provider, count);
+                true
+            }
+            None => {
+                tracing::error!("❌ Provider {} produced no batches", provider);
+                false
+            }
+        }
+    }
+
+    /// Assert that every provider that produced batches stayed within `tolerance` of the mean
+    ///
+    /// `tolerance` is a fraction of the mean batch count, so 0.2 accepts providers
+    /// producing between 80% and 120% of the average. Fails when no batches were seen.
+    pub fn assert_balanced_within(&self, tolerance: f64) -> bool {
+        let counts = self.provider_counts();
+        if counts.is_empty() {
+            tracing::error!("❌ No provider batches recorded for '{}'", self.name);
+            return false;
+        }
+
+        let mean = counts.values().sum::<usize>() as f64 / counts.len() as f64;
+        let unbalanced: Vec<String> = counts
+            .iter()
+            .filter(|(_, count)| (**count as f64 - mean).abs() > mean * tolerance)
+            .map(|(provider, count)| format!("{provider}={count}"))
+            .collect();
+
+        if unbalanced.is_empty() {
+            tracing::info!(
+                "✅ {} providers balanced within {:.0}% of {:.1} batches",
+                counts.len(),
+                tolerance * 100.0,
+                mean
+            );
+            true
+        } else {
+            tracing::error!(
+                "❌ Providers outside {:.0}% of {:.1} batches: {}",
+                tolerance * 100.0,
+                mean,
+                unbalanced.join(", ")
+            );
+            false
+        }
+    }
+
     // === Output Assertions ===
 
     /// Assert minimum number of attributes generated
@@ -244,6 +380,59 @@ impl Topic {
         }
     }
 
+    /// Assert that no attribute appears twice in output.txt
+    ///
+    /// Lines are compared under the run's normalization mode, read from the
+    /// orchestrator's startup log (exact when none was logged). A run started with
+    /// `--no-dedup` keeps duplicates by design, so the check is skipped and passes.
+    pub fn output_has_no_duplicates(&self) -> bool {
+        if !self.collector.query(&TraceQuery::new().containing(DEDUP_DISABLED_MARKER)).is_empty() {
+            tracing::info!("⏭️ Deduplication disabled for this run, skipping duplicate check");
+            return true;
+        }
+        let output = match OutputLoader::load_from_topic(&self.name) {
+            Ok(output) => output,
+            Err(e) => {
+                tracing::error!("❌ No output data available: {}", e);
+                return false;
+            }
+        };
+
+        let mode = self.normalization_mode();
+        let mut seen = HashSet::new();
+        let duplicates: Vec<&String> = output
+            .attributes
+            .iter()
+            .filter(|attribute| !seen.insert(mode.normalize(attribute)))
+            .collect();
+
+        if duplicates.is_empty() {
+            tracing::info!("✅ All {} attributes are unique ({} normalization)", output.attribute_count(), mode);
+            true
+        } else {
+            tracing::error!(
+                "❌ Found {} duplicate attributes ({} normalization), e.g. {:?}",
+                duplicates.len(),
+                mode,
+                &duplicates[..duplicates.len().min(5)]
+            );
+            false
+        }
+    }
+
+    /// Normalization mode the run deduplicated with, as logged by the orchestrator
+    fn normalization_mode(&self) -> NormalizationMode {
+        self.collector
+            .query(&TraceQuery::new().containing(NORMALIZATION_MARKER))
+            .iter()
+            .rev()
+            .find_map(|event| {
+                let (_, mode) = event.trace_event.message.split_once(NORMALIZATION_MARKER)?;
+                mode.trim().parse().ok()
+            })
+            .unwrap_or(NormalizationMode::Exact)
+    }
+
     /// Assert that specific attributes were generated
     pub fn assert_contains_attributes(&self, expected_attributes: &[&str]) -> bool {
         if let Some(output) = &self.output_data {
@@ -312,6 +501,44 @@ impl Topic {
         }
     }
 
+    /// Assert that the orchestrator detected a killed producer and restarted it in time
+    ///
+    /// Looks for the health check's failure event and then the restart event for
+    /// `producer_id` after `killed_at`, and requires the restart within `within` of the kill.
+    pub fn assert_producer_healed(&self, producer_id: &ProcessId, killed_at: DateTime<Utc>, within: Duration) -> bool {
+        let after_kill = |marker: &str, since: DateTime<Utc>| {
+            self.trace_events
+                .iter()
+                .filter(|event| event.trace_event.timestamp >= since)
+                .find(|event| event.trace_event.message.contains(marker))
+                .map(|event| event.trace_event.timestamp)
+        };
+
+        let Some(detected_at) = after_kill(&format!("🔥 Producer {} has failed", producer_id), killed_at) else {
+            tracing::error!("❌ Orchestrator never detected that {} failed", producer_id);
+            return false;
+        };
+        let Some(restarted_at) = after_kill(&format!("✅ Producer {} successfully restarted", producer_id), detected_at)
+        else {
+            tracing::error!("❌ {} was detected as failed but never restarted", producer_id);
+            return false;
+        };
+
+        let healing_time = (restarted_at - killed_at).to_std().unwrap_or_default();
+        if healing_time <= within {
+            tracing::info!("✅ {} restarted {:?} after it was killed", producer_id, healing_time);
+            true
+        } else {
+            tracing::error!(
+                "❌ {} took {:?} to restart, expected within {:?}",
+                producer_id,
+                healing_time,
+                within
+            );
+            false
+        }
+    }
+
     // === Utility Methods ===
 
     /// Print summary of topic execution
@@ -361,3 +588,74 @@ impl Topic {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use shared::logging::TraceEvent;
+    use std::time::SystemTime;
+
+    fn event(message: &str, second: u32) -> CollectedEvent {
+        CollectedEvent {
+            trace_event: TraceEvent {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, second).unwrap(),
+                level: "INFO".to_string(),
+                target: "orchestrator".to_string(),
+                message: message.to_string(),
+                process: "orchestrator".to_string(),
+                fields: HashMap::new(),
+            },
+            received_at: SystemTime::now(),
+            batch_id: "batch".to_string(),
+        }
+    }
+
+    fn healing_run(healing_events: Vec<CollectedEvent>) -> Topic {
+        let mut events = vec![event("✅ Topic 'healing' started with 20 iteration budget", 0)];
+        events.extend(healing_events);
+        events.push(event("✅ Topic 'healing' completed after 20 iterations", 30));
+        Topic::from_trace("healing", TracingCollector::from_events(events), None).unwrap()
+    }
+
+    #[test]
+    fn test_producer_healed_requires_detection_and_restart_within_bound() {
+        let killed_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 5).unwrap();
+        let producer = ProcessId::Producer(1);
+
+        let healed = healing_run(vec![
+            event("🔥 Producer producer_1 has failed", 6),
+            event("✅ Producer producer_1 successfully restarted", 8),
+        ]);
+        assert!(healed.assert_producer_healed(&producer, killed_at, Duration::from_secs(5)));
+        assert!(!healed.assert_producer_healed(&producer, killed_at, Duration::from_secs(2)));
+        assert!(!healed.assert_producer_healed(&ProcessId::Producer(2), killed_at, Duration::from_secs(5)));
+
+        // A failure from before the kill does not count as detecting it
+        let stale = healing_run(vec![
+            event("🔥 Producer producer_1 has failed", 2),
+            event("✅ Producer producer_1 successfully restarted", 3),
+        ]);
+        assert!(!stale.assert_producer_healed(&producer, killed_at, Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_duplicate_check_follows_dedup_settings_from_startup_log() {
+        let run = |startup: &[&str]| {
+            let mut events: Vec<CollectedEvent> = startup.iter().map(|message| event(message, 0)).collect();
+            events.push(event("✅ Topic 'no_such_dedup_topic' started with 5 iteration budget", 1));
+            events.push(event("✅ Topic 'no_such_dedup_topic' completed after 5 iterations", 9));
+            Topic::from_trace("no_such_dedup_topic", TracingCollector::from_events(events), None).unwrap()
+        };
+
+        assert_eq!(run(&[]).normalization_mode(), NormalizationMode::Exact);
+        assert_eq!(
+            run(&["🔤 Deduplication normalization: case-insensitive-trimmed"]).normalization_mode(),
+            NormalizationMode::CaseInsensitiveTrimmed
+        );
+
+        // Without output the check fails, unless the run kept duplicates on purpose
+        assert!(!run(&[]).output_has_no_duplicates());
+        assert!(run(&["🔁 Deduplication disabled: every generated attribute is kept"]).output_has_no_duplicates());
+    }
+}
@@ -3,7 +3,7 @@
 //! Pure business logic for analyzing metrics and generating insights
 
 use chrono::{Duration, Utc};
-use std::collections::HashMap;
+use std::fmt::Write;
 
 use crate::core::state::TimestampedMetrics;
 use crate::types::{InsightType, OptimizationInsight, TrendDirection};
@@ -352,6 +352,128 @@ impl AnalyticsEngine {
     }
 }
 
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+impl AnalyticsEngine {
+    /// Render metrics in the Prometheus text exposition format
+    pub fn render_prometheus(metrics: &SystemMetrics) -> String {
+        let mut out = String::new();
+
+        let system_gauges = [
+            ("igentai_uam", "Unique attributes per minute", metrics.uam),
+            (
+                "igentai_cost_per_minute",
+                "Spend in USD per minute",
+                metrics.cost_per_minute,
+            ),
+            (
+                "igentai_tokens_per_minute",
+                "Tokens consumed per minute",
+                metrics.tokens_per_minute,
+            ),
+            (
+                "igentai_active_producers",
+                "Producers currently running",
+                f64::from(metrics.active_producers),
+            ),
+            (
+                "igentai_uptime_seconds",
+                "Orchestrator uptime in seconds",
+                metrics.uptime_seconds as f64,
+            ),
+            (
+                "igentai_bloom_false_positive_rate",
+                "Estimated dedup bloom filter false-positive rate",
+                metrics.bloom_filter.estimated_false_positive_rate,
+            ),
+        ];
+        for (name, help, value) in system_gauges {
+            write_gauge_header(&mut out, name, help);
+            let _ = writeln!(out, "{name} {}", prometheus_value(value));
+        }
+
+        if let Some(topic) = &metrics.current_topic {
+            write_gauge_header(&mut out, "igentai_generation_info", "Topic currently being generated");
+            let _ = writeln!(
+                out,
+                "igentai_generation_info{{topic=\"{}\"}} 1",
+                escape_label_value(topic)
+            );
+        }
+
+        // Sorted so consecutive scrapes list providers in the same order
+        let mut providers: Vec<_> = metrics.by_provider.iter().collect();
+        providers.sort_by_key(|(provider_id, _)| **provider_id);
+        let provider_gauges: [ProviderGauge; 5] = [
+            (
+                "igentai_provider_uam",
+                "Unique attributes per minute by provider",
+                |m| m.uam,
+            ),
+            (
+                "igentai_provider_cost_per_minute",
+                "Spend in USD per minute by provider",
+                |m| m.cost_per_minute,
+            ),
+            (
+                "igentai_provider_tokens_per_minute",
+                "Tokens consumed per minute by provider",
+                |m| m.tokens_per_minute,
+            ),
+            (
+                "igentai_provider_response_time_ms",
+                "Average response time in milliseconds by provider",
+                |m| m.avg_response_time_ms,
+            ),
+            (
+                "igentai_provider_success_rate",
+                "Fraction of successful requests by provider",
+                |m| m.success_rate,
+            ),
+        ];
+        if !providers.is_empty() {
+            for (name, help, value) in provider_gauges {
+                write_gauge_header(&mut out, name, help);
+                for (provider_id, provider_metrics) in &providers {
+                    let _ = writeln!(
+                        out,
+                        "{name}{{provider=\"{}\"}} {}",
+                        escape_label_value(&provider_id.to_string()),
+                        prometheus_value(value(provider_metrics))
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Metric name, help text and the field it reads from a provider's metrics
+type ProviderGauge = (&'static str, &'static str, fn(&shared::ProviderMetrics) -> f64);
+
+fn write_gauge_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+}
+
+/// Format a sample value, spelling non-finite values the way Prometheus expects
+fn prometheus_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape backslashes, quotes and newlines in a label value
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
 /// Result of trend analysis
 #[derive(Debug, Clone)]
 struct TrendResult {
@@ -368,6 +490,7 @@ impl Default for AnalyticsEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
     // Test imports moved to individual tests as needed
 
     #[test]
@@ -387,8 +510,12 @@ mod tests {
             unique_per_dollar: 20.0,
             tokens_per_minute: 1000.0,
             unique_per_1k_tokens: 10.0,
+            uam_smoothed: 10.0,
+            cost_per_minute_smoothed: 0.5,
             by_producer: HashMap::new(),
             by_provider: HashMap::new(),
+            bloom_filter: Default::default(),
+            verification: Default::default(),
             active_producers: 1,
             current_topic: Some("test".to_string()),
             uptime_seconds: 3600,
@@ -399,4 +526,64 @@ mod tests {
         // Should not generate insights without history
         assert!(insights.is_empty());
     }
+
+    #[test]
+    fn test_prometheus_output_is_well_formed() {
+        let provider = shared::ProviderMetrics {
+            uam: 12.5,
+            tokens_per_minute: 800.0,
+            cost_per_minute: 0.25,
+            unique_per_dollar: 50.0,
+            unique_per_1k_tokens: 15.0,
+            avg_response_time_ms: 420.0,
+            success_rate: 0.98,
+            status: shared::ProviderStatus::Healthy,
+        };
+        let metrics = SystemMetrics {
+            uam: 20.0,
+            cost_per_minute: 0.4,
+            tokens_per_minute: 1300.0,
+            by_provider: HashMap::from([
+                (shared::ProviderId::OpenAI, provider.clone()),
+                (shared::ProviderId::Anthropic, provider),
+            ]),
+            active_producers: 3,
+            current_topic: Some("say \"cheese\"\\n\nnow".to_string()),
+            ..SystemMetrics::default()
+        };
+
+        let output = AnalyticsEngine::render_prometheus(&metrics);
+
+        let mut declared = Vec::new();
+        for line in output.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = rest.split_once(' ').unwrap();
+                assert_eq!(kind, "gauge");
+                declared.push(name.to_string());
+                continue;
+            }
+            if line.starts_with("# HELP ") {
+                continue;
+            }
+            // Every sample is `name{labels} value` with a declared name and a numeric value
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            let name = series.split('{').next().unwrap();
+            assert!(
+                declared.iter().any(|declared| declared == name),
+                "undeclared metric {name}"
+            );
+            assert!(value.parse::<f64>().is_ok(), "bad value in {line}");
+            if let Some(labels) = series.strip_prefix(name).filter(|labels| !labels.is_empty()) {
+                assert!(labels.starts_with('{') && labels.ends_with('}'), "bad labels in {line}");
+            }
+        }
+
+        assert!(output.contains("igentai_uam 20\n"));
+        assert!(output.contains("igentai_active_producers 3\n"));
+        assert!(output.contains("igentai_provider_uam{provider=\"openai\"} 12.5\n"));
+        assert!(output.contains("igentai_provider_success_rate{provider=\"anthropic\"} 0.98\n"));
+        assert!(output.contains(r#"igentai_generation_info{topic="say \"cheese\"\\n\nnow"} 1"#));
+        // Providers are listed in a stable order
+        assert!(output.find("provider=\"openai\"").unwrap() < output.find("provider=\"anthropic\"").unwrap());
+    }
 }
@@ -7,4 +7,7 @@ pub mod state;
 
 // Re-export commonly used types
 pub use analytics::AnalyticsEngine;
-pub use state::{TimestampedMetrics, WebServerState};
+pub use state::{
+    DEFAULT_ATTRIBUTE_HISTORY_CAPACITY, DEFAULT_METRICS_HISTORY_CAPACITY, DEFAULT_OUTPUT_DIR, DEFAULT_PROVIDER_STALE_AFTER,
+    TimestampedMetrics, WebServerState,
+};
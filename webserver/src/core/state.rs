@@ -3,16 +3,30 @@
 //! Pure business logic with no I/O dependencies
 
 use chrono::{DateTime, Utc};
-use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::types::{
     ActivityEvent, ActivityType, Alert, AlertLevel, ClientMessage, ClientSession, DashboardData, OptimizationInsight,
-    PerformanceMetrics, SystemHealth, TrendDirection, convert_to_websocket_message,
+    PerformanceMetrics, ProviderHealth, SystemHealth, TrendDirection, convert_to_websocket_message,
 };
-use shared::messages::webserver::CompletionReason;
-use shared::{OrchestratorUpdate, SystemMetrics};
+use shared::messages::webserver::{CompletionReason, ProducerLifecycleEvent};
+use shared::{OrchestratorUpdate, ProviderId, ProviderMetrics, ProviderStatus, SystemMetrics};
+
+/// Default number of recent unique attributes kept for late-joining clients
+pub const DEFAULT_ATTRIBUTE_HISTORY_CAPACITY: usize = 10_000;
+
+/// Default time without activity after which a provider is reported offline
+pub const DEFAULT_PROVIDER_STALE_AFTER: Duration = Duration::from_secs(60);
+
+/// Default number of statistics snapshots kept for `/api/history`
+pub const DEFAULT_METRICS_HISTORY_CAPACITY: usize = 100;
+
+/// Default orchestrator output directory served by `/api/output/:topic`
+pub const DEFAULT_OUTPUT_DIR: &str = "./output";
 
 /// Central WebServer state containing all business logic
 pub struct WebServerState {
@@ -42,10 +56,34 @@ pub struct WebServerState {
 
     /// Currently active generation
     active_generation: Option<ActiveGeneration>,
+
+    /// Recent unique attributes, oldest first, for backfilling new clients
+    attribute_history: VecDeque<String>,
+
+    /// Membership index over `attribute_history`
+    attribute_index: HashSet<String>,
+
+    /// Maximum attributes retained before the oldest are evicted
+    max_attribute_history: usize,
+
+    /// Bearer token required by protected endpoints (None disables authentication)
+    auth_token: Option<String>,
+
+    /// Whether read-only endpoints and the dashboard also require the token
+    auth_all: bool,
+
+    /// When each provider last produced attributes or changed its reported metrics
+    provider_activity: HashMap<ProviderId, Instant>,
+
+    /// Inactivity after which a provider is reported offline
+    provider_stale_after: Duration,
+
+    /// Orchestrator output directory holding one subdirectory per topic
+    output_dir: PathBuf,
 }
 
 /// Metrics with timestamp for historical tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TimestampedMetrics {
     pub metrics: SystemMetrics,
     pub timestamp: DateTime<Utc>,
@@ -71,36 +109,121 @@ impl WebServerState {
             active_alerts: HashMap::new(),
             orchestrator_connected: false,
             start_time: Instant::now(),
-            max_history_size: 100, // Keep last 100 metric updates
+            max_history_size: DEFAULT_METRICS_HISTORY_CAPACITY,
             active_generation: None,
+            attribute_history: VecDeque::new(),
+            attribute_index: HashSet::new(),
+            max_attribute_history: DEFAULT_ATTRIBUTE_HISTORY_CAPACITY,
+            auth_token: None,
+            auth_all: false,
+            provider_activity: HashMap::new(),
+            provider_stale_after: DEFAULT_PROVIDER_STALE_AFTER,
+            output_dir: PathBuf::from(DEFAULT_OUTPUT_DIR),
         }
     }
 
+    /// Require `Authorization: Bearer <token>` on control endpoints
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Extend bearer authentication to read-only endpoints and the dashboard
+    pub fn with_auth_all(mut self, auth_all: bool) -> Self {
+        self.auth_all = auth_all;
+        self
+    }
+
+    /// Bearer token protected endpoints require, if authentication is enabled
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// Whether every endpoint, not just control endpoints, requires the token
+    pub fn auth_all(&self) -> bool {
+        self.auth_all
+    }
+
+    /// Set how long a provider may go without activity before it is reported offline
+    pub fn with_provider_stale_after(mut self, stale_after: Duration) -> Self {
+        self.provider_stale_after = stale_after;
+        self
+    }
+
+    /// Set the orchestrator output directory topic downloads are read from
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = output_dir.into();
+        self
+    }
+
+    /// Orchestrator output directory topic downloads are read from
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    /// Set how many recent unique attributes are kept for `/api/attributes`
+    pub fn with_attribute_history_capacity(mut self, capacity: usize) -> Self {
+        self.max_attribute_history = capacity;
+        while self.attribute_history.len() > capacity {
+            self.evict_oldest_attribute();
+        }
+        self
+    }
+
     /// Process orchestrator update and generate client messages
     pub fn process_orchestrator_update(&mut self, update: OrchestratorUpdate) -> Vec<ClientMessage> {
         let mut client_messages = Vec::new();
 
         match update {
-            OrchestratorUpdate::NewAttributes { ref attributes, .. } => {
-                // Add activity event
+            OrchestratorUpdate::NewAttributes {
+                ref attributes,
+                ref provider_metadata,
+            } => {
+                // Add activity event, attributed to the provider when known
+                let (message, metadata) = match provider_metadata {
+                    Some(provider) => (
+                        format!(
+                            "Generated {} new attributes from {}/{}",
+                            attributes.len(),
+                            provider.provider_id,
+                            provider.model
+                        ),
+                        serde_json::json!({
+                            "count": attributes.len(),
+                            "provider": provider.provider_id,
+                            "model": provider.model,
+                        }),
+                    ),
+                    None => (
+                        format!("Generated {} new attributes", attributes.len()),
+                        serde_json::json!({ "count": attributes.len() }),
+                    ),
+                };
                 self.add_activity(ActivityEvent {
                     event_type: ActivityType::AttributesGenerated,
-                    message: format!("Generated {} new attributes", attributes.len()),
+                    message,
                     timestamp: Utc::now().timestamp() as u64,
-                    metadata: Some(serde_json::json!({ "count": attributes.len() })),
+                    metadata: Some(metadata),
                 });
+                self.record_attributes(attributes);
+                if let Some(provider) = provider_metadata {
+                    self.provider_activity.insert(provider.provider_id, Instant::now());
+                }
 
                 // Convert to WebSocket message using consistent pattern
                 client_messages.extend(convert_to_websocket_message(update.clone()));
             }
 
             OrchestratorUpdate::StatisticsUpdate {
+                request_id,
                 timestamp,
                 active_producers,
                 current_topic,
                 total_unique_attributes,
                 metrics,
             } => {
+                self.record_provider_activity(&metrics);
+
                 // Update current metrics
                 self.current_metrics = Some(metrics.clone());
 
@@ -119,6 +242,7 @@ impl WebServerState {
 
                 // Convert to client message
                 client_messages.extend(convert_to_websocket_message(OrchestratorUpdate::StatisticsUpdate {
+                    request_id,
                     timestamp,
                     active_producers,
                     current_topic,
@@ -140,6 +264,9 @@ impl WebServerState {
                 // Add activity event
                 let reason_str = match &completion_reason {
                     CompletionReason::IterationLimitReached => "iteration limit reached",
+                    CompletionReason::TimeLimitReached => "time limit reached",
+                    CompletionReason::EfficiencyFloorReached => "efficiency floor reached",
+                    CompletionReason::TargetCountReached => "target count reached",
                     CompletionReason::ManualStop => "manual stop",
                     CompletionReason::AllProducersFailed => "all producers failed",
                     CompletionReason::SystemError { error } => &format!("system error: {}", error),
@@ -167,6 +294,23 @@ impl WebServerState {
                 }));
             }
 
+            OrchestratorUpdate::ProducerLifecycle { ref producer_id, event } => {
+                let (event_type, action) = match event {
+                    ProducerLifecycleEvent::Started => (ActivityType::ProducerSpawned, "started"),
+                    ProducerLifecycleEvent::Failed => (ActivityType::ProducerFailed, "failed"),
+                    ProducerLifecycleEvent::Restarted => (ActivityType::ProducerRestarted, "was restarted"),
+                    ProducerLifecycleEvent::Stopped => (ActivityType::ProducerStopped, "stopped"),
+                };
+                self.add_activity(ActivityEvent {
+                    event_type,
+                    message: format!("Producer {} {}", producer_id, action),
+                    timestamp: Utc::now().timestamp() as u64,
+                    metadata: Some(serde_json::json!({ "producer_id": producer_id, "event": event })),
+                });
+
+                client_messages.extend(convert_to_websocket_message(update.clone()));
+            }
+
             OrchestratorUpdate::ErrorNotification(ref error_msg) => {
                 // Create alert for internal tracking
                 let alert_id = Uuid::new_v4();
@@ -279,8 +423,125 @@ impl WebServerState {
         self.current_metrics.as_ref()
     }
 
+    /// Seconds since the webserver started
+    pub fn uptime_seconds(&self) -> u64 {
+        self.start_time.elapsed().as_secs()
+    }
+
+    /// Set how many statistics snapshots are kept for `/api/history`
+    pub fn with_metrics_history_capacity(mut self, capacity: usize) -> Self {
+        self.max_history_size = capacity;
+        while self.metrics_history.len() > capacity {
+            self.metrics_history.pop_front();
+        }
+        self
+    }
+
+    /// Maximum statistics snapshots retained
+    pub fn metrics_history_capacity(&self) -> usize {
+        self.max_history_size
+    }
+
+    /// Retained statistics snapshots taken after `since` (all of them if None), oldest first
+    pub fn metrics_history_since(&self, since: Option<DateTime<Utc>>) -> Vec<TimestampedMetrics> {
+        self.metrics_history
+            .iter()
+            .filter(|entry| since.is_none_or(|since| entry.timestamp > since))
+            .cloned()
+            .collect()
+    }
+
+    /// A page of the retained attributes, oldest first, with the total retained count
+    pub fn attribute_page(&self, offset: usize, limit: usize) -> (Vec<String>, usize) {
+        let total = self.attribute_history.len();
+        let start = offset.min(total);
+        let end = start.saturating_add(limit).min(total);
+        (self.attribute_history.range(start..end).cloned().collect(), total)
+    }
+
+    /// Health of every provider in the latest metrics, sorted by provider
+    ///
+    /// Providers that never showed activity are `Unknown`; those quiet for
+    /// longer than the staleness threshold are `Offline` whatever they last reported.
+    pub fn provider_health(&self) -> Vec<ProviderHealth> {
+        let Some(metrics) = &self.current_metrics else {
+            return Vec::new();
+        };
+
+        let mut providers: Vec<ProviderHealth> = metrics
+            .by_provider
+            .iter()
+            .map(|(provider, m)| {
+                let idle = self.provider_activity.get(provider).map(Instant::elapsed);
+                let status = match idle {
+                    None => ProviderStatus::Unknown,
+                    Some(idle) if idle > self.provider_stale_after => ProviderStatus::Offline,
+                    Some(_) => m.status,
+                };
+                ProviderHealth {
+                    provider: *provider,
+                    status,
+                    success_rate: m.success_rate,
+                    avg_response_time_ms: m.avg_response_time_ms,
+                    uam: m.uam,
+                    cost_per_minute: m.cost_per_minute,
+                    seconds_since_activity: idle.map(|idle| idle.as_secs()),
+                }
+            })
+            .collect();
+        providers.sort_by_key(|health| health.provider);
+        providers
+    }
+
+    /// Inactivity after which a provider is reported offline
+    pub fn provider_stale_after(&self) -> Duration {
+        self.provider_stale_after
+    }
+
     // Private helper methods
 
+    /// Mark providers whose metrics moved since the previous update as active
+    fn record_provider_activity(&mut self, metrics: &SystemMetrics) {
+        let now = Instant::now();
+        for (provider, current) in &metrics.by_provider {
+            let previous = self
+                .current_metrics
+                .as_ref()
+                .and_then(|previous| previous.by_provider.get(provider));
+            let active = match previous {
+                Some(previous) => provider_metrics_changed(previous, current),
+                None => current.uam > 0.0 || current.tokens_per_minute > 0.0,
+            };
+            if active {
+                self.provider_activity.insert(*provider, now);
+            }
+        }
+    }
+
+    /// Append unseen attributes to the history, evicting the oldest past capacity
+    fn record_attributes(&mut self, attributes: &[String]) {
+        if self.max_attribute_history == 0 {
+            return;
+        }
+
+        for attribute in attributes {
+            if !self.attribute_index.insert(attribute.clone()) {
+                continue;
+            }
+            self.attribute_history.push_back(attribute.clone());
+            if self.attribute_history.len() > self.max_attribute_history {
+                self.evict_oldest_attribute();
+            }
+        }
+    }
+
+    /// Drop the oldest retained attribute
+    fn evict_oldest_attribute(&mut self) {
+        if let Some(oldest) = self.attribute_history.pop_front() {
+            self.attribute_index.remove(&oldest);
+        }
+    }
+
     /// Add metrics to historical tracking
     fn add_metrics_to_history(&mut self, metrics: SystemMetrics) {
         let timestamped = TimestampedMetrics {
@@ -383,8 +644,8 @@ impl WebServerState {
         if let Some(metrics) = &self.current_metrics {
             metrics
                 .by_producer
-                .iter()
-                .map(|(_producer_id, producer_metrics)| {
+                .values()
+                .map(|producer_metrics| {
                     (
                         shared::ProcessId::Producer(1), // Default producer ID since ProcessId doesn't have from_string
                         crate::types::ProducerStats {
@@ -415,3 +676,12 @@ impl Default for WebServerState {
         Self::new()
     }
 }
+
+/// Whether a provider's reported metrics differ between two updates
+fn provider_metrics_changed(previous: &ProviderMetrics, current: &ProviderMetrics) -> bool {
+    previous.uam != current.uam
+        || previous.tokens_per_minute != current.tokens_per_minute
+        || previous.cost_per_minute != current.cost_per_minute
+        || previous.avg_response_time_ms != current.avg_response_time_ms
+        || previous.success_rate != current.success_rate
+}
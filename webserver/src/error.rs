@@ -25,6 +25,10 @@ pub enum WebServerError {
     #[error("Configuration error: {message}")]
     Config { message: String },
 
+    /// Malformed or rejected client requests
+    #[error("Invalid request: {message}")]
+    InvalidRequest { message: String },
+
     /// I/O errors
     #[error("I/O error: {source}")]
     IoError { source: io::Error },
@@ -71,6 +75,13 @@ impl WebServerError {
         }
     }
 
+    /// Create invalid request error
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::InvalidRequest {
+            message: message.into(),
+        }
+    }
+
     /// Create internal error
     pub fn internal(message: impl Into<String>) -> Self {
         Self::Internal {
@@ -27,9 +27,9 @@ use shared::{OrchestratorUpdate, ProcessId, process_debug};
 use crate::types::ClientMessage;
 // Handler wrapper functions for AppState
 use axum::Json;
-use axum::extract::{Path, State, WebSocketUpgrade};
+use axum::extract::{Path, Query, State, WebSocketUpgrade};
 use axum::http::StatusCode;
-use axum::response::{Html, Response};
+use axum::response::{Html, IntoResponse, Response};
 use serde_json::Value;
 
 /// Combined application state for Axum router - using Arc for cloning
@@ -39,6 +39,7 @@ where
     W: WebSocketManager + Send + Sync + 'static,
     S: StaticFileServer + Send + Sync + 'static,
 {
+    state: Arc<Mutex<WebServerState>>,
     orchestrator_client: Arc<Mutex<O>>,
     websocket_manager: Arc<W>,
     static_server: Arc<S>,
@@ -53,6 +54,7 @@ where
 {
     fn clone(&self) -> Self {
         Self {
+            state: self.state.clone(),
             orchestrator_client: self.orchestrator_client.clone(),
             websocket_manager: self.websocket_manager.clone(),
             static_server: self.static_server.clone(),
@@ -70,6 +72,9 @@ where
     /// Core state management
     state: Arc<Mutex<WebServerState>>,
 
+    /// Bearer authentication settings taken from the state at construction
+    auth: web::auth::BearerAuth,
+
     /// Analytics engine for insights
     #[allow(dead_code)]
     analytics: AnalyticsEngine,
@@ -101,6 +106,7 @@ where
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
 
         Self {
+            auth: web::auth::BearerAuth::from_state(&state),
             state: Arc::new(Mutex::new(state)),
             analytics,
             orchestrator_client: Arc::new(Mutex::new(orchestrator_client)),
@@ -136,10 +142,11 @@ where
             rx
         } else {
             // Initialize orchestrator connection
-            match {
+            let init_result = {
                 let mut client = self.orchestrator_client.lock().await;
                 client.initialize().await
-            } {
+            };
+            match init_result {
                 Ok(_) => {
                     // Get orchestrator update receiver
                     let mut client = self.orchestrator_client.lock().await;
@@ -217,21 +224,17 @@ where
         // Main event loop
         if standalone_mode {
             // In standalone mode, just wait for server to complete or shutdown
-            loop {
-                tokio::select! {
-                    // Handle shutdown signal
-                    Some(_) = self.shutdown_rx.recv() => {
-                        shared::process_info!(shared::ProcessId::current(), "🛑 Shutting down WebServer...");
-                        break;
-                    },
-
-                    // Handle server completion
-                    result = &mut server_handle => {
-                        match result {
-                            Ok(()) => shared::process_info!(shared::ProcessId::current(), "HTTP server completed successfully"),
-                            Err(e) => shared::process_error!(shared::ProcessId::current(), "HTTP server task error: {}", e),
-                        }
-                        break;
+            tokio::select! {
+                // Handle shutdown signal
+                Some(_) = self.shutdown_rx.recv() => {
+                    shared::process_info!(shared::ProcessId::current(), "🛑 Shutting down WebServer...");
+                },
+
+                // Handle server completion
+                result = &mut server_handle => {
+                    match result {
+                        Ok(()) => shared::process_info!(shared::ProcessId::current(), "HTTP server completed successfully"),
+                        Err(e) => shared::process_error!(shared::ProcessId::current(), "HTTP server task error: {}", e),
                     }
                 }
             }
@@ -281,6 +284,7 @@ where
             OrchestratorUpdate::NewAttributes { .. } => "NewAttributes", 
             OrchestratorUpdate::GenerationComplete { .. } => "GenerationComplete",
             OrchestratorUpdate::ErrorNotification(_) => "ErrorNotification",
+            OrchestratorUpdate::ProducerLifecycle { .. } => "ProducerLifecycle",
             _ => "Other",
         };
         
@@ -309,9 +313,15 @@ where
                 ClientMessage::AttributeUpdate { attributes, .. } => {
                     process_debug!(ProcessId::current(), "🔄 Broadcasting AttributeUpdate: {} attributes", attributes.len());
                 }
+                ClientMessage::AttributeSummary { skipped_attributes } => {
+                    process_debug!(ProcessId::current(), "🔄 Broadcasting AttributeSummary: {} skipped", skipped_attributes);
+                }
                 ClientMessage::GenerationComplete { topic, .. } => {
                     process_debug!(ProcessId::current(), "🔄 Broadcasting GenerationComplete: topic={}", topic);
                 }
+                ClientMessage::ProducerEvent { producer_id, event, .. } => {
+                    process_debug!(ProcessId::current(), "🔄 Broadcasting ProducerEvent: {} {:?}", producer_id, event);
+                }
                 ClientMessage::Alert { level, title, .. } => {
                     process_debug!(ProcessId::current(), "🔄 Broadcasting Alert: level={:?}, title={}", level, title);
                 }
@@ -341,6 +351,7 @@ where
 
         // Create combined state for the router
         let app_state = AppState {
+            state: self.state.clone(),
             orchestrator_client: self.orchestrator_client.clone(),
             websocket_manager: self.websocket_manager.clone(),
             static_server: self.static_server.clone(),
@@ -349,12 +360,27 @@ where
         Router::new()
             .route("/", get(serve_index_wrapper))
             .route("/ws", get(websocket_handler_wrapper))
+            .route("/healthz", get(healthz_wrapper))
+            .route("/readyz", get(readyz_wrapper))
+            .route("/metrics", get(metrics_wrapper))
+            .route("/api/attributes", get(get_attributes_wrapper))
+            .route("/api/providers", get(get_providers_wrapper))
             .route("/api/dashboard", get(get_dashboard_wrapper))
             .route("/api/status", get(get_status_wrapper))
+            .route("/api/optimization", get(get_optimization_plan_wrapper))
+            .route("/api/history", get(get_history_wrapper))
+            .route("/api/output/:topic", get(download_output_wrapper))
             .route("/api/start", post(start_generation_wrapper))
             .route("/api/stop", post(stop_generation_wrapper))
+            .route("/api/pause", post(pause_generation_wrapper))
+            .route("/api/resume", post(resume_generation_wrapper))
+            .route("/api/config", post(update_config_wrapper))
             .route("/static/*path", get(serve_static_wrapper))
             .route("/test", get(|| async { "WebServer is running!" }))
+            .layer(axum::middleware::from_fn_with_state(
+                self.auth.clone(),
+                crate::web::auth::require_bearer_token,
+            ))
             .with_state(app_state)
     }
 }
@@ -373,6 +399,79 @@ where
     crate::web::handlers::websocket::websocket_handler(ws, State(app_state.websocket_manager)).await
 }
 
+async fn healthz_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> Json<Value>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::healthz(State(app_state.state)).await
+}
+
+async fn readyz_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> (StatusCode, Json<Value>)
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::readyz(State(app_state.state)).await
+}
+
+async fn metrics_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> impl IntoResponse
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::metrics(State(app_state.state)).await
+}
+
+async fn get_attributes_wrapper<O, W, S>(
+    State(app_state): State<AppState<O, W, S>>,
+    Query(query): Query<crate::web::handlers::api::AttributesQuery>,
+) -> Json<Value>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::get_attributes(State(app_state.state), Query(query)).await
+}
+
+async fn get_providers_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> Json<Value>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::get_providers(State(app_state.state)).await
+}
+
+async fn get_history_wrapper<O, W, S>(
+    State(app_state): State<AppState<O, W, S>>,
+    Query(query): Query<crate::web::handlers::api::HistoryQuery>,
+) -> Json<Value>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::get_history(State(app_state.state), Query(query)).await
+}
+
+async fn download_output_wrapper<O, W, S>(
+    State(app_state): State<AppState<O, W, S>>,
+    Path(topic): Path<String>,
+    Query(query): Query<crate::web::handlers::api::OutputQuery>,
+) -> Result<Response, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::download_output(State(app_state.state), Path(topic), Query(query)).await
+}
+
 async fn get_dashboard_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> Result<Json<Value>, StatusCode>
 where
     O: OrchestratorClient + Send + Sync + 'static,
@@ -388,7 +487,23 @@ where
     W: WebSocketManager + Send + Sync + 'static,
     S: StaticFileServer + Send + Sync + 'static,
 {
-    crate::web::handlers::api::get_status(State(app_state.websocket_manager)).await
+    crate::web::handlers::api::get_status(
+        State(app_state.websocket_manager),
+        State(app_state.orchestrator_client),
+        State(app_state.state),
+    )
+    .await
+}
+
+async fn get_optimization_plan_wrapper<O, W, S>(
+    State(app_state): State<AppState<O, W, S>>,
+) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::get_optimization_plan(State(app_state.orchestrator_client)).await
 }
 
 async fn start_generation_wrapper<O, W, S>(
@@ -403,13 +518,46 @@ where
     crate::web::handlers::api::start_generation(State(app_state.orchestrator_client), Json(request)).await
 }
 
-async fn stop_generation_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> Result<Json<Value>, StatusCode>
+async fn stop_generation_wrapper<O, W, S>(
+    State(app_state): State<AppState<O, W, S>>,
+    Query(query): Query<crate::web::handlers::api::StopQuery>,
+) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::stop_generation(State(app_state.orchestrator_client), Query(query)).await
+}
+
+async fn pause_generation_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::pause_generation(State(app_state.orchestrator_client)).await
+}
+
+async fn resume_generation_wrapper<O, W, S>(State(app_state): State<AppState<O, W, S>>) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+    W: WebSocketManager + Send + Sync + 'static,
+    S: StaticFileServer + Send + Sync + 'static,
+{
+    crate::web::handlers::api::resume_generation(State(app_state.orchestrator_client)).await
+}
+
+async fn update_config_wrapper<O, W, S>(
+    State(app_state): State<AppState<O, W, S>>,
+    Json(request): Json<crate::web::handlers::api::ConfigRequest>,
+) -> Result<Json<Value>, StatusCode>
 where
     O: OrchestratorClient + Send + Sync + 'static,
     W: WebSocketManager + Send + Sync + 'static,
     S: StaticFileServer + Send + Sync + 'static,
 {
-    crate::web::handlers::api::stop_generation(State(app_state.orchestrator_client)).await
+    crate::web::handlers::api::update_config(State(app_state.orchestrator_client), Json(request)).await
 }
 
 async fn serve_static_wrapper<O, W, S>(
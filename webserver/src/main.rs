@@ -6,12 +6,19 @@
 use clap::Parser;
 use shared::{ProcessId, logging, process_info};
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::signal;
 
 use webserver::{
     WebServer, WebServerResult,
-    core::{AnalyticsEngine, WebServerState},
-    services::{RealOrchestratorClient, RealStaticFileServer, RealWebSocketManager},
+    core::{
+        AnalyticsEngine, DEFAULT_ATTRIBUTE_HISTORY_CAPACITY, DEFAULT_METRICS_HISTORY_CAPACITY, DEFAULT_OUTPUT_DIR,
+        WebServerState,
+    },
+    services::{
+        RealOrchestratorClient, RealStaticFileServer, RealWebSocketManager,
+        websocket_manager::{DEFAULT_COALESCE_WINDOW, DEFAULT_SEND_QUEUE_CAPACITY, DropPolicy},
+    },
 };
 
 /// Command line arguments expected from ProcessManager
@@ -31,6 +38,14 @@ struct Args {
     #[arg(long, default_value = "info")]
     log_level: String,
 
+    /// Plain ASCII logs without emoji or colors
+    #[arg(long)]
+    plain_logs: bool,
+
+    /// Console log format (text, json)
+    #[arg(long, default_value = "text")]
+    log_format: shared::logging::LogFormat,
+
     /// Listen port for receiving orchestrator updates (IPC communication, like producers)
     #[arg(long)]
     listen_port: Option<u16>,
@@ -39,9 +54,45 @@ struct Args {
     #[arg(long, default_value = "./static")]
     static_dir: String,
 
+    /// Statistics snapshots kept for /api/history charts
+    #[arg(long, default_value_t = DEFAULT_METRICS_HISTORY_CAPACITY)]
+    metrics_history: usize,
+
+    /// Orchestrator output directory served by /api/output/:topic
+    #[arg(long, default_value = DEFAULT_OUTPUT_DIR)]
+    output_dir: String,
+
+    /// Recent unique attributes kept for clients that connect mid-generation
+    #[arg(long, default_value_t = DEFAULT_ATTRIBUTE_HISTORY_CAPACITY)]
+    attribute_history: usize,
+
     /// Orchestrator address for IPC (if not provided, runs in standalone mode)
     #[arg(long)]
     orchestrator_addr: Option<String>,
+
+    /// Bearer token required by control endpoints (/api/start, /api/stop, /api/config, ...)
+    #[arg(long)]
+    auth_token: Option<String>,
+
+    /// Also require the bearer token for read-only endpoints and the dashboard
+    #[arg(long, requires = "auth_token")]
+    auth_all: bool,
+
+    /// Seconds between WebSocket pings; clients silent for 3 intervals are dropped
+    #[arg(long, default_value = "15", value_parser = clap::value_parser!(u64).range(1..))]
+    ws_heartbeat_secs: u64,
+
+    /// Milliseconds over which attribute updates are batched per client (0 sends each immediately)
+    #[arg(long, default_value_t = DEFAULT_COALESCE_WINDOW.as_millis() as u64)]
+    ws_coalesce_ms: u64,
+
+    /// Broadcasts queued per WebSocket client whose connection is backed up
+    #[arg(long, default_value_t = DEFAULT_SEND_QUEUE_CAPACITY)]
+    ws_queue_capacity: usize,
+
+    /// What a full client queue gives up: drop-oldest, drop-newest or disconnect
+    #[arg(long, default_value_t = DropPolicy::default())]
+    ws_drop_policy: DropPolicy,
 }
 
 #[tokio::main]
@@ -57,7 +108,13 @@ async fn main() -> WebServerResult<()> {
         .trace_ep
         .as_ref()
         .map(|url| shared::logging::TracingEndpoint::new(url.clone()));
-    shared::logging::init_tracing_with_endpoint_and_level(trace_endpoint, Some(&args.log_level));
+    let log_options = shared::logging::LogOptions {
+        log_level: Some(args.log_level.clone()),
+        plain: args.plain_logs,
+        format: args.log_format,
+        ..Default::default()
+    };
+    shared::logging::init_tracing_with_options(trace_endpoint, &log_options);
 
     // Test that ProcessId is working
     process_info!(ProcessId::current(), "🚀 WebServer ProcessId initialized successfully");
@@ -119,11 +176,28 @@ async fn main() -> WebServerResult<()> {
         }
     };
 
-    let websocket_manager = RealWebSocketManager::new();
+    let websocket_manager = RealWebSocketManager::new()
+        .with_heartbeat_interval(Duration::from_secs(args.ws_heartbeat_secs))
+        .with_coalesce_window(Duration::from_millis(args.ws_coalesce_ms))
+        .with_send_queue(args.ws_queue_capacity, args.ws_drop_policy);
+    websocket_manager.start_heartbeat_task();
+    websocket_manager.start_coalescing_task();
+    websocket_manager.start_queue_drain_task();
     let static_server = RealStaticFileServer::new(args.static_dir);
 
     // Initialize core business logic
-    let state = WebServerState::new();
+    let mut state = WebServerState::new()
+        .with_attribute_history_capacity(args.attribute_history)
+        .with_metrics_history_capacity(args.metrics_history)
+        .with_output_dir(args.output_dir);
+    if let Some(token) = args.auth_token {
+        process_info!(
+            ProcessId::current(),
+            "🔒 Bearer token required for {} endpoints",
+            if args.auth_all { "all" } else { "control" }
+        );
+        state = state.with_auth_token(token).with_auth_all(args.auth_all);
+    }
     let analytics = AnalyticsEngine::new();
 
     // Create webserver with injected dependencies
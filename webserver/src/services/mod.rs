@@ -9,4 +9,4 @@ pub mod websocket_manager;
 // Re-export service implementations
 pub use orchestrator_client::RealOrchestratorClient;
 pub use static_server::RealStaticFileServer;
-pub use websocket_manager::RealWebSocketManager;
+pub use websocket_manager::{DropPolicy, RealWebSocketManager};
@@ -5,16 +5,35 @@
 //! - Connects to orchestrator to send requests
 
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{Mutex, RwLock, mpsc, oneshot};
 use tracing::debug;
 
 use crate::error::{WebServerError, WebServerResult};
-use crate::traits::OrchestratorClient;
-use shared::{OrchestratorUpdate, ProcessId, WebServerRequest, process_debug, process_info};
+use crate::traits::{OrchestratorClient, PendingReply};
+use shared::messages::webserver::{OptimizationPlan, StopMode, StopReport};
+use shared::{OrchestratorUpdate, ProcessId, SystemMetrics, WebServerRequest, process_debug, process_info};
+
+/// How long `request_status` waits for the orchestrator's reply
+pub const DEFAULT_STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `request_stop` waits for the orchestrator's reply; stopping waits on the producers
+pub const DEFAULT_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Status requests awaiting their `StatisticsUpdate`, keyed by request id
+type PendingStatus = Arc<Mutex<HashMap<u64, oneshot::Sender<SystemMetrics>>>>;
+
+/// Stop requests awaiting their `StopReport`, keyed by request id
+type PendingStops = Arc<Mutex<HashMap<u64, oneshot::Sender<StopReport>>>>;
+
+/// Optimization plan requests awaiting their reply, keyed by request id
+type PendingPlans = Arc<Mutex<HashMap<u64, oneshot::Sender<Option<OptimizationPlan>>>>>;
 
 /// Connection state for the communicator  
 #[derive(Clone)]
@@ -30,6 +49,11 @@ pub struct RealOrchestratorClient {
     update_rx: Option<mpsc::Receiver<OrchestratorUpdate>>,
     ipc_port: Option<u16>, // IPC port for orchestrator communication, None = standalone mode
     web_port: u16, // HTTP port for browser connections
+    pending_status: PendingStatus,
+    pending_stops: PendingStops,
+    pending_plans: PendingPlans,
+    next_request_id: Arc<AtomicU64>,
+    status_timeout: Duration,
 }
 
 impl RealOrchestratorClient {
@@ -46,6 +70,11 @@ impl RealOrchestratorClient {
             update_rx: Some(update_rx),
             ipc_port: Some(bind_addr.port()),
             web_port,
+            pending_status: Arc::new(Mutex::new(HashMap::new())),
+            pending_stops: Arc::new(Mutex::new(HashMap::new())),
+            pending_plans: Arc::new(Mutex::new(HashMap::new())),
+            next_request_id: Arc::new(AtomicU64::new(1)),
+            status_timeout: DEFAULT_STATUS_TIMEOUT,
         }
     }
 
@@ -64,6 +93,76 @@ impl RealOrchestratorClient {
             update_rx: Some(update_rx),
             ipc_port: None, // Standalone mode - no IPC
             web_port,
+            pending_status: Arc::new(Mutex::new(HashMap::new())),
+            pending_stops: Arc::new(Mutex::new(HashMap::new())),
+            pending_plans: Arc::new(Mutex::new(HashMap::new())),
+            next_request_id: Arc::new(AtomicU64::new(1)),
+            status_timeout: DEFAULT_STATUS_TIMEOUT,
+        }
+    }
+
+    /// Override how long `request_status` and `request_optimization_plan` wait for the orchestrator's reply
+    pub fn with_status_timeout(mut self, timeout: Duration) -> Self {
+        self.status_timeout = timeout;
+        self
+    }
+
+    /// Send a request whose reply is routed back through `pending`, returning its id and reply receiver
+    ///
+    /// Waiters that gave up are dropped from `pending` here, since their replies may never come.
+    async fn send_awaiting_reply<T>(
+        &self,
+        pending: &Mutex<HashMap<u64, oneshot::Sender<T>>>,
+        request: impl FnOnce(u64) -> WebServerRequest,
+    ) -> WebServerResult<(u64, oneshot::Receiver<T>)> {
+        if self.ipc_port.is_none() {
+            return Err(WebServerError::communication("No orchestrator in standalone mode"));
+        }
+
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (reply_tx, reply_rx) = oneshot::channel();
+        {
+            let mut pending = pending.lock().await;
+            pending.retain(|_, waiter| !waiter.is_closed());
+            pending.insert(request_id, reply_tx);
+        }
+
+        if let Err(e) = self.send_request(request(request_id)).await {
+            pending.lock().await.remove(&request_id);
+            return Err(e);
+        }
+        Ok((request_id, reply_rx))
+    }
+
+    /// Hand a status reply to the request waiting on it
+    async fn resolve_status_reply(pending: &PendingStatus, update: &OrchestratorUpdate) {
+        if let OrchestratorUpdate::StatisticsUpdate {
+            request_id: Some(request_id),
+            metrics,
+            ..
+        } = update
+            && let Some(waiter) = pending.lock().await.remove(request_id)
+        {
+            // The requester may have timed out and gone away already
+            let _ = waiter.send(metrics.clone());
+        }
+    }
+
+    /// Hand an optimization plan to the request waiting on it
+    async fn resolve_plan_reply(pending: &PendingPlans, update: &OrchestratorUpdate) {
+        if let OrchestratorUpdate::OptimizationPlan { request_id, plan } = update
+            && let Some(waiter) = pending.lock().await.remove(request_id)
+        {
+            let _ = waiter.send(plan.clone());
+        }
+    }
+
+    /// Hand a stop report to the request waiting on it
+    async fn resolve_stop_reply(pending: &PendingStops, update: &OrchestratorUpdate) {
+        if let OrchestratorUpdate::StopReport { request_id, report } = update
+            && let Some(waiter) = pending.lock().await.remove(request_id)
+        {
+            let _ = waiter.send(report.clone());
         }
     }
 
@@ -165,6 +264,9 @@ impl OrchestratorClient for RealOrchestratorClient {
                 .clone()
                 .ok_or_else(|| WebServerError::communication("Update sender not available".to_string()))?;
             let connected = self.connection.connected.clone();
+            let pending_status = self.pending_status.clone();
+            let pending_stops = self.pending_stops.clone();
+            let pending_plans = self.pending_plans.clone();
 
             tokio::spawn(async move {
                 loop {
@@ -177,12 +279,18 @@ impl OrchestratorClient for RealOrchestratorClient {
                             
                             let tx = tx.clone();
                             let connected_inner = connected.clone();
+                            let pending_status = pending_status.clone();
+                            let pending_stops = pending_stops.clone();
+                            let pending_plans = pending_plans.clone();
 
                             // Handle each connection in a separate task
                             tokio::spawn(async move {
                                 match Self::read::<OrchestratorUpdate>(&mut stream).await {
                                     Ok(update) => {
                                         debug!("📨 Received update: {:?}", update);
+                                        Self::resolve_status_reply(&pending_status, &update).await;
+                                        Self::resolve_stop_reply(&pending_stops, &update).await;
+                                        Self::resolve_plan_reply(&pending_plans, &update).await;
                                         if tx.send(update).await.is_err() {
                                             shared::process_warn!(shared::ProcessId::current(), "Update receiver dropped");
                                         }
@@ -251,6 +359,29 @@ impl OrchestratorClient for RealOrchestratorClient {
         }
     }
 
+    async fn request_status(&self) -> WebServerResult<PendingReply<SystemMetrics>> {
+        let (request_id, reply_rx) = self
+            .send_awaiting_reply(&self.pending_status, |request_id| WebServerRequest::GetStatus { request_id })
+            .await?;
+        Ok(PendingReply::new(reply_rx, self.status_timeout, format!("status reply for request {request_id}")))
+    }
+
+    async fn request_stop(&self, mode: StopMode) -> WebServerResult<PendingReply<StopReport>> {
+        let (request_id, reply_rx) = self
+            .send_awaiting_reply(&self.pending_stops, |request_id| {
+                WebServerRequest::StopGeneration { request_id, mode }
+            })
+            .await?;
+        Ok(PendingReply::new(reply_rx, DEFAULT_STOP_TIMEOUT, format!("stop report for request {request_id}")))
+    }
+
+    async fn request_optimization_plan(&self) -> WebServerResult<PendingReply<Option<OptimizationPlan>>> {
+        let (request_id, reply_rx) = self
+            .send_awaiting_reply(&self.pending_plans, |request_id| WebServerRequest::GetOptimizationPlan { request_id })
+            .await?;
+        Ok(PendingReply::new(reply_rx, self.status_timeout, format!("optimization plan for request {request_id}")))
+    }
+
     async fn get_updates(&mut self) -> WebServerResult<mpsc::Receiver<OrchestratorUpdate>> {
         // Take the receiver (can only be called once)
         self.update_rx
@@ -173,10 +173,8 @@ impl StaticFileServer for RealStaticFileServer {
                 let path = entry.path();
 
                 if path.is_file() {
-                    if let Ok(relative_path) = path.strip_prefix(base) {
-                        if let Some(path_str) = relative_path.to_str() {
-                            files.push(path_str.to_string());
-                        }
+                    if let Some(path_str) = path.strip_prefix(base).ok().and_then(|p| p.to_str()) {
+                        files.push(path_str.to_string());
                     }
                 } else if path.is_dir() {
                     collect_files(&path, base, files)?;
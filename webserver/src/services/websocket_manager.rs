@@ -1,21 +1,115 @@
 //! WebSocket client management service
 //!
-//! Manages WebSocket connections and broadcasting to browser clients
+//! Manages WebSocket connections and broadcasting to browser clients.
+//! With a coalescing window set, attribute updates are buffered per client and
+//! flushed as one batch per window, so bursts from many producers cannot flood
+//! slow clients.
+//!
+//! Broadcasts a client's socket cannot take yet wait in a per-client send queue of
+//! bounded capacity, drained by the next broadcast or by the task from
+//! `start_queue_drain_task`. Once that queue is full the configured `DropPolicy`
+//! decides what is lost, and a client that overflows it `MAX_CONSECUTIVE_OVERFLOWS`
+//! times in a row without catching up is disconnected.
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::task::JoinHandle;
-use tokio::time::{sleep, interval, Duration};
+use tokio::time::{sleep, interval, Duration, Instant};
 use uuid::Uuid;
 
 use crate::error::{WebServerError, WebServerResult};
 use crate::traits::WebSocketManager;
 use crate::types::ClientMessage;
 
+/// Longest attribute filter pattern a client may set
+///
+/// Together with the compiled size limit this keeps a hostile pattern from
+/// tying up the server; the regex engine itself matches in linear time.
+pub const MAX_FILTER_PATTERN_LEN: usize = 256;
+
+/// Upper bound on the compiled size of a filter pattern
+const FILTER_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Default time between server-side WebSocket pings
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Shortest heartbeat interval accepted by `with_heartbeat_interval`
+pub const MIN_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Pings a client may leave unanswered before its connection is dropped
+pub const HEARTBEAT_MISSED_PINGS: u32 = 3;
+
+/// Suggested window for coalescing attribute updates (the webserver binary's default)
+pub const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(200);
+
+/// Default number of broadcasts queued for a client whose socket is backed up
+pub const DEFAULT_SEND_QUEUE_CAPACITY: usize = 256;
+
+/// Broadcasts in a row a client may overflow its send queue before it is disconnected
+pub const MAX_CONSECUTIVE_OVERFLOWS: u32 = 50;
+
+/// Time between attempts to move queued broadcasts into backed-up clients' sockets
+pub const SEND_QUEUE_DRAIN_INTERVAL: Duration = Duration::from_millis(50);
+
+/// What happens to a broadcast when a client's send queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DropPolicy {
+    /// Discard the oldest queued message to make room for the new one
+    #[default]
+    DropOldest,
+    /// Discard the new message and keep what is already queued
+    DropNewest,
+    /// Disconnect the client at the first overflow
+    Disconnect,
+}
+
+impl fmt::Display for DropPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DropPolicy::DropOldest => "drop-oldest",
+            DropPolicy::DropNewest => "drop-newest",
+            DropPolicy::Disconnect => "disconnect",
+        })
+    }
+}
+
+impl FromStr for DropPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "drop-oldest" => Ok(DropPolicy::DropOldest),
+            "drop-newest" => Ok(DropPolicy::DropNewest),
+            "disconnect" => Ok(DropPolicy::Disconnect),
+            _ => Err(format!(
+                "Unknown drop policy '{}' (expected drop-oldest, drop-newest or disconnect)",
+                s
+            )),
+        }
+    }
+}
+
+/// Outcome of handing a broadcast to one client
+enum Delivery {
+    /// Written to the client's socket channel
+    Sent,
+    /// Held in the client's send queue until the socket has room
+    Queued,
+    /// Lost to the drop policy
+    Dropped,
+    /// The socket closed
+    Closed,
+    /// The client cannot keep up and should be disconnected
+    TooSlow,
+}
+
 /// WebSocket client connection info
 #[derive(Debug)]
 struct ClientConnection {
@@ -24,6 +118,156 @@ struct ClientConnection {
     sender: mpsc::Sender<ClientMessage>,
     #[allow(dead_code)]
     connected_at: DateTime<Utc>,
+    /// Last time the client answered a ping or sent any other frame
+    last_seen: Instant,
+    /// Attribute updates only carry matching attributes to this client
+    filter: Option<Regex>,
+    /// Attribute updates buffered until the next coalescing flush
+    pending: Option<ClientMessage>,
+    /// Attributes dropped because the client's queue was full, reported in a summary
+    skipped_attributes: usize,
+    /// Broadcasts waiting for room in the socket channel, oldest first
+    queue: VecDeque<ClientMessage>,
+    /// Broadcasts in a row that overflowed the queue since the client last caught up
+    overflow_streak: u32,
+}
+
+impl ClientConnection {
+    /// Whether the socket is still open and has been heard from within `timeout`
+    fn is_live(&self, timeout: Duration) -> bool {
+        !self.sender.is_closed() && self.last_seen.elapsed() <= timeout
+    }
+
+    /// Move queued broadcasts into the socket channel until it is full
+    ///
+    /// Returns how many were sent, or None if the socket closed. A client whose
+    /// queue empties has caught up, which clears its overflow streak.
+    fn drain_queue(&mut self) -> Option<usize> {
+        let mut sent = 0;
+        while let Some(queued) = self.queue.pop_front() {
+            match self.sender.try_send(queued) {
+                Ok(_) => sent += 1,
+                Err(TrySendError::Full(queued)) => {
+                    self.queue.push_front(queued);
+                    return Some(sent);
+                }
+                Err(TrySendError::Closed(_)) => return None,
+            }
+        }
+        self.overflow_streak = 0;
+        Some(sent)
+    }
+
+    /// Send a broadcast behind anything already queued, applying `policy` on overflow
+    fn deliver(&mut self, message: ClientMessage, capacity: usize, policy: DropPolicy) -> Delivery {
+        // Older messages go first so the client sees broadcasts in order
+        if self.drain_queue().is_none() {
+            return Delivery::Closed;
+        }
+
+        let message = if self.queue.is_empty() {
+            match self.sender.try_send(message) {
+                Ok(_) => {
+                    self.overflow_streak = 0;
+                    return Delivery::Sent;
+                }
+                Err(TrySendError::Full(message)) => message,
+                Err(TrySendError::Closed(_)) => return Delivery::Closed,
+            }
+        } else {
+            message
+        };
+
+        if self.queue.len() < capacity {
+            self.queue.push_back(message);
+            self.overflow_streak = 0;
+            return Delivery::Queued;
+        }
+
+        self.overflow_streak += 1;
+        if policy == DropPolicy::Disconnect || self.overflow_streak >= MAX_CONSECUTIVE_OVERFLOWS {
+            return Delivery::TooSlow;
+        }
+        if policy == DropPolicy::DropOldest {
+            self.queue.pop_front();
+            self.queue.push_back(message);
+        }
+        Delivery::Dropped
+    }
+}
+
+/// Compile a client filter pattern; an empty pattern means no filter
+fn compile_filter(pattern: &str) -> WebServerResult<Option<Regex>> {
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    if pattern.len() > MAX_FILTER_PATTERN_LEN {
+        return Err(WebServerError::invalid_request(format!(
+            "Filter pattern is {} bytes, the maximum is {}",
+            pattern.len(),
+            MAX_FILTER_PATTERN_LEN
+        )));
+    }
+
+    RegexBuilder::new(pattern)
+        .size_limit(FILTER_REGEX_SIZE_LIMIT)
+        .build()
+        .map(Some)
+        .map_err(|e| WebServerError::invalid_request(format!("Invalid filter pattern: {}", e)))
+}
+
+/// The message as a client with the given filter should see it, or None if nothing is left
+fn filter_for_client(message: &ClientMessage, filter: Option<&Regex>) -> Option<ClientMessage> {
+    let (ClientMessage::AttributeUpdate { attributes, .. }, Some(filter)) = (message, filter) else {
+        return Some(message.clone());
+    };
+
+    let matching: Vec<String> = attributes.iter().filter(|attribute| filter.is_match(attribute)).cloned().collect();
+    if matching.is_empty() {
+        return None;
+    }
+
+    let mut filtered = message.clone();
+    if let ClientMessage::AttributeUpdate { attributes, .. } = &mut filtered {
+        *attributes = matching;
+    }
+    Some(filtered)
+}
+
+/// Append an attribute update to a client's pending batch
+///
+/// The batch keeps provider and model only while every merged update agrees on them.
+fn coalesce_into(pending: &mut Option<ClientMessage>, update: ClientMessage) {
+    let Some(ClientMessage::AttributeUpdate {
+        attributes,
+        provider,
+        model,
+        metadata,
+        uniqueness_ratio,
+        ..
+    }) = pending
+    else {
+        *pending = Some(update);
+        return;
+    };
+    let ClientMessage::AttributeUpdate {
+        attributes: new_attributes,
+        provider: new_provider,
+        model: new_model,
+        uniqueness_ratio: new_ratio,
+        ..
+    } = update
+    else {
+        return;
+    };
+
+    attributes.extend(new_attributes);
+    if *provider != new_provider || *model != new_model {
+        *provider = None;
+        *model = None;
+        *metadata = None;
+    }
+    *uniqueness_ratio = new_ratio;
 }
 
 /// Real WebSocket manager implementation
@@ -31,6 +275,14 @@ struct ClientConnection {
 pub struct RealWebSocketManager {
     /// Active client connections
     clients: Arc<RwLock<HashMap<Uuid, ClientConnection>>>,
+    /// Time between server-side pings
+    heartbeat_interval: Duration,
+    /// Attribute updates are batched over this window; zero sends each update immediately
+    coalesce_window: Duration,
+    /// Broadcasts held per client while its socket is backed up
+    send_queue_capacity: usize,
+    /// What a full send queue gives up
+    drop_policy: DropPolicy,
 }
 
 impl RealWebSocketManager {
@@ -38,25 +290,150 @@ impl RealWebSocketManager {
     pub fn new() -> Self {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            coalesce_window: Duration::ZERO,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            drop_policy: DropPolicy::default(),
         }
     }
 
-    /// Clean up disconnected clients   
-    #[allow(dead_code)]
-    async fn cleanup_disconnected_clients(&self) {
+    /// Hold up to `capacity` broadcasts per backed-up client, then apply `policy`
+    ///
+    /// The capacity is raised to at least one message.
+    pub fn with_send_queue(mut self, capacity: usize, policy: DropPolicy) -> Self {
+        self.send_queue_capacity = capacity.max(1);
+        self.drop_policy = policy;
+        self
+    }
+
+    /// Broadcasts waiting in a client's send queue, or None for an unknown client
+    pub async fn queued_messages(&self, client_id: Uuid) -> Option<usize> {
+        self.clients.read().await.get(&client_id).map(|connection| connection.queue.len())
+    }
+
+    /// Batch attribute updates per client over this window; zero turns coalescing off
+    ///
+    /// Batches are sent by the task from `start_coalescing_task`, or by `flush_pending`.
+    pub fn with_coalesce_window(mut self, window: Duration) -> Self {
+        self.coalesce_window = window;
+        self
+    }
+
+    /// Send every client its buffered attribute batch
+    ///
+    /// A client whose queue is full loses the batch; the number of attributes it
+    /// missed is sent as an `AttributeSummary` once its queue has room again.
+    /// Returns the number of batches delivered.
+    pub async fn flush_pending(&self) -> usize {
         let mut clients = self.clients.write().await;
-        let mut to_remove = Vec::new();
+        let mut delivered = 0;
+        let mut closed = Vec::new();
+
+        for (client_id, connection) in clients.iter_mut() {
+            if connection.skipped_attributes > 0 {
+                let summary = ClientMessage::AttributeSummary {
+                    skipped_attributes: connection.skipped_attributes,
+                };
+                match connection.sender.try_send(summary) {
+                    Ok(_) => connection.skipped_attributes = 0,
+                    Err(TrySendError::Full(_)) => {}
+                    Err(TrySendError::Closed(_)) => closed.push(*client_id),
+                }
+            }
 
-        for (client_id, connection) in clients.iter() {
-            if connection.sender.is_closed() {
-                to_remove.push(*client_id);
+            let Some(batch) = connection.pending.take() else {
+                continue;
+            };
+            let count = match &batch {
+                ClientMessage::AttributeUpdate { attributes, .. } => attributes.len(),
+                _ => 0,
+            };
+            match connection.sender.try_send(batch) {
+                Ok(_) => delivered += 1,
+                Err(TrySendError::Full(_)) => {
+                    connection.skipped_attributes += count;
+                    shared::process_warn!(shared::ProcessId::current(), "Client {} channel full, skipped {} attributes", client_id, count);
+                }
+                Err(TrySendError::Closed(_)) => closed.push(*client_id),
             }
         }
 
-        for client_id in to_remove {
-            clients.remove(&client_id);
-            shared::process_info!(shared::ProcessId::current(), "🗑️ Cleaned up disconnected client {}", client_id);
+        for client_id in closed {
+            if clients.remove(&client_id).is_some() {
+                shared::process_info!(shared::ProcessId::current(), "🗑️ Removed disconnected client {} during flush", client_id);
+            }
+        }
+        delivered
+    }
+
+    /// Move queued broadcasts into every backed-up client's socket as far as it has room
+    ///
+    /// Clients whose socket closed are removed. Returns the number of broadcasts sent.
+    pub async fn drain_queues(&self) -> usize {
+        let mut clients = self.clients.write().await;
+        let mut sent = 0;
+        let mut closed = Vec::new();
+
+        for (client_id, connection) in clients.iter_mut().filter(|(_, connection)| !connection.queue.is_empty()) {
+            match connection.drain_queue() {
+                Some(count) => sent += count,
+                None => closed.push(*client_id),
+            }
+        }
+
+        for client_id in closed {
+            if clients.remove(&client_id).is_some() {
+                shared::process_info!(
+                    shared::ProcessId::current(),
+                    "🗑️ Removed disconnected client {} during queue drain",
+                    client_id
+                );
+            }
         }
+        sent
+    }
+
+    /// Buffer an attribute update for every client it is not filtered out for
+    async fn coalesce(&self, message: ClientMessage) {
+        let mut clients = self.clients.write().await;
+        for connection in clients.values_mut() {
+            if let Some(update) = filter_for_client(&message, connection.filter.as_ref()) {
+                coalesce_into(&mut connection.pending, update);
+            }
+        }
+    }
+
+    /// Ping clients at this interval, raised to at least `MIN_HEARTBEAT_INTERVAL`
+    ///
+    /// Clients that stay silent for `HEARTBEAT_MISSED_PINGS` intervals are dropped.
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval.max(MIN_HEARTBEAT_INTERVAL);
+        self
+    }
+
+    /// How long a client may stay silent before it is considered dead
+    pub fn heartbeat_timeout(&self) -> Duration {
+        self.heartbeat_interval * HEARTBEAT_MISSED_PINGS
+    }
+
+    /// Drop clients whose socket closed or that stopped answering pings
+    ///
+    /// Dropping a client closes its outgoing channel, which ends its connection
+    /// handler. Returns the number of clients removed.
+    pub async fn evict_stale_clients(&self) -> usize {
+        let timeout = self.heartbeat_timeout();
+        let mut clients = self.clients.write().await;
+        let stale: Vec<Uuid> = clients
+            .iter()
+            .filter(|(_, connection)| !connection.is_live(timeout))
+            .map(|(client_id, _)| *client_id)
+            .collect();
+
+        for client_id in &stale {
+            clients.remove(client_id);
+            shared::process_info!(shared::ProcessId::current(), "🗑️ Evicted unresponsive WebSocket client {}", client_id);
+        }
+        stale.len()
     }
 }
 
@@ -67,6 +444,12 @@ impl WebSocketManager for RealWebSocketManager {
             id: client_id,
             sender,
             connected_at: Utc::now(),
+            last_seen: Instant::now(),
+            filter: None,
+            pending: None,
+            skipped_attributes: 0,
+            queue: VecDeque::new(),
+            overflow_streak: 0,
         };
 
         {
@@ -107,60 +490,91 @@ impl WebSocketManager for RealWebSocketManager {
     }
 
     async fn broadcast(&self, message: ClientMessage) -> WebServerResult<()> {
-        // Get all clients and their senders at once to avoid holding the lock too long
-        let client_senders = {
-            let clients = self.clients.read().await;
-
-            if clients.is_empty() {
-                shared::process_warn!(shared::ProcessId::current(), "📭 No WebSocket clients connected - message not broadcasted");
+        if !self.coalesce_window.is_zero() {
+            if matches!(message, ClientMessage::AttributeUpdate { .. }) {
+                self.coalesce(message).await;
                 return Ok(());
             }
+            // Deliver buffered attributes first so clients see messages in order
+            self.flush_pending().await;
+        }
 
-            shared::process_info!(shared::ProcessId::current(), "📡 Preparing to broadcast message to {} clients", clients.len());
+        let mut clients = self.clients.write().await;
+        if clients.is_empty() {
+            shared::process_warn!(shared::ProcessId::current(), "📭 No WebSocket clients connected - message not broadcasted");
+            return Ok(());
+        }
 
-            clients
-                .iter()
-                .map(|(client_id, connection)| (*client_id, connection.sender.clone()))
-                .collect::<Vec<_>>()
-        };
+        shared::process_info!(shared::ProcessId::current(), "📡 Preparing to broadcast message to {} clients", clients.len());
 
         let mut failed_clients = Vec::new();
+        let mut slow_clients = Vec::new();
         let mut success_count = 0;
-        let total_clients = client_senders.len();
-
-        for (client_id, sender) in client_senders {
-            match sender.try_send(message.clone()) {
-                Ok(_) => {
+        let mut filtered_count = 0;
+        let total_clients = clients.len();
+
+        for (client_id, connection) in clients.iter_mut() {
+            let Some(message) = filter_for_client(&message, connection.filter.as_ref()) else {
+                filtered_count += 1;
+                continue;
+            };
+            match connection.deliver(message, self.send_queue_capacity, self.drop_policy) {
+                Delivery::Sent | Delivery::Queued => {
                     success_count += 1;
                 }
-                Err(TrySendError::Full(_)) => {
-                    shared::process_warn!(shared::ProcessId::current(), "Client {} channel full, dropping message", client_id);
+                Delivery::Dropped => {
+                    shared::process_warn!(shared::ProcessId::current(), "Client {} send queue full, applied {} policy", client_id, self.drop_policy);
                 }
-                Err(TrySendError::Closed(_)) => {
-                    failed_clients.push(client_id);
+                Delivery::Closed => {
+                    failed_clients.push(*client_id);
+                }
+                Delivery::TooSlow => {
+                    slow_clients.push(*client_id);
                 }
             }
         }
 
         // Clean up failed clients
-        if !failed_clients.is_empty() {
-            let mut clients = self.clients.write().await;
-            for client_id in failed_clients {
-                if clients.remove(&client_id).is_some() {
-                    shared::process_info!(shared::ProcessId::current(), "🗑️ Removed disconnected client {} during broadcast", client_id);
-                }
+        for client_id in failed_clients {
+            if clients.remove(&client_id).is_some() {
+                shared::process_info!(shared::ProcessId::current(), "🗑️ Removed disconnected client {} during broadcast", client_id);
+            }
+        }
+        for client_id in slow_clients {
+            if let Some(connection) = clients.remove(&client_id) {
+                shared::process_warn!(
+                    shared::ProcessId::current(),
+                    "🐢 Disconnected slow WebSocket client {} ({} overflows in a row, {} policy)",
+                    client_id,
+                    connection.overflow_streak,
+                    self.drop_policy
+                );
             }
         }
+        drop(clients);
 
         if success_count > 0 {
             shared::process_info!(shared::ProcessId::current(), "✅ Successfully broadcasted message to {}/{} clients", success_count, total_clients);
-        } else {
+        } else if filtered_count < total_clients {
             shared::process_warn!(shared::ProcessId::current(), "❌ Failed to broadcast message to any clients");
         }
 
         Ok(())
     }
 
+    async fn set_filter(&self, client_id: Uuid, pattern: &str) -> WebServerResult<()> {
+        let filter = compile_filter(pattern)?;
+
+        let mut clients = self.clients.write().await;
+        let connection = clients
+            .get_mut(&client_id)
+            .ok_or_else(|| WebServerError::websocket(format!("Client {} not found", client_id)))?;
+        connection.filter = filter;
+
+        shared::process_debug!(shared::ProcessId::current(), "🔍 Client {} attribute filter set to '{}'", client_id, pattern);
+        Ok(())
+    }
+
     async fn send_to_client(&self, client_id: Uuid, message: ClientMessage) -> WebServerResult<()> {
         // Get sender clone to avoid holding the lock during the send operation
         let sender = {
@@ -190,14 +604,30 @@ impl WebSocketManager for RealWebSocketManager {
         Err(WebServerError::websocket(format!("Client {} not found", client_id)))
     }
 
+    async fn record_activity(&self, client_id: Uuid) {
+        if let Some(connection) = self.clients.write().await.get_mut(&client_id) {
+            connection.last_seen = Instant::now();
+        }
+    }
+
+    fn heartbeat_interval(&self) -> Duration {
+        self.heartbeat_interval
+    }
+
     async fn client_count(&self) -> usize {
+        let timeout = self.heartbeat_timeout();
         let clients = self.clients.read().await;
-        clients.len()
+        clients.values().filter(|connection| connection.is_live(timeout)).count()
     }
 
     async fn active_clients(&self) -> Vec<Uuid> {
+        let timeout = self.heartbeat_timeout();
         let clients = self.clients.read().await;
-        clients.keys().copied().collect()
+        clients
+            .iter()
+            .filter(|(_, connection)| connection.is_live(timeout))
+            .map(|(client_id, _)| *client_id)
+            .collect()
     }
 }
 
@@ -207,34 +637,54 @@ impl Default for RealWebSocketManager {
     }
 }
 
-// Background task to periodically clean up disconnected clients
+// Background task that evicts dead connections on every heartbeat
 impl RealWebSocketManager {
-    /// Start background cleanup task
-    pub fn start_cleanup_task(&self) -> JoinHandle<()> {
-        let clients = self.clients.clone();
+    /// Start background heartbeat task
+    pub fn start_heartbeat_task(&self) -> JoinHandle<()> {
+        let manager = self.clone();
 
         tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(30));
+            let mut interval = interval(manager.heartbeat_interval);
 
             loop {
                 interval.tick().await;
 
-                let mut client_map = clients.write().await;
-                let mut to_remove = Vec::new();
-
-                for (client_id, connection) in client_map.iter() {
-                    if connection.sender.is_closed() {
-                        to_remove.push(*client_id);
-                    }
+                let evicted = manager.evict_stale_clients().await;
+                if evicted > 0 {
+                    shared::process_info!(shared::ProcessId::current(), "🧹 Evicted {} dead WebSocket clients", evicted);
                 }
+            }
+        })
+    }
 
-                if !to_remove.is_empty() {
-                    for client_id in to_remove {
-                        client_map.remove(&client_id);
-                    }
-                    shared::process_info!(shared::ProcessId::current(), "🧹 Cleaned up {} disconnected clients", client_map.len());
-                }
+    /// Start the task that drains backed-up clients' send queues between broadcasts
+    pub fn start_queue_drain_task(&self) -> JoinHandle<()> {
+        let manager = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = interval(SEND_QUEUE_DRAIN_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                manager.drain_queues().await;
             }
         })
     }
+
+    /// Start the task that flushes coalesced attribute batches, if coalescing is on
+    pub fn start_coalescing_task(&self) -> Option<JoinHandle<()>> {
+        if self.coalesce_window.is_zero() {
+            return None;
+        }
+        let manager = self.clone();
+
+        Some(tokio::spawn(async move {
+            let mut interval = interval(manager.coalesce_window);
+
+            loop {
+                interval.tick().await;
+                manager.flush_pending().await;
+            }
+        }))
+    }
 }
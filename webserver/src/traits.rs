@@ -3,12 +3,53 @@
 //! All I/O operations are abstracted through these traits for testability
 
 use async_trait::async_trait;
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 use uuid::Uuid;
 
-use crate::error::WebServerResult;
+use crate::error::{WebServerError, WebServerResult};
 use crate::types::ClientMessage;
-use shared::{OrchestratorUpdate, WebServerRequest};
+use shared::messages::webserver::{OptimizationPlan, StopMode, StopReport};
+use shared::{OrchestratorUpdate, SystemMetrics, WebServerRequest};
+
+/// Reply to a request already sent to the orchestrator
+///
+/// Waiting on it doesn't need the client, so callers release the client before waiting.
+#[derive(Debug)]
+pub struct PendingReply<T> {
+    reply: oneshot::Receiver<T>,
+    timeout: Duration,
+    description: String,
+}
+
+impl<T> PendingReply<T> {
+    /// Wait up to `timeout` for `reply`; `description` names the reply in the timeout error
+    pub fn new(reply: oneshot::Receiver<T>, timeout: Duration, description: impl Into<String>) -> Self {
+        Self {
+            reply,
+            timeout,
+            description: description.into(),
+        }
+    }
+
+    /// A reply that has already arrived
+    pub fn ready(value: T) -> Self {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let _ = reply_tx.send(value);
+        Self::new(reply_rx, Duration::MAX, "reply")
+    }
+
+    /// Wait for the reply
+    pub async fn wait(self) -> WebServerResult<T> {
+        match tokio::time::timeout(self.timeout, self.reply).await {
+            Ok(Ok(value)) => Ok(value),
+            _ => Err(WebServerError::communication(format!(
+                "No {} within {:?}",
+                self.description, self.timeout
+            ))),
+        }
+    }
+}
 
 /// Orchestrator communication service trait
 #[mockall::automock]
@@ -20,6 +61,15 @@ pub trait OrchestratorClient: Send + Sync {
     /// Send request to orchestrator
     async fn send_request(&self, request: WebServerRequest) -> WebServerResult<()>;
 
+    /// Ask the orchestrator for its current metrics; the reply arrives on the returned handle
+    async fn request_status(&self) -> WebServerResult<PendingReply<SystemMetrics>>;
+
+    /// Ask the orchestrator to stop generating; its final statistics arrive on the returned handle
+    async fn request_stop(&self, mode: StopMode) -> WebServerResult<PendingReply<StopReport>>;
+
+    /// Ask the orchestrator for the optimizer's current plan, which is None until it has planned
+    async fn request_optimization_plan(&self) -> WebServerResult<PendingReply<Option<OptimizationPlan>>>;
+
     /// Get receiver for orchestrator updates
     async fn get_updates(&mut self) -> WebServerResult<mpsc::Receiver<OrchestratorUpdate>>;
 
@@ -43,13 +93,22 @@ pub trait WebSocketManager: Send + Sync {
     /// Broadcast message to all connected clients
     async fn broadcast(&self, message: ClientMessage) -> WebServerResult<()>;
 
+    /// Set the regex a client's attribute updates are filtered by; an empty pattern clears it
+    async fn set_filter(&self, client_id: Uuid, pattern: &str) -> WebServerResult<()>;
+
     /// Send message to specific client
     async fn send_to_client(&self, client_id: Uuid, message: ClientMessage) -> WebServerResult<()>;
 
-    /// Get count of active clients
+    /// Note that a client answered a ping or sent any other frame
+    async fn record_activity(&self, client_id: Uuid);
+
+    /// Time between server-side pings sent to every client
+    fn heartbeat_interval(&self) -> Duration;
+
+    /// Get count of live clients (open and answering pings)
     async fn client_count(&self) -> usize;
 
-    /// Get list of active client IDs
+    /// Get list of live client IDs
     async fn active_clients(&self) -> Vec<Uuid>;
 }
 
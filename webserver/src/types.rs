@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-use shared::messages::webserver::CompletionReason;
-use shared::{OrchestratorUpdate, ProcessId, ProviderMetadata, SystemMetrics};
+use shared::messages::webserver::{CompletionReason, ProducerLifecycleEvent};
+use shared::{OrchestratorUpdate, ProcessId, ProviderId, ProviderMetadata, SystemMetrics};
 
 
 /// Client session information
@@ -42,7 +42,10 @@ pub enum ClientMessage {
     AttributeUpdate {
         attributes: Vec<String>,
         producer_id: ProcessId,
-        metadata: ProviderMetadata,
+        /// Provider and model that produced the batch, for grouping in the UI
+        provider: Option<ProviderId>,
+        model: Option<String>,
+        metadata: Option<ProviderMetadata>,
         uniqueness_ratio: f64,
     },
 
@@ -88,6 +91,18 @@ pub enum ClientMessage {
         final_unique_count: usize,
         completion_reason: CompletionReason,
     },
+
+    /// Attributes a slow client missed because its queue was full
+    #[serde(rename = "attribute_summary")]
+    AttributeSummary { skipped_attributes: usize },
+
+    /// Producer pool transition for the live pool-health timeline
+    #[serde(rename = "producer_event")]
+    ProducerEvent {
+        producer_id: ProcessId,
+        event: ProducerLifecycleEvent,
+        timestamp: u64,
+    },
 }
 
 /// Browser-to-server WebSocket messages (now only for read operations and pings)
@@ -101,6 +116,10 @@ pub enum ClientRequest {
     /// Ping for connection health
     #[serde(rename = "ping")]
     Ping,
+
+    /// Only forward attributes matching this regex to the client; an empty pattern clears the filter
+    #[serde(rename = "set_filter")]
+    SetFilter { pattern: String },
 }
 
 /// Optimization insights for UI display
@@ -187,6 +206,19 @@ pub struct ProviderStats {
     pub status: shared::ProviderStatus,
 }
 
+/// Health of one provider as reported by `/api/providers`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub provider: shared::ProviderId,
+    pub status: shared::ProviderStatus,
+    pub success_rate: f64,
+    pub avg_response_time_ms: f64,
+    pub uam: f64,
+    pub cost_per_minute: f64,
+    /// Seconds since the provider last showed activity (None if it never has)
+    pub seconds_since_activity: Option<u64>,
+}
+
 /// Producer statistics for UI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProducerStats {
@@ -217,6 +249,10 @@ pub enum ActivityType {
     ProducerSpawned,
     #[serde(rename = "producer_failed")]
     ProducerFailed,
+    #[serde(rename = "producer_restarted")]
+    ProducerRestarted,
+    #[serde(rename = "producer_stopped")]
+    ProducerStopped,
     #[serde(rename = "optimization_applied")]
     OptimizationApplied,
     #[serde(rename = "attributes_generated")]
@@ -253,8 +289,10 @@ pub fn convert_to_websocket_message(update: OrchestratorUpdate) -> Vec<ClientMes
             vec![ClientMessage::AttributeUpdate {
                 attributes,
                 producer_id: ProcessId::current().clone(), // Would need actual producer ID from update
-                metadata: provider_metadata.unwrap_or_else(|| create_default_provider_metadata()),
-                uniqueness_ratio: 1.0,                     // Would be calculated
+                provider: provider_metadata.as_ref().map(|metadata| metadata.provider_id),
+                model: provider_metadata.as_ref().map(|metadata| metadata.model.clone()),
+                metadata: provider_metadata,
+                uniqueness_ratio: 1.0, // Would be calculated
             }]
         }
 
@@ -264,6 +302,7 @@ pub fn convert_to_websocket_message(update: OrchestratorUpdate) -> Vec<ClientMes
             current_topic,
             total_unique_attributes,
             metrics,
+            ..
         } => {
             vec![ClientMessage::StatisticsUpdate {
                 timestamp,
@@ -290,6 +329,14 @@ pub fn convert_to_websocket_message(update: OrchestratorUpdate) -> Vec<ClientMes
             }]
         }
 
+        OrchestratorUpdate::ProducerLifecycle { producer_id, event } => {
+            vec![ClientMessage::ProducerEvent {
+                producer_id,
+                event,
+                timestamp: Utc::now().timestamp() as u64,
+            }]
+        }
+
         OrchestratorUpdate::ErrorNotification(error_msg) => {
             vec![ClientMessage::Alert {
                 level: AlertLevel::Error,
@@ -303,14 +350,3 @@ pub fn convert_to_websocket_message(update: OrchestratorUpdate) -> Vec<ClientMes
         _ => vec![], // Handle other update types as needed
     }
 }
-
-/// Helper function to create default provider metadata
-pub fn create_default_provider_metadata() -> ProviderMetadata {
-    ProviderMetadata {
-        provider_id: shared::ProviderId::Random,
-        model: "random-model".to_string(),
-        response_time_ms: 0,
-        tokens: shared::TokenUsage::default(),
-        request_timestamp: Utc::now().timestamp() as u64,
-    }
-}
@@ -0,0 +1,82 @@
+//! Optional bearer-token authentication
+//!
+//! When the server has an auth token, non-GET `/api` requests (start, stop,
+//! pause, resume, config) must carry `Authorization: Bearer <token>`. With
+//! `auth_all` every route requires it except the health probes, which stay
+//! open so supervisors can always check liveness.
+
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, Method, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+use crate::core::WebServerState;
+use shared::{ProcessId, process_warn};
+
+/// Routes that never require a token
+const OPEN_PATHS: &[&str] = &["/healthz", "/readyz"];
+
+/// Authentication settings handed to the middleware, fixed for the server's lifetime
+#[derive(Clone)]
+pub struct BearerAuth {
+    /// Token protected requests must carry (None disables authentication)
+    token: Option<Arc<str>>,
+    /// Whether every endpoint, not just control endpoints, requires the token
+    auth_all: bool,
+}
+
+impl BearerAuth {
+    /// Take the token and scope configured on the server state
+    pub fn from_state(state: &WebServerState) -> Self {
+        Self {
+            token: state.auth_token().map(Arc::from),
+            auth_all: state.auth_all(),
+        }
+    }
+}
+
+/// Whether a request needs the bearer token
+pub fn requires_auth(method: &Method, path: &str, auth_all: bool) -> bool {
+    if OPEN_PATHS.contains(&path) {
+        return false;
+    }
+    auth_all || (method != Method::GET && path.starts_with("/api/"))
+}
+
+/// Whether the Authorization header carries exactly the expected bearer token
+pub fn bearer_matches(headers: &HeaderMap, token: &str) -> bool {
+    let Some(provided) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+
+    // Compare every byte so the response time doesn't reveal how much of the token matched
+    provided.len() == token.len()
+        && provided
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Axum middleware rejecting protected requests without a valid bearer token
+pub async fn require_bearer_token(State(auth): State<BearerAuth>, request: Request, next: Next) -> Response {
+    if let Some(token) = &auth.token
+        && requires_auth(request.method(), request.uri().path(), auth.auth_all)
+        && !bearer_matches(request.headers(), token)
+    {
+        process_warn!(
+            ProcessId::current(),
+            "🔒 Rejected unauthenticated {} {}",
+            request.method(),
+            request.uri().path()
+        );
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response();
+    }
+
+    next.run(request).await
+}
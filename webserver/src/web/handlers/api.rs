@@ -2,15 +2,216 @@
 //!
 //! HTTP API endpoints for dashboard and control operations
 
-use axum::{extract::State, http::StatusCode, response::Json};
-use chrono::Utc;
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use shared::messages::webserver::StopMode;
+use std::path::{Component, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio_util::io::ReaderStream;
 
+use crate::WebServerState;
+use crate::core::analytics::{AnalyticsEngine, PROMETHEUS_CONTENT_TYPE};
 use crate::traits::{OrchestratorClient, WebSocketManager};
 
+/// Liveness probe: the server is up, whether or not the orchestrator is connected
+pub async fn healthz(State(state): State<Arc<Mutex<WebServerState>>>) -> Json<Value> {
+    let orchestrator_connected = state.lock().await.is_orchestrator_connected();
+    Json(json!({
+        "status": "ok",
+        "orchestrator_connected": orchestrator_connected
+    }))
+}
+
+/// Readiness probe: 503 until the orchestrator connection is established
+pub async fn readyz(State(state): State<Arc<Mutex<WebServerState>>>) -> (StatusCode, Json<Value>) {
+    if state.lock().await.is_orchestrator_connected() {
+        (StatusCode::OK, Json(json!({ "status": "ready" })))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "waiting_for_orchestrator" })),
+        )
+    }
+}
+
+/// Latest system metrics in Prometheus text format; zeroed until the orchestrator reports
+pub async fn metrics(
+    State(state): State<Arc<Mutex<WebServerState>>>,
+) -> ([(header::HeaderName, &'static str); 1], String) {
+    let body = match state.lock().await.current_metrics() {
+        Some(metrics) => AnalyticsEngine::render_prometheus(metrics),
+        None => AnalyticsEngine::render_prometheus(&shared::SystemMetrics::default()),
+    };
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], body)
+}
+
+/// Page size used when `/api/attributes` is called without a limit
+pub const DEFAULT_ATTRIBUTES_PAGE_SIZE: usize = 100;
+
+/// Largest page `/api/attributes` will return in one response
+pub const MAX_ATTRIBUTES_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AttributesQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// Page through recently accumulated unique attributes, oldest first - /api/attributes
+pub async fn get_attributes(
+    State(state): State<Arc<Mutex<WebServerState>>>,
+    Query(query): Query<AttributesQuery>,
+) -> Json<Value> {
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(DEFAULT_ATTRIBUTES_PAGE_SIZE).min(MAX_ATTRIBUTES_PAGE_SIZE);
+    let (attributes, total) = state.lock().await.attribute_page(offset, limit);
+
+    Json(json!({
+        "status": "ok",
+        "data": {
+            "attributes": attributes,
+            "offset": offset,
+            "limit": limit,
+            "total": total
+        }
+    }))
+}
+
+/// Per-provider health and metrics from the latest statistics update - /api/providers
+pub async fn get_providers(State(state): State<Arc<Mutex<WebServerState>>>) -> Json<Value> {
+    let state = state.lock().await;
+
+    Json(json!({
+        "status": "ok",
+        "data": {
+            "providers": state.provider_health(),
+            "stale_after_seconds": state.provider_stale_after().as_secs()
+        }
+    }))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    /// Only return snapshots taken strictly after this RFC 3339 timestamp
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Statistics snapshots over time, oldest first, for charts - /api/history?since=<ts>
+pub async fn get_history(
+    State(state): State<Arc<Mutex<WebServerState>>>,
+    Query(query): Query<HistoryQuery>,
+) -> Json<Value> {
+    let state = state.lock().await;
+
+    Json(json!({
+        "status": "ok",
+        "data": {
+            "history": state.metrics_history_since(query.since),
+            "capacity": state.metrics_history_capacity()
+        }
+    }))
+}
+
+/// Topic output files that `/api/output/:topic` can download
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFile {
+    /// `output.txt`, one unique attribute per line
+    #[default]
+    Txt,
+    /// `attributes.csv`, attributes with their provider and model
+    Csv,
+}
+
+impl OutputFile {
+    /// File name inside the topic's output directory
+    pub fn file_name(self) -> &'static str {
+        match self {
+            OutputFile::Txt => "output.txt",
+            OutputFile::Csv => "attributes.csv",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            OutputFile::Txt => "text/plain; charset=utf-8",
+            OutputFile::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OutputQuery {
+    #[serde(default)]
+    pub file: OutputFile,
+}
+
+/// Path of a topic's output file, or None if the topic name would leave the output directory
+///
+/// An empty topic maps to "default", matching the orchestrator's directory layout.
+pub fn topic_output_path(output_dir: &std::path::Path, topic: &str, file: OutputFile) -> Option<PathBuf> {
+    let topic_dir = if topic.is_empty() { "default" } else { topic };
+    let mut components = std::path::Path::new(topic_dir).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Some(output_dir.join(topic_dir).join(file.file_name())),
+        _ => None,
+    }
+}
+
+/// Download a topic's output file, streamed from disk - /api/output/:topic?file=txt|csv
+pub async fn download_output(
+    State(state): State<Arc<Mutex<WebServerState>>>,
+    Path(topic): Path<String>,
+    Query(query): Query<OutputQuery>,
+) -> Result<Response, StatusCode> {
+    let output_dir = state.lock().await.output_dir().to_path_buf();
+    let path = topic_output_path(&output_dir, &topic, query.file).ok_or(StatusCode::NOT_FOUND)?;
+
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            shared::process_warn!(
+                shared::ProcessId::current(),
+                "⚠️ Failed to open {}: {}",
+                path.display(),
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if !file.metadata().await.map(|metadata| metadata.is_file()).unwrap_or(false) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    // Keep the suggested file name to plain ASCII so it is always a valid header value
+    let safe_topic: String = topic
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let disposition = format!("attachment; filename=\"{}-{}\"", safe_topic, query.file.file_name());
+    let disposition = HeaderValue::from_str(&disposition).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // The orchestrator may still be appending, so the body is streamed without a fixed length
+    let body = Body::from_stream(ReaderStream::new(file));
+    Ok((
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(query.file.content_type())),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
 /// Get dashboard data
 pub async fn get_dashboard<W>(State(websocket_manager): State<Arc<W>>) -> Result<Json<Value>, StatusCode>
 where
@@ -34,26 +235,78 @@ where
 }
 
 /// Get system status
-pub async fn get_status<W>(State(websocket_manager): State<Arc<W>>) -> Result<Json<Value>, StatusCode>
+///
+/// Metrics come straight from the orchestrator when it answers in time, falling back to
+/// the last periodic statistics update otherwise.
+pub async fn get_status<O, W>(
+    State(websocket_manager): State<Arc<W>>,
+    State(orchestrator_client): State<Arc<Mutex<O>>>,
+    State(state): State<Arc<Mutex<WebServerState>>>,
+) -> Result<Json<Value>, StatusCode>
 where
+    O: OrchestratorClient + Send + Sync + 'static,
     W: WebSocketManager,
 {
     let client_count = websocket_manager.client_count().await;
+    // The client is only locked to send; other requests can use it while this one waits
+    let pending = orchestrator_client.lock().await.request_status().await;
+    let live_metrics = match pending {
+        Ok(reply) => reply.wait().await,
+        Err(e) => Err(e),
+    };
+
+    let state = state.lock().await;
+    let (metrics, metrics_source) = match live_metrics {
+        Ok(metrics) => (Some(metrics), "live"),
+        Err(e) => {
+            shared::process_debug!(shared::ProcessId::current(), "📊 Using cached status: {}", e);
+            (state.current_metrics().cloned(), "cached")
+        }
+    };
 
     let response = json!({
         "status": "ok",
         "data": {
             "server_status": "running",
             "connected_clients": client_count,
-            "orchestrator_connected": false, // Would get actual status
-            "uptime_seconds": 0, // Would calculate actual uptime
-            "version": env!("CARGO_PKG_VERSION")
+            "orchestrator_connected": state.is_orchestrator_connected(),
+            "uptime_seconds": state.uptime_seconds(),
+            "version": env!("CARGO_PKG_VERSION"),
+            "metrics": metrics,
+            "metrics_source": metrics.as_ref().map(|_| metrics_source)
         }
     });
 
     Ok(Json(response))
 }
 
+/// Optimization plan endpoint - /api/optimization
+///
+/// Reports why the optimizer chose its current strategy: rationale, prompt partitioning,
+/// routing, confidence and expected improvements. `data` is null until generation starts.
+pub async fn get_optimization_plan<O>(
+    State(orchestrator_client): State<Arc<Mutex<O>>>,
+) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+{
+    let pending = orchestrator_client.lock().await.request_optimization_plan().await;
+    let plan = match pending {
+        Ok(reply) => reply.wait().await,
+        Err(e) => Err(e),
+    };
+    match plan {
+        Ok(plan) => Ok(Json(json!({
+            "status": "ok",
+            "data": plan
+        }))),
+        Err(e) => {
+            shared::process_debug!(shared::ProcessId::current(), "🎯 Optimization plan unavailable: {}", e);
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StartRequest {
     pub topic: String,
@@ -61,6 +314,14 @@ pub struct StartRequest {
     pub iterations: Option<u32>,
     pub routing_strategy: Option<String>,
     pub routing_config: Option<String>,
+    /// Stop generation after this many seconds
+    pub max_runtime_seconds: Option<u64>,
+    /// Stop once cycle efficiency stays below this percentage
+    pub min_efficiency_percent: Option<f64>,
+    /// Consecutive low-efficiency cycles tolerated before stopping
+    pub efficiency_patience: Option<u32>,
+    /// Stop once this many unique attributes have been generated
+    pub target_unique_count: Option<u64>,
 }
 
 /// Start generation endpoint - /api/start
@@ -77,7 +338,10 @@ where
     let constraints = GenerationConstraints {
         max_cost_per_minute: 1.0,
         target_uam: 10.0,
-        max_runtime_seconds: None,
+        max_runtime_seconds: request.max_runtime_seconds,
+        min_efficiency_percent: request.min_efficiency_percent,
+        efficiency_patience: request.efficiency_patience,
+        target_unique_count: request.target_unique_count,
     };
 
     let webserver_request = WebServerRequest::StartGeneration {
@@ -104,23 +368,154 @@ where
     }
 }
 
-/// Stop generation endpoint - /api/stop
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StopQuery {
+    /// `immediate` (default) or `after_current_iteration` to let the iteration in progress finish
+    #[serde(default)]
+    pub mode: StopMode,
+}
+
+/// Stop generation endpoint - /api/stop?mode=immediate|after_current_iteration
+///
+/// Waits for the orchestrator's stop report so the response carries the final metrics,
+/// unique attribute count and completion reason.
 pub async fn stop_generation<O>(
     State(orchestrator_client): State<Arc<Mutex<O>>>,
+    Query(query): Query<StopQuery>,
+) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+{
+    let pending = orchestrator_client.lock().await.request_stop(query.mode).await;
+    let report = match pending {
+        Ok(reply) => reply.wait().await,
+        Err(e) => Err(e),
+    };
+    match report {
+        Ok(report) => {
+            let message = match query.mode {
+                StopMode::Immediate => "Generation stopped",
+                StopMode::AfterCurrentIteration => "Generation stopping after current iteration",
+            };
+            let response = json!({
+                "status": "success",
+                "message": message,
+                "data": {
+                    "metrics": report.metrics,
+                    "total_unique_attributes": report.total_unique_attributes,
+                    "completion_reason": report.completion_reason
+                }
+            });
+            Ok(Json(response))
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Pause generation endpoint - /api/pause
+pub async fn pause_generation<O>(
+    State(orchestrator_client): State<Arc<Mutex<O>>>,
 ) -> Result<Json<Value>, StatusCode>
 where
     O: OrchestratorClient + Send + Sync + 'static,
 {
     use shared::WebServerRequest;
 
-    let webserver_request = WebServerRequest::StopGeneration { request_id: 2 };
+    let webserver_request = WebServerRequest::PauseGeneration { request_id: 4 };
+
+    let client = orchestrator_client.lock().await;
+    match client.send_request(webserver_request).await {
+        Ok(_) => {
+            let response = json!({
+                "status": "success",
+                "message": "Generation paused"
+            });
+            Ok(Json(response))
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Resume generation endpoint - /api/resume
+pub async fn resume_generation<O>(
+    State(orchestrator_client): State<Arc<Mutex<O>>>,
+) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+{
+    use shared::WebServerRequest;
+
+    let webserver_request = WebServerRequest::ResumeGeneration { request_id: 5 };
+
+    let client = orchestrator_client.lock().await;
+    match client.send_request(webserver_request).await {
+        Ok(_) => {
+            let response = json!({
+                "status": "success",
+                "message": "Generation resumed"
+            });
+            Ok(Json(response))
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigRequest {
+    pub optimization_mode: Option<shared::OptimizationMode>,
+    pub constraints: Option<shared::GenerationConstraints>,
+}
+
+impl ConfigRequest {
+    /// Reject weights and budgets the optimizer cannot work with
+    fn validate(&self) -> Result<(), String> {
+        if let Some(shared::OptimizationMode::Weighted {
+            uam_weight,
+            cost_weight,
+            token_weight,
+        }) = &self.optimization_mode
+            && ![uam_weight, cost_weight, token_weight].iter().all(|w| w.is_finite())
+        {
+            return Err("Weighted mode weights must be finite".to_string());
+        }
+
+        if let Some(constraints) = &self.constraints
+            && !(constraints.max_cost_per_minute.is_finite() && constraints.max_cost_per_minute >= 0.0)
+        {
+            return Err("max_cost_per_minute must not be negative (0 clears the budget)".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Update optimization mode and constraints endpoint - /api/config
+pub async fn update_config<O>(
+    State(orchestrator_client): State<Arc<Mutex<O>>>,
+    Json(request): Json<ConfigRequest>,
+) -> Result<Json<Value>, StatusCode>
+where
+    O: OrchestratorClient + Send + Sync + 'static,
+{
+    use shared::WebServerRequest;
+
+    if let Err(message) = request.validate() {
+        shared::process_warn!(shared::ProcessId::current(), "⚠️ Rejected config update: {}", message);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let webserver_request = WebServerRequest::UpdateConfig {
+        request_id: 3,
+        optimization_mode: request.optimization_mode,
+        constraints: request.constraints,
+    };
 
     let client = orchestrator_client.lock().await;
     match client.send_request(webserver_request).await {
         Ok(_) => {
             let response = json!({
                 "status": "success",
-                "message": "Generation stopped"
+                "message": "Configuration update sent"
             });
             Ok(Json(response))
         }
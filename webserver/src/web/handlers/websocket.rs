@@ -49,19 +49,30 @@ where
         return;
     }
 
-    // Spawn task to handle outgoing messages
-    let _websocket_manager_clone = websocket_manager.clone();
-    let outgoing_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            let json_msg = match serde_json::to_string(&msg) {
-                Ok(json) => json,
-                Err(e) => {
-                    shared::process_error!(shared::ProcessId::current(), "Failed to serialize client message: {}", e);
-                    continue;
+    // Spawn task to handle outgoing messages and heartbeat pings
+    let heartbeat_interval = websocket_manager.heartbeat_interval();
+    let mut outgoing_task = tokio::spawn(async move {
+        let mut heartbeat = tokio::time::interval(heartbeat_interval);
+        // The first tick completes immediately; the client was just heard from
+        heartbeat.tick().await;
+
+        loop {
+            let frame = tokio::select! {
+                msg = rx.recv() => {
+                    // Channel closed: the manager dropped this client
+                    let Some(msg) = msg else { break };
+                    match serde_json::to_string(&msg) {
+                        Ok(json) => Message::Text(json),
+                        Err(e) => {
+                            shared::process_error!(shared::ProcessId::current(), "Failed to serialize client message: {}", e);
+                            continue;
+                        }
+                    }
                 }
+                _ = heartbeat.tick() => Message::Ping(Vec::new()),
             };
 
-            if let Err(e) = sender.send(Message::Text(json_msg)).await {
+            if let Err(e) = sender.send(frame).await {
                 shared::process_warn!(shared::ProcessId::current(), "Failed to send message to client {}: {}", client_id, e);
                 break;
             }
@@ -70,8 +81,13 @@ where
         debug!("Outgoing message task ended for client {}", client_id);
     });
 
-    // Handle incoming messages
-    while let Some(msg) = receiver.next().await {
+    // Handle incoming messages until the client leaves or is evicted
+    loop {
+        let msg = tokio::select! {
+            msg = receiver.next() => msg,
+            _ = &mut outgoing_task => break,
+        };
+        let Some(msg) = msg else { break };
         let msg = match msg {
             Ok(msg) => msg,
             Err(e) => {
@@ -80,6 +96,9 @@ where
             }
         };
 
+        // Any frame, pongs included, shows the connection is alive
+        websocket_manager.record_activity(client_id).await;
+
         match msg {
             Message::Text(text) => {
                 debug!("📨 Received from client {}: {}", client_id, text);
@@ -165,6 +184,21 @@ where
 
             websocket_manager.send_to_client(client_id, response).await?;
         }
+
+        ClientRequest::SetFilter { pattern } => {
+            // A rejected pattern leaves the previous filter in place
+            if let Err(e) = websocket_manager.set_filter(client_id, &pattern).await {
+                shared::process_warn!(shared::ProcessId::current(), "Rejected filter from client {}: {}", client_id, e);
+                let alert = ClientMessage::Alert {
+                    level: crate::types::AlertLevel::Warning,
+                    title: "Invalid Filter".to_string(),
+                    message: e.to_string(),
+                    timestamp: Utc::now().timestamp() as u64,
+                    dismissible: true,
+                };
+                websocket_manager.send_to_client(client_id, alert).await?;
+            }
+        }
     }
 
     Ok(())
@@ -2,4 +2,5 @@
 //!
 //! HTTP server, WebSocket handling, and routing
 
+pub mod auth;
 pub mod handlers;
@@ -0,0 +1,97 @@
+//! Integration tests for bearer-token authentication
+//!
+//! Runs a real WebServer with an auth token and sends raw HTTP requests with and without it
+
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::{Duration, sleep};
+
+use shared::messages::webserver::{CompletionReason, StopReport};
+use webserver::{
+    AnalyticsEngine, WebServer, WebServerState,
+    services::RealWebSocketManager,
+    traits::{MockOrchestratorClient, MockStaticFileServer, PendingReply},
+};
+
+const TOKEN: &str = "s3cret-token";
+
+/// Start a standalone webserver on a free local port and return its address
+async fn start_webserver(state: WebServerState, client: MockOrchestratorClient) -> SocketAddr {
+    shared::ProcessId::init_webserver();
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+    let mut server = WebServer::new(
+        state,
+        AnalyticsEngine::new(),
+        client,
+        RealWebSocketManager::new(),
+        MockStaticFileServer::new(),
+    );
+    tokio::spawn(async move { server.run(addr, true).await });
+    addr
+}
+
+/// Send a request with an optional bearer token and return the status code
+async fn request(addr: SocketAddr, method: &str, path: &str, token: Option<&str>) -> u16 {
+    let mut stream = loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => sleep(Duration::from_millis(20)).await,
+        }
+    };
+    let authorization = token.map_or(String::new(), |token| format!("Authorization: Bearer {token}\r\n"));
+    let request = format!(
+        "{method} {path} HTTP/1.1\r\nHost: localhost\r\n{authorization}Content-Length: 0\r\nConnection: close\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response.split_whitespace().nth(1).unwrap().parse().unwrap()
+}
+
+fn stop_report() -> StopReport {
+    StopReport {
+        metrics: shared::SystemMetrics::default(),
+        total_unique_attributes: 0,
+        completion_reason: Some(CompletionReason::ManualStop),
+    }
+}
+
+#[tokio::test]
+async fn test_control_endpoints_require_token() {
+    let mut client = MockOrchestratorClient::new();
+    client.expect_request_stop().times(1).returning(|_| Ok(PendingReply::ready(stop_report())));
+    let addr = start_webserver(WebServerState::new().with_auth_token(TOKEN), client).await;
+
+    assert_eq!(request(addr, "POST", "/api/stop", None).await, 401);
+    assert_eq!(request(addr, "POST", "/api/stop", Some("wrong-token")).await, 401);
+    assert_eq!(request(addr, "POST", "/api/start", None).await, 401);
+    assert_eq!(request(addr, "POST", "/api/config", None).await, 401);
+    assert_eq!(request(addr, "POST", "/api/stop", Some(TOKEN)).await, 200);
+
+    // Read-only endpoints stay open
+    assert_eq!(request(addr, "GET", "/api/attributes", None).await, 200);
+    assert_eq!(request(addr, "GET", "/healthz", None).await, 200);
+}
+
+#[tokio::test]
+async fn test_auth_all_protects_read_only_endpoints_but_not_probes() {
+    let state = WebServerState::new().with_auth_token(TOKEN).with_auth_all(true);
+    let addr = start_webserver(state, MockOrchestratorClient::new()).await;
+
+    assert_eq!(request(addr, "GET", "/api/attributes", None).await, 401);
+    assert_eq!(request(addr, "GET", "/test", None).await, 401);
+    assert_eq!(request(addr, "GET", "/api/attributes", Some(TOKEN)).await, 200);
+    assert_eq!(request(addr, "GET", "/healthz", None).await, 200);
+}
+
+#[tokio::test]
+async fn test_no_token_configured_leaves_control_endpoints_open() {
+    let mut client = MockOrchestratorClient::new();
+    client.expect_request_stop().times(1).returning(|_| Ok(PendingReply::ready(stop_report())));
+    let addr = start_webserver(WebServerState::new(), client).await;
+
+    assert_eq!(request(addr, "POST", "/api/stop", None).await, 200);
+}
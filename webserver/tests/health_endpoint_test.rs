@@ -0,0 +1,80 @@
+//! Integration tests for the liveness and readiness probes
+//!
+//! Runs a real WebServer and queries /healthz and /readyz over plain HTTP
+
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep};
+
+use webserver::{
+    AnalyticsEngine, WebServer, WebServerState,
+    services::RealWebSocketManager,
+    traits::{MockOrchestratorClient, MockStaticFileServer},
+};
+
+/// Start a webserver on a free local port and return its address
+async fn start_webserver(client: MockOrchestratorClient, standalone_mode: bool) -> SocketAddr {
+    shared::ProcessId::init_webserver();
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+    let mut server = WebServer::new(
+        WebServerState::new(),
+        AnalyticsEngine::new(),
+        client,
+        RealWebSocketManager::new(),
+        MockStaticFileServer::new(),
+    );
+    tokio::spawn(async move { server.run(addr, standalone_mode).await });
+    addr
+}
+
+/// GET a path without a WebSocket, returning the status code and JSON body
+async fn get(addr: SocketAddr, path: &str) -> (u16, serde_json::Value) {
+    let mut stream = loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => sleep(Duration::from_millis(20)).await,
+        }
+    };
+    let request = format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[tokio::test]
+async fn test_standalone_mode_is_live_but_not_ready() {
+    let addr = start_webserver(MockOrchestratorClient::new(), true).await;
+
+    let (status, body) = get(addr, "/healthz").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["orchestrator_connected"], false);
+
+    let (status, _) = get(addr, "/readyz").await;
+    assert_eq!(status, 503);
+}
+
+#[tokio::test]
+async fn test_connected_mode_is_live_and_ready() {
+    let mut client = MockOrchestratorClient::new();
+    client.expect_initialize().returning(|| Ok(()));
+    let (updates_tx, updates_rx) = mpsc::channel(1);
+    client.expect_get_updates().return_once(move || Ok(updates_rx));
+    let addr = start_webserver(client, false).await;
+
+    let (status, body) = get(addr, "/healthz").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["orchestrator_connected"], true);
+
+    let (status, body) = get(addr, "/readyz").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ready");
+    drop(updates_tx);
+}
@@ -0,0 +1,191 @@
+//! Integration tests for the optimization plan endpoint
+//!
+//! Runs a real WebServer and queries /api/optimization over plain HTTP
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::time::{Duration, sleep};
+
+use shared::messages::webserver::{ExpectedImprovements, OptimizationPlan, PromptPartitioning};
+use shared::types::ProviderConfig;
+use shared::{ProviderId, RoutingStrategy};
+use webserver::{
+    AnalyticsEngine, WebServer, WebServerError, WebServerState,
+    services::RealWebSocketManager,
+    traits::{MockOrchestratorClient, MockStaticFileServer, PendingReply},
+};
+
+/// Start a standalone webserver on a free local port and return its address
+async fn start_webserver(client: MockOrchestratorClient) -> SocketAddr {
+    shared::ProcessId::init_webserver();
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+    let mut server = WebServer::new(
+        WebServerState::new(),
+        AnalyticsEngine::new(),
+        client,
+        RealWebSocketManager::new(),
+        MockStaticFileServer::new(),
+    );
+    tokio::spawn(async move { server.run(addr, true).await });
+    addr
+}
+
+/// GET a path, returning the status code and raw body
+async fn get(addr: SocketAddr, path: &str) -> (u16, String) {
+    send(addr, "GET", path).await
+}
+
+/// Send a bodyless request, returning the status code and raw body
+async fn send(addr: SocketAddr, method: &str, path: &str) -> (u16, String) {
+    let mut stream = loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => sleep(Duration::from_millis(20)).await,
+        }
+    };
+    let request =
+        format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, body.to_string())
+}
+
+fn plan() -> OptimizationPlan {
+    OptimizationPlan {
+        strategy_name: "basic".to_string(),
+        rationale: "Uniform prompt while uniqueness stays high".to_string(),
+        partitioning: PromptPartitioning::Uniform,
+        assignment_rationales: Vec::new(),
+        routing_strategy: RoutingStrategy::Backoff {
+            provider: ProviderConfig::with_default_model(ProviderId::OpenAI),
+        },
+        confidence: 0.8,
+        expected_improvements: ExpectedImprovements {
+            uam_change_percent: 12.0,
+            cost_change_percent: -5.0,
+            quality_change_percent: 0.0,
+            time_to_effect_seconds: 30,
+        },
+        techniques_applied: vec!["template_rotation".to_string()],
+        risk_factors: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_optimization_endpoint_returns_plan_rationale() {
+    let mut client = MockOrchestratorClient::new();
+    client.expect_request_optimization_plan().returning(|| Ok(PendingReply::ready(Some(plan()))));
+    let addr = start_webserver(client).await;
+
+    let (status, body) = get(addr, "/api/optimization").await;
+    assert_eq!(status, 200);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let data = &body["data"];
+    assert_eq!(data["rationale"], "Uniform prompt while uniqueness stays high");
+    assert_eq!(data["partitioning"], "uniform");
+    assert_eq!(data["confidence"], 0.8);
+    assert_eq!(data["expected_improvements"]["uam_change_percent"], 12.0);
+    assert!(data["routing_strategy"].is_object());
+}
+
+#[tokio::test]
+async fn test_optimization_endpoint_before_planning_and_without_orchestrator() {
+    let mut client = MockOrchestratorClient::new();
+    client.expect_request_optimization_plan().times(1).returning(|| Ok(PendingReply::ready(None)));
+    client
+        .expect_request_optimization_plan()
+        .returning(|| Err(WebServerError::communication("No orchestrator in standalone mode")));
+    let addr = start_webserver(client).await;
+
+    let (status, body) = get(addr, "/api/optimization").await;
+    assert_eq!(status, 200);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(body["data"].is_null());
+
+    let (status, _) = get(addr, "/api/optimization").await;
+    assert_eq!(status, 503);
+}
+
+#[tokio::test]
+async fn test_status_reply_wait_does_not_block_other_requests() {
+    let unanswered = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = MockOrchestratorClient::new();
+    let held = unanswered.clone();
+    client.expect_request_status().returning(move || {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        held.lock().unwrap().push(reply_tx);
+        Ok(PendingReply::new(reply_rx, Duration::from_secs(2), "status reply"))
+    });
+    client.expect_request_optimization_plan().returning(|| Ok(PendingReply::ready(Some(plan()))));
+    let addr = start_webserver(client).await;
+
+    let status = tokio::spawn(async move { get(addr, "/api/status").await });
+    while unanswered.lock().unwrap().is_empty() {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    // The status request is still waiting on its reply, but the client is free for others
+    let (code, _) = tokio::time::timeout(Duration::from_secs(1), get(addr, "/api/optimization"))
+        .await
+        .expect("optimization request blocked behind the pending status reply");
+    assert_eq!(code, 200);
+    assert!(!status.is_finished());
+}
+
+#[tokio::test]
+async fn test_stop_report_wait_does_not_block_other_requests() {
+    let unanswered = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = MockOrchestratorClient::new();
+    let held = unanswered.clone();
+    client.expect_request_stop().returning(move |_| {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        held.lock().unwrap().push(reply_tx);
+        Ok(PendingReply::new(reply_rx, Duration::from_secs(2), "stop report"))
+    });
+    client.expect_request_optimization_plan().returning(|| Ok(PendingReply::ready(Some(plan()))));
+    let addr = start_webserver(client).await;
+
+    let stop = tokio::spawn(async move { send(addr, "POST", "/api/stop").await });
+    while unanswered.lock().unwrap().is_empty() {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    let (code, _) = tokio::time::timeout(Duration::from_secs(1), get(addr, "/api/optimization"))
+        .await
+        .expect("optimization request blocked behind the pending stop report");
+    assert_eq!(code, 200);
+    assert!(!stop.is_finished());
+}
+
+#[tokio::test]
+async fn test_optimization_plan_wait_does_not_block_other_requests() {
+    let unanswered = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = MockOrchestratorClient::new();
+    let held = unanswered.clone();
+    client.expect_request_optimization_plan().returning(move || {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        held.lock().unwrap().push(reply_tx);
+        Ok(PendingReply::new(reply_rx, Duration::from_secs(2), "optimization plan"))
+    });
+    client.expect_request_status().returning(|| Ok(PendingReply::ready(shared::SystemMetrics::default())));
+    let addr = start_webserver(client).await;
+
+    let optimization = tokio::spawn(async move { get(addr, "/api/optimization").await });
+    while unanswered.lock().unwrap().is_empty() {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    let (code, _) = tokio::time::timeout(Duration::from_secs(1), get(addr, "/api/status"))
+        .await
+        .expect("status request blocked behind the pending optimization plan");
+    assert_eq!(code, 200);
+    assert!(!optimization.is_finished());
+}
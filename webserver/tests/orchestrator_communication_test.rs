@@ -4,12 +4,15 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::net::TcpListener;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{Mutex, mpsc};
 
+use shared::messages::webserver::{CompletionReason, OptimizationPlan, StopMode, StopReport};
 use shared::{OrchestratorUpdate, WebServerRequest};
 use webserver::{
-    WebServerResult, core::WebServerState, services::RealOrchestratorClient, traits::OrchestratorClient,
+    WebServerResult, core::WebServerState, services::RealOrchestratorClient, traits::{OrchestratorClient, PendingReply},
     types::ClientMessage,
 };
 
@@ -23,7 +26,7 @@ async fn test_orchestrator_client_listener_failure() {
 
     let orchestrator_addr = "127.0.0.1:9999".parse().unwrap();
 
-    let mut orchestrator_client = RealOrchestratorClient::new(occupied_addr, orchestrator_addr);
+    let mut orchestrator_client = RealOrchestratorClient::new(occupied_addr, orchestrator_addr, 8080);
 
     // Initialize should fail due to occupied address
     let result = orchestrator_client.initialize().await;
@@ -34,7 +37,7 @@ async fn test_orchestrator_client_listener_failure() {
 async fn test_orchestrator_client_health_check() {
     let api_addr = "127.0.0.1:0".parse().unwrap(); // Use ephemeral port
     let orchestrator_addr = "127.0.0.1:8890".parse().unwrap();
-    let mut orchestrator_client = RealOrchestratorClient::new(api_addr, orchestrator_addr);
+    let mut orchestrator_client = RealOrchestratorClient::new(api_addr, orchestrator_addr, 8080);
 
     // Initialize the listener
     orchestrator_client.initialize().await.unwrap();
@@ -73,6 +76,100 @@ async fn test_orchestrator_update_error_handling() {
     }
 }
 
+/// Fake orchestrator that answers `GetStatus` and `StopGeneration` after `reply_delay`, or never when None
+async fn spawn_delayed_orchestrator(webserver_port: u16, reply_delay: Option<Duration>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut length = [0u8; 4];
+            stream.read_exact(&mut length).await.unwrap();
+            let mut data = vec![0u8; u32::from_be_bytes(length) as usize];
+            stream.read_exact(&mut data).await.unwrap();
+
+            let metrics = shared::SystemMetrics {
+                uam: 12.5,
+                ..Default::default()
+            };
+            let reply = match bincode::deserialize(&data).unwrap() {
+                WebServerRequest::GetStatus { request_id } => OrchestratorUpdate::StatisticsUpdate {
+                    request_id: Some(request_id),
+                    timestamp: 0,
+                    active_producers: 3,
+                    current_topic: Some("animals".to_string()),
+                    total_unique_attributes: 42,
+                    metrics,
+                },
+                WebServerRequest::StopGeneration { request_id, .. } => OrchestratorUpdate::StopReport {
+                    request_id,
+                    report: StopReport {
+                        metrics,
+                        total_unique_attributes: 42,
+                        completion_reason: Some(CompletionReason::ManualStop),
+                    },
+                },
+                _ => continue,
+            };
+            let Some(delay) = reply_delay else {
+                continue;
+            };
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let data = bincode::serialize(&reply).unwrap();
+                let mut stream = TcpStream::connect(("127.0.0.1", webserver_port)).await.unwrap();
+                stream.write_all(&(data.len() as u32).to_be_bytes()).await.unwrap();
+                stream.write_all(&data).await.unwrap();
+            });
+        }
+    });
+    addr
+}
+
+async fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port()
+}
+
+#[tokio::test]
+async fn test_request_status_waits_for_matching_reply() {
+    let port = free_port().await;
+    let orchestrator_addr = spawn_delayed_orchestrator(port, Some(Duration::from_millis(200))).await;
+    let mut client = RealOrchestratorClient::new(SocketAddr::from(([127, 0, 0, 1], port)), orchestrator_addr, 8080);
+    client.initialize().await.unwrap();
+    let mut updates = client.get_updates().await.unwrap();
+
+    let metrics = client.request_status().await.unwrap().wait().await.unwrap();
+    assert_eq!(metrics.uam, 12.5);
+
+    // The reply still reaches the regular update stream
+    let update = tokio::time::timeout(Duration::from_secs(1), updates.recv()).await.unwrap().unwrap();
+    assert!(matches!(update, OrchestratorUpdate::StatisticsUpdate { request_id: Some(_), .. }));
+}
+
+#[tokio::test]
+async fn test_request_stop_returns_final_report() {
+    let port = free_port().await;
+    let orchestrator_addr = spawn_delayed_orchestrator(port, Some(Duration::from_millis(50))).await;
+    let mut client = RealOrchestratorClient::new(SocketAddr::from(([127, 0, 0, 1], port)), orchestrator_addr, 8080);
+    client.initialize().await.unwrap();
+
+    let report = client.request_stop(StopMode::Immediate).await.unwrap().wait().await.unwrap();
+    assert_eq!(report.total_unique_attributes, 42);
+    assert_eq!(report.metrics.uam, 12.5);
+    assert_eq!(report.completion_reason, Some(CompletionReason::ManualStop));
+}
+
+#[tokio::test]
+async fn test_request_status_times_out_without_reply() {
+    let port = free_port().await;
+    let orchestrator_addr = spawn_delayed_orchestrator(port, None).await;
+    let mut client = RealOrchestratorClient::new(SocketAddr::from(([127, 0, 0, 1], port)), orchestrator_addr, 8080)
+        .with_status_timeout(Duration::from_millis(100));
+    client.initialize().await.unwrap();
+
+    let error = client.request_status().await.unwrap().wait().await.unwrap_err();
+    assert!(error.to_string().contains("No status reply"), "unexpected error: {error}");
+}
+
 // Helper to create a mock orchestrator client for testing
 #[allow(dead_code)]
 fn create_mock_orchestrator_client() -> TestOrchestratorClient {
@@ -111,6 +208,22 @@ impl OrchestratorClient for TestOrchestratorClient {
         Ok(())
     }
 
+    async fn request_status(&self) -> WebServerResult<PendingReply<shared::SystemMetrics>> {
+        Ok(PendingReply::ready(shared::SystemMetrics::default()))
+    }
+
+    async fn request_stop(&self, _mode: StopMode) -> WebServerResult<PendingReply<StopReport>> {
+        Ok(PendingReply::ready(StopReport {
+            metrics: shared::SystemMetrics::default(),
+            total_unique_attributes: 0,
+            completion_reason: Some(CompletionReason::ManualStop),
+        }))
+    }
+
+    async fn request_optimization_plan(&self) -> WebServerResult<PendingReply<Option<OptimizationPlan>>> {
+        Ok(PendingReply::ready(None))
+    }
+
     async fn get_updates(&mut self) -> WebServerResult<mpsc::Receiver<OrchestratorUpdate>> {
         let (_tx, rx) = mpsc::channel(100);
         Ok(rx)
@@ -0,0 +1,97 @@
+//! Integration tests for downloading a topic's output files
+//!
+//! Runs a real WebServer over a temporary output directory and fetches
+//! /api/output/:topic over plain HTTP
+
+use std::net::SocketAddr;
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::{Duration, sleep};
+
+use webserver::{
+    AnalyticsEngine, WebServer, WebServerState,
+    services::RealWebSocketManager,
+    traits::{MockOrchestratorClient, MockStaticFileServer},
+};
+
+/// Start a standalone webserver serving `output_dir` on a free local port
+async fn start_webserver(output_dir: &Path) -> SocketAddr {
+    shared::ProcessId::init_webserver();
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+    let mut server = WebServer::new(
+        WebServerState::new().with_output_dir(output_dir),
+        AnalyticsEngine::new(),
+        MockOrchestratorClient::new(),
+        RealWebSocketManager::new(),
+        MockStaticFileServer::new(),
+    );
+    tokio::spawn(async move { server.run(addr, true).await });
+    addr
+}
+
+/// GET a path, returning the status code, raw header block and body
+///
+/// Uses HTTP/1.0 so the streamed body is delimited by the connection closing
+/// rather than chunked encoding.
+async fn get(addr: SocketAddr, path: &str) -> (u16, String, String) {
+    let mut stream = loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => sleep(Duration::from_millis(20)).await,
+        }
+    };
+    let request = format!("GET {path} HTTP/1.0\r\nHost: localhost\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let (headers, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, headers.to_lowercase(), body.to_string())
+}
+
+fn write_topic(output_dir: &Path, topic: &str) {
+    let topic_dir = output_dir.join(topic);
+    std::fs::create_dir_all(&topic_dir).unwrap();
+    let lines: String = (0..5000).map(|i| format!("attribute {i}\n")).collect();
+    std::fs::write(topic_dir.join("output.txt"), lines).unwrap();
+    std::fs::write(topic_dir.join("attributes.csv"), "attribute,provider,model\ncats,random,random\n").unwrap();
+}
+
+#[tokio::test]
+async fn test_present_topic_downloads_output_and_csv() {
+    let output_dir = tempfile::tempdir().unwrap();
+    write_topic(output_dir.path(), "cats");
+    let addr = start_webserver(output_dir.path()).await;
+
+    let (status, headers, body) = get(addr, "/api/output/cats").await;
+    assert_eq!(status, 200);
+    assert!(headers.contains("content-disposition: attachment; filename=\"cats-output.txt\""));
+    assert!(headers.contains("content-type: text/plain"));
+    assert_eq!(body.lines().count(), 5000);
+    assert_eq!(body.lines().last(), Some("attribute 4999"));
+
+    let (status, headers, body) = get(addr, "/api/output/cats?file=csv").await;
+    assert_eq!(status, 200);
+    assert!(headers.contains("content-disposition: attachment; filename=\"cats-attributes.csv\""));
+    assert!(headers.contains("content-type: text/csv"));
+    assert_eq!(body, "attribute,provider,model\ncats,random,random\n");
+}
+
+#[tokio::test]
+async fn test_absent_topic_is_not_found() {
+    let output_dir = tempfile::tempdir().unwrap();
+    write_topic(output_dir.path(), "cats");
+    let addr = start_webserver(output_dir.path()).await;
+
+    let (status, _, _) = get(addr, "/api/output/dogs").await;
+    assert_eq!(status, 404);
+
+    // Topic names cannot reach outside the output directory
+    let (status, _, _) = get(addr, "/api/output/..%2Fcats").await;
+    assert_eq!(status, 404);
+    let (status, _, _) = get(addr, "/api/output/..").await;
+    assert_eq!(status, 404);
+}
@@ -17,9 +17,9 @@ async fn test_webserver_starts_in_test_mode() {
     let api_addr = "127.0.0.1:0".parse().unwrap();
     let orchestrator_addr = "127.0.0.1:9999".parse().unwrap(); // Non-existent
 
-    let orchestrator_client = RealOrchestratorClient::new(api_addr, orchestrator_addr);
+    let orchestrator_client = RealOrchestratorClient::new(api_addr, orchestrator_addr, 8080);
     let websocket_manager = RealWebSocketManager::new();
-    let static_server = RealStaticFileServer::new("./static".to_string());
+    let static_server = RealStaticFileServer::new("./static");
 
     let state = WebServerState::new();
     let analytics = AnalyticsEngine::new();
@@ -58,9 +58,9 @@ async fn test_webserver_fails_without_test_mode_and_no_orchestrator() {
     let api_addr = "127.0.0.1:0".parse().unwrap();
     let orchestrator_addr = "127.0.0.1:9999".parse().unwrap(); // Non-existent
 
-    let orchestrator_client = RealOrchestratorClient::new(api_addr, orchestrator_addr);
+    let orchestrator_client = RealOrchestratorClient::new(api_addr, orchestrator_addr, 8080);
     let websocket_manager = RealWebSocketManager::new();
-    let static_server = RealStaticFileServer::new("./static".to_string());
+    let static_server = RealStaticFileServer::new("./static");
 
     let state = WebServerState::new();
     let analytics = AnalyticsEngine::new();
@@ -33,13 +33,15 @@ fn test_client_message_serialization() {
     let attr_update = ClientMessage::AttributeUpdate {
         attributes: vec!["unique1".to_string(), "unique2".to_string()],
         producer_id: shared::ProcessId::init_webserver().clone(),
-        metadata: shared::ProviderMetadata {
+        provider: Some(shared::ProviderId::OpenAI),
+        model: Some("gpt-4".to_string()),
+        metadata: Some(shared::ProviderMetadata {
             provider_id: shared::ProviderId::OpenAI,
             model: "gpt-4".to_string(),
             response_time_ms: 1500,
             tokens: shared::TokenUsage::default(),
             request_timestamp: 1234567890,
-        },
+        }),
         uniqueness_ratio: 0.85,
     };
 
@@ -48,3 +50,334 @@ fn test_client_message_serialization() {
 
     assert!(matches!(parsed, ClientMessage::AttributeUpdate { .. }));
 }
+
+#[test]
+fn test_new_attributes_carry_provider_and_model_to_clients() {
+    shared::ProcessId::init_webserver();
+    let mut state = webserver::WebServerState::new();
+
+    let messages = state.process_orchestrator_update(shared::OrchestratorUpdate::NewAttributes {
+        attributes: vec!["lion".to_string()],
+        provider_metadata: Some(shared::ProviderMetadata {
+            provider_id: shared::ProviderId::Anthropic,
+            model: "claude-3-5-haiku".to_string(),
+            response_time_ms: 200,
+            tokens: shared::TokenUsage::default(),
+            request_timestamp: 1234567890,
+        }),
+    });
+
+    assert!(matches!(
+        &messages[..],
+        [ClientMessage::AttributeUpdate {
+            provider: Some(shared::ProviderId::Anthropic),
+            model: Some(model),
+            metadata: Some(_),
+            ..
+        }] if model == "claude-3-5-haiku"
+    ));
+    let activity = &state.generate_dashboard_data().recent_activity[0];
+    assert_eq!(activity.message, "Generated 1 new attributes from anthropic/claude-3-5-haiku");
+}
+
+#[test]
+fn test_producer_lifecycle_reaches_clients_and_activity_feed() {
+    use shared::messages::webserver::ProducerLifecycleEvent;
+
+    shared::ProcessId::init_webserver();
+    let mut state = webserver::WebServerState::new();
+
+    let messages = state.process_orchestrator_update(shared::OrchestratorUpdate::ProducerLifecycle {
+        producer_id: shared::ProcessId::Producer(2),
+        event: ProducerLifecycleEvent::Restarted,
+    });
+
+    assert!(matches!(
+        &messages[..],
+        [ClientMessage::ProducerEvent {
+            producer_id: shared::ProcessId::Producer(2),
+            event: ProducerLifecycleEvent::Restarted,
+            ..
+        }]
+    ));
+    let json = serde_json::to_value(&messages[0]).unwrap();
+    assert_eq!(json["type"], "producer_event");
+    assert_eq!(json["data"]["event"], "restarted");
+    let activity = &state.generate_dashboard_data().recent_activity[0];
+    assert!(matches!(activity.event_type, webserver::types::ActivityType::ProducerRestarted));
+}
+
+#[tokio::test]
+async fn test_update_config_forwards_request() {
+    use axum::{Json, extract::State};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use webserver::{traits::MockOrchestratorClient, web::handlers::api};
+
+    let mut client = MockOrchestratorClient::new();
+    client
+        .expect_send_request()
+        .withf(|request| {
+            matches!(
+                request,
+                shared::WebServerRequest::UpdateConfig {
+                    optimization_mode: Some(shared::OptimizationMode::MinimizeCost { .. }),
+                    constraints: Some(shared::GenerationConstraints { max_cost_per_minute, .. }),
+                    ..
+                } if *max_cost_per_minute == 2.5
+            )
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let request: api::ConfigRequest = serde_json::from_value(serde_json::json!({
+        "optimization_mode": { "MinimizeCost": { "target_uam": 20.0 } },
+        "constraints": { "max_cost_per_minute": 2.5, "target_uam": 20.0, "max_runtime_seconds": null }
+    }))
+    .unwrap();
+
+    let response = api::update_config(State(Arc::new(Mutex::new(client))), Json(request))
+        .await
+        .unwrap();
+    assert_eq!(response.0["status"], "success");
+}
+
+#[tokio::test]
+async fn test_update_config_rejects_invalid_input() {
+    use axum::{Json, extract::State, http::StatusCode};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use webserver::{traits::MockOrchestratorClient, web::handlers::api};
+
+    shared::ProcessId::init_webserver();
+    let invalid_requests = [
+        api::ConfigRequest {
+            optimization_mode: Some(shared::OptimizationMode::Weighted {
+                uam_weight: f64::NAN,
+                cost_weight: 0.5,
+                token_weight: 0.5,
+            }),
+            constraints: None,
+        },
+        api::ConfigRequest {
+            optimization_mode: None,
+            constraints: Some(shared::GenerationConstraints {
+                max_cost_per_minute: -1.0,
+                target_uam: 10.0,
+                max_runtime_seconds: None,
+                min_efficiency_percent: None,
+                efficiency_patience: None,
+                target_unique_count: None,
+            }),
+        },
+    ];
+
+    for request in invalid_requests {
+        let mut client = MockOrchestratorClient::new();
+        client.expect_send_request().never();
+
+        let result = api::update_config(State(Arc::new(Mutex::new(client))), Json(request)).await;
+        assert_eq!(result.unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_forward_requests() {
+    use axum::extract::State;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use webserver::{traits::MockOrchestratorClient, web::handlers::api};
+
+    let mut client = MockOrchestratorClient::new();
+    let mut seq = mockall::Sequence::new();
+    client
+        .expect_send_request()
+        .withf(|request| matches!(request, shared::WebServerRequest::PauseGeneration { .. }))
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| Ok(()));
+    client
+        .expect_send_request()
+        .withf(|request| matches!(request, shared::WebServerRequest::ResumeGeneration { .. }))
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| Ok(()));
+    let client = Arc::new(Mutex::new(client));
+
+    let paused = api::pause_generation(State(client.clone())).await.unwrap();
+    assert_eq!(paused.0["message"], "Generation paused");
+    let resumed = api::resume_generation(State(client)).await.unwrap();
+    assert_eq!(resumed.0["message"], "Generation resumed");
+}
+
+#[tokio::test]
+async fn test_attributes_endpoint_pages_history_and_evicts_oldest() {
+    use axum::extract::{Query, State};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use webserver::web::handlers::api::{self, AttributesQuery};
+
+    shared::ProcessId::init_webserver();
+    let mut state = webserver::WebServerState::new().with_attribute_history_capacity(4);
+    for batch in [vec!["a", "b", "c"], vec!["c", "d", "e", "f"]] {
+        state.process_orchestrator_update(shared::OrchestratorUpdate::NewAttributes {
+            attributes: batch.into_iter().map(String::from).collect(),
+            provider_metadata: None,
+        });
+    }
+    let state = Arc::new(Mutex::new(state));
+
+    let page = |offset, limit| {
+        let state = state.clone();
+        async move {
+            let query = AttributesQuery { offset, limit };
+            api::get_attributes(State(state), Query(query)).await.0["data"].clone()
+        }
+    };
+
+    // "a" and "b" were evicted and the repeated "c" was not stored twice
+    let all = page(None, None).await;
+    assert_eq!(all["attributes"], serde_json::json!(["c", "d", "e", "f"]));
+    assert_eq!(all["total"], 4);
+
+    assert_eq!(page(Some(1), Some(2)).await["attributes"], serde_json::json!(["d", "e"]));
+    assert_eq!(page(Some(3), Some(10)).await["attributes"], serde_json::json!(["f"]));
+    assert_eq!(page(Some(4), Some(10)).await["attributes"], serde_json::json!([]));
+    assert_eq!(page(Some(usize::MAX), Some(usize::MAX)).await["total"], 4);
+    assert_eq!(page(Some(0), Some(0)).await["attributes"], serde_json::json!([]));
+
+    let clamped = page(None, Some(api::MAX_ATTRIBUTES_PAGE_SIZE + 1)).await;
+    assert_eq!(clamped["limit"], api::MAX_ATTRIBUTES_PAGE_SIZE);
+}
+
+#[tokio::test]
+async fn test_history_endpoint_accumulates_snapshots_and_filters_by_since() {
+    use axum::extract::{Query, State};
+    use shared::{OrchestratorUpdate, SystemMetrics};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use webserver::web::handlers::api::{self, HistoryQuery};
+
+    shared::ProcessId::init_webserver();
+    let update = |uam: f64| OrchestratorUpdate::StatisticsUpdate {
+        request_id: None,
+        timestamp: 0,
+        active_producers: 1,
+        current_topic: Some("colors".to_string()),
+        total_unique_attributes: 0,
+        metrics: SystemMetrics {
+            uam,
+            ..Default::default()
+        },
+    };
+
+    let state = Arc::new(Mutex::new(webserver::WebServerState::new().with_metrics_history_capacity(3)));
+    let history = |since| {
+        let state = state.clone();
+        async move { api::get_history(State(state), Query(HistoryQuery { since })).await.0["data"].clone() }
+    };
+
+    for uam in [1.0, 2.0] {
+        state.lock().await.process_orchestrator_update(update(uam));
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    let checkpoint = state.lock().await.metrics_history_since(None).last().unwrap().timestamp;
+    for uam in [3.0, 4.0] {
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        state.lock().await.process_orchestrator_update(update(uam));
+    }
+
+    // Snapshots accumulate oldest first, bounded by the configured capacity
+    let all = history(None).await;
+    assert_eq!(all["capacity"], 3);
+    let uams: Vec<_> = all["history"].as_array().unwrap().iter().map(|entry| entry["metrics"]["uam"].clone()).collect();
+    assert_eq!(uams, vec![2.0, 3.0, 4.0]);
+    assert!(all["history"][0]["timestamp"].is_string());
+
+    // `since` returns only snapshots taken after it
+    let newer = history(Some(checkpoint)).await;
+    let uams: Vec<_> = newer["history"].as_array().unwrap().iter().map(|entry| entry["metrics"]["uam"].clone()).collect();
+    assert_eq!(uams, vec![3.0, 4.0]);
+}
+
+#[tokio::test]
+async fn test_providers_endpoint_reports_health_and_marks_stale_providers() {
+    use axum::extract::State;
+    use shared::{OrchestratorUpdate, ProviderId, ProviderMetrics, ProviderStatus, SystemMetrics};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+    use webserver::web::handlers::api;
+
+    shared::ProcessId::init_webserver();
+    let provider = |uam: f64, status| ProviderMetrics {
+        uam,
+        tokens_per_minute: uam * 100.0,
+        cost_per_minute: uam / 100.0,
+        unique_per_dollar: 0.0,
+        unique_per_1k_tokens: 0.0,
+        avg_response_time_ms: 850.0,
+        success_rate: 0.95,
+        status,
+    };
+    let update = |by_provider| OrchestratorUpdate::StatisticsUpdate {
+        request_id: None,
+        timestamp: 0,
+        active_producers: 1,
+        current_topic: Some("colors".to_string()),
+        total_unique_attributes: 0,
+        metrics: SystemMetrics {
+            by_provider,
+            ..Default::default()
+        },
+    };
+
+    let mut state = webserver::WebServerState::new();
+    state.process_orchestrator_update(update(HashMap::from([
+        (ProviderId::OpenAI, provider(12.0, ProviderStatus::Healthy)),
+        (ProviderId::Anthropic, provider(0.0, ProviderStatus::Available)),
+    ])));
+    let state = Arc::new(Mutex::new(state));
+
+    let data = api::get_providers(State(state.clone())).await.0["data"].clone();
+    assert_eq!(data["stale_after_seconds"], 60);
+    let providers = data["providers"].as_array().unwrap();
+    assert_eq!(providers.len(), 2);
+
+    let by_name = |name: &str| {
+        providers
+            .iter()
+            .find(|entry| entry["provider"] == name)
+            .unwrap_or_else(|| panic!("missing {name} in {providers:?}"))
+            .clone()
+    };
+    let openai = by_name("OpenAI");
+    assert_eq!(openai["status"], "Healthy");
+    assert_eq!(openai["uam"], 12.0);
+    assert_eq!(openai["cost_per_minute"], 0.12);
+    assert_eq!(openai["success_rate"], 0.95);
+    assert_eq!(openai["avg_response_time_ms"], 850.0);
+    assert_eq!(openai["seconds_since_activity"], 0);
+
+    // A provider that never produced anything has no known health
+    let anthropic = by_name("Anthropic");
+    assert_eq!(anthropic["status"], "Unknown");
+    assert!(anthropic["seconds_since_activity"].is_null());
+
+    // Past the staleness threshold the last reported status no longer applies
+    let stale = {
+        let mut state = state.lock().await;
+        std::mem::take(&mut *state).with_provider_stale_after(Duration::ZERO)
+    };
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let data = api::get_providers(State(Arc::new(Mutex::new(stale)))).await.0["data"].clone();
+    let openai = data["providers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["provider"] == "OpenAI")
+        .unwrap()
+        .clone();
+    assert_eq!(openai["status"], "Offline");
+}
@@ -2,17 +2,20 @@
 //!
 //! Tests WebSocket connection handling, message routing, and client lifecycle
 
-use serde_json;
 use std::sync::Arc;
 use tokio::sync::{Mutex, mpsc};
 use tokio::time::{Duration, timeout};
 use uuid::Uuid;
 
+use shared::messages::webserver::{CompletionReason, OptimizationPlan, StopMode, StopReport};
 use shared::{OrchestratorUpdate, WebServerRequest};
 use webserver::{
     WebServerResult,
-    services::RealWebSocketManager,
-    traits::{OrchestratorClient, WebSocketManager},
+    services::{
+        DropPolicy, RealWebSocketManager,
+        websocket_manager::MAX_CONSECUTIVE_OVERFLOWS,
+    },
+    traits::{OrchestratorClient, PendingReply, WebSocketManager},
     types::{AlertLevel, ClientMessage, ClientRequest},
 };
 
@@ -75,6 +78,23 @@ impl OrchestratorClient for TestOrchestratorClient {
         Ok(())
     }
 
+    async fn request_status(&self) -> WebServerResult<PendingReply<shared::SystemMetrics>> {
+        Ok(PendingReply::ready(shared::SystemMetrics::default()))
+    }
+
+    async fn request_stop(&self, mode: StopMode) -> WebServerResult<PendingReply<StopReport>> {
+        self.send_request(WebServerRequest::StopGeneration { request_id: 0, mode }).await?;
+        Ok(PendingReply::ready(StopReport {
+            metrics: shared::SystemMetrics::default(),
+            total_unique_attributes: 0,
+            completion_reason: Some(CompletionReason::ManualStop),
+        }))
+    }
+
+    async fn request_optimization_plan(&self) -> WebServerResult<PendingReply<Option<OptimizationPlan>>> {
+        Ok(PendingReply::ready(None))
+    }
+
     async fn get_updates(&mut self) -> WebServerResult<mpsc::Receiver<OrchestratorUpdate>> {
         let (_tx, rx) = mpsc::channel(100);
         Ok(rx)
@@ -264,6 +284,48 @@ async fn test_websocket_disconnected_client_cleanup() {
     // depending on the implementation details
 }
 
+#[tokio::test(start_paused = true)]
+async fn test_websocket_unresponsive_client_is_evicted_by_heartbeat() {
+    let heartbeat = Duration::from_millis(50);
+    let websocket_manager = RealWebSocketManager::new().with_heartbeat_interval(heartbeat);
+    assert_eq!(websocket_manager.heartbeat_interval(), heartbeat);
+    assert_eq!(websocket_manager.heartbeat_timeout(), heartbeat * 3);
+
+    // Both sockets stay open; only one keeps answering pings
+    let responsive_id = Uuid::new_v4();
+    let silent_id = Uuid::new_v4();
+    let (responsive_tx, _responsive_rx) = mpsc::channel(100);
+    let (silent_tx, mut silent_rx) = mpsc::channel(100);
+    websocket_manager.add_client(responsive_id, responsive_tx).await.unwrap();
+    websocket_manager.add_client(silent_id, silent_tx).await.unwrap();
+    assert_eq!(websocket_manager.client_count().await, 2);
+
+    let heartbeat_task = websocket_manager.start_heartbeat_task();
+    for round in 0..12 {
+        tokio::time::sleep(heartbeat).await;
+        websocket_manager.record_activity(responsive_id).await;
+        // The silent client stops answering after the first few pings
+        if round < 3 {
+            websocket_manager.record_activity(silent_id).await;
+        }
+    }
+
+    assert_eq!(websocket_manager.client_count().await, 1);
+    assert_eq!(websocket_manager.active_clients().await, vec![responsive_id]);
+    assert!(websocket_manager.send_to_client(silent_id, ClientMessage::ConnectionAck {
+        session_id: silent_id,
+        server_time: 0,
+    })
+    .await
+    .is_err());
+
+    // Eviction closes the silent client's channel, ending its connection handler
+    while let Ok(Some(_)) = timeout(Duration::from_millis(100), silent_rx.recv()).await {}
+    assert!(silent_rx.recv().await.is_none());
+
+    heartbeat_task.abort();
+}
+
 #[tokio::test]
 async fn test_websocket_high_volume_messaging() {
     let websocket_manager = RealWebSocketManager::new();
@@ -304,3 +366,304 @@ async fn test_websocket_high_volume_messaging() {
         message_count
     );
 }
+
+fn numbered_alert(i: usize) -> ClientMessage {
+    ClientMessage::Alert {
+        level: AlertLevel::Info,
+        title: format!("Message {}", i),
+        message: format!("This is message number {}", i),
+        timestamp: i as u64,
+        dismissible: true,
+    }
+}
+
+/// Register a client whose socket never drains: its one-slot channel already holds the ack
+async fn stalled_client(manager: &RealWebSocketManager) -> (Uuid, mpsc::Receiver<ClientMessage>) {
+    let client_id = Uuid::new_v4();
+    let (tx, rx) = mpsc::channel(1);
+    manager.add_client(client_id, tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    (client_id, rx)
+}
+
+#[tokio::test]
+async fn test_websocket_stalled_client_queue_stays_bounded() {
+    for (policy, first_kept) in [(DropPolicy::DropOldest, 16), (DropPolicy::DropNewest, 0)] {
+        let websocket_manager = RealWebSocketManager::new().with_send_queue(4, policy);
+        let (client_id, mut rx) = stalled_client(&websocket_manager).await;
+
+        for i in 0..20 {
+            websocket_manager.broadcast(numbered_alert(i)).await.unwrap();
+            assert!(websocket_manager.queued_messages(client_id).await.unwrap() <= 4);
+        }
+        assert_eq!(websocket_manager.queued_messages(client_id).await, Some(4));
+
+        // Once the socket takes the ack, the oldest surviving broadcast moves up
+        assert!(matches!(rx.recv().await.unwrap(), ClientMessage::ConnectionAck { .. }));
+        websocket_manager.broadcast(numbered_alert(20)).await.unwrap();
+        match rx.recv().await.unwrap() {
+            ClientMessage::Alert { timestamp, .. } => assert_eq!(timestamp, first_kept, "{policy} kept the wrong end"),
+            other => panic!("Expected Alert, got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_stalled_client_is_disconnected() {
+    // The disconnect policy drops the client at the first overflow
+    let websocket_manager = RealWebSocketManager::new().with_send_queue(4, DropPolicy::Disconnect);
+    let (client_id, mut rx) = stalled_client(&websocket_manager).await;
+    for i in 0..5 {
+        websocket_manager.broadcast(numbered_alert(i)).await.unwrap();
+    }
+    assert_eq!(websocket_manager.queued_messages(client_id).await, None);
+    assert!(matches!(rx.recv().await.unwrap(), ClientMessage::ConnectionAck { .. }));
+    assert!(rx.recv().await.is_none(), "channel should close with the client");
+
+    // Dropping policies still give up on a client that never catches up
+    let websocket_manager = RealWebSocketManager::new().with_send_queue(4, DropPolicy::DropOldest);
+    let (client_id, _rx) = stalled_client(&websocket_manager).await;
+    for i in 0..4 + MAX_CONSECUTIVE_OVERFLOWS as usize - 1 {
+        websocket_manager.broadcast(numbered_alert(i)).await.unwrap();
+    }
+    assert_eq!(websocket_manager.queued_messages(client_id).await, Some(4));
+    websocket_manager.broadcast(numbered_alert(100)).await.unwrap();
+    assert_eq!(websocket_manager.queued_messages(client_id).await, None);
+    assert_eq!(websocket_manager.client_count().await, 0);
+}
+
+#[tokio::test]
+async fn test_websocket_queued_broadcast_ends_overflow_streak() {
+    let websocket_manager = RealWebSocketManager::new().with_send_queue(4, DropPolicy::DropOldest);
+    let (client_id, mut rx) = stalled_client(&websocket_manager).await;
+    let overflow = |websocket_manager: &RealWebSocketManager| {
+        let websocket_manager = websocket_manager.clone();
+        async move {
+            for i in 0..MAX_CONSECUTIVE_OVERFLOWS as usize - 1 {
+                websocket_manager.broadcast(numbered_alert(i)).await.unwrap();
+            }
+        }
+    };
+    for i in 0..4 {
+        websocket_manager.broadcast(numbered_alert(i)).await.unwrap();
+    }
+    overflow(&websocket_manager).await;
+
+    // The socket takes one message, so the next broadcast is queued rather than dropped
+    assert!(matches!(rx.recv().await.unwrap(), ClientMessage::ConnectionAck { .. }));
+    websocket_manager.broadcast(numbered_alert(100)).await.unwrap();
+
+    // Only overflows since then count toward disconnecting the client
+    overflow(&websocket_manager).await;
+    assert_eq!(websocket_manager.queued_messages(client_id).await, Some(4));
+    assert_eq!(websocket_manager.client_count().await, 1);
+}
+
+#[tokio::test]
+async fn test_websocket_queue_drains_without_further_broadcasts() {
+    let websocket_manager = RealWebSocketManager::new().with_send_queue(4, DropPolicy::DropOldest);
+    let (client_id, mut rx) = stalled_client(&websocket_manager).await;
+    for i in 0..2 {
+        websocket_manager.broadcast(numbered_alert(i)).await.unwrap();
+    }
+    assert_eq!(websocket_manager.queued_messages(client_id).await, Some(2));
+
+    // The drain task hands each queued broadcast over as soon as the socket has room
+    let drain_task = websocket_manager.start_queue_drain_task();
+    assert!(matches!(rx.recv().await.unwrap(), ClientMessage::ConnectionAck { .. }));
+    for expected in 0..2 {
+        match timeout(Duration::from_secs(1), rx.recv()).await.unwrap().unwrap() {
+            ClientMessage::Alert { timestamp, .. } => assert_eq!(timestamp, expected),
+            other => panic!("Expected Alert, got {:?}", other),
+        }
+    }
+    drain_task.abort();
+    assert_eq!(websocket_manager.queued_messages(client_id).await, Some(0));
+}
+
+fn attribute_update(attributes: &[&str]) -> ClientMessage {
+    ClientMessage::AttributeUpdate {
+        attributes: attributes.iter().map(|attribute| attribute.to_string()).collect(),
+        producer_id: shared::ProcessId::Producer(1),
+        provider: None,
+        model: None,
+        metadata: None,
+        uniqueness_ratio: 1.0,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_websocket_rapid_attribute_updates_are_coalesced() {
+    let websocket_manager = RealWebSocketManager::new().with_coalesce_window(Duration::from_millis(50));
+    let client_id = Uuid::new_v4();
+    let (tx, mut rx) = mpsc::channel(100);
+    websocket_manager.add_client(client_id, tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = timeout(Duration::from_millis(50), rx.recv()).await;
+    let flush_task = websocket_manager.start_coalescing_task().unwrap();
+
+    // Far more updates than the client's queue could hold one message each
+    for i in 0..1000 {
+        websocket_manager.broadcast(attribute_update(&[&format!("animal {i}")])).await.unwrap();
+    }
+
+    let mut batches = 0;
+    let mut received = Vec::new();
+    while let Ok(Some(message)) = timeout(Duration::from_millis(200), rx.recv()).await {
+        match message {
+            ClientMessage::AttributeUpdate { attributes, .. } => received.extend(attributes),
+            other => panic!("Expected AttributeUpdate, got {:?}", other),
+        }
+        batches += 1;
+    }
+    flush_task.abort();
+
+    assert!(batches <= 5, "1000 updates arrived in {} messages", batches);
+    assert_eq!(received.len(), 1000);
+    assert_eq!(received[999], "animal 999");
+}
+
+#[tokio::test]
+async fn test_websocket_full_queue_gets_skipped_attribute_summary() {
+    let websocket_manager = RealWebSocketManager::new().with_coalesce_window(Duration::from_millis(50));
+    let client_id = Uuid::new_v4();
+    let (tx, mut rx) = mpsc::channel(1);
+    websocket_manager.add_client(client_id, tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = timeout(Duration::from_millis(50), rx.recv()).await;
+
+    // The first batch fills the queue, so the second is dropped and only counted
+    websocket_manager.broadcast(attribute_update(&["cat"])).await.unwrap();
+    assert_eq!(websocket_manager.flush_pending().await, 1);
+    websocket_manager.broadcast(attribute_update(&["dog", "owl"])).await.unwrap();
+    assert_eq!(websocket_manager.flush_pending().await, 0);
+
+    assert!(matches!(rx.recv().await.unwrap(), ClientMessage::AttributeUpdate { .. }));
+    websocket_manager.flush_pending().await;
+    assert!(matches!(
+        rx.recv().await.unwrap(),
+        ClientMessage::AttributeSummary { skipped_attributes: 2 }
+    ));
+}
+
+#[tokio::test]
+async fn test_websocket_attribute_filter_is_per_client() {
+    let websocket_manager = RealWebSocketManager::new();
+    let filtered_id = Uuid::new_v4();
+    let unfiltered_id = Uuid::new_v4();
+    let (filtered_tx, mut filtered_rx) = mpsc::channel(100);
+    let (unfiltered_tx, mut unfiltered_rx) = mpsc::channel(100);
+    websocket_manager.add_client(filtered_id, filtered_tx).await.unwrap();
+    websocket_manager.add_client(unfiltered_id, unfiltered_tx).await.unwrap();
+
+    // Clear initial ConnectionAck messages
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = timeout(Duration::from_millis(50), filtered_rx.recv()).await;
+    let _ = timeout(Duration::from_millis(50), unfiltered_rx.recv()).await;
+
+    websocket_manager.set_filter(filtered_id, "^cat").await.unwrap();
+    websocket_manager.broadcast(attribute_update(&["cat", "dog", "catfish"])).await.unwrap();
+
+    match filtered_rx.recv().await.unwrap() {
+        ClientMessage::AttributeUpdate { attributes, .. } => assert_eq!(attributes, vec!["cat", "catfish"]),
+        other => panic!("Expected AttributeUpdate, got {:?}", other),
+    }
+    match unfiltered_rx.recv().await.unwrap() {
+        ClientMessage::AttributeUpdate { attributes, .. } => assert_eq!(attributes, vec!["cat", "dog", "catfish"]),
+        other => panic!("Expected AttributeUpdate, got {:?}", other),
+    }
+
+    // Updates with no matching attributes are not sent to the filtered client at all, other messages still are
+    websocket_manager.broadcast(attribute_update(&["dog"])).await.unwrap();
+    websocket_manager
+        .broadcast(ClientMessage::ConnectionAck { session_id: filtered_id, server_time: 0 })
+        .await
+        .unwrap();
+    assert!(matches!(filtered_rx.recv().await.unwrap(), ClientMessage::ConnectionAck { .. }));
+    assert!(matches!(unfiltered_rx.recv().await.unwrap(), ClientMessage::AttributeUpdate { .. }));
+
+    // An empty pattern clears the filter
+    websocket_manager.set_filter(filtered_id, "").await.unwrap();
+    websocket_manager.broadcast(attribute_update(&["dog"])).await.unwrap();
+    assert!(matches!(filtered_rx.recv().await.unwrap(), ClientMessage::AttributeUpdate { .. }));
+}
+
+#[tokio::test]
+async fn test_websocket_rejects_invalid_and_oversized_filters() {
+    let websocket_manager = RealWebSocketManager::new();
+    let client_id = Uuid::new_v4();
+    let (tx, mut rx) = mpsc::channel(100);
+    websocket_manager.add_client(client_id, tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = timeout(Duration::from_millis(50), rx.recv()).await;
+
+    websocket_manager.set_filter(client_id, "dog").await.unwrap();
+    assert!(websocket_manager.set_filter(client_id, "(unclosed").await.is_err());
+    let oversized = "a".repeat(webserver::services::websocket_manager::MAX_FILTER_PATTERN_LEN + 1);
+    assert!(websocket_manager.set_filter(client_id, &oversized).await.is_err());
+
+    // The previous filter stays in effect
+    websocket_manager.broadcast(attribute_update(&["cat", "dog"])).await.unwrap();
+    match rx.recv().await.unwrap() {
+        ClientMessage::AttributeUpdate { attributes, .. } => assert_eq!(attributes, vec!["dog"]),
+        other => panic!("Expected AttributeUpdate, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_invalid_filter_alerts_only_that_client() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    shared::ProcessId::init_webserver();
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let mut server = webserver::WebServer::new(
+        webserver::WebServerState::new(),
+        webserver::AnalyticsEngine::new(),
+        webserver::traits::MockOrchestratorClient::new(),
+        RealWebSocketManager::new(),
+        webserver::traits::MockStaticFileServer::new(),
+    );
+    tokio::spawn(async move { server.run(addr, true).await });
+
+    let connect = || async move {
+        loop {
+            if let Ok((socket, _)) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await {
+                break socket;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    };
+    let mut sender = connect().await;
+    let mut bystander = connect().await;
+
+    let request = serde_json::json!({ "type": "set_filter", "data": { "pattern": "[a-" } });
+    sender.send(Message::Text(request.to_string())).await.unwrap();
+
+    let alert = timeout(Duration::from_secs(2), async {
+        loop {
+            let Some(Ok(Message::Text(text))) = sender.next().await else {
+                panic!("connection closed before alert");
+            };
+            if let Ok(ClientMessage::Alert { level, title, .. }) = serde_json::from_str(&text) {
+                break (level, title);
+            }
+        }
+    })
+    .await
+    .expect("sender should receive an alert");
+    assert!(matches!(alert.0, AlertLevel::Warning));
+    assert_eq!(alert.1, "Invalid Filter");
+
+    // The other connection sees no alert
+    let bystander_alert = timeout(Duration::from_millis(200), async {
+        while let Some(Ok(Message::Text(text))) = bystander.next().await {
+            if let Ok(ClientMessage::Alert { .. }) = serde_json::from_str(&text) {
+                return true;
+            }
+        }
+        false
+    })
+    .await;
+    assert!(!matches!(bystander_alert, Ok(true)));
+}